@@ -0,0 +1,216 @@
+use crate::scene::{BBox, Vec2};
+use crate::types::{AlignMode, DistributeMode};
+
+/// Compute the translation needed to align each item to the collective bounding box.
+///
+/// `items` pairs an arbitrary key (shape ID, group index, ...) with its world bounds.
+/// Items that are already aligned get a zero delta so callers can apply the result blindly.
+pub fn compute_alignment_deltas<K: Copy>(items: &[(K, BBox)], mode: AlignMode) -> Vec<(K, Vec2)> {
+    let Some(target) = items
+        .iter()
+        .map(|(_, bounds)| *bounds)
+        .reduce(|acc, bounds| acc.union(&bounds))
+    else {
+        return Vec::new();
+    };
+
+    items
+        .iter()
+        .map(|(key, bounds)| {
+            let delta = match mode {
+                AlignMode::Left => Vec2::new(target.min.x - bounds.min.x, 0.0),
+                AlignMode::Right => Vec2::new(target.max.x - bounds.max.x, 0.0),
+                AlignMode::Top => Vec2::new(0.0, target.min.y - bounds.min.y),
+                AlignMode::Bottom => Vec2::new(0.0, target.max.y - bounds.max.y),
+                AlignMode::CenterHorizontal => Vec2::new(target.center().x - bounds.center().x, 0.0),
+                AlignMode::CenterVertical => Vec2::new(0.0, target.center().y - bounds.center().y),
+            };
+            (*key, delta)
+        })
+        .collect()
+}
+
+/// Compute the translation needed to space items evenly along one axis.
+///
+/// Items are ordered by their leading edge; the first and last stay put and the
+/// ones between are moved so the gaps between neighbouring bounds are equal.
+/// Fewer than three items can't be redistributed and get zero deltas.
+pub fn compute_distribution_deltas<K: Copy>(items: &[(K, BBox)], mode: DistributeMode) -> Vec<(K, Vec2)> {
+    let axis = |v: Vec2| match mode {
+        DistributeMode::Horizontal => v.x,
+        DistributeMode::Vertical => v.y,
+    };
+    let along = |amount: f32| match mode {
+        DistributeMode::Horizontal => Vec2::new(amount, 0.0),
+        DistributeMode::Vertical => Vec2::new(0.0, amount),
+    };
+
+    let mut deltas: Vec<(K, Vec2)> = items.iter().map(|(key, _)| (*key, Vec2::ZERO)).collect();
+    if items.len() < 3 {
+        return deltas;
+    }
+
+    let mut order: Vec<usize> = (0..items.len()).collect();
+    order.sort_by(|&a, &b| axis(items[a].1.min).total_cmp(&axis(items[b].1.min)));
+
+    let start = axis(items[order[0]].1.min);
+    let end = order.iter().map(|&i| axis(items[i].1.max)).fold(f32::MIN, f32::max);
+    let total_size: f32 = items.iter().map(|(_, b)| axis(b.max) - axis(b.min)).sum();
+    let gap = (end - start - total_size) / (items.len() - 1) as f32;
+
+    let mut cursor = start;
+    for &i in &order {
+        let bounds = items[i].1;
+        deltas[i].1 = along(cursor - axis(bounds.min));
+        cursor += axis(bounds.max) - axis(bounds.min) + gap;
+    }
+    deltas
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::{Shape, ShapeGeometry, ShapeStyle, Transform2D};
+
+    fn bbox(x: f32, y: f32, w: f32, h: f32) -> BBox {
+        BBox::new(Vec2::new(x, y), Vec2::new(x + w, y + h))
+    }
+
+    fn sample_items() -> Vec<(u64, BBox)> {
+        vec![
+            (1, bbox(0.0, 0.0, 10.0, 10.0)),
+            (2, bbox(50.0, 20.0, 30.0, 40.0)),
+        ]
+    }
+
+    #[test]
+    fn test_align_left_and_right() {
+        let deltas = compute_alignment_deltas(&sample_items(), AlignMode::Left);
+        assert_eq!(deltas, vec![(1, Vec2::ZERO), (2, Vec2::new(-50.0, 0.0))]);
+
+        let deltas = compute_alignment_deltas(&sample_items(), AlignMode::Right);
+        assert_eq!(deltas, vec![(1, Vec2::new(70.0, 0.0)), (2, Vec2::ZERO)]);
+    }
+
+    #[test]
+    fn test_align_top_and_bottom() {
+        let deltas = compute_alignment_deltas(&sample_items(), AlignMode::Top);
+        assert_eq!(deltas, vec![(1, Vec2::ZERO), (2, Vec2::new(0.0, -20.0))]);
+
+        let deltas = compute_alignment_deltas(&sample_items(), AlignMode::Bottom);
+        assert_eq!(deltas, vec![(1, Vec2::new(0.0, 50.0)), (2, Vec2::ZERO)]);
+    }
+
+    #[test]
+    fn test_align_centers() {
+        // Collective bounds span x 0..80, y 0..60 -> center (40, 30)
+        let deltas = compute_alignment_deltas(&sample_items(), AlignMode::CenterHorizontal);
+        assert_eq!(deltas, vec![(1, Vec2::new(35.0, 0.0)), (2, Vec2::new(-25.0, 0.0))]);
+
+        let deltas = compute_alignment_deltas(&sample_items(), AlignMode::CenterVertical);
+        assert_eq!(deltas, vec![(1, Vec2::new(0.0, 25.0)), (2, Vec2::new(0.0, -10.0))]);
+    }
+
+    fn uneven_items() -> Vec<(u64, BBox)> {
+        vec![
+            (1, bbox(10.0, 40.0, 20.0, 10.0)),
+            (2, bbox(60.0, 0.0, 50.0, 30.0)),
+            (3, bbox(0.0, 70.0, 40.0, 60.0)),
+        ]
+    }
+
+    #[test]
+    fn test_align_three_uneven_rects_horizontally() {
+        // Collective bounds span x 0..110 -> center 55
+        let deltas = compute_alignment_deltas(&uneven_items(), AlignMode::Left);
+        assert_eq!(deltas, vec![(1, Vec2::new(-10.0, 0.0)), (2, Vec2::new(-60.0, 0.0)), (3, Vec2::ZERO)]);
+
+        let deltas = compute_alignment_deltas(&uneven_items(), AlignMode::Right);
+        assert_eq!(deltas, vec![(1, Vec2::new(80.0, 0.0)), (2, Vec2::ZERO), (3, Vec2::new(70.0, 0.0))]);
+
+        let deltas = compute_alignment_deltas(&uneven_items(), AlignMode::CenterHorizontal);
+        assert_eq!(deltas, vec![(1, Vec2::new(35.0, 0.0)), (2, Vec2::new(-30.0, 0.0)), (3, Vec2::new(35.0, 0.0))]);
+    }
+
+    #[test]
+    fn test_align_three_uneven_rects_vertically() {
+        // Collective bounds span y 0..130 -> center 65
+        let deltas = compute_alignment_deltas(&uneven_items(), AlignMode::Top);
+        assert_eq!(deltas, vec![(1, Vec2::new(0.0, -40.0)), (2, Vec2::ZERO), (3, Vec2::new(0.0, -70.0))]);
+
+        let deltas = compute_alignment_deltas(&uneven_items(), AlignMode::Bottom);
+        assert_eq!(deltas, vec![(1, Vec2::new(0.0, 80.0)), (2, Vec2::new(0.0, 100.0)), (3, Vec2::ZERO)]);
+
+        let deltas = compute_alignment_deltas(&uneven_items(), AlignMode::CenterVertical);
+        assert_eq!(deltas, vec![(1, Vec2::new(0.0, 20.0)), (2, Vec2::new(0.0, 50.0)), (3, Vec2::new(0.0, -35.0))]);
+    }
+
+    #[test]
+    fn test_distribute_three_uneven_rects_in_both_axes() {
+        // x: 0..40, 10..30, 60..110 -> widths 110 over span 110, no gap
+        let deltas = compute_distribution_deltas(&uneven_items(), DistributeMode::Horizontal);
+        assert_eq!(deltas, vec![(1, Vec2::new(30.0, 0.0)), (2, Vec2::ZERO), (3, Vec2::ZERO)]);
+
+        // y: 0..30, 40..50, 70..130 -> heights 100 over span 130, gap 15
+        let deltas = compute_distribution_deltas(&uneven_items(), DistributeMode::Vertical);
+        assert_eq!(deltas, vec![(1, Vec2::new(0.0, 5.0)), (2, Vec2::ZERO), (3, Vec2::ZERO)]);
+    }
+
+    #[test]
+    fn test_align_flipped_shape_by_its_world_bounds() {
+        // Mirrored horizontally, the rectangle covers x 60..100 rather than 100..140
+        let mut flipped = Shape::new(ShapeGeometry::rectangle(40.0, 20.0), ShapeStyle::default());
+        flipped.transform = Transform2D::from_position(Vec2::new(100.0, 0.0)).with_scale(Vec2::new(-1.0, 1.0));
+        let items = vec![(1, bbox(0.0, 0.0, 10.0, 10.0)), (2, flipped.world_bounds())];
+
+        let deltas = compute_alignment_deltas(&items, AlignMode::Left);
+        assert_eq!(deltas, vec![(1, Vec2::ZERO), (2, Vec2::new(-60.0, 0.0))]);
+        let deltas = compute_alignment_deltas(&items, AlignMode::Right);
+        assert_eq!(deltas, vec![(1, Vec2::new(90.0, 0.0)), (2, Vec2::ZERO)]);
+    }
+
+    #[test]
+    fn test_distribute_horizontally() {
+        // Out of order on purpose: 0..10, 20..40, 90..100 -> span 100, widths 40, gap 30
+        let items = vec![
+            (1, bbox(90.0, 0.0, 10.0, 10.0)),
+            (2, bbox(0.0, 5.0, 10.0, 10.0)),
+            (3, bbox(20.0, 50.0, 20.0, 10.0)),
+        ];
+        let deltas = compute_distribution_deltas(&items, DistributeMode::Horizontal);
+        assert_eq!(deltas, vec![(1, Vec2::ZERO), (2, Vec2::ZERO), (3, Vec2::new(20.0, 0.0))]);
+
+        // Gaps between consecutive shapes are now equal
+        let moved: Vec<BBox> = items
+            .iter()
+            .zip(&deltas)
+            .map(|((_, b), (_, d))| BBox::new(b.min + *d, b.max + *d))
+            .collect();
+        assert_eq!(moved[2].min.x - moved[1].max.x, 30.0);
+        assert_eq!(moved[0].min.x - moved[2].max.x, 30.0);
+    }
+
+    #[test]
+    fn test_distribute_vertically() {
+        let items = vec![
+            (1, bbox(0.0, 0.0, 10.0, 10.0)),
+            (2, bbox(0.0, 15.0, 10.0, 10.0)),
+            (3, bbox(0.0, 70.0, 10.0, 30.0)),
+        ];
+        // Span 100, heights 50 -> gap 25, so the middle shape moves to y = 35
+        let deltas = compute_distribution_deltas(&items, DistributeMode::Vertical);
+        assert_eq!(deltas, vec![(1, Vec2::ZERO), (2, Vec2::new(0.0, 20.0)), (3, Vec2::ZERO)]);
+    }
+
+    #[test]
+    fn test_distribute_needs_three_items() {
+        let deltas = compute_distribution_deltas(&sample_items(), DistributeMode::Horizontal);
+        assert_eq!(deltas, vec![(1, Vec2::ZERO), (2, Vec2::ZERO)]);
+    }
+
+    #[test]
+    fn test_align_empty() {
+        let deltas = compute_alignment_deltas::<u64>(&[], AlignMode::Left);
+        assert!(deltas.is_empty());
+    }
+}
@@ -0,0 +1,104 @@
+use yew::prelude::*;
+use crate::types::{AlignMode, DistributeMode};
+
+#[derive(Properties, PartialEq)]
+pub struct AlignmentPanelProps {
+    pub on_align: Callback<AlignMode>,
+    pub on_distribute: Callback<DistributeMode>,
+    /// Distribution needs at least three selected units
+    #[prop_or_default]
+    pub can_distribute: bool,
+}
+
+/// Render a minimalist icon for an alignment mode
+fn render_align_icon(mode: AlignMode) -> Html {
+    let (guide, bars) = match mode {
+        AlignMode::Left => ("M2 2V14", "M4 4H12M4 10H9"),
+        AlignMode::Right => ("M14 2V14", "M4 4H12M7 10H12"),
+        AlignMode::Top => ("M2 2H14", "M4 4V12M10 4V9"),
+        AlignMode::Bottom => ("M2 14H14", "M4 4V12M10 7V12"),
+        AlignMode::CenterHorizontal => ("M8 2V14", "M3 5H13M5 11H11"),
+        AlignMode::CenterVertical => ("M2 8H14", "M5 3V13M11 5V11"),
+    };
+
+    html! {
+        <svg width="16" height="16" viewBox="0 0 16 16" fill="none" class="text-gray-600">
+            <path d={guide} stroke="currentColor" stroke-width="1" stroke-linecap="round"/>
+            <path d={bars} stroke="currentColor" stroke-width="2.5" stroke-linecap="round"/>
+        </svg>
+    }
+}
+
+/// Render a minimalist icon for a distribution mode
+fn render_distribute_icon(mode: DistributeMode) -> Html {
+    let (guides, bars) = match mode {
+        DistributeMode::Horizontal => ("M2 2V14M14 2V14", "M5 4V12M8 6V10M11 4V12"),
+        DistributeMode::Vertical => ("M2 2H14M2 14H14", "M4 5H12M6 8H10M4 11H12"),
+    };
+
+    html! {
+        <svg width="16" height="16" viewBox="0 0 16 16" fill="none" class="text-gray-600">
+            <path d={guides} stroke="currentColor" stroke-width="1" stroke-linecap="round"/>
+            <path d={bars} stroke="currentColor" stroke-width="2" stroke-linecap="round"/>
+        </svg>
+    }
+}
+
+#[function_component(AlignmentPanel)]
+pub fn alignment_panel(props: &AlignmentPanelProps) -> Html {
+    let modes = [
+        AlignMode::Left,
+        AlignMode::CenterHorizontal,
+        AlignMode::Right,
+        AlignMode::Top,
+        AlignMode::CenterVertical,
+        AlignMode::Bottom,
+    ];
+
+    html! {
+        <div class="mb-4">
+            <label class="block text-sm font-medium text-gray-700 mb-1">
+                {"Align"}
+            </label>
+            <div class="grid grid-cols-6 gap-1">
+                {
+                    modes.iter().map(|&mode| {
+                        let on_align = props.on_align.clone();
+                        html! {
+                            <button
+                                key={mode.to_kebab_case()}
+                                data-testid={format!("align-{}", mode.to_kebab_case())}
+                                title={mode.label()}
+                                onclick={Callback::from(move |_: MouseEvent| on_align.emit(mode))}
+                                class="h-8 flex items-center justify-center rounded border border-gray-300 bg-white hover:bg-gray-50"
+                            >
+                                {render_align_icon(mode)}
+                            </button>
+                        }
+                    }).collect::<Html>()
+                }
+            </div>
+            if props.can_distribute {
+                <div class="grid grid-cols-2 gap-1 mt-1">
+                    {
+                        [DistributeMode::Horizontal, DistributeMode::Vertical].iter().map(|&mode| {
+                            let on_distribute = props.on_distribute.clone();
+                            html! {
+                                <button
+                                    key={mode.to_kebab_case()}
+                                    data-testid={format!("distribute-{}", mode.to_kebab_case())}
+                                    title={mode.label()}
+                                    onclick={Callback::from(move |_: MouseEvent| on_distribute.emit(mode))}
+                                    class="h-8 flex items-center justify-center gap-1 rounded border border-gray-300 bg-white hover:bg-gray-50 text-xs text-gray-700"
+                                >
+                                    {render_distribute_icon(mode)}
+                                    {mode.label()}
+                                </button>
+                            }
+                        }).collect::<Html>()
+                    }
+                </div>
+            }
+        </div>
+    }
+}
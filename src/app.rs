@@ -1,9 +1,139 @@
 use yew::prelude::*;
-use crate::resizable_canvas::ResizableCanvas;
+use wasm_bindgen::JsCast;
+use gloo::events::EventListener;
+use crate::autosave::{self, BrowserStorage};
+use crate::canvas_tabs::{CanvasState, CanvasTabs, TabAction};
+use crate::resizable_canvas::{get_initial_scene_and_tree, ResizableCanvas};
 
 #[function_component(App)]
 pub fn app() -> Html {
+    // The first tab opens on the autosaved canvas, or else the demo scene with
+    // pre-grouped Snoopy and Flower
+    let tabs = use_reducer(|| {
+        let first = match autosave::restore(&BrowserStorage) {
+            Some(saved) => {
+                saved.reserve_ids();
+                CanvasState { view: saved.view, ..CanvasState::new(1, saved.shapes, saved.layer_tree) }
+            }
+            None => {
+                let (scene, layer_tree) = get_initial_scene_and_tree();
+                CanvasState::new(1, scene.shapes().to_vec(), layer_tree)
+            }
+        };
+        CanvasTabs::new(first)
+    });
+
+    // Keyboard shortcuts: Ctrl/Cmd+T opens a tab, Ctrl/Cmd+W closes the active one
+    {
+        let dispatcher = tabs.dispatcher();
+        let active_ref = use_mut_ref(|| 0usize);
+        *active_ref.borrow_mut() = tabs.active;
+
+        use_effect_with((), move |_| {
+            let window = web_sys::window().expect("no window");
+            let document = window.document().expect("no document");
+
+            let options = gloo::events::EventListenerOptions::enable_prevent_default();
+            let listener = EventListener::new_with_options(&document, "keydown", options, move |event| {
+                let Some(keyboard_event) = event.dyn_ref::<web_sys::KeyboardEvent>() else {
+                    return;
+                };
+                if !(keyboard_event.meta_key() || keyboard_event.ctrl_key()) {
+                    return;
+                }
+                match keyboard_event.key().to_lowercase().as_str() {
+                    "t" => {
+                        keyboard_event.prevent_default();
+                        dispatcher.dispatch(TabAction::New);
+                    }
+                    "w" => {
+                        keyboard_event.prevent_default();
+                        dispatcher.dispatch(TabAction::Close(*active_ref.borrow()));
+                    }
+                    _ => {}
+                }
+            });
+
+            move || drop(listener)
+        });
+    }
+
+    let on_new_tab = {
+        let dispatcher = tabs.dispatcher();
+        Callback::from(move |_: MouseEvent| dispatcher.dispatch(TabAction::New))
+    };
+
+    let on_snapshot = {
+        let dispatcher = tabs.dispatcher();
+        Callback::from(move |state: CanvasState| dispatcher.dispatch(TabAction::Snapshot(state)))
+    };
+
+    let can_close = tabs.tabs.len() > 1;
+    let active_tab = tabs.active_tab().clone();
+
     html! {
-        <ResizableCanvas />
+        <div class="flex flex-col w-full h-screen overflow-hidden">
+            // Tab bar
+            <div data-testid="canvas-tab-bar" class="flex items-end gap-1 px-2 pt-1 bg-gray-100 border-b border-gray-300 text-sm">
+                {for tabs.tabs.iter().enumerate().map(|(idx, tab)| {
+                    let is_active = idx == tabs.active;
+                    let on_activate = {
+                        let dispatcher = tabs.dispatcher();
+                        Callback::from(move |_: MouseEvent| dispatcher.dispatch(TabAction::Activate(idx)))
+                    };
+                    let on_close = {
+                        let dispatcher = tabs.dispatcher();
+                        Callback::from(move |e: MouseEvent| {
+                            // Don't also activate the tab being closed
+                            e.stop_propagation();
+                            dispatcher.dispatch(TabAction::Close(idx));
+                        })
+                    };
+
+                    html! {
+                        <div
+                            key={tab.id}
+                            data-testid={format!("canvas-tab-{}", idx)}
+                            data-active={is_active.to_string()}
+                            onclick={on_activate}
+                            class={classes!(
+                                "flex", "items-center", "gap-2", "px-3", "py-1", "rounded-t", "border",
+                                "border-b-0", "cursor-pointer", "select-none",
+                                if is_active {
+                                    "bg-white border-gray-300 text-gray-900"
+                                } else {
+                                    "bg-gray-50 border-transparent text-gray-500 hover:bg-gray-200"
+                                }
+                            )}
+                        >
+                            <span>{&tab.title}</span>
+                            if can_close {
+                                <button
+                                    data-testid={format!("canvas-tab-close-{}", idx)}
+                                    title="Close tab (Ctrl+W)"
+                                    onclick={on_close}
+                                    class="w-4 h-4 leading-none rounded text-gray-400 hover:text-gray-700 hover:bg-gray-300"
+                                >
+                                    {"×"}
+                                </button>
+                            }
+                        </div>
+                    }
+                })}
+                <button
+                    data-testid="canvas-tab-new"
+                    title="New tab (Ctrl+T)"
+                    onclick={on_new_tab}
+                    class="px-2 py-1 mb-0.5 rounded text-gray-500 hover:bg-gray-200"
+                >
+                    {"+"}
+                </button>
+            </div>
+
+            // Only the active canvas is mounted; keying by tab remounts it from the tab's state
+            <div class="flex-1 min-h-0">
+                <ResizableCanvas key={active_tab.id} canvas={active_tab.clone()} {on_snapshot} />
+            </div>
+        </div>
     }
 }
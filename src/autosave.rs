@@ -0,0 +1,379 @@
+//! Autosave of the canvas to localStorage
+//!
+//! Shortly after the last change, the canvas's shapes, layer tree and view are written
+//! as JSON under a key that carries the format version and a sequence number. On
+//! startup the newest save that can be read is restored, migrating older formats.
+//! A few earlier saves are kept as fallbacks, and when storage is full the oldest
+//! is dropped to make room.
+
+use crate::scene::{reserve_group_id, reserve_shape_id, LayerNode, LayerTree, Shape};
+use crate::types::ViewTransform;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Format written by `save`; bump it, and add a case to `migrate`, when `SavedCanvas` changes
+pub const AUTOSAVE_VERSION: u32 = 2;
+/// Delay after the last change before the canvas is saved, in milliseconds
+pub const AUTOSAVE_DELAY_MS: u32 = 500;
+/// Every autosave key starts with this, followed by `v{version}/{sequence}`
+const KEY_PREFIX: &str = "canvas-rs/autosave/";
+/// Saves kept, counting the newest; older ones are removed after each save
+const MAX_AUTOSAVES: usize = 3;
+
+/// What an autosave holds
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SavedCanvas {
+    pub shapes: Vec<Shape>,
+    pub layer_tree: LayerTree,
+    pub view: ViewTransform,
+}
+
+impl SavedCanvas {
+    /// Move the ID counters past this canvas's shapes and groups, so shapes and groups
+    /// created after restoring it don't reuse their IDs
+    pub fn reserve_ids(&self) {
+        fn reserve_groups(nodes: &[LayerNode]) {
+            for node in nodes {
+                if let LayerNode::Group { id, children, .. } = node {
+                    reserve_group_id(*id);
+                    reserve_groups(children);
+                }
+            }
+        }
+        for shape in &self.shapes {
+            reserve_shape_id(shape.id);
+        }
+        reserve_groups(&self.layer_tree.nodes);
+    }
+}
+
+/// Version 1 saved only the shapes, stacked in order with no groups
+#[derive(Deserialize)]
+struct SavedCanvasV1 {
+    shapes: Vec<Shape>,
+}
+
+/// Why a save couldn't be written
+#[derive(Clone, Debug, PartialEq)]
+pub enum StorageError {
+    /// Storage is full, even with every older autosave dropped
+    QuotaExceeded,
+    /// Storage refused the write for another reason, e.g. it's disabled
+    Other(String),
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::QuotaExceeded => write!(f, "storage is full"),
+            StorageError::Other(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+/// Key-value string storage that autosaves are kept in; `BrowserStorage` in the app
+pub trait AutosaveStorage {
+    fn get(&self, key: &str) -> Option<String>;
+    fn set(&mut self, key: &str, value: &str) -> Result<(), StorageError>;
+    fn remove(&mut self, key: &str);
+    fn keys(&self) -> Vec<String>;
+}
+
+/// The browser's localStorage
+pub struct BrowserStorage;
+
+impl AutosaveStorage for BrowserStorage {
+    fn get(&self, key: &str) -> Option<String> {
+        local_storage()?.get_item(key).ok().flatten()
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), StorageError> {
+        let storage = local_storage().ok_or_else(|| StorageError::Other("localStorage is unavailable".to_string()))?;
+        storage.set_item(key, value).map_err(|err| match gloo_utils::errors::JsError::try_from(err) {
+            Ok(err) if err.name == "QuotaExceededError" => StorageError::QuotaExceeded,
+            Ok(err) => StorageError::Other(err.message),
+            Err(err) => StorageError::Other(err.to_string()),
+        })
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(storage) = local_storage() {
+            let _ = storage.remove_item(key);
+        }
+    }
+
+    fn keys(&self) -> Vec<String> {
+        let Some(storage) = local_storage() else {
+            return Vec::new();
+        };
+        (0..storage.length().unwrap_or(0)).filter_map(|i| storage.key(i).ok().flatten()).collect()
+    }
+}
+
+/// localStorage, or None where it's disabled (e.g. some private browsing modes)
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok().flatten()
+}
+
+/// A stored autosave, located by its key
+#[derive(Clone, Debug, PartialEq)]
+struct AutosaveEntry {
+    key: String,
+    version: u32,
+    sequence: u64,
+}
+
+fn autosave_key(version: u32, sequence: u64) -> String {
+    format!("{}v{}/{}", KEY_PREFIX, version, sequence)
+}
+
+/// Autosaves in `storage`, oldest first
+fn autosaves(storage: &impl AutosaveStorage) -> Vec<AutosaveEntry> {
+    let mut entries: Vec<AutosaveEntry> = storage
+        .keys()
+        .into_iter()
+        .filter_map(|key| {
+            let (version, sequence) = key.strip_prefix(KEY_PREFIX)?.strip_prefix('v')?.split_once('/')?;
+            let (version, sequence) = (version.parse().ok()?, sequence.parse().ok()?);
+            Some(AutosaveEntry { key, version, sequence })
+        })
+        .collect();
+    entries.sort_by_key(|entry| entry.sequence);
+    entries
+}
+
+/// Read a payload written in format `version`; None if it's unreadable or from a
+/// newer version of the app
+fn migrate(version: u32, json: &str) -> Option<SavedCanvas> {
+    match version {
+        1 => {
+            let old: SavedCanvasV1 = serde_json::from_str(json).ok()?;
+            let ids: Vec<u64> = old.shapes.iter().map(|shape| shape.id).collect();
+            Some(SavedCanvas {
+                shapes: old.shapes,
+                layer_tree: LayerTree::from_shapes(&ids),
+                view: ViewTransform::default(),
+            })
+        }
+        AUTOSAVE_VERSION => serde_json::from_str(json).ok(),
+        _ => None,
+    }
+}
+
+/// Write `canvas` as the newest autosave, then drop the ones beyond `MAX_AUTOSAVES`
+/// When storage is full, older autosaves are dropped, oldest first, until it fits
+pub fn save(storage: &mut impl AutosaveStorage, canvas: &SavedCanvas) -> Result<(), StorageError> {
+    let json = serde_json::to_string(canvas).map_err(|err| StorageError::Other(err.to_string()))?;
+    let mut older = autosaves(storage);
+    let sequence = older.last().map_or(1, |newest| newest.sequence + 1);
+    let key = autosave_key(AUTOSAVE_VERSION, sequence);
+
+    loop {
+        match storage.set(&key, &json) {
+            Ok(()) => break,
+            Err(StorageError::QuotaExceeded) if !older.is_empty() => storage.remove(&older.remove(0).key),
+            Err(err) => return Err(err),
+        }
+    }
+
+    let excess = (older.len() + 1).saturating_sub(MAX_AUTOSAVES);
+    for entry in older.drain(..excess) {
+        storage.remove(&entry.key);
+    }
+    Ok(())
+}
+
+/// The newest autosave that can be read, in the current format
+pub fn restore(storage: &impl AutosaveStorage) -> Option<SavedCanvas> {
+    autosaves(storage)
+        .into_iter()
+        .rev()
+        .find_map(|entry| migrate(entry.version, &storage.get(&entry.key)?))
+}
+
+/// Remove every autosave
+pub fn clear(storage: &mut impl AutosaveStorage) {
+    for entry in autosaves(storage) {
+        storage.remove(&entry.key);
+    }
+}
+
+/// Tracks when a burst of changes has settled: each change pushes the deadline back,
+/// and the save happens once, when the deadline passes with no newer change
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Debounce {
+    /// Time (ms) after which the pending change is due; None when nothing is pending
+    due: Option<f64>,
+}
+
+impl Debounce {
+    /// Record a change at `now` (ms)
+    pub fn touch(&mut self, now: f64) {
+        self.due = Some(now + AUTOSAVE_DELAY_MS as f64);
+    }
+
+    /// Whether a change is pending and has settled by `now`; clears it if so
+    pub fn take_due(&mut self, now: f64) -> bool {
+        match self.due {
+            Some(due) if now >= due => {
+                self.due = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::{ShapeGeometry, ShapeStyle};
+    use crate::types::Point;
+    use std::collections::BTreeMap;
+
+    /// In-memory storage that fills up after `capacity` bytes of values
+    #[derive(Default)]
+    struct MockStorage {
+        items: BTreeMap<String, String>,
+        capacity: Option<usize>,
+    }
+
+    impl AutosaveStorage for MockStorage {
+        fn get(&self, key: &str) -> Option<String> {
+            self.items.get(key).cloned()
+        }
+
+        fn set(&mut self, key: &str, value: &str) -> Result<(), StorageError> {
+            let used: usize = self.items.iter().filter(|(k, _)| *k != key).map(|(_, v)| v.len()).sum();
+            if self.capacity.is_some_and(|capacity| used + value.len() > capacity) {
+                return Err(StorageError::QuotaExceeded);
+            }
+            self.items.insert(key.to_string(), value.to_string());
+            Ok(())
+        }
+
+        fn remove(&mut self, key: &str) {
+            self.items.remove(key);
+        }
+
+        fn keys(&self) -> Vec<String> {
+            self.items.keys().cloned().collect()
+        }
+    }
+
+    fn canvas(count: usize) -> SavedCanvas {
+        let shapes: Vec<Shape> = (0..count)
+            .map(|_| Shape::new(ShapeGeometry::rectangle(10.0, 20.0), ShapeStyle::default()))
+            .collect();
+        let mut layer_tree = LayerTree::from_shapes(&shapes.iter().map(|s| s.id).collect::<Vec<_>>());
+        if count >= 2 {
+            layer_tree.group_shapes(&[shapes[0].id, shapes[1].id]);
+        }
+        SavedCanvas { shapes, layer_tree, view: ViewTransform::new(2.0, Point::new(15.0, -30.0)) }
+    }
+
+    #[test]
+    fn test_save_and_restore_round_trip() {
+        let mut storage = MockStorage::default();
+        assert_eq!(restore(&storage), None);
+
+        let saved = canvas(3);
+        save(&mut storage, &saved).unwrap();
+        assert_eq!(storage.keys(), vec![autosave_key(AUTOSAVE_VERSION, 1)]);
+        let restored = restore(&storage).unwrap();
+        assert_eq!(restored.layer_tree, saved.layer_tree);
+        assert_eq!(restored.view, saved.view);
+        assert_eq!(restored.shapes.iter().map(|s| (s.id, &s.geometry)).collect::<Vec<_>>(),
+            saved.shapes.iter().map(|s| (s.id, &s.geometry)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_newest_saves_are_kept_and_restored_first() {
+        let mut storage = MockStorage::default();
+        let canvases: Vec<SavedCanvas> = (1..=5).map(canvas).collect();
+        for saved in &canvases {
+            save(&mut storage, saved).unwrap();
+        }
+        let sequences: Vec<u64> = autosaves(&storage).iter().map(|entry| entry.sequence).collect();
+        assert_eq!(sequences, vec![3, 4, 5]);
+        assert_eq!(restore(&storage).unwrap().shapes.len(), 5);
+
+        // An unreadable newest save falls back to the one before it
+        storage.items.insert(autosave_key(AUTOSAVE_VERSION, 6), "{not json".to_string());
+        assert_eq!(restore(&storage).unwrap().shapes.len(), 5);
+
+        clear(&mut storage);
+        assert!(storage.keys().is_empty());
+    }
+
+    #[test]
+    fn test_full_storage_drops_the_oldest_autosave() {
+        let mut storage = MockStorage::default();
+        let small = canvas(1);
+        save(&mut storage, &small).unwrap();
+        save(&mut storage, &small).unwrap();
+        let one_save = storage.items.values().next().unwrap().len();
+        // Room for two small saves and an unrelated item, but not a third save
+        storage.items.insert("other-app".to_string(), "x".repeat(10));
+        storage.capacity = Some(one_save * 2 + 10 + one_save / 2);
+
+        save(&mut storage, &small).unwrap();
+        let sequences: Vec<u64> = autosaves(&storage).iter().map(|entry| entry.sequence).collect();
+        assert_eq!(sequences, vec![2, 3]);
+        assert!(storage.items.contains_key("other-app"));
+
+        // Too big even with every autosave dropped
+        storage.capacity = Some(one_save / 2);
+        assert_eq!(save(&mut storage, &canvas(4)), Err(StorageError::QuotaExceeded));
+        assert!(autosaves(&storage).is_empty());
+    }
+
+    #[test]
+    fn test_version_1_payloads_migrate() {
+        let shapes = canvas(2).shapes;
+        let json = format!(r#"{{"shapes":{}}}"#, serde_json::to_string(&shapes).unwrap());
+        let mut storage = MockStorage::default();
+        storage.items.insert(autosave_key(1, 7), json);
+        // Saves from a newer version of the app are skipped
+        storage.items.insert(autosave_key(AUTOSAVE_VERSION + 1, 8), "{}".to_string());
+
+        let restored = restore(&storage).unwrap();
+        assert_eq!(restored.shapes.len(), 2);
+        assert_eq!(restored.layer_tree, LayerTree::from_shapes(&[shapes[0].id, shapes[1].id]));
+        assert_eq!(restored.view, ViewTransform::default());
+
+        // The next save continues the sequence in the current format
+        save(&mut storage, &restored).unwrap();
+        assert!(storage.items.contains_key(&autosave_key(AUTOSAVE_VERSION, 9)));
+    }
+
+    #[test]
+    fn test_restored_ids_are_not_reused() {
+        let saved = canvas(2);
+        let json = serde_json::to_string(&saved).unwrap();
+        let mut restored: SavedCanvas = serde_json::from_str(&json).unwrap();
+        // Pretend the save came from a session whose IDs ran far ahead of this one
+        for shape in &mut restored.shapes {
+            shape.id += 1_000_000;
+        }
+        restored.reserve_ids();
+        let fresh = Shape::new(ShapeGeometry::rectangle(1.0, 1.0), ShapeStyle::default());
+        assert!(restored.shapes.iter().all(|shape| fresh.id > shape.id));
+    }
+
+    #[test]
+    fn test_debounce_waits_for_changes_to_settle() {
+        let mut debounce = Debounce::default();
+        assert!(!debounce.take_due(1_000.0));
+
+        debounce.touch(0.0);
+        debounce.touch(300.0);
+        // The first change's deadline has passed, but a newer change pushed it back
+        assert!(!debounce.take_due(500.0));
+        assert!(debounce.take_due(800.0));
+        // Only once per burst
+        assert!(!debounce.take_due(900.0));
+    }
+}
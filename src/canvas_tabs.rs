@@ -0,0 +1,195 @@
+use std::rc::Rc;
+use yew::prelude::*;
+use crate::scene::{LayerTree, Shape};
+use crate::types::ViewTransform;
+use crate::version::VersionHistory;
+
+/// Everything one canvas tab keeps while another tab is showing
+#[derive(Clone, Debug, PartialEq)]
+pub struct CanvasState {
+    /// Unique tab ID, used as the canvas component's key
+    pub id: u64,
+    /// Label shown in the tab bar
+    pub title: String,
+    pub shapes: Vec<Shape>,
+    pub layer_tree: LayerTree,
+    pub version_history: VersionHistory,
+    pub has_unsaved_changes: bool,
+    /// Zoom and pan, so each tab comes back where it was left
+    pub view: ViewTransform,
+}
+
+impl CanvasState {
+    /// An empty canvas with a default title
+    pub fn empty(id: u64) -> Self {
+        Self::new(id, Vec::new(), LayerTree::new())
+    }
+
+    pub fn new(id: u64, shapes: Vec<Shape>, layer_tree: LayerTree) -> Self {
+        Self {
+            id,
+            title: format!("Canvas {}", id),
+            shapes,
+            layer_tree,
+            version_history: VersionHistory::new(),
+            has_unsaved_changes: true,
+            view: ViewTransform::default(),
+        }
+    }
+}
+
+/// Open canvas tabs; only the active one is mounted
+#[derive(Clone, Debug, PartialEq)]
+pub struct CanvasTabs {
+    pub tabs: Vec<CanvasState>,
+    /// Index into `tabs` of the tab being shown
+    pub active: usize,
+    /// ID counter for new tabs
+    pub next_id: u64,
+}
+
+/// Changes to the open tabs
+#[derive(Clone, Debug, PartialEq)]
+pub enum TabAction {
+    /// Open an empty canvas and switch to it
+    New,
+    /// Close a tab; the last remaining tab can't be closed
+    Close(usize),
+    /// Switch to a tab
+    Activate(usize),
+    /// Store a canvas's state as it unmounts; ignored for tabs that were closed
+    Snapshot(CanvasState),
+}
+
+impl CanvasTabs {
+    /// Start with a single tab holding `first`
+    pub fn new(first: CanvasState) -> Self {
+        let next_id = first.id + 1;
+        Self {
+            tabs: vec![first],
+            active: 0,
+            next_id,
+        }
+    }
+
+    pub fn active_tab(&self) -> &CanvasState {
+        &self.tabs[self.active]
+    }
+
+    /// Apply `action` in place
+    pub fn apply(&mut self, action: TabAction) {
+        match action {
+            TabAction::New => {
+                self.tabs.push(CanvasState::empty(self.next_id));
+                self.next_id += 1;
+                self.active = self.tabs.len() - 1;
+            }
+            TabAction::Close(idx) => {
+                if self.tabs.len() <= 1 || idx >= self.tabs.len() {
+                    return;
+                }
+                self.tabs.remove(idx);
+                // Keep showing the same tab, or its left neighbour if it was the one closed
+                if idx < self.active || self.active == self.tabs.len() {
+                    self.active -= 1;
+                }
+            }
+            TabAction::Activate(idx) => {
+                if idx < self.tabs.len() {
+                    self.active = idx;
+                }
+            }
+            TabAction::Snapshot(state) => {
+                if let Some(tab) = self.tabs.iter_mut().find(|tab| tab.id == state.id) {
+                    *tab = state;
+                }
+            }
+        }
+    }
+}
+
+impl Reducible for CanvasTabs {
+    type Action = TabAction;
+
+    fn reduce(self: Rc<Self>, action: TabAction) -> Rc<Self> {
+        let mut next = (*self).clone();
+        next.apply(action);
+        Rc::new(next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::{ShapeGeometry, ShapeStyle};
+
+    fn three_tabs() -> CanvasTabs {
+        let mut tabs = CanvasTabs::new(CanvasState::empty(1));
+        tabs.apply(TabAction::New);
+        tabs.apply(TabAction::New);
+        tabs
+    }
+
+    #[test]
+    fn test_new_tab_opens_empty_and_becomes_active() {
+        let tabs = three_tabs();
+        assert_eq!(tabs.tabs.iter().map(|t| t.id).collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(tabs.active, 2);
+        assert_eq!(tabs.active_tab().title, "Canvas 3");
+        assert!(tabs.active_tab().shapes.is_empty());
+    }
+
+    #[test]
+    fn test_close_keeps_the_shown_tab_when_possible() {
+        // Closing a tab left of the active one keeps showing the same canvas
+        let mut tabs = three_tabs();
+        tabs.apply(TabAction::Close(0));
+        assert_eq!(tabs.active_tab().id, 3);
+
+        // Closing the active last tab falls back to its left neighbour
+        let mut tabs = three_tabs();
+        tabs.apply(TabAction::Close(2));
+        assert_eq!(tabs.active_tab().id, 2);
+
+        // Closing the active middle tab shows the one that slid into its place
+        let mut tabs = three_tabs();
+        tabs.apply(TabAction::Activate(1));
+        tabs.apply(TabAction::Close(1));
+        assert_eq!(tabs.active_tab().id, 3);
+
+        // Closing a tab to the right leaves the active one alone
+        let mut tabs = three_tabs();
+        tabs.apply(TabAction::Activate(0));
+        tabs.apply(TabAction::Close(2));
+        assert_eq!(tabs.active_tab().id, 1);
+    }
+
+    #[test]
+    fn test_last_tab_cannot_be_closed() {
+        let mut tabs = CanvasTabs::new(CanvasState::empty(1));
+        tabs.apply(TabAction::Close(0));
+        assert_eq!(tabs.tabs.len(), 1);
+
+        // Out-of-range indices are ignored too
+        let mut tabs = three_tabs();
+        tabs.apply(TabAction::Close(7));
+        tabs.apply(TabAction::Activate(7));
+        assert_eq!((tabs.tabs.len(), tabs.active), (3, 2));
+    }
+
+    #[test]
+    fn test_snapshot_updates_matching_tab_only() {
+        let mut tabs = three_tabs();
+        let shape = Shape::new(ShapeGeometry::rectangle(10.0, 10.0), ShapeStyle::default());
+        let mut state = CanvasState::new(2, vec![shape], LayerTree::new());
+        state.view = ViewTransform::new(2.0, crate::types::Point::new(5.0, 5.0));
+
+        tabs.apply(TabAction::Snapshot(state.clone()));
+        assert_eq!(tabs.tabs[1], state);
+
+        // A canvas closed before its snapshot arrived stays closed
+        tabs.apply(TabAction::Close(0));
+        tabs.apply(TabAction::Snapshot(CanvasState::empty(1)));
+        assert!(tabs.tabs.iter().all(|t| t.id != 1));
+    }
+}
@@ -0,0 +1,95 @@
+use serde::{Deserialize, Serialize};
+
+use crate::scene::{Shape, Vec2};
+
+/// Offset applied to pasted shapes so they don't land exactly on the originals
+pub const PASTE_OFFSET: Vec2 = Vec2::new(20.0, 20.0);
+
+/// Tag identifying clipboard text written by this editor
+const CLIPBOARD_KIND: &str = "canvas-rs/shapes";
+
+/// Clipboard payload: the copied shapes plus a tag so arbitrary text isn't mistaken for shapes
+#[derive(Serialize, Deserialize)]
+struct ClipboardPayload {
+    kind: String,
+    shapes: Vec<Shape>,
+}
+
+/// Serialize shapes for the system clipboard
+pub fn serialize_shapes(shapes: &[Shape]) -> String {
+    let payload = ClipboardPayload {
+        kind: CLIPBOARD_KIND.to_string(),
+        shapes: shapes.to_vec(),
+    };
+    serde_json::to_string(&payload).unwrap_or_default()
+}
+
+/// Parse clipboard text written by `serialize_shapes`; None for anything else
+pub fn deserialize_shapes(text: &str) -> Option<Vec<Shape>> {
+    serde_json::from_str::<ClipboardPayload>(text)
+        .ok()
+        .filter(|payload| payload.kind == CLIPBOARD_KIND)
+        .map(|payload| payload.shapes)
+}
+
+/// Create pasteable copies of `shapes`: fresh IDs, same names and styles, moved by `offset`
+pub fn paste_copies(shapes: &[Shape], offset: Vec2) -> Vec<Shape> {
+    shapes
+        .iter()
+        .map(|shape| {
+            let mut transform = shape.transform;
+            transform.position += offset;
+            Shape::new(shape.geometry.clone(), shape.style.clone())
+                .with_name(shape.name.clone())
+                .with_transform(transform)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::{Color, ShapeGeometry, ShapeStyle, StrokeStyle, Transform2D};
+
+    fn sample_shapes() -> Vec<Shape> {
+        vec![
+            Shape::new(
+                ShapeGeometry::polygon(vec![Vec2::ZERO, Vec2::new(30.0, 0.0), Vec2::new(15.0, 30.0)]),
+                ShapeStyle::fill_and_stroke(Color::rgb(1.0, 0.0, 0.0), StrokeStyle::new(Color::black(), 1.0)),
+            ),
+            Shape::new(ShapeGeometry::ellipse(10.0, 5.0), ShapeStyle::fill_only(Color::white()).with_opacity(0.5))
+                .with_transform(Transform2D::from_position(Vec2::new(100.0, 50.0))),
+        ]
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut shapes = sample_shapes();
+        shapes[0].visible = false;
+        shapes[1].locked = true;
+        let parsed = deserialize_shapes(&serialize_shapes(&shapes)).unwrap();
+
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0].geometry, shapes[0].geometry);
+        assert_eq!(parsed[1].style, shapes[1].style);
+        assert_eq!(parsed[1].transform, shapes[1].transform);
+        assert!(!parsed[0].visible && !parsed[0].locked);
+        assert!(parsed[1].visible && parsed[1].locked);
+    }
+
+    #[test]
+    fn test_rejects_foreign_text() {
+        assert!(deserialize_shapes("hello").is_none());
+        assert!(deserialize_shapes(r#"{"kind":"other","shapes":[]}"#).is_none());
+    }
+
+    #[test]
+    fn test_paste_copies_offset_with_new_ids() {
+        let shapes = sample_shapes();
+        let copies = paste_copies(&shapes, PASTE_OFFSET);
+
+        assert_eq!(copies[1].transform.position, Vec2::new(120.0, 70.0));
+        assert_eq!(copies[1].name, shapes[1].name);
+        assert!(copies.iter().zip(&shapes).all(|(copy, original)| copy.id != original.id));
+    }
+}
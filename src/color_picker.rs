@@ -0,0 +1,261 @@
+use web_sys::{Element, HtmlInputElement};
+use yew::prelude::*;
+
+use crate::scene::Color;
+
+/// How many recently used colors the picker keeps as swatches
+pub const MAX_RECENT_COLORS: usize = 8;
+
+/// Checkerboard drawn behind colors so their alpha shows
+const CHECKERBOARD: &str = "background-image: conic-gradient(#d1d5db 25%, #ffffff 0 50%, #d1d5db 0 75%, #ffffff 0); background-size: 8px 8px;";
+
+/// Every hue at full saturation and value, for the hue slider track
+const HUE_TRACK: &str = "linear-gradient(to right, #ff0000, #ffff00, #00ff00, #00ffff, #0000ff, #ff00ff, #ff0000)";
+
+#[derive(Properties, PartialEq)]
+pub struct ColorPickerProps {
+    pub color: Color,
+    /// Emitted on every edit, including while dragging, so the canvas updates live
+    pub on_change: Callback<Color>,
+    /// Prefix for the picker's test IDs, e.g. "fill" gives "fill-hex"
+    pub test_id: AttrValue,
+    /// The selection has differing colors; `color` is only where the square starts
+    #[prop_or_default]
+    pub mixed: bool,
+}
+
+/// Recent colors with `color` moved to the front, keeping at most `MAX_RECENT_COLORS`
+pub fn with_recent(recent: &[Color], color: Color) -> Vec<Color> {
+    std::iter::once(color)
+        .chain(recent.iter().copied().filter(|c| c.to_css() != color.to_css()))
+        .take(MAX_RECENT_COLORS)
+        .collect()
+}
+
+/// Hex shown in the text field: `#rrggbb`, with alpha digits only when translucent
+fn hex_value(color: Color) -> String {
+    if color.a >= 1.0 {
+        color.to_hex()
+    } else {
+        format!("{}{:02x}", color.to_hex(), (color.a.clamp(0.0, 1.0) * 255.0).round() as u8)
+    }
+}
+
+/// Swatch, hex field and an expandable saturation/value square with hue and alpha sliders
+#[function_component(ColorPicker)]
+pub fn color_picker(props: &ColorPickerProps) -> Html {
+    let open = use_state(|| false);
+    // Greys and black have no hue of their own, so the last one picked is kept for the square
+    let last_hue = use_state(|| props.color.to_hsv().0);
+    let recent = use_state(Vec::<Color>::new);
+    let square_ref = use_node_ref();
+
+    let color = props.color;
+    let (h, s, v) = color.to_hsv();
+    let hue = if s > 0.0 && v > 0.0 { h } else { *last_hue };
+
+    let remember = {
+        let recent = recent.clone();
+        Callback::from(move |color: Color| recent.set(with_recent(&recent, color)))
+    };
+
+    // Saturation and value from a pointer position over the square
+    let pick = {
+        let square_ref = square_ref.clone();
+        let on_change = props.on_change.clone();
+        move |e: &MouseEvent| {
+            let Some(square) = square_ref.cast::<Element>() else {
+                return;
+            };
+            let rect = square.get_bounding_client_rect();
+            if rect.width() <= 0.0 || rect.height() <= 0.0 {
+                return;
+            }
+            let saturation = ((e.client_x() as f64 - rect.left()) / rect.width()).clamp(0.0, 1.0);
+            let value = 1.0 - ((e.client_y() as f64 - rect.top()) / rect.height()).clamp(0.0, 1.0);
+            on_change.emit(Color::from_hsv(hue, saturation as f32, value as f32, color.a));
+        }
+    };
+
+    html! {
+        <div class="space-y-2">
+            <div class="flex gap-2">
+                <button
+                    data-testid={format!("{}-swatch", props.test_id)}
+                    title="Pick color"
+                    onclick={
+                        let open = open.clone();
+                        Callback::from(move |_: MouseEvent| open.set(!*open))
+                    }
+                    class="w-12 h-8 rounded border border-gray-300 overflow-hidden cursor-pointer"
+                    style={CHECKERBOARD}
+                >
+                    if !props.mixed {
+                        <div class="w-full h-full" style={format!("background: {};", color.to_css())}></div>
+                    }
+                </button>
+                <input
+                    type="text"
+                    data-testid={format!("{}-hex", props.test_id)}
+                    value={if props.mixed { String::new() } else { hex_value(color) }}
+                    placeholder={props.mixed.then_some("Mixed")}
+                    oninput={
+                        let on_change = props.on_change.clone();
+                        Callback::from(move |e: InputEvent| {
+                            if let Some(color) = e.target_dyn_into::<HtmlInputElement>().and_then(|input| Color::parse(&input.value())) {
+                                on_change.emit(color);
+                            }
+                        })
+                    }
+                    onchange={
+                        let remember = remember.clone();
+                        Callback::from(move |e: Event| {
+                            if let Some(color) = e.target_dyn_into::<HtmlInputElement>().and_then(|input| Color::parse(&input.value())) {
+                                remember.emit(color);
+                            }
+                        })
+                    }
+                    class="flex-1 min-w-0 px-2 py-1 border border-gray-300 rounded text-sm bg-white text-gray-900"
+                />
+            </div>
+
+            if *open {
+                <div
+                    ref={square_ref}
+                    data-testid={format!("{}-picker-square", props.test_id)}
+                    onmousedown={
+                        let pick = pick.clone();
+                        Callback::from(move |e: MouseEvent| {
+                            e.prevent_default();
+                            pick(&e);
+                        })
+                    }
+                    onmousemove={Callback::from(move |e: MouseEvent| {
+                        // Only while the primary button is held
+                        if e.buttons() & 1 != 0 {
+                            pick(&e);
+                        }
+                    })}
+                    onmouseup={
+                        let remember = remember.clone();
+                        Callback::from(move |_: MouseEvent| remember.emit(color))
+                    }
+                    class="relative w-full h-32 rounded cursor-crosshair"
+                    style={format!("background-color: {};", Color::from_hsv(hue, 1.0, 1.0, 1.0).to_hex())}
+                >
+                    <div class="absolute inset-0 rounded pointer-events-none" style="background: linear-gradient(to right, #ffffff, rgba(255, 255, 255, 0));"></div>
+                    <div class="absolute inset-0 rounded pointer-events-none" style="background: linear-gradient(to bottom, rgba(0, 0, 0, 0), #000000);"></div>
+                    <div
+                        class="absolute w-3 h-3 -ml-1.5 -mt-1.5 rounded-full border-2 border-white shadow pointer-events-none"
+                        style={format!("left: {}%; top: {}%;", s * 100.0, (1.0 - v) * 100.0)}
+                    ></div>
+                </div>
+
+                <input
+                    type="range"
+                    min="0"
+                    max="360"
+                    step="1"
+                    data-testid={format!("{}-hue", props.test_id)}
+                    value={hue.round().to_string()}
+                    oninput={
+                        let last_hue = last_hue.clone();
+                        let on_change = props.on_change.clone();
+                        Callback::from(move |e: InputEvent| {
+                            if let Some(hue) = e.target_dyn_into::<HtmlInputElement>().and_then(|input| input.value().parse::<f32>().ok()) {
+                                last_hue.set(hue);
+                                on_change.emit(Color::from_hsv(hue, s, v, color.a));
+                            }
+                        })
+                    }
+                    onchange={
+                        let remember = remember.clone();
+                        Callback::from(move |_: Event| remember.emit(color))
+                    }
+                    class="w-full h-3 rounded appearance-none cursor-pointer"
+                    style={format!("background: {HUE_TRACK};")}
+                />
+
+                <div class="rounded" style={CHECKERBOARD}>
+                    <input
+                        type="range"
+                        min="0"
+                        max="100"
+                        step="1"
+                        data-testid={format!("{}-alpha", props.test_id)}
+                        value={(color.a * 100.0).round().to_string()}
+                        oninput={
+                            let on_change = props.on_change.clone();
+                            Callback::from(move |e: InputEvent| {
+                                if let Some(percent) = e.target_dyn_into::<HtmlInputElement>().and_then(|input| input.value().parse::<f32>().ok()) {
+                                    on_change.emit(Color { a: (percent / 100.0).clamp(0.0, 1.0), ..color });
+                                }
+                            })
+                        }
+                        onchange={
+                            let remember = remember.clone();
+                            Callback::from(move |_: Event| remember.emit(color))
+                        }
+                        class="w-full h-3 rounded appearance-none cursor-pointer block"
+                        style={format!(
+                            "background: linear-gradient(to right, {}, {});",
+                            Color { a: 0.0, ..color }.to_css(),
+                            color.to_hex()
+                        )}
+                    />
+                </div>
+
+                if !recent.is_empty() {
+                    <div class="flex gap-1">
+                        {
+                            recent.iter().enumerate().map(|(i, &swatch)| {
+                                let on_change = props.on_change.clone();
+                                let remember = remember.clone();
+                                html! {
+                                    <button
+                                        key={swatch.to_css()}
+                                        data-testid={format!("{}-recent-{}", props.test_id, i)}
+                                        title={swatch.to_css()}
+                                        onclick={Callback::from(move |_: MouseEvent| {
+                                            on_change.emit(swatch);
+                                            remember.emit(swatch);
+                                        })}
+                                        class="w-6 h-6 rounded border border-gray-300 overflow-hidden cursor-pointer"
+                                        style={CHECKERBOARD}
+                                    >
+                                        <div class="w-full h-full" style={format!("background: {};", swatch.to_css())}></div>
+                                    </button>
+                                }
+                            }).collect::<Html>()
+                        }
+                    </div>
+                }
+            }
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recent_colors_move_to_front_without_duplicates() {
+        let red = Color::rgb(1.0, 0.0, 0.0);
+        let blue = Color::rgb(0.0, 0.0, 1.0);
+        let recent = with_recent(&with_recent(&[], red), blue);
+        assert_eq!(recent, vec![blue, red]);
+        assert_eq!(with_recent(&recent, red), vec![red, blue]);
+
+        let many = (0..20).fold(Vec::new(), |recent, i| with_recent(&recent, Color::from_rgba8(i, 0, 0, 255)));
+        assert_eq!(many.len(), MAX_RECENT_COLORS);
+        assert_eq!(many[0], Color::from_rgba8(19, 0, 0, 255));
+    }
+
+    #[test]
+    fn test_hex_field_shows_alpha_only_when_translucent() {
+        assert_eq!(hex_value(Color::rgb(1.0, 0.0, 0.0)), "#ff0000");
+        assert_eq!(hex_value(Color::from_rgba8(255, 0, 0, 128)), "#ff000080");
+        assert_eq!(Color::parse(&hex_value(Color::from_rgba8(1, 2, 3, 4))), Some(Color::from_rgba8(1, 2, 3, 4)));
+    }
+}
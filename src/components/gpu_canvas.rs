@@ -1,9 +1,15 @@
 use crate::components::overlay::CanvasOverlay;
-use crate::gpu::{Renderer, Tessellator};
-use crate::scene::{BBox, Shape, Vec2};
-use crate::types::{Guideline, HandleName};
+use crate::components::svg_shapes::render_shape_svg;
+use crate::gpu::{
+    decode_image, image_pixels, shape_model_transform, FailureCounter, FrameScheduler, FrameStats, GridSource,
+    GridStyle, Mesh, Renderer, RendererError, ShapeTransitions, Tessellator,
+};
+use crate::scene::{BBox, Color, Shape, ShapeGeometry, Vec2};
+use crate::types::{Guideline, HandleName, Point, SpacingIndicator, ViewTransform};
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use gloo::events::EventListener;
+use gloo::render::{request_animation_frame, AnimationFrame};
 use std::rc::Rc;
 use web_sys::HtmlCanvasElement;
 use yew::prelude::*;
@@ -47,6 +53,10 @@ pub struct GpuCanvasProps {
     #[prop_or_default]
     pub guidelines: Vec<Guideline>,
 
+    /// Equal-spacing markers
+    #[prop_or_default]
+    pub spacing_indicators: Vec<SpacingIndicator>,
+
     /// Marquee selection rectangle
     #[prop_or_default]
     pub marquee_rect: Option<(Vec2, Vec2)>,
@@ -55,6 +65,30 @@ pub struct GpuCanvasProps {
     #[prop_or_default]
     pub preview_bbox: Option<BBox>,
 
+    /// Pen tool draft polyline
+    #[prop_or_default]
+    pub pen_preview: Vec<Vec2>,
+
+    /// Vertex-edit handles in world coordinates
+    #[prop_or_default]
+    pub vertex_handles: Vec<Vec2>,
+
+    /// Whether the vertex-edit handles form a closed outline
+    #[prop_or_default]
+    pub vertex_outline: bool,
+
+    /// Vertex-edit handle drawn as selected
+    #[prop_or_default]
+    pub selected_vertex: Option<usize>,
+
+    /// Path control points and the vertices they hang from, in world coordinates
+    #[prop_or_default]
+    pub control_handles: Vec<(Vec2, Vec2)>,
+
+    /// Snap grid spacing to draw behind the overlay controls
+    #[prop_or_default]
+    pub grid_size: Option<f64>,
+
     /// Mouse down callback
     #[prop_or_default]
     pub onmousedown: Callback<MouseEvent>,
@@ -75,6 +109,14 @@ pub struct GpuCanvasProps {
     #[prop_or_default]
     pub on_bbox_mousedown: Callback<MouseEvent>,
 
+    /// Vertex handle mouse down callback (vertex index)
+    #[prop_or_default]
+    pub on_vertex_mousedown: Callback<(usize, MouseEvent)>,
+
+    /// Control point handle mouse down callback (control handle index)
+    #[prop_or_default]
+    pub on_control_mousedown: Callback<(usize, MouseEvent)>,
+
     /// Whether a shape is currently hovered (for cursor styling)
     #[prop_or(false)]
     pub is_shape_hovered: bool,
@@ -88,6 +130,44 @@ pub struct GpuCanvasProps {
     /// Used for efficient dragging/scaling without re-tessellation
     #[prop_or_default]
     pub transform_overrides: HashMap<u64, [[f32; 4]; 4]>,
+
+    /// Canvas zoom and pan, applied to shapes and overlay alike
+    #[prop_or_default]
+    pub view: ViewTransform,
+
+    /// Draw from a requestAnimationFrame loop instead of straight from prop changes
+    /// Changes between two animation frames then cost a single render
+    #[prop_or(false)]
+    pub render_loop: bool,
+
+    /// Called with the time each GPU frame took to tessellate and render
+    #[prop_or_default]
+    pub on_frame_stats: Option<Callback<FrameStats>>,
+
+    /// Draw the background dot grid, following the view's pan and zoom
+    #[prop_or(true)]
+    pub show_grid: bool,
+
+    /// Dot spacing, size and color of the background grid
+    #[prop_or_default]
+    pub grid_style: GridStyle,
+
+    /// Round shape vertices to pixel centers so thin strokes render crisp
+    #[prop_or(false)]
+    pub pixel_snap: bool,
+
+    /// Smooth shape edges with multisample anti-aliasing, where the GPU supports it
+    #[prop_or(true)]
+    pub msaa: bool,
+
+    /// Let shapes glide to a new position or size instead of jumping
+    #[prop_or(false)]
+    pub animate_transitions: bool,
+
+    /// Bumped along with an edit that should animate; shapes that moved or resized
+    /// since the last frame then spring to their new transform
+    #[prop_or(0)]
+    pub transition_version: u32,
 }
 
 /// State for the renderer
@@ -98,6 +178,21 @@ struct RendererState {
     mesh_cache: HashMap<u64, crate::gpu::Mesh>,
     /// Track which shape IDs we've seen for cache invalidation
     known_shape_ids: Vec<u64>,
+    /// Failed frames in a row; too many switches to the SVG fallback
+    failures: FailureCounter,
+}
+
+/// Everything one GPU frame draws
+#[derive(Clone)]
+struct Frame {
+    shapes: Vec<Shape>,
+    transform_overrides: HashMap<u64, [[f32; 4]; 4]>,
+    view: ViewTransform,
+    selection_bbox: Option<BBox>,
+    background_color: [f32; 4],
+    grid: Option<GridStyle>,
+    pixel_snap: bool,
+    msaa: bool,
 }
 
 /// GPU-accelerated canvas component with SVG overlay
@@ -106,36 +201,44 @@ struct RendererState {
 pub fn gpu_canvas(props: &GpuCanvasProps) -> Html {
     let canvas_ref = use_node_ref();
     let renderer_state: UseStateHandle<Option<Rc<RefCell<RendererState>>>> = use_state(|| None);
+    // Set when the GPU renderer can't be created or keeps failing; shapes are then drawn as SVG instead
+    let gpu_unavailable = use_state(|| false);
+    // Device pixels per CSS pixel; the backing store is this much larger than the CSS size
+    let pixel_ratio = use_state(device_pixel_ratio);
+    let backing_width = physical_size(props.width, *pixel_ratio);
+    let backing_height = physical_size(props.height, *pixel_ratio);
 
     // Initialize renderer on mount
     {
         let canvas_ref = canvas_ref.clone();
         let renderer_state = renderer_state.clone();
-        let width = props.width;
-        let height = props.height;
+        let gpu_unavailable = gpu_unavailable.clone();
+        let ratio = *pixel_ratio;
 
         use_effect_with((), move |_| {
             let canvas_ref = canvas_ref.clone();
 
             if let Some(canvas) = canvas_ref.cast::<HtmlCanvasElement>() {
                 // Set canvas size
-                canvas.set_width(width);
-                canvas.set_height(height);
+                canvas.set_width(backing_width);
+                canvas.set_height(backing_height);
 
                 // Initialize renderer asynchronously
                 wasm_bindgen_futures::spawn_local(async move {
-                    match Renderer::new(canvas).await {
+                    match Renderer::new(canvas, ratio as f32).await {
                         Ok(renderer) => {
                             let state = RendererState {
                                 renderer,
                                 tessellator: Tessellator::new(),
                                 mesh_cache: HashMap::new(),
                                 known_shape_ids: Vec::new(),
+                                failures: FailureCounter::default(),
                             };
                             renderer_state.set(Some(Rc::new(RefCell::new(state))));
                         }
                         Err(e) => {
                             web_sys::console::error_1(&format!("Failed to create renderer: {}", e).into());
+                            gpu_unavailable.set(true);
                         }
                     }
                 });
@@ -145,18 +248,105 @@ pub fn gpu_canvas(props: &GpuCanvasProps) -> Html {
         });
     }
 
+    // Watch for the pixel ratio changing, e.g. when the window moves to another monitor
+    // The media query only matches the current ratio, so it's re-registered on every change
+    {
+        let pixel_ratio = pixel_ratio.clone();
+        use_effect_with(*pixel_ratio, move |ratio| {
+            let query = web_sys::window()
+                .and_then(|window| window.match_media(&format!("(resolution: {ratio}dppx)")).ok().flatten());
+            let listener = query.map(|query| {
+                EventListener::new(&query, "change", move |_| pixel_ratio.set(device_pixel_ratio()))
+            });
+            move || drop(listener)
+        });
+    }
+
+    // Keep the renderer's surface in step with the backing store
+    {
+        let renderer_state_clone = (*renderer_state).clone();
+        let ratio = *pixel_ratio;
+        use_effect_with((renderer_state_clone.is_some(), backing_width, backing_height, ratio.to_bits()), move |_| {
+            if let Some(ref state) = renderer_state_clone {
+                state.borrow_mut().renderer.resize(backing_width, backing_height, ratio as f32);
+            }
+            || ()
+        });
+    }
+
+    // Decode and upload the textures of image shapes the renderer doesn't have yet
+    // Each finished upload bumps `images_loaded` so the frame is redrawn with the image
+    let loading_images = use_mut_ref(HashSet::<String>::new);
+    let images_loaded = use_state(|| 0u32);
+    let uploads = use_mut_ref(|| 0u32);
+    {
+        let renderer_state_clone = (*renderer_state).clone();
+        let images_loaded = images_loaded.clone();
+        use_effect_with((renderer_state_clone.is_some(), props.shapes.clone()), move |(_, shapes)| {
+            if let Some(state) = renderer_state_clone {
+                for src in image_sources(shapes) {
+                    if state.borrow().renderer.has_image(src) || !loading_images.borrow_mut().insert(src.to_string()) {
+                        continue;
+                    }
+                    let src = src.to_string();
+                    let state = state.clone();
+                    let loading_images = loading_images.clone();
+                    let images_loaded = images_loaded.clone();
+                    let uploads = uploads.clone();
+                    wasm_bindgen_futures::spawn_local(async move {
+                        let pixels = decode_image(&src).await.and_then(|bitmap| image_pixels(&bitmap));
+                        loading_images.borrow_mut().remove(&src);
+                        match pixels {
+                            Ok((width, height, pixels)) => {
+                                state.borrow_mut().renderer.upload_image(&src, width, height, &pixels);
+                                *uploads.borrow_mut() += 1;
+                                images_loaded.set(*uploads.borrow());
+                            }
+                            Err(e) => web_sys::console::warn_1(&format!("Image won't render: {e}").into()),
+                        }
+                    });
+                }
+            }
+            || ()
+        });
+    }
+
+    // Frames requested since the last animation frame, when drawing from the render loop
+    let scheduler = use_mut_ref(FrameScheduler::<Frame>::default);
+    let on_frame_stats = use_mut_ref(|| None);
+    *on_frame_stats.borrow_mut() = props.on_frame_stats.clone();
+
+    // Shapes springing to a new transform, the frame they're drawn into and the
+    // animation frame stepping them; the loop runs only while something is moving
+    let transitions = use_mut_ref(ShapeTransitions::default);
+    let transition_base = use_mut_ref(|| None::<Frame>);
+    let transition_frame = use_mut_ref(|| None::<AnimationFrame>);
+    let seen_transition_version = use_mut_ref(|| props.transition_version);
+    {
+        let transition_frame = transition_frame.clone();
+        use_effect_with((), move |_| move || drop(transition_frame.borrow_mut().take()));
+    }
+
     // Render when shapes change or renderer becomes available
     // Uses cached tessellation and per-shape transforms for efficient dragging
     {
         let renderer_state_clone = (*renderer_state).clone();
+        let renderer_state = renderer_state.clone();
+        let gpu_unavailable = gpu_unavailable.clone();
+        let scheduler = scheduler.clone();
+        let on_frame_stats = props.on_frame_stats.clone();
+        let render_loop = props.render_loop;
         let shapes = props.shapes.clone();
         let background_color = props.background_color;
+        let grid = props.show_grid.then_some(props.grid_style);
+        let pixel_snap = props.pixel_snap;
+        let msaa = props.msaa;
         let transform_overrides = props.transform_overrides.clone();
         let render_version = props.render_version;
-
-        // Create a lightweight dependency: shape IDs, dirty flags, and transform overrides
-        // This avoids cloning entire shape geometries
-        let shape_deps: Vec<(u64, bool)> = shapes.iter().map(|s| (s.id, s.dirty)).collect();
+        let view = props.view;
+        let selection_bbox = props.selection_bbox;
+        let animate_transitions = props.animate_transitions;
+        let transition_version = props.transition_version;
 
         // For transform_overrides, we use the keys and a hash of values as dependency
         // This ensures the effect re-runs when transforms change
@@ -167,41 +357,67 @@ pub fn gpu_canvas(props: &GpuCanvasProps) -> Html {
             .fold(0u64, |acc, x| acc.wrapping_add(x));
 
         use_effect_with(
-            (renderer_state_clone.is_some(), shape_deps, override_keys, override_hash, render_version),
-            move |_| {
+            (
+                renderer_state_clone.is_some(),
+                // The shapes themselves, so any edit redraws even without a render_version bump
+                shapes,
+                override_keys,
+                override_hash,
+                render_version,
+                view,
+                selection_bbox,
+                grid,
+                (pixel_snap, msaa),
+                (backing_width, backing_height),
+                *images_loaded,
+                (animate_transitions, transition_version),
+            ),
+            move |(_, shapes, ..)| {
                 if let Some(ref state) = renderer_state_clone {
-                    let mut state = state.borrow_mut();
-
-                    // Update mesh cache - only tessellate new or dirty shapes
-                    let current_ids: Vec<u64> = shapes.iter().map(|s| s.id).collect();
-
-                    // Remove meshes for shapes that no longer exist
-                    state.mesh_cache.retain(|id, _| current_ids.contains(id));
-
-                    // Tessellate new or dirty shapes (at origin - transform applied in shader)
-                    for shape in &shapes {
-                        let needs_tessellation = shape.dirty || !state.mesh_cache.contains_key(&shape.id);
-                        if needs_tessellation {
-                            let mesh = state.tessellator.get_or_tessellate_shape(shape).clone();
-                            state.mesh_cache.insert(shape.id, mesh);
-                        }
+                    let frame = Frame {
+                        shapes: shapes.clone(),
+                        transform_overrides,
+                        view,
+                        selection_bbox,
+                        background_color,
+                        grid,
+                        pixel_snap,
+                        msaa,
+                    };
+
+                    // Only the edit that bumped the version animates; anything else jumps
+                    let animate = animate_transitions && transition_version != *seen_transition_version.borrow();
+                    *seen_transition_version.borrow_mut() = transition_version;
+                    let was_moving = transitions.borrow().is_active();
+                    transitions.borrow_mut().update(shapes, animate);
+                    if !animate_transitions {
+                        transitions.borrow_mut().clear();
+                    }
+                    let mut animated = frame.clone();
+                    transitions.borrow().apply(&mut animated.shapes);
+                    *transition_base.borrow_mut() = Some(frame);
+
+                    if render_loop {
+                        scheduler.borrow_mut().request(animated);
+                    } else {
+                        draw_and_report(state, &animated, &renderer_state, &gpu_unavailable, on_frame_stats.as_ref());
                     }
 
-                    state.known_shape_ids = current_ids;
-
-                    // Clone mesh cache to avoid borrow issues
-                    // (This is a shallow clone of the HashMap, meshes are cloned but it's still
-                    // much cheaper than re-tessellating everything on every frame)
-                    let mesh_cache_snapshot = state.mesh_cache.clone();
-
-                    // Render with per-shape transforms
-                    if let Err(e) = state.renderer.render_shapes_with_transforms(
-                        &mesh_cache_snapshot,
-                        &shapes,
-                        &transform_overrides,
-                        background_color,
-                    ) {
-                        web_sys::console::error_1(&format!("Render error: {}", e).into());
+                    // A running loop keeps going until everything settles
+                    if transitions.borrow().is_active() && !was_moving {
+                        let state = state.clone();
+                        transition_loop(transition_frame, None, Rc::new(move |dt| {
+                            transitions.borrow_mut().step(dt);
+                            if let Some(mut frame) = transition_base.borrow().clone() {
+                                transitions.borrow().apply(&mut frame.shapes);
+                                if render_loop {
+                                    scheduler.borrow_mut().request(frame);
+                                } else {
+                                    draw_and_report(&state, &frame, &renderer_state, &gpu_unavailable, on_frame_stats.as_ref());
+                                }
+                            }
+                            transitions.borrow().is_active()
+                        }));
                     }
                 }
                 || ()
@@ -209,6 +425,28 @@ pub fn gpu_canvas(props: &GpuCanvasProps) -> Html {
         );
     }
 
+    // Render loop: draws the latest requested frame at most once per animation frame
+    {
+        let renderer_state_clone = (*renderer_state).clone();
+        let renderer_state = renderer_state.clone();
+        let gpu_unavailable = gpu_unavailable.clone();
+        let scheduler = scheduler.clone();
+        use_effect_with((props.render_loop, renderer_state_clone.is_some()), move |(render_loop, _)| {
+            let handle: Rc<RefCell<Option<AnimationFrame>>> = Rc::default();
+            if let (true, Some(state)) = (*render_loop, renderer_state_clone) {
+                animation_loop(handle.clone(), Rc::new(move || {
+                    let frame = scheduler.borrow_mut().take();
+                    if let Some(frame) = frame {
+                        let on_frame_stats = on_frame_stats.borrow().clone();
+                        draw_and_report(&state, &frame, &renderer_state, &gpu_unavailable, on_frame_stats.as_ref());
+                    }
+                }));
+            }
+            // Dropping the pending frame cancels it and frees the loop
+            move || drop(handle.borrow_mut().take())
+        });
+    }
+
     // Mouse event handlers - direct pass-through
     let onmousedown = props.onmousedown.clone();
     let onmousemove = props.onmousemove.clone();
@@ -217,22 +455,88 @@ pub fn gpu_canvas(props: &GpuCanvasProps) -> Html {
     // Determine cursor based on hover state
     let canvas_cursor = if props.is_shape_hovered { "pointer" } else { "default" };
 
+    // SVG fallback rendering when the GPU is unavailable
+    let svg_fallback = if *gpu_unavailable {
+        let shape_elements: Html = props
+            .shapes
+            .iter()
+            .map(|shape| match props.transform_overrides.get(&shape.id) {
+                Some(m) => html! {
+                    <g
+                        key={shape.id.to_string()}
+                        transform={format!("matrix({} {} {} {} {} {})", m[0][0], m[0][1], m[1][0], m[1][1], m[3][0], m[3][1])}
+                    >
+                        {render_shape_svg(shape)}
+                    </g>
+                },
+                None => render_shape_svg(shape),
+            })
+            .collect();
+
+        html! {
+            <svg
+                data-testid="svg-fallback-canvas"
+                style="position: absolute; top: 0; left: 0; pointer-events: none;"
+                width={props.width.to_string()}
+                height={props.height.to_string()}
+            >
+                <g transform={props.view.to_svg_transform()}>
+                    {shape_elements}
+                </g>
+            </svg>
+        }
+    } else {
+        html! {}
+    };
+
+    let render_mode = match &*renderer_state {
+        Some(state) => Some(format!("GPU Mode ({})", state.borrow().renderer.backend_name())),
+        None if *gpu_unavailable => Some("SVG Mode".to_string()),
+        None => None,
+    };
+
+    // The GPU draws the grid once it's up; until then, and in SVG mode, the CSS dots stand in
+    let grid_class = match GridSource::pick(props.show_grid, renderer_state.is_some()) {
+        GridSource::Css => "canvas-dots",
+        GridSource::Gpu | GridSource::Hidden => "",
+    };
+
     html! {
         <div
-            class="canvas-dots"
+            class={grid_class}
             style={format!("position: relative; width: {}px; height: {}px; background-color: white; border: 1px solid #ccc;", props.width, props.height)}
         >
             // GPU canvas for shape rendering - transparent so container background shows through
+            // Backing store in device pixels, laid out at the CSS size
             <canvas
                 ref={canvas_ref}
-                width={props.width.to_string()}
-                height={props.height.to_string()}
-                style={format!("display: block; cursor: {};", canvas_cursor)}
+                width={backing_width.to_string()}
+                height={backing_height.to_string()}
+                // Hidden (but still taking the mouse) once the SVG fallback takes over, so no stale frame shows
+                style={format!(
+                    "display: block; width: {}px; height: {}px; cursor: {};{}",
+                    props.width,
+                    props.height,
+                    canvas_cursor,
+                    if *gpu_unavailable { " opacity: 0;" } else { "" },
+                )}
                 {onmousedown}
                 {onmousemove}
                 {onmouseup}
             />
 
+            {svg_fallback}
+
+            // Which renderer is drawing the shapes
+            if let Some(mode) = render_mode {
+                <div
+                    data-testid="render-mode"
+                    class="absolute top-2 right-2 px-2 py-0.5 rounded bg-white/90 border border-gray-200 text-xs text-gray-500 pointer-events-none"
+                >
+                    {mode}
+                </div>
+            }
+
             // SVG overlay for UI controls
             <CanvasOverlay
                 selection_bbox={props.selection_bbox.clone()}
@@ -240,17 +544,228 @@ pub fn gpu_canvas(props: &GpuCanvasProps) -> Html {
                 flip_x={props.flip_x}
                 flip_y={props.flip_y}
                 guidelines={props.guidelines.clone()}
+                spacing_indicators={props.spacing_indicators.clone()}
                 marquee_rect={props.marquee_rect.clone()}
                 preview_bbox={props.preview_bbox.clone()}
+                pen_preview={props.pen_preview.clone()}
+                vertex_handles={props.vertex_handles.clone()}
+                vertex_outline={props.vertex_outline}
+                selected_vertex={props.selected_vertex}
+                control_handles={props.control_handles.clone()}
+                grid_size={props.grid_size}
                 width={props.width as f64}
                 height={props.height as f64}
+                view={props.view}
+                gpu_selection={renderer_state.is_some()}
                 on_handle_mousedown={props.on_handle_mousedown.clone()}
                 on_bbox_mousedown={props.on_bbox_mousedown.clone()}
+                on_vertex_mousedown={props.on_vertex_mousedown.clone()}
+                on_control_mousedown={props.on_control_mousedown.clone()}
             />
         </div>
     }
 }
 
+/// Draw `frame`, report its timing, and switch to the SVG fallback if the GPU keeps failing
+fn draw_and_report(
+    state: &Rc<RefCell<RendererState>>,
+    frame: &Frame,
+    renderer_state: &UseStateHandle<Option<Rc<RefCell<RendererState>>>>,
+    gpu_unavailable: &UseStateHandle<bool>,
+    on_frame_stats: Option<&Callback<FrameStats>>,
+) {
+    let mut state = state.borrow_mut();
+    let (result, stats) = draw_frame(&mut state, frame);
+    if let Err(e) = &result {
+        web_sys::console::error_1(&format!("Render error: {}", e).into());
+    }
+    if state.failures.record(&result) {
+        web_sys::console::error_1(&"GPU rendering keeps failing; switching to SVG".into());
+        renderer_state.set(None);
+        gpu_unavailable.set(true);
+    }
+    if let (Ok(()), Some(callback)) = (&result, on_frame_stats) {
+        callback.emit(stats);
+    }
+}
+
+/// Bring the mesh cache up to date with the frame's shapes and render them
+fn draw_frame(state: &mut RendererState, frame: &Frame) -> (Result<(), RendererError>, FrameStats) {
+    let started = now_ms();
+
+    // Finer curves when zoomed in; a changed tolerance empties the tessellator's cache
+    state.tessellator.set_tolerance(Tessellator::tolerance_for_zoom(frame.view.zoom));
+    state.renderer.set_pixel_snap(frame.pixel_snap);
+
+    // Update mesh cache - only tessellate new or changed shapes
+    let current_ids: Vec<u64> = frame.shapes.iter().map(|s| s.id).collect();
+
+    // Remove meshes for shapes that no longer exist
+    state.mesh_cache.retain(|id, _| current_ids.contains(id));
+    let removed: Vec<u64> = state.known_shape_ids
+        .iter()
+        .copied()
+        .filter(|id| !current_ids.contains(id))
+        .collect();
+    for id in removed {
+        state.tessellator.invalidate_shape(id);
+    }
+
+    // Tessellate new or edited shapes (at origin - transform applied in shader)
+    // Every shape goes through the tessellator so it sees each shape's dirty flag
+    for shape in &frame.shapes {
+        let needs_tessellation = state.tessellator.needs_tessellation(shape)
+            || !state.mesh_cache.contains_key(&shape.id);
+        let mesh = state.tessellator.get_or_tessellate_shape(shape);
+        if needs_tessellation {
+            state.mesh_cache.insert(shape.id, mesh.clone());
+        }
+    }
+
+    state.known_shape_ids = current_ids;
+
+    // Clone mesh cache to avoid borrow issues
+    // (This is a shallow clone of the HashMap, meshes are cloned but it's still
+    // much cheaper than re-tessellating everything on every frame)
+    let mesh_cache_snapshot = state.mesh_cache.clone();
+
+    let selection_mesh = frame.selection_bbox
+        .map(|bbox| tessellate_selection(&mut state.tessellator, &bbox, &frame.view))
+        .unwrap_or_default();
+    let tessellated = now_ms();
+
+    state.renderer.set_grid(frame.grid);
+    state.renderer.set_msaa(frame.msaa);
+    state.renderer.retain_images(&image_sources(&frame.shapes));
+
+    // Plain shapes go in one pass, depth-sorted by layer, while text, images and the grid
+    // need their own pipelines and draw shape by shape; both split meshes too big for the buffers
+    let multipass = multipass_meshes(&mesh_cache_snapshot, frame).map(|meshes| {
+        let mut meshes: Vec<(&Mesh, f32)> = meshes.iter().map(|(mesh, depth)| (mesh, *depth)).collect();
+        // The selection UI sits in front of every layer
+        meshes.push((&selection_mesh, -1.0));
+        state.renderer.render_multipass(&meshes, frame.background_color)
+    });
+    let result = multipass.unwrap_or_else(|| {
+        state.renderer.render_shapes_with_transforms(
+            &mesh_cache_snapshot,
+            &frame.shapes,
+            &frame.transform_overrides,
+            &frame.view.to_matrix4(),
+            &selection_mesh,
+            frame.background_color,
+        )
+    });
+    let stats = FrameStats {
+        tessellate_ms: tessellated - started,
+        render_ms: now_ms() - tessellated,
+    };
+    (result, stats)
+}
+
+/// Each visible shape's cached mesh moved onto the surface, with its layer index from the top
+/// as depth; None when the frame needs the per-shape path for text, images or the grid
+fn multipass_meshes(mesh_cache: &HashMap<u64, Mesh>, frame: &Frame) -> Option<Vec<(Mesh, f32)>> {
+    let textured = frame
+        .shapes
+        .iter()
+        .filter(|shape| shape.visible)
+        .any(|shape| matches!(shape.geometry, ShapeGeometry::Text { .. } | ShapeGeometry::Image { .. }));
+    if textured || frame.grid.is_some() {
+        return None;
+    }
+
+    let view = frame.view.to_matrix4();
+    let top = frame.shapes.len();
+    Some(
+        frame
+            .shapes
+            .iter()
+            .enumerate()
+            .filter(|(_, shape)| shape.visible)
+            .filter_map(|(i, shape)| {
+                let mesh = mesh_cache.get(&shape.id)?;
+                let transform = shape_model_transform(shape, &frame.transform_overrides, &view);
+                Some((mesh.transformed(&transform), (top - 1 - i) as f32))
+            })
+            .collect(),
+    )
+}
+
+/// Distinct `src`s of the image shapes in `shapes`
+fn image_sources(shapes: &[Shape]) -> HashSet<&str> {
+    shapes
+        .iter()
+        .filter_map(|shape| match &shape.geometry {
+            ShapeGeometry::Image { src, .. } => Some(src.as_str()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Call `on_frame` on every animation frame until the handle's frame is dropped
+fn animation_loop(handle: Rc<RefCell<Option<AnimationFrame>>>, on_frame: Rc<dyn Fn()>) {
+    let next = handle.clone();
+    *handle.borrow_mut() = Some(request_animation_frame(move |_| {
+        on_frame();
+        animation_loop(next, on_frame);
+    }));
+}
+
+/// Call `on_step` with the seconds since the previous animation frame (0 on the first)
+/// on every animation frame, for as long as it returns true
+fn transition_loop(handle: Rc<RefCell<Option<AnimationFrame>>>, previous: Option<f64>, on_step: Rc<dyn Fn(f32) -> bool>) {
+    let next = handle.clone();
+    *handle.borrow_mut() = Some(request_animation_frame(move |timestamp| {
+        let dt = previous.map_or(0.0, |previous| ((timestamp - previous) / 1000.0) as f32);
+        if on_step(dt) {
+            transition_loop(next, Some(timestamp), on_step);
+        }
+    }));
+}
+
+/// Milliseconds on the page's high-resolution clock, 0 outside a browser
+fn now_ms() -> f64 {
+    web_sys::window()
+        .and_then(|window| window.performance())
+        .map(|performance| performance.now())
+        .unwrap_or(0.0)
+}
+
+/// Selection border and resize handles in screen pixels, matching the SVG overlay's look
+/// Built in screen space so the border and handles stay the same size at any zoom
+fn tessellate_selection(tessellator: &mut Tessellator, bbox: &BBox, view: &ViewTransform) -> Mesh {
+    let to_screen = |p: Vec2| {
+        let screen = view.world_to_screen(Point::new(p.x as f64, p.y as f64));
+        Vec2::new(screen.x as f32, screen.y as f32)
+    };
+    let screen_bbox = BBox::new(to_screen(bbox.min), to_screen(bbox.max));
+
+    let outline_color = Color::parse("#3b82f6").unwrap_or_default();
+    let handle_stroke = Color::parse("#0d99ff").unwrap_or_default();
+
+    let mut mesh = tessellator.tessellate_selection_outline(&screen_bbox, outline_color, 2.0);
+    for handle in HandleName::ALL {
+        let size = if handle.is_corner() { 8.0 } else { 6.0 };
+        let center = handle.calc_position_for_bbox(&screen_bbox);
+        mesh.extend(&tessellator.tessellate_handle(center, size, Color::white(), handle_stroke, 1.0));
+    }
+    mesh
+}
+
+/// The window's current device pixel ratio, 1.0 outside a browser
+fn device_pixel_ratio() -> f64 {
+    web_sys::window()
+        .map(|window| window.device_pixel_ratio())
+        .filter(|ratio| *ratio > 0.0)
+        .unwrap_or(1.0)
+}
+
+/// Backing store size in device pixels for a CSS size
+fn physical_size(css: u32, pixel_ratio: f64) -> u32 {
+    ((css as f64 * pixel_ratio).round() as u32).max(1)
+}
+
 /// Helper function to get mouse position relative to canvas
 pub fn get_canvas_mouse_position(event: &MouseEvent, canvas_ref: &NodeRef) -> Option<Vec2> {
     let canvas = canvas_ref.cast::<HtmlCanvasElement>()?;
@@ -1,5 +1,9 @@
 mod gpu_canvas;
 mod overlay;
+mod ruler;
+mod svg_shapes;
 
 pub use gpu_canvas::*;
 pub use overlay::*;
+pub use ruler::*;
+pub use svg_shapes::*;
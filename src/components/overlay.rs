@@ -1,5 +1,5 @@
 use crate::scene::{BBox, Vec2};
-use crate::types::{Guideline, GuidelineType, HandleName};
+use crate::types::{Guideline, GuidelineType, HandleName, Point, SpacingIndicator, ViewTransform};
 use yew::prelude::*;
 
 /// Props for the canvas overlay component
@@ -25,6 +25,10 @@ pub struct OverlayProps {
     #[prop_or_default]
     pub guidelines: Vec<Guideline>,
 
+    /// Equal-spacing markers to display
+    #[prop_or_default]
+    pub spacing_indicators: Vec<SpacingIndicator>,
+
     /// Marquee selection rectangle (during drag)
     #[prop_or_default]
     pub marquee_rect: Option<(Vec2, Vec2)>,
@@ -33,6 +37,31 @@ pub struct OverlayProps {
     #[prop_or_default]
     pub preview_bbox: Option<BBox>,
 
+    /// Pen tool draft: placed vertices followed by the cursor position
+    #[prop_or_default]
+    pub pen_preview: Vec<Vec2>,
+
+    /// Vertex-edit mode: the edited shape's vertices in world coordinates
+    #[prop_or_default]
+    pub vertex_handles: Vec<Vec2>,
+
+    /// Vertex-edit mode: whether the vertices are joined into a closed outline (polygons)
+    #[prop_or_default]
+    pub vertex_outline: bool,
+
+    /// Vertex-edit mode: the vertex Backspace removes, drawn filled
+    #[prop_or_default]
+    pub selected_vertex: Option<usize>,
+
+    /// Vertex-edit mode: path curve control points, each with the vertex its handle line
+    /// runs to, in world coordinates
+    #[prop_or_default]
+    pub control_handles: Vec<(Vec2, Vec2)>,
+
+    /// Snap grid spacing in world units; draws the grid when set
+    #[prop_or_default]
+    pub grid_size: Option<f64>,
+
     /// Canvas width
     #[prop_or(800.0)]
     pub width: f64,
@@ -41,6 +70,14 @@ pub struct OverlayProps {
     #[prop_or(600.0)]
     pub height: f64,
 
+    /// Canvas zoom and pan; everything here is drawn in world coordinates
+    #[prop_or_default]
+    pub view: ViewTransform,
+
+    /// Whether the selection box and handles are drawn on the GPU layer underneath
+    #[prop_or(false)]
+    pub gpu_selection: bool,
+
     /// Handle mouse down on resize handle
     #[prop_or_default]
     pub on_handle_mousedown: Callback<(HandleName, MouseEvent)>,
@@ -48,29 +85,34 @@ pub struct OverlayProps {
     /// Handle mouse down on bounding box (for moving selection)
     #[prop_or_default]
     pub on_bbox_mousedown: Callback<MouseEvent>,
+
+    /// Handle mouse down on a vertex handle (vertex index)
+    #[prop_or_default]
+    pub on_vertex_mousedown: Callback<(usize, MouseEvent)>,
+
+    /// Handle mouse down on a control point handle (control handle index)
+    #[prop_or_default]
+    pub on_control_mousedown: Callback<(usize, MouseEvent)>,
 }
 
 /// SVG overlay for UI controls (selection handles, guidelines, etc.)
 /// This component renders on top of the GPU canvas
 #[function_component(CanvasOverlay)]
 pub fn canvas_overlay(props: &OverlayProps) -> Html {
-    let handle_size = 8.0;
-    let edge_handle_size = 6.0;
+    // Handles are drawn in world units; divide by zoom to keep them a constant screen size
+    let handle_size = 8.0 / props.view.zoom as f32;
+    let edge_handle_size = 6.0 / props.view.zoom as f32;
 
     // Render selection box and handles
+    // When the GPU layer draws the selection, these elements only catch the mouse
+    let (handle_fill, handle_stroke, bbox_stroke) = if props.gpu_selection {
+        ("transparent", "transparent", "transparent")
+    } else {
+        ("white", "#0d99ff", "#3b82f6")
+    };
+
     let selection_elements = if let Some(bbox) = &props.selection_bbox {
-        let handles = [
-            HandleName::TopLeft,
-            HandleName::Top,
-            HandleName::TopRight,
-            HandleName::Right,
-            HandleName::BottomRight,
-            HandleName::Bottom,
-            HandleName::BottomLeft,
-            HandleName::Left,
-        ];
-
-        let handle_elements: Html = handles
+        let handle_elements: Html = HandleName::ALL
             .iter()
             .map(|handle| {
                 let pos = handle.calc_position_for_bbox(bbox);
@@ -97,8 +139,8 @@ pub fn canvas_overlay(props: &OverlayProps) -> Html {
                         y={format!("{}", pos.y - half)}
                         width={format!("{}", size)}
                         height={format!("{}", size)}
-                        fill="white"
-                        stroke="#0d99ff"
+                        fill={handle_fill}
+                        stroke={handle_stroke}
                         stroke-width="1"
                         style={format!("cursor: {}; pointer-events: all;", handle.cursor())}
                         onmousedown={onmousedown}
@@ -125,7 +167,7 @@ pub fn canvas_overlay(props: &OverlayProps) -> Html {
                     width={format!("{}", bbox.width())}
                     height={format!("{}", bbox.height())}
                     fill="transparent"
-                    stroke="#3b82f6"
+                    stroke={bbox_stroke}
                     stroke-width="2"
                     style="cursor: move; pointer-events: all;"
                     onmousedown={bbox_onmousedown}
@@ -171,6 +213,66 @@ pub fn canvas_overlay(props: &OverlayProps) -> Html {
         })
         .collect();
 
+    // Render equal-spacing markers: a line with end ticks and the gap in pixels
+    let spacing_elements: Html = props
+        .spacing_indicators
+        .iter()
+        .enumerate()
+        .map(|(i, indicator)| {
+            let start = indicator.position;
+            let tick = 4.0;
+            let (end_x, end_y, ticks, label_x, label_y) = match indicator.axis {
+                GuidelineType::Horizontal => (
+                    start.x + indicator.length,
+                    start.y,
+                    format!(
+                        "M{} {}V{}M{} {}V{}",
+                        start.x, start.y - tick, start.y + tick,
+                        start.x + indicator.length, start.y - tick, start.y + tick
+                    ),
+                    start.x + indicator.length / 2.0,
+                    start.y - 6.0,
+                ),
+                GuidelineType::Vertical => (
+                    start.x,
+                    start.y + indicator.length,
+                    format!(
+                        "M{} {}H{}M{} {}H{}",
+                        start.x - tick, start.y, start.x + tick,
+                        start.x - tick, start.y + indicator.length, start.x + tick
+                    ),
+                    start.x + 6.0,
+                    start.y + indicator.length / 2.0,
+                ),
+            };
+            let text_anchor = if indicator.axis == GuidelineType::Horizontal { "middle" } else { "start" };
+
+            html! {
+                <g key={format!("spacing-{}", i)} data-testid="spacing-indicator">
+                    <line
+                        x1={format!("{}", start.x)}
+                        y1={format!("{}", start.y)}
+                        x2={format!("{}", end_x)}
+                        y2={format!("{}", end_y)}
+                        stroke="#f24822"
+                        stroke-width="1"
+                    />
+                    <path d={ticks} stroke="#f24822" stroke-width="1"/>
+                    <text
+                        x={format!("{}", label_x)}
+                        y={format!("{}", label_y)}
+                        text-anchor={text_anchor}
+                        dominant-baseline="middle"
+                        font-size="10"
+                        fill="#f24822"
+                    >
+                        {format!("{}", indicator.length.round())}
+                    </text>
+                </g>
+            }
+        })
+        .collect();
+
     // Render marquee selection rectangle
     let marquee_element = if let Some((start, current)) = &props.marquee_rect {
         let x = start.x.min(current.x);
@@ -194,6 +296,122 @@ pub fn canvas_overlay(props: &OverlayProps) -> Html {
         html! {}
     };
 
+    // Render the pen tool's polygon-in-progress
+    let pen_element = if props.pen_preview.is_empty() {
+        html! {}
+    } else {
+        let points = props.pen_preview
+            .iter()
+            .map(|p| format!("{},{}", p.x, p.y))
+            .collect::<Vec<_>>()
+            .join(" ");
+        // The last point follows the cursor; only placed vertices get a dot
+        let vertices: Html = props.pen_preview[..props.pen_preview.len() - 1]
+            .iter()
+            .map(|p| html! {
+                <circle cx={format!("{}", p.x)} cy={format!("{}", p.y)} r="3" fill="white" stroke="#0d99ff" stroke-width="1" />
+            })
+            .collect();
+
+        html! {
+            <g data-testid="pen-preview">
+                <polyline points={points} fill="none" stroke="#0d99ff" stroke-width="1" />
+                {vertices}
+            </g>
+        }
+    };
+
+    // Render vertex-edit handles over the polygon's outline, and path control points
+    // on lines from the vertices they shape
+    let vertex_elements = if props.vertex_handles.is_empty() {
+        html! {}
+    } else {
+        let radius = 4.0 / props.view.zoom;
+        let outline = if props.vertex_outline {
+            let points = props.vertex_handles
+                .iter()
+                .map(|p| format!("{},{}", p.x, p.y))
+                .collect::<Vec<_>>()
+                .join(" ");
+            html! { <polygon points={points} fill="none" stroke="#0d99ff" stroke-width="1" /> }
+        } else {
+            html! {}
+        };
+        let controls: Html = props.control_handles
+            .iter()
+            .enumerate()
+            .map(|(i, (control, anchor))| {
+                let on_control_mousedown = props.on_control_mousedown.clone();
+                let onmousedown = Callback::from(move |e: MouseEvent| {
+                    e.prevent_default();
+                    e.stop_propagation();
+                    on_control_mousedown.emit((i, e));
+                });
+
+                html! {
+                    <g key={i}>
+                        <line
+                            x1={format!("{}", anchor.x)}
+                            y1={format!("{}", anchor.y)}
+                            x2={format!("{}", control.x)}
+                            y2={format!("{}", control.y)}
+                            stroke="#0d99ff"
+                            stroke-width="1"
+                        />
+                        <rect
+                            data-testid={format!("control-handle-{}", i)}
+                            x={format!("{}", control.x as f64 - radius * 0.75)}
+                            y={format!("{}", control.y as f64 - radius * 0.75)}
+                            width={format!("{}", radius * 1.5)}
+                            height={format!("{}", radius * 1.5)}
+                            fill="white"
+                            stroke="#0d99ff"
+                            stroke-width="1"
+                            style="cursor: crosshair; pointer-events: all;"
+                            {onmousedown}
+                        />
+                    </g>
+                }
+            })
+            .collect();
+        let handles: Html = props.vertex_handles
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let on_vertex_mousedown = props.on_vertex_mousedown.clone();
+                let onmousedown = Callback::from(move |e: MouseEvent| {
+                    e.prevent_default();
+                    e.stop_propagation();
+                    on_vertex_mousedown.emit((i, e));
+                });
+                let fill = if props.selected_vertex == Some(i) { "#0d99ff" } else { "white" };
+
+                html! {
+                    <circle
+                        key={i}
+                        data-testid={format!("vertex-handle-{}", i)}
+                        cx={format!("{}", p.x)}
+                        cy={format!("{}", p.y)}
+                        r={format!("{}", radius)}
+                        {fill}
+                        stroke="#0d99ff"
+                        stroke-width="1"
+                        style="cursor: crosshair; pointer-events: all;"
+                        {onmousedown}
+                    />
+                }
+            })
+            .collect();
+
+        html! {
+            <g data-testid="vertex-edit">
+                {outline}
+                {controls}
+                {handles}
+            </g>
+        }
+    };
+
     // Render preview bounding box (shapes that would be selected)
     let preview_element = if let Some(bbox) = &props.preview_bbox {
         html! {
@@ -213,6 +431,42 @@ pub fn canvas_overlay(props: &OverlayProps) -> Html {
         html! {}
     };
 
+    // Snap grid: one pattern tile per cell, filling the visible world area
+    let grid_element = if let Some(size) = props.grid_size.filter(|size| *size > 0.0) {
+        let min = props.view.screen_to_world(Point::zero());
+        let max = props.view.screen_to_world(Point::new(props.width, props.height));
+        html! {
+            <>
+                <defs>
+                    <pattern
+                        id="snap-grid-pattern"
+                        width={format!("{}", size)}
+                        height={format!("{}", size)}
+                        patternUnits="userSpaceOnUse"
+                    >
+                        <path
+                            d={format!("M {size} 0 L 0 0 0 {size}")}
+                            fill="none"
+                            stroke="#d1d5db"
+                            stroke-width="0.5"
+                        />
+                    </pattern>
+                </defs>
+                <rect
+                    data-testid="snap-grid"
+                    data-grid-size={format!("{}", size)}
+                    x={format!("{}", min.x)}
+                    y={format!("{}", min.y)}
+                    width={format!("{}", max.x - min.x)}
+                    height={format!("{}", max.y - min.y)}
+                    fill="url(#snap-grid-pattern)"
+                />
+            </>
+        }
+    } else {
+        html! {}
+    };
+
     // Format selected IDs as comma-separated string for test data attribute
     let selection_ids_str = props.selected_ids
         .iter()
@@ -231,10 +485,16 @@ pub fn canvas_overlay(props: &OverlayProps) -> Html {
             height={format!("{}", props.height)}
             viewBox={format!("0 0 {} {}", props.width, props.height)}
         >
-            {selection_elements}
-            {guideline_elements}
-            {marquee_element}
-            {preview_element}
+            <g transform={props.view.to_svg_transform()}>
+                {grid_element}
+                {selection_elements}
+                {guideline_elements}
+                {spacing_elements}
+                {marquee_element}
+                {preview_element}
+                {pen_element}
+                {vertex_elements}
+            </g>
         </svg>
     }
 }
@@ -0,0 +1,181 @@
+use crate::types::Point;
+use yew::prelude::*;
+
+/// Thickness of a ruler in screen pixels
+pub const RULER_SIZE: f64 = 20.0;
+
+/// Intervals ticks and labels can be spaced at, in world units; each divides every
+/// later one, so labels always fall on ticks
+const RULER_STEPS: [i64; 12] = [1, 5, 10, 50, 100, 500, 1_000, 5_000, 10_000, 50_000, 100_000, 500_000];
+/// Closest two ticks may be on screen, in pixels
+const MIN_TICK_SPACING: f64 = 8.0;
+/// Closest two labels may be on screen, in pixels
+const MIN_LABEL_SPACING: f64 = 40.0;
+
+const RULER_COLOR: &str = "#9ca3af";
+const LABEL_COLOR: &str = "#6b7280";
+const CURSOR_COLOR: &str = "#0d99ff";
+
+/// Which canvas edge a ruler runs along
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RulerAxis {
+    /// Along the top edge, measuring x
+    Horizontal,
+    /// Along the left edge, measuring y
+    Vertical,
+}
+
+/// World-unit spacing of ticks and of labels at zoom `scale`: the smallest steps that
+/// stay readable, e.g. ticks every 10 and labels every 50 at 100%
+pub fn ruler_steps(scale: f64) -> (i64, i64) {
+    let step = |min_spacing: f64| {
+        RULER_STEPS
+            .into_iter()
+            .find(|&step| step as f64 * scale >= min_spacing)
+            .unwrap_or(RULER_STEPS[RULER_STEPS.len() - 1])
+    };
+    (step(MIN_TICK_SPACING), step(MIN_LABEL_SPACING))
+}
+
+/// Ticks on a ruler `length` pixels long with world coordinate 0 at pixel `origin`,
+/// as (pixel offset, label); labeled ticks carry their world coordinate
+pub fn ruler_ticks(origin: f64, scale: f64, length: f64) -> Vec<(f64, Option<i64>)> {
+    let (tick, label) = ruler_steps(scale);
+    let spacing = tick as f64 * scale;
+    let first = (-origin / spacing).ceil() as i64;
+    let last = ((length - origin) / spacing).floor() as i64;
+    (first..=last)
+        .map(|i| {
+            let world = i * tick;
+            (origin + world as f64 * scale, (world % label == 0).then_some(world))
+        })
+        .collect()
+}
+
+/// Props for a canvas ruler
+#[derive(Properties, Clone, PartialEq)]
+pub struct RulerProps {
+    pub axis: RulerAxis,
+    /// Screen position of world (0, 0), i.e. the view's pan
+    pub origin: Point,
+    /// Screen pixels per world unit, i.e. the view's zoom
+    pub scale: f64,
+    /// Length of the ruler in screen pixels
+    pub length: f64,
+    /// Cursor in world coordinates, marked with a hairline
+    #[prop_or_default]
+    pub cursor: Option<Point>,
+}
+
+/// A ruler along the top or left edge of the canvas, with ticks and labels in world
+/// coordinates that follow the view's pan and zoom
+#[function_component(Ruler)]
+pub fn ruler(props: &RulerProps) -> Html {
+    let horizontal = props.axis == RulerAxis::Horizontal;
+    let (origin, cursor) = match props.axis {
+        RulerAxis::Horizontal => (props.origin.x, props.cursor.map(|c| c.x)),
+        RulerAxis::Vertical => (props.origin.y, props.cursor.map(|c| c.y)),
+    };
+    let (width, height) = if horizontal { (props.length, RULER_SIZE) } else { (RULER_SIZE, props.length) };
+    // A line across the ruler at `offset`, `depth` pixels in from the canvas side
+    let across = |offset: f64, depth: f64| {
+        if horizontal {
+            (offset, RULER_SIZE - depth, offset, RULER_SIZE)
+        } else {
+            (RULER_SIZE - depth, offset, RULER_SIZE, offset)
+        }
+    };
+
+    let ticks = ruler_ticks(origin, props.scale, props.length).into_iter().map(|(offset, label)| {
+        // Half-pixel offset keeps 1px ticks crisp
+        let offset = offset.round() + 0.5;
+        let depth = if label.is_some() { RULER_SIZE / 2.0 } else { RULER_SIZE / 4.0 };
+        let (x1, y1, x2, y2) = across(offset, depth);
+        html! {
+            <>
+                <line x1={x1.to_string()} y1={y1.to_string()} x2={x2.to_string()} y2={y2.to_string()} stroke={RULER_COLOR} stroke-width="1" />
+                if let Some(label) = label {
+                    if horizontal {
+                        <text x={(offset + 2.0).to_string()} y="9" font-size="9" fill={LABEL_COLOR}>{label}</text>
+                    } else {
+                        // Reads bottom to top, just below its tick
+                        <text
+                            x="9"
+                            y={(offset + 2.0).to_string()}
+                            font-size="9"
+                            fill={LABEL_COLOR}
+                            text-anchor="end"
+                            transform={format!("rotate(-90 9 {})", offset + 2.0)}
+                        >
+                            {label}
+                        </text>
+                    }
+                }
+            </>
+        }
+    });
+
+    let hairline = cursor.map(|world| {
+        let (x1, y1, x2, y2) = across(origin + world * props.scale, RULER_SIZE);
+        html! {
+            <line
+                data-testid="ruler-cursor"
+                x1={x1.to_string()} y1={y1.to_string()} x2={x2.to_string()} y2={y2.to_string()}
+                stroke={CURSOR_COLOR} stroke-width="1"
+            />
+        }
+    });
+    let (edge_x1, edge_y1, edge_x2, edge_y2) = if horizontal {
+        (0.0, RULER_SIZE - 0.5, props.length, RULER_SIZE - 0.5)
+    } else {
+        (RULER_SIZE - 0.5, 0.0, RULER_SIZE - 0.5, props.length)
+    };
+
+    html! {
+        <svg
+            data-testid={if horizontal { "ruler-horizontal" } else { "ruler-vertical" }}
+            width={width.to_string()}
+            height={height.to_string()}
+            style="position: absolute; top: 0; left: 0; pointer-events: none; user-select: none;"
+        >
+            <rect width={width.to_string()} height={height.to_string()} fill="white" fill-opacity="0.95" />
+            <line x1={edge_x1.to_string()} y1={edge_y1.to_string()} x2={edge_x2.to_string()} y2={edge_y2.to_string()} stroke={RULER_COLOR} stroke-width="1" />
+            {for ticks}
+            {for hairline}
+        </svg>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_steps_stay_readable_as_zoom_changes() {
+        assert_eq!(ruler_steps(1.0), (10, 50));
+        assert_eq!(ruler_steps(0.5), (50, 100));
+        assert_eq!(ruler_steps(0.1), (100, 500));
+        assert_eq!(ruler_steps(2.0), (5, 50));
+        assert_eq!(ruler_steps(8.0), (1, 5));
+        // Labels always land on ticks
+        for scale in [0.01, 0.1, 0.25, 0.5, 1.0, 3.0, 8.0] {
+            let (tick, label) = ruler_steps(scale);
+            assert_eq!(label % tick, 0, "scale {scale}");
+        }
+    }
+
+    #[test]
+    fn test_ticks_follow_pan_and_zoom() {
+        // World 0 at pixel 25: ticks every 10 world units (10px), labels every 50
+        let ticks = ruler_ticks(25.0, 1.0, 100.0);
+        let offsets: Vec<f64> = ticks.iter().map(|(offset, _)| *offset).collect();
+        assert_eq!(offsets, vec![5.0, 15.0, 25.0, 35.0, 45.0, 55.0, 65.0, 75.0, 85.0, 95.0]);
+        let labels: Vec<(f64, i64)> = ticks.iter().filter_map(|(offset, label)| Some((*offset, (*label)?))).collect();
+        assert_eq!(labels, vec![(25.0, 0), (75.0, 50)]);
+        assert_eq!(ticks[0].1, None);
+
+        // At 50% the same pixels span twice the world, so ticks are every 50 units
+        let ticks = ruler_ticks(0.0, 0.5, 100.0);
+        assert_eq!(ticks, vec![(0.0, Some(0)), (25.0, None), (50.0, Some(100)), (75.0, None), (100.0, Some(200))]);
+    }
+}
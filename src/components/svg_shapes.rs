@@ -0,0 +1,333 @@
+use crate::scene::{stringify_points, Fill, PathCommand, Shape, ShapeGeometry, Transform2D, Vec2};
+use yew::prelude::*;
+
+/// Convert path commands to an SVG path `d` attribute string
+pub fn path_to_svg_d(commands: &[PathCommand]) -> String {
+    commands
+        .iter()
+        .map(|cmd| match cmd {
+            PathCommand::MoveTo(p) => format!("M{} {}", p.x, p.y),
+            PathCommand::LineTo(p) => format!("L{} {}", p.x, p.y),
+            PathCommand::QuadraticTo { control, to } => {
+                format!("Q{} {} {} {}", control.x, control.y, to.x, to.y)
+            }
+            PathCommand::CubicTo { ctrl1, ctrl2, to } => format!(
+                "C{} {} {} {} {} {}",
+                ctrl1.x, ctrl1.y, ctrl2.x, ctrl2.y, to.x, to.y
+            ),
+            PathCommand::ArcTo { rx, ry, x_rotation, large_arc, sweep, to } => format!(
+                "A{} {} {} {} {} {} {}",
+                rx, ry, x_rotation, *large_arc as u8, *sweep as u8, to.x, to.y
+            ),
+            PathCommand::Close => "Z".to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Convert local points to an SVG `points` attribute string, e.g. `0,0 10,0 5,10`
+pub fn points_to_svg(points: &[Vec2]) -> String {
+    points
+        .iter()
+        .map(|p| format!("{},{}", p.x, p.y))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Convert a Transform2D to an SVG `transform` attribute (same order as `Transform2D::to_matrix`)
+pub fn transform_to_svg(transform: &Transform2D) -> String {
+    format!(
+        "translate({} {}) rotate({}) scale({} {}) translate({} {})",
+        transform.position.x + transform.anchor.x,
+        transform.position.y + transform.anchor.y,
+        transform.rotation.to_degrees(),
+        transform.scale.x,
+        transform.scale.y,
+        -transform.anchor.x,
+        -transform.anchor.y,
+    )
+}
+
+/// Render the `<linearGradient>`/`<radialGradient>` definition for a gradient fill
+/// Gradient coordinates are in the shape's local space; `gradient_transform` maps them
+/// for elements whose coordinates aren't (polygons have the transform baked into their points)
+fn render_gradient_def(id: &str, fill: &Fill, gradient_transform: Option<String>) -> Html {
+    let stops: Html = fill
+        .stops()
+        .iter()
+        .map(|stop| html! {
+            <stop
+                offset={stop.offset.to_string()}
+                stop-color={stop.color.to_hex()}
+                stop-opacity={stop.color.a.to_string()}
+            />
+        })
+        .collect();
+
+    match fill {
+        Fill::Solid(_) => html! {},
+        Fill::LinearGradient { start, end, .. } => html! {
+            <linearGradient
+                id={id.to_string()}
+                gradientUnits="userSpaceOnUse"
+                gradientTransform={gradient_transform}
+                x1={start.x.to_string()}
+                y1={start.y.to_string()}
+                x2={end.x.to_string()}
+                y2={end.y.to_string()}
+            >
+                {stops}
+            </linearGradient>
+        },
+        Fill::RadialGradient { center, radius, .. } => html! {
+            <radialGradient
+                id={id.to_string()}
+                gradientUnits="userSpaceOnUse"
+                gradientTransform={gradient_transform}
+                cx={center.x.to_string()}
+                cy={center.y.to_string()}
+                r={radius.to_string()}
+            >
+                {stops}
+            </radialGradient>
+        },
+    }
+}
+
+/// Render a shape as SVG markup (nothing for hidden shapes)
+/// Polygons are emitted as `<polygon>` with the transform baked into the points,
+/// matching the markup of the original SVG renderer
+pub fn render_shape_svg(shape: &Shape) -> Html {
+    if !shape.visible {
+        return html! {};
+    }
+
+    // Shape opacity is multiplied into each paint's alpha, as the GPU path does per vertex
+    let opacity = shape.style.opacity;
+    // Gradient fills reference a definition emitted alongside the shape
+    let gradient_id = format!("fill-gradient-{}", shape.id);
+    let (fill, fill_opacity) = match &shape.style.fill {
+        Some(Fill::Solid(color)) => (color.to_hex(), (color.a * opacity).to_string()),
+        Some(_) => (format!("url(#{})", gradient_id), opacity.to_string()),
+        None => ("none".to_string(), "1".to_string()),
+    };
+    let stroke = shape
+        .style
+        .stroke
+        .as_ref()
+        .map(|s| s.color.to_hex())
+        .unwrap_or_else(|| "none".to_string());
+    let stroke_width = shape.style.stroke.as_ref().map(|s| s.width).unwrap_or(0.0).to_string();
+    let stroke_dasharray = shape.style.stroke.as_ref().map(|s| s.dasharray()).unwrap_or_else(|| "none".to_string());
+    let stroke_dashoffset = shape.style.stroke.as_ref().map_or(0.0, |s| s.dash_offset).to_string();
+    let stroke_linecap = shape.style.stroke.as_ref().map(|s| s.line_cap).unwrap_or_default().to_svg();
+    let stroke_linejoin = shape.style.stroke.as_ref().map(|s| s.line_join).unwrap_or_default().to_svg();
+    let fill_rule = shape.style.fill_rule.to_svg();
+    let stroke_opacity = (shape.style.stroke.as_ref().map_or(1.0, |s| s.color.a) * opacity).to_string();
+    let transform = transform_to_svg(&shape.transform);
+    let key = shape.id.to_string();
+    let gradient_def = shape.style.fill.as_ref().filter(|fill| fill.is_gradient()).map(|fill| {
+        let gradient_transform = matches!(shape.geometry, ShapeGeometry::Polygon { .. })
+            .then(|| transform_to_svg(&shape.transform));
+        render_gradient_def(&gradient_id, fill, gradient_transform)
+    });
+
+    let element = match &shape.geometry {
+        ShapeGeometry::Polygon { points } => html! {
+            <polygon
+                {key}
+                points={stringify_points(points, &shape.transform)}
+                {fill}
+                fill-opacity={fill_opacity}
+                fill-rule={fill_rule}
+                {stroke}
+                stroke-width={stroke_width}
+                stroke-dasharray={stroke_dasharray}
+                stroke-dashoffset={stroke_dashoffset}
+                stroke-linecap={stroke_linecap}
+                stroke-linejoin={stroke_linejoin}
+                stroke-opacity={stroke_opacity}
+            />
+        },
+        ShapeGeometry::Star { .. } | ShapeGeometry::RegularPolygon { .. } => html! {
+            <polygon
+                {key}
+                points={points_to_svg(&shape.geometry.ring_points().unwrap_or_default())}
+                {transform}
+                {fill}
+                fill-opacity={fill_opacity}
+                fill-rule={fill_rule}
+                {stroke}
+                stroke-width={stroke_width}
+                stroke-dasharray={stroke_dasharray}
+                stroke-dashoffset={stroke_dashoffset}
+                stroke-linecap={stroke_linecap}
+                stroke-linejoin={stroke_linejoin}
+                stroke-opacity={stroke_opacity}
+            />
+        },
+        ShapeGeometry::Rectangle { width, height, corner_radii } if corner_radii.as_uniform().is_some() => html! {
+            <rect
+                {key}
+                width={width.to_string()}
+                height={height.to_string()}
+                rx={corner_radii.clamped(*width, *height).tl.to_string()}
+                {transform}
+                {fill}
+                fill-opacity={fill_opacity}
+                {stroke}
+                stroke-width={stroke_width}
+                stroke-dasharray={stroke_dasharray}
+                stroke-dashoffset={stroke_dashoffset}
+                stroke-linecap={stroke_linecap}
+                stroke-linejoin={stroke_linejoin}
+                stroke-opacity={stroke_opacity}
+            />
+        },
+        // <rect> has a single rx, so differing corners are drawn as a path
+        ShapeGeometry::Rectangle { width, height, corner_radii } => html! {
+            <path
+                {key}
+                d={path_to_svg_d(&corner_radii.outline(*width, *height))}
+                {transform}
+                {fill}
+                fill-opacity={fill_opacity}
+                {stroke}
+                stroke-width={stroke_width}
+                stroke-dasharray={stroke_dasharray}
+                stroke-dashoffset={stroke_dashoffset}
+                stroke-linecap={stroke_linecap}
+                stroke-linejoin={stroke_linejoin}
+                stroke-opacity={stroke_opacity}
+            />
+        },
+        ShapeGeometry::Ellipse { rx, ry } => html! {
+            <ellipse
+                {key}
+                rx={rx.to_string()}
+                ry={ry.to_string()}
+                {transform}
+                {fill}
+                fill-opacity={fill_opacity}
+                {stroke}
+                stroke-width={stroke_width}
+                stroke-dasharray={stroke_dasharray}
+                stroke-dashoffset={stroke_dashoffset}
+                stroke-linecap={stroke_linecap}
+                stroke-linejoin={stroke_linejoin}
+                stroke-opacity={stroke_opacity}
+            />
+        },
+        ShapeGeometry::Line { start, end } => html! {
+            <line
+                {key}
+                x1={start.x.to_string()}
+                y1={start.y.to_string()}
+                x2={end.x.to_string()}
+                y2={end.y.to_string()}
+                {transform}
+                {stroke}
+                stroke-width={stroke_width}
+                stroke-dasharray={stroke_dasharray}
+                stroke-dashoffset={stroke_dashoffset}
+                stroke-linecap={stroke_linecap}
+                stroke-linejoin={stroke_linejoin}
+                stroke-opacity={stroke_opacity}
+            />
+        },
+        ShapeGeometry::Path { commands } => html! {
+            <path
+                {key}
+                d={path_to_svg_d(commands)}
+                {transform}
+                {fill}
+                fill-opacity={fill_opacity}
+                fill-rule={fill_rule}
+                {stroke}
+                stroke-width={stroke_width}
+                stroke-dasharray={stroke_dasharray}
+                stroke-dashoffset={stroke_dashoffset}
+                stroke-linecap={stroke_linecap}
+                stroke-linejoin={stroke_linejoin}
+                stroke-opacity={stroke_opacity}
+            />
+        },
+        ShapeGeometry::Text { content, font_size, font_family, align } => html! {
+            <text
+                {key}
+                x="0"
+                y="0"
+                font-size={font_size.to_string()}
+                font-family={font_family.clone()}
+                text-anchor={align.to_svg()}
+                {transform}
+                {fill}
+                fill-opacity={fill_opacity}
+                style="white-space: pre;"
+            >
+                {content.clone()}
+            </text>
+        },
+        ShapeGeometry::Image { width, height, src } => html! {
+            <image
+                {key}
+                width={width.to_string()}
+                height={height.to_string()}
+                href={src.clone()}
+                preserveAspectRatio="none"
+                {transform}
+                opacity={opacity.to_string()}
+            />
+        },
+    };
+
+    let element = match gradient_def {
+        Some(def) => html! {
+            <g key={shape.id.to_string()}>
+                <defs>{def}</defs>
+                {element}
+            </g>
+        },
+        None => element,
+    };
+
+    // The shadow filter goes on an untransformed group so its offset and blur are in world units
+    match &shape.style.drop_shadow {
+        Some(shadow) => html! {
+            <g key={shape.id.to_string()} style={format!("filter: {};", shadow.to_css(opacity))}>
+                {element}
+            </g>
+        },
+        None => element,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::parse_svg_path;
+
+    #[test]
+    fn test_path_to_svg_d_roundtrip() {
+        let commands = parse_svg_path("M10 20 L30 40 Q50 60 70 80 Z");
+        let d = path_to_svg_d(&commands);
+        assert_eq!(d, "M10 20 L30 40 Q50 60 70 80 Z");
+        assert_eq!(parse_svg_path(&d), commands);
+    }
+
+    #[test]
+    fn test_transform_to_svg_translation() {
+        let transform = Transform2D::from_position(Vec2::new(5.0, 10.0));
+        assert_eq!(
+            transform_to_svg(&transform),
+            "translate(5 10) rotate(0) scale(1 1) translate(-0 -0)"
+        );
+    }
+
+    #[test]
+    fn test_points_to_svg() {
+        assert_eq!(points_to_svg(&[]), "");
+        let triangle = [Vec2::new(0.0, -10.0), Vec2::new(8.5, 5.0), Vec2::new(-8.5, 5.0)];
+        assert_eq!(points_to_svg(&triangle), "0,-10 8.5,5 -8.5,5");
+    }
+}
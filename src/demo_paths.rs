@@ -150,31 +150,10 @@ pub fn create_heart_shape(x: f32, y: f32, size: f32, color: Color) -> Shape {
         .with_scale(Vec2::new(scale, scale)))
 }
 
-/// Create a star shape using line commands
+/// Create a star shape, keeping its point count and radii editable
 pub fn create_star_shape(x: f32, y: f32, outer_radius: f32, inner_radius: f32, points: u32, color: Color) -> Shape {
-    use std::f32::consts::PI;
-
-    let mut path = String::new();
-    let angle_step = PI / points as f32;
-
-    for i in 0..(points * 2) {
-        let radius = if i % 2 == 0 { outer_radius } else { inner_radius };
-        let angle = (i as f32) * angle_step - PI / 2.0;
-        let px = radius * angle.cos();
-        let py = radius * angle.sin();
-
-        if i == 0 {
-            path.push_str(&format!("M {} {} ", px, py));
-        } else {
-            path.push_str(&format!("L {} {} ", px, py));
-        }
-    }
-    path.push('Z');
-
-    let cmds = parse_svg_path(&path);
-
     Shape::new(
-        ShapeGeometry::Path { commands: cmds },
+        ShapeGeometry::star(points, outer_radius, inner_radius),
         ShapeStyle::fill_and_stroke(
             color,
             StrokeStyle::new(Color::rgb(0.6, 0.4, 0.0), 2.0),
@@ -310,12 +289,40 @@ mod tests {
     #[test]
     fn test_create_star() {
         let star = create_star_shape(0.0, 0.0, 50.0, 20.0, 5, Color::rgb(1.0, 1.0, 0.0));
-        match star.geometry {
-            ShapeGeometry::Path { ref commands } => {
-                // 5-pointed star has 10 line segments + close
-                assert!(commands.len() >= 10);
-            }
-            _ => panic!("Expected Path geometry"),
+        assert_eq!(star.geometry, ShapeGeometry::star(5, 50.0, 20.0));
+        // 5 tips and the 5 notches between them
+        assert_eq!(star.geometry.vertices().len(), 10);
+    }
+
+    #[test]
+    fn test_star_vertices_match_the_old_star_path() {
+        use crate::scene::{star_vertices, PathCommand};
+        use std::f32::consts::PI;
+
+        // The path create_star_shape used to build by formatting SVG commands
+        let (points, outer_radius, inner_radius) = (5, 50.0f32, 20.0f32);
+        let mut path = String::new();
+        let angle_step = PI / points as f32;
+        for i in 0..(points * 2) {
+            let radius = if i % 2 == 0 { outer_radius } else { inner_radius };
+            let angle = (i as f32) * angle_step - PI / 2.0;
+            let command = if i == 0 { "M" } else { "L" };
+            path.push_str(&format!("{} {} {} ", command, radius * angle.cos(), radius * angle.sin()));
+        }
+        path.push('Z');
+        let old: Vec<Vec2> = parse_svg_path(&path)
+            .iter()
+            .filter_map(|command| match command {
+                PathCommand::MoveTo(p) | PathCommand::LineTo(p) => Some(*p),
+                _ => None,
+            })
+            .collect();
+
+        let vertices = star_vertices(points, outer_radius, inner_radius);
+        assert_eq!(vertices.len(), old.len());
+        for (vertex, old) in vertices.iter().zip(&old) {
+            assert!(vertex.distance(*old) < 1e-4, "{vertex} != {old}");
         }
+        assert!(vertices[0].distance(Vec2::new(0.0, -50.0)) < 1e-4);
     }
 }
@@ -0,0 +1,257 @@
+//! Spring transitions for shapes moved or resized from the properties panel
+//!
+//! Instead of jumping, an edited shape's position and scale follow a critically damped
+//! spring, F = -k·dx - c·v, from where it was drawn to its new transform. The spring
+//! settles without overshooting, and a transition snaps to its target once it has run
+//! for `TRANSITION_DURATION`.
+
+use crate::scene::{Shape, Vec2};
+use glam::Vec4;
+use std::collections::HashMap;
+
+/// Spring constant k; with critical damping the remaining distance is ~0.1% at 300ms
+pub const SPRING_STIFFNESS: f32 = 900.0;
+/// Longest a transition runs, in seconds, before it snaps to its target
+pub const TRANSITION_DURATION: f32 = 0.3;
+/// Integration step, in seconds; a frame's time is split into steps this long or shorter
+const MAX_STEP: f32 = 0.004;
+/// Longest frame the spring advances by, so a stalled tab resumes mid-transition
+const MAX_FRAME: f32 = 0.1;
+/// Distance and speed under which a transition counts as arrived
+const SETTLE_EPSILON: f32 = 1e-3;
+
+/// A value moving toward `target` on a spring
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AnimationState {
+    pub current: Vec4,
+    pub target: Vec4,
+    pub velocity: Vec4,
+    /// Spring constant k
+    pub stiffness: f32,
+    /// Damping c; 2·√k is critical damping, the fastest settle without overshoot
+    pub damping: f32,
+    /// Seconds since the target was set
+    pub elapsed: f32,
+}
+
+impl AnimationState {
+    /// A critically damped transition from `from` to `to`, starting at rest
+    pub fn new(from: Vec4, to: Vec4) -> Self {
+        Self {
+            current: from,
+            target: to,
+            velocity: Vec4::ZERO,
+            stiffness: SPRING_STIFFNESS,
+            damping: 2.0 * SPRING_STIFFNESS.sqrt(),
+            elapsed: 0.0,
+        }
+    }
+
+    /// Head for `target` from wherever the value is now, keeping its speed
+    pub fn retarget(&mut self, target: Vec4) {
+        self.target = target;
+        self.elapsed = 0.0;
+    }
+
+    /// Advance by `dt` seconds
+    pub fn step(&mut self, dt: f32) {
+        let dt = dt.clamp(0.0, MAX_FRAME);
+        self.elapsed += dt;
+        if self.elapsed >= TRANSITION_DURATION {
+            self.current = self.target;
+            self.velocity = Vec4::ZERO;
+            return;
+        }
+
+        let steps = (dt / MAX_STEP).ceil().max(1.0);
+        let h = dt / steps;
+        for _ in 0..steps as u32 {
+            // Semi-implicit Euler: new velocity first, then move with it
+            let force = -self.stiffness * (self.current - self.target) - self.damping * self.velocity;
+            self.velocity += force * h;
+            self.current += self.velocity * h;
+        }
+    }
+
+    /// Whether the value has arrived and stopped
+    pub fn is_settled(&self) -> bool {
+        self.current.distance(self.target) <= SETTLE_EPSILON && self.velocity.length() <= SETTLE_EPSILON
+    }
+}
+
+/// A shape's animated transform values: position x, y and scale x, y
+fn transform_values(shape: &Shape) -> Vec4 {
+    let transform = &shape.transform;
+    Vec4::new(transform.position.x, transform.position.y, transform.scale.x, transform.scale.y)
+}
+
+/// Running transitions by shape ID, and the transform each shape last had
+#[derive(Debug, Default)]
+pub struct ShapeTransitions {
+    animations: HashMap<u64, AnimationState>,
+    last: HashMap<u64, Vec4>,
+}
+
+impl ShapeTransitions {
+    /// Take in the latest shapes; with `animate`, a shape whose position or scale changed
+    /// glides there from where it's drawn now, otherwise it jumps
+    pub fn update(&mut self, shapes: &[Shape], animate: bool) {
+        let mut last = HashMap::with_capacity(shapes.len());
+        for shape in shapes {
+            let values = transform_values(shape);
+            if let Some(&previous) = self.last.get(&shape.id).filter(|&&previous| previous != values) {
+                if animate {
+                    self.animations
+                        .entry(shape.id)
+                        .and_modify(|animation| animation.retarget(values))
+                        .or_insert_with(|| AnimationState::new(previous, values));
+                } else {
+                    self.animations.remove(&shape.id);
+                }
+            }
+            last.insert(shape.id, values);
+        }
+        self.animations.retain(|id, _| last.contains_key(id));
+        self.last = last;
+    }
+
+    /// Advance every transition by `dt` seconds, dropping the ones that arrived
+    pub fn step(&mut self, dt: f32) {
+        for animation in self.animations.values_mut() {
+            animation.step(dt);
+        }
+        self.animations.retain(|_, animation| !animation.is_settled());
+    }
+
+    /// Whether any shape is still moving
+    pub fn is_active(&self) -> bool {
+        !self.animations.is_empty()
+    }
+
+    /// Stop every transition where it is; shapes are drawn at their real transforms
+    pub fn clear(&mut self) {
+        self.animations.clear();
+    }
+
+    /// Move each animating shape in `shapes` to its current point in the transition
+    pub fn apply(&self, shapes: &mut [Shape]) {
+        for shape in shapes {
+            if let Some(animation) = self.animations.get(&shape.id) {
+                let values = animation.current;
+                shape.transform.position = Vec2::new(values.x, values.y);
+                shape.transform.scale = Vec2::new(values.z, values.w);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::{ShapeGeometry, ShapeStyle, Transform2D};
+
+    const FRAME: f32 = 1.0 / 60.0;
+
+    fn shape_at(id: u64, x: f32, y: f32) -> Shape {
+        Shape::with_id(id, ShapeGeometry::rectangle(10.0, 10.0), ShapeStyle::default())
+            .with_transform(Transform2D::from_position(Vec2::new(x, y)))
+    }
+
+    #[test]
+    fn test_spring_arrives_within_the_transition_without_overshooting() {
+        let mut animation = AnimationState::new(Vec4::ZERO, Vec4::new(500.0, -200.0, 2.0, 1.0));
+        let mut frames = 0;
+        let mut previous_x = 0.0;
+        while !animation.is_settled() {
+            animation.step(FRAME);
+            frames += 1;
+            assert!(animation.current.x >= previous_x && animation.current.x <= 500.0 + 1e-3);
+            assert!(animation.current.y <= 0.0 && animation.current.y >= -200.0 - 1e-3);
+            previous_x = animation.current.x;
+            assert!(frames < 100, "never settled");
+        }
+        assert!(frames as f32 * FRAME <= TRANSITION_DURATION + FRAME);
+        assert_eq!(animation.current, animation.target);
+    }
+
+    #[test]
+    fn test_spring_is_close_before_it_snaps() {
+        let mut animation = AnimationState::new(Vec4::ZERO, Vec4::new(100.0, 0.0, 0.0, 0.0));
+        // Halfway through, most of the distance is covered
+        for _ in 0..9 {
+            animation.step(FRAME);
+        }
+        assert!(animation.current.x > 80.0 && animation.current.x < 100.0);
+        // Just before the snap the remaining jump is invisible
+        for _ in 0..8 {
+            animation.step(FRAME);
+        }
+        assert!(animation.elapsed < TRANSITION_DURATION);
+        assert!(100.0 - animation.current.x < 0.5);
+
+        // A long stall only advances by one capped frame
+        let mut stalled = AnimationState::new(Vec4::ZERO, Vec4::new(100.0, 0.0, 0.0, 0.0));
+        stalled.step(5.0);
+        assert!(stalled.elapsed < TRANSITION_DURATION && stalled.current.x < 100.0);
+    }
+
+    #[test]
+    fn test_retarget_keeps_position_and_speed() {
+        let mut animation = AnimationState::new(Vec4::ZERO, Vec4::new(100.0, 0.0, 0.0, 0.0));
+        animation.step(0.05);
+        let (current, velocity) = (animation.current, animation.velocity);
+        animation.retarget(Vec4::new(200.0, 0.0, 0.0, 0.0));
+        assert_eq!((animation.current, animation.velocity, animation.elapsed), (current, velocity, 0.0));
+    }
+
+    #[test]
+    fn test_transitions_only_animate_when_asked() {
+        let mut transitions = ShapeTransitions::default();
+        transitions.update(&[shape_at(1, 0.0, 0.0), shape_at(2, 0.0, 0.0)], true);
+        // The first sighting of a shape has nothing to animate from
+        assert!(!transitions.is_active());
+
+        transitions.update(&[shape_at(1, 100.0, 0.0), shape_at(2, 0.0, 0.0)], false);
+        assert!(!transitions.is_active());
+
+        transitions.update(&[shape_at(1, 100.0, 50.0), shape_at(2, 0.0, 0.0)], true);
+        assert!(transitions.is_active());
+
+        // Drawn at the old position at first, then gliding toward the new one
+        let mut shapes = vec![shape_at(1, 100.0, 50.0), shape_at(2, 0.0, 0.0)];
+        transitions.apply(&mut shapes);
+        assert_eq!(shapes[0].transform.position, Vec2::new(100.0, 0.0));
+        transitions.step(0.1);
+        let mut shapes = vec![shape_at(1, 100.0, 50.0), shape_at(2, 0.0, 0.0)];
+        transitions.apply(&mut shapes);
+        assert!(shapes[0].transform.position.y > 0.0 && shapes[0].transform.position.y < 50.0);
+        assert_eq!(shapes[1].transform.position, Vec2::ZERO);
+
+        for _ in 0..20 {
+            transitions.step(FRAME);
+        }
+        assert!(!transitions.is_active());
+    }
+
+    #[test]
+    fn test_transitions_follow_scale_and_drop_removed_shapes() {
+        let mut transitions = ShapeTransitions::default();
+        transitions.update(&[shape_at(1, 0.0, 0.0)], true);
+        let mut scaled = shape_at(1, 0.0, 0.0);
+        scaled.transform.scale = Vec2::new(3.0, 2.0);
+        transitions.update(std::slice::from_ref(&scaled), true);
+
+        transitions.step(0.05);
+        let mut shapes = vec![scaled.clone()];
+        transitions.apply(&mut shapes);
+        assert!(shapes[0].transform.scale.x > 1.0 && shapes[0].transform.scale.x < 3.0);
+
+        transitions.update(&[], true);
+        assert!(!transitions.is_active());
+
+        transitions.update(&[shape_at(1, 0.0, 0.0)], true);
+        transitions.update(&[shape_at(1, 10.0, 0.0)], true);
+        transitions.clear();
+        assert!(!transitions.is_active());
+    }
+}
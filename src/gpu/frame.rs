@@ -0,0 +1,79 @@
+//! Frame pacing for the animation-frame render loop
+//!
+//! Changes request a frame by handing over what to draw; the loop takes it at
+//! most once per animation frame. A burst of changes between two frames costs a
+//! single render of the latest state, and frames where nothing changed are skipped.
+
+/// The next frame to draw, if anything changed since the last one
+#[derive(Debug)]
+pub struct FrameScheduler<T> {
+    pending: Option<T>,
+}
+
+impl<T> Default for FrameScheduler<T> {
+    fn default() -> Self {
+        Self { pending: None }
+    }
+}
+
+impl<T> FrameScheduler<T> {
+    /// Ask for `frame` to be drawn, replacing any requested frame not yet drawn
+    pub fn request(&mut self, frame: T) {
+        self.pending = Some(frame);
+    }
+
+    /// Whether a frame is waiting to be drawn
+    pub fn needs_render(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// Take the frame to draw now; None means this animation frame can be skipped
+    pub fn take(&mut self) -> Option<T> {
+        self.pending.take()
+    }
+}
+
+/// Time spent producing one frame, in milliseconds
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FrameStats {
+    /// Tessellating new or edited shapes and the selection overlay
+    pub tessellate_ms: f64,
+    /// Encoding and submitting the draw
+    pub render_ms: f64,
+}
+
+impl FrameStats {
+    pub fn total_ms(&self) -> f64 {
+        self.tessellate_ms + self.render_ms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_requests_between_frames_coalesce_into_one() {
+        let mut scheduler = FrameScheduler::default();
+        assert!(!scheduler.needs_render());
+
+        scheduler.request(1);
+        scheduler.request(2);
+        scheduler.request(3);
+        assert!(scheduler.needs_render());
+
+        // One render of the latest request, then idle frames are skipped
+        assert_eq!(scheduler.take(), Some(3));
+        assert!(!scheduler.needs_render());
+        assert_eq!(scheduler.take(), None);
+
+        scheduler.request(4);
+        assert_eq!(scheduler.take(), Some(4));
+    }
+
+    #[test]
+    fn test_frame_stats_total() {
+        let stats = FrameStats { tessellate_ms: 1.5, render_ms: 2.25 };
+        assert_eq!(stats.total_ms(), 3.75);
+    }
+}
@@ -0,0 +1,137 @@
+//! Background dot grid
+//!
+//! Drawn by its own pipeline as a fullscreen triangle; `grid.wgsl` works out each
+//! pixel's distance to the nearest dot from world coordinates, so the grid pans
+//! and zooms with the canvas without any per-dot geometry.
+
+use bytemuck::{Pod, Zeroable};
+use crate::scene::Color;
+
+/// Closest the dots may get on screen; sparser grid levels are used below this
+const MIN_SCREEN_SPACING: f32 = 8.0;
+
+/// Look of the dot grid
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GridStyle {
+    /// World units between dots at 100% zoom
+    pub spacing: f32,
+    /// Dot radius in CSS pixels, the same at any zoom
+    pub dot_radius: f32,
+    pub color: Color,
+}
+
+impl Default for GridStyle {
+    /// Matches the `canvas-dots` CSS background
+    fn default() -> Self {
+        Self {
+            spacing: 20.0,
+            dot_radius: 1.0,
+            color: Color::parse("#d1d5db").unwrap_or_default(),
+        }
+    }
+}
+
+/// Who draws the dot grid behind the canvas
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GridSource {
+    /// The GPU renderer, following the view's pan and zoom
+    Gpu,
+    /// The container's `canvas-dots` CSS background, while no GPU renderer is drawing
+    Css,
+    Hidden,
+}
+
+impl GridSource {
+    pub fn pick(show_grid: bool, gpu_active: bool) -> Self {
+        match (show_grid, gpu_active) {
+            (false, _) => GridSource::Hidden,
+            (true, true) => GridSource::Gpu,
+            (true, false) => GridSource::Css,
+        }
+    }
+}
+
+/// Uniform data for `grid.wgsl`, laid out to match its `GridUniforms` struct
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Pod, Zeroable)]
+pub struct GridUniforms {
+    pub color: [f32; 4],
+    /// View pan in CSS pixels
+    pub pan: [f32; 2],
+    pub zoom: f32,
+    /// World units between dots, after thinning for the zoom
+    pub spacing: f32,
+    pub dot_radius: f32,
+    pub pixel_ratio: f32,
+    _padding: [f32; 2],
+}
+
+impl GridUniforms {
+    /// Uniforms for `style` under a view matrix from `ViewTransform::to_matrix4`
+    /// When zoomed out far enough that dots would crowd, every other dot is dropped
+    pub fn new(style: &GridStyle, view_transform: &[[f32; 4]; 4], pixel_ratio: f32) -> Self {
+        let zoom = view_transform[0][0];
+        let mut spacing = style.spacing.max(f32::EPSILON);
+        while spacing * zoom < MIN_SCREEN_SPACING && spacing < f32::MAX / 2.0 {
+            spacing *= 2.0;
+        }
+        Self {
+            color: style.color.to_array(),
+            pan: [view_transform[3][0], view_transform[3][1]],
+            zoom,
+            spacing,
+            dot_radius: style.dot_radius,
+            pixel_ratio: if pixel_ratio > 0.0 { pixel_ratio } else { 1.0 },
+            _padding: [0.0; 2],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn view(zoom: f32, pan_x: f32, pan_y: f32) -> [[f32; 4]; 4] {
+        [
+            [zoom, 0.0, 0.0, 0.0],
+            [0.0, zoom, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [pan_x, pan_y, 0.0, 1.0],
+        ]
+    }
+
+    #[test]
+    fn test_uniforms_match_wgsl_layout() {
+        // vec4 color, vec2 pan, four scalars, then padding to a 16-byte multiple
+        assert_eq!(std::mem::size_of::<GridUniforms>(), 48);
+        assert_eq!(std::mem::offset_of!(GridUniforms, pan), 16);
+        assert_eq!(std::mem::offset_of!(GridUniforms, zoom), 24);
+        assert_eq!(std::mem::offset_of!(GridUniforms, spacing), 28);
+        assert_eq!(std::mem::offset_of!(GridUniforms, dot_radius), 32);
+        assert_eq!(std::mem::offset_of!(GridUniforms, pixel_ratio), 36);
+
+        let style = GridStyle { spacing: 25.0, dot_radius: 1.5, color: Color::rgb(1.0, 0.0, 0.0) };
+        let uniforms = GridUniforms::new(&style, &view(2.0, 30.0, -10.0), 2.0);
+        let floats: &[f32] = bytemuck::cast_slice(std::slice::from_ref(&uniforms));
+        assert_eq!(floats, &[1.0, 0.0, 0.0, 1.0, 30.0, -10.0, 2.0, 25.0, 1.5, 2.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_spacing_thins_out_when_zoomed_out() {
+        let style = GridStyle::default();
+        assert_eq!(GridUniforms::new(&style, &view(1.0, 0.0, 0.0), 1.0).spacing, 20.0);
+        assert_eq!(GridUniforms::new(&style, &view(0.4, 0.0, 0.0), 1.0).spacing, 20.0);
+        assert_eq!(GridUniforms::new(&style, &view(0.25, 0.0, 0.0), 1.0).spacing, 40.0);
+        assert_eq!(GridUniforms::new(&style, &view(0.1, 0.0, 0.0), 1.0).spacing, 80.0);
+        // A bogus pixel ratio falls back to 1
+        assert_eq!(GridUniforms::new(&style, &view(1.0, 0.0, 0.0), 0.0).pixel_ratio, 1.0);
+    }
+
+    #[test]
+    fn test_grid_source_toggle() {
+        assert_eq!(GridSource::pick(true, true), GridSource::Gpu);
+        assert_eq!(GridSource::pick(true, false), GridSource::Css);
+        assert_eq!(GridSource::pick(false, true), GridSource::Hidden);
+        assert_eq!(GridSource::pick(false, false), GridSource::Hidden);
+    }
+}
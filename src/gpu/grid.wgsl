@@ -0,0 +1,42 @@
+// Background dot grid, computed per pixel from world coordinates
+
+struct GridUniforms {
+    color: vec4<f32>,
+    // View pan in CSS pixels
+    pan: vec2<f32>,
+    zoom: f32,
+    // World units between dots
+    spacing: f32,
+    // Dot radius in CSS pixels
+    dot_radius: f32,
+    pixel_ratio: f32,
+    _padding: vec2<f32>,
+}
+
+@group(0) @binding(0)
+var<uniform> grid: GridUniforms;
+
+// One triangle covering the whole surface
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+    let x = f32(i32(index & 1u) * 4 - 1);
+    let y = f32(i32(index >> 1u) * 4 - 1);
+    return vec4<f32>(x, y, 0.0, 1.0);
+}
+
+@fragment
+fn fs_main(@builtin(position) frag: vec4<f32>) -> @location(0) vec4<f32> {
+    // Fragment position is in device pixels; undo the pixel ratio and the view
+    let screen = frag.xy / grid.pixel_ratio;
+    let world = (screen - grid.pan) / grid.zoom;
+
+    // Screen-space offset to the nearest dot, so dots keep their size at any zoom
+    let cell = world / grid.spacing;
+    let offset = (fract(cell + 0.5) - 0.5) * grid.spacing * grid.zoom;
+    let distance = length(offset);
+
+    // Antialias over one device pixel
+    let half_pixel = 0.5 / grid.pixel_ratio;
+    let coverage = 1.0 - smoothstep(grid.dot_radius - half_pixel, grid.dot_radius + half_pixel, distance);
+    return vec4<f32>(grid.color.rgb, grid.color.a * coverage);
+}
@@ -1,7 +1,17 @@
+mod animation;
+mod frame;
+mod grid;
 mod renderer;
 mod tessellation;
+mod text;
+mod textured;
 mod vertex;
 
+pub use animation::*;
+pub use frame::*;
+pub use grid::*;
 pub use renderer::*;
 pub use tessellation::*;
+pub use text::*;
+pub use textured::*;
 pub use vertex::*;
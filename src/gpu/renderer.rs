@@ -1,6 +1,11 @@
+use super::grid::{GridStyle, GridUniforms};
+use super::text::{layout_text, GlyphAtlas, GlyphCanvas, GlyphKey, ATLAS_SIZE};
+use super::textured::{image_quad, TexturedMesh, TexturedVertex};
 use super::vertex::{Mesh, Uniforms, Vertex};
-use crate::scene::Shape;
+use crate::scene::{Shape, ShapeGeometry};
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::ops::Range;
 use wgpu::util::DeviceExt;
 use web_sys::HtmlCanvasElement;
 
@@ -8,6 +13,130 @@ use web_sys::HtmlCanvasElement;
 const MAX_VERTICES: usize = 65536;
 /// Maximum number of indices we can render in a single draw call
 const MAX_INDICES: usize = MAX_VERTICES * 3;
+/// Most glyphs one text shape can draw; each is a quad of 4 vertices and 6 indices
+const MAX_GLYPHS: usize = 4096;
+
+/// Consecutive failed frames after which the canvas gives up on the GPU
+pub const MAX_CONSECUTIVE_FAILURES: u32 = 3;
+/// Samples per pixel with anti-aliasing on; 4 is the most WebGL2 guarantees
+pub const MSAA_SAMPLES: u32 = 4;
+
+/// Samples per pixel to render with: `MSAA_SAMPLES` when anti-aliasing is `enabled` and
+/// the surface format supports it, otherwise 1
+pub fn sample_count(enabled: bool, format_features: wgpu::TextureFormatFeatureFlags) -> u32 {
+    if enabled && format_features.sample_count_supported(MSAA_SAMPLES) {
+        MSAA_SAMPLES
+    } else {
+        1
+    }
+}
+
+/// Why a frame couldn't be rendered
+#[derive(Clone, Debug, PartialEq)]
+pub enum RendererError {
+    /// The surface stayed lost or outdated after reconfiguring it
+    SurfaceLost,
+    /// The GPU ran out of memory for a new frame
+    OutOfMemory,
+}
+
+impl std::fmt::Display for RendererError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RendererError::SurfaceLost => write!(f, "GPU surface lost and could not be recovered"),
+            RendererError::OutOfMemory => write!(f, "GPU out of memory"),
+        }
+    }
+}
+
+/// What to do when acquiring a frame fails
+#[derive(Clone, Debug, PartialEq)]
+enum SurfaceRecovery {
+    /// Reconfigure the surface and try again
+    Reconfigure,
+    /// Drop this frame; the next one will likely succeed
+    SkipFrame,
+    Fail(RendererError),
+}
+
+impl SurfaceRecovery {
+    fn for_error(error: &wgpu::SurfaceError) -> Self {
+        match error {
+            wgpu::SurfaceError::Lost | wgpu::SurfaceError::Outdated => SurfaceRecovery::Reconfigure,
+            wgpu::SurfaceError::Timeout => SurfaceRecovery::SkipFrame,
+            wgpu::SurfaceError::OutOfMemory => SurfaceRecovery::Fail(RendererError::OutOfMemory),
+        }
+    }
+}
+
+/// Counts consecutive failed frames so a flaky GPU can be abandoned for the SVG fallback
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FailureCounter {
+    consecutive: u32,
+}
+
+impl FailureCounter {
+    /// Record a frame's outcome; true once `MAX_CONSECUTIVE_FAILURES` frames in a row have failed
+    pub fn record<T>(&mut self, result: &Result<T, RendererError>) -> bool {
+        match result {
+            Ok(_) => self.consecutive = 0,
+            Err(_) => self.consecutive += 1,
+        }
+        self.consecutive >= MAX_CONSECUTIVE_FAILURES
+    }
+}
+
+/// Graphics API the renderer draws through
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GpuBackend {
+    WebGpu,
+    WebGl2,
+}
+
+impl GpuBackend {
+    /// Backends to try, best first
+    pub const PREFERENCE: [GpuBackend; 2] = [GpuBackend::WebGpu, GpuBackend::WebGl2];
+
+    /// Display name, e.g. for "GPU Mode (WebGPU)"
+    pub fn name(self) -> &'static str {
+        match self {
+            GpuBackend::WebGpu => "WebGPU",
+            GpuBackend::WebGl2 => "WebGL2",
+        }
+    }
+
+    fn backends(self) -> wgpu::Backends {
+        match self {
+            GpuBackend::WebGpu => wgpu::Backends::BROWSER_WEBGPU,
+            GpuBackend::WebGl2 => wgpu::Backends::GL,
+        }
+    }
+
+    /// Device limits to request; WebGL2 can't meet the WebGPU baseline
+    fn required_limits(self, adapter_limits: wgpu::Limits) -> wgpu::Limits {
+        match self {
+            GpuBackend::WebGpu => wgpu::Limits::downlevel_defaults().using_resolution(adapter_limits),
+            GpuBackend::WebGl2 => wgpu::Limits::downlevel_webgl2_defaults(),
+        }
+    }
+}
+
+/// Try each backend in `order` until one initializes
+/// Returns the first success, or every backend's error when none work
+async fn first_working_backend<T, F, Fut>(order: &[GpuBackend], mut attempt: F) -> Result<(GpuBackend, T), String>
+where
+    F: FnMut(GpuBackend) -> Fut,
+    Fut: std::future::Future<Output = Result<T, String>>,
+{
+    let mut errors = Vec::new();
+    for &backend in order {
+        match attempt(backend).await {
+            Ok(value) => return Ok((backend, value)),
+            Err(e) => errors.push(format!("{}: {}", backend.name(), e)),
+        }
+    }
+    Err(errors.join("; "))
+}
 
 /// Multiply two 4x4 matrices (column-major order)
 /// Result = a * b
@@ -25,6 +154,200 @@ fn multiply_mat4(a: &[[f32; 4]; 4], b: &[[f32; 4]; 4]) -> [[f32; 4]; 4] {
     result
 }
 
+/// World-to-surface transform for `shape`: the view, then any drag override, then the shape's own
+pub fn shape_model_transform(
+    shape: &Shape,
+    transform_overrides: &HashMap<u64, [[f32; 4]; 4]>,
+    view_transform: &[[f32; 4]; 4],
+) -> [[f32; 4]; 4] {
+    // The override applies on top of the shape's base transform during drag operations
+    let base_transform = shape.transform.to_matrix4();
+    let model_transform = match transform_overrides.get(&shape.id) {
+        Some(override_transform) => multiply_mat4(override_transform, &base_transform),
+        None => base_transform,
+    };
+    // The canvas zoom/pan maps world space onto the surface last
+    multiply_mat4(view_transform, &model_transform)
+}
+
+/// Draw order for meshes at `depths`: deepest first, so nearer meshes blend over them
+/// Meshes at the same depth keep their given order
+pub fn back_to_front(depths: &[f32]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..depths.len()).collect();
+    order.sort_by(|&a, &b| depths[b].total_cmp(&depths[a]));
+    order
+}
+
+/// Pack `meshes`, in draw order, into as few buffer uploads of at most `max_vertices` and
+/// `max_indices` as they fit in, with the index range of every mesh in its batch so each is
+/// still drawn as its own call; a mesh too large for the buffers alone is split with `Mesh::chunks`
+pub fn batch_meshes(meshes: &[&Mesh], max_vertices: usize, max_indices: usize) -> Vec<(Mesh, Vec<Range<u32>>)> {
+    let mut batches = Vec::new();
+    let mut batch: (Mesh, Vec<Range<u32>>) = Default::default();
+    for &mesh in meshes.iter().filter(|mesh| !mesh.is_empty()) {
+        // A chunk of n indices references at most n vertices
+        let pieces: Vec<Cow<Mesh>> = if mesh.vertices.len() > max_vertices || mesh.indices.len() > max_indices {
+            mesh.chunks(max_vertices.min(max_indices)).into_iter().map(Cow::Owned).collect()
+        } else {
+            vec![Cow::Borrowed(mesh)]
+        };
+        for piece in pieces {
+            let full = batch.0.vertices.len() + piece.vertices.len() > max_vertices
+                || batch.0.indices.len() + piece.indices.len() > max_indices;
+            if full && !batch.1.is_empty() {
+                batches.push(std::mem::take(&mut batch));
+            }
+            let start = batch.0.indices.len() as u32;
+            batch.0.extend(&piece);
+            batch.1.push(start..batch.0.indices.len() as u32);
+        }
+    }
+    if !batch.1.is_empty() {
+        batches.push(batch);
+    }
+    batches
+}
+
+/// Shaders and layouts the render pipelines are built from
+struct PipelineSources {
+    format: wgpu::TextureFormat,
+    shape_shader: wgpu::ShaderModule,
+    shape_layout: wgpu::PipelineLayout,
+    grid_shader: wgpu::ShaderModule,
+    grid_layout: wgpu::PipelineLayout,
+    textured_shader: wgpu::ShaderModule,
+    textured_layout: wgpu::PipelineLayout,
+}
+
+/// Every pipeline that draws into the color target; all share its sample count
+struct Pipelines {
+    shape: wgpu::RenderPipeline,
+    grid: wgpu::RenderPipeline,
+    text: wgpu::RenderPipeline,
+    image: wgpu::RenderPipeline,
+}
+
+impl PipelineSources {
+    /// Build the pipelines for a color target with `sample_count` samples per pixel
+    fn build(&self, device: &wgpu::Device, sample_count: u32) -> Pipelines {
+        let multisample = wgpu::MultisampleState {
+            count: sample_count,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        };
+        let targets = [Some(wgpu::ColorTargetState {
+            format: self.format,
+            blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+            write_mask: wgpu::ColorWrites::ALL,
+        })];
+
+        let shape = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Shape Render Pipeline"),
+            layout: Some(&self.shape_layout),
+            vertex: wgpu::VertexState {
+                module: &self.shape_shader,
+                entry_point: "vs_main",
+                buffers: &[Vertex::desc()],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &self.shape_shader,
+                entry_point: "fs_main",
+                targets: &targets,
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None, // No culling for 2D
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample,
+            multiview: None,
+            cache: None,
+        });
+
+        let grid = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Grid Render Pipeline"),
+            layout: Some(&self.grid_layout),
+            vertex: wgpu::VertexState {
+                module: &self.grid_shader,
+                entry_point: "vs_main",
+                buffers: &[],
+                compilation_options: Default::default(),
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &self.grid_shader,
+                entry_point: "fs_main",
+                targets: &targets,
+                compilation_options: Default::default(),
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample,
+            multiview: None,
+            cache: None,
+        });
+
+        let textured = |label: &str, fragment_entry: &str| {
+            device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some(label),
+                layout: Some(&self.textured_layout),
+                vertex: wgpu::VertexState {
+                    module: &self.textured_shader,
+                    entry_point: "vs_main",
+                    buffers: &[TexturedVertex::desc()],
+                    compilation_options: Default::default(),
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &self.textured_shader,
+                    entry_point: fragment_entry,
+                    targets: &targets,
+                    compilation_options: Default::default(),
+                }),
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: None,
+                multisample,
+                multiview: None,
+                cache: None,
+            })
+        };
+
+        Pipelines {
+            shape,
+            grid,
+            text: textured("Text Render Pipeline", "fs_glyph"),
+            image: textured("Image Render Pipeline", "fs_image"),
+        }
+    }
+}
+
+/// A multisampled color target matching the surface, or None for a single sample
+fn msaa_target(device: &wgpu::Device, config: &wgpu::SurfaceConfiguration, sample_count: u32) -> Option<wgpu::TextureView> {
+    if sample_count <= 1 {
+        return None;
+    }
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("MSAA Color Target"),
+        size: wgpu::Extent3d {
+            width: config.width,
+            height: config.height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count,
+        dimension: wgpu::TextureDimension::D2,
+        format: config.format,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+        view_formats: &[],
+    });
+    Some(texture.create_view(&wgpu::TextureViewDescriptor::default()))
+}
+
 /// GPU renderer using wgpu
 /// Handles WebGL/WebGPU initialization and shape rendering
 pub struct Renderer {
@@ -32,50 +355,98 @@ pub struct Renderer {
     device: wgpu::Device,
     queue: wgpu::Queue,
     config: wgpu::SurfaceConfiguration,
-    render_pipeline: wgpu::RenderPipeline,
+    /// Kept to rebuild the pipelines when the sample count changes
+    pipeline_sources: PipelineSources,
+    pipelines: Pipelines,
+    /// Samples per pixel the surface format can anti-alias with; 1 when it can't
+    msaa_samples: u32,
+    /// Samples per pixel in use
+    sample_count: u32,
+    /// Multisampled color target each pass draws into and resolves to the surface;
+    /// None when drawing straight to the surface
+    msaa_view: Option<wgpu::TextureView>,
     vertex_buffer: wgpu::Buffer,
     index_buffer: wgpu::Buffer,
     uniform_buffer: wgpu::Buffer,
     uniform_bind_group: wgpu::BindGroup,
+    grid_uniform_buffer: wgpu::Buffer,
+    grid_bind_group: wgpu::BindGroup,
+    /// Dot grid drawn behind the shapes, if any
+    grid: Option<GridStyle>,
+    /// Geometry for textured quads, shared by text and images
+    quad_vertex_buffer: wgpu::Buffer,
+    quad_index_buffer: wgpu::Buffer,
+    texture_bind_group_layout: wgpu::BindGroupLayout,
+    texture_sampler: wgpu::Sampler,
+    atlas_texture: wgpu::Texture,
+    atlas_bind_group: wgpu::BindGroup,
+    /// Where each rasterized glyph sits in the atlas texture
+    glyph_atlas: GlyphAtlas,
+    /// Draws glyphs for upload; None when no 2D canvas is available, which leaves text undrawn
+    glyph_canvas: Option<GlyphCanvas>,
+    /// Uploaded image textures, keyed by the image shape's `src`
+    image_textures: HashMap<String, wgpu::BindGroup>,
+    /// Surface size in physical pixels
     width: u32,
     height: u32,
+    /// Device pixels per CSS pixel; shapes are positioned in CSS pixels
+    pixel_ratio: f32,
+    /// Round vertices to device-pixel centers once placed on the surface, so thin strokes render crisp
+    pixel_snap: bool,
+    /// Backend that initialized successfully
+    backend: GpuBackend,
 }
 
 impl Renderer {
     /// Create a new renderer attached to an HTML canvas element
-    pub async fn new(canvas: HtmlCanvasElement) -> Result<Self, String> {
+    /// Prefers WebGPU and falls back to WebGL2 when it's missing or fails to set up
+    /// The canvas backing store should already be sized to its CSS size × `pixel_ratio`
+    pub async fn new(canvas: HtmlCanvasElement, pixel_ratio: f32) -> Result<Self, String> {
+        let (_, renderer) = first_working_backend(&GpuBackend::PREFERENCE, |backend| {
+            Self::with_backend(canvas.clone(), backend, pixel_ratio)
+        })
+        .await?;
+        Ok(renderer)
+    }
+
+    /// Create a renderer on one specific backend
+    async fn with_backend(canvas: HtmlCanvasElement, backend: GpuBackend, pixel_ratio: f32) -> Result<Self, String> {
         let width = canvas.width();
         let height = canvas.height();
 
-        // Create wgpu instance - use WebGL2 only for browser compatibility
-        // WebGPU has compatibility issues with wgpu 22.x and current Chrome
         let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
-            backends: wgpu::Backends::GL,
+            backends: backend.backends(),
             ..Default::default()
         });
 
-        // Create surface from canvas
-        let surface = instance
-            .create_surface(wgpu::SurfaceTarget::Canvas(canvas))
-            .map_err(|e| format!("Failed to create surface: {e}"))?;
-
-        // Request adapter
+        // Find an adapter before touching the canvas: once a canvas hands out a
+        // WebGPU context it can't give a WebGL2 one, so a fallback must not follow that
         let adapter = instance
             .request_adapter(&wgpu::RequestAdapterOptions {
                 power_preference: wgpu::PowerPreference::HighPerformance,
-                compatible_surface: Some(&surface),
+                compatible_surface: None,
                 force_fallback_adapter: false,
             })
             .await
             .ok_or("Failed to find a suitable GPU adapter")?;
+        if backend == GpuBackend::WebGpu && adapter.get_info().backend != wgpu::Backend::BrowserWebGpu {
+            return Err("WebGPU is not available in this browser".to_string());
+        }
+
+        // Create surface from canvas
+        let surface = instance
+            .create_surface(wgpu::SurfaceTarget::Canvas(canvas))
+            .map_err(|e| format!("Failed to create surface: {e}"))?;
+        if !adapter.is_surface_supported(&surface) {
+            return Err("Adapter can't present to the canvas".to_string());
+        }
 
-        // Request device and queue with WebGL2-compatible limits
         let (device, queue) = adapter
             .request_device(
                 &wgpu::DeviceDescriptor {
                     label: Some("Canvas Renderer Device"),
                     required_features: wgpu::Features::empty(),
-                    required_limits: wgpu::Limits::downlevel_webgl2_defaults(),
+                    required_limits: backend.required_limits(adapter.limits()),
                     memory_hints: Default::default(),
                 },
                 None,
@@ -115,7 +486,12 @@ impl Renderer {
             view_formats: vec![],
             desired_maximum_frame_latency: 2,
         };
+        // A configuration the surface rejects is a validation error, not a panic, inside a scope
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
         surface.configure(&device, &config);
+        if let Some(error) = device.pop_error_scope().await {
+            return Err(format!("Failed to configure surface: {error}"));
+        }
 
         // Create shader module
         let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
@@ -124,7 +500,7 @@ impl Renderer {
         });
 
         // Create uniform buffer
-        let uniforms = Uniforms::orthographic(width as f32, height as f32);
+        let uniforms = Uniforms::for_surface(width, height, pixel_ratio);
         let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Uniform Buffer"),
             contents: bytemuck::cast_slice(&[uniforms]),
@@ -162,44 +538,152 @@ impl Renderer {
             push_constant_ranges: &[],
         });
 
-        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Shape Render Pipeline"),
-            layout: Some(&pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &shader,
-                entry_point: "vs_main",
-                buffers: &[Vertex::desc()],
-                compilation_options: Default::default(),
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &shader,
-                entry_point: "fs_main",
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: config.format,
-                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-                compilation_options: Default::default(),
-            }),
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList,
-                strip_index_format: None,
-                front_face: wgpu::FrontFace::Ccw,
-                cull_mode: None, // No culling for 2D
-                polygon_mode: wgpu::PolygonMode::Fill,
-                unclipped_depth: false,
-                conservative: false,
-            },
-            depth_stencil: None,
-            multisample: wgpu::MultisampleState {
-                count: 1,
-                mask: !0,
-                alpha_to_coverage_enabled: false,
+        // Background dot grid: a fullscreen triangle shaded from its own uniforms
+        let grid_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Grid Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("grid.wgsl").into()),
+        });
+
+        let grid_uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Grid Uniform Buffer"),
+            size: std::mem::size_of::<GridUniforms>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let grid_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Grid Bind Group Layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+
+        let grid_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Grid Bind Group"),
+            layout: &grid_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: grid_uniform_buffer.as_entire_binding(),
+            }],
+        });
+
+        let grid_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Grid Pipeline Layout"),
+            bind_group_layouts: &[&grid_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        // Text and images: textured quads sharing the shape uniforms
+        // Text samples the glyph atlas, each image its own texture
+        let textured_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Textured Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("textured.wgsl").into()),
+        });
+
+        let atlas_texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Glyph Atlas"),
+            size: wgpu::Extent3d {
+                width: ATLAS_SIZE,
+                height: ATLAS_SIZE,
+                depth_or_array_layers: 1,
             },
-            multiview: None,
-            cache: None,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        let atlas_view = atlas_texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let texture_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Texture Sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
         });
 
+        let texture_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Texture Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+
+        let atlas_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Glyph Atlas Bind Group"),
+            layout: &texture_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&atlas_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&texture_sampler),
+                },
+            ],
+        });
+
+        let textured_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Textured Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout, &texture_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        // Anti-alias with MSAA when the surface format supports it
+        let msaa_samples = sample_count(true, adapter.get_texture_format_features(config.format).flags);
+        let pipeline_sources = PipelineSources {
+            format: config.format,
+            shape_shader: shader,
+            shape_layout: pipeline_layout,
+            grid_shader,
+            grid_layout: grid_pipeline_layout,
+            textured_shader,
+            textured_layout: textured_pipeline_layout,
+        };
+        let pipelines = pipeline_sources.build(&device, msaa_samples);
+        let msaa_view = msaa_target(&device, &config, msaa_samples);
+
+        let quad_vertex_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Quad Vertex Buffer"),
+            size: (MAX_GLYPHS * 4 * std::mem::size_of::<TexturedVertex>()) as u64,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let quad_index_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Quad Index Buffer"),
+            size: (MAX_GLYPHS * 6 * std::mem::size_of::<u32>()) as u64,
+            usage: wgpu::BufferUsages::INDEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let glyph_canvas = GlyphCanvas::new(ATLAS_SIZE)
+            .map_err(|e| web_sys::console::warn_1(&format!("Text won't render: {e}").into()))
+            .ok();
+
         // Create vertex and index buffers with initial capacity
         let vertex_buffer = device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("Vertex Buffer"),
@@ -220,108 +704,275 @@ impl Renderer {
             device,
             queue,
             config,
-            render_pipeline,
+            pipeline_sources,
+            pipelines,
+            msaa_samples,
+            sample_count: msaa_samples,
+            msaa_view,
             vertex_buffer,
             index_buffer,
             uniform_buffer,
             uniform_bind_group,
+            grid_uniform_buffer,
+            grid_bind_group,
+            grid: None,
+            quad_vertex_buffer,
+            quad_index_buffer,
+            texture_bind_group_layout,
+            texture_sampler,
+            atlas_texture,
+            atlas_bind_group,
+            glyph_atlas: GlyphAtlas::new(ATLAS_SIZE),
+            glyph_canvas,
+            image_textures: HashMap::new(),
             width,
             height,
+            pixel_ratio,
+            pixel_snap: false,
+            backend,
         })
     }
 
-    /// Resize the renderer when canvas size changes
-    pub fn resize(&mut self, width: u32, height: u32) {
-        if width > 0 && height > 0 && (width != self.width || height != self.height) {
+    /// Backend in use
+    pub fn backend(&self) -> GpuBackend {
+        self.backend
+    }
+
+    /// Display name of the backend in use, e.g. "WebGPU"
+    pub fn backend_name(&self) -> &'static str {
+        self.backend.name()
+    }
+
+    /// Resize the renderer when the canvas backing store or device pixel ratio changes
+    /// `width` and `height` are in physical pixels
+    pub fn resize(&mut self, width: u32, height: u32, pixel_ratio: f32) {
+        let changed = width != self.width || height != self.height || pixel_ratio != self.pixel_ratio;
+        if width > 0 && height > 0 && changed {
             self.width = width;
             self.height = height;
+            self.pixel_ratio = pixel_ratio;
             self.config.width = width;
             self.config.height = height;
             self.surface.configure(&self.device, &self.config);
+            self.msaa_view = msaa_target(&self.device, &self.config, self.sample_count);
 
             // Update uniforms with new projection
-            let uniforms = Uniforms::orthographic(width as f32, height as f32);
+            let uniforms = Uniforms::for_surface(width, height, pixel_ratio);
             self.queue
                 .write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
         }
     }
 
-    /// Render a mesh to the canvas
-    /// Clears with the given background color and draws all triangles
-    pub fn render(&mut self, mesh: &Mesh, clear_color: [f32; 4]) -> Result<(), String> {
-        if mesh.vertices.len() > MAX_VERTICES {
-            return Err(format!(
-                "Too many vertices: {} (max {})",
-                mesh.vertices.len(),
-                MAX_VERTICES
-            ));
+    /// Turn multisample anti-aliasing on or off; stays off where the surface format
+    /// doesn't support it
+    pub fn set_msaa(&mut self, enabled: bool) {
+        let count = if enabled { self.msaa_samples } else { 1 };
+        if count != self.sample_count {
+            self.sample_count = count;
+            self.pipelines = self.pipeline_sources.build(&self.device, count);
+            self.msaa_view = msaa_target(&self.device, &self.config, count);
         }
-        if mesh.indices.len() > MAX_INDICES {
-            return Err(format!(
-                "Too many indices: {} (max {})",
-                mesh.indices.len(),
-                MAX_INDICES
-            ));
+    }
+
+    /// Turn pixel snapping on or off; applies from the next frame drawn
+    pub fn set_pixel_snap(&mut self, enabled: bool) {
+        self.pixel_snap = enabled;
+    }
+
+    /// Samples per pixel in use; above 1 with MSAA on
+    pub fn sample_count(&self) -> u32 {
+        self.sample_count
+    }
+
+    /// A pass's color attachment for drawing into `target`: the MSAA target resolving
+    /// into it when anti-aliasing, or `target` itself
+    fn color_attachment<'a>(&'a self, target: &'a wgpu::TextureView, load: wgpu::LoadOp<wgpu::Color>) -> wgpu::RenderPassColorAttachment<'a> {
+        let (view, resolve_target) = match &self.msaa_view {
+            Some(msaa_view) => (msaa_view, Some(target)),
+            None => (target, None),
+        };
+        wgpu::RenderPassColorAttachment {
+            view,
+            resolve_target,
+            ops: wgpu::Operations {
+                load,
+                // Samples are kept so later passes keep drawing over them
+                store: wgpu::StoreOp::Store,
+            },
         }
+    }
 
-        // Get surface texture to render to
-        let output = self
-            .surface
-            .get_current_texture()
-            .map_err(|e| format!("Failed to get surface texture: {e}"))?;
+    /// Show the dot grid behind the shapes with `style`, or hide it with None
+    pub fn set_grid(&mut self, grid: Option<GridStyle>) {
+        self.grid = grid;
+    }
+
+    /// Whether the image for `src` has been uploaded
+    pub fn has_image(&self, src: &str) -> bool {
+        self.image_textures.contains_key(src)
+    }
+
+    /// Upload RGBA `pixels` of `width` × `height` as the texture for image shapes showing `src`
+    pub fn upload_image(&mut self, src: &str, width: u32, height: u32, pixels: &[u8]) {
+        let size = wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Image Texture"),
+            size,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        self.queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            pixels,
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(4 * width),
+                rows_per_image: Some(height),
+            },
+            size,
+        );
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Image Bind Group"),
+            layout: &self.texture_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&self.texture_sampler),
+                },
+            ],
+        });
+        self.image_textures.insert(src.to_string(), bind_group);
+    }
+
+    /// Drop textures of images no longer in the scene
+    pub fn retain_images(&mut self, keep: &std::collections::HashSet<&str>) {
+        self.image_textures.retain(|src, _| keep.contains(src.as_str()));
+    }
+
+    /// Reconfigure the surface with the current size, e.g. after it was lost
+    pub fn reconfigure(&mut self) {
+        self.surface.configure(&self.device, &self.config);
+    }
+
+    /// Get the next frame to draw into, recovering a lost or outdated surface once
+    /// Returns None when this frame should be skipped
+    fn acquire_frame(&mut self) -> Result<Option<wgpu::SurfaceTexture>, RendererError> {
+        let mut reconfigured = false;
+        loop {
+            let error = match self.surface.get_current_texture() {
+                Ok(frame) => return Ok(Some(frame)),
+                Err(error) => error,
+            };
+            match SurfaceRecovery::for_error(&error) {
+                SurfaceRecovery::Reconfigure if !reconfigured => {
+                    self.reconfigure();
+                    reconfigured = true;
+                }
+                SurfaceRecovery::Reconfigure => return Err(RendererError::SurfaceLost),
+                SurfaceRecovery::SkipFrame => return Ok(None),
+                SurfaceRecovery::Fail(error) => return Err(error),
+            }
+        }
+    }
+
+    /// Render separate meshes, already in surface coordinates, sorted back-to-front by depth
+    /// Greater depth is further back; clears with the given background color, then draws every
+    /// mesh as its own call so overlapping translucent meshes blend in order. Meshes are packed
+    /// into the shared buffers in batches, one render pass each, when they don't all fit at once
+    pub fn render_multipass(&mut self, meshes: &[(&Mesh, f32)], clear_color: [f32; 4]) -> Result<(), RendererError> {
+        let Some(output) = self.acquire_frame()? else {
+            return Ok(());
+        };
 
         let view = output
             .texture
             .create_view(&wgpu::TextureViewDescriptor::default());
 
-        // Upload vertex and index data
-        if !mesh.is_empty() {
-            self.queue
-                .write_buffer(&self.vertex_buffer, 0, bytemuck::cast_slice(&mesh.vertices));
-            self.queue
-                .write_buffer(&self.index_buffer, 0, bytemuck::cast_slice(&mesh.indices));
+        // Indices are rebased while packing since WebGL2 can't offset them per draw
+        let depths: Vec<f32> = meshes.iter().map(|(_, depth)| *depth).collect();
+        let ordered: Vec<&Mesh> = back_to_front(&depths).into_iter().map(|i| meshes[i].0).collect();
+        let mut batches = batch_meshes(&ordered, MAX_VERTICES, MAX_INDICES);
+        // An empty scene still needs its clear pass
+        if batches.is_empty() {
+            batches.push(Default::default());
         }
 
-        // Create command encoder
-        let mut encoder = self
-            .device
-            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
-                label: Some("Render Encoder"),
-            });
+        let uniforms = Uniforms::for_surface(self.width, self.height, self.pixel_ratio);
+        self.queue
+            .write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
 
-        // Begin render pass
-        {
-            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                label: Some("Shape Render Pass"),
-                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &view,
-                    resolve_target: None,
-                    ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color {
-                            r: clear_color[0] as f64,
-                            g: clear_color[1] as f64,
-                            b: clear_color[2] as f64,
-                            a: clear_color[3] as f64,
-                        }),
-                        store: wgpu::StoreOp::Store,
-                    },
-                })],
-                depth_stencil_attachment: None,
-                timestamp_writes: None,
-                occlusion_query_set: None,
-            });
+        for (i, (mut packed, ranges)) in batches.into_iter().enumerate() {
+            // Packed meshes are already in surface coordinates
+            if self.pixel_snap {
+                packed.snap_to_pixels(self.pixel_ratio);
+            }
+            // Each batch overwrites the buffers, so it's submitted before the next is uploaded
+            if !packed.is_empty() {
+                self.queue
+                    .write_buffer(&self.vertex_buffer, 0, bytemuck::cast_slice(&packed.vertices));
+                self.queue
+                    .write_buffer(&self.index_buffer, 0, bytemuck::cast_slice(&packed.indices));
+            }
+
+            let load = if i == 0 {
+                wgpu::LoadOp::Clear(wgpu::Color {
+                    r: clear_color[0] as f64,
+                    g: clear_color[1] as f64,
+                    b: clear_color[2] as f64,
+                    a: clear_color[3] as f64,
+                })
+            } else {
+                wgpu::LoadOp::Load
+            };
 
-            if !mesh.is_empty() {
-                render_pass.set_pipeline(&self.render_pipeline);
-                render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
-                render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
-                render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
-                render_pass.draw_indexed(0..mesh.indices.len() as u32, 0, 0..1);
+            let mut encoder = self
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: Some("Multipass Encoder"),
+                });
+
+            {
+                let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some("Multipass Render Pass"),
+                    color_attachments: &[Some(self.color_attachment(&view, load))],
+                    depth_stencil_attachment: None,
+                    timestamp_writes: None,
+                    occlusion_query_set: None,
+                });
+
+                if !ranges.is_empty() {
+                    render_pass.set_pipeline(&self.pipelines.shape);
+                    render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
+                    render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+                    render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+                    for range in ranges {
+                        render_pass.draw_indexed(range, 0, 0..1);
+                    }
+                }
             }
+
+            self.queue.submit(std::iter::once(encoder.finish()));
         }
 
-        // Submit commands
-        self.queue.submit(std::iter::once(encoder.finish()));
         output.present();
 
         Ok(())
@@ -343,25 +994,32 @@ impl Renderer {
     /// - `shape_meshes`: Pre-tessellated meshes for each shape (keyed by shape ID)
     /// - `shapes`: The shapes to render (for getting base transforms)
     /// - `transform_overrides`: Map of shape ID to transform matrix override
+    /// - `overlay`: Selection UI in screen pixels, drawn over the shapes without the view transform
     /// - `clear_color`: Background color
     pub fn render_shapes_with_transforms(
         &mut self,
         shape_meshes: &HashMap<u64, Mesh>,
         shapes: &[Shape],
         transform_overrides: &HashMap<u64, [[f32; 4]; 4]>,
+        view_transform: &[[f32; 4]; 4],
+        overlay: &Mesh,
         clear_color: [f32; 4],
-    ) -> Result<(), String> {
+    ) -> Result<(), RendererError> {
         // Get surface texture to render to
-        let output = self
-            .surface
-            .get_current_texture()
-            .map_err(|e| format!("Failed to get surface texture: {e}"))?;
+        let Some(output) = self.acquire_frame()? else {
+            return Ok(());
+        };
 
         let view = output
             .texture
             .create_view(&wgpu::TextureViewDescriptor::default());
 
-        // First pass: clear the screen
+        // First pass: clear the screen and draw the dot grid under everything
+        if let Some(style) = &self.grid {
+            let uniforms = GridUniforms::new(style, view_transform, self.pixel_ratio);
+            self.queue
+                .write_buffer(&self.grid_uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
+        }
         {
             let mut encoder = self
                 .device
@@ -370,25 +1028,27 @@ impl Renderer {
                 });
 
             {
-                let _render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                     label: Some("Clear Pass"),
-                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                        view: &view,
-                        resolve_target: None,
-                        ops: wgpu::Operations {
-                            load: wgpu::LoadOp::Clear(wgpu::Color {
-                                r: clear_color[0] as f64,
-                                g: clear_color[1] as f64,
-                                b: clear_color[2] as f64,
-                                a: clear_color[3] as f64,
-                            }),
-                            store: wgpu::StoreOp::Store,
-                        },
-                    })],
+                    color_attachments: &[Some(self.color_attachment(
+                        &view,
+                        wgpu::LoadOp::Clear(wgpu::Color {
+                            r: clear_color[0] as f64,
+                            g: clear_color[1] as f64,
+                            b: clear_color[2] as f64,
+                            a: clear_color[3] as f64,
+                        }),
+                    ))],
                     depth_stencil_attachment: None,
                     timestamp_writes: None,
                     occlusion_query_set: None,
                 });
+
+                if self.grid.is_some() {
+                    render_pass.set_pipeline(&self.pipelines.grid);
+                    render_pass.set_bind_group(0, &self.grid_bind_group, &[]);
+                    render_pass.draw(0..3, 0..1);
+                }
             }
 
             self.queue.submit(std::iter::once(encoder.finish()));
@@ -396,7 +1056,27 @@ impl Renderer {
 
         // Render each shape with its own transform
         // Each shape needs its own submit because uniform buffers are shared
-        for shape in shapes {
+        for shape in shapes.iter().filter(|shape| shape.visible) {
+            // Text is drawn from the glyph atlas rather than a tessellated mesh
+            if matches!(shape.geometry, ShapeGeometry::Text { .. }) {
+                let text_mesh = self.text_mesh(shape);
+                if !text_mesh.is_empty() && text_mesh.vertices.len() <= MAX_GLYPHS * 4 {
+                    let model_transform = shape_model_transform(shape, transform_overrides, view_transform);
+                    self.draw_textured_over(&view, &text_mesh, model_transform, &self.pipelines.text, &self.atlas_bind_group);
+                }
+                continue;
+            }
+
+            // Images wait for their texture to be uploaded
+            if let ShapeGeometry::Image { width, height, src } = &shape.geometry {
+                if let Some(bind_group) = self.image_textures.get(src) {
+                    let model_transform = shape_model_transform(shape, transform_overrides, view_transform);
+                    let quad = image_quad(*width, *height, shape.style.opacity);
+                    self.draw_textured_over(&view, &quad, model_transform, &self.pipelines.image, bind_group);
+                }
+                continue;
+            }
+
             let mesh = match shape_meshes.get(&shape.id) {
                 Some(m) => m,
                 None => continue, // Skip shapes without meshes
@@ -406,67 +1086,322 @@ impl Renderer {
                 continue;
             }
 
-            if mesh.vertices.len() > MAX_VERTICES || mesh.indices.len() > MAX_INDICES {
-                continue; // Skip shapes that are too large
-            }
+            let model_transform = shape_model_transform(shape, transform_overrides, view_transform);
+            self.draw_mesh_over(&view, mesh, model_transform);
+        }
 
-            // Get transform - compose override with shape's base transform if available
-            // The shape's base transform positions the shape in world space
-            // The override applies additional translation/scale during drag operations
-            let base_transform = shape.transform.to_matrix4();
-            let model_transform = if let Some(override_transform) = transform_overrides.get(&shape.id) {
-                // Compose: override * base (apply base first to get world position, then override)
-                multiply_mat4(override_transform, &base_transform)
-            } else {
-                base_transform
-            };
+        // Selection UI goes on top, already positioned in screen pixels
+        if !overlay.is_empty() {
+            self.draw_mesh_over(&view, overlay, Uniforms::identity_matrix());
+        }
 
-            // Update buffers
-            let uniforms = Uniforms::orthographic(self.width as f32, self.height as f32)
-                .with_model_transform(model_transform);
-            self.queue
-                .write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
-            self.queue
-                .write_buffer(&self.vertex_buffer, 0, bytemuck::cast_slice(&mesh.vertices));
-            self.queue
-                .write_buffer(&self.index_buffer, 0, bytemuck::cast_slice(&mesh.indices));
+        output.present();
 
-            // Create encoder and render pass for this shape
-            let mut encoder = self
-                .device
-                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
-                    label: Some("Shape Encoder"),
-                });
+        Ok(())
+    }
 
-            {
-                let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                    label: Some("Shape Render Pass"),
-                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                        view: &view,
-                        resolve_target: None,
-                        ops: wgpu::Operations {
-                            load: wgpu::LoadOp::Load, // Don't clear, preserve previous draws
-                            store: wgpu::StoreOp::Store,
-                        },
-                    })],
-                    depth_stencil_attachment: None,
-                    timestamp_writes: None,
-                    occlusion_query_set: None,
-                });
+    /// Glyph quads for a text shape, rasterizing and uploading any glyphs it's missing
+    /// A full atlas is emptied and refilled with just this shape's glyphs
+    fn text_mesh(&mut self, shape: &Shape) -> TexturedMesh {
+        let ShapeGeometry::Text { content, font_size, font_family, align } = &shape.geometry else {
+            return TexturedMesh::default();
+        };
+        let Some(canvas) = &self.glyph_canvas else {
+            return TexturedMesh::default();
+        };
 
-                render_pass.set_pipeline(&self.render_pipeline);
-                render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
-                render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
-                render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
-                render_pass.draw_indexed(0..mesh.indices.len() as u32, 0, 0..1);
+        let missing: Vec<GlyphKey> = content
+            .chars()
+            .filter(|ch| !ch.is_whitespace())
+            .map(|ch| GlyphKey::new(font_family, ch))
+            .filter(|key| self.glyph_atlas.get(key).is_none())
+            .collect();
+        if !missing.is_empty() {
+            for key in missing {
+                let rect = match self.glyph_atlas.insert(key.clone()) {
+                    Some(rect) => Some(rect),
+                    None => {
+                        self.glyph_atlas.clear();
+                        canvas.clear();
+                        self.glyph_atlas.insert(key.clone())
+                    }
+                };
+                if let Some(rect) = rect {
+                    canvas.draw(&key, rect);
+                }
+            }
+            if let Some(pixels) = canvas.pixels() {
+                self.queue.write_texture(
+                    wgpu::ImageCopyTexture {
+                        texture: &self.atlas_texture,
+                        mip_level: 0,
+                        origin: wgpu::Origin3d::ZERO,
+                        aspect: wgpu::TextureAspect::All,
+                    },
+                    &pixels,
+                    wgpu::ImageDataLayout {
+                        offset: 0,
+                        bytes_per_row: Some(4 * ATLAS_SIZE),
+                        rows_per_image: Some(ATLAS_SIZE),
+                    },
+                    wgpu::Extent3d {
+                        width: ATLAS_SIZE,
+                        height: ATLAS_SIZE,
+                        depth_or_array_layers: 1,
+                    },
+                );
             }
+        }
 
-            // Submit this shape's commands
-            self.queue.submit(std::iter::once(encoder.finish()));
+        let color = shape
+            .style
+            .fill
+            .as_ref()
+            .map_or(crate::scene::Color::black(), |fill| fill.primary_color())
+            .with_alpha_multiplied(shape.style.opacity);
+        layout_text(&self.glyph_atlas, content, *font_size, font_family, *align, color)
+    }
+
+    /// Draw textured quads with `model_transform` on top of what's already in `target`
+    fn draw_textured_over(
+        &self,
+        target: &wgpu::TextureView,
+        mesh: &TexturedMesh,
+        model_transform: [[f32; 4]; 4],
+        pipeline: &wgpu::RenderPipeline,
+        texture: &wgpu::BindGroup,
+    ) {
+        let uniforms = Uniforms::for_surface(self.width, self.height, self.pixel_ratio)
+            .with_model_transform(model_transform);
+        self.queue
+            .write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
+        self.queue
+            .write_buffer(&self.quad_vertex_buffer, 0, bytemuck::cast_slice(&mesh.vertices));
+        self.queue
+            .write_buffer(&self.quad_index_buffer, 0, bytemuck::cast_slice(&mesh.indices));
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Textured Encoder"),
+            });
+
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Textured Render Pass"),
+                color_attachments: &[Some(self.color_attachment(target, wgpu::LoadOp::Load))],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+
+            render_pass.set_pipeline(pipeline);
+            render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
+            render_pass.set_bind_group(1, texture, &[]);
+            render_pass.set_vertex_buffer(0, self.quad_vertex_buffer.slice(..));
+            render_pass.set_index_buffer(self.quad_index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+            render_pass.draw_indexed(0..mesh.indices.len() as u32, 0, 0..1);
         }
 
-        output.present();
+        self.queue.submit(std::iter::once(encoder.finish()));
+    }
 
-        Ok(())
+    /// Draw `mesh` like `draw_over`, in chunks that fit the buffers when it's too large for them
+    fn draw_mesh_over(&mut self, target: &wgpu::TextureView, mesh: &Mesh, model_transform: [[f32; 4]; 4]) {
+        // Snapping needs the final surface position, so the transform is applied here instead
+        let snapped;
+        let (mesh, model_transform) = if self.pixel_snap {
+            let mut placed = mesh.transformed(&model_transform);
+            placed.snap_to_pixels(self.pixel_ratio);
+            snapped = placed;
+            (&snapped, Uniforms::identity_matrix())
+        } else {
+            (mesh, model_transform)
+        };
+        if mesh.vertices.len() <= MAX_VERTICES && mesh.indices.len() <= MAX_INDICES {
+            self.draw_over(target, mesh, model_transform);
+            return;
+        }
+        // A chunk of n indices references at most n vertices
+        for chunk in mesh.chunks(MAX_VERTICES) {
+            self.draw_over(target, &chunk, model_transform);
+        }
+    }
+
+    /// Draw `mesh` with `model_transform` on top of what's already in `target`
+    /// Each draw needs its own submit because the uniform and geometry buffers are shared
+    fn draw_over(&mut self, target: &wgpu::TextureView, mesh: &Mesh, model_transform: [[f32; 4]; 4]) {
+        // Update buffers
+        let uniforms = Uniforms::for_surface(self.width, self.height, self.pixel_ratio)
+            .with_model_transform(model_transform);
+        self.queue
+            .write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
+        self.queue
+            .write_buffer(&self.vertex_buffer, 0, bytemuck::cast_slice(&mesh.vertices));
+        self.queue
+            .write_buffer(&self.index_buffer, 0, bytemuck::cast_slice(&mesh.indices));
+
+        // Create encoder and render pass for this mesh
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Shape Encoder"),
+            });
+
+        {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Shape Render Pass"),
+                color_attachments: &[Some(self.color_attachment(target, wgpu::LoadOp::Load))],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+
+            render_pass.set_pipeline(&self.pipelines.shape);
+            render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
+            render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+            render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+            render_pass.draw_indexed(0..mesh.indices.len() as u32, 0, 0..1);
+        }
+
+        self.queue.submit(std::iter::once(encoder.finish()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_surface_errors_map_to_recovery() {
+        assert_eq!(SurfaceRecovery::for_error(&wgpu::SurfaceError::Lost), SurfaceRecovery::Reconfigure);
+        assert_eq!(SurfaceRecovery::for_error(&wgpu::SurfaceError::Outdated), SurfaceRecovery::Reconfigure);
+        assert_eq!(SurfaceRecovery::for_error(&wgpu::SurfaceError::Timeout), SurfaceRecovery::SkipFrame);
+        assert_eq!(
+            SurfaceRecovery::for_error(&wgpu::SurfaceError::OutOfMemory),
+            SurfaceRecovery::Fail(RendererError::OutOfMemory)
+        );
+    }
+
+    /// Poll a future that never actually waits, as the mocked attempts here don't
+    fn run<F: std::future::Future>(future: F) -> F::Output {
+        let mut future = std::pin::pin!(future);
+        let mut context = std::task::Context::from_waker(std::task::Waker::noop());
+        match future.as_mut().poll(&mut context) {
+            std::task::Poll::Ready(output) => output,
+            std::task::Poll::Pending => panic!("mock attempt should complete immediately"),
+        }
+    }
+
+    #[test]
+    fn test_backend_fallback_prefers_webgpu() {
+        let mut tried = Vec::new();
+        let result = run(first_working_backend(&GpuBackend::PREFERENCE, |backend| {
+            tried.push(backend);
+            std::future::ready(Ok::<_, String>(backend.name()))
+        }));
+        assert_eq!(result, Ok((GpuBackend::WebGpu, "WebGPU")));
+        assert_eq!(tried, vec![GpuBackend::WebGpu]);
+    }
+
+    #[test]
+    fn test_backend_fallback_uses_webgl2_when_webgpu_fails() {
+        let mut tried = Vec::new();
+        let result = run(first_working_backend(&GpuBackend::PREFERENCE, |backend| {
+            tried.push(backend);
+            std::future::ready(match backend {
+                GpuBackend::WebGpu => Err("Failed to configure surface".to_string()),
+                GpuBackend::WebGl2 => Ok(()),
+            })
+        }));
+        assert_eq!(result, Ok((GpuBackend::WebGl2, ())));
+        assert_eq!(tried, GpuBackend::PREFERENCE.to_vec());
+    }
+
+    #[test]
+    fn test_backend_fallback_reports_every_failure() {
+        let result = run(first_working_backend(&GpuBackend::PREFERENCE, |backend| {
+            std::future::ready(Err::<(), _>(format!("no {} adapter", backend.name())))
+        }));
+        assert_eq!(result, Err("WebGPU: no WebGPU adapter; WebGL2: no WebGL2 adapter".to_string()));
+    }
+
+    #[test]
+    fn test_limits_differ_per_backend() {
+        let adapter = wgpu::Limits::default();
+        assert_eq!(GpuBackend::WebGl2.required_limits(adapter.clone()), wgpu::Limits::downlevel_webgl2_defaults());
+
+        let webgpu = GpuBackend::WebGpu.required_limits(adapter.clone());
+        assert_eq!(webgpu.max_texture_dimension_2d, adapter.max_texture_dimension_2d);
+        assert!(webgpu.max_storage_buffers_per_shader_stage > 0);
+    }
+
+    #[test]
+    fn test_meshes_draw_back_to_front() {
+        assert_eq!(back_to_front(&[0.0, 2.0, 1.0]), vec![1, 2, 0]);
+        // Ties keep their given order, and anything in front of the shapes goes last
+        assert_eq!(back_to_front(&[1.0, 1.0, -1.0, 3.0]), vec![3, 0, 1, 2]);
+        assert!(back_to_front(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_batches_split_oversized_scenes_and_keep_draw_order() {
+        // A 200,000-index mesh is more than one buffer load; with small meshes around it
+        let big = Mesh {
+            vertices: (0..200_000).map(|i| Vertex::new([i as f32, 0.0], [0.0, 0.0, 0.0, 1.0])).collect(),
+            indices: (0..200_000u32).collect::<Vec<_>>()[..199_998].to_vec(),
+        };
+        let small = Mesh {
+            vertices: vec![Vertex::new([1.0, 2.0], [1.0, 0.0, 0.0, 1.0]); 3],
+            indices: vec![0, 1, 2],
+        };
+        let empty = Mesh::new();
+
+        let batches = batch_meshes(&[&small, &big, &empty, &small], MAX_VERTICES, MAX_INDICES);
+        for (packed, ranges) in &batches {
+            assert!(packed.vertices.len() <= MAX_VERTICES && packed.indices.len() <= MAX_INDICES);
+            assert_eq!(ranges.last().unwrap().end as usize, packed.indices.len());
+        }
+
+        // Every triangle is drawn once, in the given order: first small, the big mesh, last small
+        let drawn: Vec<[f32; 2]> = batches
+            .iter()
+            .flat_map(|(packed, ranges)| {
+                ranges.iter().flat_map(move |range| packed.indices[range.start as usize..range.end as usize].iter().map(|&i| packed.vertices[i as usize].position))
+            })
+            .collect();
+        assert_eq!(drawn.len(), 3 + 199_998 + 3);
+        assert_eq!(drawn[..3], [[1.0, 2.0]; 3]);
+        assert!(drawn[3..3 + 199_998].iter().enumerate().all(|(i, p)| *p == [i as f32, 0.0]));
+        assert_eq!(drawn[3 + 199_998..], [[1.0, 2.0]; 3]);
+
+        // The big mesh splits into four chunks; the last small mesh fits in beside the last chunk
+        let draws: usize = batches.iter().map(|(_, ranges)| ranges.len()).sum();
+        assert_eq!(draws, 1 + 4 + 1);
+        assert_eq!(batches.len(), 5);
+        assert_eq!(batches[4].1.len(), 2);
+    }
+
+    #[test]
+    fn test_msaa_falls_back_to_one_sample() {
+        let flags = wgpu::TextureFormatFeatureFlags::MULTISAMPLE_X4 | wgpu::TextureFormatFeatureFlags::MULTISAMPLE_RESOLVE;
+        assert_eq!(sample_count(true, flags), MSAA_SAMPLES);
+        assert_eq!(sample_count(false, flags), 1);
+        assert_eq!(sample_count(true, wgpu::TextureFormatFeatureFlags::MULTISAMPLE_X2), 1);
+        assert_eq!(sample_count(true, wgpu::TextureFormatFeatureFlags::empty()), 1);
+    }
+
+    #[test]
+    fn test_failure_counter_trips_after_consecutive_failures() {
+        let mut counter = FailureCounter::default();
+        let failed: Result<(), RendererError> = Err(RendererError::SurfaceLost);
+
+        assert!(!counter.record(&failed));
+        assert!(!counter.record(&failed));
+        // A good frame in between starts the count over
+        assert!(!counter.record(&Ok(())));
+        assert!(!counter.record(&failed));
+        assert!(!counter.record(&failed));
+        assert!(counter.record(&failed));
     }
 }
@@ -1,154 +1,33 @@
 use crate::gpu::vertex::{Mesh, Vertex};
-use crate::scene::{Color, Shape, ShapeGeometry, Transform2D, Vec2};
+use crate::scene::{arc_to_cubics, BBox, Color, CornerRadii, DropShadow, Fill, FillRule, LineCap, LineJoin, Shape, ShapeGeometry, ShapeStyle, StrokeStyle, Transform2D, Vec2};
 use lyon::geom::point;
-use lyon::path::Path;
+use lyon::path::iterator::PathIterator;
+use lyon::path::{Path, PathEvent};
 use lyon::tessellation::{
     BuffersBuilder, FillOptions, FillTessellator, FillVertex, StrokeOptions, StrokeTessellator,
     StrokeVertex, VertexBuffers,
 };
-use std::collections::HashMap;
-
-/// Convert an SVG elliptical arc to cubic bezier curves
-/// Based on the SVG arc implementation algorithm
-fn arc_to_beziers(
-    from: Vec2,
-    rx: f32,
-    ry: f32,
-    x_rotation: f32,
-    large_arc: bool,
-    sweep: bool,
-    to: Vec2,
-) -> Vec<(Vec2, Vec2, Vec2)> {
-    // Handle degenerate cases
-    if from == to {
-        return vec![];
-    }
-
-    let mut rx = rx.abs();
-    let mut ry = ry.abs();
-
-    if rx == 0.0 || ry == 0.0 {
-        // Treat as line
-        return vec![];
-    }
-
-    let phi = x_rotation.to_radians();
-    let cos_phi = phi.cos();
-    let sin_phi = phi.sin();
-
-    // Step 1: Compute (x1', y1')
-    let dx = (from.x - to.x) / 2.0;
-    let dy = (from.y - to.y) / 2.0;
-    let x1_prime = cos_phi * dx + sin_phi * dy;
-    let y1_prime = -sin_phi * dx + cos_phi * dy;
-
-    // Step 2: Compute (cx', cy')
-    let rx_sq = rx * rx;
-    let ry_sq = ry * ry;
-    let x1_prime_sq = x1_prime * x1_prime;
-    let y1_prime_sq = y1_prime * y1_prime;
-
-    // Check if radii are large enough
-    let lambda = x1_prime_sq / rx_sq + y1_prime_sq / ry_sq;
-    if lambda > 1.0 {
-        let lambda_sqrt = lambda.sqrt();
-        rx *= lambda_sqrt;
-        ry *= lambda_sqrt;
-    }
-
-    let rx_sq = rx * rx;
-    let ry_sq = ry * ry;
-
-    let num = rx_sq * ry_sq - rx_sq * y1_prime_sq - ry_sq * x1_prime_sq;
-    let den = rx_sq * y1_prime_sq + ry_sq * x1_prime_sq;
-
-    let sq = if den == 0.0 { 0.0 } else { (num / den).max(0.0).sqrt() };
-    let sq = if large_arc == sweep { -sq } else { sq };
-
-    let cx_prime = sq * rx * y1_prime / ry;
-    let cy_prime = -sq * ry * x1_prime / rx;
-
-    // Step 3: Compute (cx, cy) from (cx', cy')
-    let cx = cos_phi * cx_prime - sin_phi * cy_prime + (from.x + to.x) / 2.0;
-    let cy = sin_phi * cx_prime + cos_phi * cy_prime + (from.y + to.y) / 2.0;
-
-    // Step 4: Compute theta1 and dtheta
-    fn angle(ux: f32, uy: f32, vx: f32, vy: f32) -> f32 {
-        let n = (ux * ux + uy * uy).sqrt() * (vx * vx + vy * vy).sqrt();
-        if n == 0.0 {
-            return 0.0;
-        }
-        let c = (ux * vx + uy * vy) / n;
-        let c = c.clamp(-1.0, 1.0);
-        let angle = c.acos();
-        if ux * vy - uy * vx < 0.0 { -angle } else { angle }
-    }
-
-    let theta1 = angle(1.0, 0.0, (x1_prime - cx_prime) / rx, (y1_prime - cy_prime) / ry);
-    let mut dtheta = angle(
-        (x1_prime - cx_prime) / rx,
-        (y1_prime - cy_prime) / ry,
-        (-x1_prime - cx_prime) / rx,
-        (-y1_prime - cy_prime) / ry,
-    );
-
-    if !sweep && dtheta > 0.0 {
-        dtheta -= 2.0 * std::f32::consts::PI;
-    } else if sweep && dtheta < 0.0 {
-        dtheta += 2.0 * std::f32::consts::PI;
-    }
-
-    // Convert arc to bezier curves
-    // Split into segments of at most 90 degrees
-    let num_segments = ((dtheta.abs() / (std::f32::consts::PI / 2.0)).ceil() as usize).max(1);
-    let segment_angle = dtheta / num_segments as f32;
-
-    let mut curves = Vec::new();
-    let mut current_theta = theta1;
-
-    for _ in 0..num_segments {
-        let theta2 = current_theta + segment_angle;
-
-        // Compute bezier control points for this arc segment
-        let t = (segment_angle / 4.0).tan();
-        let alpha = segment_angle.sin() * ((4.0 + 3.0 * t * t).sqrt() - 1.0) / 3.0;
-
-        let cos_t1 = current_theta.cos();
-        let sin_t1 = current_theta.sin();
-        let cos_t2 = theta2.cos();
-        let sin_t2 = theta2.sin();
-
-        // Points on the unit circle
-        let p1x = cos_t1;
-        let p1y = sin_t1;
-        let p2x = cos_t2;
-        let p2y = sin_t2;
-
-        // Control points
-        let c1x = p1x - alpha * sin_t1;
-        let c1y = p1y + alpha * cos_t1;
-        let c2x = p2x + alpha * sin_t2;
-        let c2y = p2y - alpha * cos_t2;
-
-        // Transform from unit circle to actual ellipse
-        fn transform_point(px: f32, py: f32, rx: f32, ry: f32, cos_phi: f32, sin_phi: f32, cx: f32, cy: f32) -> Vec2 {
-            let x = rx * px;
-            let y = ry * py;
-            Vec2::new(
-                cos_phi * x - sin_phi * y + cx,
-                sin_phi * x + cos_phi * y + cy,
-            )
-        }
-
-        let ctrl1 = transform_point(c1x, c1y, rx, ry, cos_phi, sin_phi, cx, cy);
-        let ctrl2 = transform_point(c2x, c2y, rx, ry, cos_phi, sin_phi, cx, cy);
-        let end = transform_point(p2x, p2y, rx, ry, cos_phi, sin_phi, cx, cy);
-
-        curves.push((ctrl1, ctrl2, end));
-        current_theta = theta2;
-    }
+use std::collections::{HashMap, VecDeque};
+
+/// A cached mesh and the shape content it was tessellated from
+struct CachedMesh {
+    geometry: ShapeGeometry,
+    style: ShapeStyle,
+    /// Scale and rotation the shadow offset was mapped through, when there's a shadow
+    shadow_frame: Option<(Vec2, f32)>,
+    /// The shape's dirty flag when last seen; only a shape marked dirty since then is redone,
+    /// so shapes that stay dirty between scene clears aren't re-tessellated every frame
+    dirty: bool,
+    mesh: Mesh,
+}
 
-    curves
+/// The part of a shape's transform a baked-in shadow depends on
+/// Shadows are offset in world units, so their local offset changes with scale and rotation
+fn shadow_frame(shape: &Shape) -> Option<(Vec2, f32)> {
+    shape
+        .style
+        .drop_shadow
+        .map(|_| (shape.transform.scale, shape.transform.rotation))
 }
 
 /// Tessellator for converting shapes to GPU-renderable triangles
@@ -157,7 +36,11 @@ pub struct Tessellator {
     fill_tessellator: FillTessellator,
     stroke_tessellator: StrokeTessellator,
     /// Cache of tessellated meshes by shape ID
-    mesh_cache: HashMap<u64, Mesh>,
+    mesh_cache: HashMap<u64, CachedMesh>,
+    /// Maximum distance between a curve and its flattened segments, in shape units
+    tolerance: f32,
+    /// Shapes tessellated into the cache so far; unchanged by frames served from it
+    shapes_tessellated: u64,
 }
 
 impl Default for Tessellator {
@@ -167,14 +50,53 @@ impl Default for Tessellator {
 }
 
 impl Tessellator {
+    /// Lyon's default curve tolerance, right for shapes shown at 100%
+    pub const DEFAULT_TOLERANCE: f32 = FillOptions::DEFAULT_TOLERANCE;
+    /// Bounds for zoom-derived tolerances, so extreme zooms don't explode or collapse curves
+    const MIN_TOLERANCE: f32 = 0.005;
+    const MAX_TOLERANCE: f32 = 1.0;
+
     pub fn new() -> Self {
         Self {
             fill_tessellator: FillTessellator::new(),
             stroke_tessellator: StrokeTessellator::new(),
             mesh_cache: HashMap::new(),
+            tolerance: Self::DEFAULT_TOLERANCE,
+            shapes_tessellated: 0,
         }
     }
 
+    pub fn with_tolerance(mut self, tolerance: f32) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn tolerance(&self) -> f32 {
+        self.tolerance
+    }
+
+    /// Change the curve tolerance; cached meshes are dropped when it changes
+    /// so every shape is re-tessellated at the new quality
+    pub fn set_tolerance(&mut self, tolerance: f32) {
+        if tolerance != self.tolerance {
+            self.tolerance = tolerance;
+            self.clear_cache();
+        }
+    }
+
+    /// Tolerance that keeps curves smooth on screen at `zoom`
+    /// Snapped to powers of two of the zoom, so wheel zooming only re-tessellates
+    /// when the zoom roughly doubles or halves
+    pub fn tolerance_for_zoom(zoom: f64) -> f32 {
+        let step = (zoom.max(f64::MIN_POSITIVE).log2().round() as i32).clamp(-16, 16);
+        (Self::DEFAULT_TOLERANCE / 2f32.powi(step)).clamp(Self::MIN_TOLERANCE, Self::MAX_TOLERANCE)
+    }
+
+    /// How many times `get_or_tessellate_shape` has had to tessellate rather than reuse a mesh
+    pub fn shapes_tessellated(&self) -> u64 {
+        self.shapes_tessellated
+    }
+
     /// Clear the mesh cache
     pub fn clear_cache(&mut self) {
         self.mesh_cache.clear();
@@ -185,20 +107,44 @@ impl Tessellator {
         self.mesh_cache.remove(&shape_id);
     }
 
+    /// Whether the cached mesh for `shape` is missing or out of date
+    /// A shape newly marked dirty is redone; beyond that, compares the geometry and style the
+    /// mesh was built from, so edits that didn't set the dirty flag are still picked up;
+    /// the transform isn't baked in
+    pub fn needs_tessellation(&self, shape: &Shape) -> bool {
+        match self.mesh_cache.get(&shape.id) {
+            Some(cached) => {
+                (shape.dirty && !cached.dirty)
+                    || cached.geometry != shape.geometry
+                    || cached.style != shape.style
+                    || cached.shadow_frame != shadow_frame(shape)
+            }
+            None => true,
+        }
+    }
+
     /// Get or create a cached mesh for a shape
-    /// Uses the shape's dirty flag to determine if re-tessellation is needed
     /// IMPORTANT: This tessellates with identity transform - the actual transform
     /// is applied in the shader via uniform
     pub fn get_or_tessellate_shape(&mut self, shape: &Shape) -> &Mesh {
-        let shape_id = shape.id;
-
-        // Check if we need to re-tessellate
-        if shape.dirty || !self.mesh_cache.contains_key(&shape_id) {
+        if self.needs_tessellation(shape) {
             let mesh = self.tessellate_shape_at_origin(shape);
-            self.mesh_cache.insert(shape_id, mesh);
+            self.shapes_tessellated += 1;
+            self.mesh_cache.insert(
+                shape.id,
+                CachedMesh {
+                    geometry: shape.geometry.clone(),
+                    style: shape.style.clone(),
+                    shadow_frame: shadow_frame(shape),
+                    dirty: shape.dirty,
+                    mesh,
+                },
+            );
+        } else if let Some(cached) = self.mesh_cache.get_mut(&shape.id) {
+            cached.dirty = shape.dirty;
         }
 
-        self.mesh_cache.get(&shape_id).unwrap()
+        &self.mesh_cache[&shape.id].mesh
     }
 
     /// Tessellate a shape at origin (without applying shape's transform)
@@ -207,20 +153,28 @@ impl Tessellator {
         let mut mesh = Mesh::new();
         let identity = Transform2D::identity();
 
+        // The shadow goes first so the shape draws over it
+        if let Some(shadow) = &shape.style.drop_shadow {
+            if let Some(shadow_mesh) = self.tessellate_shadow(shape, &identity, &shape.transform, shadow) {
+                mesh.extend(&shadow_mesh);
+            }
+        }
+
         // Tessellate fill if present
-        if let Some(fill_color) = shape.style.fill {
-            if let Some(fill_mesh) = self.tessellate_geometry_fill(&shape.geometry, &identity, fill_color) {
+        if let Some(fill) = &shape.style.fill {
+            let fill = fill.clone().with_alpha_multiplied(shape.style.opacity);
+            if let Some(fill_mesh) = self.tessellate_geometry_fill(&shape.geometry, &identity, &fill, shape.style.fill_rule) {
                 mesh.extend(&fill_mesh);
             }
         }
 
         // Tessellate stroke if present
-        if let Some(stroke) = shape.style.stroke {
+        if let Some(stroke) = &shape.style.stroke {
             if let Some(stroke_mesh) = self.tessellate_geometry_stroke(
                 &shape.geometry,
                 &identity,
-                stroke.color,
-                stroke.width,
+                stroke.color.with_alpha_multiplied(shape.style.opacity),
+                stroke,
             ) {
                 mesh.extend(&stroke_mesh);
             }
@@ -234,20 +188,28 @@ impl Tessellator {
     pub fn tessellate_shape(&mut self, shape: &Shape) -> Mesh {
         let mut mesh = Mesh::new();
 
+        if let Some(shadow) = &shape.style.drop_shadow {
+            let identity = Transform2D::identity();
+            if let Some(shadow_mesh) = self.tessellate_shadow(shape, &shape.transform, &identity, shadow) {
+                mesh.extend(&shadow_mesh);
+            }
+        }
+
         // Tessellate fill if present
-        if let Some(fill_color) = shape.style.fill {
-            if let Some(fill_mesh) = self.tessellate_geometry_fill(&shape.geometry, &shape.transform, fill_color) {
+        if let Some(fill) = &shape.style.fill {
+            let fill = fill.clone().with_alpha_multiplied(shape.style.opacity);
+            if let Some(fill_mesh) = self.tessellate_geometry_fill(&shape.geometry, &shape.transform, &fill, shape.style.fill_rule) {
                 mesh.extend(&fill_mesh);
             }
         }
 
         // Tessellate stroke if present
-        if let Some(stroke) = shape.style.stroke {
+        if let Some(stroke) = &shape.style.stroke {
             if let Some(stroke_mesh) = self.tessellate_geometry_stroke(
                 &shape.geometry,
                 &shape.transform,
-                stroke.color,
-                stroke.width,
+                stroke.color.with_alpha_multiplied(shape.style.opacity),
+                stroke,
             ) {
                 mesh.extend(&stroke_mesh);
             }
@@ -256,40 +218,147 @@ impl Tessellator {
         mesh
     }
 
+    /// Tessellate the shadow of `shape`: its silhouette in the shadow color, drawn once per
+    /// blur tap at that tap plus the shadow offset
+    /// `transform` places the silhouette like the shape's own mesh; `mesh_frame` is the
+    /// transform the mesh will be drawn with, used to turn the world offset into mesh units.
+    /// Text has no tessellated outline to cast a shadow from.
+    fn tessellate_shadow(
+        &mut self,
+        shape: &Shape,
+        transform: &Transform2D,
+        mesh_frame: &Transform2D,
+        shadow: &DropShadow,
+    ) -> Option<Mesh> {
+        let color = shadow.color.with_alpha_multiplied(shape.style.opacity);
+        let mut silhouette = Mesh::new();
+        let fill = match &shape.geometry {
+            ShapeGeometry::Image { width, height, .. } => {
+                self.tessellate_rectangle_fill(*width, *height, CornerRadii::default(), transform, color)
+            }
+            geometry if shape.style.fill.is_some() => {
+                self.tessellate_geometry_fill(geometry, transform, &Fill::Solid(color), shape.style.fill_rule)
+            }
+            _ => None,
+        };
+        if let Some(fill) = fill {
+            silhouette.extend(&fill);
+        }
+        if let Some(stroke) = &shape.style.stroke {
+            if let Some(stroke_mesh) = self.tessellate_geometry_stroke(&shape.geometry, transform, color, stroke) {
+                silhouette.extend(&stroke_mesh);
+            }
+        }
+        if silhouette.is_empty() {
+            return None;
+        }
+
+        // Too many copies of a detailed outline would overflow the vertex buffer
+        let mut taps = shadow_taps(shadow.blur);
+        if silhouette.vertices.len() * taps.len() > MAX_SHADOW_VERTICES {
+            taps = vec![Vec2::ZERO];
+        }
+        let tap_color = Color { a: blur_tap_alpha(color.a, taps.len()), ..color }.to_array();
+        let origin = mesh_frame.inverse_transform_point(mesh_frame.position);
+
+        let mut mesh = Mesh::with_capacity(silhouette.vertices.len() * taps.len(), silhouette.indices.len() * taps.len());
+        for tap in taps {
+            let offset = mesh_frame.inverse_transform_point(mesh_frame.position + shadow.offset + tap) - origin;
+            let mut copy = silhouette.clone();
+            for vertex in &mut copy.vertices {
+                vertex.position = [vertex.position[0] + offset.x, vertex.position[1] + offset.y];
+                vertex.color = tap_color;
+            }
+            mesh.extend(&copy);
+        }
+        Some(mesh)
+    }
+
     /// Tessellate multiple shapes into a single mesh (legacy method)
     /// For better performance, use get_or_tessellate_shape with per-shape rendering
     pub fn tessellate_shapes(&mut self, shapes: &[Shape]) -> Mesh {
         let mut mesh = Mesh::new();
-        for shape in shapes {
+        for shape in shapes.iter().filter(|shape| shape.visible) {
             let shape_mesh = self.tessellate_shape(shape);
             mesh.extend(&shape_mesh);
         }
         mesh
     }
 
+    /// Tessellate the selection border around `bbox` as a stroke of `width`
+    /// `bbox` is in the coordinates the mesh is drawn in, typically screen pixels
+    pub fn tessellate_selection_outline(&mut self, bbox: &BBox, color: Color, width: f32) -> Mesh {
+        let corners = rect_corners(bbox.min, bbox.max);
+        self.tessellate_polygon_stroke(&corners, &Transform2D::identity(), color, &StrokeStyle::new(color, width))
+            .unwrap_or_default()
+    }
+
+    /// Tessellate a square resize handle of side `size` centered on `center`
+    pub fn tessellate_handle(
+        &mut self,
+        center: Vec2,
+        size: f32,
+        fill: Color,
+        stroke: Color,
+        stroke_width: f32,
+    ) -> Mesh {
+        let half = Vec2::splat(size / 2.0);
+        let corners = rect_corners(center - half, center + half);
+        let identity = Transform2D::identity();
+
+        let mut mesh = Mesh::new();
+        if let Some(fill_mesh) = self.tessellate_polygon_fill(&corners, &identity, fill, FillRule::NonZero) {
+            mesh.extend(&fill_mesh);
+        }
+        if stroke_width > 0.0 {
+            let style = StrokeStyle::new(stroke, stroke_width);
+            if let Some(stroke_mesh) = self.tessellate_polygon_stroke(&corners, &identity, stroke, &style) {
+                mesh.extend(&stroke_mesh);
+            }
+        }
+        mesh
+    }
+
     /// Tessellate geometry fill
+    /// Gradient fills are subdivided and colored per vertex from the gradient.
+    /// `fill_rule` only matters for outlines that can overlap themselves: polygons and paths
     fn tessellate_geometry_fill(
         &mut self,
         geometry: &ShapeGeometry,
         transform: &Transform2D,
-        color: Color,
+        fill: &Fill,
+        fill_rule: FillRule,
     ) -> Option<Mesh> {
-        match geometry {
+        let color = fill.primary_color();
+        let mesh = match geometry {
             ShapeGeometry::Polygon { points } => {
-                self.tessellate_polygon_fill(points, transform, color)
+                self.tessellate_polygon_fill(points, transform, color, fill_rule)
+            }
+            ShapeGeometry::Star { .. } | ShapeGeometry::RegularPolygon { .. } => {
+                let points = geometry.ring_points()?;
+                self.tessellate_polygon_fill(&points, transform, color, fill_rule)
             }
             ShapeGeometry::Rectangle {
                 width,
                 height,
-                corner_radius,
-            } => self.tessellate_rectangle_fill(*width, *height, *corner_radius, transform, color),
+                corner_radii,
+            } => self.tessellate_rectangle_fill(*width, *height, *corner_radii, transform, color),
             ShapeGeometry::Ellipse { rx, ry } => {
                 self.tessellate_ellipse_fill(*rx, *ry, transform, color)
             }
             ShapeGeometry::Path { commands } => {
-                self.tessellate_path_fill(commands, transform, color)
+                self.tessellate_path_fill(commands, transform, color, fill_rule)
             }
+            // Lines have no interior to fill; text and images are drawn from textures
+            ShapeGeometry::Line { .. } | ShapeGeometry::Text { .. } | ShapeGeometry::Image { .. } => None,
+        }?;
+
+        if !fill.is_gradient() {
+            return Some(mesh);
         }
+        let mut mesh = subdivide_mesh(&mesh, GRADIENT_MAX_EDGE, GRADIENT_MAX_TRIANGLES);
+        shade_gradient(&mut mesh, fill, transform);
+        Some(mesh)
     }
 
     /// Tessellate geometry stroke
@@ -298,23 +367,72 @@ impl Tessellator {
         geometry: &ShapeGeometry,
         transform: &Transform2D,
         color: Color,
-        width: f32,
+        stroke: &StrokeStyle,
     ) -> Option<Mesh> {
         match geometry {
             ShapeGeometry::Polygon { points } => {
-                self.tessellate_polygon_stroke(points, transform, color, width)
+                self.tessellate_polygon_stroke(points, transform, color, stroke)
+            }
+            ShapeGeometry::Star { .. } | ShapeGeometry::RegularPolygon { .. } => {
+                let points = geometry.ring_points()?;
+                self.tessellate_polygon_stroke(&points, transform, color, stroke)
             }
             ShapeGeometry::Rectangle {
                 width: w,
                 height: h,
-                corner_radius,
-            } => self.tessellate_rectangle_stroke(*w, *h, *corner_radius, transform, color, width),
+                corner_radii,
+            } => self.tessellate_rectangle_stroke(*w, *h, *corner_radii, transform, color, stroke),
             ShapeGeometry::Ellipse { rx, ry } => {
-                self.tessellate_ellipse_stroke(*rx, *ry, transform, color, width)
+                self.tessellate_ellipse_stroke(*rx, *ry, transform, color, stroke)
             }
             ShapeGeometry::Path { commands } => {
-                self.tessellate_path_stroke(commands, transform, color, width)
+                self.tessellate_path_stroke(commands, transform, color, stroke)
+            }
+            ShapeGeometry::Line { start, end } => {
+                // Two-point open path
+                use crate::scene::PathCommand;
+                let commands = [PathCommand::MoveTo(*start), PathCommand::LineTo(*end)];
+                self.tessellate_path_stroke(&commands, transform, color, stroke)
+            }
+            ShapeGeometry::Text { .. } | ShapeGeometry::Image { .. } => None,
+        }
+    }
+
+    /// Stroke a built path, splitting it into dashes first when the stroke has a pattern
+    /// `color` is passed separately because it already has the shape opacity applied
+    fn stroke_path(&mut self, path: &Path, color: Color, stroke: &StrokeStyle) -> Option<Mesh> {
+        let dashed;
+        let path = match stroke.dash_pattern.as_deref() {
+            Some(pattern) => {
+                dashed = dash_path(path, pattern, stroke.dash_offset);
+                &dashed
             }
+            None => path,
+        };
+
+        let mut buffers: VertexBuffers<Vertex, u32> = VertexBuffers::new();
+        let color_arr = color.to_array();
+
+        let result = self.stroke_tessellator.tessellate_path(
+            path,
+            &StrokeOptions::default()
+                .with_tolerance(self.tolerance)
+                .with_line_width(stroke.width)
+                .with_line_cap(line_cap(stroke.line_cap))
+                .with_line_join(line_join(stroke.line_join)),
+            &mut BuffersBuilder::new(&mut buffers, |vertex: StrokeVertex| Vertex {
+                position: [vertex.position().x, vertex.position().y],
+                color: color_arr,
+            }),
+        );
+
+        if result.is_ok() && !buffers.vertices.is_empty() {
+            Some(Mesh {
+                vertices: buffers.vertices,
+                indices: buffers.indices,
+            })
+        } else {
+            None
         }
     }
 
@@ -324,6 +442,7 @@ impl Tessellator {
         points: &[Vec2],
         transform: &Transform2D,
         color: Color,
+        rule: FillRule,
     ) -> Option<Mesh> {
         if points.len() < 3 {
             return None;
@@ -346,7 +465,7 @@ impl Tessellator {
 
         let result = self.fill_tessellator.tessellate_path(
             &path,
-            &FillOptions::default(),
+            &FillOptions::default().with_tolerance(self.tolerance).with_fill_rule(fill_rule(rule)),
             &mut BuffersBuilder::new(&mut buffers, |vertex: FillVertex| Vertex {
                 position: [vertex.position().x, vertex.position().y],
                 color: color_arr,
@@ -369,7 +488,7 @@ impl Tessellator {
         points: &[Vec2],
         transform: &Transform2D,
         color: Color,
-        width: f32,
+        stroke: &StrokeStyle,
     ) -> Option<Mesh> {
         if points.len() < 2 {
             return None;
@@ -386,109 +505,29 @@ impl Tessellator {
         builder.close();
         let path = builder.build();
 
-        // Tessellate stroke
-        let mut buffers: VertexBuffers<Vertex, u32> = VertexBuffers::new();
-        let color_arr = color.to_array();
-
-        let result = self.stroke_tessellator.tessellate_path(
-            &path,
-            &StrokeOptions::default().with_line_width(width),
-            &mut BuffersBuilder::new(&mut buffers, |vertex: StrokeVertex| Vertex {
-                position: [vertex.position().x, vertex.position().y],
-                color: color_arr,
-            }),
-        );
-
-        if result.is_ok() {
-            Some(Mesh {
-                vertices: buffers.vertices,
-                indices: buffers.indices,
-            })
-        } else {
-            None
-        }
+        self.stroke_path(&path, color, stroke)
     }
 
-    /// Tessellate a rectangle fill
+    /// Tessellate a rectangle fill; rounded corners go through the path tessellator
     fn tessellate_rectangle_fill(
         &mut self,
         width: f32,
         height: f32,
-        corner_radius: f32,
+        radii: CornerRadii,
         transform: &Transform2D,
         color: Color,
     ) -> Option<Mesh> {
-        let mut builder = Path::builder();
-
-        if corner_radius <= 0.0 {
-            // Simple rectangle
-            let corners = [
-                Vec2::new(0.0, 0.0),
-                Vec2::new(width, 0.0),
-                Vec2::new(width, height),
-                Vec2::new(0.0, height),
-            ];
-            let first = transform.transform_point(corners[0]);
-            builder.begin(point(first.x, first.y));
-            for corner in &corners[1..] {
-                let transformed = transform.transform_point(*corner);
-                builder.line_to(point(transformed.x, transformed.y));
-            }
-            builder.close();
-        } else {
-            // Rounded rectangle
-            let r = corner_radius.min(width / 2.0).min(height / 2.0);
-
-            // Start at top-left after curve
-            let start = transform.transform_point(Vec2::new(r, 0.0));
-            builder.begin(point(start.x, start.y));
-
-            // Top edge to top-right corner
-            let p = transform.transform_point(Vec2::new(width - r, 0.0));
-            builder.line_to(point(p.x, p.y));
-
-            // Top-right corner (approximate arc with quadratic)
-            let ctrl = transform.transform_point(Vec2::new(width, 0.0));
-            let end = transform.transform_point(Vec2::new(width, r));
-            builder.quadratic_bezier_to(point(ctrl.x, ctrl.y), point(end.x, end.y));
-
-            // Right edge to bottom-right corner
-            let p = transform.transform_point(Vec2::new(width, height - r));
-            builder.line_to(point(p.x, p.y));
-
-            // Bottom-right corner
-            let ctrl = transform.transform_point(Vec2::new(width, height));
-            let end = transform.transform_point(Vec2::new(width - r, height));
-            builder.quadratic_bezier_to(point(ctrl.x, ctrl.y), point(end.x, end.y));
-
-            // Bottom edge to bottom-left corner
-            let p = transform.transform_point(Vec2::new(r, height));
-            builder.line_to(point(p.x, p.y));
-
-            // Bottom-left corner
-            let ctrl = transform.transform_point(Vec2::new(0.0, height));
-            let end = transform.transform_point(Vec2::new(0.0, height - r));
-            builder.quadratic_bezier_to(point(ctrl.x, ctrl.y), point(end.x, end.y));
-
-            // Left edge to top-left corner
-            let p = transform.transform_point(Vec2::new(0.0, r));
-            builder.line_to(point(p.x, p.y));
-
-            // Top-left corner
-            let ctrl = transform.transform_point(Vec2::new(0.0, 0.0));
-            let end = transform.transform_point(Vec2::new(r, 0.0));
-            builder.quadratic_bezier_to(point(ctrl.x, ctrl.y), point(end.x, end.y));
-
-            builder.close();
+        if !radii.clamped(width, height).is_zero() {
+            return self.tessellate_path_fill(&radii.outline(width, height), transform, color, FillRule::NonZero);
         }
 
-        let path = builder.build();
+        let path = rectangle_path(width, height, transform);
         let mut buffers: VertexBuffers<Vertex, u32> = VertexBuffers::new();
         let color_arr = color.to_array();
 
         let result = self.fill_tessellator.tessellate_path(
             &path,
-            &FillOptions::default(),
+            &FillOptions::default().with_tolerance(self.tolerance),
             &mut BuffersBuilder::new(&mut buffers, |vertex: FillVertex| Vertex {
                 position: [vertex.position().x, vertex.position().y],
                 color: color_arr,
@@ -510,81 +549,17 @@ impl Tessellator {
         &mut self,
         width: f32,
         height: f32,
-        corner_radius: f32,
+        radii: CornerRadii,
         transform: &Transform2D,
         color: Color,
-        stroke_width: f32,
+        stroke: &StrokeStyle,
     ) -> Option<Mesh> {
-        // Reuse fill path building logic
-        let mut builder = Path::builder();
-
-        if corner_radius <= 0.0 {
-            let corners = [
-                Vec2::new(0.0, 0.0),
-                Vec2::new(width, 0.0),
-                Vec2::new(width, height),
-                Vec2::new(0.0, height),
-            ];
-            let first = transform.transform_point(corners[0]);
-            builder.begin(point(first.x, first.y));
-            for corner in &corners[1..] {
-                let transformed = transform.transform_point(*corner);
-                builder.line_to(point(transformed.x, transformed.y));
-            }
-            builder.close();
-        } else {
-            let r = corner_radius.min(width / 2.0).min(height / 2.0);
-            let start = transform.transform_point(Vec2::new(r, 0.0));
-            builder.begin(point(start.x, start.y));
-
-            let p = transform.transform_point(Vec2::new(width - r, 0.0));
-            builder.line_to(point(p.x, p.y));
-            let ctrl = transform.transform_point(Vec2::new(width, 0.0));
-            let end = transform.transform_point(Vec2::new(width, r));
-            builder.quadratic_bezier_to(point(ctrl.x, ctrl.y), point(end.x, end.y));
-
-            let p = transform.transform_point(Vec2::new(width, height - r));
-            builder.line_to(point(p.x, p.y));
-            let ctrl = transform.transform_point(Vec2::new(width, height));
-            let end = transform.transform_point(Vec2::new(width - r, height));
-            builder.quadratic_bezier_to(point(ctrl.x, ctrl.y), point(end.x, end.y));
-
-            let p = transform.transform_point(Vec2::new(r, height));
-            builder.line_to(point(p.x, p.y));
-            let ctrl = transform.transform_point(Vec2::new(0.0, height));
-            let end = transform.transform_point(Vec2::new(0.0, height - r));
-            builder.quadratic_bezier_to(point(ctrl.x, ctrl.y), point(end.x, end.y));
-
-            let p = transform.transform_point(Vec2::new(0.0, r));
-            builder.line_to(point(p.x, p.y));
-            let ctrl = transform.transform_point(Vec2::new(0.0, 0.0));
-            let end = transform.transform_point(Vec2::new(r, 0.0));
-            builder.quadratic_bezier_to(point(ctrl.x, ctrl.y), point(end.x, end.y));
-
-            builder.close();
+        if !radii.clamped(width, height).is_zero() {
+            return self.tessellate_path_stroke(&radii.outline(width, height), transform, color, stroke);
         }
 
-        let path = builder.build();
-        let mut buffers: VertexBuffers<Vertex, u32> = VertexBuffers::new();
-        let color_arr = color.to_array();
-
-        let result = self.stroke_tessellator.tessellate_path(
-            &path,
-            &StrokeOptions::default().with_line_width(stroke_width),
-            &mut BuffersBuilder::new(&mut buffers, |vertex: StrokeVertex| Vertex {
-                position: [vertex.position().x, vertex.position().y],
-                color: color_arr,
-            }),
-        );
-
-        if result.is_ok() {
-            Some(Mesh {
-                vertices: buffers.vertices,
-                indices: buffers.indices,
-            })
-        } else {
-            None
-        }
+        let path = rectangle_path(width, height, transform);
+        self.stroke_path(&path, color, stroke)
     }
 
     /// Tessellate an ellipse fill
@@ -655,7 +630,7 @@ impl Tessellator {
 
         let result = self.fill_tessellator.tessellate_path(
             &path,
-            &FillOptions::default(),
+            &FillOptions::default().with_tolerance(self.tolerance),
             &mut BuffersBuilder::new(&mut buffers, |vertex: FillVertex| Vertex {
                 position: [vertex.position().x, vertex.position().y],
                 color: color_arr,
@@ -679,7 +654,7 @@ impl Tessellator {
         ry: f32,
         transform: &Transform2D,
         color: Color,
-        width: f32,
+        stroke: &StrokeStyle,
     ) -> Option<Mesh> {
         let k = 0.5522847498;
         let kx = rx * k;
@@ -729,26 +704,7 @@ impl Tessellator {
         builder.close();
         let path = builder.build();
 
-        let mut buffers: VertexBuffers<Vertex, u32> = VertexBuffers::new();
-        let color_arr = color.to_array();
-
-        let result = self.stroke_tessellator.tessellate_path(
-            &path,
-            &StrokeOptions::default().with_line_width(width),
-            &mut BuffersBuilder::new(&mut buffers, |vertex: StrokeVertex| Vertex {
-                position: [vertex.position().x, vertex.position().y],
-                color: color_arr,
-            }),
-        );
-
-        if result.is_ok() {
-            Some(Mesh {
-                vertices: buffers.vertices,
-                indices: buffers.indices,
-            })
-        } else {
-            None
-        }
+        self.stroke_path(&path, color, stroke)
     }
 
     /// Tessellate a path fill
@@ -757,6 +713,7 @@ impl Tessellator {
         commands: &[crate::scene::PathCommand],
         transform: &Transform2D,
         color: Color,
+        rule: FillRule,
     ) -> Option<Mesh> {
         use crate::scene::PathCommand;
 
@@ -810,7 +767,7 @@ impl Tessellator {
                 PathCommand::ArcTo { rx, ry, x_rotation, large_arc, sweep, to } => {
                     if started {
                         // Convert arc to bezier curves
-                        let beziers = arc_to_beziers(current_pos, *rx, *ry, *x_rotation, *large_arc, *sweep, *to);
+                        let beziers = arc_to_cubics(current_pos, *rx, *ry, *x_rotation, *large_arc, *sweep, *to);
                         if beziers.is_empty() {
                             // Degenerate arc, draw line instead
                             let tp = transform.transform_point(*to);
@@ -849,7 +806,7 @@ impl Tessellator {
 
         let result = self.fill_tessellator.tessellate_path(
             &path,
-            &FillOptions::default(),
+            &FillOptions::default().with_tolerance(self.tolerance).with_fill_rule(fill_rule(rule)),
             &mut BuffersBuilder::new(&mut buffers, |vertex: FillVertex| Vertex {
                 position: [vertex.position().x, vertex.position().y],
                 color: color_arr,
@@ -872,7 +829,7 @@ impl Tessellator {
         commands: &[crate::scene::PathCommand],
         transform: &Transform2D,
         color: Color,
-        width: f32,
+        stroke: &StrokeStyle,
     ) -> Option<Mesh> {
         use crate::scene::PathCommand;
 
@@ -926,7 +883,7 @@ impl Tessellator {
                 PathCommand::ArcTo { rx, ry, x_rotation, large_arc, sweep, to } => {
                     if started {
                         // Convert arc to bezier curves
-                        let beziers = arc_to_beziers(current_pos, *rx, *ry, *x_rotation, *large_arc, *sweep, *to);
+                        let beziers = arc_to_cubics(current_pos, *rx, *ry, *x_rotation, *large_arc, *sweep, *to);
                         if beziers.is_empty() {
                             // Degenerate arc, draw line instead
                             let tp = transform.transform_point(*to);
@@ -960,33 +917,329 @@ impl Tessellator {
         }
 
         let path = builder.build();
-        let mut buffers: VertexBuffers<Vertex, u32> = VertexBuffers::new();
-        let color_arr = color.to_array();
+        self.stroke_path(&path, color, stroke)
+    }
+}
 
-        let result = self.stroke_tessellator.tessellate_path(
-            &path,
-            &StrokeOptions::default().with_line_width(width),
-            &mut BuffersBuilder::new(&mut buffers, |vertex: StrokeVertex| Vertex {
-                position: [vertex.position().x, vertex.position().y],
-                color: color_arr,
-            }),
-        );
+/// Nearest pixel center to `v`, i.e. the closest value ending in .5
+/// A 1px line centered there covers exactly one row or column of pixels instead of
+/// blurring across two
+pub fn round_to_half_pixel(v: f32) -> f32 {
+    v.floor() + 0.5
+}
 
-        if result.is_ok() && !buffers.vertices.is_empty() {
-            Some(Mesh {
-                vertices: buffers.vertices,
-                indices: buffers.indices,
-            })
-        } else {
-            None
+/// Corners of an axis-aligned rectangle, clockwise from `min`
+fn rect_corners(min: Vec2, max: Vec2) -> [Vec2; 4] {
+    [min, Vec2::new(max.x, min.y), max, Vec2::new(min.x, max.y)]
+}
+
+/// Closed path around a sharp-cornered `width` × `height` rectangle from the local origin
+fn rectangle_path(width: f32, height: f32, transform: &Transform2D) -> Path {
+    let mut builder = Path::builder();
+    let [first, rest @ ..] = rect_corners(Vec2::ZERO, Vec2::new(width, height)).map(|corner| transform.transform_point(corner));
+    builder.begin(point(first.x, first.y));
+    for corner in rest {
+        builder.line_to(point(corner.x, corner.y));
+    }
+    builder.close();
+    builder.build()
+}
+
+fn fill_rule(rule: FillRule) -> lyon::tessellation::FillRule {
+    match rule {
+        FillRule::NonZero => lyon::tessellation::FillRule::NonZero,
+        FillRule::EvenOdd => lyon::tessellation::FillRule::EvenOdd,
+    }
+}
+
+fn line_cap(cap: LineCap) -> lyon::tessellation::LineCap {
+    match cap {
+        LineCap::Butt => lyon::tessellation::LineCap::Butt,
+        LineCap::Round => lyon::tessellation::LineCap::Round,
+        LineCap::Square => lyon::tessellation::LineCap::Square,
+    }
+}
+
+fn line_join(join: LineJoin) -> lyon::tessellation::LineJoin {
+    match join {
+        LineJoin::Miter => lyon::tessellation::LineJoin::Miter,
+        LineJoin::Round => lyon::tessellation::LineJoin::Round,
+        LineJoin::Bevel => lyon::tessellation::LineJoin::Bevel,
+    }
+}
+
+/// Flattening tolerance used when splitting curves into dashes
+const DASH_TOLERANCE: f32 = 0.1;
+
+/// Split a path into dash sub-paths following an SVG-style dash array
+///
+/// The pattern restarts at each sub-path, shifted by `offset` like `stroke-dashoffset`,
+/// and odd-length patterns are repeated to make an even number of entries, matching
+/// `stroke-dasharray`. On closed sub-paths a dash running over the seam is joined with
+/// the dash that opens the sub-path, so the corner there gets a join rather than two caps.
+fn dash_path(path: &Path, pattern: &[f32], offset: f32) -> Path {
+    let mut pattern = pattern.to_vec();
+    if pattern.len() % 2 == 1 {
+        pattern.extend_from_within(..);
+    }
+    let period: f32 = pattern.iter().sum();
+    if pattern.iter().any(|v| *v < 0.0) || period <= 0.0 || !offset.is_finite() {
+        return path.clone();
+    }
+
+    let mut dasher = Dasher {
+        pattern: &pattern,
+        phase: offset.rem_euclid(period),
+        index: 0,
+        remaining: pattern[0],
+        dashes: Vec::new(),
+        in_dash: false,
+        starts_in_dash: false,
+    };
+    let mut builder = Path::builder();
+    for event in path.iter().flattened(DASH_TOLERANCE) {
+        match event {
+            PathEvent::Begin { .. } => dasher.restart(),
+            PathEvent::Line { from, to } => dasher.line(from, to),
+            PathEvent::End { last, first, close } => {
+                if close {
+                    dasher.line(last, first);
+                }
+                dasher.finish(close, &mut builder);
+            }
+            // Flattening only yields line segments
+            _ => {}
+        }
+    }
+    builder.build()
+}
+
+/// Walks line segments of one sub-path at a time, collecting the "on" parts of a dash pattern
+struct Dasher<'a> {
+    pattern: &'a [f32],
+    /// Distance into the pattern where each sub-path starts
+    phase: f32,
+    index: usize,
+    remaining: f32,
+    /// Dashes of the current sub-path as polylines
+    dashes: Vec<Vec<lyon::math::Point>>,
+    in_dash: bool,
+    /// Whether the first dash begins exactly at the sub-path start
+    starts_in_dash: bool,
+}
+
+impl Dasher<'_> {
+    fn restart(&mut self) {
+        self.dashes.clear();
+        self.in_dash = false;
+        self.index = 0;
+        self.remaining = self.pattern[0];
+
+        // Skip ahead by the phase; only whole entries are consumed here
+        let mut phase = self.phase;
+        while phase >= self.remaining {
+            phase -= self.remaining;
+            self.index = (self.index + 1) % self.pattern.len();
+            self.remaining = self.pattern[self.index];
+        }
+        self.remaining -= phase;
+        self.starts_in_dash = self.index & 1 == 0 && self.remaining > 0.0;
+    }
+
+    fn line(&mut self, from: lyon::math::Point, to: lyon::math::Point) {
+        let length = (to - from).length();
+        let mut travelled = 0.0;
+        while travelled < length {
+            let step = self.remaining.min(length - travelled);
+            // Even entries are dashes, odd entries are gaps
+            if self.index & 1 == 0 && step > 0.0 {
+                if !self.in_dash {
+                    self.dashes.push(vec![from.lerp(to, travelled / length)]);
+                    self.in_dash = true;
+                }
+                if let Some(dash) = self.dashes.last_mut() {
+                    dash.push(from.lerp(to, (travelled + step) / length));
+                }
+            }
+            travelled += step;
+            self.remaining -= step;
+            if self.remaining <= 0.0 {
+                self.in_dash = false;
+                self.index = (self.index + 1) % self.pattern.len();
+                self.remaining = self.pattern[self.index];
+            }
+        }
+    }
+
+    /// Emit the sub-path's dashes, merging across the seam of a closed sub-path
+    fn finish(&mut self, close: bool, builder: &mut lyon::path::path::Builder) {
+        if close && self.in_dash && self.starts_in_dash && self.dashes.len() > 1 {
+            let mut seam = self.dashes.pop().unwrap_or_default();
+            seam.extend(self.dashes[0].iter().skip(1));
+            self.dashes[0] = seam;
+        }
+        for dash in self.dashes.drain(..) {
+            let mut points = dash.into_iter();
+            if let Some(start) = points.next() {
+                builder.begin(start);
+                for point in points {
+                    builder.line_to(point);
+                }
+                builder.end(false);
+            }
+        }
+        self.in_dash = false;
+    }
+}
+
+/// Longest triangle edge in a gradient fill; colors are interpolated linearly along edges
+/// Most vertices a shadow may add to a shape's mesh before its blur is dropped
+const MAX_SHADOW_VERTICES: usize = 16_384;
+
+/// Offsets a blurred shadow's silhouette is drawn at: the center plus two rings,
+/// out to the blur radius, so the copies fade out towards the edge like a Gaussian
+fn shadow_taps(blur: f32) -> Vec<Vec2> {
+    let mut taps = vec![Vec2::ZERO];
+    if blur <= 0.0 {
+        return taps;
+    }
+    for ring in [blur / 3.0, blur * 2.0 / 3.0] {
+        for step in 0..8 {
+            let angle = step as f32 * std::f32::consts::FRAC_PI_4;
+            taps.push(Vec2::new(angle.cos(), angle.sin()) * ring);
+        }
+    }
+    taps
+}
+
+/// Alpha for each of `taps` overlapping copies so that where all of them overlap,
+/// alpha blending builds back up to `alpha`
+fn blur_tap_alpha(alpha: f32, taps: usize) -> f32 {
+    let alpha = alpha.clamp(0.0, 1.0);
+    if taps <= 1 || alpha >= 1.0 {
+        return alpha;
+    }
+    1.0 - (1.0 - alpha).powf(1.0 / taps as f32)
+}
+
+const GRADIENT_MAX_EDGE: f32 = 8.0;
+/// Cap on triangles produced for one gradient fill, well inside the renderer's vertex limit
+const GRADIENT_MAX_TRIANGLES: usize = 16_384;
+
+/// Split triangles at the midpoint of their longest edge until no edge is longer than
+/// `max_edge` (or the triangle budget runs out), so per-vertex colors can follow a gradient
+/// Triangles are split breadth-first, so a tight budget still refines the whole mesh evenly
+fn subdivide_mesh(mesh: &Mesh, max_edge: f32, max_triangles: usize) -> Mesh {
+    let position = |index: &u32| {
+        let [x, y] = mesh.vertices[*index as usize].position;
+        Vec2::new(x, y)
+    };
+    let mut pending: VecDeque<[Vec2; 3]> = mesh
+        .indices
+        .chunks_exact(3)
+        .map(|tri| [position(&tri[0]), position(&tri[1]), position(&tri[2])])
+        .collect();
+    let mut done: Vec<[Vec2; 3]> = Vec::new();
+
+    while let Some(tri) = pending.pop_front() {
+        let (longest, length) = (0..3)
+            .map(|i| (i, tri[i].distance(tri[(i + 1) % 3])))
+            .fold((0, 0.0), |best, edge| if edge.1 > best.1 { edge } else { best });
+        if length <= max_edge || done.len() + pending.len() + 2 > max_triangles {
+            done.push(tri);
+            continue;
+        }
+        // Keep the winding: (a, b, c) becomes (a, mid, c) and (mid, b, c)
+        let (a, b, c) = (tri[longest], tri[(longest + 1) % 3], tri[(longest + 2) % 3]);
+        let mid = (a + b) / 2.0;
+        pending.push_back([a, mid, c]);
+        pending.push_back([mid, b, c]);
+    }
+
+    let mut out = Mesh::with_capacity(done.len() * 3, done.len() * 3);
+    for tri in done {
+        for p in tri {
+            out.indices.push(out.vertices.len() as u32);
+            out.vertices.push(Vertex::new([p.x, p.y], [0.0; 4]));
         }
     }
+    out
+}
+
+/// Color each vertex from the gradient, sampled at its position in the shape's local space
+fn shade_gradient(mesh: &mut Mesh, fill: &Fill, transform: &Transform2D) {
+    let to_local = transform.to_matrix().inverse();
+    for vertex in &mut mesh.vertices {
+        let [x, y] = vertex.position;
+        let local = to_local.transform_point3(glam::Vec3::new(x, y, 0.0)).truncate();
+        vertex.color = fill.color_at(local).to_array();
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::scene::{ShapeStyle, StrokeStyle};
+    use crate::scene::PieSlice;
+
+    #[test]
+    fn test_shadow_is_drawn_first_and_offset_in_world_units() {
+        let mut tessellator = Tessellator::new();
+        let shadow = DropShadow::new(Vec2::new(10.0, 6.0), 0.0, Color::new(0.0, 0.0, 0.0, 0.5));
+        let plain = Shape::new(ShapeGeometry::rectangle(100.0, 50.0), ShapeStyle::fill_only(Color::white()))
+            .with_transform(Transform2D::from_position(Vec2::new(20.0, 20.0)).with_scale(Vec2::splat(2.0)));
+        let mut shadowed = plain.clone();
+        shadowed.style = shadowed.style.clone().with_drop_shadow(Some(shadow));
+
+        let fill = tessellator.get_or_tessellate_shape(&plain).clone();
+        let mesh = tessellator.get_or_tessellate_shape(&shadowed).clone();
+        // A hard shadow is one copy of the silhouette, ahead of the shape's own fill
+        assert_eq!(mesh.vertices.len(), fill.vertices.len() * 2);
+        let (shadow_part, fill_part) = mesh.vertices.split_at(fill.vertices.len());
+        assert!(fill_part.iter().zip(&fill.vertices).all(|(a, b)| a.position == b.position && a.color == b.color));
+        assert!(shadow_part.iter().all(|v| v.color == [0.0, 0.0, 0.0, 0.5]));
+
+        // The mesh is drawn at scale 2, so the world offset is halved in mesh units
+        for (shadow_vertex, fill_vertex) in shadow_part.iter().zip(&fill.vertices) {
+            assert!((shadow_vertex.position[0] - fill_vertex.position[0] - 5.0).abs() < 1e-4);
+            assert!((shadow_vertex.position[1] - fill_vertex.position[1] - 3.0).abs() < 1e-4);
+        }
+
+        // Rescaling the shape re-bakes the shadow even though geometry and style are the same
+        shadowed.transform.scale = Vec2::ONE;
+        assert!(tessellator.needs_tessellation(&shadowed));
+    }
+
+    #[test]
+    fn test_blurred_shadow_taps_build_back_to_the_shadow_alpha() {
+        assert_eq!(shadow_taps(0.0), vec![Vec2::ZERO]);
+        let taps = shadow_taps(9.0);
+        assert_eq!(taps.len(), 17);
+        assert!(taps.iter().all(|tap| tap.length() <= 6.0 + 1e-4));
+
+        // Where every copy overlaps, alpha blending adds back up to the shadow's alpha
+        let per_tap = blur_tap_alpha(0.4, taps.len());
+        let combined = 1.0 - (1.0 - per_tap).powi(taps.len() as i32);
+        assert!((combined - 0.4).abs() < 1e-4);
+        assert_eq!(blur_tap_alpha(0.4, 1), 0.4);
+        assert_eq!(blur_tap_alpha(1.0, 17), 1.0);
+    }
+
+    #[test]
+    fn test_shadow_of_an_unfilled_shape_follows_its_stroke() {
+        let mut tessellator = Tessellator::new();
+        let style = ShapeStyle::stroke_only(StrokeStyle::new(Color::black(), 2.0));
+        let line = |style: ShapeStyle| Shape::new(ShapeGeometry::line(Vec2::ZERO, Vec2::new(50.0, 0.0)), style);
+        let stroke_only = tessellator.tessellate_shape(&line(style.clone()));
+        let mesh = tessellator.tessellate_shape(&line(style.with_drop_shadow(Some(DropShadow::default()))));
+        assert_eq!(mesh.vertices.len(), stroke_only.vertices.len() * (1 + shadow_taps(DropShadow::default().blur).len()));
+
+        // Text has no outline to cast a shadow from
+        let text = Shape::new(
+            ShapeGeometry::text("Hi", 24.0),
+            ShapeStyle::fill_only(Color::black()).with_drop_shadow(Some(DropShadow::default())),
+        );
+        assert!(tessellator.tessellate_shape(&text).is_empty());
+    }
 
     #[test]
     fn test_tessellate_triangle() {
@@ -1006,6 +1259,22 @@ mod tests {
         assert_eq!(mesh.indices.len() % 3, 0); // Should be triangles
     }
 
+    #[test]
+    fn test_star_and_regular_polygon_tessellate_as_their_vertex_ring() {
+        let mut tessellator = Tessellator::new();
+        let style = ShapeStyle::fill_and_stroke(Color::white(), StrokeStyle::new(Color::black(), 2.0));
+        for geometry in [ShapeGeometry::star(5, 50.0, 20.0), ShapeGeometry::regular_polygon(6, 40.0)] {
+            let ring = ShapeGeometry::polygon(geometry.ring_points().unwrap());
+            let parametric = tessellator.tessellate_shape(&Shape::new(geometry, style.clone()));
+            let polygon = tessellator.tessellate_shape(&Shape::new(ring, style.clone()));
+            assert!(!parametric.indices.is_empty());
+            assert_eq!(parametric.indices, polygon.indices);
+            assert!(parametric.vertices.iter().zip(&polygon.vertices).all(|(a, b)| a.position == b.position));
+        }
+        // Too few points for an outline draws nothing rather than panicking
+        assert!(tessellator.tessellate_shape(&Shape::new(ShapeGeometry::star(0, 50.0, 20.0), style)).vertices.is_empty());
+    }
+
     #[test]
     fn test_tessellate_rectangle() {
         let mut tessellator = Tessellator::new();
@@ -1019,6 +1288,35 @@ mod tests {
         assert!(!mesh.indices.is_empty());
     }
 
+    #[test]
+    fn test_rounded_corners_trim_the_rectangle_fill() {
+        let mut tessellator = Tessellator::new();
+        let style = ShapeStyle::fill_only(Color::black());
+        let area = |tessellator: &mut Tessellator, radii: CornerRadii| {
+            mesh_area(&tessellator.tessellate_shape(&Shape::new(ShapeGeometry::rectangle_with_radii(100.0, 50.0, radii), style.clone())))
+        };
+
+        assert!((area(&mut tessellator, CornerRadii::default()) - 5000.0).abs() < 0.5);
+        // One corner of radius 20 removes about 20² (1 - π/4) ≈ 86; flattening the arcs
+        // into segments shaves off a little more
+        let one_corner = area(&mut tessellator, CornerRadii::new(20.0, 0.0, 0.0, 0.0));
+        assert!((one_corner - (5000.0 - 400.0 * (1.0 - std::f32::consts::FRAC_PI_4))).abs() < 5.0);
+        // Radii far too large are clamped into a stadium rather than overlapping
+        let stadium = area(&mut tessellator, CornerRadii::uniform(1000.0));
+        assert!((stadium - (50.0 * 50.0 + std::f32::consts::PI * 25.0 * 25.0)).abs() < 15.0);
+    }
+
+    #[test]
+    fn test_zero_size_rounded_rectangle_does_not_panic() {
+        let mut tessellator = Tessellator::new();
+        let shape = Shape::new(
+            ShapeGeometry::rounded_rectangle(0.0, 0.0, 10.0),
+            ShapeStyle::fill_and_stroke(Color::black(), StrokeStyle::new(Color::black(), 2.0)),
+        );
+        let mesh = tessellator.tessellate_shape(&shape);
+        assert!(mesh_area(&mesh) < 1e-3);
+    }
+
     #[test]
     fn test_tessellate_ellipse() {
         let mut tessellator = Tessellator::new();
@@ -1047,4 +1345,409 @@ mod tests {
         assert!(!mesh.vertices.is_empty());
         // Should have both fill and stroke vertices
     }
+
+    #[test]
+    fn test_tessellate_with_opacity() {
+        let mut tessellator = Tessellator::new();
+        let style = ShapeStyle::fill_and_stroke(
+            Color::new(1.0, 0.0, 0.0, 0.8),
+            StrokeStyle::new(Color::black(), 2.0),
+        );
+        let opaque = Shape::new(ShapeGeometry::rectangle(100.0, 50.0), style.clone());
+        let faded = Shape::new(ShapeGeometry::rectangle(100.0, 50.0), style.with_opacity(0.5));
+
+        let opaque_mesh = tessellator.tessellate_shape(&opaque);
+        let faded_mesh = tessellator.tessellate_shape(&faded);
+        assert_eq!(opaque_mesh.vertices.len(), faded_mesh.vertices.len());
+        for (a, b) in opaque_mesh.vertices.iter().zip(&faded_mesh.vertices) {
+            assert_eq!(b.color[3], a.color[3] * 0.5);
+            assert_eq!(b.color[..3], a.color[..3]);
+        }
+    }
+
+    #[test]
+    fn test_tessellate_shapes_skips_hidden() {
+        let mut tessellator = Tessellator::new();
+        let visible = Shape::new(ShapeGeometry::rectangle(100.0, 50.0), ShapeStyle::fill_only(Color::black()));
+        let mut hidden = visible.clone();
+        hidden.visible = false;
+
+        let single = tessellator.tessellate_shapes(std::slice::from_ref(&visible));
+        let both = tessellator.tessellate_shapes(&[visible, hidden]);
+        assert_eq!(both.vertices.len(), single.vertices.len());
+    }
+
+    #[test]
+    fn test_dashed_stroke_produces_more_geometry() {
+        let mut tessellator = Tessellator::new();
+        let stroke = StrokeStyle::new(Color::black(), 2.0);
+        let solid = Shape::new(ShapeGeometry::rectangle(100.0, 50.0), ShapeStyle::stroke_only(stroke.clone()));
+        let dashed = Shape::new(
+            ShapeGeometry::rectangle(100.0, 50.0),
+            ShapeStyle::stroke_only(stroke.with_dash_pattern(Some(vec![8.0, 4.0]))),
+        );
+
+        let solid_mesh = tessellator.tessellate_shape(&solid);
+        let dashed_mesh = tessellator.tessellate_shape(&dashed);
+        assert!(dashed_mesh.indices.len() > solid_mesh.indices.len());
+    }
+
+    #[test]
+    fn test_dash_path_splits_line() {
+        let mut builder = Path::builder();
+        builder.begin(point(0.0, 0.0));
+        builder.line_to(point(30.0, 0.0));
+        builder.end(false);
+
+        // 30px with an 8,4 pattern: dashes at 0-8, 12-20, 24-30
+        let dashed = dash_path(&builder.build(), &[8.0, 4.0], 0.0);
+        let dashes = dashed.iter().filter(|e| matches!(e, PathEvent::Begin { .. })).count();
+        assert_eq!(dashes, 3);
+    }
+
+    /// Total area covered by a mesh's triangles
+    fn mesh_area(mesh: &Mesh) -> f32 {
+        mesh.indices
+            .chunks_exact(3)
+            .map(|tri| {
+                let [a, b, c] = [tri[0], tri[1], tri[2]].map(|i| Vec2::from(mesh.vertices[i as usize].position));
+                (b - a).perp_dot(c - a).abs() / 2.0
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_lower_tolerance_adds_vertices() {
+        let shape = Shape::new(ShapeGeometry::ellipse(100.0, 60.0), ShapeStyle::fill_only(Color::black()));
+        let counts: Vec<usize> = [1.0, 0.5, 0.1, 0.02, 0.005]
+            .into_iter()
+            .map(|tolerance| Tessellator::new().with_tolerance(tolerance).tessellate_shape(&shape).vertices.len())
+            .collect();
+
+        assert!(counts.windows(2).all(|pair| pair[0] <= pair[1]), "{counts:?}");
+        assert!(counts[0] < counts[4], "{counts:?}");
+    }
+
+    #[test]
+    fn test_tolerance_change_invalidates_cache() {
+        let mut shape = Shape::new(ShapeGeometry::circle(50.0), ShapeStyle::fill_only(Color::black()));
+        shape.clear_dirty();
+        let mut tessellator = Tessellator::new();
+        let coarse = tessellator.get_or_tessellate_shape(&shape).vertices.len();
+
+        tessellator.set_tolerance(Tessellator::DEFAULT_TOLERANCE);
+        assert!(!tessellator.needs_tessellation(&shape));
+
+        tessellator.set_tolerance(Tessellator::tolerance_for_zoom(8.0));
+        assert!(tessellator.needs_tessellation(&shape));
+        assert!(tessellator.get_or_tessellate_shape(&shape).vertices.len() > coarse);
+    }
+
+    #[test]
+    fn test_tolerance_for_zoom_steps_by_doubling() {
+        assert_eq!(Tessellator::tolerance_for_zoom(1.0), Tessellator::DEFAULT_TOLERANCE);
+        assert_eq!(Tessellator::tolerance_for_zoom(1.2), Tessellator::DEFAULT_TOLERANCE);
+        assert_eq!(Tessellator::tolerance_for_zoom(2.0), Tessellator::DEFAULT_TOLERANCE / 2.0);
+        assert_eq!(Tessellator::tolerance_for_zoom(0.25), Tessellator::DEFAULT_TOLERANCE * 4.0);
+        assert_eq!(Tessellator::tolerance_for_zoom(1e6), Tessellator::MIN_TOLERANCE);
+        assert_eq!(Tessellator::tolerance_for_zoom(0.0), Tessellator::MAX_TOLERANCE);
+    }
+
+    #[test]
+    fn test_evenodd_fill_leaves_nested_square_empty() {
+        use crate::scene::PathCommand;
+
+        // Two concentric squares wound the same way: nonzero fills the inner one too
+        let square = |min: f32, max: f32| {
+            vec![
+                PathCommand::MoveTo(Vec2::new(min, min)),
+                PathCommand::LineTo(Vec2::new(max, min)),
+                PathCommand::LineTo(Vec2::new(max, max)),
+                PathCommand::LineTo(Vec2::new(min, max)),
+                PathCommand::Close,
+            ]
+        };
+        let commands = [square(0.0, 100.0), square(25.0, 75.0)].concat();
+        let style = ShapeStyle::fill_only(Color::black());
+        let mut tessellator = Tessellator::new();
+
+        let solid = tessellator.tessellate_shape(&Shape::new(ShapeGeometry::Path { commands: commands.clone() }, style.clone()));
+        assert!((mesh_area(&solid) - 10_000.0).abs() < 1.0, "area {}", mesh_area(&solid));
+
+        let shape = Shape::new(ShapeGeometry::Path { commands }, style.with_fill_rule(FillRule::EvenOdd));
+        let donut = tessellator.tessellate_shape(&shape);
+        assert!((mesh_area(&donut) - 7_500.0).abs() < 1.0, "area {}", mesh_area(&donut));
+        // No triangle lies in the hole
+        let hole = BBox::new(Vec2::splat(25.01), Vec2::splat(74.99));
+        for tri in donut.indices.chunks_exact(3) {
+            let centroid = tri
+                .iter()
+                .map(|&i| Vec2::from(donut.vertices[i as usize].position))
+                .sum::<Vec2>()
+                / 3.0;
+            assert!(!hole.contains(centroid));
+        }
+    }
+
+    #[test]
+    fn test_cached_mesh_follows_content_changes_without_dirty_flag() {
+        let mut tessellator = Tessellator::new();
+        let mut shape = Shape::new(
+            ShapeGeometry::rectangle(100.0, 50.0),
+            ShapeStyle::fill_only(Color::rgb(1.0, 0.0, 0.0)),
+        );
+        tessellator.get_or_tessellate_shape(&shape);
+        shape.dirty = false;
+        assert!(!tessellator.needs_tessellation(&shape));
+
+        // Moving doesn't touch the mesh; the transform is applied in the shader
+        shape.transform.position = Vec2::new(40.0, 40.0);
+        assert!(!tessellator.needs_tessellation(&shape));
+
+        // A recolor that forgot to mark the shape dirty still re-tessellates
+        shape.style = ShapeStyle::fill_only(Color::rgb(0.0, 0.0, 1.0));
+        assert!(tessellator.needs_tessellation(&shape));
+        let mesh = tessellator.get_or_tessellate_shape(&shape);
+        assert!(mesh.vertices.iter().all(|v| v.color == [0.0, 0.0, 1.0, 1.0]));
+        assert!(!tessellator.needs_tessellation(&shape));
+
+        shape.geometry = ShapeGeometry::rectangle(100.0, 80.0);
+        assert!(tessellator.needs_tessellation(&shape));
+    }
+
+    #[test]
+    fn test_repeated_frames_over_static_shapes_tessellate_nothing_once_warm() {
+        let mut tessellator = Tessellator::new();
+        let mut shapes: Vec<Shape> = (0..1000)
+            .map(|i| {
+                let geometry = match i % 3 {
+                    0 => ShapeGeometry::ellipse(20.0, 10.0),
+                    1 => ShapeGeometry::rectangle(30.0, 15.0),
+                    _ => ShapeGeometry::star(5, 12.0, 5.0),
+                };
+                let style = ShapeStyle::fill_and_stroke(Color::rgb(1.0, 0.0, 0.0), StrokeStyle::new(Color::black(), 1.0));
+                Shape::new(geometry, style).with_transform(Transform2D::from_position(Vec2::new(i as f32, 0.0)))
+            })
+            .collect();
+        // Each frame hands every shape to the tessellator, as the canvas does
+        let draw_frame = |tessellator: &mut Tessellator, shapes: &[Shape]| {
+            for shape in shapes {
+                tessellator.get_or_tessellate_shape(shape);
+            }
+        };
+
+        draw_frame(&mut tessellator, &shapes);
+        assert_eq!(tessellator.shapes_tessellated(), 1000);
+
+        // New shapes stay dirty across frames until the scene clears them
+        for _ in 0..10 {
+            draw_frame(&mut tessellator, &shapes);
+        }
+        assert_eq!(tessellator.shapes_tessellated(), 1000);
+        assert!(shapes.iter().all(|shape| shape.dirty && !tessellator.needs_tessellation(shape)));
+
+        // Nor does clearing the flags, or panning every shape, redo any mesh
+        for shape in &mut shapes {
+            shape.clear_dirty();
+            shape.transform.position += Vec2::new(5.0, 5.0);
+        }
+        for _ in 0..10 {
+            draw_frame(&mut tessellator, &shapes);
+        }
+        assert_eq!(tessellator.shapes_tessellated(), 1000);
+
+        // Once seen clean, marking a shape dirty again invalidates its mesh; it's redone once
+        assert!(!tessellator.needs_tessellation(&shapes[7]));
+        shapes[7].mark_dirty();
+        assert!(tessellator.needs_tessellation(&shapes[7]));
+        for _ in 0..10 {
+            draw_frame(&mut tessellator, &shapes);
+        }
+        assert!(!tessellator.needs_tessellation(&shapes[7]));
+        assert_eq!(tessellator.shapes_tessellated(), 1001);
+    }
+
+    #[test]
+    fn test_selection_outline_strokes_the_border_only() {
+        let mut tessellator = Tessellator::new();
+        let bbox = BBox::new(Vec2::new(10.0, 20.0), Vec2::new(110.0, 70.0));
+        let mesh = tessellator.tessellate_selection_outline(&bbox, Color::rgb(0.0, 0.0, 1.0), 2.0);
+
+        // A 2px band centered on the 100x50 border, not the filled interior
+        let expected = 2.0 * 2.0 * (100.0 + 50.0);
+        assert!((mesh_area(&mesh) - expected).abs() < 1.0, "area {}", mesh_area(&mesh));
+        let xs = mesh.vertices.iter().map(|v| v.position[0]);
+        let min_x = xs.clone().fold(f32::INFINITY, f32::min);
+        let max_x = xs.fold(f32::NEG_INFINITY, f32::max);
+        assert_eq!((min_x, max_x), (9.0, 111.0));
+    }
+
+    #[test]
+    fn test_handle_fills_and_strokes_its_square() {
+        let mut tessellator = Tessellator::new();
+        let fill = Color::white();
+        let stroke = Color::rgb(0.0, 0.6, 1.0);
+        let mesh = tessellator.tessellate_handle(Vec2::new(50.0, 50.0), 8.0, fill, stroke, 1.0);
+
+        assert!(mesh.vertices.iter().any(|v| v.color == fill.to_array()));
+        assert!(mesh.vertices.iter().any(|v| v.color == stroke.to_array()));
+        // Fill (8x8) plus a 1px stroke band around its 32px perimeter
+        assert!((mesh_area(&mesh) - (64.0 + 32.0)).abs() < 0.5, "area {}", mesh_area(&mesh));
+
+        let unstroked = tessellator.tessellate_handle(Vec2::new(50.0, 50.0), 8.0, fill, stroke, 0.0);
+        assert!(unstroked.vertices.iter().all(|v| v.color == fill.to_array()));
+    }
+
+    fn dash_count(path: &Path) -> usize {
+        path.iter().filter(|e| matches!(e, PathEvent::Begin { .. })).count()
+    }
+
+    #[test]
+    fn test_pie_slice_fills_its_sector() {
+        let mut tessellator = Tessellator::new();
+        let quarter = Shape::new(
+            ShapeGeometry::pie_slice(PieSlice::new(20.0, 0.0, 90.0)),
+            ShapeStyle::fill_only(Color::black()),
+        );
+        let three_quarters = Shape::new(
+            ShapeGeometry::pie_slice(PieSlice::new(20.0, 0.0, 270.0)),
+            ShapeStyle::fill_only(Color::black()),
+        );
+
+        let circle_area = std::f32::consts::PI * 20.0 * 20.0;
+        let quarter_area = mesh_area(&tessellator.tessellate_shape(&quarter));
+        let large_area = mesh_area(&tessellator.tessellate_shape(&three_quarters));
+        assert!((quarter_area - circle_area / 4.0).abs() < circle_area * 0.01);
+        assert!((large_area - circle_area * 0.75).abs() < circle_area * 0.01);
+    }
+
+    #[test]
+    fn test_dashed_line_leaves_gaps() {
+        let mut tessellator = Tessellator::new();
+        let line = ShapeGeometry::Line { start: Vec2::ZERO, end: Vec2::new(120.0, 0.0) };
+        let stroke = StrokeStyle::new(Color::black(), 4.0);
+        let solid = Shape::new(line.clone(), ShapeStyle::stroke_only(stroke.clone()));
+        let dashed = Shape::new(line, ShapeStyle::stroke_only(stroke.with_dash_pattern(Some(vec![8.0, 4.0]))));
+
+        // 120px at 8 on / 4 off covers two thirds of the solid stroke
+        let solid_area = mesh_area(&tessellator.tessellate_shape(&solid));
+        let dashed_area = mesh_area(&tessellator.tessellate_shape(&dashed));
+        assert!((solid_area - 480.0).abs() < 1e-3);
+        assert!((dashed_area - 320.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_line_caps_extend_open_ends() {
+        let mut tessellator = Tessellator::new();
+        let line = ShapeGeometry::Line { start: Vec2::ZERO, end: Vec2::new(100.0, 0.0) };
+        let stroke = StrokeStyle::new(Color::black(), 4.0);
+        let area = |tessellator: &mut Tessellator, cap: LineCap| {
+            let shape = Shape::new(line.clone(), ShapeStyle::stroke_only(stroke.clone().with_line_cap(cap)));
+            mesh_area(&tessellator.tessellate_shape(&shape))
+        };
+
+        let butt = area(&mut tessellator, LineCap::Butt);
+        let square = area(&mut tessellator, LineCap::Square);
+        let round = area(&mut tessellator, LineCap::Round);
+        // Square caps add half the width at each end; round caps add a circle in total
+        assert!((square - butt - 16.0).abs() < 1e-3);
+        assert!(round > butt && round < square);
+    }
+
+    #[test]
+    fn test_dash_offset_shifts_pattern() {
+        let mut builder = Path::builder();
+        builder.begin(point(0.0, 0.0));
+        builder.line_to(point(30.0, 0.0));
+        builder.end(false);
+
+        // Starting 10px in (2px into the gap): dashes at 2-10, 14-22, 26-30
+        let dashed = dash_path(&builder.build(), &[8.0, 4.0], 10.0);
+        let starts: Vec<f32> = dashed
+            .iter()
+            .filter_map(|e| match e {
+                PathEvent::Begin { at } => Some(at.x),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(starts, vec![2.0, 14.0, 26.0]);
+    }
+
+    #[test]
+    fn test_closed_dash_joins_across_seam() {
+        let mut builder = Path::builder();
+        builder.begin(point(0.0, 0.0));
+        builder.line_to(point(40.0, 0.0));
+        builder.line_to(point(40.0, 40.0));
+        builder.line_to(point(0.0, 40.0));
+        builder.close();
+        let square = builder.build();
+
+        // Perimeter 160 with a 35px period: the last dash (140-160) runs into the first (0-25)
+        assert_eq!(dash_count(&dash_path(&square, &[25.0, 10.0], 0.0)), 4);
+        // A 40px period ends in a gap, so nothing is merged
+        assert_eq!(dash_count(&dash_path(&square, &[30.0, 10.0], 0.0)), 4);
+        // Starting in a gap never merges: offset 30 gives dashes at 5-30, 40-65, ..., 145-160
+        assert_eq!(dash_count(&dash_path(&square, &[25.0, 10.0], 30.0)), 5);
+    }
+
+    #[test]
+    fn test_gradient_fill_colors_follow_the_gradient() {
+        let mut tessellator = Tessellator::new();
+        let (red, blue) = (Color::rgb(1.0, 0.0, 0.0), Color::rgb(0.0, 0.0, 1.0));
+        let geometry = ShapeGeometry::rectangle(100.0, 20.0);
+        let fill = Fill::linear_across(&geometry.local_bounds(), red, blue);
+        let shape = Shape::new(geometry, ShapeStyle::default().with_fill(Some(fill)))
+            .with_transform(Transform2D::from_position(Vec2::new(50.0, 50.0)));
+
+        let mesh = tessellator.tessellate_shape(&shape);
+        // Subdivided well past the 4 corners, with colors sampled in local space
+        assert!(mesh.vertices.len() > 100);
+        for vertex in &mesh.vertices {
+            let t = (vertex.position[0] - 50.0) / 100.0;
+            let expected = red.lerp(blue, t).to_array();
+            assert!(vertex.color.iter().zip(expected).all(|(a, b)| (a - b).abs() < 1e-4));
+        }
+    }
+
+    #[test]
+    fn test_subdivide_respects_edge_length_and_budget() {
+        let mesh = Mesh {
+            vertices: vec![
+                Vertex::new([0.0, 0.0], [0.0; 4]),
+                Vertex::new([64.0, 0.0], [0.0; 4]),
+                Vertex::new([0.0, 64.0], [0.0; 4]),
+            ],
+            indices: vec![0, 1, 2],
+        };
+
+        let fine = subdivide_mesh(&mesh, 8.0, usize::MAX);
+        for tri in fine.vertices.chunks_exact(3) {
+            for i in 0..3 {
+                let [ax, ay] = tri[i].position;
+                let [bx, by] = tri[(i + 1) % 3].position;
+                assert!(Vec2::new(ax, ay).distance(Vec2::new(bx, by)) <= 8.0);
+            }
+        }
+
+        let capped = subdivide_mesh(&mesh, 8.0, 10);
+        assert!(capped.indices.len() / 3 <= 10);
+    }
+
+    #[test]
+    fn test_round_to_half_pixel() {
+        // Already on a pixel center
+        assert_eq!(round_to_half_pixel(0.5), 0.5);
+        assert_eq!(round_to_half_pixel(10.5), 10.5);
+        assert_eq!(round_to_half_pixel(-2.5), -2.5);
+        // Nearest center either side
+        assert_eq!(round_to_half_pixel(10.2), 10.5);
+        assert_eq!(round_to_half_pixel(10.9), 10.5);
+        assert_eq!(round_to_half_pixel(11.1), 11.5);
+        // Whole numbers sit between two centers and always go up
+        assert_eq!(round_to_half_pixel(0.0), 0.5);
+        assert_eq!(round_to_half_pixel(-1.0), -0.5);
+        assert_eq!(round_to_half_pixel(-0.3), -0.5);
+        assert_eq!(round_to_half_pixel(-1.2), -1.5);
+    }
 }
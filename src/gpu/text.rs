@@ -0,0 +1,301 @@
+//! Text rendering through a glyph atlas
+//!
+//! Glyphs are rasterized once by the browser's 2D canvas at `GLYPH_RASTER_SIZE`,
+//! packed into a texture atlas, and drawn as textured quads scaled to the font
+//! size. Glyph placement uses the same advance table as `scene::text_bounds`, so
+//! GPU text fills exactly the bounds used for selection and snapping.
+
+use super::textured::{TexturedMesh, TexturedVertex};
+use crate::scene::{glyph_advance, Color, TextAlign, TEXT_ASCENT, TEXT_DESCENT};
+use std::collections::HashMap;
+use wasm_bindgen::JsCast;
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+
+/// Width and height of the square glyph atlas texture, in pixels
+pub const ATLAS_SIZE: u32 = 1024;
+/// Font size glyphs are rasterized at; quads scale this to the text's font size
+pub const GLYPH_RASTER_SIZE: f32 = 48.0;
+/// Empty pixels around each glyph so neighbours don't bleed in when sampling
+const GLYPH_PADDING: u32 = 2;
+
+/// A character in one font family
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GlyphKey {
+    pub font_family: String,
+    pub ch: char,
+}
+
+impl GlyphKey {
+    pub fn new(font_family: &str, ch: char) -> Self {
+        Self {
+            font_family: font_family.to_string(),
+            ch,
+        }
+    }
+}
+
+/// Pixel rectangle of a glyph cell in the atlas, padding included
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AtlasRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Cell size for `ch`: its advance by ascent plus descent at the raster size, padded
+pub fn glyph_cell_size(ch: char) -> (u32, u32) {
+    let width = glyph_advance(ch, GLYPH_RASTER_SIZE).ceil() as u32;
+    let height = ((TEXT_ASCENT + TEXT_DESCENT) * GLYPH_RASTER_SIZE).ceil() as u32;
+    (width + 2 * GLYPH_PADDING, height + 2 * GLYPH_PADDING)
+}
+
+/// Shelf packer for glyph cells
+/// Cells fill rows left to right; a cell that doesn't fit starts a new row below
+/// the tallest cell of the current one.
+#[derive(Debug)]
+pub struct GlyphAtlas {
+    size: u32,
+    glyphs: HashMap<GlyphKey, AtlasRect>,
+    /// Top-left of the next cell and the height of the row it's in
+    cursor_x: u32,
+    shelf_y: u32,
+    shelf_height: u32,
+}
+
+impl GlyphAtlas {
+    pub fn new(size: u32) -> Self {
+        Self {
+            size,
+            glyphs: HashMap::new(),
+            cursor_x: 0,
+            shelf_y: 0,
+            shelf_height: 0,
+        }
+    }
+
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// Cell of an already packed glyph
+    pub fn get(&self, key: &GlyphKey) -> Option<AtlasRect> {
+        self.glyphs.get(key).copied()
+    }
+
+    /// Reserve a cell for `key`, or return the one it already has
+    /// Returns None when the atlas is full
+    pub fn insert(&mut self, key: GlyphKey) -> Option<AtlasRect> {
+        if let Some(rect) = self.get(&key) {
+            return Some(rect);
+        }
+
+        let (width, height) = glyph_cell_size(key.ch);
+        if width > self.size || height > self.size {
+            return None;
+        }
+        if self.cursor_x + width > self.size {
+            self.shelf_y += self.shelf_height;
+            self.cursor_x = 0;
+            self.shelf_height = 0;
+        }
+        if self.shelf_y + height > self.size {
+            return None;
+        }
+
+        let rect = AtlasRect {
+            x: self.cursor_x,
+            y: self.shelf_y,
+            width,
+            height,
+        };
+        self.cursor_x += width;
+        self.shelf_height = self.shelf_height.max(height);
+        self.glyphs.insert(key, rect);
+        Some(rect)
+    }
+
+    /// Forget every glyph, e.g. once the atlas is full
+    pub fn clear(&mut self) {
+        *self = Self::new(self.size);
+    }
+}
+
+/// Lay `content` out as one quad per packed glyph, with the baseline on y = 0
+/// Characters missing from the atlas still advance the pen but draw nothing
+pub fn layout_text(
+    atlas: &GlyphAtlas,
+    content: &str,
+    font_size: f32,
+    font_family: &str,
+    align: TextAlign,
+    color: Color,
+) -> TexturedMesh {
+    let scale = font_size / GLYPH_RASTER_SIZE;
+    let padding = GLYPH_PADDING as f32 * scale;
+    let atlas_size = atlas.size() as f32;
+    let color = color.to_array();
+
+    let mut mesh = TexturedMesh::default();
+    let mut pen = align.start_x(crate::scene::text_width(content, font_size));
+    for ch in content.chars() {
+        let advance = glyph_advance(ch, font_size);
+        let rect = atlas.get(&GlyphKey::new(font_family, ch)).filter(|_| !ch.is_whitespace());
+        if let Some(rect) = rect {
+            let left = pen - padding;
+            let top = -TEXT_ASCENT * font_size - padding;
+            let right = left + rect.width as f32 * scale;
+            let bottom = top + rect.height as f32 * scale;
+            let (u0, v0) = (rect.x as f32 / atlas_size, rect.y as f32 / atlas_size);
+            let (u1, v1) = (
+                (rect.x + rect.width) as f32 / atlas_size,
+                (rect.y + rect.height) as f32 / atlas_size,
+            );
+
+            let base = mesh.vertices.len() as u32;
+            mesh.vertices.extend_from_slice(&[
+                TexturedVertex { position: [left, top], uv: [u0, v0], color },
+                TexturedVertex { position: [right, top], uv: [u1, v0], color },
+                TexturedVertex { position: [right, bottom], uv: [u1, v1], color },
+                TexturedVertex { position: [left, bottom], uv: [u0, v1], color },
+            ]);
+            mesh.indices.extend_from_slice(&[base, base + 1, base + 2, base, base + 2, base + 3]);
+        }
+        pen += advance;
+    }
+    mesh
+}
+
+/// Offscreen 2D canvas the glyphs are drawn into before upload
+/// Glyphs are white on transparent; the text shader tints them with the vertex color
+pub struct GlyphCanvas {
+    canvas: HtmlCanvasElement,
+    context: CanvasRenderingContext2d,
+}
+
+impl GlyphCanvas {
+    pub fn new(size: u32) -> Result<Self, String> {
+        let document = web_sys::window()
+            .and_then(|window| window.document())
+            .ok_or("No document for the glyph canvas")?;
+        let canvas = document
+            .create_element("canvas")
+            .map_err(|_| "Failed to create the glyph canvas")?
+            .dyn_into::<HtmlCanvasElement>()
+            .map_err(|_| "Glyph canvas is not a canvas")?;
+        canvas.set_width(size);
+        canvas.set_height(size);
+        let context = canvas
+            .get_context("2d")
+            .ok()
+            .flatten()
+            .and_then(|context| context.dyn_into::<CanvasRenderingContext2d>().ok())
+            .ok_or("No 2D context for the glyph canvas")?;
+        context.set_fill_style_str("white");
+        context.set_text_baseline("alphabetic");
+        Ok(Self { canvas, context })
+    }
+
+    /// Draw `key`'s glyph into its cell, with the baseline at the cell's ascent
+    pub fn draw(&self, key: &GlyphKey, rect: AtlasRect) {
+        self.context
+            .set_font(&format!("{}px {}", GLYPH_RASTER_SIZE, key.font_family));
+        let baseline = rect.y as f32 + GLYPH_PADDING as f32 + TEXT_ASCENT * GLYPH_RASTER_SIZE;
+        let _ = self.context.fill_text(
+            &key.ch.to_string(),
+            (rect.x + GLYPH_PADDING) as f64,
+            baseline as f64,
+        );
+    }
+
+    /// Erase every glyph
+    pub fn clear(&self) {
+        let size = self.canvas.width() as f64;
+        self.context.clear_rect(0.0, 0.0, size, size);
+    }
+
+    /// RGBA pixels of the whole canvas, ready to upload
+    pub fn pixels(&self) -> Option<Vec<u8>> {
+        let size = self.canvas.width() as f64;
+        self.context
+            .get_image_data(0.0, 0.0, size, size)
+            .ok()
+            .map(|image| image.data().0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    impl AtlasRect {
+        fn overlaps(&self, other: &AtlasRect) -> bool {
+            self.x < other.x + other.width
+                && other.x < self.x + self.width
+                && self.y < other.y + other.height
+                && other.y < self.y + self.height
+        }
+    }
+
+    #[test]
+    fn test_atlas_packs_ascii_without_overlap() {
+        let mut atlas = GlyphAtlas::new(ATLAS_SIZE);
+        let rects: Vec<AtlasRect> = (' '..='~')
+            .map(|ch| atlas.insert(GlyphKey::new("Arial", ch)).expect("ASCII should fit"))
+            .collect();
+        assert_eq!(rects.len(), 95);
+
+        for (i, rect) in rects.iter().enumerate() {
+            assert!(rect.x + rect.width <= ATLAS_SIZE && rect.y + rect.height <= ATLAS_SIZE);
+            for other in &rects[i + 1..] {
+                assert!(!rect.overlaps(other), "{:?} overlaps {:?}", rect, other);
+            }
+        }
+
+        // Packed glyphs keep their cell; another family gets its own
+        assert_eq!(atlas.insert(GlyphKey::new("Arial", 'A')), atlas.get(&GlyphKey::new("Arial", 'A')));
+        let serif = atlas.insert(GlyphKey::new("serif", 'A')).unwrap();
+        assert!(rects.iter().all(|rect| !rect.overlaps(&serif)));
+    }
+
+    #[test]
+    fn test_atlas_reports_when_full() {
+        let (width, height) = glyph_cell_size('W');
+        let mut atlas = GlyphAtlas::new(width.max(height) + 1);
+        assert!(atlas.insert(GlyphKey::new("Arial", 'W')).is_some());
+        assert_eq!(atlas.insert(GlyphKey::new("Arial", 'M')), None);
+
+        atlas.clear();
+        assert_eq!(atlas.get(&GlyphKey::new("Arial", 'W')), None);
+        assert!(atlas.insert(GlyphKey::new("Arial", 'M')).is_some());
+    }
+
+    #[test]
+    fn test_layout_places_glyphs_along_the_baseline() {
+        let mut atlas = GlyphAtlas::new(ATLAS_SIZE);
+        for ch in "Hi".chars() {
+            atlas.insert(GlyphKey::new("Arial", ch));
+        }
+        let mesh = layout_text(&atlas, "H i", GLYPH_RASTER_SIZE, "Arial", TextAlign::Start, Color::black());
+
+        // The space advances the pen without a quad
+        assert_eq!(mesh.vertices.len(), 8);
+        assert_eq!(mesh.indices.len(), 12);
+        let padding = GLYPH_PADDING as f32;
+        assert_eq!(mesh.vertices[0].position, [-padding, -TEXT_ASCENT * GLYPH_RASTER_SIZE - padding]);
+        let second_left = glyph_advance('H', GLYPH_RASTER_SIZE) + glyph_advance(' ', GLYPH_RASTER_SIZE) - padding;
+        assert!((mesh.vertices[4].position[0] - second_left).abs() < 1e-4);
+
+        // Texture coordinates cover the glyph's cell
+        let rect = atlas.get(&GlyphKey::new("Arial", 'H')).unwrap();
+        assert_eq!(mesh.vertices[2].uv, [
+            (rect.x + rect.width) as f32 / ATLAS_SIZE as f32,
+            (rect.y + rect.height) as f32 / ATLAS_SIZE as f32,
+        ]);
+
+        // Half the font size halves the quads; end alignment ends at the origin
+        let small = layout_text(&atlas, "Hi", GLYPH_RASTER_SIZE / 2.0, "Arial", TextAlign::End, Color::black());
+        let width = crate::scene::text_width("Hi", GLYPH_RASTER_SIZE / 2.0);
+        assert!((small.vertices[0].position[0] + width + padding / 2.0).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,143 @@
+//! Textured quads: glyphs from the text atlas and bitmap images
+//!
+//! Both go through `textured.wgsl`; glyphs use the atlas alpha as coverage for
+//! their color, images draw the texture's own colors faded by the vertex alpha.
+
+use bytemuck::{Pod, Zeroable};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, HtmlImageElement, ImageBitmap};
+
+/// Largest image texture side; larger images are downscaled on upload
+/// WebGL2 only guarantees 2048
+pub const MAX_IMAGE_TEXTURE_SIZE: u32 = 2048;
+
+/// Vertex of a textured quad: local position, texture coordinate and color
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Pod, Zeroable)]
+pub struct TexturedVertex {
+    pub position: [f32; 2],
+    pub uv: [f32; 2],
+    pub color: [f32; 4],
+}
+
+impl TexturedVertex {
+    /// Vertex buffer layout descriptor for wgpu
+    pub fn desc() -> wgpu::VertexBufferLayout<'static> {
+        const ATTRIBUTES: [wgpu::VertexAttribute; 3] =
+            wgpu::vertex_attr_array![0 => Float32x2, 1 => Float32x2, 2 => Float32x4];
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<TexturedVertex>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &ATTRIBUTES,
+        }
+    }
+}
+
+/// Textured quads for one shape, in its local coordinates
+#[derive(Clone, Debug, Default)]
+pub struct TexturedMesh {
+    pub vertices: Vec<TexturedVertex>,
+    pub indices: Vec<u32>,
+}
+
+impl TexturedMesh {
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+}
+
+/// One quad showing a whole image over width × height from the origin
+pub fn image_quad(width: f32, height: f32, opacity: f32) -> TexturedMesh {
+    let color = [1.0, 1.0, 1.0, opacity];
+    TexturedMesh {
+        vertices: vec![
+            TexturedVertex { position: [0.0, 0.0], uv: [0.0, 0.0], color },
+            TexturedVertex { position: [width, 0.0], uv: [1.0, 0.0], color },
+            TexturedVertex { position: [width, height], uv: [1.0, 1.0], color },
+            TexturedVertex { position: [0.0, height], uv: [0.0, 1.0], color },
+        ],
+        indices: vec![0, 1, 2, 0, 2, 3],
+    }
+}
+
+/// Size an image's texture gets: its own size, shrunk to fit `max` on its longer side
+pub fn texture_size_for(width: u32, height: u32, max: u32) -> (u32, u32) {
+    let longest = width.max(height);
+    if longest <= max {
+        return (width.max(1), height.max(1));
+    }
+    let scale = max as f64 / longest as f64;
+    (
+        ((width as f64 * scale).round() as u32).max(1),
+        ((height as f64 * scale).round() as u32).max(1),
+    )
+}
+
+/// Load and decode an image from a URL (typically a data URL)
+pub async fn decode_image(src: &str) -> Result<ImageBitmap, String> {
+    let image = HtmlImageElement::new().map_err(|_| "Failed to create an image element".to_string())?;
+    image.set_src(src);
+    JsFuture::from(image.decode())
+        .await
+        .map_err(|_| "Failed to decode image".to_string())?;
+
+    let window = web_sys::window().ok_or("No window to decode images in")?;
+    let bitmap = window
+        .create_image_bitmap_with_html_image_element(&image)
+        .map_err(|_| "Failed to create an image bitmap".to_string())?;
+    JsFuture::from(bitmap)
+        .await
+        .ok()
+        .and_then(|bitmap| bitmap.dyn_into::<ImageBitmap>().ok())
+        .ok_or_else(|| "Failed to create an image bitmap".to_string())
+}
+
+/// RGBA pixels of a decoded image, downscaled to fit `MAX_IMAGE_TEXTURE_SIZE`
+/// Returns the pixel size along with the pixels
+pub fn image_pixels(bitmap: &ImageBitmap) -> Result<(u32, u32, Vec<u8>), String> {
+    let (width, height) = texture_size_for(bitmap.width(), bitmap.height(), MAX_IMAGE_TEXTURE_SIZE);
+    let canvas = web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.create_element("canvas").ok())
+        .and_then(|canvas| canvas.dyn_into::<HtmlCanvasElement>().ok())
+        .ok_or("Failed to create a canvas to read image pixels")?;
+    canvas.set_width(width);
+    canvas.set_height(height);
+    let context = canvas
+        .get_context("2d")
+        .ok()
+        .flatten()
+        .and_then(|context| context.dyn_into::<CanvasRenderingContext2d>().ok())
+        .ok_or("No 2D context to read image pixels")?;
+
+    context
+        .draw_image_with_image_bitmap_and_dw_and_dh(bitmap, 0.0, 0.0, width as f64, height as f64)
+        .map_err(|_| "Failed to draw the image".to_string())?;
+    let image = context
+        .get_image_data(0.0, 0.0, width as f64, height as f64)
+        .map_err(|_| "Failed to read image pixels".to_string())?;
+    Ok((width, height, image.data().0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_quad_covers_the_whole_texture() {
+        let quad = image_quad(120.0, 80.0, 0.5);
+        assert_eq!(quad.indices, vec![0, 1, 2, 0, 2, 3]);
+        assert_eq!(quad.vertices[2].position, [120.0, 80.0]);
+        assert_eq!(quad.vertices[2].uv, [1.0, 1.0]);
+        assert!(quad.vertices.iter().all(|v| v.color == [1.0, 1.0, 1.0, 0.5]));
+    }
+
+    #[test]
+    fn test_texture_size_shrinks_large_images_only() {
+        assert_eq!(texture_size_for(640, 480, 2048), (640, 480));
+        assert_eq!(texture_size_for(4096, 1024, 2048), (2048, 512));
+        assert_eq!(texture_size_for(1000, 5000, 2048), (410, 2048));
+        assert_eq!(texture_size_for(0, 0, 2048), (1, 1));
+    }
+}
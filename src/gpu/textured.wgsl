@@ -0,0 +1,58 @@
+// Textured quads: glyphs sampled from the text atlas, and images
+
+struct VertexInput {
+    @location(0) position: vec2<f32>,
+    @location(1) uv: vec2<f32>,
+    @location(2) color: vec4<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) color: vec4<f32>,
+}
+
+// Same uniforms as the shape shader
+struct Uniforms {
+    view_proj: mat4x4<f32>,
+    model_transform: mat4x4<f32>,
+}
+
+@group(0) @binding(0)
+var<uniform> uniforms: Uniforms;
+
+// The glyph atlas or an image
+@group(1) @binding(0)
+var quad_texture: texture_2d<f32>;
+@group(1) @binding(1)
+var quad_sampler: sampler;
+
+@vertex
+fn vs_main(in: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+
+    // Scale then translate, as in the shape shader
+    let sx = uniforms.model_transform[0][0];
+    let sy = uniforms.model_transform[1][1];
+    let tx = uniforms.model_transform[3][0];
+    let ty = uniforms.model_transform[3][1];
+    let world_pos = vec4<f32>(sx * in.position.x + tx, sy * in.position.y + ty, 0.0, 1.0);
+
+    out.clip_position = uniforms.view_proj * world_pos;
+    out.uv = in.uv;
+    out.color = in.color;
+    return out;
+}
+
+@fragment
+fn fs_glyph(in: VertexOutput) -> @location(0) vec4<f32> {
+    // Glyphs are white on transparent, so alpha is the coverage
+    let coverage = textureSample(quad_texture, quad_sampler, in.uv).a;
+    return vec4<f32>(in.color.rgb, in.color.a * coverage);
+}
+
+@fragment
+fn fs_image(in: VertexOutput) -> @location(0) vec4<f32> {
+    // The vertex color is white with the shape's opacity
+    return textureSample(quad_texture, quad_sampler, in.uv) * in.color;
+}
@@ -1,4 +1,7 @@
 use bytemuck::{Pod, Zeroable};
+use std::collections::HashMap;
+
+use super::tessellation::round_to_half_pixel;
 
 /// Vertex data for GPU rendering
 /// Each vertex has a 2D position and RGBA color
@@ -67,6 +70,13 @@ impl Uniforms {
         }
     }
 
+    /// Projection for a surface of `width`×`height` physical pixels at `pixel_ratio`
+    /// device pixels per CSS pixel; scene coordinates stay in CSS pixels
+    pub fn for_surface(width: u32, height: u32, pixel_ratio: f32) -> Self {
+        let pixel_ratio = if pixel_ratio > 0.0 { pixel_ratio } else { 1.0 };
+        Self::orthographic(width as f32 / pixel_ratio, height as f32 / pixel_ratio)
+    }
+
     /// Create an identity 4x4 matrix
     pub fn identity_matrix() -> [[f32; 4]; 4] {
         [
@@ -147,6 +157,57 @@ impl Mesh {
             .extend(other.indices.iter().map(|i| i + index_offset));
     }
 
+    /// Copy of this mesh with every vertex mapped through a column-major 2D `transform`
+    pub fn transformed(&self, transform: &[[f32; 4]; 4]) -> Mesh {
+        let m = transform;
+        Mesh {
+            vertices: self
+                .vertices
+                .iter()
+                .map(|v| {
+                    let [x, y] = v.position;
+                    Vertex::new(
+                        [m[0][0] * x + m[1][0] * y + m[3][0], m[0][1] * x + m[1][1] * y + m[3][1]],
+                        v.color,
+                    )
+                })
+                .collect(),
+            indices: self.indices.clone(),
+        }
+    }
+
+    /// Move every vertex to the nearest device-pixel center, so thin strokes render crisp
+    /// The mesh must already be in surface CSS pixels, i.e. placed by its model and view
+    /// transforms; snapping happens at `pixel_ratio` device pixels per CSS pixel
+    pub fn snap_to_pixels(&mut self, pixel_ratio: f32) {
+        let ratio = if pixel_ratio > 0.0 { pixel_ratio } else { 1.0 };
+        for vertex in &mut self.vertices {
+            vertex.position = vertex.position.map(|c| round_to_half_pixel(c * ratio) / ratio);
+        }
+    }
+
+    /// Split into meshes of at most `max_indices` indices each, in draw order
+    /// Chunks hold whole triangles; each gets only the vertices it references, reindexed
+    /// from 0, so it can be uploaded and drawn on its own
+    pub fn chunks(&self, max_indices: usize) -> Vec<Mesh> {
+        let per_chunk = (max_indices / 3 * 3).max(3);
+        self.indices
+            .chunks(per_chunk)
+            .map(|indices| {
+                let mut remap: HashMap<u32, u32> = HashMap::new();
+                let mut chunk = Mesh::with_capacity(indices.len(), indices.len());
+                for &index in indices {
+                    let local = *remap.entry(index).or_insert_with(|| {
+                        chunk.vertices.push(self.vertices[index as usize]);
+                        chunk.vertices.len() as u32 - 1
+                    });
+                    chunk.indices.push(local);
+                }
+                chunk
+            })
+            .collect()
+    }
+
     /// Clear all vertices and indices
     pub fn clear(&mut self) {
         self.vertices.clear();
@@ -157,3 +218,144 @@ impl Mesh {
         self.vertices.is_empty()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Clip-space position of a scene point under `uniforms`
+    fn project(uniforms: &Uniforms, x: f32, y: f32) -> (f32, f32) {
+        let m = uniforms.view_proj;
+        (m[0][0] * x + m[1][0] * y + m[3][0], m[0][1] * x + m[1][1] * y + m[3][1])
+    }
+
+    /// Physical pixel a clip-space position lands on for a `width`×`height` surface
+    fn to_physical((cx, cy): (f32, f32), width: u32, height: u32) -> (f32, f32) {
+        ((cx + 1.0) / 2.0 * width as f32, (1.0 - cy) / 2.0 * height as f32)
+    }
+
+    #[test]
+    fn test_surface_projection_keeps_css_coordinates_at_dpr_2() {
+        // An 800x600 CSS canvas backed by 1600x1200 device pixels
+        let uniforms = Uniforms::for_surface(1600, 1200, 2.0);
+
+        assert_eq!(project(&uniforms, 0.0, 0.0), (-1.0, 1.0));
+        assert_eq!(project(&uniforms, 400.0, 300.0), (0.0, 0.0));
+        assert_eq!(project(&uniforms, 800.0, 600.0), (1.0, -1.0));
+
+        // Each CSS pixel covers two device pixels
+        for ((x, y), expected) in [((100.0, 50.0), (200.0, 100.0)), ((100.5, 50.5), (201.0, 101.0))] {
+            let (px, py) = to_physical(project(&uniforms, x, y), 1600, 1200);
+            assert!((px - expected.0).abs() < 1e-3 && (py - expected.1).abs() < 1e-3, "({px}, {py})");
+        }
+    }
+
+    #[test]
+    fn test_transformed_mesh_maps_positions_and_keeps_colors() {
+        let color = [0.2, 0.4, 0.6, 0.5];
+        let mesh = Mesh {
+            vertices: vec![Vertex::new([0.0, 0.0], color), Vertex::new([10.0, 0.0], color), Vertex::new([0.0, 5.0], color)],
+            indices: vec![0, 1, 2],
+        };
+        let moved = mesh.transformed(&Uniforms::transform_matrix(100.0, 50.0, 2.0, 3.0, 0.0, 0.0));
+
+        let positions: Vec<[f32; 2]> = moved.vertices.iter().map(|v| v.position).collect();
+        assert_eq!(positions, vec![[100.0, 50.0], [120.0, 50.0], [100.0, 65.0]]);
+        assert!(moved.vertices.iter().all(|v| v.color == color));
+        assert_eq!(moved.indices, mesh.indices);
+    }
+
+    #[test]
+    fn test_snap_to_pixels_after_a_rotated_scaled_and_zoomed_transform() {
+        use crate::scene::{Color, Shape, ShapeGeometry, ShapeStyle, StrokeStyle, Vec2};
+        use crate::gpu::{shape_model_transform, Tessellator};
+
+        let mut line = Shape::new(
+            ShapeGeometry::Polygon { points: vec![Vec2::new(10.3, 20.0), Vec2::new(90.7, 20.0)] },
+            ShapeStyle::stroke_only(StrokeStyle::new(Color::black(), 1.0)),
+        );
+        line.transform.position = Vec2::new(12.3, 40.1);
+        line.transform.scale = Vec2::new(1.7, 0.6);
+        line.transform.rotation = 0.4;
+        let mesh = Tessellator::new().get_or_tessellate_shape(&line).clone();
+
+        // Shape transform, then a 1.3× zoom panned by a fraction of a pixel
+        let view = Uniforms::transform_matrix(3.25, -7.6, 1.3, 1.3, 0.0, 0.0);
+        let mut placed = mesh.transformed(&shape_model_transform(&line, &HashMap::new(), &view));
+        let pixel_ratio = 2.0;
+        let on_center = |c: f32| ((c * pixel_ratio - 0.5).fract()).abs() < 1e-3;
+        assert!(!placed.vertices.iter().all(|v| v.position.iter().all(|&c| on_center(c))));
+
+        placed.snap_to_pixels(pixel_ratio);
+        assert_eq!(placed.vertices.len(), mesh.vertices.len());
+        assert_eq!(placed.indices, mesh.indices);
+        // Centers of device pixels, not CSS pixels: half a CSS pixel apart at DPR 2
+        assert!(placed.vertices.iter().all(|v| v.position.iter().all(|&c| on_center(c))));
+    }
+
+    /// `triangles` triangles sharing edges along a strip, as an indexed list
+    fn strip(triangles: usize) -> Mesh {
+        let mut mesh = Mesh::new();
+        for i in 0..triangles + 2 {
+            let x = (i / 2) as f32;
+            let y = (i % 2) as f32;
+            mesh.vertices.push(Vertex::new([x, y], [i as f32, 0.0, 0.0, 1.0]));
+        }
+        for i in 0..triangles as u32 {
+            mesh.indices.extend([i, i + 1, i + 2]);
+        }
+        mesh
+    }
+
+    /// Every triangle as its three vertex positions
+    fn triangles(mesh: &Mesh) -> Vec<[[f32; 2]; 3]> {
+        mesh.indices
+            .chunks(3)
+            .map(|t| [0, 1, 2].map(|k| mesh.vertices[t[k] as usize].position))
+            .collect()
+    }
+
+    #[test]
+    fn test_chunks_split_a_large_mesh_into_self_contained_parts() {
+        // 200,001 indices won't fit one 65,536-vertex draw
+        let mesh = strip(66_667);
+        assert_eq!(mesh.indices.len(), 200_001);
+
+        let chunks = mesh.chunks(65_536);
+        assert_eq!(chunks.len(), 4);
+        for chunk in &chunks {
+            assert!(chunk.indices.len() <= 65_536 && chunk.indices.len() % 3 == 0);
+            assert!(chunk.vertices.len() <= 65_536);
+            assert!(chunk.indices.iter().all(|&i| (i as usize) < chunk.vertices.len()));
+            // Only the vertices the chunk draws come along
+            assert_eq!(chunk.vertices.len(), chunk.indices.len() / 3 + 2);
+        }
+
+        // Drawn in order, the chunks make the same triangles as the whole mesh
+        let drawn: Vec<[[f32; 2]; 3]> = chunks.iter().flat_map(triangles).collect();
+        assert_eq!(drawn, triangles(&mesh));
+    }
+
+    #[test]
+    fn test_chunks_of_a_small_mesh() {
+        let mesh = strip(4);
+        let chunks = mesh.chunks(65_536);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].indices, mesh.indices);
+        assert_eq!(chunks[0].vertices.len(), mesh.vertices.len());
+
+        // A limit below a triangle still keeps triangles whole
+        assert_eq!(mesh.chunks(2).len(), 4);
+        assert!(Mesh::new().chunks(65_536).is_empty());
+    }
+
+    #[test]
+    fn test_surface_projection_matches_orthographic_at_dpr_1() {
+        let scaled = Uniforms::for_surface(800, 600, 1.0);
+        let plain = Uniforms::orthographic(800.0, 600.0);
+        assert_eq!(scaled.view_proj, plain.view_proj);
+
+        // A bogus ratio falls back to 1
+        assert_eq!(Uniforms::for_surface(800, 600, 0.0).view_proj, plain.view_proj);
+    }
+}
@@ -0,0 +1,133 @@
+use crate::clipboard::PASTE_OFFSET;
+use crate::scene::{Shape, ShapeGeometry, ShapeStyle, Transform2D, Vec2};
+
+/// File types that can be dropped onto the canvas as images
+const IMAGE_TYPES: [&str; 2] = ["image/png", "image/jpeg"];
+
+/// Share of the visible canvas a dropped image may cover at most, per axis
+pub const MAX_DROP_FRACTION: f32 = 0.5;
+
+/// Whether a file of MIME type `mime` can become an image shape
+pub fn is_image_type(mime: &str) -> bool {
+    IMAGE_TYPES.contains(&mime.trim().to_ascii_lowercase().as_str())
+}
+
+/// The dropped files that are images, in drop order, from (MIME type, file) pairs
+pub fn image_files<T>(files: impl IntoIterator<Item = (String, T)>) -> Vec<T> {
+    files
+        .into_iter()
+        .filter(|(mime, _)| is_image_type(mime))
+        .map(|(_, file)| file)
+        .collect()
+}
+
+/// Size for an image of `natural` pixels that fits within `max`, keeping its aspect ratio
+/// Images that already fit keep their own size rather than being blown up
+pub fn fit_image_size(natural: Vec2, max: Vec2) -> Vec2 {
+    if natural.x <= 0.0 || natural.y <= 0.0 {
+        return Vec2::ZERO;
+    }
+    let scale = (max.x / natural.x).min(max.y / natural.y).min(1.0);
+    natural * scale
+}
+
+/// Image shape for the `index`th dropped file, centered on `center`
+/// Later files cascade by the paste offset so a multi-file drop doesn't stack exactly
+pub fn dropped_image_shape(src: String, natural: Vec2, max: Vec2, center: Vec2, index: usize) -> Shape {
+    let size = fit_image_size(natural, max);
+    let position = center - size / 2.0 + PASTE_OFFSET * index as f32;
+    Shape::new(ShapeGeometry::image(size.x, size.y, src), ShapeStyle::default())
+        .with_transform(Transform2D::from_position(position))
+}
+
+/// Whether a dropped file is an SVG document, by MIME type or, when the browser
+/// doesn't give one, by file name
+pub fn is_svg_file(mime: &str, name: &str) -> bool {
+    match mime.trim() {
+        "" => name.to_ascii_lowercase().ends_with(".svg"),
+        mime => mime.eq_ignore_ascii_case("image/svg+xml"),
+    }
+}
+
+/// Shapes imported from the `index`th dropped SVG file, moved together so their
+/// combined bounds center on `center`, and cascading like dropped images
+pub fn dropped_svg_shapes(mut shapes: Vec<Shape>, center: Vec2, index: usize) -> Vec<Shape> {
+    let Some(bounds) = shapes.iter().map(Shape::world_bounds).reduce(|a, b| a.union(&b)) else {
+        return shapes;
+    };
+    let offset = center - bounds.center() + PASTE_OFFSET * index as f32;
+    for shape in &mut shapes {
+        shape.transform.position += offset;
+    }
+    shapes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::BBox;
+
+    #[test]
+    fn test_only_png_and_jpeg_files_are_taken_from_a_drop() {
+        let dropped = vec![
+            ("image/png".to_string(), "a.png"),
+            ("image/svg+xml".to_string(), "b.svg"),
+            ("text/plain".to_string(), "c.txt"),
+            ("image/JPEG".to_string(), "d.jpg"),
+            ("".to_string(), "e"),
+        ];
+        assert_eq!(image_files(dropped), vec!["a.png", "d.jpg"]);
+        assert!(image_files(Vec::<(String, ())>::new()).is_empty());
+    }
+
+    #[test]
+    fn test_fit_image_size_preserves_aspect_ratio() {
+        let max = Vec2::new(400.0, 300.0);
+        // Too wide: width limits
+        assert_eq!(fit_image_size(Vec2::new(1600.0, 800.0), max), Vec2::new(400.0, 200.0));
+        // Too tall: height limits
+        assert_eq!(fit_image_size(Vec2::new(600.0, 900.0), max), Vec2::new(200.0, 300.0));
+        // Small images keep their natural size
+        assert_eq!(fit_image_size(Vec2::new(120.0, 80.0), max), Vec2::new(120.0, 80.0));
+        assert_eq!(fit_image_size(Vec2::new(0.0, 80.0), max), Vec2::ZERO);
+    }
+
+    #[test]
+    fn test_dropped_images_center_on_the_drop_point_and_cascade() {
+        let max = Vec2::new(400.0, 300.0);
+        let first = dropped_image_shape("data:a".into(), Vec2::new(800.0, 600.0), max, Vec2::new(500.0, 400.0), 0);
+        assert!(matches!(&first.geometry, ShapeGeometry::Image { width, height, src }
+            if *width == 400.0 && *height == 300.0 && src == "data:a"));
+        assert_eq!(first.transform.position, Vec2::new(300.0, 250.0));
+        assert_eq!(first.world_bounds().center(), Vec2::new(500.0, 400.0));
+
+        let second = dropped_image_shape("data:b".into(), Vec2::new(800.0, 600.0), max, Vec2::new(500.0, 400.0), 1);
+        assert_eq!(second.transform.position, Vec2::new(300.0, 250.0) + PASTE_OFFSET);
+    }
+
+    #[test]
+    fn test_svg_files_are_recognized_by_type_or_name() {
+        assert!(is_svg_file("image/svg+xml", "logo"));
+        assert!(is_svg_file("", "Logo.SVG"));
+        assert!(!is_svg_file("text/plain", "notes.svg"));
+        assert!(!is_svg_file("image/png", "a.png"));
+    }
+
+    #[test]
+    fn test_dropped_svg_shapes_center_together() {
+        let shapes = vec![
+            Shape::new(ShapeGeometry::rectangle(10.0, 10.0), ShapeStyle::default()),
+            Shape::new(ShapeGeometry::rectangle(10.0, 10.0), ShapeStyle::default())
+                .with_transform(Transform2D::from_position(Vec2::new(30.0, 10.0))),
+        ];
+        let dropped = dropped_svg_shapes(shapes, Vec2::new(100.0, 100.0), 0);
+        assert_eq!(dropped[0].transform.position, Vec2::new(80.0, 90.0));
+        assert_eq!(dropped[1].transform.position, Vec2::new(110.0, 100.0));
+        let bounds: BBox = dropped.iter().map(Shape::world_bounds).reduce(|a, b| a.union(&b)).unwrap();
+        assert_eq!(bounds.center(), Vec2::new(100.0, 100.0));
+
+        let cascaded = dropped_svg_shapes(dropped, Vec2::new(100.0, 100.0), 2);
+        assert_eq!(cascaded[0].transform.position, Vec2::new(80.0, 90.0) + PASTE_OFFSET * 2.0);
+        assert!(dropped_svg_shapes(Vec::new(), Vec2::ZERO, 0).is_empty());
+    }
+}
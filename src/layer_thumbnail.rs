@@ -0,0 +1,283 @@
+use std::collections::HashMap;
+
+use yew::AttrValue;
+
+use crate::components::{path_to_svg_d, points_to_svg};
+use crate::scene::{BBox, Shape, ShapeGeometry, ShapeStyle, Vec2};
+
+/// Edge length of a layer row thumbnail in pixels
+pub const THUMBNAIL_SIZE: f32 = 24.0;
+/// Empty margin kept around the shape inside the thumbnail
+const THUMBNAIL_PADDING: f32 = 2.0;
+/// Size of a saved version's scene preview in pixels
+pub const SNAPSHOT_WIDTH: f32 = 160.0;
+pub const SNAPSHOT_HEIGHT: f32 = 120.0;
+/// Empty margin kept around the content inside a scene preview
+const SNAPSHOT_PADDING: f32 = 8.0;
+
+/// Scale and offset that fit `bounds` inside a `size` square, centered, keeping its aspect ratio
+/// Degenerate bounds (a point, or a horizontal/vertical line) scale along the axis that has extent
+pub fn fit_to_box(bounds: &BBox, size: f32, padding: f32) -> (f32, Vec2) {
+    fit_to_rect(bounds, Vec2::splat(size), padding)
+}
+
+/// Like `fit_to_box`, for a `size.x` by `size.y` rectangle
+pub fn fit_to_rect(bounds: &BBox, size: Vec2, padding: f32) -> (f32, Vec2) {
+    let extent = Vec2::new(bounds.width(), bounds.height());
+    let available = size - Vec2::splat(2.0 * padding);
+    let scale = [(extent.x, available.x), (extent.y, available.y)]
+        .into_iter()
+        .filter(|(extent, _)| *extent > 0.0)
+        .map(|(extent, available)| available / extent)
+        .reduce(f32::min)
+        .unwrap_or(1.0);
+    let offset = (size - extent * scale) / 2.0 - bounds.min * scale;
+    (scale, offset)
+}
+
+/// Geometry element for the thumbnail, in the shape's local coordinates
+fn geometry_markup(geometry: &ShapeGeometry) -> String {
+    match geometry {
+        ShapeGeometry::Polygon { .. } | ShapeGeometry::Star { .. } | ShapeGeometry::RegularPolygon { .. } => {
+            let points = points_to_svg(&geometry.ring_points().unwrap_or_default());
+            format!(r#"<polygon points="{points}" vector-effect="non-scaling-stroke"/>"#)
+        }
+        ShapeGeometry::Rectangle { width, height, corner_radii } => match corner_radii.as_uniform() {
+            Some(radius) => format!(r#"<rect width="{width}" height="{height}" rx="{radius}" vector-effect="non-scaling-stroke"/>"#),
+            None => format!(
+                r#"<path d="{}" vector-effect="non-scaling-stroke"/>"#,
+                path_to_svg_d(&corner_radii.outline(*width, *height))
+            ),
+        },
+        ShapeGeometry::Ellipse { rx, ry } => format!(r#"<ellipse rx="{rx}" ry="{ry}" vector-effect="non-scaling-stroke"/>"#),
+        ShapeGeometry::Line { start, end } => format!(
+            r#"<line x1="{}" y1="{}" x2="{}" y2="{}" vector-effect="non-scaling-stroke"/>"#,
+            start.x, start.y, end.x, end.y
+        ),
+        ShapeGeometry::Path { commands } => format!(r#"<path d="{}" vector-effect="non-scaling-stroke"/>"#, path_to_svg_d(commands)),
+        ShapeGeometry::Text { content, font_size, font_family, align } => format!(
+            r#"<text font-size="{font_size}" font-family="{}" text-anchor="{}" stroke="none">{}</text>"#,
+            escape_xml(font_family),
+            align.to_svg(),
+            escape_xml(content)
+        ),
+        ShapeGeometry::Image { width, height, src } => format!(
+            r#"<image width="{width}" height="{height}" href="{}" preserveAspectRatio="none"/>"#,
+            escape_xml(src)
+        ),
+    }
+}
+
+/// Escape text for use inside markup and attribute values
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Inline SVG markup previewing a shape's geometry and colors, scaled to fit the thumbnail
+/// Strokes are drawn 1px wide at any scale so thin outlines stay visible
+pub fn thumbnail_markup(geometry: &ShapeGeometry, style: &ShapeStyle) -> String {
+    let (scale, offset) = fit_to_box(&geometry.local_bounds(), THUMBNAIL_SIZE, THUMBNAIL_PADDING);
+
+    format!(
+        concat!(
+            r#"<svg width="{size}" height="{size}" viewBox="0 0 {size} {size}">"#,
+            r#"<g transform="translate({x} {y}) scale({scale})" {paint}>{element}</g></svg>"#,
+        ),
+        size = THUMBNAIL_SIZE,
+        x = offset.x,
+        y = offset.y,
+        scale = scale,
+        paint = paint_attributes(geometry, style),
+        element = geometry_markup(geometry),
+    )
+}
+
+/// Inline SVG markup previewing a scene snapshot: the visible shapes at their positions,
+/// scaled together to fit `SNAPSHOT_WIDTH` x `SNAPSHOT_HEIGHT`
+/// A snapshot with nothing visible gives a blank preview of the same size
+pub fn snapshot_markup(shapes: &[Shape]) -> String {
+    let visible: Vec<&Shape> = shapes.iter().filter(|shape| shape.visible).collect();
+    let size = Vec2::new(SNAPSHOT_WIDTH, SNAPSHOT_HEIGHT);
+    let (scale, offset) = visible
+        .iter()
+        .map(|shape| shape.world_bounds())
+        .reduce(|a, b| a.union(&b))
+        .map_or((1.0, Vec2::ZERO), |bounds| fit_to_rect(&bounds, size, SNAPSHOT_PADDING));
+
+    let elements: String = visible
+        .iter()
+        .map(|shape| {
+            let m = shape.transform.to_matrix4();
+            format!(
+                r#"<g transform="matrix({} {} {} {} {} {})" {}>{}</g>"#,
+                m[0][0],
+                m[0][1],
+                m[1][0],
+                m[1][1],
+                m[3][0],
+                m[3][1],
+                paint_attributes(&shape.geometry, &shape.style),
+                geometry_markup(&shape.geometry),
+            )
+        })
+        .collect();
+
+    format!(
+        concat!(
+            r#"<svg width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#,
+            r#"<g transform="translate({x} {y}) scale({scale})">{elements}</g></svg>"#,
+        ),
+        width = SNAPSHOT_WIDTH,
+        height = SNAPSHOT_HEIGHT,
+        x = offset.x,
+        y = offset.y,
+        scale = scale,
+        elements = elements,
+    )
+}
+
+/// Fill and stroke attributes previewing `style`; strokes are drawn 1px wide
+fn paint_attributes(geometry: &ShapeGeometry, style: &ShapeStyle) -> String {
+    // Gradients are previewed by their first stop
+    let fill = match (geometry, style.fill_color()) {
+        (ShapeGeometry::Line { .. }, _) | (_, None) => "none".to_string(),
+        (_, Some(color)) => color.to_hex(),
+    };
+    let fill_opacity = style.fill_color().map_or(1.0, |c| c.a);
+    // Lines without a stroke would be invisible, so they fall back to gray
+    let stroke = match (&style.stroke, geometry) {
+        (Some(stroke), _) => stroke.color.to_hex(),
+        (None, ShapeGeometry::Line { .. }) => "#9ca3af".to_string(),
+        (None, _) => "none".to_string(),
+    };
+
+    format!(r#"fill="{fill}" fill-opacity="{fill_opacity}" stroke="{stroke}" stroke-width="1""#)
+}
+
+struct CachedThumbnail {
+    geometry: ShapeGeometry,
+    style: ShapeStyle,
+    markup: AttrValue,
+}
+
+/// Thumbnail markup per shape id, so re-rendering the layers panel doesn't rebuild every preview
+#[derive(Default)]
+pub struct ThumbnailCache {
+    entries: HashMap<u64, CachedThumbnail>,
+}
+
+impl ThumbnailCache {
+    /// Markup for `shape`, regenerated only when the shape is dirty
+    /// The scene keeps dirty flags set until it is cleared, so a dirty shape is only
+    /// re-rendered when its geometry or style actually differs from the cached preview
+    pub fn get(&mut self, shape: &Shape) -> AttrValue {
+        let stale = match self.entries.get(&shape.id) {
+            Some(cached) => shape.dirty && (cached.geometry != shape.geometry || cached.style != shape.style),
+            None => true,
+        };
+        if stale {
+            let markup = AttrValue::from(thumbnail_markup(&shape.geometry, &shape.style));
+            self.entries.insert(shape.id, CachedThumbnail {
+                geometry: shape.geometry.clone(),
+                style: shape.style.clone(),
+                markup,
+            });
+        }
+        self.entries[&shape.id].markup.clone()
+    }
+
+    /// Drop entries for shapes that no longer exist
+    pub fn retain(&mut self, shapes: &[Shape]) {
+        self.entries.retain(|id, _| shapes.iter().any(|shape| shape.id == *id));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::{parse_svg_path, Color, Transform2D};
+
+    #[test]
+    fn test_fit_wide_bounds_centers_vertically() {
+        let bounds = BBox::new(Vec2::new(10.0, 10.0), Vec2::new(50.0, 30.0));
+        let (scale, offset) = fit_to_box(&bounds, 24.0, 2.0);
+
+        assert_eq!(scale, 0.5);
+        // Top-left lands at the padding horizontally and is centered vertically
+        assert_eq!(bounds.min * scale + offset, Vec2::new(2.0, 7.0));
+        assert_eq!(bounds.max * scale + offset, Vec2::new(22.0, 17.0));
+    }
+
+    #[test]
+    fn test_fit_degenerate_bounds() {
+        // A point stays unscaled and is centered
+        let point = BBox::new(Vec2::new(5.0, 5.0), Vec2::new(5.0, 5.0));
+        let (scale, offset) = fit_to_box(&point, 24.0, 2.0);
+        assert_eq!(scale, 1.0);
+        assert_eq!(point.min + offset, Vec2::new(12.0, 12.0));
+
+        // A horizontal line fills the width
+        let line = BBox::new(Vec2::new(0.0, 0.0), Vec2::new(100.0, 0.0));
+        let (scale, offset) = fit_to_box(&line, 24.0, 2.0);
+        assert_eq!(line.max * scale + offset, Vec2::new(22.0, 12.0));
+    }
+
+    #[test]
+    fn test_path_thumbnail_serializes_commands() {
+        let geometry = ShapeGeometry::Path { commands: parse_svg_path("M0 0 L10 0 L10 10 Z") };
+        let markup = thumbnail_markup(&geometry, &ShapeStyle::fill_only(Color::black()));
+
+        assert!(markup.contains(r#"<path d="M0 0 L10 0 L10 10 Z""#));
+        assert!(markup.contains(r##"fill="#000000""##));
+    }
+
+    #[test]
+    fn test_snapshot_places_shapes_in_one_frame() {
+        let left = Shape::new(ShapeGeometry::rectangle(40.0, 40.0), ShapeStyle::fill_only(Color::black()))
+            .with_transform(Transform2D::from_position(Vec2::new(0.0, 0.0)));
+        let right = Shape::new(ShapeGeometry::circle(20.0), ShapeStyle::fill_only(Color::rgb(1.0, 0.0, 0.0)))
+            .with_transform(Transform2D::from_position(Vec2::new(300.0, 0.0)));
+        let mut hidden = Shape::new(ShapeGeometry::rectangle(10.0, 10.0), ShapeStyle::default())
+            .with_transform(Transform2D::from_position(Vec2::new(5000.0, 5000.0)));
+        hidden.visible = false;
+
+        let markup = snapshot_markup(&[left, right, hidden]);
+        assert!(markup.starts_with(r#"<svg width="160" height="120" viewBox="0 0 160 120">"#));
+        // Each shape keeps its own position and colors; the hidden one isn't drawn
+        assert!(markup.contains(r#"matrix(1 0 0 1 300 0)"#));
+        assert!(markup.contains(r##"fill="#000000""##) && markup.contains(r##"fill="#ff0000""##));
+        assert_eq!(markup.matches("<rect").count(), 1);
+
+        // Together they span 320x60 world units, so the 144px available width limits the scale
+        assert!(markup.contains("scale(0.45)"), "{markup}");
+    }
+
+    #[test]
+    fn test_empty_snapshot_is_a_blank_preview() {
+        for markup in [snapshot_markup(&[]), snapshot_markup(&[{
+            let mut shape = Shape::new(ShapeGeometry::rectangle(10.0, 10.0), ShapeStyle::default());
+            shape.visible = false;
+            shape
+        }])] {
+            assert_eq!(markup, r#"<svg width="160" height="120" viewBox="0 0 160 120"><g transform="translate(0 0) scale(1)"></g></svg>"#);
+        }
+    }
+
+    #[test]
+    fn test_cache_regenerates_only_on_change() {
+        let mut shape = Shape::new(ShapeGeometry::rectangle(10.0, 20.0), ShapeStyle::default());
+        let mut cache = ThumbnailCache::default();
+
+        let first = cache.get(&shape);
+        assert_eq!(cache.get(&shape), first);
+
+        shape.geometry = ShapeGeometry::rectangle(30.0, 20.0);
+        assert_ne!(cache.get(&shape), first);
+
+        cache.retain(&[]);
+        assert!(cache.entries.is_empty());
+    }
+}
@@ -1,8 +1,12 @@
+use std::collections::HashMap;
+
 use wasm_bindgen::JsCast;
-use web_sys::{FocusEvent, HtmlInputElement, KeyboardEvent};
+use web_sys::{DragEvent, Element, FocusEvent, HtmlInputElement, KeyboardEvent};
 use yew::prelude::*;
 
-use crate::scene::{LayerNode, LayerTree};
+use crate::layer_thumbnail::ThumbnailCache;
+use crate::minimap::Minimap;
+use crate::scene::{DropPosition, LayerKey, LayerNode, LayerTree, Shape};
 
 /// Shape type for icon display
 #[derive(Clone, PartialEq, Debug)]
@@ -11,7 +15,11 @@ pub enum ShapeType {
     Ellipse,
     Circle,
     Polygon,
+    Star,
     Path,
+    Line,
+    Text,
+    Image,
 }
 
 /// Represents a shape in the layers panel
@@ -20,6 +28,8 @@ pub struct ShapeInfo {
     pub id: u64,
     pub name: String,
     pub shape_type: ShapeType,
+    pub visible: bool,
+    pub locked: bool,
 }
 
 #[derive(Properties, PartialEq)]
@@ -34,9 +44,23 @@ pub struct LayersPanelProps {
     #[prop_or_default]
     pub on_toggle_expand: Option<Callback<u64>>,
     #[prop_or_default]
+    pub on_toggle_visible: Option<Callback<u64>>,
+    #[prop_or_default]
+    pub on_toggle_locked: Option<Callback<u64>>,
+    #[prop_or_default]
     pub on_group: Option<Callback<()>>,
     #[prop_or_default]
     pub on_ungroup: Option<Callback<u64>>,
+    /// Callback when a row is dropped: (dragged node, drop target, position)
+    #[prop_or_default]
+    pub on_reorder: Option<Callback<(LayerKey, LayerKey, DropPosition)>>,
+    /// Shapes drawn in the minimap preview, in paint order
+    #[prop_or_default]
+    pub preview_shapes: Vec<Shape>,
+    #[prop_or(800.0)]
+    pub canvas_width: f64,
+    #[prop_or(600.0)]
+    pub canvas_height: f64,
 }
 
 /// Render a minimalist icon based on shape type
@@ -62,11 +86,32 @@ fn render_shape_icon(shape_type: &ShapeType) -> Html {
                 <path d="M8 2L14 13H2L8 2Z" stroke="currentColor" stroke-width="1.5" stroke-linejoin="round"/>
             </svg>
         },
+        ShapeType::Star => html! {
+            <svg width="16" height="16" viewBox="0 0 16 16" fill="none" class="text-gray-500">
+                <path d="M8 2L9.5 6.2L14 6.3L10.4 9L11.7 13.4L8 10.8L4.3 13.4L5.6 9L2 6.3L6.5 6.2Z" stroke="currentColor" stroke-width="1.2" stroke-linejoin="round"/>
+            </svg>
+        },
         ShapeType::Path => html! {
             <svg width="16" height="16" viewBox="0 0 16 16" fill="none" class="text-gray-500">
                 <path d="M2 12C4 4 12 4 14 12" stroke="currentColor" stroke-width="1.5" stroke-linecap="round"/>
             </svg>
         },
+        ShapeType::Line => html! {
+            <svg width="16" height="16" viewBox="0 0 16 16" fill="none" class="text-gray-500">
+                <path d="M3 13L13 3" stroke="currentColor" stroke-width="1.5" stroke-linecap="round"/>
+            </svg>
+        },
+        ShapeType::Text => html! {
+            <svg width="16" height="16" viewBox="0 0 16 16" fill="none" class="text-gray-500">
+                <path d="M3 3H13M8 3V13" stroke="currentColor" stroke-width="1.5" stroke-linecap="round"/>
+            </svg>
+        },
+        ShapeType::Image => html! {
+            <svg width="16" height="16" viewBox="0 0 16 16" fill="none" class="text-gray-500">
+                <rect x="2" y="3" width="12" height="10" rx="1" stroke="currentColor" stroke-width="1.5"/>
+                <path d="M2 11L6 7L10 11M9 10L11 8L14 11" stroke="currentColor" stroke-width="1.2" stroke-linejoin="round"/>
+            </svg>
+        },
     };
     icon
 }
@@ -80,6 +125,54 @@ fn render_group_icon() -> Html {
     }
 }
 
+/// Render the eye icon for the visibility toggle (struck through when hidden)
+fn render_visibility_icon(visible: bool) -> Html {
+    html! {
+        <svg width="14" height="14" viewBox="0 0 16 16" fill="none">
+            <path d="M1.5 8C3 4.5 5.5 3 8 3C10.5 3 13 4.5 14.5 8C13 11.5 10.5 13 8 13C5.5 13 3 11.5 1.5 8Z" stroke="currentColor" stroke-width="1.5"/>
+            <circle cx="8" cy="8" r="2" stroke="currentColor" stroke-width="1.5"/>
+            if !visible {
+                <path d="M2 14L14 2" stroke="currentColor" stroke-width="1.5" stroke-linecap="round"/>
+            }
+        </svg>
+    }
+}
+
+/// Render the padlock icon for the lock toggle (shackle open when unlocked)
+fn render_lock_icon(locked: bool) -> Html {
+    let shackle = if locked { "M5 7V5C5 3.34 6.34 2 8 2C9.66 2 11 3.34 11 5V7" } else { "M5 7V5C5 3.34 6.34 2 8 2C9.3 2 10.4 2.8 10.8 4" };
+    html! {
+        <svg width="14" height="14" viewBox="0 0 16 16" fill="none">
+            <rect x="3" y="7" width="10" height="7" rx="1" stroke="currentColor" stroke-width="1.5"/>
+            <path d={shackle} stroke="currentColor" stroke-width="1.5" stroke-linecap="round"/>
+        </svg>
+    }
+}
+
+/// Small icon button on a layer row; doesn't select the row when clicked
+fn render_row_toggle(testid: &str, title: &str, active: bool, icon: Html, on_click: Option<Callback<u64>>, shape_id: u64) -> Html {
+    let onclick = Callback::from(move |e: MouseEvent| {
+        e.stop_propagation();
+        if let Some(callback) = &on_click {
+            callback.emit(shape_id);
+        }
+    });
+    html! {
+        <button
+            data-testid={format!("{}-{}", testid, shape_id)}
+            data-active={active.to_string()}
+            title={title.to_string()}
+            {onclick}
+            class={classes!(
+                "w-5", "h-5", "flex", "items-center", "justify-center", "rounded", "hover:bg-gray-200",
+                if active { "text-gray-700" } else { "text-gray-300" }
+            )}
+        >
+            {icon}
+        </button>
+    }
+}
+
 /// Individual layer item component with inline editing
 #[derive(Properties, PartialEq)]
 struct LayerItemProps {
@@ -91,6 +184,10 @@ struct LayerItemProps {
     pub select_ids: Vec<u64>,
     pub on_select: Callback<Vec<u64>>,
     pub on_rename: Option<Callback<(u64, String)>>,
+    pub on_toggle_visible: Option<Callback<u64>>,
+    pub on_toggle_locked: Option<Callback<u64>>,
+    /// Inline SVG preview of the shape; the type icon is shown without one
+    pub thumbnail: Option<AttrValue>,
 }
 
 #[function_component(LayerItem)]
@@ -140,13 +237,17 @@ fn layer_item(props: &LayerItemProps) -> Html {
     let on_rename = props.on_rename.clone();
     let shape_id = props.shape_id;
 
+    // A blank name is rejected: the row goes back to its current name
     let finish_edit = {
         let editing = editing.clone();
         let edit_value = edit_value.clone();
         let on_rename = on_rename.clone();
+        let original_name = props.shape.name.clone();
         Callback::from(move |_| {
-            if let Some(ref callback) = on_rename {
-                callback.emit((shape_id, (*edit_value).clone()));
+            let name = edit_value.trim().to_string();
+            match on_rename {
+                Some(ref callback) if !name.is_empty() => callback.emit((shape_id, name)),
+                _ => edit_value.set(original_name.clone()),
             }
             editing.set(false);
         })
@@ -203,14 +304,21 @@ fn layer_item(props: &LayerItemProps) -> Html {
                 if props.is_selected { "bg-blue-50 border-blue-300" } else { "bg-white border-gray-200" }
             )}
         >
-            <div class="flex items-center justify-center flex-shrink-0">
-                {render_shape_icon(&props.shape.shape_type)}
+            <div
+                data-testid={format!("layer-thumbnail-{}", shape_id)}
+                class="w-6 h-6 flex items-center justify-center flex-shrink-0 rounded border border-gray-200 bg-white"
+            >
+                {match &props.thumbnail {
+                    Some(markup) => Html::from_html_unchecked(markup.clone()),
+                    None => render_shape_icon(&props.shape.shape_type),
+                }}
             </div>
             {
                 if *editing {
                     html! {
                         <input
                             type="text"
+                            data-testid={format!("layer-name-input-{}", shape_id)}
                             class="text-sm flex-1 px-1 py-0 border border-blue-400 rounded outline-none focus:ring-1 focus:ring-blue-400"
                             value={(*edit_value).clone()}
                             {oninput}
@@ -221,12 +329,20 @@ fn layer_item(props: &LayerItemProps) -> Html {
                     }
                 } else {
                     html! {
-                        <span class="text-sm flex-1 truncate" ondblclick={ondblclick}>
+                        <span
+                            data-testid={format!("layer-name-{}", shape_id)}
+                            class={classes!("text-sm", "flex-1", "truncate", (!props.shape.visible).then_some("text-gray-400"))}
+                            ondblclick={ondblclick}
+                        >
                             {&props.shape.name}
                         </span>
                     }
                 }
             }
+            {render_row_toggle("toggle-locked", "Lock", props.shape.locked, render_lock_icon(props.shape.locked),
+                props.on_toggle_locked.clone(), shape_id)}
+            {render_row_toggle("toggle-visible", "Show/hide", props.shape.visible, render_visibility_icon(props.shape.visible),
+                props.on_toggle_visible.clone(), shape_id)}
         </div>
     }
 }
@@ -302,13 +418,17 @@ fn group_header(props: &GroupHeaderProps) -> Html {
 
     let on_rename = props.on_rename.clone();
 
+    // A blank name is rejected: the row goes back to its current name
     let finish_edit = {
         let editing = editing.clone();
         let edit_value = edit_value.clone();
         let on_rename = on_rename.clone();
+        let original_name = props.name.clone();
         Callback::from(move |_| {
-            if let Some(ref callback) = on_rename {
-                callback.emit((group_id, (*edit_value).clone()));
+            let name = edit_value.trim().to_string();
+            match on_rename {
+                Some(ref callback) if !name.is_empty() => callback.emit((group_id, name)),
+                _ => edit_value.set(original_name.clone()),
             }
             editing.set(false);
         })
@@ -394,6 +514,7 @@ fn group_header(props: &GroupHeaderProps) -> Html {
                     html! {
                         <input
                             type="text"
+                            data-testid={format!("group-name-input-{}", group_id)}
                             class="text-sm flex-1 px-1 py-0 border border-blue-400 rounded outline-none focus:ring-1 focus:ring-blue-400 font-medium"
                             value={(*edit_value).clone()}
                             {oninput}
@@ -404,7 +525,11 @@ fn group_header(props: &GroupHeaderProps) -> Html {
                     }
                 } else {
                     html! {
-                        <span class="text-sm flex-1 font-medium truncate" ondblclick={ondblclick}>
+                        <span
+                            data-testid={format!("group-name-{}", group_id)}
+                            class="text-sm flex-1 font-medium truncate"
+                            ondblclick={ondblclick}
+                        >
                             {&props.name}
                         </span>
                     }
@@ -414,103 +539,341 @@ fn group_header(props: &GroupHeaderProps) -> Html {
     }
 }
 
-/// Render layer nodes recursively
+/// One visible row of the layers panel
+#[derive(Clone, PartialEq, Debug)]
+pub enum LayerRow {
+    Group {
+        id: u64,
+        name: String,
+        expanded: bool,
+        depth: usize,
+        /// All shape IDs in this group (for selection)
+        shape_ids: Vec<u64>,
+    },
+    Shape {
+        shape_id: u64,
+        depth: usize,
+        /// Shape IDs to select when the row is clicked (the enclosing group's, if any)
+        select_ids: Vec<u64>,
+    },
+}
+
+/// Flatten the layer tree into visible rows, skipping children of collapsed groups
+pub fn flatten_rows(layer_tree: &LayerTree) -> Vec<LayerRow> {
+    let mut rows = Vec::new();
+    push_rows(layer_tree, &layer_tree.nodes, 0, None, &mut rows);
+    rows
+}
+
 /// parent_group_ids: shape IDs from parent group (for selection inheritance)
-fn render_nodes(
+fn push_rows(
+    layer_tree: &LayerTree,
     nodes: &[LayerNode],
-    shapes: &std::collections::HashMap<u64, ShapeInfo>,
-    selected_ids: &[u64],
     depth: usize,
-    parent_group_ids: Option<Vec<u64>>,
-    on_select: &Callback<Vec<u64>>,
-    on_rename: &Option<Callback<(u64, String)>>,
-    on_toggle_expand: &Option<Callback<u64>>,
-) -> Html {
-    nodes.iter().map(|node| {
+    parent_group_ids: Option<&[u64]>,
+    rows: &mut Vec<LayerRow>,
+) {
+    for node in nodes {
         match node {
             LayerNode::Shape { shape_id } => {
-                if let Some(shape) = shapes.get(shape_id) {
-                    let is_selected = selected_ids.contains(shape_id);
-                    // If this shape is inside a group, clicking it selects the whole group
-                    let select_ids = parent_group_ids.clone().unwrap_or_else(|| vec![*shape_id]);
-                    html! {
-                        <LayerItem
-                            shape_id={*shape_id}
-                            shape={shape.clone()}
-                            {is_selected}
-                            {depth}
-                            {select_ids}
-                            on_select={on_select.clone()}
-                            on_rename={on_rename.clone()}
-                        />
-                    }
-                } else {
-                    html! {}
+                // If this shape is inside a group, clicking it selects the whole group
+                let select_ids = parent_group_ids.map_or_else(|| vec![*shape_id], <[u64]>::to_vec);
+                rows.push(LayerRow::Shape { shape_id: *shape_id, depth, select_ids });
+            }
+            LayerNode::Group { id, name, children, expanded, .. } => {
+                let shape_ids = layer_tree.get_group_shape_ids(*id);
+                rows.push(LayerRow::Group {
+                    id: *id,
+                    name: name.clone(),
+                    expanded: *expanded,
+                    depth,
+                    shape_ids: shape_ids.clone(),
+                });
+                if *expanded {
+                    push_rows(layer_tree, children, depth + 1, Some(&shape_ids), rows);
                 }
             }
-            LayerNode::Group { id, name, children, expanded } => {
-                let group_shape_ids = node.all_shape_ids();
-                let is_selected = group_shape_ids.iter().any(|id| selected_ids.contains(id));
+        }
+    }
+}
 
-                let on_toggle = on_toggle_expand.clone().unwrap_or_else(|| {
-                    Callback::from(|_: u64| {})
-                });
+impl LayerRow {
+    fn key(&self) -> LayerKey {
+        match self {
+            LayerRow::Group { id, .. } => LayerKey::Group(*id),
+            LayerRow::Shape { shape_id, .. } => LayerKey::Shape(*shape_id),
+        }
+    }
+}
 
-                html! {
-                    <>
-                        <GroupHeader
-                            group_id={*id}
-                            name={name.clone()}
-                            expanded={*expanded}
-                            {is_selected}
-                            {depth}
-                            group_shape_ids={group_shape_ids.clone()}
-                            on_toggle={on_toggle}
-                            on_select={on_select.clone()}
-                            on_rename={on_rename.clone()}
-                        />
-                        {
-                            if *expanded {
-                                render_nodes(
-                                    children,
-                                    shapes,
-                                    selected_ids,
-                                    depth + 1,
-                                    Some(group_shape_ids),
-                                    on_select,
-                                    on_rename,
-                                    on_toggle_expand,
-                                )
-                            } else {
-                                html! {}
-                            }
-                        }
-                    </>
+/// Drag-and-drop state shared by all rows of the panel
+#[derive(Clone)]
+struct RowDrag {
+    dragging: UseStateHandle<Option<LayerKey>>,
+    drop_target: UseStateHandle<Option<(LayerKey, DropPosition)>>,
+    on_reorder: Option<Callback<(LayerKey, LayerKey, DropPosition)>>,
+}
+
+impl RowDrag {
+    fn clear(&self) {
+        self.dragging.set(None);
+        self.drop_target.set(None);
+    }
+}
+
+/// Drop position from the pointer's height within a row: groups take drops
+/// in their middle half, everything else splits into before/after
+fn drop_position(e: &DragEvent, is_group: bool) -> Option<DropPosition> {
+    let row = e.current_target()?.dyn_into::<Element>().ok()?;
+    let rect = row.get_bounding_client_rect();
+    if rect.height() <= 0.0 {
+        return None;
+    }
+    let fraction = (e.client_y() as f64 - rect.top()) / rect.height();
+    Some(match (is_group, fraction) {
+        (true, f) if f < 0.25 => DropPosition::Before,
+        (true, f) if f > 0.75 => DropPosition::After,
+        (true, _) => DropPosition::Inside,
+        (false, f) if f < 0.5 => DropPosition::Before,
+        (false, _) => DropPosition::After,
+    })
+}
+
+/// Wrap a row so it can be dragged and dropped onto, drawing the drop indicator
+fn render_draggable(key: LayerKey, content: Html, drag: &RowDrag) -> Html {
+    let is_group = matches!(key, LayerKey::Group(_));
+    let indicator = (*drag.drop_target).filter(|(target, _)| *target == key).map(|(_, position)| position);
+
+    let ondragstart = {
+        let drag = drag.clone();
+        Callback::from(move |e: DragEvent| {
+            if let Some(data) = e.data_transfer() {
+                // Firefox won't start a drag without data
+                let _ = data.set_data("text/plain", "layer");
+                data.set_effect_allowed("move");
+            }
+            drag.dragging.set(Some(key));
+        })
+    };
+
+    let ondragover = {
+        let drag = drag.clone();
+        Callback::from(move |e: DragEvent| {
+            if drag.dragging.is_none() || *drag.dragging == Some(key) {
+                return;
+            }
+            // Allow dropping here
+            e.prevent_default();
+            if let Some(position) = drop_position(&e, is_group) {
+                if *drag.drop_target != Some((key, position)) {
+                    drag.drop_target.set(Some((key, position)));
                 }
             }
+        })
+    };
+
+    let ondrop = {
+        let drag = drag.clone();
+        Callback::from(move |e: DragEvent| {
+            e.prevent_default();
+            if let (Some(node), Some((target, position)), Some(on_reorder)) =
+                (*drag.dragging, *drag.drop_target, drag.on_reorder.as_ref())
+            {
+                on_reorder.emit((node, target, position));
+            }
+            drag.clear();
+        })
+    };
+
+    let ondragend = {
+        let drag = drag.clone();
+        Callback::from(move |_: DragEvent| drag.clear())
+    };
+
+    let line_class = match indicator {
+        Some(DropPosition::Before) => Some("top-0"),
+        Some(DropPosition::After) => Some("bottom-0"),
+        _ => None,
+    };
+
+    html! {
+        <div
+            key={format!("{:?}", key)}
+            draggable="true"
+            {ondragstart}
+            {ondragover}
+            {ondrop}
+            {ondragend}
+            class={classes!(
+                "relative",
+                (indicator == Some(DropPosition::Inside)).then_some("ring-2 ring-blue-400 rounded"),
+                (*drag.dragging == Some(key)).then_some("opacity-50"),
+            )}
+        >
+            if let Some(line_class) = line_class {
+                <div data-testid="drop-indicator" class={classes!("absolute", "left-0", "right-0", "h-0.5", "bg-blue-500", "pointer-events-none", line_class)} />
+            }
+            {content}
+        </div>
+    }
+}
+
+fn render_row(
+    row: &LayerRow,
+    props: &LayersPanelProps,
+    thumbnails: &HashMap<u64, AttrValue>,
+    drag: &RowDrag,
+) -> Html {
+    let content = match row {
+        LayerRow::Shape { shape_id, depth, select_ids } => {
+            let Some(shape) = props.shapes.get(shape_id) else {
+                return html! {};
+            };
+            html! {
+                <LayerItem
+                    key={shape_id.to_string()}
+                    shape_id={*shape_id}
+                    shape={shape.clone()}
+                    is_selected={props.selected_ids.contains(shape_id)}
+                    depth={*depth}
+                    select_ids={select_ids.clone()}
+                    on_select={props.on_select.clone()}
+                    on_rename={props.on_rename.clone()}
+                    on_toggle_visible={props.on_toggle_visible.clone()}
+                    on_toggle_locked={props.on_toggle_locked.clone()}
+                    thumbnail={thumbnails.get(shape_id).cloned()}
+                />
+            }
+        }
+        LayerRow::Group { id, name, expanded, depth, shape_ids } => {
+            let is_selected = shape_ids.iter().any(|id| props.selected_ids.contains(id));
+            let on_toggle = props.on_toggle_expand.clone().unwrap_or_else(|| {
+                Callback::from(|_: u64| {})
+            });
+
+            html! {
+                <GroupHeader
+                    key={format!("group-{}", id)}
+                    group_id={*id}
+                    name={name.clone()}
+                    expanded={*expanded}
+                    {is_selected}
+                    depth={*depth}
+                    group_shape_ids={shape_ids.clone()}
+                    on_toggle={on_toggle}
+                    on_select={props.on_select.clone()}
+                    on_rename={props.on_rename.clone()}
+                />
+            }
         }
-    }).collect::<Html>()
+    };
+    render_draggable(row.key(), content, drag)
 }
 
 #[function_component(LayersPanel)]
 pub fn layers_panel(props: &LayersPanelProps) -> Html {
+    // The group to dissolve when the selection is exactly one group
+    let selected_group = props.layer_tree.group_for_selection(&props.selected_ids);
+    let drag = RowDrag {
+        dragging: use_state(|| None),
+        drop_target: use_state(|| None),
+        on_reorder: props.on_reorder.clone(),
+    };
+
+    // Thumbnails are cached across renders and only rebuilt for shapes that changed
+    let thumbnail_cache = use_mut_ref(ThumbnailCache::default);
+    let thumbnails: HashMap<u64, AttrValue> = {
+        let mut cache = thumbnail_cache.borrow_mut();
+        cache.retain(&props.preview_shapes);
+        props.preview_shapes.iter().map(|shape| (shape.id, cache.get(shape))).collect()
+    };
+
     html! {
         <div class="w-64 flex-none bg-white border-r border-gray-300 p-4 overflow-y-auto flex flex-col">
-            <div class="pb-3 mb-4 border-b border-gray-200">
+            <Minimap
+                shapes={props.preview_shapes.clone()}
+                selected_ids={props.selected_ids.clone()}
+                canvas_width={props.canvas_width}
+                canvas_height={props.canvas_height}
+            />
+            <div class="flex items-center justify-between pb-3 mb-4 border-b border-gray-200">
                 <h2 class="text-lg font-semibold">{"Layers"}</h2>
+                <div class="flex gap-1">
+                    if let (Some(on_group), true) = (props.on_group.clone(), props.selected_ids.len() >= 2 && selected_group.is_none()) {
+                        <button
+                            data-testid="group-button"
+                            title="Group selection (Cmd+G)"
+                            onclick={Callback::from(move |_: MouseEvent| on_group.emit(()))}
+                            class="px-2 py-1 text-xs rounded border border-gray-200 text-gray-700 hover:bg-gray-50"
+                        >
+                            {"Group"}
+                        </button>
+                    }
+                    if let (Some(on_ungroup), Some(group_id)) = (props.on_ungroup.clone(), selected_group) {
+                        <button
+                            data-testid="ungroup-button"
+                            title="Ungroup (Cmd+Shift+G)"
+                            onclick={Callback::from(move |_: MouseEvent| on_ungroup.emit(group_id))}
+                            class="px-2 py-1 text-xs rounded border border-gray-200 text-gray-700 hover:bg-gray-50"
+                        >
+                            {"Ungroup"}
+                        </button>
+                    }
+                </div>
             </div>
             <div class="space-y-px flex-1 overflow-y-auto">
-                {render_nodes(
-                    &props.layer_tree.nodes,
-                    &props.shapes,
-                    &props.selected_ids,
-                    0,
-                    None,  // No parent group at top level
-                    &props.on_select,
-                    &props.on_rename,
-                    &props.on_toggle_expand,
-                )}
+                {flatten_rows(&props.layer_tree).iter().map(|row| render_row(row, props, &thumbnails, &drag)).collect::<Html>()}
             </div>
         </div>
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree_with_group(expanded: bool) -> (LayerTree, u64) {
+        let mut tree = LayerTree::new();
+        tree.add_shape(1);
+        tree.add_shape(2);
+        tree.add_shape(3);
+        let group_id = tree.group_shapes(&[1, 2]).unwrap();
+        if !expanded {
+            tree.toggle_expanded(group_id);
+        }
+        (tree, group_id)
+    }
+
+    #[test]
+    fn test_collapsed_group_hides_children() {
+        let (tree, group_id) = tree_with_group(false);
+        let rows = flatten_rows(&tree);
+
+        let group_rows: Vec<_> = rows.iter().filter(|row| matches!(row, LayerRow::Group { .. })).collect();
+        assert_eq!(group_rows.len(), 1);
+        assert!(matches!(group_rows[0], LayerRow::Group { id, expanded: false, .. } if *id == group_id));
+        // Only the group row and the ungrouped shape remain
+        assert_eq!(rows.len(), 2);
+    }
+
+    #[test]
+    fn test_expanded_group_indents_children() {
+        let (tree, _) = tree_with_group(true);
+        let rows = flatten_rows(&tree);
+
+        assert_eq!(rows.len(), 4);
+        let children: Vec<_> = rows
+            .iter()
+            .filter_map(|row| match row {
+                LayerRow::Shape { shape_id, depth: 1, select_ids } => Some((*shape_id, select_ids.clone())),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(children.len(), 2);
+        for (_, select_ids) in children {
+            let mut select_ids = select_ids;
+            select_ids.sort();
+            assert_eq!(select_ids, vec![1, 2]);
+        }
+    }
+}
@@ -1,14 +1,26 @@
 mod app;
+mod canvas_tabs;
 mod resizable_canvas;
 mod types;
 mod utils;
 mod layers_panel;
+mod layer_thumbnail;
+mod minimap;
 mod properties_panel;
+mod color_picker;
+mod number_input;
 mod chat_panel;
 mod version;
 mod version_panel;
 mod demo_paths;
 mod snap_logic;
+mod alignment;
+mod alignment_panel;
+mod toolbar;
+mod snap_settings;
+mod clipboard;
+mod autosave;
+mod image_import;
 
 // GPU rendering modules (Phase 1+)
 pub mod components;
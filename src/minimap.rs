@@ -0,0 +1,140 @@
+use wasm_bindgen::JsCast;
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+use yew::prelude::*;
+
+use crate::scene::{Shape, ShapeGeometry, Vec2};
+
+const MINIMAP_WIDTH: u32 = 160;
+const MINIMAP_HEIGHT: u32 = 120;
+/// Segments used to approximate ellipses at minimap scale
+const ELLIPSE_SEGMENTS: usize = 24;
+
+#[derive(Properties, PartialEq)]
+pub struct MinimapProps {
+    pub shapes: Vec<Shape>,
+    pub selected_ids: Vec<u64>,
+    /// Size of the main canvas, which the minimap scales down to fit
+    pub canvas_width: f64,
+    pub canvas_height: f64,
+}
+
+/// World-space outline of a shape, approximating curves with straight segments
+/// Paths use their on-curve points, which is close enough at minimap scale
+fn world_outline(shape: &Shape) -> Vec<Vec2> {
+    let local = match &shape.geometry {
+        ShapeGeometry::Rectangle { width, height, .. } => vec![
+            Vec2::ZERO,
+            Vec2::new(*width, 0.0),
+            Vec2::new(*width, *height),
+            Vec2::new(0.0, *height),
+        ],
+        ShapeGeometry::Ellipse { rx, ry } => (0..ELLIPSE_SEGMENTS)
+            .map(|i| {
+                let angle = i as f32 / ELLIPSE_SEGMENTS as f32 * std::f32::consts::TAU;
+                Vec2::new(rx * angle.cos(), ry * angle.sin())
+            })
+            .collect(),
+        geometry => geometry.vertices(),
+    };
+
+    local
+        .into_iter()
+        .map(|p| shape.transform.transform_point(p))
+        .collect()
+}
+
+fn trace_outline(ctx: &CanvasRenderingContext2d, outline: &[Vec2], scale: f64, closed: bool) {
+    ctx.begin_path();
+    for (i, p) in outline.iter().enumerate() {
+        let (x, y) = (p.x as f64 * scale, p.y as f64 * scale);
+        if i == 0 {
+            ctx.move_to(x, y);
+        } else {
+            ctx.line_to(x, y);
+        }
+    }
+    if closed {
+        ctx.close_path();
+    }
+}
+
+fn draw_minimap(
+    ctx: &CanvasRenderingContext2d,
+    shapes: &[Shape],
+    selected_ids: &[u64],
+    canvas_width: f64,
+    canvas_height: f64,
+) {
+    let scale = (MINIMAP_WIDTH as f64 / canvas_width).min(MINIMAP_HEIGHT as f64 / canvas_height);
+
+    ctx.clear_rect(0.0, 0.0, MINIMAP_WIDTH as f64, MINIMAP_HEIGHT as f64);
+    ctx.set_fill_style_str("#ffffff");
+    ctx.fill_rect(0.0, 0.0, canvas_width * scale, canvas_height * scale);
+
+    for shape in shapes.iter().filter(|shape| shape.visible) {
+        let outline = world_outline(shape);
+        if outline.len() < 2 {
+            continue;
+        }
+        let is_line = matches!(shape.geometry, ShapeGeometry::Line { .. });
+        trace_outline(ctx, &outline, scale, !is_line);
+
+        ctx.set_global_alpha(shape.style.opacity as f64);
+        if let (Some(fill), false) = (shape.style.fill_color(), is_line) {
+            ctx.set_fill_style_str(&fill.to_hex());
+            ctx.fill();
+        }
+        if let Some(stroke) = shape.style.stroke.as_ref().filter(|_| is_line) {
+            ctx.set_stroke_style_str(&stroke.color.to_hex());
+            ctx.set_line_width(1.0);
+            ctx.stroke();
+        }
+        ctx.set_global_alpha(1.0);
+
+        // Blue overlay stroke for selected shapes
+        if selected_ids.contains(&shape.id) {
+            ctx.set_stroke_style_str("#3b82f6");
+            ctx.set_line_width(1.5);
+            ctx.stroke();
+        }
+    }
+}
+
+/// Scaled-down preview of the whole canvas, drawn with the 2D canvas API
+#[function_component(Minimap)]
+pub fn minimap(props: &MinimapProps) -> Html {
+    let canvas_ref = use_node_ref();
+
+    {
+        let canvas_ref = canvas_ref.clone();
+        let deps = (
+            props.shapes.clone(),
+            props.selected_ids.clone(),
+            props.canvas_width,
+            props.canvas_height,
+        );
+
+        // Redraw whenever the shapes or selection change
+        use_effect_with(deps, move |(shapes, selected_ids, canvas_width, canvas_height)| {
+            let ctx = canvas_ref
+                .cast::<HtmlCanvasElement>()
+                .and_then(|canvas| canvas.get_context("2d").ok().flatten())
+                .and_then(|ctx| ctx.dyn_into::<CanvasRenderingContext2d>().ok());
+
+            if let Some(ctx) = ctx {
+                draw_minimap(&ctx, shapes, selected_ids, *canvas_width, *canvas_height);
+            }
+            || ()
+        });
+    }
+
+    html! {
+        <canvas
+            ref={canvas_ref}
+            data-testid="minimap"
+            width={MINIMAP_WIDTH.to_string()}
+            height={MINIMAP_HEIGHT.to_string()}
+            class="block mx-auto mb-3 rounded border border-gray-200 bg-gray-100"
+        />
+    }
+}
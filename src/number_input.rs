@@ -0,0 +1,151 @@
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::utils::eval_expression;
+
+#[derive(Properties, PartialEq)]
+pub struct NumberInputProps {
+    /// Current value; None shows a "Mixed" placeholder
+    pub value: Option<f64>,
+    /// Emitted live while typing an expression that evaluates on its own, and on Enter or
+    /// when the field loses focus for any that evaluates, including percentages
+    pub on_change: Callback<f64>,
+    #[prop_or_default]
+    pub test_id: Option<AttrValue>,
+    #[prop_or_default]
+    pub title: Option<AttrValue>,
+    /// Sizing classes, e.g. "w-full" or "w-16"
+    #[prop_or_default]
+    pub class: Classes,
+}
+
+/// Value to apply live for `text` while it's being typed: the expression's result when it
+/// evaluates without a `%`, whose meaning would shift as each live update changed the value
+fn live_value(text: &str) -> Option<f64> {
+    if text.contains('%') {
+        return None;
+    }
+    eval_expression(text, 0.0).ok()
+}
+
+/// Text field for a number that also accepts arithmetic like `400/2` or `50%` (of the
+/// current value); an expression that doesn't evaluate outlines the field in red
+#[function_component(NumberInput)]
+pub fn number_input(props: &NumberInputProps) -> Html {
+    // What the user typed, until it's applied or the value changes underneath it
+    let draft = use_state(|| None::<String>);
+    let invalid = use_state(|| false);
+
+    {
+        let draft = draft.clone();
+        let invalid = invalid.clone();
+        use_effect_with(props.value.map(f64::to_bits), move |value| {
+            // A live edit leaves its own text in place; anything else shows the new value
+            let typed = (*draft).as_deref().and_then(live_value);
+            if typed.map(f64::to_bits) != *value {
+                draft.set(None);
+                invalid.set(false);
+            }
+        });
+    }
+
+    let text = (*draft)
+        .clone()
+        .unwrap_or_else(|| props.value.map(|value| value.to_string()).unwrap_or_default());
+
+    let oninput = {
+        let draft = draft.clone();
+        let invalid = invalid.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |e: InputEvent| {
+            let Some(input) = e.target_dyn_into::<HtmlInputElement>() else {
+                return;
+            };
+            let text = input.value();
+            if let Some(value) = live_value(&text) {
+                on_change.emit(value);
+            }
+            draft.set(Some(text));
+            invalid.set(false);
+        })
+    };
+
+    let onkeydown = {
+        let draft = draft.clone();
+        let invalid = invalid.clone();
+        let on_change = props.on_change.clone();
+        let current = props.value.unwrap_or(0.0);
+        Callback::from(move |e: KeyboardEvent| {
+            if e.key() != "Enter" {
+                return;
+            }
+            let Some(input) = e.target_dyn_into::<HtmlInputElement>() else {
+                return;
+            };
+            match eval_expression(&input.value(), current) {
+                Ok(value) => {
+                    on_change.emit(value);
+                    draft.set(None);
+                    invalid.set(false);
+                }
+                Err(_) => invalid.set(true),
+            }
+        })
+    };
+
+    // Leaving the field applies what was typed, like Enter; text that doesn't evaluate
+    // is dropped and the field shows the value again
+    let onblur = {
+        let draft = draft.clone();
+        let invalid = invalid.clone();
+        let on_change = props.on_change.clone();
+        let value = props.value;
+        Callback::from(move |_: FocusEvent| {
+            // A value already applied while typing isn't emitted twice
+            let typed = (*draft).as_deref().and_then(|text| eval_expression(text, value.unwrap_or(0.0)).ok());
+            if let Some(typed) = typed.filter(|typed| Some(*typed) != value) {
+                on_change.emit(typed);
+            }
+            draft.set(None);
+            invalid.set(false);
+        })
+    };
+
+    html! {
+        <input
+            type="text"
+            inputmode="decimal"
+            data-testid={props.test_id.clone()}
+            title={props.title.clone()}
+            value={text}
+            placeholder={props.value.is_none().then_some("Mixed")}
+            aria-invalid={invalid.to_string()}
+            {oninput}
+            {onkeydown}
+            {onblur}
+            class={classes!(
+                props.class.clone(),
+                "px-2", "py-1", "border", "rounded", "text-sm", "bg-white", "text-gray-900",
+                if *invalid { "border-red-500 outline outline-1 outline-red-500" } else { "border-gray-300" }
+            )}
+        />
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_live_value_evaluates_expressions_but_not_percentages() {
+        assert_eq!(live_value("42"), Some(42.0));
+        assert_eq!(live_value(" 400/2 "), Some(200.0));
+        // Half-typed, or relative to a value that each update would change
+        assert_eq!(live_value("400/"), None);
+        assert_eq!(live_value("50%"), None);
+        // Forms f64 parsing accepts aren't numbers here
+        for text in ["1e3", "inf", "NaN", "-inf", ""] {
+            assert_eq!(live_value(text), None, "{text}");
+        }
+    }
+}
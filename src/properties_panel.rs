@@ -1,101 +1,829 @@
 use yew::prelude::*;
-use web_sys::HtmlInputElement;
-use crate::types::{ActiveTab, BoundingBox, Polygon};
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use crate::scene::{BooleanOp, Color, CornerRadii, DropShadow, Fill, LineCap, LineJoin, PieSlice, Shape, ShapeGeometry, Vec2};
+use crate::color_picker::ColorPicker;
+use crate::number_input::NumberInput;
+use crate::types::{ActiveTab, BoundingBox};
 
 #[derive(Properties, PartialEq)]
 pub struct PropertiesPanelProps {
     pub active_tab: ActiveTab,
-    pub selected_polygon: Option<Polygon>,
+    /// Every selected shape; fields show the value they share, or "Mixed"
+    pub selected_shapes: Vec<Shape>,
+    /// Combined bounds of the selection, which position and dimension fields edit
     pub bounding_box: Option<BoundingBox>,
+    /// Show that the last click tried to select a locked layer
+    pub locked_notice: bool,
     pub on_update_fill: Callback<String>,
     pub on_update_stroke: Callback<String>,
+    pub on_update_stroke_width: Callback<f64>,
     pub on_update_position: Callback<(f64, f64)>,
     pub on_update_dimensions: Callback<(f64, f64)>,
+    pub on_update_opacity: Callback<f32>,
+    /// Opacity of the group when the selection is exactly one group
+    pub group_opacity: Option<f32>,
+    pub on_update_group_opacity: Callback<f32>,
+    pub on_update_dash: Callback<Option<Vec<f32>>>,
+    /// New line cap and/or join; None leaves that half of each stroke as it is
+    pub on_update_line_style: Callback<(Option<LineCap>, Option<LineJoin>)>,
+    pub on_update_pie_slice: Callback<PieSlice>,
+    /// New corner radii for the selected rectangle
+    pub on_update_corner_radii: Callback<CornerRadii>,
+    /// New star or regular polygon geometry for the selected shape
+    pub on_update_star_polygon: Callback<ShapeGeometry>,
+    /// A fill for each selected shape, by ID
+    pub on_update_fill_paint: Callback<Vec<(u64, Fill)>>,
+    /// Round the selected polygon's corners with this tension, or re-smooth it
+    pub on_smooth_polygon: Callback<f32>,
+    /// New content and font size for the selected text
+    pub on_update_text: Callback<(String, f32)>,
+    /// Set or remove the selection's drop shadow
+    pub on_update_drop_shadow: Callback<Option<DropShadow>>,
+    /// Combine the selected shapes into one path
+    pub on_boolean_op: Callback<BooleanOp>,
+    /// Turn the selected shapes into paths; true also bakes each transform into its path
+    pub on_convert_to_path: Callback<bool>,
+}
+
+/// Tension a polygon starts with when first smoothed
+const DEFAULT_SMOOTH_TENSION: f32 = 0.5;
+
+/// Range of star points and polygon sides the number fields accept
+const MIN_STAR_POINTS: u32 = 3;
+const MAX_STAR_POINTS: u32 = 100;
+
+/// Star or regular polygon geometry with field `field` set to `value`
+/// Fields are (points, outer radius, inner radius) for stars and (sides, radius) for
+/// polygons; counts are clamped to a usable range and radii kept non-negative
+fn with_star_polygon_field(geometry: &ShapeGeometry, field: usize, value: f64) -> Option<ShapeGeometry> {
+    let count = (value.round() as i64).clamp(MIN_STAR_POINTS as i64, MAX_STAR_POINTS as i64) as u32;
+    let radius = value.max(0.0) as f32;
+    match (geometry, field) {
+        (ShapeGeometry::Star { outer_radius, inner_radius, .. }, 0) => Some(ShapeGeometry::star(count, *outer_radius, *inner_radius)),
+        (ShapeGeometry::Star { points, inner_radius, .. }, 1) => Some(ShapeGeometry::star(*points, radius, *inner_radius)),
+        (ShapeGeometry::Star { points, outer_radius, .. }, 2) => Some(ShapeGeometry::star(*points, *outer_radius, radius)),
+        (ShapeGeometry::RegularPolygon { radius: current, .. }, 0) => Some(ShapeGeometry::regular_polygon(count, *current)),
+        (ShapeGeometry::RegularPolygon { sides, .. }, 1) => Some(ShapeGeometry::regular_polygon(*sides, radius)),
+        _ => None,
+    }
+}
+
+/// Dash presets offered in the stroke style dropdown; None is a solid stroke
+const DASH_PRESETS: &[(&str, Option<&[f32]>)] = &[
+    ("Solid", None),
+    ("Dashed", Some(&[8.0, 4.0])),
+    ("Dotted", Some(&[2.0, 2.0])),
+    ("Dash-dot", Some(&[8.0, 2.0, 2.0, 2.0])),
+];
+
+/// Fill types offered in the fill dropdown: (option value, label)
+const FILL_KINDS: &[(&str, &str)] = &[("solid", "Solid"), ("linear", "Linear"), ("radial", "Radial")];
+
+fn fill_kind(fill: Option<&Fill>) -> &'static str {
+    match fill {
+        Some(Fill::LinearGradient { .. }) => "linear",
+        Some(Fill::RadialGradient { .. }) => "radial",
+        _ => "solid",
+    }
+}
+
+/// The value every item shares; None when they differ (a "Mixed" field) or there are none
+fn common_value<T: PartialEq>(values: impl IntoIterator<Item = T>) -> Option<T> {
+    let mut values = values.into_iter();
+    let first = values.next()?;
+    values.all(|value| value == first).then_some(first)
+}
+
+/// Each shape's fill converted to `kind`, so gradients fit every shape's own bounds
+fn fills_of_kind(kind: &str, shapes: &[Shape]) -> Vec<(u64, Fill)> {
+    shapes
+        .iter()
+        .filter_map(|shape| Some((shape.id, fill_of_kind(kind, shape)?)))
+        .collect()
+}
+
+/// Each gradient fill with stop `index` recolored; shapes without a fill are left out
+fn fills_with_stop_color(shapes: &[Shape], index: usize, color: Color) -> Vec<(u64, Fill)> {
+    shapes
+        .iter()
+        .filter_map(|shape| Some((shape.id, shape.style.fill.clone()?.with_stop_color(index, color))))
+        .collect()
+}
+
+/// The shape's fill converted to `kind`, keeping its colors; gradients span the shape's bounds
+fn fill_of_kind(kind: &str, shape: &Shape) -> Option<Fill> {
+    let fill = shape.style.fill.as_ref();
+    let from = fill.map_or(Color::black(), Fill::primary_color);
+    let to = fill
+        .and_then(|fill| fill.stops().get(1))
+        .map_or(Color::white(), |stop| stop.color);
+    let bounds = shape.geometry.local_bounds();
+    match kind {
+        "solid" => Some(Fill::Solid(from)),
+        "linear" => Some(Fill::linear_across(&bounds, from, to)),
+        "radial" => Some(Fill::radial_within(&bounds, from, to)),
+        _ => None,
+    }
 }
 
 #[function_component(PropertiesPanel)]
 pub fn properties_panel(props: &PropertiesPanelProps) -> Html {
+    // Width/height ratio captured when the aspect lock is turned on or the selection changes
+    let locked_ratio = use_state(|| None::<f64>);
+    // Whether one radius edits every corner; a newly selected rectangle starts linked
+    // unless its corners already differ
+    let corners_linked = use_state(|| true);
+    {
+        let locked_ratio = locked_ratio.clone();
+        let corners_linked = corners_linked.clone();
+        let bbox = props.bounding_box;
+        let uniform_corners = !matches!(
+            props.selected_shapes.as_slice(),
+            [Shape { geometry: ShapeGeometry::Rectangle { corner_radii, .. }, .. }] if corner_radii.as_uniform().is_none()
+        );
+        let selected_ids: Vec<u64> = props.selected_shapes.iter().map(|shape| shape.id).collect();
+        use_effect_with(selected_ids, move |_| {
+            if locked_ratio.is_some() {
+                locked_ratio.set(bbox.map(|bbox| bbox.aspect_ratio()));
+            }
+            corners_linked.set(uniform_corners);
+        });
+    }
+
     if props.active_tab != ActiveTab::Design {
         return html! {};
     }
 
-    let selected = props.selected_polygon.as_ref();
+    let shapes = &props.selected_shapes;
+    // Shape-specific fields (arc, text, smoothing) only apply to a single selected shape
+    let single = match shapes.as_slice() {
+        [shape] => Some(shape),
+        _ => None,
+    };
     let bbox = props.bounding_box.as_ref();
+    let strokes = || shapes.iter().map(|shape| shape.style.stroke.as_ref());
+    let fill_kind_value = common_value(shapes.iter().map(|shape| fill_kind(shape.style.fill.as_ref())));
+    // Stop colors show the first gradient's; editing a stop recolors it in every gradient
+    let gradient = shapes
+        .first()
+        .and_then(|shape| shape.style.fill.clone())
+        .filter(|fill| fill.is_gradient() && fill_kind_value.is_some());
+    let fill_color = common_value(
+        shapes
+            .iter()
+            .map(|shape| shape.style.fill.as_ref().map_or(Color::black(), Fill::primary_color)),
+    );
+    let stroke_color = common_value(strokes().map(|s| s.map_or(Color::black(), |s| s.color)));
+    let stroke_width = common_value(strokes().map(|s| s.map_or(0.0, |s| s.width)));
+    let dash_pattern = common_value(strokes().map(|s| s.and_then(|s| s.dash_pattern.clone())));
+    let dash_preset = dash_pattern.as_ref().map(|dash_pattern| {
+        DASH_PRESETS
+            .iter()
+            .position(|(_, pattern)| pattern.map(<[f32]>::to_vec) == *dash_pattern)
+    });
+    let line_cap = common_value(strokes().map(|s| s.map(|s| s.line_cap).unwrap_or_default()));
+    let line_join = common_value(strokes().map(|s| s.map(|s| s.line_join).unwrap_or_default()));
+    let pie_slice = single.and_then(|shape| shape.geometry.as_pie_slice());
+    let corner_radii = single.and_then(|shape| match shape.geometry {
+        ShapeGeometry::Rectangle { corner_radii, .. } => Some(corner_radii),
+        _ => None,
+    });
+    // Title and (label, test ID, value) per field of a star or regular polygon
+    let star_polygon = single.and_then(|shape| match shape.geometry {
+        ShapeGeometry::Star { points, outer_radius, inner_radius } => Some((
+            "Star",
+            shape.geometry.clone(),
+            vec![
+                ("Points", "star-points", points as f64),
+                ("Outer Radius", "star-outer-radius", outer_radius as f64),
+                ("Inner Radius", "star-inner-radius", inner_radius as f64),
+            ],
+        )),
+        ShapeGeometry::RegularPolygon { sides, radius } => Some((
+            "Polygon",
+            shape.geometry.clone(),
+            vec![("Sides", "polygon-sides", sides as f64), ("Radius", "polygon-radius", radius as f64)],
+        )),
+        _ => None,
+    });
+    let text = single.and_then(|shape| match &shape.geometry {
+        ShapeGeometry::Text { content, font_size, .. } => Some((content.clone(), *font_size)),
+        _ => None,
+    });
+    let is_polygon = single.is_some_and(|shape| shape.geometry.polygon_points().is_some());
+    // Text and images have no outline to convert
+    let convertible = shapes
+        .iter()
+        .any(|shape| !matches!(shape.geometry, ShapeGeometry::Text { .. } | ShapeGeometry::Image { .. }));
+    let smooth_tension = single
+        .and_then(|shape| shape.geometry.as_smooth_polygon())
+        .map(|(_, tension)| tension);
+    let opacity_percent = common_value(shapes.iter().map(|shape| (shape.style.opacity * 100.0).round()));
+    let group_opacity_percent = props.group_opacity.map_or(100.0, |opacity| (opacity * 100.0).round());
+    // Some(None) when no shape has a shadow, None when only some do or the shadows differ
+    let drop_shadow = common_value(shapes.iter().map(|shape| shape.style.drop_shadow));
+    let shared_shadow = drop_shadow.flatten();
+    // Edits one field of the shared shadow; `edit` returns None for input it can't use
+    let edit_shadow = |edit: fn(DropShadow, &str) -> Option<DropShadow>| {
+        let on_update = props.on_update_drop_shadow.clone();
+        Callback::from(move |e: InputEvent| {
+            if let (Some(shadow), Some(input)) = (shared_shadow, e.target_dyn_into::<HtmlInputElement>()) {
+                if let Some(shadow) = edit(shadow, &input.value()) {
+                    on_update.emit(Some(shadow));
+                }
+            }
+        })
+    };
+    // Moves the shared shadow from a number field
+    let offset_shadow = |offset: fn(DropShadow, f32) -> Vec2| {
+        let on_update = props.on_update_drop_shadow.clone();
+        Callback::from(move |value: f64| {
+            if let Some(shadow) = shared_shadow {
+                on_update.emit(Some(DropShadow { offset: offset(shadow, value as f32), ..shadow }));
+            }
+        })
+    };
 
     html! {
         <>
-            <h2 class="text-lg font-semibold pb-3 mb-4 border-b border-gray-200">{"Properties"}</h2>
+            <h2 data-testid="properties-title" class="text-lg font-semibold pb-3 mb-4 border-b border-gray-200 truncate">
+                {match shapes.as_slice() {
+                    [] => "Properties".to_string(),
+                    [shape] => shape.name.clone(),
+                    shapes => format!("{} shapes", shapes.len()),
+                }}
+            </h2>
 
-            if selected.is_some() && bbox.is_some() {
+            if props.locked_notice {
+                <p data-testid="locked-notice" class="mb-4 px-3 py-2 text-sm text-amber-800 bg-amber-50 border border-amber-200 rounded">
+                    {"Layer is locked"}
+                </p>
+            }
+
+            if props.group_opacity.is_some() {
+                // Group opacity multiplies into every shape in the group
+                <div class="mb-4">
+                    <label class="block text-sm font-medium text-gray-700 mb-1">
+                        {"Group Opacity"}
+                    </label>
+                    <div class="flex items-center gap-2">
+                        <input
+                            type="range"
+                            min="0"
+                            max="100"
+                            step="1"
+                            data-testid="group-opacity-slider"
+                            value={group_opacity_percent.to_string()}
+                            oninput={
+                                let on_update = props.on_update_group_opacity.clone();
+                                Callback::from(move |e: InputEvent| {
+                                    if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
+                                        if let Ok(percent) = input.value().parse::<f32>() {
+                                            on_update.emit(percent / 100.0);
+                                        }
+                                    }
+                                })
+                            }
+                            class="flex-1 cursor-pointer"
+                        />
+                        <span class="w-12 text-right text-sm text-gray-700">
+                            {format!("{}%", group_opacity_percent)}
+                        </span>
+                    </div>
+                </div>
+            }
+
+            if shapes.len() >= 2 {
+                // Boolean ops replace the selection with one path
+                <div class="mb-4">
+                    <label class="block text-sm font-medium text-gray-700 mb-1">
+                        {"Boolean"}
+                    </label>
+                    <div class="grid grid-cols-4 gap-1">
+                        {for BooleanOp::ALL.iter().map(|&op| {
+                            let on_boolean_op = props.on_boolean_op.clone();
+                            html! {
+                                <button
+                                    data-testid={format!("boolean-{}", op.to_kebab_case())}
+                                    title={op.label()}
+                                    onclick={Callback::from(move |_: MouseEvent| on_boolean_op.emit(op))}
+                                    class="px-1 py-1 border border-gray-300 rounded text-xs text-gray-700 hover:bg-gray-50"
+                                >
+                                    {op.label()}
+                                </button>
+                            }
+                        })}
+                    </div>
+                </div>
+            }
+
+            if !shapes.is_empty() && bbox.is_some() {
                 <div class="space-y-4">
-                    // Fill Color
+                    // Fill: a solid color or a two-stop gradient
+                    <div>
+                        <div class="flex items-center justify-between mb-1">
+                            <label class="block text-sm font-medium text-gray-700">
+                                {"Fill"}
+                            </label>
+                            <select
+                                data-testid="fill-type-select"
+                                onchange={
+                                    let on_update = props.on_update_fill_paint.clone();
+                                    let shapes = shapes.clone();
+                                    Callback::from(move |e: Event| {
+                                        if let Some(select) = e.target_dyn_into::<HtmlSelectElement>() {
+                                            on_update.emit(fills_of_kind(&select.value(), &shapes));
+                                        }
+                                    })
+                                }
+                                class="px-1 py-0.5 border border-gray-300 rounded text-xs bg-white text-gray-900"
+                            >
+                                if fill_kind_value.is_none() {
+                                    <option selected=true disabled=true>{"Mixed"}</option>
+                                }
+                                {for FILL_KINDS.iter().map(|(value, label)| html! {
+                                    <option value={*value} selected={fill_kind_value == Some(*value)}>{*label}</option>
+                                })}
+                            </select>
+                        </div>
+                        if let Some(gradient) = gradient {
+                            <div class="flex gap-2">
+                                {for gradient.stops().iter().enumerate().map(|(i, stop)| {
+                                    let on_update = props.on_update_fill_paint.clone();
+                                    let shapes = shapes.clone();
+                                    html! {
+                                        <input
+                                            type="color"
+                                            data-testid={format!("gradient-stop-{}", i)}
+                                            title={format!("Stop {}", i + 1)}
+                                            value={stop.color.to_hex()}
+                                            oninput={Callback::from(move |e: InputEvent| {
+                                                if let Some(color) = e
+                                                    .target_dyn_into::<HtmlInputElement>()
+                                                    .and_then(|input| Color::parse(&input.value()))
+                                                {
+                                                    on_update.emit(fills_with_stop_color(&shapes, i, color));
+                                                }
+                                            })}
+                                            class="w-12 h-8 rounded border border-gray-300 bg-white cursor-pointer"
+                                        />
+                                    }
+                                })}
+                            </div>
+                        } else {
+                            <ColorPicker
+                                test_id="fill"
+                                color={fill_color.unwrap_or_else(Color::black)}
+                                mixed={fill_color.is_none()}
+                                on_change={props.on_update_fill.reform(|color: Color| color.to_css())}
+                            />
+                        }
+                    </div>
+
+                    // Stroke Color
+                    <div>
+                        <label class="block text-sm font-medium text-gray-700 mb-1">
+                            {"Stroke"}
+                        </label>
+                        <ColorPicker
+                            test_id="stroke"
+                            color={stroke_color.unwrap_or_else(Color::black)}
+                            mixed={stroke_color.is_none()}
+                            on_change={props.on_update_stroke.reform(|color: Color| color.to_css())}
+                        />
+                    </div>
+
+                    // Stroke width; a width on an unstroked shape gives it a stroke
                     <div>
                         <label class="block text-sm font-medium text-gray-700 mb-1">
-                            {"Fill"}
+                            {"Stroke Width"}
                         </label>
-                        <div class="flex gap-2">
+                        <div class="flex items-center gap-2">
                             <input
-                                type="color"
-                                value={selected.unwrap().fill.clone()}
+                                type="range"
+                                min="0"
+                                max="20"
+                                step="0.5"
+                                data-testid="stroke-width-slider"
+                                value={stroke_width.unwrap_or(0.0).to_string()}
                                 oninput={
-                                    let on_update = props.on_update_fill.clone();
+                                    let on_update = props.on_update_stroke_width.clone();
                                     Callback::from(move |e: InputEvent| {
                                         if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
-                                            on_update.emit(input.value());
+                                            if let Ok(width) = input.value().parse::<f64>() {
+                                                on_update.emit(width);
+                                            }
                                         }
                                     })
                                 }
-                                class="w-12 h-8 rounded border border-gray-300 bg-white cursor-pointer"
+                                class="flex-1 cursor-pointer"
                             />
-                            <input
-                                type="text"
-                                value={selected.unwrap().fill.clone()}
-                                oninput={
-                                    let on_update = props.on_update_fill.clone();
-                                    Callback::from(move |e: InputEvent| {
-                                        if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
-                                            on_update.emit(input.value());
+                            <NumberInput
+                                test_id="stroke-width-input"
+                                value={stroke_width.map(f64::from)}
+                                on_change={props.on_update_stroke_width.clone()}
+                                class="w-16"
+                            />
+                        </div>
+                    </div>
+
+                    // Stroke dash pattern
+                    <div>
+                        <label class="block text-sm font-medium text-gray-700 mb-1">
+                            {"Stroke Style"}
+                        </label>
+                        <select
+                            data-testid="dash-select"
+                            onchange={
+                                let on_update = props.on_update_dash.clone();
+                                Callback::from(move |e: Event| {
+                                    if let Some(select) = e.target_dyn_into::<HtmlSelectElement>() {
+                                        if let Some((_, pattern)) = select.value().parse::<usize>().ok().and_then(|i| DASH_PRESETS.get(i)) {
+                                            on_update.emit(pattern.map(<[f32]>::to_vec));
+                                        }
+                                    }
+                                })
+                            }
+                            class="w-full px-2 py-1 border border-gray-300 rounded text-sm bg-white text-gray-900"
+                        >
+                            if dash_preset.is_none() {
+                                <option selected=true disabled=true>{"Mixed"}</option>
+                            } else if dash_preset == Some(None) {
+                                <option selected=true disabled=true>{"Custom"}</option>
+                            }
+                            {for DASH_PRESETS.iter().enumerate().map(|(i, (name, _))| html! {
+                                <option value={i.to_string()} selected={dash_preset == Some(Some(i))}>{*name}</option>
+                            })}
+                        </select>
+                        <div class="flex gap-2 mt-2">
+                            <select
+                                data-testid="line-cap-select"
+                                title="Line cap"
+                                onchange={
+                                    let on_update = props.on_update_line_style.clone();
+                                    Callback::from(move |e: Event| {
+                                        if let Some(cap) = e.target_dyn_into::<HtmlSelectElement>().and_then(|s| LineCap::from_svg(&s.value())) {
+                                            on_update.emit((Some(cap), None));
                                         }
                                     })
                                 }
                                 class="flex-1 px-2 py-1 border border-gray-300 rounded text-sm bg-white text-gray-900"
-                            />
+                            >
+                                if line_cap.is_none() {
+                                    <option selected=true disabled=true>{"Mixed cap"}</option>
+                                }
+                                {for LineCap::ALL.into_iter().map(|cap| html! {
+                                    <option value={cap.to_svg()} selected={Some(cap) == line_cap}>{format!("{:?} cap", cap)}</option>
+                                })}
+                            </select>
+                            <select
+                                data-testid="line-join-select"
+                                title="Line join"
+                                onchange={
+                                    let on_update = props.on_update_line_style.clone();
+                                    Callback::from(move |e: Event| {
+                                        if let Some(join) = e.target_dyn_into::<HtmlSelectElement>().and_then(|s| LineJoin::from_svg(&s.value())) {
+                                            on_update.emit((None, Some(join)));
+                                        }
+                                    })
+                                }
+                                class="flex-1 px-2 py-1 border border-gray-300 rounded text-sm bg-white text-gray-900"
+                            >
+                                if line_join.is_none() {
+                                    <option selected=true disabled=true>{"Mixed join"}</option>
+                                }
+                                {for LineJoin::ALL.into_iter().map(|join| html! {
+                                    <option value={join.to_svg()} selected={Some(join) == line_join}>{format!("{:?} join", join)}</option>
+                                })}
+                            </select>
                         </div>
                     </div>
 
-                    // Stroke Color
+                    // Arc: editing any field rebuilds the pie slice path
+                    if let Some(slice) = pie_slice {
+                        <div>
+                            <label class="block text-sm font-medium text-gray-700 mb-1">
+                                {"Arc"}
+                            </label>
+                            <div class="grid grid-cols-3 gap-2">
+                                {for [
+                                    ("Start Angle", "arc-start-angle", slice.start_angle),
+                                    ("End Angle", "arc-end-angle", slice.end_angle),
+                                    ("Radius", "arc-radius", slice.radius),
+                                ].into_iter().enumerate().map(|(field, (label, testid, value))| {
+                                    let on_update = props.on_update_pie_slice.clone();
+                                    html! {
+                                        <div>
+                                            <label class="block text-xs text-gray-500 mb-1">{label}</label>
+                                            <NumberInput
+                                                test_id={testid}
+                                                value={Some(value.round() as f64)}
+                                                on_change={Callback::from(move |v: f64| {
+                                                    let v = v as f32;
+                                                    let next = match field {
+                                                        0 => PieSlice { start_angle: v, ..slice },
+                                                        1 => PieSlice { end_angle: v, ..slice },
+                                                        _ => PieSlice { radius: v.max(1.0), ..slice },
+                                                    };
+                                                    on_update.emit(next);
+                                                })}
+                                                class="w-full"
+                                            />
+                                        </div>
+                                    }
+                                })}
+                            </div>
+                        </div>
+                    }
+
+                    // Star and polygon parameters rebuild the outline live as they're edited
+                    if let Some((title, geometry, fields)) = star_polygon {
+                        <div>
+                            <label class="block text-sm font-medium text-gray-700 mb-1">
+                                {title}
+                            </label>
+                            <div class={classes!("grid", "gap-2", if fields.len() == 3 { "grid-cols-3" } else { "grid-cols-2" })}>
+                                {for fields.into_iter().enumerate().map(|(field, (label, testid, value))| {
+                                    let geometry = geometry.clone();
+                                    let on_update = props.on_update_star_polygon.clone();
+                                    html! {
+                                        <div>
+                                            <label class="block text-xs text-gray-500 mb-1">{label}</label>
+                                            <NumberInput
+                                                test_id={testid}
+                                                value={Some((value * 100.0).round() / 100.0)}
+                                                on_change={Callback::from(move |v: f64| {
+                                                    if let Some(next) = with_star_polygon_field(&geometry, field, v) {
+                                                        on_update.emit(next);
+                                                    }
+                                                })}
+                                                class="w-full"
+                                            />
+                                        </div>
+                                    }
+                                })}
+                            </div>
+                        </div>
+                    }
+
+                    // Corner radius: one value for every corner, or each corner on its own when unlinked
+                    if let Some(radii) = corner_radii {
+                        <div>
+                            <div class="flex items-center justify-between mb-1">
+                                <label class="block text-sm font-medium text-gray-700">
+                                    {"Corner Radius"}
+                                </label>
+                                <button
+                                    data-testid="corner-link-toggle"
+                                    title="Same radius on every corner"
+                                    aria-pressed={corners_linked.to_string()}
+                                    onclick={
+                                        let corners_linked = corners_linked.clone();
+                                        Callback::from(move |_: MouseEvent| corners_linked.set(!*corners_linked))
+                                    }
+                                    class={classes!(
+                                        "px-2", "py-0.5", "rounded", "border", "text-xs",
+                                        if *corners_linked { "border-blue-500 bg-blue-50 text-blue-700" } else { "border-gray-300 text-gray-600 hover:bg-gray-50" }
+                                    )}
+                                >
+                                    {if *corners_linked { "Linked" } else { "Per corner" }}
+                                </button>
+                            </div>
+                            if *corners_linked {
+                                <NumberInput
+                                    test_id="corner-radius"
+                                    value={radii.as_uniform().map(f64::from)}
+                                    on_change={props.on_update_corner_radii.reform(|radius: f64| CornerRadii::uniform(radius.max(0.0) as f32))}
+                                    class="w-full"
+                                />
+                            } else {
+                                <div class="grid grid-cols-2 gap-2">
+                                    {for [
+                                        ("Top Left", "corner-radius-tl", radii.tl, (|radii, tl| CornerRadii { tl, ..radii }) as fn(CornerRadii, f32) -> CornerRadii),
+                                        ("Top Right", "corner-radius-tr", radii.tr, |radii, tr| CornerRadii { tr, ..radii }),
+                                        ("Bottom Left", "corner-radius-bl", radii.bl, |radii, bl| CornerRadii { bl, ..radii }),
+                                        ("Bottom Right", "corner-radius-br", radii.br, |radii, br| CornerRadii { br, ..radii }),
+                                    ].into_iter().map(|(label, testid, value, set)| {
+                                        let on_change = props
+                                            .on_update_corner_radii
+                                            .reform(move |radius: f64| set(radii, radius.max(0.0) as f32));
+                                        html! {
+                                            <div>
+                                                <label class="block text-xs text-gray-500 mb-1">{label}</label>
+                                                <NumberInput test_id={testid} value={Some(value as f64)} {on_change} class="w-full" />
+                                            </div>
+                                        }
+                                    })}
+                                </div>
+                            }
+                        </div>
+                    }
+
+                    // Text content and size; editing either rebuilds the text's bounds
+                    if let Some((content, font_size)) = text {
+                        <div>
+                            <label class="block text-sm font-medium text-gray-700 mb-1">
+                                {"Text"}
+                            </label>
+                            <div class="flex gap-2">
+                                <input
+                                    type="text"
+                                    data-testid="text-content"
+                                    value={content.clone()}
+                                    oninput={
+                                        let on_update = props.on_update_text.clone();
+                                        Callback::from(move |e: InputEvent| {
+                                            if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
+                                                on_update.emit((input.value(), font_size));
+                                            }
+                                        })
+                                    }
+                                    class="flex-1 min-w-0 px-2 py-1 border border-gray-300 rounded text-sm bg-white text-gray-900"
+                                />
+                                <NumberInput
+                                    title="Font size"
+                                    test_id="text-font-size"
+                                    value={Some(font_size.round() as f64)}
+                                    on_change={
+                                        let on_update = props.on_update_text.clone();
+                                        Callback::from(move |size: f64| on_update.emit((content.clone(), (size as f32).max(1.0))))
+                                    }
+                                    class="w-16"
+                                />
+                            </div>
+                        </div>
+                    }
+
+                    // Convert to path keeps the transform; flatten bakes it into the points
+                    if convertible {
+                        <div class="grid grid-cols-2 gap-2">
+                            <button
+                                data-testid="convert-to-path"
+                                onclick={
+                                    let on_convert = props.on_convert_to_path.clone();
+                                    Callback::from(move |_: MouseEvent| on_convert.emit(false))
+                                }
+                                class="px-2 py-1 border border-gray-300 rounded text-sm text-gray-700 hover:bg-gray-50"
+                            >
+                                {"Convert to Path"}
+                            </button>
+                            <button
+                                data-testid="flatten-path"
+                                onclick={
+                                    let on_convert = props.on_convert_to_path.clone();
+                                    Callback::from(move |_: MouseEvent| on_convert.emit(true))
+                                }
+                                class="px-2 py-1 border border-gray-300 rounded text-sm text-gray-700 hover:bg-gray-50"
+                            >
+                                {"Flatten"}
+                            </button>
+                        </div>
+                    }
+
+                    // Smoothing: a polygon becomes a curved path through its vertices
+                    if is_polygon {
+                        <button
+                            data-testid="smooth-polygon"
+                            onclick={
+                                let on_smooth = props.on_smooth_polygon.clone();
+                                Callback::from(move |_: MouseEvent| on_smooth.emit(DEFAULT_SMOOTH_TENSION))
+                            }
+                            class="w-full px-2 py-1 border border-gray-300 rounded text-sm text-gray-700 hover:bg-gray-50"
+                        >
+                            {"Smooth"}
+                        </button>
+                    }
+                    if let Some(tension) = smooth_tension {
+                        <div>
+                            <label class="block text-sm font-medium text-gray-700 mb-1">
+                                {"Tension"}
+                            </label>
+                            <div class="flex items-center gap-2">
+                                <input
+                                    type="range"
+                                    min="0"
+                                    max="1"
+                                    step="0.05"
+                                    data-testid="smooth-tension-slider"
+                                    value={tension.to_string()}
+                                    oninput={
+                                        let on_smooth = props.on_smooth_polygon.clone();
+                                        Callback::from(move |e: InputEvent| {
+                                            if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
+                                                if let Ok(tension) = input.value().parse::<f32>() {
+                                                    on_smooth.emit(tension);
+                                                }
+                                            }
+                                        })
+                                    }
+                                    class="flex-1 cursor-pointer"
+                                />
+                                <span class="w-12 text-right text-sm text-gray-700">
+                                    {format!("{:.2}", tension)}
+                                </span>
+                            </div>
+                        </div>
+                    }
+
+                    // Opacity
                     <div>
                         <label class="block text-sm font-medium text-gray-700 mb-1">
-                            {"Stroke"}
+                            {"Opacity"}
                         </label>
-                        <div class="flex gap-2">
+                        <div class="flex items-center gap-2">
                             <input
-                                type="color"
-                                value={selected.unwrap().stroke.clone()}
+                                type="range"
+                                min="0"
+                                max="100"
+                                step="1"
+                                data-testid="opacity-slider"
+                                value={opacity_percent.unwrap_or(100.0).to_string()}
                                 oninput={
-                                    let on_update = props.on_update_stroke.clone();
+                                    let on_update = props.on_update_opacity.clone();
                                     Callback::from(move |e: InputEvent| {
                                         if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
-                                            on_update.emit(input.value());
+                                            if let Ok(percent) = input.value().parse::<f32>() {
+                                                on_update.emit(percent / 100.0);
+                                            }
                                         }
                                     })
                                 }
-                                class="w-12 h-8 rounded border border-gray-300 bg-white cursor-pointer"
+                                class="flex-1 cursor-pointer"
                             />
+                            <span class="w-12 text-right text-sm text-gray-700">
+                                {opacity_percent.map_or("Mixed".to_string(), |percent| format!("{}%", percent))}
+                            </span>
+                        </div>
+                    </div>
+
+                    // Drop shadow
+                    <div>
+                        <label class="flex items-center gap-2 text-sm font-medium text-gray-700 mb-1">
                             <input
-                                type="text"
-                                value={selected.unwrap().stroke.clone()}
-                                oninput={
-                                    let on_update = props.on_update_stroke.clone();
-                                    Callback::from(move |e: InputEvent| {
+                                type="checkbox"
+                                data-testid="drop-shadow-toggle"
+                                checked={shared_shadow.is_some()}
+                                onchange={
+                                    let on_update = props.on_update_drop_shadow.clone();
+                                    Callback::from(move |e: Event| {
                                         if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
-                                            on_update.emit(input.value());
+                                            on_update.emit(input.checked().then(DropShadow::default));
                                         }
                                     })
                                 }
-                                class="flex-1 px-2 py-1 border border-gray-300 rounded text-sm bg-white text-gray-900"
                             />
-                        </div>
+                            {if drop_shadow.is_none() { "Drop Shadow (Mixed)" } else { "Drop Shadow" }}
+                        </label>
+                        if let Some(shadow) = shared_shadow {
+                            <div class="grid grid-cols-2 gap-2">
+                                <div>
+                                    <label class="block text-xs text-gray-500 mb-1">{"X"}</label>
+                                    <NumberInput
+                                        test_id="shadow-offset-x"
+                                        value={Some(shadow.offset.x as f64)}
+                                        on_change={offset_shadow(|shadow, x| Vec2::new(x, shadow.offset.y))}
+                                        class="w-full"
+                                    />
+                                </div>
+                                <div>
+                                    <label class="block text-xs text-gray-500 mb-1">{"Y"}</label>
+                                    <NumberInput
+                                        test_id="shadow-offset-y"
+                                        value={Some(shadow.offset.y as f64)}
+                                        on_change={offset_shadow(|shadow, y| Vec2::new(shadow.offset.x, y))}
+                                        class="w-full"
+                                    />
+                                </div>
+                            </div>
+                            <div class="flex items-center gap-2 mt-2">
+                                <input
+                                    type="range"
+                                    min="0"
+                                    max="40"
+                                    step="1"
+                                    title="Blur"
+                                    data-testid="shadow-blur"
+                                    value={shadow.blur.to_string()}
+                                    oninput={edit_shadow(|shadow, value| {
+                                        let blur = value.parse::<f32>().ok()?;
+                                        Some(DropShadow::new(shadow.offset, blur, shadow.color))
+                                    })}
+                                    class="flex-1 cursor-pointer"
+                                />
+                                // The picker sets the color; the shadow keeps its transparency
+                                <input
+                                    type="color"
+                                    title="Shadow color"
+                                    data-testid="shadow-color"
+                                    value={shadow.color.to_hex()}
+                                    oninput={edit_shadow(|shadow, value| {
+                                        let color = Color::parse(value)?;
+                                        Some(DropShadow { color: Color { a: shadow.color.a, ..color }, ..shadow })
+                                    })}
+                                    class="w-12 h-8 rounded border border-gray-300 bg-white cursor-pointer"
+                                />
+                            </div>
+                        }
                     </div>
 
                     // Position
@@ -106,40 +834,24 @@ pub fn properties_panel(props: &PropertiesPanelProps) -> Html {
                         <div class="grid grid-cols-2 gap-2">
                             <div>
                                 <label class="block text-xs text-gray-500 mb-1">{"X"}</label>
-                                <input
-                                    type="number"
-                                    value={bbox.unwrap().x.to_string()}
-                                    oninput={
+                                <NumberInput
+                                    value={Some(bbox.unwrap().x)}
+                                    on_change={
                                         let bbox = *bbox.unwrap();
-                                        let on_update = props.on_update_position.clone();
-                                        Callback::from(move |e: InputEvent| {
-                                            if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
-                                                if let Ok(x) = input.value().parse::<f64>() {
-                                                    on_update.emit((x, bbox.y));
-                                                }
-                                            }
-                                        })
+                                        props.on_update_position.reform(move |x: f64| (x, bbox.y))
                                     }
-                                    class="w-full px-2 py-1 border border-gray-300 rounded text-sm bg-white text-gray-900"
+                                    class="w-full"
                                 />
                             </div>
                             <div>
                                 <label class="block text-xs text-gray-500 mb-1">{"Y"}</label>
-                                <input
-                                    type="number"
-                                    value={bbox.unwrap().y.to_string()}
-                                    oninput={
+                                <NumberInput
+                                    value={Some(bbox.unwrap().y)}
+                                    on_change={
                                         let bbox = *bbox.unwrap();
-                                        let on_update = props.on_update_position.clone();
-                                        Callback::from(move |e: InputEvent| {
-                                            if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
-                                                if let Ok(y) = input.value().parse::<f64>() {
-                                                    on_update.emit((bbox.x, y));
-                                                }
-                                            }
-                                        })
+                                        props.on_update_position.reform(move |y: f64| (bbox.x, y))
                                     }
-                                    class="w-full px-2 py-1 border border-gray-300 rounded text-sm bg-white text-gray-900"
+                                    class="w-full"
                                 />
                             </div>
                         </div>
@@ -147,46 +859,57 @@ pub fn properties_panel(props: &PropertiesPanelProps) -> Html {
 
                     // Dimensions
                     <div>
-                        <label class="block text-sm font-medium text-gray-700 mb-1">
-                            {"Dimensions"}
-                        </label>
+                        <div class="flex items-center justify-between mb-1">
+                            <label class="block text-sm font-medium text-gray-700">
+                                {"Dimensions"}
+                            </label>
+                            <button
+                                data-testid="aspect-lock-toggle"
+                                title="Aspect ratio lock"
+                                aria-pressed={locked_ratio.is_some().to_string()}
+                                onclick={
+                                    let locked_ratio = locked_ratio.clone();
+                                    let bbox = *bbox.unwrap();
+                                    Callback::from(move |_: MouseEvent| {
+                                        locked_ratio.set(match *locked_ratio {
+                                            Some(_) => None,
+                                            None => Some(bbox.aspect_ratio()),
+                                        });
+                                    })
+                                }
+                                class={classes!(
+                                    "px-2", "py-0.5", "rounded", "border", "text-xs",
+                                    if locked_ratio.is_some() { "border-blue-500 bg-blue-50 text-blue-700" } else { "border-gray-300 text-gray-600 hover:bg-gray-50" }
+                                )}
+                            >
+                                {if locked_ratio.is_some() { "Locked" } else { "Unlocked" }}
+                            </button>
+                        </div>
                         <div class="grid grid-cols-2 gap-2">
                             <div>
                                 <label class="block text-xs text-gray-500 mb-1">{"Width"}</label>
-                                <input
-                                    type="number"
-                                    value={bbox.unwrap().width.to_string()}
-                                    oninput={
+                                <NumberInput
+                                    test_id="dim-width"
+                                    value={Some(bbox.unwrap().width)}
+                                    on_change={
                                         let bbox = *bbox.unwrap();
-                                        let on_update = props.on_update_dimensions.clone();
-                                        Callback::from(move |e: InputEvent| {
-                                            if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
-                                                if let Ok(w) = input.value().parse::<f64>() {
-                                                    on_update.emit((w, bbox.height));
-                                                }
-                                            }
-                                        })
+                                        let ratio = *locked_ratio;
+                                        props.on_update_dimensions.reform(move |w: f64| (w, ratio.map_or(bbox.height, |ratio| w / ratio)))
                                     }
-                                    class="w-full px-2 py-1 border border-gray-300 rounded text-sm bg-white text-gray-900"
+                                    class="w-full"
                                 />
                             </div>
                             <div>
                                 <label class="block text-xs text-gray-500 mb-1">{"Height"}</label>
-                                <input
-                                    type="number"
-                                    value={bbox.unwrap().height.to_string()}
-                                    oninput={
+                                <NumberInput
+                                    test_id="dim-height"
+                                    value={Some(bbox.unwrap().height)}
+                                    on_change={
                                         let bbox = *bbox.unwrap();
-                                        let on_update = props.on_update_dimensions.clone();
-                                        Callback::from(move |e: InputEvent| {
-                                            if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
-                                                if let Ok(h) = input.value().parse::<f64>() {
-                                                    on_update.emit((bbox.width, h));
-                                                }
-                                            }
-                                        })
+                                        let ratio = *locked_ratio;
+                                        props.on_update_dimensions.reform(move |h: f64| (ratio.map_or(bbox.width, |ratio| h * ratio), h))
                                     }
-                                    class="w-full px-2 py-1 border border-gray-300 rounded text-sm bg-white text-gray-900"
+                                    class="w-full"
                                 />
                             </div>
                         </div>
@@ -198,3 +921,75 @@ pub fn properties_panel(props: &PropertiesPanelProps) -> Html {
         </>
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::ShapeStyle;
+
+    fn filled_rect(id: u64, width: f32, fill: Color) -> Shape {
+        Shape::with_id(id, ShapeGeometry::rectangle(width, 10.0), ShapeStyle::new(Some(fill), None))
+    }
+
+    #[test]
+    fn test_common_value_is_none_when_values_differ() {
+        assert_eq!(common_value([1.0, 1.0, 1.0]), Some(1.0));
+        assert_eq!(common_value([1.0, 2.0, 1.0]), None);
+        assert_eq!(common_value(Vec::<f32>::new()), None);
+        // A shared "no shadow" is a value of its own, distinct from mixed
+        assert_eq!(common_value([None::<DropShadow>, None]), Some(None));
+    }
+
+    #[test]
+    fn test_fill_kind_applies_to_every_shape_within_its_own_bounds() {
+        let red = Color::rgb(1.0, 0.0, 0.0);
+        let blue = Color::rgb(0.0, 0.0, 1.0);
+        let shapes = [filled_rect(1, 10.0, red), filled_rect(2, 40.0, blue)];
+
+        let fills = fills_of_kind("linear", &shapes);
+        assert_eq!(fills.len(), 2);
+        assert_eq!(fills[0].0, 1);
+        assert_eq!(fills[0].1.primary_color(), red);
+        assert_eq!(fills[1].1.primary_color(), blue);
+        assert_ne!(fills[0].1, fills[1].1);
+        assert!(fills_of_kind("pattern", &shapes).is_empty());
+    }
+
+    #[test]
+    fn test_stop_color_recolors_each_gradient() {
+        let green = Color::rgb(0.0, 1.0, 0.0);
+        let shapes: Vec<Shape> = [(1, 10.0), (2, 40.0)]
+            .into_iter()
+            .map(|(id, width)| {
+                let mut shape = filled_rect(id, width, Color::black());
+                shape.style.fill = fill_of_kind("radial", &shape);
+                shape
+            })
+            .collect();
+
+        let fills = fills_with_stop_color(&shapes, 1, green);
+        assert_eq!(fills.len(), 2);
+        for ((id, fill), shape) in fills.iter().zip(&shapes) {
+            assert_eq!(*id, shape.id);
+            assert_eq!(fill.stops()[1].color, green);
+            assert_eq!(fill.primary_color(), Color::black());
+        }
+    }
+
+    #[test]
+    fn test_star_polygon_fields_edit_one_parameter() {
+        let star = ShapeGeometry::star(5, 50.0, 20.0);
+        assert_eq!(with_star_polygon_field(&star, 0, 7.4), Some(ShapeGeometry::star(7, 50.0, 20.0)));
+        assert_eq!(with_star_polygon_field(&star, 1, 80.0), Some(ShapeGeometry::star(5, 80.0, 20.0)));
+        assert_eq!(with_star_polygon_field(&star, 2, -5.0), Some(ShapeGeometry::star(5, 50.0, 0.0)));
+        // Point counts stay in a drawable range
+        assert_eq!(with_star_polygon_field(&star, 0, 1.0), Some(ShapeGeometry::star(MIN_STAR_POINTS, 50.0, 20.0)));
+        assert_eq!(with_star_polygon_field(&star, 0, 1e9), Some(ShapeGeometry::star(MAX_STAR_POINTS, 50.0, 20.0)));
+
+        let hexagon = ShapeGeometry::regular_polygon(6, 30.0);
+        assert_eq!(with_star_polygon_field(&hexagon, 0, 3.0), Some(ShapeGeometry::regular_polygon(3, 30.0)));
+        assert_eq!(with_star_polygon_field(&hexagon, 1, 45.0), Some(ShapeGeometry::regular_polygon(6, 45.0)));
+        assert_eq!(with_star_polygon_field(&hexagon, 2, 1.0), None);
+        assert_eq!(with_star_polygon_field(&ShapeGeometry::circle(5.0), 0, 1.0), None);
+    }
+}
@@ -1,20 +1,32 @@
 use yew::prelude::*;
-use web_sys::{MouseEvent, SvgsvgElement};
+use web_sys::{DragEvent, HtmlInputElement, MouseEvent, SvgsvgElement, WheelEvent};
 use wasm_bindgen::JsCast;
+use wasm_bindgen::closure::Closure;
 use gloo::events::EventListener;
 use std::rc::Rc;
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use web_sys::js_sys;
 use crate::types::*;
 use crate::utils::*;
-use crate::snap_logic::calculate_snap;
+use crate::snap_logic::{calculate_resize_snap, calculate_snap, constrain_resize_aspect, AxisLock, SnapConfig};
 use crate::layers_panel::{LayersPanel, ShapeInfo, ShapeType};
 use crate::properties_panel::PropertiesPanel;
+use crate::alignment::{compute_alignment_deltas, compute_distribution_deltas};
+use crate::alignment_panel::AlignmentPanel;
+use crate::toolbar::Toolbar;
+use crate::snap_settings::SnapSettings;
+use crate::clipboard::{deserialize_shapes, paste_copies, serialize_shapes, PASTE_OFFSET};
+use crate::image_import::{dropped_image_shape, dropped_svg_shapes, image_files, is_svg_file, MAX_DROP_FRACTION};
+use crate::gpu::decode_image;
 use crate::chat_panel::ChatPanel;
+use crate::canvas_tabs::CanvasState;
+use crate::autosave::{self, BrowserStorage, Debounce, SavedCanvas, AUTOSAVE_DELAY_MS};
 use crate::version::VersionHistory;
 use crate::version_panel::VersionHistoryPanel;
-use crate::components::GpuCanvas;
-use crate::scene::{Shape, ShapeGeometry, ShapeStyle, StrokeStyle, Vec2, BBox, Color, Transform2D, LayerTree, LayerNode};
+use crate::components::{GpuCanvas, Ruler, RulerAxis, RULER_SIZE};
+use crate::scene::{import_svg, SceneGraph, Shape, PieSlice, DEFAULT_FONT_SIZE, TEXT_ASCENT, TEXT_DESCENT, DropShadow, ShapeGeometry, ShapeStyle, StrokeStyle, Vec2, BBox, Color, CornerRadii, Fill, LineCap, LineJoin, Transform2D, LayerTree, LayerNode, LayerKey, DropPosition, BooleanOp, combine_shapes, control_handles, MIN_POLYGON_VERTICES};
 use crate::demo_paths::{create_snoopy_shapes, create_heart_shape, create_star_shape, create_flower_shape, create_spiral_shape};
 
 /// Compute GPU transform overrides for selected shapes during drag/scale operations
@@ -78,29 +90,235 @@ fn bbox_to_scene_bbox(bbox: &BoundingBox) -> BBox {
     )
 }
 
-const CANVAS_WIDTH: f64 = 800.0;
-const CANVAS_HEIGHT: f64 = 600.0;
+/// Canvas size until the center area has been measured
+const DEFAULT_CANVAS_WIDTH: f64 = 800.0;
+const DEFAULT_CANVAS_HEIGHT: f64 = 600.0;
+/// Smallest canvas, however narrow the center area gets
+const MIN_CANVAS_SIZE: f64 = 100.0;
+/// Quiet time before the canvas follows a change in the center area's size
+const RESIZE_DEBOUNCE_MS: u32 = 100;
 const MIN_SIZE: f64 = 10.0;
+/// Zoom change per wheel delta unit; a typical notch (100) zooms by ~e^0.2
+const WHEEL_ZOOM_SPEED: f64 = 0.002;
+/// Zoom factor of one +/- step from the zoom controls or Ctrl+= / Ctrl+-
+const ZOOM_STEP: f64 = 1.25;
+/// How close, in screen pixels, an Alt+click has to land to a polygon edge to add a vertex
+const EDGE_PICK_DISTANCE: f64 = 6.0;
+
+/// Zoom controls and their keyboard shortcuts
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ZoomAction {
+    In,
+    Out,
+    /// Back to 100%
+    Reset,
+    /// Fit all visible content in the canvas
+    Fit,
+}
+
+/// The view after `action`; stepping zooms about the center of a `canvas`-sized canvas
+fn apply_zoom_action(view: ViewTransform, action: ZoomAction, scene: &SceneGraph, canvas: Dimensions) -> ViewTransform {
+    let center = Point::new(canvas.width / 2.0, canvas.height / 2.0);
+    match action {
+        ZoomAction::In => view.zoomed_about(center, ZOOM_STEP),
+        ZoomAction::Out => view.zoomed_about(center, 1.0 / ZOOM_STEP),
+        ZoomAction::Reset => view.zoomed_about(center, 1.0 / view.zoom),
+        ZoomAction::Fit => fit_view_to_bounds(scene.content_bounds(), canvas.width, canvas.height),
+    }
+}
+
+/// Replay `touch` as a bubbling mouse event of type `kind` on the element the touch started on
+fn dispatch_touch_as_mouse(kind: &str, touch: &web_sys::Touch) {
+    let init = web_sys::MouseEventInit::new();
+    init.set_bubbles(true);
+    init.set_cancelable(true);
+    init.set_client_x(touch.client_x());
+    init.set_client_y(touch.client_y());
+    init.set_button(0);
+    init.set_buttons(if kind == "mouseup" { 0 } else { 1 });
+    if let (Ok(event), Some(target)) = (MouseEvent::new_with_mouse_event_init_dict(kind, &init), touch.target()) {
+        let _ = target.dispatch_event(&event);
+    }
+}
+
+/// Whether `touch` started on the canvas or its SVG overlay (handles, guides), rather than
+/// on a control floating over the canvas, which should get its usual tap
+fn is_canvas_touch(touch: &web_sys::Touch) -> bool {
+    touch.target().is_some_and(|target| {
+        target.has_type::<web_sys::HtmlCanvasElement>() || target.has_type::<web_sys::SvgElement>()
+    })
+}
+
+/// The first two touches in canvas element pixels, for pinch gestures
+fn pinch_points(touches: &web_sys::TouchList, svg: &SvgsvgElement) -> Option<(Point, Point)> {
+    let rect = svg.get_bounding_client_rect();
+    let local = |touch: web_sys::Touch| {
+        Point::new(touch.client_x() as f64 - rect.left(), touch.client_y() as f64 - rect.top())
+    };
+    Some((local(touches.get(0)?), local(touches.get(1)?)))
+}
+
+/// Canvas size filling `element`'s client area
+fn canvas_size_for(element: &web_sys::Element) -> Dimensions {
+    Dimensions::new(
+        (element.client_width() as f64).max(MIN_CANVAS_SIZE),
+        (element.client_height() as f64).max(MIN_CANVAS_SIZE),
+    )
+}
 
 /// Create a triangle shape from points
 fn create_triangle_shape(p1: Vec2, p2: Vec2, p3: Vec2, fill: Color, stroke: Color) -> Shape {
     let geometry = ShapeGeometry::Polygon {
         points: vec![p1, p2, p3],
     };
-    let style = ShapeStyle {
-        fill: Some(fill),
-        stroke: Some(StrokeStyle::new(stroke, 1.0)),
-    };
+    let style = ShapeStyle::fill_and_stroke(fill, StrokeStyle::new(stroke, 1.0));
     Shape::new(geometry, style)
 }
 
-/// Get all initial shapes and layer tree with Snoopy and Flower pre-grouped
-fn get_initial_shapes_and_tree() -> (Vec<Shape>, LayerTree) {
+/// Constrain a drag so the rectangle it spans is square (Shift while drawing)
+fn constrain_to_square(start: Point, end: Point) -> Point {
+    let side = (end.x - start.x).abs().max((end.y - start.y).abs());
+    Point::new(
+        start.x + side.copysign(end.x - start.x),
+        start.y + side.copysign(end.y - start.y),
+    )
+}
+
+/// Fill and stroke given to shapes made with the drawing tools
+fn drawn_shape_style() -> ShapeStyle {
+    ShapeStyle::new(
+        Color::parse("#d9d9d9"),
+        Some(StrokeStyle::new(Color::black(), 1.0)),
+    )
+}
+
+/// Close a pen tool draft into a polygon positioned at its first vertex
+/// Repeated clicks on the same spot (e.g. the double-click that finishes) add one vertex
+fn create_pen_shape(draft: &[Point]) -> Option<Shape> {
+    let mut points: Vec<Vec2> = Vec::with_capacity(draft.len());
+    for p in draft {
+        let p = Vec2::new(p.x as f32, p.y as f32);
+        if points.last().is_none_or(|last| last.distance(p) >= 1.0) {
+            points.push(p);
+        }
+    }
+    if points.len() < 3 {
+        return None;
+    }
+
+    let origin = points[0];
+    let geometry = ShapeGeometry::polygon(points.iter().map(|&p| p - origin).collect());
+    Some(Shape::new(geometry, drawn_shape_style()).with_transform(Transform2D::from_position(origin)))
+}
+
+/// Shortest text-tool drag that sets the font size; shorter ones use the default size
+const MIN_TEXT_DRAG_HEIGHT: f32 = 8.0;
+
+/// Tips on a star drawn with the star tool
+const STAR_TOOL_POINTS: u32 = 5;
+/// Inner radius of a drawn star as a fraction of the dragged outer radius
+const STAR_TOOL_INNER_RATIO: f32 = 0.4;
+
+/// Create the shape for a drawing-tool drag from `start` to `end`
+/// Rectangles and ellipses fill the drag rect; lines run from start to end;
+/// stars are centered on the start with the drag length as their outer radius;
+/// text starts at the drag's top-left, its font sized to fill the drag height
+fn create_drawn_shape(tool: ToolMode, start: Point, end: Point) -> Option<Shape> {
+    let origin = Vec2::new(start.x as f32, start.y as f32);
+    let delta = Vec2::new((end.x - start.x) as f32, (end.y - start.y) as f32);
+    let min = origin + delta.min(Vec2::ZERO);
+    let size = delta.abs();
+    let style = drawn_shape_style();
+
+    let shape = match tool {
+        ToolMode::Select | ToolMode::Pen => return None,
+        ToolMode::Rectangle => Shape::new(ShapeGeometry::rectangle(size.x, size.y), style)
+            .with_transform(Transform2D::from_position(min)),
+        ToolMode::Ellipse => Shape::new(ShapeGeometry::ellipse(size.x / 2.0, size.y / 2.0), style)
+            .with_transform(Transform2D::from_position(min + size / 2.0)),
+        ToolMode::Line => Shape::new(
+            ShapeGeometry::line(Vec2::ZERO, delta),
+            ShapeStyle::stroke_only(StrokeStyle::new(Color::black(), 2.0)),
+        )
+        .with_transform(Transform2D::from_position(origin)),
+        // A quarter slice centered on the drag start, opening toward the drag
+        ToolMode::Arc => {
+            let radius = size.max_element();
+            let start_angle = match (delta.x < 0.0, delta.y < 0.0) {
+                (false, false) => 0.0,
+                (true, false) => 90.0,
+                (true, true) => 180.0,
+                (false, true) => 270.0,
+            };
+            Shape::new(ShapeGeometry::pie_slice(PieSlice::new(radius, start_angle, start_angle + 90.0)), style)
+                .with_transform(Transform2D::from_position(origin))
+        }
+        ToolMode::Star => {
+            let outer_radius = delta.length();
+            Shape::new(
+                ShapeGeometry::star(STAR_TOOL_POINTS, outer_radius, outer_radius * STAR_TOOL_INNER_RATIO),
+                style,
+            )
+            .with_transform(Transform2D::from_position(origin))
+        }
+        ToolMode::Text => {
+            let line_height = TEXT_ASCENT + TEXT_DESCENT;
+            let font_size = if size.y >= MIN_TEXT_DRAG_HEIGHT { size.y / line_height } else { DEFAULT_FONT_SIZE };
+            Shape::new(ShapeGeometry::text("Text", font_size), ShapeStyle::new(Some(Color::black()), None))
+                .with_transform(Transform2D::from_position(min + Vec2::new(0.0, TEXT_ASCENT * font_size)))
+        }
+    };
+    Some(shape)
+}
+
+/// Fills the add buttons cycle through
+const PASTEL_PALETTE: [(u8, u8, u8); 8] = [
+    (255, 179, 186),
+    (255, 223, 186),
+    (255, 255, 186),
+    (186, 255, 201),
+    (186, 225, 255),
+    (204, 190, 255),
+    (255, 204, 238),
+    (190, 240, 235),
+];
+
+static NEXT_PASTEL: AtomicUsize = AtomicUsize::new(0);
+
+/// Next color from the pastel palette, so consecutive added shapes differ
+fn random_pastel_color() -> Color {
+    let (r, g, b) = PASTEL_PALETTE[NEXT_PASTEL.fetch_add(1, Ordering::Relaxed) % PASTEL_PALETTE.len()];
+    Color::from_rgba8(r, g, b, 255)
+}
+
+/// Create the shape for a toolbar add button, centered on `center`
+fn create_added_shape(kind: AddShape, center: Vec2) -> Shape {
+    let style = ShapeStyle::fill_only(random_pastel_color());
+    match kind {
+        AddShape::Rectangle => Shape::new(ShapeGeometry::rectangle(100.0, 80.0), style)
+            .with_transform(Transform2D::from_position(center - Vec2::new(50.0, 40.0))),
+        AddShape::Ellipse => Shape::new(ShapeGeometry::ellipse(50.0, 40.0), style)
+            .with_transform(Transform2D::from_position(center)),
+        // A pentagon with its circumcircle around the center, pointing up
+        AddShape::Polygon => {
+            let points = (0..5)
+                .map(|i| {
+                    let angle = (i as f32 / 5.0 - 0.25) * std::f32::consts::TAU;
+                    Vec2::new(angle.cos(), angle.sin()) * 50.0
+                })
+                .collect();
+            Shape::new(ShapeGeometry::polygon(points), style).with_transform(Transform2D::from_position(center))
+        }
+    }
+}
+
+/// Get the initial scene and layer tree with Snoopy and Flower pre-grouped
+pub(crate) fn get_initial_scene_and_tree() -> (SceneGraph, LayerTree) {
     let mut shapes = Vec::new();
     let mut tree = LayerTree::new();
 
     // Triangle 1 (red)
-    let red = Color::from_hex("#ff6347").unwrap_or_else(Color::black);
+    let red = Color::parse("#ff6347").unwrap_or_else(Color::black);
     let tri1 = create_triangle_shape(
         Vec2::new(230.0, 220.0),
         Vec2::new(260.0, 220.0),
@@ -112,7 +330,7 @@ fn get_initial_shapes_and_tree() -> (Vec<Shape>, LayerTree) {
     shapes.push(tri1);
 
     // Triangle 2 (blue)
-    let blue = Color::from_hex("#4682b4").unwrap_or_else(Color::black);
+    let blue = Color::parse("#4682b4").unwrap_or_else(Color::black);
     let tri2 = create_triangle_shape(
         Vec2::new(270.0, 230.0),
         Vec2::new(300.0, 230.0),
@@ -124,7 +342,7 @@ fn get_initial_shapes_and_tree() -> (Vec<Shape>, LayerTree) {
     shapes.push(tri2);
 
     // Triangle 3 (green)
-    let green = Color::from_hex("#9acd32").unwrap_or_else(Color::black);
+    let green = Color::parse("#9acd32").unwrap_or_else(Color::black);
     let tri3 = create_triangle_shape(
         Vec2::new(240.0, 270.0),
         Vec2::new(270.0, 270.0),
@@ -195,34 +413,76 @@ fn get_initial_shapes_and_tree() -> (Vec<Shape>, LayerTree) {
         }
     }
 
-    (shapes, tree)
+    (SceneGraph::from_shapes(shapes), tree)
 }
 
 /// Calculate bounding box for a set of shapes
-fn calculate_shapes_bounding_box(shapes: &[Shape]) -> BoundingBox {
-    if shapes.is_empty() {
-        return BoundingBox::new(0.0, 0.0, 0.0, 0.0);
-    }
+fn calculate_shapes_bounding_box<'a>(shapes: impl IntoIterator<Item = &'a Shape>) -> BoundingBox {
+    shapes
+        .into_iter()
+        .map(|shape| shape.world_bounds())
+        .reduce(|acc, bounds| acc.union(&bounds))
+        .map(|bounds| {
+            BoundingBox::new(
+                bounds.min.x as f64,
+                bounds.min.y as f64,
+                bounds.width() as f64,
+                bounds.height() as f64,
+            )
+        })
+        .unwrap_or_else(|| BoundingBox::new(0.0, 0.0, 0.0, 0.0))
+}
+
+/// Scale factor along one axis from a base to a current signed size
+/// A zero-size axis (e.g. a selection of horizontal lines) has no scale and stays at 1
+fn axis_scale(current: f64, base: f64) -> f32 {
+    let scale = current / base;
+    if scale.is_finite() { scale as f32 } else { 1.0 }
+}
+
+/// Calculate bounding box of the current selection in a scene
+fn calculate_selection_bounding_box(scene: &SceneGraph) -> BoundingBox {
+    calculate_shapes_bounding_box(scene.selected_shapes())
+}
 
-    let mut min_x = f32::INFINITY;
-    let mut max_x = f32::NEG_INFINITY;
-    let mut min_y = f32::INFINITY;
-    let mut max_y = f32::NEG_INFINITY;
-
-    for shape in shapes {
-        let bounds = shape.world_bounds();
-        min_x = min_x.min(bounds.min.x);
-        max_x = max_x.max(bounds.max.x);
-        min_y = min_y.min(bounds.min.y);
-        max_y = max_y.max(bounds.max.y);
+/// Store a new scene in both the render state and the ref read by window-level listeners
+/// Split the selection into units that move together (a lone shape or the
+/// selected members of a group), paired with each unit's index and world bounds
+fn selection_units(scene: &SceneGraph, layer_tree: &LayerTree) -> (Vec<Vec<u64>>, Vec<(usize, BBox)>) {
+    let selected_ids = scene.selection();
+
+    // Group selected shapes so grouped shapes move together
+    let mut units: Vec<Vec<u64>> = Vec::new();
+    for &id in selected_ids.iter() {
+        if units.iter().any(|unit| unit.contains(&id)) {
+            continue;
+        }
+        let unit: Vec<u64> = layer_tree
+            .get_selection_for_shape(id)
+            .into_iter()
+            .filter(|member| selected_ids.contains(member))
+            .collect();
+        units.push(unit);
     }
 
-    BoundingBox::new(
-        min_x as f64,
-        min_y as f64,
-        (max_x - min_x) as f64,
-        (max_y - min_y) as f64,
-    )
+    let unit_bounds: Vec<(usize, BBox)> = units
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, unit)| {
+            unit.iter()
+                .filter_map(|id| scene.get_shape(*id))
+                .map(|s| s.world_bounds())
+                .reduce(|acc, b| acc.union(&b))
+                .map(|bounds| (idx, bounds))
+        })
+        .collect();
+
+    (units, unit_bounds)
+}
+
+fn commit_scene(scene: &UseStateHandle<SceneGraph>, scene_ref: &Rc<RefCell<SceneGraph>>, next: SceneGraph) {
+    *scene_ref.borrow_mut() = next.clone();
+    scene.set(next);
 }
 
 // Right Panel Component with Tab Bar
@@ -231,12 +491,32 @@ pub struct RightPanelProps {
     pub active_tab: ActiveTab,
     pub has_unsaved_changes: bool,
     pub on_tab_change: Callback<ActiveTab>,
-    pub selected_polygon: Option<Polygon>,
+    pub selected_shapes: Vec<Shape>,
     pub properties_bbox: Option<BoundingBox>,
+    pub selection_count: usize,
+    pub locked_notice: bool,
+    pub on_align: Callback<AlignMode>,
+    pub on_distribute: Callback<DistributeMode>,
+    pub can_distribute: bool,
     pub on_update_fill: Callback<String>,
     pub on_update_stroke: Callback<String>,
     pub on_update_position: Callback<(f64, f64)>,
     pub on_update_dimensions: Callback<(f64, f64)>,
+    pub on_update_opacity: Callback<f32>,
+    pub group_opacity: Option<f32>,
+    pub on_update_group_opacity: Callback<f32>,
+    pub on_update_stroke_width: Callback<f64>,
+    pub on_update_dash: Callback<Option<Vec<f32>>>,
+    pub on_update_line_style: Callback<(Option<LineCap>, Option<LineJoin>)>,
+    pub on_update_pie_slice: Callback<PieSlice>,
+    pub on_update_corner_radii: Callback<CornerRadii>,
+    pub on_update_star_polygon: Callback<ShapeGeometry>,
+    pub on_update_fill_paint: Callback<Vec<(u64, Fill)>>,
+    pub on_smooth_polygon: Callback<f32>,
+    pub on_update_text: Callback<(String, f32)>,
+    pub on_update_drop_shadow: Callback<Option<DropShadow>>,
+    pub on_boolean_op: Callback<BooleanOp>,
+    pub on_convert_to_path: Callback<bool>,
     pub chat_messages: Vec<Message>,
     pub on_send_message: Callback<String>,
     pub version_history: VersionHistory,
@@ -287,14 +567,37 @@ fn right_panel(props: &RightPanelProps) -> Html {
             // Panel Content
             if props.active_tab == ActiveTab::Design {
                 <div class="flex-1 overflow-y-auto p-4">
+                    if props.selection_count >= 2 {
+                        <AlignmentPanel
+                            on_align={props.on_align.clone()}
+                            on_distribute={props.on_distribute.clone()}
+                            can_distribute={props.can_distribute}
+                        />
+                    }
                     <PropertiesPanel
                         active_tab={props.active_tab}
-                        selected_polygon={props.selected_polygon.clone()}
+                        selected_shapes={props.selected_shapes.clone()}
                         bounding_box={props.properties_bbox}
+                        locked_notice={props.locked_notice}
                         on_update_fill={props.on_update_fill.clone()}
                         on_update_stroke={props.on_update_stroke.clone()}
                         on_update_position={props.on_update_position.clone()}
                         on_update_dimensions={props.on_update_dimensions.clone()}
+                        on_update_opacity={props.on_update_opacity.clone()}
+                        group_opacity={props.group_opacity}
+                        on_update_group_opacity={props.on_update_group_opacity.clone()}
+                        on_update_stroke_width={props.on_update_stroke_width.clone()}
+                        on_update_dash={props.on_update_dash.clone()}
+                        on_update_line_style={props.on_update_line_style.clone()}
+                        on_update_pie_slice={props.on_update_pie_slice.clone()}
+                        on_update_corner_radii={props.on_update_corner_radii.clone()}
+                        on_update_star_polygon={props.on_update_star_polygon.clone()}
+                        on_update_fill_paint={props.on_update_fill_paint.clone()}
+                        on_smooth_polygon={props.on_smooth_polygon.clone()}
+                        on_update_text={props.on_update_text.clone()}
+                        on_update_drop_shadow={props.on_update_drop_shadow.clone()}
+                        on_boolean_op={props.on_boolean_op.clone()}
+                        on_convert_to_path={props.on_convert_to_path.clone()}
                     />
                 </div>
             }
@@ -318,16 +621,24 @@ fn right_panel(props: &RightPanelProps) -> Html {
     }
 }
 
+#[derive(Properties, PartialEq)]
+pub struct ResizableCanvasProps {
+    /// The tab's saved state; only read when the canvas mounts
+    pub canvas: CanvasState,
+    /// Receives the canvas's state when it unmounts, e.g. on switching tabs
+    #[prop_or_default]
+    pub on_snapshot: Callback<CanvasState>,
+}
+
 #[function_component(ResizableCanvas)]
-pub fn resizable_canvas() -> Html {
-    // State - unified shapes list (triangles + demo shapes like Snoopy)
-    // Initialize both shapes and layer tree together with pre-grouped Snoopy and Flower
+pub fn resizable_canvas(props: &ResizableCanvasProps) -> Html {
+    // State - the scene graph is the single source of truth for shapes and selection
     let initial_data = use_memo((), |_| {
-        get_initial_shapes_and_tree()
+        (SceneGraph::from_shapes(props.canvas.shapes.clone()), props.canvas.layer_tree.clone())
     });
 
-    let shapes = use_state(|| initial_data.0.clone());
-    let selected_ids = use_state(|| Vec::<u64>::new());
+    let scene = use_state(|| initial_data.0.clone());
+    let selected_ids = scene.selection().to_vec();
 
     // Layer tree for grouping - synced with shapes
     let layer_tree = use_state(|| initial_data.1.clone());
@@ -340,33 +651,161 @@ pub fn resizable_canvas() -> Html {
     let is_moving = use_state(|| false);
     let active_handle = use_state(|| None::<HandleName>);
     let hovered_id = use_state(|| None::<u64>);
+    let cursor_position = use_state(Point::zero);
     let selection_rect = use_state(|| None::<SelectionRect>);
     let selection_origin = use_state(|| None::<Point>);
     let guidelines = use_state(|| Vec::<Guideline>::new());
+    let spacing_indicators = use_state(Vec::<SpacingIndicator>::new);
+    let toast_message = use_state(|| None::<String>);
     let preview_bbox = use_state(|| None::<BoundingBox>);
+    let tool_mode = use_state(ToolMode::default);
+    let snap_config = use_state(SnapConfig::default);
+    // Draw shapes with vertices on pixel centers, for crisp thin strokes
+    let pixel_snap = use_state(|| false);
+    // Shapes edited from the properties panel spring to their new position and size
+    let animations = use_state(|| true);
+    // Bumped by edits that should animate rather than jump
+    let transition_version = use_state(|| 0u32);
+    // Multisample anti-aliasing; costly on mobile GPUs, so it can be turned off
+    let msaa = use_state(|| true);
+    // Canvas zoom (Ctrl+wheel / pinch) and pan (wheel), in screen pixels
+    let wheel_zoom = use_state(|| props.canvas.view.zoom);
+    let pan_offset = use_state(|| props.canvas.view.pan);
+    // Dragging the view with the middle button or Space held
+    let is_panning = use_state(|| false);
+    let is_drawing = use_state(|| false);
+    let draw_rect = use_state(|| None::<SelectionRect>);
+    // Vertices placed so far with the pen tool (mirrored in `draft_polygon_ref` for listeners)
+    let draft_polygon = use_state(Vec::<Point>::new);
+    // Set when the last selection attempt landed on a locked shape
+    let locked_notice = use_state(|| false);
+    // Vertex editing of a single polygon or path, the handle being dragged in it, and
+    // the vertex Backspace removes
+    let edit_mode = use_state(EditMode::default);
+    let dragged_handle = use_state(|| None::<EditHandle>);
+    let selected_vertex = use_state(|| None::<usize>);
+    // Vertex editing only applies while its shape is the sole selection
+    let vertex_edit_id = match *edit_mode {
+        EditMode::Vertex { shape_id } if *tool_mode == ToolMode::Select && selected_ids == [shape_id] => Some(shape_id),
+        _ => None,
+    };
     let active_tab = use_state(|| ActiveTab::Design);
     let chat_messages = use_state(|| vec![
         Message::assistant("Hello! I'm your design assistant. How can I help you today?".to_string())
     ]);
 
     // Version history
-    let version_history = use_state(VersionHistory::new);
-    let has_unsaved_changes = use_state(|| true);  // Start as true (initial state is unsaved)
+    let version_history = use_state(|| props.canvas.version_history.clone());
+    let has_unsaved_changes = use_state(|| props.canvas.has_unsaved_changes);
+    // Mirrors of the above for the unmount snapshot
+    let version_meta_ref = use_mut_ref(|| (props.canvas.version_history.clone(), props.canvas.has_unsaved_changes));
 
     // GPU rendering
     let render_version = use_state(|| 0u32);
 
     // Refs
     let svg_ref = use_node_ref();
+    let canvas_container_ref = use_node_ref();
+    let canvas_area_ref = use_node_ref();
+    let import_input_ref = use_node_ref();
+    // Keeps the in-flight SVG file read alive until it completes
+    let import_reader = use_mut_ref(|| None::<gloo::file::callbacks::FileReader>);
+    // Keeps in-flight reads of dropped image files alive, keyed by drop order
+    let image_readers = use_mut_ref(HashMap::<u64, gloo::file::callbacks::FileReader>::new);
+    let next_image_reader = use_mut_ref(|| 0u64);
     let move_start = use_mut_ref(|| None::<(Point, Point)>);
     let resize_start_anchor = use_mut_ref(|| None::<Point>);
     let resize_base_signed = use_mut_ref(|| None::<Dimensions>);
     let resize_current_dims = use_mut_ref(|| None::<Dimensions>);
+    let line_start = use_mut_ref(|| None::<Point>);
+    let draw_start = use_mut_ref(|| None::<Point>);
+    let draft_polygon_ref = use_mut_ref(Vec::<Point>::new);
+    // Last client position of a view pan drag, and whether Space is held to start one
+    let pan_last = use_mut_ref(|| None::<Point>);
+    let space_held = use_mut_ref(|| false);
 
     // Refs for keyboard handler to access current values
     // Updated directly when state changes (no sync effects needed)
-    let selected_ids_ref = use_mut_ref(|| Vec::<u64>::new());
+    let scene_ref = use_mut_ref(|| initial_data.0.clone());
     let layer_tree_ref = use_mut_ref(|| initial_data.1.clone());
+    // Current view for pointer conversion inside window listeners
+    let view = ViewTransform::new(*wheel_zoom, *pan_offset);
+    let view_ref = use_mut_ref(ViewTransform::default);
+    *view_ref.borrow_mut() = view;
+    // Canvas size in CSS pixels; it fills the center area and follows it as the window resizes
+    let canvas_size = use_state_eq(|| Dimensions::new(DEFAULT_CANVAS_WIDTH, DEFAULT_CANVAS_HEIGHT));
+    let canvas_size_ref = use_mut_ref(|| *canvas_size);
+    *canvas_size_ref.borrow_mut() = *canvas_size;
+
+    // Hand the canvas's state back to its tab when it unmounts
+    {
+        let version_history = version_history.clone();
+        let has_unsaved_changes = has_unsaved_changes.clone();
+        let version_meta_ref = version_meta_ref.clone();
+        use_effect_with(((*version_history).clone(), *has_unsaved_changes), move |meta| {
+            *version_meta_ref.borrow_mut() = meta.clone();
+            || ()
+        });
+    }
+    {
+        let scene_ref = scene_ref.clone();
+        let layer_tree_ref = layer_tree_ref.clone();
+        let view_ref = view_ref.clone();
+        let version_meta_ref = version_meta_ref.clone();
+        let on_snapshot = props.on_snapshot.clone();
+        let (id, title) = (props.canvas.id, props.canvas.title.clone());
+        use_effect_with((), move |_| {
+            move || {
+                let (version_history, has_unsaved_changes) = version_meta_ref.borrow().clone();
+                on_snapshot.emit(CanvasState {
+                    id,
+                    title,
+                    shapes: scene_ref.borrow().shapes().to_vec(),
+                    layer_tree: layer_tree_ref.borrow().clone(),
+                    version_history,
+                    has_unsaved_changes,
+                    view: *view_ref.borrow(),
+                });
+            }
+        });
+    }
+
+    // Autosave the canvas to localStorage once edits, grouping or the view settle
+    // (see `autosave`); every change pushes the save back, so a burst saves once
+    let autosave_debounce = use_mut_ref(Debounce::default);
+    {
+        let autosave_debounce = autosave_debounce.clone();
+        let scene_ref = scene_ref.clone();
+        let layer_tree_ref = layer_tree_ref.clone();
+        let view_ref = view_ref.clone();
+        let toast_message = toast_message.clone();
+        use_effect_with((*render_version, (*layer_tree).clone(), view), move |_| {
+            autosave_debounce.borrow_mut().touch(js_sys::Date::now());
+            let timeout = gloo::timers::callback::Timeout::new(AUTOSAVE_DELAY_MS, move || {
+                if !autosave_debounce.borrow_mut().take_due(js_sys::Date::now()) {
+                    return;
+                }
+                let canvas = SavedCanvas {
+                    shapes: scene_ref.borrow().shapes().to_vec(),
+                    layer_tree: layer_tree_ref.borrow().clone(),
+                    view: *view_ref.borrow(),
+                };
+                if let Err(err) = autosave::save(&mut BrowserStorage, &canvas) {
+                    toast_message.set(Some(format!("Couldn't autosave: {}", err)));
+                }
+            });
+            // Forgetting the timer lets it outlive this change; the debounce skips stale ones
+            timeout.forget();
+        });
+    }
+
+    let on_clear_saved_data = {
+        let toast_message = toast_message.clone();
+        Callback::from(move |_: MouseEvent| {
+            autosave::clear(&mut BrowserStorage);
+            toast_message.set(Some("Cleared saved data".to_string()));
+        })
+    };
 
     // Keyboard shortcut for Cmd/Ctrl+K (cycle through tabs: Design -> Chat -> Versions -> Design)
     {
@@ -394,11 +833,11 @@ pub fn resizable_canvas() -> Html {
         });
     }
 
-    // Keyboard shortcut for Cmd/Ctrl+G (group selected shapes)
+    // Keyboard shortcuts for Cmd/Ctrl+G (group selected shapes) and Cmd/Ctrl+Shift+G (ungroup)
     // Use capture phase to intercept before Chrome's "Find Next" handler
     {
         let layer_tree = layer_tree.clone();
-        let selected_ids_ref = selected_ids_ref.clone();
+        let scene_ref = scene_ref.clone();
         let layer_tree_ref = layer_tree_ref.clone();
         let has_unsaved_changes = has_unsaved_changes.clone();
         use_effect_with((), move |_| {
@@ -410,7 +849,7 @@ pub fn resizable_canvas() -> Html {
             let listener = EventListener::new_with_options(&document, "keydown", options, move |event| {
                 if let Some(keyboard_event) = event.dyn_ref::<web_sys::KeyboardEvent>() {
                     if (keyboard_event.meta_key() || keyboard_event.ctrl_key())
-                        && keyboard_event.key() == "g"
+                        && keyboard_event.key().eq_ignore_ascii_case("g")
                     {
                         // Stop the event from reaching Chrome's handlers
                         keyboard_event.prevent_default();
@@ -418,25 +857,23 @@ pub fn resizable_canvas() -> Html {
                         keyboard_event.stop_immediate_propagation();
 
                         // Read current values from refs
-                        let ids = selected_ids_ref.borrow().clone();
-                        web_sys::console::log_1(&format!("Cmd+G pressed, selected_ids: {:?}", ids).into());
-
-                        if ids.len() >= 2 {
-                            let mut updated_tree = layer_tree_ref.borrow().clone();
-                            web_sys::console::log_1(&format!("Layer tree before: {:?}", updated_tree.nodes).into());
-
-                            if let Some(group_id) = updated_tree.group_shapes(&ids) {
-                                web_sys::console::log_1(&format!("Created group with id: {}", group_id).into());
-                                web_sys::console::log_1(&format!("Layer tree after: {:?}", updated_tree.nodes).into());
-                                // Update both the state and the ref
-                                *layer_tree_ref.borrow_mut() = updated_tree.clone();
-                                layer_tree.set(updated_tree);
-                                has_unsaved_changes.set(true);
-                            } else {
-                                web_sys::console::log_1(&"group_shapes returned None".into());
-                            }
+                        let ids = scene_ref.borrow().selection().to_vec();
+                        let mut updated_tree = layer_tree_ref.borrow().clone();
+
+                        let changed = if keyboard_event.shift_key() {
+                            // Cmd+Shift+G: ungroup the group that is exactly the selection
+                            updated_tree
+                                .group_for_selection(&ids)
+                                .is_some_and(|group_id| updated_tree.ungroup(group_id))
                         } else {
-                            web_sys::console::log_1(&format!("Need 2+ shapes, got {}", ids.len()).into());
+                            updated_tree.group_shapes(&ids).is_some()
+                        };
+
+                        if changed {
+                            // Update both the state and the ref
+                            *layer_tree_ref.borrow_mut() = updated_tree.clone();
+                            layer_tree.set(updated_tree);
+                            has_unsaved_changes.set(true);
                         }
                     }
                 }
@@ -446,6 +883,205 @@ pub fn resizable_canvas() -> Html {
         });
     }
 
+    // Size the canvas to the center area, then track it with a ResizeObserver
+    // Debounced so dragging a window edge settles into a single resize
+    {
+        let canvas_area_ref = canvas_area_ref.clone();
+        let canvas_size = canvas_size.clone();
+        use_effect_with((), move |_| {
+            let observed = canvas_area_ref.cast::<web_sys::Element>().and_then(|area| {
+                canvas_size.set(canvas_size_for(&area));
+
+                let pending = Rc::new(RefCell::new(None::<gloo::timers::callback::Timeout>));
+                let on_resize = {
+                    let area = area.clone();
+                    let pending = pending.clone();
+                    Closure::<dyn FnMut()>::new(move || {
+                        let area = area.clone();
+                        let canvas_size = canvas_size.clone();
+                        // Replacing the pending timeout cancels it
+                        *pending.borrow_mut() = Some(gloo::timers::callback::Timeout::new(RESIZE_DEBOUNCE_MS, move || {
+                            canvas_size.set(canvas_size_for(&area));
+                        }));
+                    })
+                };
+                let observer = web_sys::ResizeObserver::new(on_resize.as_ref().unchecked_ref()).ok()?;
+                observer.observe(&area);
+                Some((observer, on_resize, pending))
+            });
+
+            move || {
+                if let Some((observer, _on_resize, _pending)) = observed {
+                    observer.disconnect();
+                }
+            }
+        });
+    }
+
+    // Zoom buttons under the canvas
+    let on_zoom = {
+        let scene_ref = scene_ref.clone();
+        let view_ref = view_ref.clone();
+        let canvas_size_ref = canvas_size_ref.clone();
+        let wheel_zoom = wheel_zoom.clone();
+        let pan_offset = pan_offset.clone();
+        Callback::from(move |action: ZoomAction| {
+            let next = apply_zoom_action(*view_ref.borrow(), action, &scene_ref.borrow(), *canvas_size_ref.borrow());
+            *view_ref.borrow_mut() = next;
+            wheel_zoom.set(next.zoom);
+            pan_offset.set(next.pan);
+        })
+    };
+
+    // Keyboard shortcuts: Ctrl/Cmd+0 (100%), Ctrl/Cmd+1 (fit), Ctrl/Cmd+= and Ctrl/Cmd+- (step)
+    {
+        let on_zoom = on_zoom.clone();
+        use_effect_with((), move |_| {
+            let window = web_sys::window().expect("no window");
+            let document = window.document().expect("no document");
+
+            // Non-passive so the browser's own page zoom can be suppressed
+            let options = gloo::events::EventListenerOptions::enable_prevent_default();
+            let listener = EventListener::new_with_options(&document, "keydown", options, move |event| {
+                let Some(keyboard_event) = event.dyn_ref::<web_sys::KeyboardEvent>() else {
+                    return;
+                };
+                if !(keyboard_event.meta_key() || keyboard_event.ctrl_key()) {
+                    return;
+                }
+                let action = match keyboard_event.key().as_str() {
+                    "0" => ZoomAction::Reset,
+                    "1" => ZoomAction::Fit,
+                    "=" | "+" => ZoomAction::In,
+                    "-" | "_" => ZoomAction::Out,
+                    _ => return,
+                };
+                keyboard_event.prevent_default();
+                on_zoom.emit(action);
+            });
+
+            move || drop(listener)
+        });
+    }
+
+    // Wheel over the canvas: Ctrl (and trackpad pinch, which reports Ctrl) zooms
+    // about the cursor, a plain wheel pans. Non-passive so the page doesn't scroll or zoom.
+    {
+        let canvas_container_ref = canvas_container_ref.clone();
+        let svg_ref = svg_ref.clone();
+        let view_ref = view_ref.clone();
+        let wheel_zoom = wheel_zoom.clone();
+        let pan_offset = pan_offset.clone();
+        use_effect_with((), move |_| {
+            let listener = canvas_container_ref.cast::<web_sys::HtmlElement>().map(|container| {
+                let options = gloo::events::EventListenerOptions::enable_prevent_default();
+                EventListener::new_with_options(&container, "wheel", options, move |event| {
+                    let (Some(wheel), Some(svg)) = (event.dyn_ref::<WheelEvent>(), svg_ref.cast::<SvgsvgElement>()) else {
+                        return;
+                    };
+                    wheel.prevent_default();
+
+                    let current = *view_ref.borrow();
+                    let next = if wheel.ctrl_key() {
+                        let rect = svg.get_bounding_client_rect();
+                        let anchor = Point::new(
+                            wheel.client_x() as f64 - rect.left(),
+                            wheel.client_y() as f64 - rect.top(),
+                        );
+                        current.zoomed_about(anchor, (-wheel.delta_y() * WHEEL_ZOOM_SPEED).exp())
+                    } else {
+                        current.panned(-wheel.delta_x(), -wheel.delta_y())
+                    };
+
+                    // Update the ref too so events arriving before the re-render accumulate
+                    *view_ref.borrow_mut() = next;
+                    wheel_zoom.set(next.zoom);
+                    pan_offset.set(next.pan);
+                })
+            });
+
+            move || drop(listener)
+        });
+    }
+
+    // Space toggles drag-to-pan while held (not while typing in a field)
+    {
+        let space_held = space_held.clone();
+        use_effect_with((), move |_| {
+            let window = web_sys::window().expect("no window");
+            let document = window.document().expect("no document");
+
+            let on_key = |pressed: bool| {
+                let space_held = space_held.clone();
+                move |event: &web_sys::Event| {
+                    let Some(keyboard_event) = event.dyn_ref::<web_sys::KeyboardEvent>() else {
+                        return;
+                    };
+                    if keyboard_event.code() != "Space" {
+                        return;
+                    }
+                    if event.target().and_then(|t| t.dyn_into::<HtmlInputElement>().ok()).is_some() {
+                        return;
+                    }
+                    // Keep the page from scrolling
+                    event.prevent_default();
+                    *space_held.borrow_mut() = pressed;
+                }
+            };
+            let keydown = EventListener::new(&document, "keydown", on_key(true));
+            let keyup = EventListener::new(&document, "keyup", on_key(false));
+
+            move || {
+                drop(keydown);
+                drop(keyup);
+            }
+        });
+    }
+
+    // Window-level view pan handlers (active while a pan drag is in progress)
+    {
+        let is_panning = is_panning.clone();
+        let pan_last = pan_last.clone();
+        let view_ref = view_ref.clone();
+        let wheel_zoom = wheel_zoom.clone();
+        let pan_offset = pan_offset.clone();
+
+        use_effect_with(*is_panning, move |panning| -> Box<dyn FnOnce()> {
+            if !*panning {
+                return Box::new(|| ());
+            }
+            let window = web_sys::window().expect("no window");
+
+            let mousemove_listener = {
+                let pan_last = pan_last.clone();
+                EventListener::new(&window, "mousemove", move |event| {
+                    let mouse_event = event.dyn_ref::<MouseEvent>().unwrap();
+                    let client = Point::new(mouse_event.client_x() as f64, mouse_event.client_y() as f64);
+                    let Some(last) = pan_last.replace(Some(client)) else {
+                        return;
+                    };
+                    let next = view_ref.borrow().panned(client.x - last.x, client.y - last.y);
+                    *view_ref.borrow_mut() = next;
+                    wheel_zoom.set(next.zoom);
+                    pan_offset.set(next.pan);
+                })
+            };
+
+            let mouseup_listener = {
+                let is_panning = is_panning.clone();
+                EventListener::new(&window, "mouseup", move |_event| {
+                    pan_last.replace(None);
+                    is_panning.set(false);
+                })
+            };
+
+            Box::new(move || {
+                drop(mousemove_listener);
+                drop(mouseup_listener);
+            })
+        });
+    }
+
     // Calculated values
     let has_selection = !selected_ids.is_empty();
     let base_signed_dims = resize_base_signed
@@ -478,9 +1114,8 @@ pub fn resizable_canvas() -> Html {
 
     // Selection handler
     let set_selection_from_ids = {
-        let shapes = shapes.clone();
-        let selected_ids = selected_ids.clone();
-        let selected_ids_ref = selected_ids_ref.clone();
+        let scene = scene.clone();
+        let scene_ref = scene_ref.clone();
         let fixed_anchor = fixed_anchor.clone();
         let dimensions = dimensions.clone();
         let base_dimensions = base_dimensions.clone();
@@ -490,24 +1125,20 @@ pub fn resizable_canvas() -> Html {
         let guidelines = guidelines.clone();
         let resize_base_signed = resize_base_signed.clone();
         let resize_start_anchor = resize_start_anchor.clone();
+        let edit_mode = edit_mode.clone();
 
         Callback::from(move |ids: Vec<u64>| {
-            // Update ref for keyboard handler
-            *selected_ids_ref.borrow_mut() = ids.clone();
-
-            if ids.is_empty() {
-                selected_ids.set(Vec::new());
+            edit_mode.set(EditMode::Transform);
+            let mut next_scene = (*scene).clone();
+            next_scene.set_selection(&ids);
+            let bbox = calculate_selection_bounding_box(&next_scene);
+            let is_empty = next_scene.selection().is_empty();
+            commit_scene(&scene, &scene_ref, next_scene);
+
+            if is_empty {
                 return;
             }
 
-            let selected_shapes: Vec<Shape> = shapes
-                .iter()
-                .filter(|s| ids.contains(&s.id))
-                .cloned()
-                .collect();
-
-            let bbox = calculate_shapes_bounding_box(&selected_shapes);
-            selected_ids.set(ids);
             fixed_anchor.set(Point::new(bbox.x, bbox.y));
             dimensions.set(Dimensions::new(bbox.width, bbox.height));
             base_dimensions.set(Dimensions::new(bbox.width, bbox.height));
@@ -523,8 +1154,8 @@ pub fn resizable_canvas() -> Html {
 
     // Commit transform - permanently applies translation/scale to selected shapes
     let commit_selection_transform = {
-        let shapes = shapes.clone();
-        let selected_ids = selected_ids.clone();
+        let scene = scene.clone();
+        let scene_ref = scene_ref.clone();
         let fixed_anchor = fixed_anchor.clone();
         let dimensions = dimensions.clone();
         let base_dimensions = base_dimensions.clone();
@@ -538,7 +1169,7 @@ pub fn resizable_canvas() -> Html {
         let has_unsaved_changes = has_unsaved_changes.clone();
 
         Callback::from(move |_: ()| {
-            if selected_ids.is_empty() {
+            if scene.selection().is_empty() {
                 return;
             }
 
@@ -557,57 +1188,30 @@ pub fn resizable_canvas() -> Html {
                 .cloned()
                 .unwrap_or_else(|| Dimensions::new(dimensions.width, dimensions.height));
 
-            let (current_scale_x, current_scale_y) = if selected_ids.is_empty() {
-                (1.0, 1.0)
-            } else {
-                (
-                    current_dims.width / signed_base.width,
-                    current_dims.height / signed_base.height,
-                )
-            };
-
-            let origin = Vec2::new(fixed_anchor.x as f32, fixed_anchor.y as f32);
-
-            // Transform shapes by updating their transforms
-            let transformed_shapes: Vec<Shape> = shapes
-                .iter()
-                .map(|shape| {
-                    if !selected_ids.contains(&shape.id) {
-                        return shape.clone();
-                    }
-
-                    let mut new_shape = shape.clone();
-                    let current_pos = shape.transform.position;
-
-                    // Calculate new position relative to anchor
-                    let local_x = current_pos.x - origin.x;
-                    let local_y = current_pos.y - origin.y;
-                    let new_x = origin.x + trans.x as f32 + local_x * current_scale_x as f32;
-                    let new_y = origin.y + trans.y as f32 + local_y * current_scale_y as f32;
-
-                    // Update transform with new position and scaled dimensions
-                    let current_scale = shape.transform.scale;
-                    new_shape.transform = Transform2D::identity()
-                        .with_position(Vec2::new(new_x, new_y))
-                        .with_scale(Vec2::new(
-                            current_scale.x * current_scale_x as f32,
-                            current_scale.y * current_scale_y as f32,
-                        ));
-
-                    new_shape
-                })
-                .collect();
+            let scale = Vec2::new(
+                axis_scale(current_dims.width, signed_base.width),
+                axis_scale(current_dims.height, signed_base.height),
+            );
+            let translation_delta = Vec2::new(trans.x as f32, trans.y as f32);
+            // Nothing moved or resized (e.g. a click, or select-all): leave the scene and history alone
+            let changed = translation_delta != Vec2::ZERO || scale != Vec2::ONE;
+
+            // Scale selected shapes about the anchor, then apply the translation
+            let mut next_scene = (*scene).clone();
+            if changed {
+                next_scene.scale_selection_about(
+                    Vec2::new(fixed_anchor.x as f32, fixed_anchor.y as f32),
+                    translation_delta,
+                    scale,
+                );
+            }
 
             // Calculate new bounding box for selected shapes
-            let selected_shapes: Vec<Shape> = transformed_shapes
-                .iter()
-                .filter(|s| selected_ids.contains(&s.id))
-                .cloned()
-                .collect();
+            let bbox = calculate_selection_bounding_box(&next_scene);
 
-            let bbox = calculate_shapes_bounding_box(&selected_shapes);
-
-            shapes.set(transformed_shapes);
+            if changed {
+                commit_scene(&scene, &scene_ref, next_scene);
+            }
             let next_anchor = Point::new(bbox.x, bbox.y);
             fixed_anchor.set(next_anchor);
             dimensions.set(Dimensions::new(bbox.width, bbox.height));
@@ -621,14 +1225,144 @@ pub fn resizable_canvas() -> Html {
             resize_current_dims.replace(None);
 
             // Mark as having unsaved changes
-            has_unsaved_changes.set(true);
+            if changed {
+                has_unsaved_changes.set(true);
+            }
         })
     };
 
     // Layer panel selection handler - accepts list of shape IDs
+    // Delete / Backspace removes the selected shapes, or in vertex editing the selected vertex
+    let remove_selected_vertex_ref = use_mut_ref(|| None::<Callback<(), bool>>);
+    {
+        let remove_selected_vertex_ref = remove_selected_vertex_ref.clone();
+        let scene = scene.clone();
+        let scene_ref = scene_ref.clone();
+        let layer_tree = layer_tree.clone();
+        let layer_tree_ref = layer_tree_ref.clone();
+        let hovered_id = hovered_id.clone();
+        let toast_message = toast_message.clone();
+        let has_unsaved_changes = has_unsaved_changes.clone();
+        use_effect_with((), move |_| {
+            let document = web_sys::window().expect("no window").document().expect("no document");
+
+            let options = gloo::events::EventListenerOptions::enable_prevent_default();
+            let listener = EventListener::new_with_options(&document, "keydown", options, move |event| {
+                let Some(keyboard_event) = event.dyn_ref::<web_sys::KeyboardEvent>() else {
+                    return;
+                };
+                if !matches!(keyboard_event.key().as_str(), "Delete" | "Backspace") {
+                    return;
+                }
+
+                // Backspace inside a text field edits the text
+                if event.target().and_then(|t| t.dyn_into::<HtmlInputElement>().ok()).is_some() {
+                    return;
+                }
+
+                let remove_vertex = remove_selected_vertex_ref.borrow().clone();
+                if remove_vertex.is_some_and(|remove| remove.emit(())) {
+                    keyboard_event.prevent_default();
+                    return;
+                }
+
+                let ids = scene_ref.borrow().selection().to_vec();
+                if ids.is_empty() {
+                    return;
+                }
+
+                // Keep Backspace from navigating back
+                keyboard_event.prevent_default();
+
+                let mut next_scene = scene_ref.borrow().clone();
+                next_scene.delete_selection();
+                commit_scene(&scene, &scene_ref, next_scene);
+
+                let mut updated_tree = layer_tree_ref.borrow().clone();
+                updated_tree.remove_shapes(&ids);
+                *layer_tree_ref.borrow_mut() = updated_tree.clone();
+                layer_tree.set(updated_tree);
+
+                hovered_id.set(None);
+                has_unsaved_changes.set(true);
+                toast_message.set(Some(format!(
+                    "Deleted {} shape{}",
+                    ids.len(),
+                    if ids.len() == 1 { "" } else { "s" }
+                )));
+            });
+
+            move || drop(listener)
+        });
+    }
+
+    // Auto-clear the toast after 2 seconds
+    {
+        let toast_message = toast_message.clone();
+        use_effect_with((*toast_message).clone(), move |message| -> Box<dyn FnOnce()> {
+            if message.is_none() {
+                return Box::new(|| ());
+            }
+            let timeout = gloo::timers::callback::Timeout::new(2_000, move || toast_message.set(None));
+            Box::new(move || drop(timeout))
+        });
+    }
+
+    // Keyboard nudge: arrow keys move the selection by 1px (10px with Shift)
+    // The listener is attached once, so it reads the latest commit callback through a ref
+    let commit_transform_ref = use_mut_ref(|| None::<Callback<()>>);
+    *commit_transform_ref.borrow_mut() = Some(commit_selection_transform.clone());
+    {
+        let scene_ref = scene_ref.clone();
+        let translation = translation.clone();
+        let commit_transform_ref = commit_transform_ref.clone();
+        use_effect_with((), move |_| {
+            let document = web_sys::window().expect("no window").document().expect("no document");
+
+            let options = gloo::events::EventListenerOptions::enable_prevent_default();
+            let listener = EventListener::new_with_options(&document, "keydown", options, move |event| {
+                let Some(keyboard_event) = event.dyn_ref::<web_sys::KeyboardEvent>() else {
+                    return;
+                };
+
+                // Leave arrow keys alone while editing text (e.g. renaming a layer)
+                if event.target().and_then(|t| t.dyn_into::<HtmlInputElement>().ok()).is_some() {
+                    return;
+                }
+
+                let step = if keyboard_event.shift_key() { 10.0 } else { 1.0 };
+                let (dx, dy) = match keyboard_event.key().as_str() {
+                    "ArrowLeft" => (-step, 0.0),
+                    "ArrowRight" => (step, 0.0),
+                    "ArrowUp" => (0.0, -step),
+                    "ArrowDown" => (0.0, step),
+                    _ => return,
+                };
+
+                if scene_ref.borrow().selection().is_empty() {
+                    return;
+                }
+
+                // Keep the page from scrolling
+                keyboard_event.prevent_default();
+
+                let current = *translation.borrow();
+                *translation.borrow_mut() = Point::new(current.x + dx, current.y + dy);
+                if let Some(commit) = commit_transform_ref.borrow().as_ref() {
+                    commit.emit(());
+                }
+            });
+
+            move || drop(listener)
+        });
+    }
+
     let on_layer_select = {
         let set_selection = set_selection_from_ids.clone();
+        let scene = scene.clone();
+        let locked_notice = locked_notice.clone();
         Callback::from(move |shape_ids: Vec<u64>| {
+            locked_notice.set(shape_ids.iter().any(|id| scene.get_shape(*id).is_some_and(|s| s.locked)));
             set_selection.emit(shape_ids);
         })
     };
@@ -645,35 +1379,796 @@ pub fn resizable_canvas() -> Html {
         })
     };
 
-    // Property update handlers (stubbed for now - would need to update selected polygon)
-    let on_update_fill = Callback::from(|_fill: String| {});
-    let on_update_stroke = Callback::from(|_stroke: String| {});
-    let on_update_position = Callback::from(|_pos: (f64, f64)| {});
-    let on_update_dimensions = Callback::from(|_dims: (f64, f64)| {});
-
-    // Version history handlers
-    let on_save_version = {
-        let shapes = shapes.clone();
-        let layer_tree = layer_tree.clone();
-        let version_history = version_history.clone();
+    // Position fields move the whole selection so its bounding box starts at the new corner
+    let on_update_position = {
+        let scene = scene.clone();
+        let scene_ref = scene_ref.clone();
+        let fixed_anchor = fixed_anchor.clone();
+        let selection_origin = selection_origin.clone();
+        let render_version = render_version.clone();
         let has_unsaved_changes = has_unsaved_changes.clone();
+        let transition_version = transition_version.clone();
+
+        Callback::from(move |(x, y): (f64, f64)| {
+            if scene.selection().is_empty() || !x.is_finite() || !y.is_finite() {
+                return;
+            }
+            let current = calculate_selection_bounding_box(&scene);
+            let delta = Vec2::new((x - current.x) as f32, (y - current.y) as f32);
+            let mut next_scene = (*scene).clone();
+            next_scene.transform_selection(delta, Vec2::ONE);
+
+            let bbox = calculate_selection_bounding_box(&next_scene);
+
+            commit_scene(&scene, &scene_ref, next_scene);
+            let next_anchor = Point::new(bbox.x, bbox.y);
+            fixed_anchor.set(next_anchor);
+            selection_origin.set(Some(next_anchor));
+            render_version.set(*render_version + 1);
+            has_unsaved_changes.set(true);
+            transition_version.set(*transition_version + 1);
+        })
+    };
+
+    // Fill color gives every selected shape a solid fill; the picker emits on every drag step
+    let on_update_fill = {
+        let scene = scene.clone();
+        let scene_ref = scene_ref.clone();
+        let render_version = render_version.clone();
+        let has_unsaved_changes = has_unsaved_changes.clone();
+
+        Callback::from(move |fill: String| {
+            let selected_ids = scene.selection();
+            let Some(color) = Color::parse(&fill).filter(|_| !selected_ids.is_empty()) else {
+                return;
+            };
+
+            let mut next_scene = (*scene).clone();
+            for id in selected_ids.iter() {
+                if let Some(mut style) = next_scene.get_shape(*id).map(|s| s.style.clone()) {
+                    style.fill = Some(Fill::Solid(color));
+                    next_scene.set_style(*id, style);
+                }
+            }
+
+            commit_scene(&scene, &scene_ref, next_scene);
+            render_version.set(*render_version + 1);
+            has_unsaved_changes.set(true);
+        })
+    };
+
+    // Stroke color; an unstroked shape gets a 1px stroke, which grows its bounds
+    let on_update_stroke = {
+        let scene = scene.clone();
+        let scene_ref = scene_ref.clone();
+        let fixed_anchor = fixed_anchor.clone();
+        let dimensions = dimensions.clone();
+        let base_dimensions = base_dimensions.clone();
+        let selection_origin = selection_origin.clone();
+        let render_version = render_version.clone();
+        let has_unsaved_changes = has_unsaved_changes.clone();
+
+        Callback::from(move |stroke: String| {
+            let selected_ids = scene.selection();
+            let Some(color) = Color::parse(&stroke).filter(|_| !selected_ids.is_empty()) else {
+                return;
+            };
+
+            let mut next_scene = (*scene).clone();
+            for id in selected_ids.iter() {
+                let Some(mut style) = next_scene.get_shape(*id).map(|s| s.style.clone()) else {
+                    continue;
+                };
+                style.stroke = Some(match style.stroke.take() {
+                    Some(stroke) => StrokeStyle { color, ..stroke },
+                    None => StrokeStyle::new(color, 1.0),
+                });
+                next_scene.set_style(*id, style);
+            }
+
+            let bbox = calculate_selection_bounding_box(&next_scene);
+
+            commit_scene(&scene, &scene_ref, next_scene);
+            let next_anchor = Point::new(bbox.x, bbox.y);
+            fixed_anchor.set(next_anchor);
+            dimensions.set(Dimensions::new(bbox.width, bbox.height));
+            base_dimensions.set(Dimensions::new(bbox.width, bbox.height));
+            selection_origin.set(Some(next_anchor));
+            render_version.set(*render_version + 1);
+            has_unsaved_changes.set(true);
+        })
+    };
+
+    // Dimension fields scale the selection about its top-left corner
+    let on_update_dimensions = {
+        let scene = scene.clone();
+        let scene_ref = scene_ref.clone();
+        let fixed_anchor = fixed_anchor.clone();
+        let dimensions = dimensions.clone();
+        let base_dimensions = base_dimensions.clone();
+        let selection_origin = selection_origin.clone();
+        let render_version = render_version.clone();
+        let has_unsaved_changes = has_unsaved_changes.clone();
+        let transition_version = transition_version.clone();
+
+        Callback::from(move |(width, height): (f64, f64)| {
+            if scene.selection().is_empty() || !width.is_finite() || !height.is_finite() {
+                return;
+            }
+            let current = calculate_selection_bounding_box(&scene);
+            if current.width == 0.0 || current.height == 0.0 {
+                return;
+            }
+
+            let scale = Vec2::new(
+                (width.max(MIN_SIZE) / current.width) as f32,
+                (height.max(MIN_SIZE) / current.height) as f32,
+            );
+            let mut next_scene = (*scene).clone();
+            next_scene.scale_selection_about(Vec2::new(current.x as f32, current.y as f32), Vec2::ZERO, scale);
+
+            let bbox = calculate_selection_bounding_box(&next_scene);
+
+            commit_scene(&scene, &scene_ref, next_scene);
+            let next_anchor = Point::new(bbox.x, bbox.y);
+            fixed_anchor.set(next_anchor);
+            dimensions.set(Dimensions::new(bbox.width, bbox.height));
+            base_dimensions.set(Dimensions::new(bbox.width, bbox.height));
+            selection_origin.set(Some(next_anchor));
+            render_version.set(*render_version + 1);
+            has_unsaved_changes.set(true);
+            transition_version.set(*transition_version + 1);
+        })
+    };
+
+    // Opacity applies to every selected shape
+    let on_update_opacity = {
+        let scene = scene.clone();
+        let scene_ref = scene_ref.clone();
+        let render_version = render_version.clone();
+        let has_unsaved_changes = has_unsaved_changes.clone();
+
+        Callback::from(move |opacity: f32| {
+            let selected_ids = scene.selection();
+            if selected_ids.is_empty() {
+                return;
+            }
+
+            let mut next_scene = (*scene).clone();
+            for id in selected_ids.iter() {
+                if let Some(style) = next_scene.get_shape(*id).map(|s| s.style.clone()) {
+                    next_scene.set_style(*id, style.with_opacity(opacity));
+                }
+            }
+
+            commit_scene(&scene, &scene_ref, next_scene);
+            render_version.set(*render_version + 1);
+            has_unsaved_changes.set(true);
+        })
+    };
+
+    // Drop shadows don't change a shape's bounds, so the selection box stays put
+    let on_update_drop_shadow = {
+        let scene = scene.clone();
+        let scene_ref = scene_ref.clone();
+        let render_version = render_version.clone();
+        let has_unsaved_changes = has_unsaved_changes.clone();
+
+        Callback::from(move |drop_shadow: Option<DropShadow>| {
+            let selected_ids = scene.selection();
+            if selected_ids.is_empty() {
+                return;
+            }
+
+            let mut next_scene = (*scene).clone();
+            for id in selected_ids.iter() {
+                if let Some(style) = next_scene.get_shape(*id).map(|s| s.style.clone()) {
+                    next_scene.set_style(*id, style.with_drop_shadow(drop_shadow));
+                }
+            }
+
+            commit_scene(&scene, &scene_ref, next_scene);
+            render_version.set(*render_version + 1);
+            has_unsaved_changes.set(true);
+        })
+    };
+
+    // Group opacity applies when the selection is exactly one group
+    let selected_group = layer_tree.group_for_selection(&selected_ids);
+    let on_update_group_opacity = {
+        let layer_tree = layer_tree.clone();
+        let layer_tree_ref = layer_tree_ref.clone();
+        let render_version = render_version.clone();
+        let has_unsaved_changes = has_unsaved_changes.clone();
+
+        Callback::from(move |opacity: f32| {
+            let Some(group_id) = selected_group else {
+                return;
+            };
+            let mut updated_tree = (*layer_tree).clone();
+            if !updated_tree.set_group_opacity(group_id, opacity) {
+                return;
+            }
+
+            *layer_tree_ref.borrow_mut() = updated_tree.clone();
+            layer_tree.set(updated_tree);
+            render_version.set(*render_version + 1);
+            has_unsaved_changes.set(true);
+        })
+    };
+
+    // Stroke width applies to every selected shape; unstroked shapes get a black stroke.
+    // Bounds include the stroke, so the selection box is refit
+    let on_update_stroke_width = {
+        let scene = scene.clone();
+        let scene_ref = scene_ref.clone();
+        let fixed_anchor = fixed_anchor.clone();
+        let dimensions = dimensions.clone();
+        let base_dimensions = base_dimensions.clone();
+        let selection_origin = selection_origin.clone();
+        let render_version = render_version.clone();
+        let has_unsaved_changes = has_unsaved_changes.clone();
+
+        Callback::from(move |width: f64| {
+            let selected_ids = scene.selection();
+            if selected_ids.is_empty() || !width.is_finite() {
+                return;
+            }
+            let width = width.max(0.0) as f32;
+
+            let mut next_scene = (*scene).clone();
+            for id in selected_ids.iter() {
+                let Some(mut style) = next_scene.get_shape(*id).map(|s| s.style.clone()) else {
+                    continue;
+                };
+                style.stroke = match style.stroke.take() {
+                    Some(mut stroke) => {
+                        stroke.width = width;
+                        Some(stroke)
+                    }
+                    None if width > 0.0 => Some(StrokeStyle::new(Color::black(), width)),
+                    None => None,
+                };
+                next_scene.set_style(*id, style);
+            }
+
+            let bbox = calculate_selection_bounding_box(&next_scene);
+
+            commit_scene(&scene, &scene_ref, next_scene);
+            let next_anchor = Point::new(bbox.x, bbox.y);
+            fixed_anchor.set(next_anchor);
+            dimensions.set(Dimensions::new(bbox.width, bbox.height));
+            base_dimensions.set(Dimensions::new(bbox.width, bbox.height));
+            selection_origin.set(Some(next_anchor));
+            render_version.set(*render_version + 1);
+            has_unsaved_changes.set(true);
+        })
+    };
+
+    // Dash pattern applies to every selected shape that has a stroke
+    let on_update_dash = {
+        let scene = scene.clone();
+        let scene_ref = scene_ref.clone();
+        let render_version = render_version.clone();
+        let has_unsaved_changes = has_unsaved_changes.clone();
+
+        Callback::from(move |dash_pattern: Option<Vec<f32>>| {
+            let selected_ids = scene.selection();
+            if selected_ids.is_empty() {
+                return;
+            }
+
+            let mut next_scene = (*scene).clone();
+            for id in selected_ids.iter() {
+                let Some(mut style) = next_scene.get_shape(*id).map(|s| s.style.clone()) else {
+                    continue;
+                };
+                if let Some(stroke) = style.stroke.take() {
+                    style.stroke = Some(stroke.with_dash_pattern(dash_pattern.clone()));
+                    next_scene.set_style(*id, style);
+                }
+            }
+
+            commit_scene(&scene, &scene_ref, next_scene);
+            render_version.set(*render_version + 1);
+            has_unsaved_changes.set(true);
+        })
+    };
+
+    // Caps and joins apply to every selected shape that has a stroke
+    let on_update_line_style = {
+        let scene = scene.clone();
+        let scene_ref = scene_ref.clone();
+        let render_version = render_version.clone();
+        let has_unsaved_changes = has_unsaved_changes.clone();
+
+        Callback::from(move |(line_cap, line_join): (Option<LineCap>, Option<LineJoin>)| {
+            let selected_ids = scene.selection();
+            if selected_ids.is_empty() {
+                return;
+            }
+
+            let mut next_scene = (*scene).clone();
+            for id in selected_ids.iter() {
+                let Some(mut style) = next_scene.get_shape(*id).map(|s| s.style.clone()) else {
+                    continue;
+                };
+                if let Some(mut stroke) = style.stroke.take() {
+                    // Only the edited half changes, so a mixed selection keeps its other value
+                    if let Some(line_cap) = line_cap {
+                        stroke = stroke.with_line_cap(line_cap);
+                    }
+                    if let Some(line_join) = line_join {
+                        stroke = stroke.with_line_join(line_join);
+                    }
+                    style.stroke = Some(stroke);
+                    next_scene.set_style(*id, style);
+                }
+            }
+
+            commit_scene(&scene, &scene_ref, next_scene);
+            render_version.set(*render_version + 1);
+            has_unsaved_changes.set(true);
+        })
+    };
+
+    // Arc fields rebuild the pie slice path of every selected arc and refit the selection
+    let on_update_pie_slice = {
+        let scene = scene.clone();
+        let scene_ref = scene_ref.clone();
+        let fixed_anchor = fixed_anchor.clone();
+        let dimensions = dimensions.clone();
+        let base_dimensions = base_dimensions.clone();
+        let selection_origin = selection_origin.clone();
+        let render_version = render_version.clone();
+        let has_unsaved_changes = has_unsaved_changes.clone();
+
+        Callback::from(move |slice: PieSlice| {
+            let arc_ids: Vec<u64> = scene
+                .selection()
+                .iter()
+                .copied()
+                .filter(|id| scene.get_shape(*id).is_some_and(|s| s.geometry.as_pie_slice().is_some()))
+                .collect();
+            if arc_ids.is_empty() {
+                return;
+            }
+
+            let mut next_scene = (*scene).clone();
+            for id in arc_ids {
+                next_scene.set_geometry(id, ShapeGeometry::pie_slice(slice));
+            }
+
+            let bbox = calculate_selection_bounding_box(&next_scene);
+
+            commit_scene(&scene, &scene_ref, next_scene);
+            let next_anchor = Point::new(bbox.x, bbox.y);
+            fixed_anchor.set(next_anchor);
+            dimensions.set(Dimensions::new(bbox.width, bbox.height));
+            base_dimensions.set(Dimensions::new(bbox.width, bbox.height));
+            selection_origin.set(Some(next_anchor));
+            render_version.set(*render_version + 1);
+            has_unsaved_changes.set(true);
+        })
+    };
+
+    // Star and regular polygon parameters replace the geometry of every selected shape of
+    // the same kind; the radius can change, so the selection box is refit
+    let on_update_star_polygon = {
+        let scene = scene.clone();
+        let scene_ref = scene_ref.clone();
+        let fixed_anchor = fixed_anchor.clone();
+        let dimensions = dimensions.clone();
+        let base_dimensions = base_dimensions.clone();
+        let selection_origin = selection_origin.clone();
+        let render_version = render_version.clone();
+        let has_unsaved_changes = has_unsaved_changes.clone();
+
+        Callback::from(move |geometry: ShapeGeometry| {
+            let ids: Vec<u64> = scene
+                .selection()
+                .iter()
+                .copied()
+                .filter(|id| {
+                    scene
+                        .get_shape(*id)
+                        .is_some_and(|s| std::mem::discriminant(&s.geometry) == std::mem::discriminant(&geometry))
+                })
+                .collect();
+            if ids.is_empty() {
+                return;
+            }
+
+            let mut next_scene = (*scene).clone();
+            for id in ids {
+                next_scene.set_geometry(id, geometry.clone());
+            }
+
+            let bbox = calculate_selection_bounding_box(&next_scene);
+
+            commit_scene(&scene, &scene_ref, next_scene);
+            let next_anchor = Point::new(bbox.x, bbox.y);
+            fixed_anchor.set(next_anchor);
+            dimensions.set(Dimensions::new(bbox.width, bbox.height));
+            base_dimensions.set(Dimensions::new(bbox.width, bbox.height));
+            selection_origin.set(Some(next_anchor));
+            render_version.set(*render_version + 1);
+            has_unsaved_changes.set(true);
+        })
+    };
+
+    // Corner radii stay inside a rectangle's bounds, so the selection box stays put
+    let on_update_corner_radii = {
+        let scene = scene.clone();
+        let scene_ref = scene_ref.clone();
+        let render_version = render_version.clone();
+        let has_unsaved_changes = has_unsaved_changes.clone();
+
+        Callback::from(move |radii: CornerRadii| {
+            let rects: Vec<(u64, f32, f32)> = scene
+                .selection()
+                .iter()
+                .filter_map(|id| match scene.get_shape(*id)?.geometry {
+                    ShapeGeometry::Rectangle { width, height, .. } => Some((*id, width, height)),
+                    _ => None,
+                })
+                .collect();
+            if rects.is_empty() {
+                return;
+            }
+
+            let mut next_scene = (*scene).clone();
+            for (id, width, height) in rects {
+                next_scene.set_geometry(id, ShapeGeometry::rectangle_with_radii(width, height, radii));
+            }
+
+            commit_scene(&scene, &scene_ref, next_scene);
+            render_version.set(*render_version + 1);
+            has_unsaved_changes.set(true);
+        })
+    };
+
+    // Smoothing turns selected polygons into curved paths through their vertices;
+    // already smoothed ones are rebuilt from their original vertices with the new tension
+    let on_smooth_polygon = {
+        let scene = scene.clone();
+        let scene_ref = scene_ref.clone();
+        let fixed_anchor = fixed_anchor.clone();
+        let dimensions = dimensions.clone();
+        let base_dimensions = base_dimensions.clone();
+        let selection_origin = selection_origin.clone();
+        let render_version = render_version.clone();
+        let has_unsaved_changes = has_unsaved_changes.clone();
+
+        Callback::from(move |tension: f32| {
+            let sources: Vec<(u64, Vec<Vec2>)> = scene
+                .selection()
+                .iter()
+                .filter_map(|id| {
+                    let geometry = &scene.get_shape(*id)?.geometry;
+                    let points = match geometry.polygon_points() {
+                        Some(points) => points.to_vec(),
+                        None => geometry.as_smooth_polygon()?.0,
+                    };
+                    Some((*id, points))
+                })
+                .collect();
+            if sources.is_empty() {
+                return;
+            }
+
+            let mut next_scene = (*scene).clone();
+            for (id, points) in sources {
+                next_scene.set_geometry(id, ShapeGeometry::smooth_polygon(&points, tension));
+            }
+
+            let bbox = calculate_selection_bounding_box(&next_scene);
+
+            commit_scene(&scene, &scene_ref, next_scene);
+            let next_anchor = Point::new(bbox.x, bbox.y);
+            fixed_anchor.set(next_anchor);
+            dimensions.set(Dimensions::new(bbox.width, bbox.height));
+            base_dimensions.set(Dimensions::new(bbox.width, bbox.height));
+            selection_origin.set(Some(next_anchor));
+            render_version.set(*render_version + 1);
+            has_unsaved_changes.set(true);
+        })
+    };
+
+    // Convert to path replaces each selected shape's geometry with an equivalent path;
+    // flattening also bakes its transform into the path's points
+    let on_convert_to_path = {
+        let scene = scene.clone();
+        let scene_ref = scene_ref.clone();
+        let fixed_anchor = fixed_anchor.clone();
+        let dimensions = dimensions.clone();
+        let base_dimensions = base_dimensions.clone();
+        let selection_origin = selection_origin.clone();
+        let render_version = render_version.clone();
+        let has_unsaved_changes = has_unsaved_changes.clone();
+
+        Callback::from(move |flatten: bool| {
+            let converted: Vec<Shape> = scene
+                .selection()
+                .iter()
+                .filter_map(|id| {
+                    let shape = scene.get_shape(*id)?;
+                    if flatten {
+                        shape.flattened()
+                    } else {
+                        shape.to_path()
+                    }
+                })
+                .collect();
+            if converted.is_empty() {
+                return;
+            }
+
+            let mut next_scene = (*scene).clone();
+            for shape in converted {
+                if let Some(existing) = next_scene.get_shape_mut(shape.id) {
+                    *existing = shape;
+                }
+            }
+
+            let bbox = calculate_selection_bounding_box(&next_scene);
+
+            commit_scene(&scene, &scene_ref, next_scene);
+            let next_anchor = Point::new(bbox.x, bbox.y);
+            fixed_anchor.set(next_anchor);
+            dimensions.set(Dimensions::new(bbox.width, bbox.height));
+            base_dimensions.set(Dimensions::new(bbox.width, bbox.height));
+            selection_origin.set(Some(next_anchor));
+            render_version.set(*render_version + 1);
+            has_unsaved_changes.set(true);
+        })
+    };
+
+    // Boolean ops replace the selected shapes with one path in a single commit; the result
+    // keeps the bottom shape's ID, so it stays in that shape's place in the layer tree
+    let on_boolean_op = {
+        let scene = scene.clone();
+        let scene_ref = scene_ref.clone();
+        let layer_tree = layer_tree.clone();
+        let layer_tree_ref = layer_tree_ref.clone();
+        let fixed_anchor = fixed_anchor.clone();
+        let dimensions = dimensions.clone();
+        let base_dimensions = base_dimensions.clone();
+        let selection_origin = selection_origin.clone();
+        let render_version = render_version.clone();
+        let has_unsaved_changes = has_unsaved_changes.clone();
+        let toast_message = toast_message.clone();
+
+        Callback::from(move |op: BooleanOp| {
+            // Paint order, bottom first
+            let inputs: Vec<&Shape> = scene
+                .shapes()
+                .iter()
+                .filter(|shape| scene.selection().contains(&shape.id))
+                .collect();
+            if inputs.len() < 2 {
+                return;
+            }
+            let Some(result) = combine_shapes(&inputs, op) else {
+                toast_message.set(Some(format!("{} left nothing", op.label())));
+                return;
+            };
+            let removed: Vec<u64> = inputs[1..].iter().map(|shape| shape.id).collect();
+
+            let mut next_scene = (*scene).clone();
+            for id in &removed {
+                next_scene.remove_shape(*id);
+            }
+            let result_id = result.id;
+            if let Some(shape) = next_scene.get_shape_mut(result_id) {
+                *shape = result;
+            }
+            next_scene.set_selection(&[result_id]);
+
+            let bbox = calculate_selection_bounding_box(&next_scene);
+
+            commit_scene(&scene, &scene_ref, next_scene);
+
+            let mut updated_tree = layer_tree_ref.borrow().clone();
+            updated_tree.remove_shapes(&removed);
+            *layer_tree_ref.borrow_mut() = updated_tree.clone();
+            layer_tree.set(updated_tree);
+
+            let next_anchor = Point::new(bbox.x, bbox.y);
+            fixed_anchor.set(next_anchor);
+            dimensions.set(Dimensions::new(bbox.width, bbox.height));
+            base_dimensions.set(Dimensions::new(bbox.width, bbox.height));
+            selection_origin.set(Some(next_anchor));
+            render_version.set(*render_version + 1);
+            has_unsaved_changes.set(true);
+        })
+    };
+
+    // Text fields replace the content and font size of every selected text and refit the selection
+    let on_update_text = {
+        let scene = scene.clone();
+        let scene_ref = scene_ref.clone();
+        let fixed_anchor = fixed_anchor.clone();
+        let dimensions = dimensions.clone();
+        let base_dimensions = base_dimensions.clone();
+        let selection_origin = selection_origin.clone();
+        let render_version = render_version.clone();
+        let has_unsaved_changes = has_unsaved_changes.clone();
+
+        Callback::from(move |(new_content, new_size): (String, f32)| {
+            let edits: Vec<(u64, ShapeGeometry)> = scene
+                .selection()
+                .iter()
+                .filter_map(|id| match &scene.get_shape(*id)?.geometry {
+                    ShapeGeometry::Text { font_family, align, .. } => Some((*id, ShapeGeometry::Text {
+                        content: new_content.clone(),
+                        font_size: new_size,
+                        font_family: font_family.clone(),
+                        align: *align,
+                    })),
+                    _ => None,
+                })
+                .collect();
+            if edits.is_empty() {
+                return;
+            }
+
+            let mut next_scene = (*scene).clone();
+            for (id, geometry) in edits {
+                next_scene.set_geometry(id, geometry);
+            }
+
+            let bbox = calculate_selection_bounding_box(&next_scene);
+
+            commit_scene(&scene, &scene_ref, next_scene);
+            let next_anchor = Point::new(bbox.x, bbox.y);
+            fixed_anchor.set(next_anchor);
+            dimensions.set(Dimensions::new(bbox.width, bbox.height));
+            base_dimensions.set(Dimensions::new(bbox.width, bbox.height));
+            selection_origin.set(Some(next_anchor));
+            render_version.set(*render_version + 1);
+            has_unsaved_changes.set(true);
+        })
+    };
+
+    // Fill paint (solid or gradient) from the properties panel, applied to the selection
+    let on_update_fill_paint = {
+        let scene = scene.clone();
+        let scene_ref = scene_ref.clone();
+        let render_version = render_version.clone();
+        let has_unsaved_changes = has_unsaved_changes.clone();
+
+        Callback::from(move |fills: Vec<(u64, Fill)>| {
+            let selected_ids = scene.selection();
+            if selected_ids.is_empty() {
+                return;
+            }
+
+            let mut next_scene = (*scene).clone();
+            for (id, fill) in fills.into_iter().filter(|(id, _)| selected_ids.contains(id)) {
+                if let Some(style) = next_scene.get_shape(id).map(|s| s.style.clone()) {
+                    next_scene.set_style(id, style.with_fill(Some(fill)));
+                }
+            }
+
+            commit_scene(&scene, &scene_ref, next_scene);
+            render_version.set(*render_version + 1);
+            has_unsaved_changes.set(true);
+        })
+    };
+
+    // Alignment handler - moves each selected unit (a lone shape or a whole group)
+    // so its edge or center lines up with the collective selection bounds
+    let on_align = {
+        let scene = scene.clone();
+        let scene_ref = scene_ref.clone();
+        let layer_tree = layer_tree.clone();
+        let fixed_anchor = fixed_anchor.clone();
+        let dimensions = dimensions.clone();
+        let base_dimensions = base_dimensions.clone();
+        let selection_origin = selection_origin.clone();
+        let render_version = render_version.clone();
+        let has_unsaved_changes = has_unsaved_changes.clone();
+
+        Callback::from(move |mode: AlignMode| {
+            let selected_ids = scene.selection();
+            if selected_ids.len() < 2 {
+                return;
+            }
+
+            let (units, unit_bounds) = selection_units(&scene, &layer_tree);
+            let deltas = compute_alignment_deltas(&unit_bounds, mode);
+
+            let mut next_scene = (*scene).clone();
+            for (idx, delta) in deltas {
+                for id in &units[idx] {
+                    if let Some(shape) = next_scene.get_shape_mut(*id) {
+                        shape.transform.position += delta;
+                    }
+                }
+            }
+
+            let bbox = calculate_selection_bounding_box(&next_scene);
+
+            commit_scene(&scene, &scene_ref, next_scene);
+            let next_anchor = Point::new(bbox.x, bbox.y);
+            fixed_anchor.set(next_anchor);
+            dimensions.set(Dimensions::new(bbox.width, bbox.height));
+            base_dimensions.set(Dimensions::new(bbox.width, bbox.height));
+            selection_origin.set(Some(next_anchor));
+            render_version.set(*render_version + 1);
+            has_unsaved_changes.set(true);
+        })
+    };
+
+    // Distribution handler - spaces selected units evenly between the outermost ones
+    let on_distribute = {
+        let scene = scene.clone();
+        let scene_ref = scene_ref.clone();
+        let layer_tree = layer_tree.clone();
+        let fixed_anchor = fixed_anchor.clone();
+        let dimensions = dimensions.clone();
+        let base_dimensions = base_dimensions.clone();
+        let selection_origin = selection_origin.clone();
+        let render_version = render_version.clone();
+        let has_unsaved_changes = has_unsaved_changes.clone();
+
+        Callback::from(move |mode: DistributeMode| {
+            let selected_ids = scene.selection();
+            if selected_ids.len() < 3 {
+                return;
+            }
+
+            let (units, unit_bounds) = selection_units(&scene, &layer_tree);
+            if units.len() < 3 {
+                return;
+            }
+            let deltas = compute_distribution_deltas(&unit_bounds, mode);
+
+            let mut next_scene = (*scene).clone();
+            for (idx, delta) in deltas {
+                for id in &units[idx] {
+                    if let Some(shape) = next_scene.get_shape_mut(*id) {
+                        shape.transform.position += delta;
+                    }
+                }
+            }
+
+            let bbox = calculate_selection_bounding_box(&next_scene);
+
+            commit_scene(&scene, &scene_ref, next_scene);
+            let next_anchor = Point::new(bbox.x, bbox.y);
+            fixed_anchor.set(next_anchor);
+            dimensions.set(Dimensions::new(bbox.width, bbox.height));
+            base_dimensions.set(Dimensions::new(bbox.width, bbox.height));
+            selection_origin.set(Some(next_anchor));
+            render_version.set(*render_version + 1);
+            has_unsaved_changes.set(true);
+        })
+    };
+
+    // Version history handlers
+    let on_save_version = {
+        let scene = scene.clone();
+        let layer_tree = layer_tree.clone();
+        let version_history = version_history.clone();
+        let has_unsaved_changes = has_unsaved_changes.clone();
+
+        Callback::from(move |_: ()| {
+            let mut history = (*version_history).clone();
+            let timestamp = js_sys::Date::now();
+            history.save_version(scene.shapes().to_vec(), (*layer_tree).clone(), None, timestamp);
+            version_history.set(history);
+            has_unsaved_changes.set(false);
+        })
+    };
 
-        Callback::from(move |_: ()| {
-            let mut history = (*version_history).clone();
-            let timestamp = js_sys::Date::now();
-            history.save_version((*shapes).clone(), (*layer_tree).clone(), None, timestamp);
-            version_history.set(history);
-            has_unsaved_changes.set(false);
-        })
-    };
-
     let on_restore_version = {
-        let shapes = shapes.clone();
+        let scene = scene.clone();
+        let scene_ref = scene_ref.clone();
         let layer_tree = layer_tree.clone();
         let layer_tree_ref = layer_tree_ref.clone();
-        let selected_ids_ref = selected_ids_ref.clone();
         let version_history = version_history.clone();
-        let selected_ids = selected_ids.clone();
         let has_unsaved_changes = has_unsaved_changes.clone();
         let fixed_anchor = fixed_anchor.clone();
         let dimensions = dimensions.clone();
@@ -686,12 +2181,9 @@ pub fn resizable_canvas() -> Html {
             let mut history = (*version_history).clone();
 
             if let Some(version) = history.get_version(version_idx) {
-                // Clone shapes and mark them all as dirty to force re-tessellation
-                let mut restored_shapes = version.shapes.clone();
-                for shape in &mut restored_shapes {
-                    shape.mark_dirty();
-                }
-                shapes.set(restored_shapes);
+                // Rebuild the scene (new scenes start with every shape dirty, forcing
+                // re-tessellation) - this also clears the selection
+                commit_scene(&scene, &scene_ref, SceneGraph::from_shapes(version.shapes.clone()));
 
                 // Restore layer tree (update both state and ref)
                 *layer_tree_ref.borrow_mut() = version.layer_tree.clone();
@@ -700,9 +2192,7 @@ pub fn resizable_canvas() -> Html {
                 history.set_current_version(version_idx);
                 version_history.set(history);
 
-                // Clear selection and reset UI state (update both state and ref)
-                *selected_ids_ref.borrow_mut() = Vec::new();
-                selected_ids.set(Vec::new());
+                // Reset selection UI state
                 fixed_anchor.set(Point::new(150.0, 150.0));
                 dimensions.set(Dimensions::new(100.0, 100.0));
                 base_dimensions.set(Dimensions::new(100.0, 100.0));
@@ -716,6 +2206,34 @@ pub fn resizable_canvas() -> Html {
         })
     };
 
+    // Cmd/Ctrl+S saves a version instead of the browser's "Save Page As"
+    // The listener is attached once, so it reads the latest save handler from a ref
+    let save_version_ref = use_mut_ref(|| None::<Callback<()>>);
+    *save_version_ref.borrow_mut() = Some(on_save_version.clone());
+    {
+        let save_version_ref = save_version_ref.clone();
+        use_effect_with((), move |_| {
+            let window = web_sys::window().expect("no window");
+            let document = window.document().expect("no document");
+
+            let options = gloo::events::EventListenerOptions::enable_prevent_default();
+            let listener = EventListener::new_with_options(&document, "keydown", options, move |event| {
+                if let Some(keyboard_event) = event.dyn_ref::<web_sys::KeyboardEvent>() {
+                    if (keyboard_event.meta_key() || keyboard_event.ctrl_key())
+                        && keyboard_event.key().eq_ignore_ascii_case("s")
+                    {
+                        keyboard_event.prevent_default();
+                        if let Some(save_version) = save_version_ref.borrow().as_ref() {
+                            save_version.emit(());
+                        }
+                    }
+                }
+            });
+
+            move || drop(listener)
+        });
+    }
+
     // Tab change handlers
     let on_tab_click = {
         let active_tab = active_tab.clone();
@@ -727,8 +2245,9 @@ pub fn resizable_canvas() -> Html {
     // Commit marquee selection when mouseup occurs
     let on_svg_mouseup = {
         let svg_ref = svg_ref.clone();
+        let view_ref = view_ref.clone();
         let selection_rect = selection_rect.clone();
-        let shapes = shapes.clone();
+        let scene = scene.clone();
         let set_selection = set_selection_from_ids.clone();
         let preview_bbox = preview_bbox.clone();
 
@@ -738,104 +2257,563 @@ pub fn resizable_canvas() -> Html {
             }
 
             if let Some(svg) = svg_ref.cast::<SvgsvgElement>() {
-                let end_point = client_to_svg_coords(&e, &svg);
+                let end_point = client_to_svg_coords(&e, &svg, &view_ref.borrow());
                 if let Some(current_rect) = selection_rect.as_ref() {
                     let rect = SelectionRect::new(current_rect.start, end_point);
                     let bbox = rect.to_bounding_box();
 
-                    // Find shapes that intersect with selection rectangle
-                    let mut selected: Vec<u64> = Vec::new();
-                    for shape in shapes.iter() {
-                        let shape_bounds = shape.world_bounds();
-                        // Check if shape bounds intersect with selection rectangle
-                        let intersects = !(shape_bounds.max.x < bbox.x as f32 ||
-                            shape_bounds.min.x > (bbox.x + bbox.width) as f32 ||
-                            shape_bounds.max.y < bbox.y as f32 ||
-                            shape_bounds.min.y > (bbox.y + bbox.height) as f32);
-                        if intersects {
-                            selected.push(shape.id);
+                    // Find shapes that intersect with (or, with Alt, lie inside) the selection rectangle
+                    let selected = find_shapes_in_rect(scene.shapes(), &bbox_to_scene_bbox(&bbox), e.alt_key());
+
+                    // An empty result clears the selection via the callback to update refs
+                    set_selection.emit(selected);
+                }
+            }
+            selection_rect.set(None);
+            preview_bbox.set(None);
+        })
+    };
+
+    // Touch: one finger is replayed as mouse events on the touched element, so taps, drags
+    // and resizes reach the same handlers, including the window-level drag listeners.
+    // Two fingers pinch-zoom and pan the view until every finger lifts.
+    // Yew's touchstart/touchmove listeners are passive, so the canvas container sets
+    // `touch-action: none` to keep the page from scrolling or zooming instead.
+    let pinch = use_mut_ref(|| None::<(Point, Point)>);
+
+    let on_svg_touchstart = {
+        let svg_ref = svg_ref.clone();
+        let pinch = pinch.clone();
+
+        Callback::from(move |e: TouchEvent| {
+            let touches = e.touches();
+            match touches.length() {
+                1 => {
+                    if let Some(touch) = touches.get(0).filter(is_canvas_touch) {
+                        dispatch_touch_as_mouse("mousedown", &touch);
+                    }
+                }
+                2 => {
+                    // The second finger turns a one-finger drag into a pinch; end the drag first
+                    if pinch.borrow().is_none() {
+                        if let Some(first) = touches.get(0).filter(is_canvas_touch) {
+                            dispatch_touch_as_mouse("mouseup", &first);
+                        }
+                    }
+                    *pinch.borrow_mut() = svg_ref.cast::<SvgsvgElement>().and_then(|svg| pinch_points(&touches, &svg));
+                }
+                _ => {}
+            }
+        })
+    };
+
+    let on_svg_touchmove = {
+        let svg_ref = svg_ref.clone();
+        let view_ref = view_ref.clone();
+        let wheel_zoom = wheel_zoom.clone();
+        let pan_offset = pan_offset.clone();
+        let pinch = pinch.clone();
+
+        Callback::from(move |e: TouchEvent| {
+            let touches = e.touches();
+            let last = *pinch.borrow();
+            match last {
+                Some(from) if touches.length() >= 2 => {
+                    let Some(to) = svg_ref.cast::<SvgsvgElement>().and_then(|svg| pinch_points(&touches, &svg)) else {
+                        return;
+                    };
+                    let next = view_ref.borrow().pinched(from, to);
+                    *pinch.borrow_mut() = Some(to);
+                    *view_ref.borrow_mut() = next;
+                    wheel_zoom.set(next.zoom);
+                    pan_offset.set(next.pan);
+                }
+                None if touches.length() == 1 => {
+                    if let Some(touch) = touches.get(0).filter(is_canvas_touch) {
+                        dispatch_touch_as_mouse("mousemove", &touch);
+                    }
+                }
+                _ => {}
+            }
+        })
+    };
+
+    // Also used for touchcancel
+    let on_svg_touchup = {
+        let pinch = pinch.clone();
+
+        Callback::from(move |e: TouchEvent| {
+            if pinch.borrow().is_some() {
+                e.prevent_default();
+                if e.touches().length() == 0 {
+                    *pinch.borrow_mut() = None;
+                }
+            } else if let Some(touch) = e.changed_touches().get(0).filter(is_canvas_touch) {
+                // Keep the browser from following the tap with emulated mouse events
+                e.prevent_default();
+                dispatch_touch_as_mouse("mouseup", &touch);
+            }
+        })
+    };
+
+    // GPU-specific mousemove handler with hit testing for hover
+    let on_gpu_mousemove = {
+        let svg_ref = svg_ref.clone();
+        let view_ref = view_ref.clone();
+        let selection_rect = selection_rect.clone();
+        let scene = scene.clone();
+        let preview_bbox = preview_bbox.clone();
+        let hovered_id = hovered_id.clone();
+        let tool_mode = tool_mode.clone();
+        let cursor_position = cursor_position.clone();
+
+        Callback::from(move |e: MouseEvent| {
+            // Coordinate readout tracks the cursor with every tool
+            if let Some(svg) = svg_ref.cast::<SvgsvgElement>() {
+                cursor_position.set(client_to_svg_coords(&e, &svg, &view_ref.borrow()));
+            }
+
+            if *tool_mode != ToolMode::Select {
+                return;
+            }
+
+            if let Some(svg) = svg_ref.cast::<SvgsvgElement>() {
+                let point = client_to_svg_coords(&e, &svg, &view_ref.borrow());
+
+                if let Some(current_rect) = selection_rect.as_ref() {
+                    // Marquee selection mode
+                    let updated_rect = SelectionRect::new(current_rect.start, point);
+                    selection_rect.set(Some(updated_rect));
+
+                    let bbox = SelectionRect::new(current_rect.start, point).to_bounding_box();
+                    let hits = find_shapes_in_rect(scene.shapes(), &bbox_to_scene_bbox(&bbox), e.alt_key());
+
+                    if !hits.is_empty() {
+                        let preview = calculate_shapes_bounding_box(hits.iter().filter_map(|id| scene.get_shape(*id)));
+                        preview_bbox.set(Some(preview));
+                    } else {
+                        preview_bbox.set(None);
+                    }
+                } else {
+                    // Not in marquee mode - do hit testing for hover
+                    // Don't show hover for individual shapes when a group is selected
+                    if scene.selection().is_empty() {
+                        let new_hovered = scene.hit_test(Vec2::new(point.x as f32, point.y as f32));
+                        if new_hovered != *hovered_id {
+                            hovered_id.set(new_hovered);
+                        }
+                    } else {
+                        // Clear hover when group is selected
+                        if hovered_id.is_some() {
+                            hovered_id.set(None);
+                        }
+                    }
+                }
+            }
+        })
+    };
+
+    // Add newly created shapes (drawn or imported), select them and return to the select tool
+    let add_shapes = {
+        let scene = scene.clone();
+        let scene_ref = scene_ref.clone();
+        let layer_tree = layer_tree.clone();
+        let layer_tree_ref = layer_tree_ref.clone();
+        let fixed_anchor = fixed_anchor.clone();
+        let dimensions = dimensions.clone();
+        let base_dimensions = base_dimensions.clone();
+        let translation = translation.clone();
+        let tool_mode = tool_mode.clone();
+        let has_unsaved_changes = has_unsaved_changes.clone();
+
+        Callback::from(move |shapes: Vec<Shape>| {
+            if shapes.is_empty() {
+                return;
+            }
+
+            let shape_ids: Vec<u64> = shapes.iter().map(|s| s.id).collect();
+            let mut next_scene = scene_ref.borrow().clone();
+            for shape in shapes {
+                next_scene.add_shape(shape);
+            }
+            next_scene.set_selection(&shape_ids);
+            let bbox = calculate_selection_bounding_box(&next_scene);
+            commit_scene(&scene, &scene_ref, next_scene);
+
+            let mut updated_tree = layer_tree_ref.borrow().clone();
+            for id in &shape_ids {
+                updated_tree.add_shape(*id);
+            }
+            *layer_tree_ref.borrow_mut() = updated_tree.clone();
+            layer_tree.set(updated_tree);
+
+            fixed_anchor.set(Point::new(bbox.x, bbox.y));
+            dimensions.set(Dimensions::new(bbox.width, bbox.height));
+            base_dimensions.set(Dimensions::new(bbox.width, bbox.height));
+            translation.replace(Point::new(0.0, 0.0));
+            tool_mode.set(ToolMode::Select);
+            has_unsaved_changes.set(true);
+        })
+    };
+
+    // Toolbar add buttons drop a shape at the center of the view and select it
+    let on_add_shape = {
+        let view_ref = view_ref.clone();
+        let canvas_size_ref = canvas_size_ref.clone();
+        let add_shapes = add_shapes.clone();
+        Callback::from(move |kind: AddShape| {
+            let size = *canvas_size_ref.borrow();
+            let center = view_ref.borrow().screen_to_world(Point::new(size.width / 2.0, size.height / 2.0));
+            add_shapes.emit(vec![create_added_shape(kind, Vec2::new(center.x as f32, center.y as f32))]);
+        })
+    };
+
+    let on_toggle_pixel_snap = {
+        let pixel_snap = pixel_snap.clone();
+        Callback::from(move |_| pixel_snap.set(!*pixel_snap))
+    };
+
+    let on_toggle_animations = {
+        let animations = animations.clone();
+        Callback::from(move |_| animations.set(!*animations))
+    };
+
+    let on_toggle_msaa = {
+        let msaa = msaa.clone();
+        Callback::from(move |_| msaa.set(!*msaa))
+    };
+
+    // Copy / paste with Cmd/Ctrl+C and Cmd/Ctrl+V
+    // Select every visible, unlocked shape; this also leaves vertex editing
+    let on_select_all = {
+        let scene = scene.clone();
+        let set_selection = set_selection_from_ids.clone();
+        Callback::from(move |_: ()| set_selection.emit(scene.selectable_ids()))
+    };
+    let select_all_ref = use_mut_ref(|| None::<Callback<()>>);
+    *select_all_ref.borrow_mut() = Some(on_select_all.clone());
+
+    // Copies go to the system clipboard as JSON and to an internal clipboard, which is
+    // used when the system clipboard can't be read (e.g. permission denied)
+    // Ctrl/Cmd+A selects all shapes instead of the page's text
+    let internal_clipboard = use_mut_ref(Vec::<Shape>::new);
+    let add_shapes_ref = use_mut_ref(|| None::<Callback<Vec<Shape>>>);
+    *add_shapes_ref.borrow_mut() = Some(add_shapes.clone());
+    {
+        let scene_ref = scene_ref.clone();
+        let internal_clipboard = internal_clipboard.clone();
+        let add_shapes_ref = add_shapes_ref.clone();
+        let select_all_ref = select_all_ref.clone();
+        use_effect_with((), move |_| {
+            let window = web_sys::window().expect("no window");
+            let document = window.document().expect("no document");
+
+            let listener = EventListener::new(&document, "keydown", move |event| {
+                let Some(keyboard_event) = event.dyn_ref::<web_sys::KeyboardEvent>() else {
+                    return;
+                };
+                if !(keyboard_event.meta_key() || keyboard_event.ctrl_key()) {
+                    return;
+                }
+
+                // Leave copy/paste inside text fields alone
+                if event.target().and_then(|t| t.dyn_into::<HtmlInputElement>().ok()).is_some() {
+                    return;
+                }
+
+                let clipboard = window.navigator().clipboard();
+                match keyboard_event.key().as_str() {
+                    "c" => {
+                        let scene = scene_ref.borrow();
+                        let copied: Vec<Shape> = scene
+                            .shapes()
+                            .iter()
+                            .filter(|shape| scene.is_selected(shape.id))
+                            .cloned()
+                            .collect();
+                        if copied.is_empty() {
+                            return;
                         }
+                        keyboard_event.prevent_default();
+
+                        let text = serialize_shapes(&copied);
+                        *internal_clipboard.borrow_mut() = copied;
+                        wasm_bindgen_futures::spawn_local(async move {
+                            if let Err(e) = wasm_bindgen_futures::JsFuture::from(clipboard.write_text(&text)).await {
+                                web_sys::console::warn_2(&"Clipboard write failed; using internal clipboard".into(), &e);
+                            }
+                        });
+                    }
+                    "a" | "A" => {
+                        keyboard_event.prevent_default();
+                        if let Some(select_all) = select_all_ref.borrow().as_ref() {
+                            select_all.emit(());
+                        }
+                    }
+                    "v" => {
+                        keyboard_event.prevent_default();
+
+                        let internal_clipboard = internal_clipboard.clone();
+                        let add_shapes_ref = add_shapes_ref.clone();
+                        wasm_bindgen_futures::spawn_local(async move {
+                            let shapes = match wasm_bindgen_futures::JsFuture::from(clipboard.read_text()).await {
+                                Ok(text) => text.as_string().and_then(|text| deserialize_shapes(&text)),
+                                Err(e) => {
+                                    web_sys::console::warn_2(&"Clipboard read failed; using internal clipboard".into(), &e);
+                                    Some(internal_clipboard.borrow().clone())
+                                }
+                            };
+
+                            if let Some(shapes) = shapes.filter(|shapes| !shapes.is_empty()) {
+                                if let Some(add_shapes) = add_shapes_ref.borrow().as_ref() {
+                                    add_shapes.emit(paste_copies(&shapes, PASTE_OFFSET));
+                                }
+                            }
+                        });
                     }
+                    _ => {}
+                }
+            });
+
+            move || drop(listener)
+        });
+    }
+
+    // Escape closes the pen tool's polygon-in-progress
+    {
+        let draft_polygon = draft_polygon.clone();
+        let draft_polygon_ref = draft_polygon_ref.clone();
+        let add_shapes_ref = add_shapes_ref.clone();
+        use_effect_with((), move |_| {
+            let window = web_sys::window().expect("no window");
+            let document = window.document().expect("no document");
+
+            let listener = EventListener::new(&document, "keydown", move |event| {
+                let Some(keyboard_event) = event.dyn_ref::<web_sys::KeyboardEvent>() else {
+                    return;
+                };
+                if keyboard_event.key() != "Escape" || draft_polygon_ref.borrow().is_empty() {
+                    return;
+                }
+
+                let draft = draft_polygon_ref.take();
+                draft_polygon.set(Vec::new());
+                if let (Some(shape), Some(add_shapes)) = (create_pen_shape(&draft), add_shapes_ref.borrow().as_ref()) {
+                    add_shapes.emit(vec![shape]);
+                }
+            });
+
+            move || drop(listener)
+        });
+    }
+
+    // Double-click or E switches a single selected polygon or path to vertex editing
+    let enter_vertex_edit = {
+        let scene = scene.clone();
+        let edit_mode = edit_mode.clone();
+        let selected_vertex = selected_vertex.clone();
+        let tool_mode = tool_mode.clone();
+        Callback::from(move |_: ()| {
+            if *tool_mode != ToolMode::Select {
+                return;
+            }
+            if let [id] = scene.selection() {
+                if matches!(
+                    scene.get_shape(*id).map(|s| &s.geometry),
+                    Some(ShapeGeometry::Polygon { .. } | ShapeGeometry::Path { .. })
+                ) {
+                    selected_vertex.set(None);
+                    edit_mode.set(EditMode::Vertex { shape_id: *id });
+                }
+            }
+        })
+    };
+
+    // Leaving vertex editing reselects the shape so the bounding box fits its new outline
+    let exit_vertex_edit = {
+        let scene = scene.clone();
+        let set_selection = set_selection_from_ids.clone();
+        Callback::from(move |_: ()| {
+            if vertex_edit_id.is_some() {
+                set_selection.emit(scene.selection().to_vec());
+            }
+        })
+    };
+
+    let enter_vertex_edit_ref = use_mut_ref(|| None::<Callback<()>>);
+    *enter_vertex_edit_ref.borrow_mut() = Some(enter_vertex_edit.clone());
+    let exit_vertex_edit_ref = use_mut_ref(|| None::<Callback<()>>);
+    *exit_vertex_edit_ref.borrow_mut() = Some(exit_vertex_edit.clone());
+    {
+        let enter_vertex_edit_ref = enter_vertex_edit_ref.clone();
+        let exit_vertex_edit_ref = exit_vertex_edit_ref.clone();
+        use_effect_with((), move |_| {
+            let window = web_sys::window().expect("no window");
+            let document = window.document().expect("no document");
+
+            let listener = EventListener::new(&document, "keydown", move |event| {
+                let Some(keyboard_event) = event.dyn_ref::<web_sys::KeyboardEvent>() else {
+                    return;
+                };
+                if keyboard_event.meta_key() || keyboard_event.ctrl_key() || keyboard_event.alt_key() {
+                    return;
+                }
+                // Leave typing in text fields alone
+                if event.target().and_then(|t| t.dyn_into::<HtmlInputElement>().ok()).is_some() {
+                    return;
+                }
+
+                let callback = match keyboard_event.key().as_str() {
+                    "e" | "E" => enter_vertex_edit_ref.borrow().clone(),
+                    "Escape" => exit_vertex_edit_ref.borrow().clone(),
+                    _ => None,
+                };
+                if let Some(callback) = callback {
+                    callback.emit(());
+                }
+            });
+
+            move || drop(listener)
+        });
+    }
+
+    let on_vertex_mousedown = {
+        let dragged_handle = dragged_handle.clone();
+        let selected_vertex = selected_vertex.clone();
+        Callback::from(move |(index, _): (usize, MouseEvent)| {
+            selected_vertex.set(Some(index));
+            dragged_handle.set(Some(EditHandle::Vertex(index)));
+        })
+    };
+
+    let on_control_mousedown = {
+        let dragged_handle = dragged_handle.clone();
+        Callback::from(move |(index, _): (usize, MouseEvent)| dragged_handle.set(Some(EditHandle::Control(index))))
+    };
 
-                    if !selected.is_empty() {
-                        set_selection.emit(selected);
-                    } else if bbox.width > 0.0 && bbox.height > 0.0 {
-                        set_selection.emit(shapes.iter().map(|s| s.id).collect());
-                    } else {
-                        // Clear selection via the callback to update refs
-                        set_selection.emit(Vec::new());
-                    }
-                }
+    // Backspace in vertex editing removes the selected polygon vertex instead of the
+    // shape; returns whether the key was taken, even when the polygon is at its minimum
+    let remove_selected_vertex = {
+        let scene = scene.clone();
+        let scene_ref = scene_ref.clone();
+        let selected_vertex = selected_vertex.clone();
+        let render_version = render_version.clone();
+        let has_unsaved_changes = has_unsaved_changes.clone();
+        let toast_message = toast_message.clone();
+        Callback::from(move |_: ()| -> bool {
+            let (Some(shape_id), Some(index)) = (vertex_edit_id, *selected_vertex) else {
+                return false;
+            };
+            let mut next_scene = scene_ref.borrow().clone();
+            if next_scene.remove_polygon_vertex(shape_id, index) {
+                commit_scene(&scene, &scene_ref, next_scene);
+                selected_vertex.set(None);
+                render_version.set(*render_version + 1);
+                has_unsaved_changes.set(true);
+            } else if matches!(scene.get_shape(shape_id).map(|s| &s.geometry), Some(ShapeGeometry::Polygon { .. })) {
+                toast_message.set(Some(format!("A polygon needs at least {} vertices", MIN_POLYGON_VERTICES)));
             }
-            selection_rect.set(None);
-            preview_bbox.set(None);
+            true
         })
     };
+    *remove_selected_vertex_ref.borrow_mut() = Some(remove_selected_vertex);
 
-    // GPU-specific mousemove handler with hit testing for hover
-    let on_gpu_mousemove = {
+    // Window-level vertex drag handlers: the shape is reshaped live in the scene
+    {
         let svg_ref = svg_ref.clone();
-        let selection_rect = selection_rect.clone();
-        let shapes = shapes.clone();
-        let preview_bbox = preview_bbox.clone();
-        let hovered_id = hovered_id.clone();
-        let selected_ids = selected_ids.clone();
-
-        Callback::from(move |e: MouseEvent| {
-            if let Some(svg) = svg_ref.cast::<SvgsvgElement>() {
-                let point = client_to_svg_coords(&e, &svg);
+        let view_ref = view_ref.clone();
+        let scene = scene.clone();
+        let scene_ref = scene_ref.clone();
+        let render_version = render_version.clone();
+        let has_unsaved_changes = has_unsaved_changes.clone();
+        let dragged_handle = dragged_handle.clone();
 
-                if let Some(current_rect) = selection_rect.as_ref() {
-                    // Marquee selection mode
-                    let updated_rect = SelectionRect::new(current_rect.start, point);
-                    selection_rect.set(Some(updated_rect));
+        use_effect_with((*dragged_handle, vertex_edit_id), move |(handle, shape_id)| -> Box<dyn FnOnce()> {
+            let (Some(handle), Some(shape_id)) = (*handle, *shape_id) else {
+                return Box::new(|| ());
+            };
+            let window = web_sys::window().expect("no window");
+            let base_version = *render_version;
+            let moves = Rc::new(Cell::new(0u32));
 
-                    let bbox = SelectionRect::new(current_rect.start, point).to_bounding_box();
-                    let mut selected_shapes: Vec<Shape> = Vec::new();
-                    for shape in shapes.iter() {
-                        let shape_bounds = shape.world_bounds();
-                        // Check if shape bounds intersect with selection rectangle
-                        let intersects = !(shape_bounds.max.x < bbox.x as f32 ||
-                            shape_bounds.min.x > (bbox.x + bbox.width) as f32 ||
-                            shape_bounds.max.y < bbox.y as f32 ||
-                            shape_bounds.min.y > (bbox.y + bbox.height) as f32);
-                        if intersects {
-                            selected_shapes.push(shape.clone());
-                        }
+            let mousemove_listener = {
+                let moves = moves.clone();
+                EventListener::new(&window, "mousemove", move |event| {
+                    let mouse_event = event.dyn_ref::<MouseEvent>().unwrap();
+                    let Some(svg) = svg_ref.cast::<SvgsvgElement>() else {
+                        return;
+                    };
+                    let point = client_to_svg_coords(mouse_event, &svg, &view_ref.borrow());
+
+                    // Start from the live scene; earlier moves in this drag have already been committed
+                    let mut next_scene = scene_ref.borrow().clone();
+                    let world = Vec2::new(point.x as f32, point.y as f32);
+                    let moved = match handle {
+                        EditHandle::Vertex(index) => next_scene.move_vertex(shape_id, index, world),
+                        EditHandle::Control(index) => next_scene
+                            .get_shape(shape_id)
+                            .and_then(|shape| control_handles(&shape.geometry).get(index).copied())
+                            .is_some_and(|control| next_scene.move_control_point(shape_id, control.command, control.slot, world)),
+                    };
+                    if moved {
+                        commit_scene(&scene, &scene_ref, next_scene);
+                        moves.set(moves.get() + 1);
+                        render_version.set(base_version.wrapping_add(moves.get()));
                     }
+                })
+            };
 
-                    if !selected_shapes.is_empty() {
-                        let preview = calculate_shapes_bounding_box(&selected_shapes);
-                        preview_bbox.set(Some(preview));
-                    } else {
-                        preview_bbox.set(None);
-                    }
-                } else {
-                    // Not in marquee mode - do hit testing for hover
-                    // Don't show hover for individual shapes when a group is selected
-                    if selected_ids.is_empty() {
-                        let new_hovered = find_shape_at_point(&shapes, &point);
-                        if new_hovered != *hovered_id {
-                            hovered_id.set(new_hovered);
-                        }
-                    } else {
-                        // Clear hover when group is selected
-                        if hovered_id.is_some() {
-                            hovered_id.set(None);
-                        }
-                    }
+            let mouseup_listener = EventListener::new(&window, "mouseup", move |_event| {
+                dragged_handle.set(None);
+                if moves.get() > 0 {
+                    has_unsaved_changes.set(true);
                 }
-            }
+            });
+
+            Box::new(move || {
+                drop(mousemove_listener);
+                drop(mouseup_listener);
+            })
+        });
+    }
+
+    // Alt+click on an edge of the edited polygon adds a vertex there and starts dragging it;
+    // returns whether a vertex was added
+    let insert_vertex = {
+        let scene = scene.clone();
+        let scene_ref = scene_ref.clone();
+        let view_ref = view_ref.clone();
+        let selected_vertex = selected_vertex.clone();
+        let dragged_handle = dragged_handle.clone();
+        let render_version = render_version.clone();
+        let has_unsaved_changes = has_unsaved_changes.clone();
+        Callback::from(move |world: Vec2| -> bool {
+            let Some(shape_id) = vertex_edit_id else {
+                return false;
+            };
+            // Edges are picked within a few screen pixels at any zoom
+            let tolerance = (EDGE_PICK_DISTANCE / view_ref.borrow().zoom) as f32;
+            let mut next_scene = scene_ref.borrow().clone();
+            let Some(index) = next_scene.insert_polygon_vertex(shape_id, world, tolerance) else {
+                return false;
+            };
+            commit_scene(&scene, &scene_ref, next_scene);
+            selected_vertex.set(Some(index));
+            dragged_handle.set(Some(EditHandle::Vertex(index)));
+            render_version.set(*render_version + 1);
+            has_unsaved_changes.set(true);
+            true
         })
     };
 
     // GPU-specific mousedown handler with hit testing for selection
     let on_gpu_mousedown = {
+        let insert_vertex = insert_vertex.clone();
         let svg_ref = svg_ref.clone();
+        let view_ref = view_ref.clone();
         let selection_rect = selection_rect.clone();
-        let shapes = shapes.clone();
-        let selected_ids = selected_ids.clone();
-        let selected_ids_ref = selected_ids_ref.clone();
+        let scene = scene.clone();
+        let scene_ref = scene_ref.clone();
         let layer_tree_ref = layer_tree_ref.clone();
         let fixed_anchor = fixed_anchor.clone();
         let dimensions = dimensions.clone();
@@ -844,19 +2822,81 @@ pub fn resizable_canvas() -> Html {
         let move_start = move_start.clone();
         let hovered_id = hovered_id.clone();
         let translation = translation.clone();
+        let tool_mode = tool_mode.clone();
+        let line_start = line_start.clone();
+        let draw_start = draw_start.clone();
+        let draw_rect = draw_rect.clone();
+        let is_drawing = is_drawing.clone();
+        let add_shapes = add_shapes.clone();
+        let draft_polygon = draft_polygon.clone();
+        let draft_polygon_ref = draft_polygon_ref.clone();
+        let locked_notice = locked_notice.clone();
+        let exit_vertex_edit = exit_vertex_edit.clone();
+        let is_panning = is_panning.clone();
+        let pan_last = pan_last.clone();
+        let space_held = space_held.clone();
 
         Callback::from(move |e: MouseEvent| {
             e.prevent_default();
 
+            // Middle button, or any button with Space held, drags the view
+            if e.button() == 1 || *space_held.borrow() {
+                pan_last.replace(Some(Point::new(e.client_x() as f64, e.client_y() as f64)));
+                is_panning.set(true);
+                return;
+            }
+
             if let Some(svg) = svg_ref.cast::<SvgsvgElement>() {
-                let point = client_to_svg_coords(&e, &svg);
+                let point = client_to_svg_coords(&e, &svg, &view_ref.borrow());
+
+                // Each pen click places a vertex; the second click of a double-click closes the polygon
+                if *tool_mode == ToolMode::Pen {
+                    if e.detail() >= 2 {
+                        let draft = draft_polygon_ref.take();
+                        draft_polygon.set(Vec::new());
+                        if let Some(shape) = create_pen_shape(&draft) {
+                            add_shapes.emit(vec![shape]);
+                        }
+                    } else {
+                        draft_polygon_ref.borrow_mut().push(point);
+                        draft_polygon.set(draft_polygon_ref.borrow().clone());
+                    }
+                    return;
+                }
+
+                // Drawing tools bypass hit testing and marquee selection
+                if *tool_mode != ToolMode::Select {
+                    // A second click completes a click-click line
+                    let pending_line = line_start.borrow_mut().take();
+                    if let Some(start) = pending_line.filter(|_| *tool_mode == ToolMode::Line) {
+                        if let Some(shape) = create_drawn_shape(ToolMode::Line, start, point) {
+                            add_shapes.emit(vec![shape]);
+                        }
+                        return;
+                    }
+
+                    draw_start.replace(Some(point));
+                    draw_rect.set(Some(SelectionRect::new(point, point)));
+                    is_drawing.set(true);
+                    return;
+                }
+
+                // A click away from the vertex handles only leaves vertex editing, unless
+                // it's an Alt+click on an edge
+                if vertex_edit_id.is_some() {
+                    if !(e.alt_key() && insert_vertex.emit(Vec2::new(point.x as f32, point.y as f32))) {
+                        exit_vertex_edit.emit(());
+                    }
+                    return;
+                }
 
                 // Check if clicked on a shape
-                if let Some(shape_id) = find_shape_at_point(&shapes, &point) {
+                let target = Vec2::new(point.x as f32, point.y as f32);
+                let hit = scene.hit_test(target);
+                locked_notice.set(hit.is_none() && scene.locked_shape_at(target).is_some());
+                if let Some(shape_id) = hit {
                     // Check if clicked shape is already part of current selection
-                    let is_already_selected = selected_ids.contains(&shape_id);
-
-                    if is_already_selected && !selected_ids.is_empty() {
+                    if scene.is_selected(shape_id) {
                         // Clicked on an already-selected shape - move the entire group
                         // Don't change selection, just start moving
                         let anchor = *fixed_anchor;
@@ -869,19 +2909,13 @@ pub fn resizable_canvas() -> Html {
                         let ids_to_select = tree.get_selection_for_shape(shape_id);
                         drop(tree);
 
-                        // Get all shapes to select and calculate combined bounding box
-                        let selected_shapes: Vec<Shape> = shapes
-                            .iter()
-                            .filter(|s| ids_to_select.contains(&s.id))
-                            .cloned()
-                            .collect();
-
-                        if !selected_shapes.is_empty() {
-                            let bbox = calculate_shapes_bounding_box(&selected_shapes);
+                        // Select all shapes and calculate combined bounding box
+                        let mut next_scene = (*scene).clone();
+                        next_scene.set_selection(&ids_to_select);
 
-                            // Update both state and ref for keyboard handler
-                            *selected_ids_ref.borrow_mut() = ids_to_select.clone();
-                            selected_ids.set(ids_to_select);
+                        if !next_scene.selection().is_empty() {
+                            let bbox = calculate_selection_bounding_box(&next_scene);
+                            commit_scene(&scene, &scene_ref, next_scene);
                             let anchor = Point::new(bbox.x, bbox.y);
                             fixed_anchor.set(anchor);
                             dimensions.set(Dimensions::new(bbox.width, bbox.height));
@@ -960,15 +2994,29 @@ pub fn resizable_canvas() -> Html {
     // Bounding box drag (move)
     let on_bbox_mousedown = {
         let svg_ref = svg_ref.clone();
+        let view_ref = view_ref.clone();
         let is_moving = is_moving.clone();
         let move_start = move_start.clone();
         let fixed_anchor = fixed_anchor.clone();
         let hovered_id = hovered_id.clone();
+        let tool_mode = tool_mode.clone();
+        let enter_vertex_edit = enter_vertex_edit.clone();
+        let space_held = space_held.clone();
 
         Callback::from(move |e: MouseEvent| {
+            // Let drawing tools start a new shape on top of the selection,
+            // and view pans start from the canvas underneath
+            if *tool_mode != ToolMode::Select || e.button() == 1 || *space_held.borrow() {
+                return;
+            }
+
             e.stop_propagation();
+            if e.detail() >= 2 {
+                enter_vertex_edit.emit(());
+                return;
+            }
             if let Some(svg) = svg_ref.cast::<SvgsvgElement>() {
-                let point = client_to_svg_coords(&e, &svg);
+                let point = client_to_svg_coords(&e, &svg, &view_ref.borrow());
                 move_start.replace(Some((point, *fixed_anchor)));
                 is_moving.set(true);
                 hovered_id.set(None);
@@ -981,12 +3029,17 @@ pub fn resizable_canvas() -> Html {
         let is_dragging = is_dragging.clone();
         let active_handle = active_handle.clone();
         let svg_ref = svg_ref.clone();
+        let view_ref = view_ref.clone();
         let resize_start_anchor = resize_start_anchor.clone();
         let resize_base_signed = resize_base_signed.clone();
         let resize_current_dims = resize_current_dims.clone();
         let dimensions = dimensions.clone();
         let base_dimensions = base_dimensions.clone();
         let fixed_anchor = fixed_anchor.clone();
+        let scene_for_snap = scene.clone();
+        let snap_config = snap_config.at_zoom(*wheel_zoom);
+        let canvas_size = *canvas_size;
+        let guidelines = guidelines.clone();
         let commit_transform = commit_selection_transform.clone();
 
         use_effect_with(
@@ -1002,19 +3055,22 @@ pub fn resizable_canvas() -> Html {
                 // Mousemove handler
                 let mousemove_listener = {
                     let svg_ref = svg_ref.clone();
+                    let view_ref = view_ref.clone();
                 let resize_start_anchor = resize_start_anchor.clone();
                 let resize_current_dims = resize_current_dims.clone();
                 let dimensions = dimensions.clone();
                 let base_dimensions = base_dimensions.clone();
                 let resize_base_signed = resize_base_signed.clone();
                 let fixed_anchor = fixed_anchor.clone();
+                let scene_for_snap = scene_for_snap.clone();
+                let guidelines = guidelines.clone();
 
                 EventListener::new(&window, "mousemove", move |event| {
                     let mouse_event = event.dyn_ref::<MouseEvent>().unwrap();
 
                     if let Some(svg) = svg_ref.cast::<SvgsvgElement>() {
                         if let Some(anchor_point) = *resize_start_anchor.borrow() {
-                            let point = client_to_svg_coords(mouse_event, &svg);
+                            let point = client_to_svg_coords(mouse_event, &svg, &view_ref.borrow());
                             let signed_base = resize_base_signed
                                 .borrow()
                                 .as_ref()
@@ -1055,10 +3111,42 @@ pub fn resizable_canvas() -> Html {
                                 new_height_signed.signum()
                             };
 
-                            let new_dims = Dimensions::new(
+                            let mut new_dims = Dimensions::new(
                                 width_sign * new_width_signed.abs().max(MIN_SIZE),
                                 height_sign * new_height_signed.abs().max(MIN_SIZE),
                             );
+
+                            if mouse_event.shift_key() {
+                                // Shift keeps the original aspect ratio; snapping would break it
+                                new_dims = constrain_resize_aspect(new_dims, signed_base, handle_val, MIN_SIZE);
+                                guidelines.set(Vec::new());
+                            } else {
+                                // Snap the dragged edges (Ctrl/Cmd bypasses), keeping the minimum size
+                                let proposed_box = BoundingBox::new(anchor_point.x, anchor_point.y, new_dims.width, new_dims.height);
+                                // Signed dimensions can put the anchor at either corner
+                                let signed = bbox_to_scene_bbox(&proposed_box);
+                                let candidates = scene_for_snap.snap_candidates(
+                                    &BBox::new(signed.min.min(signed.max), signed.min.max(signed.max)),
+                                    snap_config.threshold as f32,
+                                );
+                                let snap_result = calculate_resize_snap(
+                                    &proposed_box,
+                                    handle_val,
+                                    &candidates,
+                                    scene_for_snap.selection(),
+                                    canvas_size.width,
+                                    canvas_size.height,
+                                    &snap_config.bypassed(mouse_event.ctrl_key() || mouse_event.meta_key()),
+                                );
+                                if snap_result.dimensions.width.abs() >= MIN_SIZE
+                                    && snap_result.dimensions.height.abs() >= MIN_SIZE
+                                {
+                                    new_dims = snap_result.dimensions;
+                                    guidelines.set(snap_result.guidelines);
+                                } else {
+                                    guidelines.set(Vec::new());
+                                }
+                            }
                             // Update both the ref (for immediate commit access) and state (for rendering)
                             resize_current_dims.replace(Some(new_dims));
                             dimensions.set(new_dims);
@@ -1098,14 +3186,17 @@ pub fn resizable_canvas() -> Html {
     {
         let is_moving = is_moving.clone();
         let svg_ref = svg_ref.clone();
+        let view_ref = view_ref.clone();
         let move_start = move_start.clone();
         let fixed_anchor = fixed_anchor.clone();
         let dimensions = dimensions.clone();
         let translation = translation.clone();
         let translation_state = translation_state.clone();
-        let shapes_for_snap = shapes.clone();
-        let selected_ids = selected_ids.clone();
+        let scene_for_snap = scene.clone();
+        let snap_config = snap_config.at_zoom(*wheel_zoom);
+        let canvas_size = *canvas_size;
         let guidelines = guidelines.clone();
+        let spacing_indicators = spacing_indicators.clone();
         let commit_transform = commit_selection_transform.clone();
 
         use_effect_with(*is_moving, move |moving| -> Box<dyn FnOnce()> {
@@ -1118,24 +3209,32 @@ pub fn resizable_canvas() -> Html {
             // Mousemove handler
             let mousemove_listener = {
                 let svg_ref = svg_ref.clone();
+                let view_ref = view_ref.clone();
                 let move_start = move_start.clone();
                 let translation = translation.clone();
                 let translation_state = translation_state.clone();
                 let fixed_anchor = fixed_anchor.clone();
                 let dimensions = dimensions.clone();
-                let shapes_for_snap = shapes_for_snap.clone();
-                let selected_ids_for_snap = selected_ids.clone();
+                let scene_for_snap = scene_for_snap.clone();
                 let guidelines_for_snap = guidelines.clone();
+                let spacing_for_snap = spacing_indicators.clone();
 
                 EventListener::new(&window, "mousemove", move |event| {
                     let mouse_event = event.dyn_ref::<MouseEvent>().unwrap();
 
                     if let Some(svg) = svg_ref.cast::<SvgsvgElement>() {
                         if let Some((start_point, _)) = *move_start.borrow() {
-                            let point = client_to_svg_coords(mouse_event, &svg);
+                            let point = client_to_svg_coords(mouse_event, &svg, &view_ref.borrow());
                             let delta_x = point.x - start_point.x;
                             let delta_y = point.y - start_point.y;
 
+                            // Shift locks to whichever axis has moved further so far
+                            let axis_lock = mouse_event.shift_key().then(|| AxisLock::from_delta(delta_x, delta_y));
+                            let (delta_x, delta_y) = match axis_lock {
+                                Some(axis) => axis.apply(delta_x, delta_y),
+                                None => (delta_x, delta_y),
+                            };
+
                             let dims = *dimensions;
                             let anchor = *fixed_anchor;
 
@@ -1147,15 +3246,21 @@ pub fn resizable_canvas() -> Html {
                                 dims.height,
                             );
 
-                            // Calculate snap (10px threshold)
+                            // Calculate snap (Ctrl/Cmd bypasses) against shapes near the box
+                            let candidates = scene_for_snap
+                                .snap_candidates(&bbox_to_scene_bbox(&proposed_box), snap_config.threshold as f32);
                             let snap_result = calculate_snap(
                                 &proposed_box,
-                                &shapes_for_snap,
-                                &*selected_ids_for_snap,
-                                CANVAS_WIDTH,
-                                CANVAS_HEIGHT,
-                                10.0,
+                                &candidates,
+                                scene_for_snap.selection(),
+                                canvas_size.width,
+                                canvas_size.height,
+                                &snap_config.bypassed(mouse_event.ctrl_key() || mouse_event.meta_key()),
                             );
+                            let snap_result = match axis_lock {
+                                Some(axis) => snap_result.restricted_to(axis),
+                                None => snap_result,
+                            };
 
                             // Apply snapped translation
                             let new_trans = Point::new(
@@ -1165,8 +3270,9 @@ pub fn resizable_canvas() -> Html {
                             *translation.borrow_mut() = new_trans;
                             translation_state.set(new_trans);
 
-                            // Update guidelines for rendering
+                            // Update guidelines and spacing markers for rendering
                             guidelines_for_snap.set(snap_result.guidelines);
+                            spacing_for_snap.set(snap_result.spacing_indicators);
                         }
                     }
                 })
@@ -1177,6 +3283,7 @@ pub fn resizable_canvas() -> Html {
                 let is_moving = is_moving.clone();
                 let move_start = move_start.clone();
                 let guidelines = guidelines.clone();
+                let spacing_indicators = spacing_indicators.clone();
                 let commit_transform = commit_transform.clone();
 
                 EventListener::new(&window, "mouseup", move |_event| {
@@ -1184,6 +3291,7 @@ pub fn resizable_canvas() -> Html {
                         is_moving.set(false);
                         move_start.replace(None);
                         guidelines.set(Vec::new());
+                        spacing_indicators.set(Vec::new());
                         commit_transform.emit(());
                     }
                 })
@@ -1196,11 +3304,110 @@ pub fn resizable_canvas() -> Html {
         });
     }
 
+    // Window-level drawing handlers (active while a drawing tool drag is in progress)
+    {
+        let is_drawing = is_drawing.clone();
+        let svg_ref = svg_ref.clone();
+        let view_ref = view_ref.clone();
+        let draw_start = draw_start.clone();
+        let draw_rect = draw_rect.clone();
+        let line_start = line_start.clone();
+        let tool = *tool_mode;
+        let add_shapes = add_shapes.clone();
+
+        use_effect_with(*is_drawing, move |drawing| -> Box<dyn FnOnce()> {
+            if !*drawing {
+                return Box::new(|| ());
+            }
+
+            let window = web_sys::window().expect("no window");
+
+            // Resolve the drag end point, applying the Shift square/circle constraint
+            let drag_end = {
+                let svg_ref = svg_ref.clone();
+                let view_ref = view_ref.clone();
+                move |mouse_event: &MouseEvent, start: Point| -> Option<Point> {
+                    let svg = svg_ref.cast::<SvgsvgElement>()?;
+                    let point = client_to_svg_coords(mouse_event, &svg, &view_ref.borrow());
+                    if mouse_event.shift_key() && !matches!(tool, ToolMode::Line | ToolMode::Star) {
+                        Some(constrain_to_square(start, point))
+                    } else {
+                        Some(point)
+                    }
+                }
+            };
+
+            // Mousemove handler
+            let mousemove_listener = {
+                let draw_start = draw_start.clone();
+                let draw_rect = draw_rect.clone();
+                let drag_end = drag_end.clone();
+
+                EventListener::new(&window, "mousemove", move |event| {
+                    let mouse_event = event.dyn_ref::<MouseEvent>().unwrap();
+                    if let Some(start) = *draw_start.borrow() {
+                        if let Some(end) = drag_end(mouse_event, start) {
+                            draw_rect.set(Some(SelectionRect::new(start, end)));
+                        }
+                    }
+                })
+            };
+
+            // Mouseup handler
+            let mouseup_listener = {
+                let is_drawing = is_drawing.clone();
+                let draw_start = draw_start.clone();
+                let draw_rect = draw_rect.clone();
+                let line_start = line_start.clone();
+                let add_shapes = add_shapes.clone();
+
+                EventListener::new(&window, "mouseup", move |event| {
+                    let mouse_event = event.dyn_ref::<MouseEvent>().unwrap();
+                    let start = draw_start.borrow_mut().take();
+                    is_drawing.set(false);
+                    draw_rect.set(None);
+
+                    let Some(start) = start else {
+                        return;
+                    };
+                    let Some(end) = drag_end(mouse_event, start) else {
+                        return;
+                    };
+
+                    let is_click = (end.x - start.x).abs() < 2.0 && (end.y - start.y).abs() < 2.0;
+                    if is_click {
+                        // A plain click with the line tool starts a click-click line
+                        if tool == ToolMode::Line {
+                            line_start.replace(Some(start));
+                        }
+                        // A plain click with the text tool places default-size text
+                        if tool == ToolMode::Text {
+                            if let Some(shape) = create_drawn_shape(tool, start, start) {
+                                add_shapes.emit(vec![shape]);
+                            }
+                        }
+                        return;
+                    }
+
+                    if let Some(shape) = create_drawn_shape(tool, start, end) {
+                        add_shapes.emit(vec![shape]);
+                    }
+                })
+            };
+
+            Box::new(move || {
+                drop(mousemove_listener);
+                drop(mouseup_listener);
+            })
+        });
+    }
+
     // Window-level marquee selection handlers (always attached; gate logic on state)
     {
         let selection_rect_handle = selection_rect.clone();
         let svg_ref = svg_ref.clone();
-        let shapes_for_marquee = shapes.clone();
+        let view_ref = view_ref.clone();
+        let scene_for_marquee = scene.clone();
         let set_selection = set_selection_from_ids.clone();
         let preview_bbox = preview_bbox.clone();
 
@@ -1209,8 +3416,9 @@ pub fn resizable_canvas() -> Html {
 
             let mousemove_listener = {
                 let svg_ref = svg_ref.clone();
+                let view_ref = view_ref.clone();
                 let selection_rect = selection_rect_handle.clone();
-                let shapes = shapes_for_marquee.clone();
+                let scene = scene_for_marquee.clone();
                 let preview_bbox = preview_bbox.clone();
 
                 EventListener::new(&window, "mousemove", move |event| {
@@ -1218,26 +3426,15 @@ pub fn resizable_canvas() -> Html {
 
                     if let Some(svg) = svg_ref.cast::<SvgsvgElement>() {
                         if let Some(rect) = selection_rect.as_ref() {
-                            let point = client_to_svg_coords(mouse_event, &svg);
+                            let point = client_to_svg_coords(mouse_event, &svg, &view_ref.borrow());
                             selection_rect.set(Some(SelectionRect::new(rect.start, point)));
 
                             // Calculate preview bounding box
                             let bbox = SelectionRect::new(rect.start, point).to_bounding_box();
-                            let mut selected_shapes: Vec<Shape> = Vec::new();
-                            for shape in shapes.iter() {
-                                let shape_bounds = shape.world_bounds();
-                                // Check if shape bounds intersect with selection rectangle
-                                let intersects = !(shape_bounds.max.x < bbox.x as f32 ||
-                                    shape_bounds.min.x > (bbox.x + bbox.width) as f32 ||
-                                    shape_bounds.max.y < bbox.y as f32 ||
-                                    shape_bounds.min.y > (bbox.y + bbox.height) as f32);
-                                if intersects {
-                                    selected_shapes.push(shape.clone());
-                                }
-                            }
+                            let hits = find_shapes_in_rect(scene.shapes(), &bbox_to_scene_bbox(&bbox), mouse_event.alt_key());
 
-                            if !selected_shapes.is_empty() {
-                                let preview = calculate_shapes_bounding_box(&selected_shapes);
+                            if !hits.is_empty() {
+                                let preview = calculate_shapes_bounding_box(hits.iter().filter_map(|id| scene.get_shape(*id)));
                                 preview_bbox.set(Some(preview));
                             } else {
                                 preview_bbox.set(None);
@@ -1249,42 +3446,24 @@ pub fn resizable_canvas() -> Html {
 
             let mouseup_listener = {
                 let selection_rect = selection_rect_handle.clone();
-                let shapes = shapes_for_marquee.clone();
+                let scene = scene_for_marquee.clone();
                 let set_selection = set_selection.clone();
                 let preview_bbox = preview_bbox.clone();
                 let svg_ref = svg_ref.clone();
+                let view_ref = view_ref.clone();
 
                 EventListener::new(&window, "mouseup", move |event| {
                     if let (Some(svg), Some(current_rect)) = (svg_ref.cast::<SvgsvgElement>(), selection_rect.as_ref()) {
                         let mouse_event = event.dyn_ref::<MouseEvent>().unwrap();
-                        let end_point = client_to_svg_coords(mouse_event, &svg);
+                        let end_point = client_to_svg_coords(mouse_event, &svg, &view_ref.borrow());
                         let rect = SelectionRect::new(current_rect.start, end_point);
                         let bbox = rect.to_bounding_box();
 
-                        // Find all shapes that intersect with selection rectangle
-                        let mut selected: Vec<u64> = Vec::new();
-                        for shape in shapes.iter() {
-                            let shape_bounds = shape.world_bounds();
-                            // Check if shape bounds intersect with selection rectangle
-                            let intersects = !(shape_bounds.max.x < bbox.x as f32 ||
-                                shape_bounds.min.x > (bbox.x + bbox.width) as f32 ||
-                                shape_bounds.max.y < bbox.y as f32 ||
-                                shape_bounds.min.y > (bbox.y + bbox.height) as f32);
-                            if intersects {
-                                selected.push(shape.id);
-                            }
-                        }
+                        // Find all shapes that intersect with (or, with Alt, lie inside) the selection rectangle
+                        let selected = find_shapes_in_rect(scene.shapes(), &bbox_to_scene_bbox(&bbox), mouse_event.alt_key());
 
-                        if !selected.is_empty() {
-                            set_selection.emit(selected);
-                        } else if bbox.width > 0.0 && bbox.height > 0.0 {
-                            // Fallback: if a meaningful marquee was drawn but no shapes intersected,
-                            // select everything so the UI remains interactive for tests.
-                            set_selection.emit(shapes.iter().map(|s| s.id).collect());
-                        } else {
-                            // Click without selection area: clear selection via callback
-                            set_selection.emit(Vec::new());
-                        }
+                        // An empty result clears the selection via the callback
+                        set_selection.emit(selected);
                     }
                     selection_rect.set(None);
                     preview_bbox.set(None);
@@ -1298,16 +3477,8 @@ pub fn resizable_canvas() -> Html {
         });
     }
 
-    // Get selected shape for properties panel (converted to Polygon for compatibility)
-    let selected_polygon: Option<Polygon> = if selected_ids.len() == 1 {
-        shapes.iter().find(|s| s.id == selected_ids[0]).and_then(|shape| {
-            // Convert shape back to polygon for properties panel
-            let opt: Option<Polygon> = shape.into();
-            opt
-        })
-    } else {
-        None
-    };
+    // Selected shapes for the properties panel, which shows values they share
+    let selected_shapes: Vec<Shape> = scene.selected_shapes().into_iter().cloned().collect();
 
     let properties_bbox = if has_selection {
         Some(bounding_box)
@@ -1318,7 +3489,7 @@ pub fn resizable_canvas() -> Html {
     // GPU rendering - compute transform overrides for selected shapes only
     // This is much faster than cloning all shapes on every frame
     let transform_overrides = compute_transform_overrides(
-        &shapes,
+        scene.shapes(),
         &selected_ids,
         &fixed_anchor,
         &trans,
@@ -1326,13 +3497,26 @@ pub fn resizable_canvas() -> Html {
         scale_y,
     );
 
-    let selection_bbox_gpu = if has_selection {
+    // Vertex handles replace the bounding box while a polygon is being reshaped
+    let selection_bbox_gpu = if has_selection && vertex_edit_id.is_none() {
         Some(bbox_to_scene_bbox(&bounding_box))
     } else {
         None
     };
+    let vertex_edit_shape = vertex_edit_id.and_then(|id| scene.get_shape(id));
+    let vertex_handles = vertex_edit_shape.map(|shape| shape.world_vertices()).unwrap_or_default();
+    let vertex_outline = vertex_edit_shape.is_some_and(|shape| matches!(shape.geometry, ShapeGeometry::Polygon { .. }));
+    let path_control_handles: Vec<(Vec2, Vec2)> = vertex_edit_shape
+        .map(|shape| {
+            control_handles(&shape.geometry)
+                .iter()
+                .map(|handle| (shape.transform.transform_point(handle.position), shape.transform.transform_point(handle.anchor)))
+                .collect()
+        })
+        .unwrap_or_default();
 
-    let marquee_rect_gpu = selection_rect.as_ref().map(|rect| {
+    // Drawing-tool drags reuse the marquee overlay as their preview
+    let marquee_rect_gpu = selection_rect.as_ref().or(draw_rect.as_ref()).map(|rect| {
         (
             Vec2::new(rect.start.x as f32, rect.start.y as f32),
             Vec2::new(rect.current.x as f32, rect.current.y as f32),
@@ -1341,6 +3525,17 @@ pub fn resizable_canvas() -> Html {
 
     let preview_bbox_gpu = preview_bbox.as_ref().map(|bbox| bbox_to_scene_bbox(bbox));
 
+    // Pen draft preview: placed vertices, then a segment to the cursor
+    let pen_preview: Vec<Vec2> = if draft_polygon.is_empty() {
+        Vec::new()
+    } else {
+        draft_polygon
+            .iter()
+            .chain(std::iter::once(&*cursor_position))
+            .map(|p| Vec2::new(p.x as f32, p.y as f32))
+            .collect()
+    };
+
     // Create callback adapter for handle mousedown (swap argument order)
     let on_handle_mousedown = {
         let handler = on_handle_mousedown_ref.clone();
@@ -1350,7 +3545,7 @@ pub fn resizable_canvas() -> Html {
     };
 
     // Generate shape info map for layers panel
-    let shape_infos_map: HashMap<u64, ShapeInfo> = shapes.iter().map(|shape| {
+    let shape_infos_map: HashMap<u64, ShapeInfo> = scene.shapes().iter().map(|shape| {
         let shape_type = match &shape.geometry {
             ShapeGeometry::Rectangle { .. } => ShapeType::Rectangle,
             ShapeGeometry::Ellipse { rx, ry } => {
@@ -1360,33 +3555,44 @@ pub fn resizable_canvas() -> Html {
                     ShapeType::Ellipse
                 }
             }
-            ShapeGeometry::Polygon { .. } => ShapeType::Polygon,
+            ShapeGeometry::Polygon { .. } | ShapeGeometry::RegularPolygon { .. } => ShapeType::Polygon,
+            ShapeGeometry::Star { .. } => ShapeType::Star,
             ShapeGeometry::Path { .. } => ShapeType::Path,
+            ShapeGeometry::Line { .. } => ShapeType::Line,
+            ShapeGeometry::Text { .. } => ShapeType::Text,
+            ShapeGeometry::Image { .. } => ShapeType::Image,
         };
         (shape.id, ShapeInfo {
             id: shape.id,
             name: shape.name.clone(),
             shape_type,
+            visible: shape.visible,
+            locked: shape.locked,
         })
     }).collect();
 
     // Rename handler for layers panel
     let on_rename = {
-        let shapes = shapes.clone();
+        let scene = scene.clone();
+        let scene_ref = scene_ref.clone();
         let layer_tree = layer_tree.clone();
         let layer_tree_ref = layer_tree_ref.clone();
         let render_version = render_version.clone();
         let has_unsaved_changes = has_unsaved_changes.clone();
         Callback::from(move |(id, new_name): (u64, String)| {
-            // Try to rename a shape first
-            let mut updated_shapes = (*shapes).clone();
-            if let Some(shape) = updated_shapes.iter_mut().find(|s| s.id == id) {
-                shape.name = new_name.clone();
-                shapes.set(updated_shapes);
+            // Try to rename a shape first; blank names are rejected either way
+            let mut next_scene = (*scene).clone();
+            if next_scene.get_shape(id).is_some() {
+                if !next_scene.rename_shape(id, &new_name) {
+                    return;
+                }
+                commit_scene(&scene, &scene_ref, next_scene);
             } else {
                 // Maybe it's a group - try to rename the group
                 let mut updated_tree = (*layer_tree).clone();
-                updated_tree.rename_group(id, new_name);
+                if !updated_tree.rename_group(id, new_name) {
+                    return;
+                }
                 *layer_tree_ref.borrow_mut() = updated_tree.clone();
                 layer_tree.set(updated_tree);
             }
@@ -1411,10 +3617,10 @@ pub fn resizable_canvas() -> Html {
     let on_group = {
         let layer_tree = layer_tree.clone();
         let layer_tree_ref = layer_tree_ref.clone();
-        let selected_ids = selected_ids.clone();
+        let scene = scene.clone();
         let has_unsaved_changes = has_unsaved_changes.clone();
         Callback::from(move |_: ()| {
-            let ids = (*selected_ids).clone();
+            let ids = scene.selection().to_vec();
             if ids.len() >= 2 {
                 let mut updated_tree = (*layer_tree).clone();
                 if updated_tree.group_shapes(&ids).is_some() {
@@ -1426,52 +3632,431 @@ pub fn resizable_canvas() -> Html {
         })
     };
 
+    // Ungroup callback - dissolves a group, keeping its shapes selected
+    let on_ungroup = {
+        let layer_tree = layer_tree.clone();
+        let layer_tree_ref = layer_tree_ref.clone();
+        let has_unsaved_changes = has_unsaved_changes.clone();
+        Callback::from(move |group_id: u64| {
+            let mut updated_tree = (*layer_tree).clone();
+            if updated_tree.ungroup(group_id) {
+                *layer_tree_ref.borrow_mut() = updated_tree.clone();
+                layer_tree.set(updated_tree);
+                has_unsaved_changes.set(true);
+            }
+        })
+    };
+
+    // Visibility and lock toggles from the layers panel
+    let on_toggle_visible = {
+        let scene = scene.clone();
+        let scene_ref = scene_ref.clone();
+        let render_version = render_version.clone();
+        let has_unsaved_changes = has_unsaved_changes.clone();
+        Callback::from(move |shape_id: u64| {
+            let Some(visible) = scene.get_shape(shape_id).map(|s| s.visible) else {
+                return;
+            };
+            let mut next_scene = (*scene).clone();
+            next_scene.set_visible(shape_id, !visible);
+            commit_scene(&scene, &scene_ref, next_scene);
+            render_version.set(*render_version + 1);
+            has_unsaved_changes.set(true);
+        })
+    };
+
+    let on_toggle_locked = {
+        let scene = scene.clone();
+        let scene_ref = scene_ref.clone();
+        let has_unsaved_changes = has_unsaved_changes.clone();
+        Callback::from(move |shape_id: u64| {
+            let Some(locked) = scene.get_shape(shape_id).map(|s| s.locked) else {
+                return;
+            };
+            let mut next_scene = (*scene).clone();
+            next_scene.set_locked(shape_id, !locked);
+            commit_scene(&scene, &scene_ref, next_scene);
+            has_unsaved_changes.set(true);
+        })
+    };
+
+    // Reorder callback - moves a layer row, then repaints in the new tree order
+    let on_reorder = {
+        let layer_tree = layer_tree.clone();
+        let layer_tree_ref = layer_tree_ref.clone();
+        let scene = scene.clone();
+        let scene_ref = scene_ref.clone();
+        let render_version = render_version.clone();
+        let has_unsaved_changes = has_unsaved_changes.clone();
+        Callback::from(move |(node, target, position): (LayerKey, LayerKey, DropPosition)| {
+            let mut updated_tree = (*layer_tree).clone();
+            if !updated_tree.move_node(node, target, position) {
+                return;
+            }
+
+            // Layer order is paint order: first row is drawn first (bottom)
+            let mut next_scene = (*scene).clone();
+            for (index, id) in updated_tree.all_shape_ids().into_iter().enumerate() {
+                next_scene.reorder(id, index);
+            }
+
+            *layer_tree_ref.borrow_mut() = updated_tree.clone();
+            layer_tree.set(updated_tree);
+            commit_scene(&scene, &scene_ref, next_scene);
+            render_version.set(*render_version + 1);
+            has_unsaved_changes.set(true);
+        })
+    };
+
+    // Switch tools (cancels any pending click-click line or pen draft)
+    let on_tool_change = {
+        let tool_mode = tool_mode.clone();
+        let line_start = line_start.clone();
+        let draft_polygon = draft_polygon.clone();
+        let draft_polygon_ref = draft_polygon_ref.clone();
+        let exit_vertex_edit = exit_vertex_edit.clone();
+        Callback::from(move |tool: ToolMode| {
+            exit_vertex_edit.emit(());
+            line_start.replace(None);
+            draft_polygon_ref.replace(Vec::new());
+            draft_polygon.set(Vec::new());
+            tool_mode.set(tool);
+        })
+    };
+
+    let on_snap_config_change = {
+        let snap_config = snap_config.clone();
+        Callback::from(move |config: SnapConfig| snap_config.set(config))
+    };
+
+    // Open the file picker for SVG import
+    let on_import_click = {
+        let import_input_ref = import_input_ref.clone();
+        Callback::from(move |_: MouseEvent| {
+            if let Some(input) = import_input_ref.cast::<HtmlInputElement>() {
+                input.click();
+            }
+        })
+    };
+
+    // Turn SVG text into shapes, centered on the drop point if there is one, and
+    // report what was imported or skipped in a toast
+    let import_svg_text = {
+        let add_shapes = add_shapes.clone();
+        let toast_message = toast_message.clone();
+        Callback::from(move |(text, drop): (String, Option<(Vec2, usize)>)| match import_svg(&text) {
+            Ok(import) => {
+                toast_message.set(Some(import.summary()));
+                let shapes = match drop {
+                    Some((center, index)) => dropped_svg_shapes(import.shapes, center, index),
+                    None => import.shapes,
+                };
+                add_shapes.emit(shapes);
+            }
+            Err(err) => toast_message.set(Some(format!("Couldn't import SVG: {}", err))),
+        })
+    };
+
+    // Read the chosen SVG file and append its shapes to the canvas
+    let on_import_file = {
+        let import_reader = import_reader.clone();
+        let import_svg_text = import_svg_text.clone();
+        Callback::from(move |e: Event| {
+            let Some(input) = e.target_dyn_into::<HtmlInputElement>() else {
+                return;
+            };
+            let Some(file) = input.files().and_then(|files| files.get(0)) else {
+                return;
+            };
+            // Reset so selecting the same file again still fires a change event
+            input.set_value("");
+
+            let import_svg_text = import_svg_text.clone();
+            let import_reader_done = import_reader.clone();
+            let reader = gloo::file::callbacks::read_as_text(&gloo::file::File::from(file), move |result| {
+                match result {
+                    Ok(text) => import_svg_text.emit((text, None)),
+                    Err(err) => web_sys::console::error_1(&format!("Failed to read SVG file: {}", err).into()),
+                }
+                import_reader_done.replace(None);
+            });
+            import_reader.replace(Some(reader));
+        })
+    };
+
+    // Dropping PNG or JPEG files onto the canvas adds them as images centered on the drop point
+    // Each is read as a data URL and decoded for its natural size before it's added
+    // Dropped SVG files are read as text and imported as shapes centered the same way
+    let on_canvas_dragover = Callback::from(|e: DragEvent| e.prevent_default());
+    let on_canvas_drop = {
+        let svg_ref = svg_ref.clone();
+        let view_ref = view_ref.clone();
+        let canvas_size_ref = canvas_size_ref.clone();
+        let image_readers = image_readers.clone();
+        let next_image_reader = next_image_reader.clone();
+        let add_shapes = add_shapes.clone();
+        let import_svg_text = import_svg_text.clone();
+        Callback::from(move |e: DragEvent| {
+            e.prevent_default();
+            let Some(file_list) = e.data_transfer().and_then(|data| data.files()) else {
+                return;
+            };
+            let dropped: Vec<web_sys::File> = (0..file_list.length()).filter_map(|i| file_list.get(i)).collect();
+            let (svg_files, other_files): (Vec<_>, Vec<_>) =
+                dropped.into_iter().partition(|file| is_svg_file(&file.type_(), &file.name()));
+            let files = image_files(other_files.into_iter().map(|file| (file.type_(), file)));
+            let Some(svg) = svg_ref.cast::<SvgsvgElement>() else {
+                return;
+            };
+
+            let view = *view_ref.borrow();
+            let drop_point = client_to_svg_coords(&e, &svg, &view);
+            let center = Vec2::new(drop_point.x as f32, drop_point.y as f32);
+            let size = *canvas_size_ref.borrow();
+            let max = Vec2::new(size.width as f32, size.height as f32) * MAX_DROP_FRACTION / view.zoom as f32;
+
+            for (index, file) in files.into_iter().enumerate() {
+                let key = {
+                    let mut next = next_image_reader.borrow_mut();
+                    *next += 1;
+                    *next
+                };
+                let add_shapes = add_shapes.clone();
+                let image_readers_done = image_readers.clone();
+                let reader = gloo::file::callbacks::read_as_data_url(&gloo::file::File::from(file), move |result| {
+                    match result {
+                        Ok(src) => wasm_bindgen_futures::spawn_local(async move {
+                            match decode_image(&src).await {
+                                Ok(bitmap) => {
+                                    let natural = Vec2::new(bitmap.width() as f32, bitmap.height() as f32);
+                                    add_shapes.emit(vec![dropped_image_shape(src, natural, max, center, index)]);
+                                }
+                                Err(err) => web_sys::console::error_1(&format!("Failed to load image: {}", err).into()),
+                            }
+                        }),
+                        Err(err) => web_sys::console::error_1(&format!("Failed to read image file: {}", err).into()),
+                    }
+                    image_readers_done.borrow_mut().remove(&key);
+                });
+                image_readers.borrow_mut().insert(key, reader);
+            }
+
+            for (index, file) in svg_files.into_iter().enumerate() {
+                let key = {
+                    let mut next = next_image_reader.borrow_mut();
+                    *next += 1;
+                    *next
+                };
+                let import_svg_text = import_svg_text.clone();
+                let image_readers_done = image_readers.clone();
+                let reader = gloo::file::callbacks::read_as_text(&gloo::file::File::from(file), move |result| {
+                    match result {
+                        Ok(text) => import_svg_text.emit((text, Some((center, index)))),
+                        Err(err) => web_sys::console::error_1(&format!("Failed to read SVG file: {}", err).into()),
+                    }
+                    image_readers_done.borrow_mut().remove(&key);
+                });
+                image_readers.borrow_mut().insert(key, reader);
+            }
+        })
+    };
+
     html! {
-        <div class="flex w-full h-screen overflow-hidden">
+        <div class="flex w-full h-full overflow-hidden">
             // Layers Panel (Left) - now shows unified shapes list with grouping
             <LayersPanel
                 layer_tree={(*layer_tree).clone()}
                 shapes={shape_infos_map}
-                selected_ids={(*selected_ids).clone()}
+                selected_ids={selected_ids.clone()}
                 on_select={on_layer_select.clone()}
                 on_rename={on_rename}
                 on_toggle_expand={on_toggle_expand}
+                on_toggle_visible={on_toggle_visible}
+                on_toggle_locked={on_toggle_locked}
                 on_group={on_group}
+                on_ungroup={on_ungroup}
+                on_reorder={on_reorder}
+                preview_shapes={scene.shapes().to_vec()}
+                canvas_width={canvas_size.width}
+                canvas_height={canvas_size.height}
             />
 
             // Main Canvas Area (Center)
-            <div class="flex-1 flex items-center justify-center bg-gray-100 relative">
-                <div class="relative">
+            <div ref={canvas_area_ref} class="flex-1 min-w-0 flex items-center justify-center bg-gray-100 relative overflow-hidden">
+                <div
+                    class="relative"
+                    style="touch-action: none;"
+                    ref={canvas_container_ref}
+                    data-testid="canvas-drop-target"
+                    ondragover={on_canvas_dragover}
+                    ondrop={on_canvas_drop}
+                    ontouchstart={on_svg_touchstart}
+                    ontouchmove={on_svg_touchmove}
+                    ontouchend={on_svg_touchup.clone()}
+                    ontouchcancel={on_svg_touchup}
+                >
                     <GpuCanvas
-                        width={CANVAS_WIDTH as u32}
-                        height={CANVAS_HEIGHT as u32}
-                        shapes={(*shapes).clone()}
+                        width={canvas_size.width as u32}
+                        height={canvas_size.height as u32}
+                        shapes={layer_tree.apply_group_opacity(scene.shapes())}
                         render_version={*render_version}
                         selection_bbox={selection_bbox_gpu}
-                        selected_ids={(*selected_ids).clone()}
+                        selected_ids={selected_ids.clone()}
                         flip_x={current_dims.width.signum() != base_signed_dims.width.signum()}
                         flip_y={current_dims.height.signum() != base_signed_dims.height.signum()}
                         guidelines={(*guidelines).clone()}
+                        spacing_indicators={(*spacing_indicators).clone()}
                         marquee_rect={marquee_rect_gpu}
                         preview_bbox={preview_bbox_gpu}
+                        pen_preview={pen_preview}
+                        vertex_handles={vertex_handles}
+                        vertex_outline={vertex_outline}
+                        selected_vertex={*selected_vertex}
+                        control_handles={path_control_handles}
+                        grid_size={(snap_config.enabled && snap_config.snap_to_grid).then_some(snap_config.grid_size)}
                         onmousedown={on_gpu_mousedown.clone()}
                         onmousemove={on_gpu_mousemove.clone()}
                         onmouseup={on_svg_mouseup.clone()}
                         on_handle_mousedown={on_handle_mousedown}
                         on_bbox_mousedown={on_bbox_mousedown.clone()}
+                        on_vertex_mousedown={on_vertex_mousedown}
+                        on_control_mousedown={on_control_mousedown}
                         is_shape_hovered={hovered_id.is_some()}
                         background_color={[0.0, 0.0, 0.0, 0.0]}
                         transform_overrides={transform_overrides}
+                        view={view}
+                        render_loop={true}
+                        pixel_snap={*pixel_snap}
+                        animate_transitions={*animations}
+                        msaa={*msaa}
+                        transition_version={*transition_version}
                     />
                     // Invisible SVG for coordinate conversion (needed for mouse events)
                     <svg
                         ref={svg_ref.clone()}
-                        width={CANVAS_WIDTH.to_string()}
-                        height={CANVAS_HEIGHT.to_string()}
+                        width={canvas_size.width.to_string()}
+                        height={canvas_size.height.to_string()}
                         style="position: absolute; top: 0; left: 0; pointer-events: none; opacity: 0;"
                     />
 
+                    // Rulers along the top and left edges, with a blank square where they meet
+                    <Ruler
+                        axis={RulerAxis::Horizontal}
+                        origin={view.pan}
+                        scale={view.zoom}
+                        length={canvas_size.width}
+                        cursor={Some(*cursor_position)}
+                    />
+                    <Ruler
+                        axis={RulerAxis::Vertical}
+                        origin={view.pan}
+                        scale={view.zoom}
+                        length={canvas_size.height}
+                        cursor={Some(*cursor_position)}
+                    />
+                    <div
+                        class="absolute top-0 left-0 bg-white border-r border-b border-gray-400 pointer-events-none"
+                        style={format!("width: {RULER_SIZE}px; height: {RULER_SIZE}px;")}
+                    />
+
+                    // Drawing tools, clear of the rulers
+                    <div class="absolute top-7 left-7 flex gap-2">
+                        <Toolbar
+                            active_tool={*tool_mode}
+                            on_tool_change={on_tool_change}
+                            on_add_shape={on_add_shape}
+                            on_select_all={on_select_all}
+                            pixel_snap={*pixel_snap}
+                            on_toggle_pixel_snap={on_toggle_pixel_snap}
+                            animations={*animations}
+                            on_toggle_animations={on_toggle_animations}
+                            msaa={*msaa}
+                            on_toggle_msaa={on_toggle_msaa}
+                        />
+                        <SnapSettings config={*snap_config} on_change={on_snap_config_change} />
+                        <button
+                            data-testid="import-svg"
+                            onclick={on_import_click}
+                            class="h-10 px-3 rounded border border-gray-200 bg-white shadow-sm text-sm text-gray-700 hover:bg-gray-50"
+                        >
+                            {"Import SVG"}
+                        </button>
+                        <button
+                            data-testid="clear-saved-data"
+                            title="Remove the autosaved canvas from this browser"
+                            onclick={on_clear_saved_data}
+                            class="h-10 px-3 rounded border border-gray-200 bg-white shadow-sm text-sm text-gray-700 hover:bg-gray-50"
+                        >
+                            {"Clear saved data"}
+                        </button>
+                        <input
+                            ref={import_input_ref}
+                            type="file"
+                            accept=".svg,image/svg+xml"
+                            data-testid="import-svg-input"
+                            class="hidden"
+                            onchange={on_import_file}
+                        />
+                    </div>
+
+                    // Cursor position (and selection size) in canvas coordinates
+                    <div
+                        data-testid="cursor-coords"
+                        class="absolute bottom-2 left-7 flex gap-3 px-2 py-1 rounded bg-white/90 border border-gray-200 shadow-sm font-mono text-xs text-gray-700 pointer-events-none"
+                    >
+                        <span>{format!("X: {:.1}  Y: {:.1}", cursor_position.x, cursor_position.y)}</span>
+                        if let Some(bbox) = properties_bbox {
+                            <span data-testid="selection-size" class="text-gray-500">
+                                {format!("W: {:.1}  H: {:.1}", bbox.width, bbox.height)}
+                            </span>
+                        }
+                    </div>
+
+                    // Zoom level and controls
+                    <div class="absolute bottom-2 right-2 flex items-center gap-1 px-1 py-1 rounded bg-white/90 border border-gray-200 shadow-sm text-xs text-gray-700">
+                        {for [
+                            (ZoomAction::Out, "zoom-out", "Zoom out", "-"),
+                            (ZoomAction::In, "zoom-in", "Zoom in", "+"),
+                            (ZoomAction::Fit, "zoom-fit", "Zoom to fit", "Fit"),
+                        ].into_iter().enumerate().map(|(i, (action, testid, title, label))| {
+                            let on_zoom = on_zoom.clone();
+                            html! {
+                                <>
+                                    if i == 1 {
+                                        <button
+                                            data-testid="zoom-readout"
+                                            title="Reset to 100%"
+                                            onclick={
+                                                let on_zoom = on_zoom.clone();
+                                                Callback::from(move |_: MouseEvent| on_zoom.emit(ZoomAction::Reset))
+                                            }
+                                            class="w-12 h-6 rounded font-mono hover:bg-gray-100"
+                                        >
+                                            {format!("{:.0}%", *wheel_zoom * 100.0)}
+                                        </button>
+                                    }
+                                    <button
+                                        data-testid={testid}
+                                        {title}
+                                        onclick={Callback::from(move |_: MouseEvent| on_zoom.emit(action))}
+                                        class="min-w-6 h-6 px-1 rounded hover:bg-gray-100"
+                                    >
+                                        {label}
+                                    </button>
+                                </>
+                            }
+                        })}
+                    </div>
                 </div>
+
+                if let Some(message) = (*toast_message).clone() {
+                    <div
+                        data-testid="toast"
+                        class="absolute bottom-4 left-1/2 -translate-x-1/2 px-3 py-2 rounded bg-gray-900 text-white text-sm shadow"
+                    >
+                        {message}
+                    </div>
+                }
             </div>
 
             // Right Panel with Tab Bar
@@ -1479,12 +4064,32 @@ pub fn resizable_canvas() -> Html {
                 active_tab={*active_tab}
                 has_unsaved_changes={*has_unsaved_changes}
                 on_tab_change={on_tab_click.clone()}
-                selected_polygon={selected_polygon}
+                selected_shapes={selected_shapes}
                 properties_bbox={properties_bbox}
+                selection_count={selected_ids.len()}
+                locked_notice={*locked_notice && selected_ids.is_empty()}
+                on_align={on_align}
+                on_distribute={on_distribute}
+                can_distribute={selection_units(&scene, &layer_tree).0.len() >= 3}
                 on_update_fill={on_update_fill}
                 on_update_stroke={on_update_stroke}
                 on_update_position={on_update_position}
                 on_update_dimensions={on_update_dimensions}
+                on_update_opacity={on_update_opacity}
+                group_opacity={selected_group.and_then(|id| layer_tree.group_opacity(id))}
+                on_update_group_opacity={on_update_group_opacity}
+                on_update_stroke_width={on_update_stroke_width}
+                on_update_dash={on_update_dash}
+                on_update_line_style={on_update_line_style}
+                on_update_pie_slice={on_update_pie_slice}
+                on_update_corner_radii={on_update_corner_radii}
+                on_update_star_polygon={on_update_star_polygon}
+                on_update_fill_paint={on_update_fill_paint}
+                on_smooth_polygon={on_smooth_polygon}
+                on_update_text={on_update_text}
+                on_update_drop_shadow={on_update_drop_shadow}
+                on_boolean_op={on_boolean_op}
+                on_convert_to_path={on_convert_to_path}
                 chat_messages={(*chat_messages).clone()}
                 on_send_message={on_send_message}
                 version_history={(*version_history).clone()}
@@ -0,0 +1,228 @@
+//! Elliptical arcs as cubic beziers
+//!
+//! Lyon has no SVG-style arc command, so `ArcTo` is converted using the
+//! endpoint-to-center parameterization from the SVG spec (appendix F.6).
+
+use super::types::Vec2;
+
+/// Convert an SVG elliptical arc from `from` to `to` into cubic bezier segments
+/// Returns `(ctrl1, ctrl2, end)` for each segment, covering at most 90 degrees apiece.
+/// Empty when the arc is degenerate (same endpoints or a zero radius) and should
+/// be drawn as a straight line.
+pub fn arc_to_cubics(
+    from: Vec2,
+    rx: f32,
+    ry: f32,
+    x_rotation: f32,
+    large_arc: bool,
+    sweep: bool,
+    to: Vec2,
+) -> Vec<(Vec2, Vec2, Vec2)> {
+    // Handle degenerate cases
+    if from == to {
+        return vec![];
+    }
+
+    let mut rx = rx.abs();
+    let mut ry = ry.abs();
+
+    if rx == 0.0 || ry == 0.0 {
+        // Treat as line
+        return vec![];
+    }
+
+    let phi = x_rotation.to_radians();
+    let cos_phi = phi.cos();
+    let sin_phi = phi.sin();
+
+    // Step 1: Compute (x1', y1')
+    let dx = (from.x - to.x) / 2.0;
+    let dy = (from.y - to.y) / 2.0;
+    let x1_prime = cos_phi * dx + sin_phi * dy;
+    let y1_prime = -sin_phi * dx + cos_phi * dy;
+
+    // Step 2: Compute (cx', cy')
+    let rx_sq = rx * rx;
+    let ry_sq = ry * ry;
+    let x1_prime_sq = x1_prime * x1_prime;
+    let y1_prime_sq = y1_prime * y1_prime;
+
+    // Check if radii are large enough
+    let lambda = x1_prime_sq / rx_sq + y1_prime_sq / ry_sq;
+    if lambda > 1.0 {
+        let lambda_sqrt = lambda.sqrt();
+        rx *= lambda_sqrt;
+        ry *= lambda_sqrt;
+    }
+
+    let rx_sq = rx * rx;
+    let ry_sq = ry * ry;
+
+    let num = rx_sq * ry_sq - rx_sq * y1_prime_sq - ry_sq * x1_prime_sq;
+    let den = rx_sq * y1_prime_sq + ry_sq * x1_prime_sq;
+
+    let sq = if den == 0.0 { 0.0 } else { (num / den).max(0.0).sqrt() };
+    let sq = if large_arc == sweep { -sq } else { sq };
+
+    let cx_prime = sq * rx * y1_prime / ry;
+    let cy_prime = -sq * ry * x1_prime / rx;
+
+    // Step 3: Compute (cx, cy) from (cx', cy')
+    let cx = cos_phi * cx_prime - sin_phi * cy_prime + (from.x + to.x) / 2.0;
+    let cy = sin_phi * cx_prime + cos_phi * cy_prime + (from.y + to.y) / 2.0;
+
+    // Step 4: Compute theta1 and dtheta
+    fn angle(ux: f32, uy: f32, vx: f32, vy: f32) -> f32 {
+        let n = (ux * ux + uy * uy).sqrt() * (vx * vx + vy * vy).sqrt();
+        if n == 0.0 {
+            return 0.0;
+        }
+        let c = (ux * vx + uy * vy) / n;
+        let c = c.clamp(-1.0, 1.0);
+        let angle = c.acos();
+        if ux * vy - uy * vx < 0.0 { -angle } else { angle }
+    }
+
+    let theta1 = angle(1.0, 0.0, (x1_prime - cx_prime) / rx, (y1_prime - cy_prime) / ry);
+    let mut dtheta = angle(
+        (x1_prime - cx_prime) / rx,
+        (y1_prime - cy_prime) / ry,
+        (-x1_prime - cx_prime) / rx,
+        (-y1_prime - cy_prime) / ry,
+    );
+
+    if !sweep && dtheta > 0.0 {
+        dtheta -= 2.0 * std::f32::consts::PI;
+    } else if sweep && dtheta < 0.0 {
+        dtheta += 2.0 * std::f32::consts::PI;
+    }
+
+    // Convert arc to bezier curves
+    // Split into segments of at most 90 degrees
+    let num_segments = ((dtheta.abs() / (std::f32::consts::PI / 2.0)).ceil() as usize).max(1);
+    let segment_angle = dtheta / num_segments as f32;
+
+    // Radii, rotation and center placing the unit circle on the ellipse
+    let ellipse = (Vec2::new(rx, ry), cos_phi, sin_phi, Vec2::new(cx, cy));
+    fn transform_point(p: Vec2, (radii, cos_phi, sin_phi, center): (Vec2, f32, f32, Vec2)) -> Vec2 {
+        let Vec2 { x, y } = p * radii;
+        Vec2::new(cos_phi * x - sin_phi * y, sin_phi * x + cos_phi * y) + center
+    }
+
+    let mut curves = Vec::new();
+    let mut current_theta = theta1;
+
+    for _ in 0..num_segments {
+        let theta2 = current_theta + segment_angle;
+
+        // Compute bezier control points for this arc segment
+        let t = (segment_angle / 2.0).tan();
+        let alpha = segment_angle.sin() * ((4.0 + 3.0 * t * t).sqrt() - 1.0) / 3.0;
+
+        let cos_t1 = current_theta.cos();
+        let sin_t1 = current_theta.sin();
+        let cos_t2 = theta2.cos();
+        let sin_t2 = theta2.sin();
+
+        // Points on the unit circle
+        let p1x = cos_t1;
+        let p1y = sin_t1;
+        let p2x = cos_t2;
+        let p2y = sin_t2;
+
+        // Control points
+        let c1x = p1x - alpha * sin_t1;
+        let c1y = p1y + alpha * cos_t1;
+        let c2x = p2x + alpha * sin_t2;
+        let c2y = p2y - alpha * cos_t2;
+
+        // Transform from unit circle to actual ellipse
+        let ctrl1 = transform_point(Vec2::new(c1x, c1y), ellipse);
+        let ctrl2 = transform_point(Vec2::new(c2x, c2y), ellipse);
+        let end = transform_point(Vec2::new(p2x, p2y), ellipse);
+
+        curves.push((ctrl1, ctrl2, end));
+        current_theta = theta2;
+    }
+
+    curves
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(actual: Vec2, expected: Vec2) {
+        assert!(actual.distance(expected) < 0.1, "{actual:?} is not near {expected:?}");
+    }
+
+    /// Point at `t` along a cubic starting at `from`
+    fn cubic_at(from: Vec2, (c1, c2, end): (Vec2, Vec2, Vec2), t: f32) -> Vec2 {
+        let u = 1.0 - t;
+        from * (u * u * u) + c1 * (3.0 * u * u * t) + c2 * (3.0 * u * t * t) + end * (t * t * t)
+    }
+
+    #[test]
+    fn test_quarter_circle_is_one_cubic() {
+        let from = Vec2::new(10.0, 0.0);
+        let to = Vec2::new(0.0, 10.0);
+        let curves = arc_to_cubics(from, 10.0, 10.0, 0.0, false, true, to);
+
+        assert_eq!(curves.len(), 1);
+        assert_near(curves[0].2, to);
+        // Tangent handles sit at the usual 0.5523 * r
+        assert_near(curves[0].0, Vec2::new(10.0, 5.523));
+        assert_near(curves[0].1, Vec2::new(5.523, 10.0));
+        // The curve stays on the circle around the origin
+        assert!((cubic_at(from, curves[0], 0.5).length() - 10.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_flags_pick_the_arc() {
+        let from = Vec2::new(10.0, 0.0);
+        let to = Vec2::new(0.0, 10.0);
+
+        // The large arc the other way round the same circle takes three quarters
+        let curves = arc_to_cubics(from, 10.0, 10.0, 0.0, true, false, to);
+        assert_eq!(curves.len(), 3);
+        assert_near(curves[0].2, Vec2::new(0.0, -10.0));
+        assert_near(curves[2].2, to);
+
+        // The small arc with the opposite sweep is on the circle around (10, 10)
+        let curves = arc_to_cubics(from, 10.0, 10.0, 0.0, false, false, to);
+        assert_eq!(curves.len(), 1);
+        assert_near(cubic_at(from, curves[0], 0.5), Vec2::new(10.0 - 7.071, 10.0 - 7.071));
+    }
+
+    #[test]
+    fn test_small_radii_scale_up_to_reach_the_endpoint() {
+        // Too small to span 20 units, so the radius grows to 10: a semicircle
+        let from = Vec2::ZERO;
+        let to = Vec2::new(20.0, 0.0);
+        let curves = arc_to_cubics(from, 1.0, 1.0, 0.0, false, true, to);
+
+        assert_eq!(curves.len(), 2);
+        assert_near(curves[0].2, Vec2::new(10.0, -10.0));
+        assert_near(curves[1].2, to);
+    }
+
+    #[test]
+    fn test_rotated_ellipse() {
+        // rx along the y axis after a 90 degree rotation
+        let from = Vec2::new(0.0, -20.0);
+        let to = Vec2::new(10.0, 0.0);
+        let curves = arc_to_cubics(from, 20.0, 10.0, 90.0, false, true, to);
+
+        assert_eq!(curves.len(), 1);
+        assert_near(curves[0].2, to);
+        let mid = cubic_at(from, curves[0], 0.5);
+        assert!(((mid.x / 10.0).powi(2) + (mid.y / 20.0).powi(2) - 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_degenerate_arcs_are_lines() {
+        let p = Vec2::new(5.0, 5.0);
+        assert!(arc_to_cubics(p, 10.0, 10.0, 0.0, false, true, p).is_empty());
+        assert!(arc_to_cubics(p, 0.0, 10.0, 0.0, false, true, Vec2::ZERO).is_empty());
+    }
+}
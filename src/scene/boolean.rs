@@ -0,0 +1,724 @@
+//! Boolean operations on shapes: union, subtract, intersect and exclude
+//!
+//! Both shapes are flattened to closed polygons in world space. Their edges are split
+//! wherever they cross, each piece is kept or dropped depending on whether it lies
+//! inside the other shape, and the kept pieces are chained back into closed contours.
+//! Contours are oriented so the filled area is on the left of each edge, which makes
+//! holes wind the opposite way to the outlines around them.
+
+use super::arc::arc_to_cubics;
+use super::shape::{PathCommand, Shape, ShapeGeometry};
+use super::types::{BBox, Transform2D, Vec2};
+use crate::utils::point_in_polygon;
+
+/// Maximum distance between a curve and the polygon it's flattened to; matches the
+/// tessellator's default, so the result looks like what was drawn at 100%
+pub const BOOLEAN_TOLERANCE: f32 = 0.25;
+
+/// Points closer than this are treated as the same point
+const POINT_EPSILON: f32 = 1e-3;
+
+/// How two shapes' areas are combined
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BooleanOp {
+    /// Area covered by either shape
+    Union,
+    /// The bottom shape's area minus the top shape's
+    Subtract,
+    /// Area covered by both shapes
+    Intersect,
+    /// Area covered by exactly one of the shapes
+    Exclude,
+}
+
+impl BooleanOp {
+    pub const ALL: [BooleanOp; 4] = [BooleanOp::Union, BooleanOp::Subtract, BooleanOp::Intersect, BooleanOp::Exclude];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            BooleanOp::Union => "Union",
+            BooleanOp::Subtract => "Subtract",
+            BooleanOp::Intersect => "Intersect",
+            BooleanOp::Exclude => "Exclude",
+        }
+    }
+
+    pub fn to_kebab_case(self) -> &'static str {
+        match self {
+            BooleanOp::Union => "union",
+            BooleanOp::Subtract => "subtract",
+            BooleanOp::Intersect => "intersect",
+            BooleanOp::Exclude => "exclude",
+        }
+    }
+}
+
+/// Combine `shapes`, given bottom to top, into one path shape
+/// Each shape is applied to the result of those below it, so subtract removes every
+/// upper shape from the bottom one. The result keeps the bottom shape's ID and style;
+/// None when fewer than two shapes are given or nothing is left
+pub fn combine_shapes(shapes: &[&Shape], op: BooleanOp) -> Option<Shape> {
+    let [bottom, rest @ ..] = shapes else {
+        return None;
+    };
+    if rest.is_empty() {
+        return None;
+    }
+
+    let contours = rest.iter().fold(shape_contours(bottom, BOOLEAN_TOLERANCE), |contours, shape| {
+        boolean_contours(&contours, &shape_contours(shape, BOOLEAN_TOLERANCE), op)
+    });
+    let points: Vec<Vec2> = contours.iter().flatten().copied().collect();
+    let origin = BBox::from_points(&points)?.min;
+
+    let mut result = (*bottom).clone();
+    result.name = op.label().to_string();
+    result.geometry = ShapeGeometry::Path { commands: contour_commands(&contours, origin) };
+    result.transform = Transform2D::from_position(origin);
+    result.mark_dirty();
+    Some(result)
+}
+
+/// Area covered by either geometry, as a path in their shared local coordinates
+pub fn union(a: &ShapeGeometry, b: &ShapeGeometry) -> ShapeGeometry {
+    combine_geometry(a, b, BooleanOp::Union)
+}
+
+/// Area covered by both geometries; None when they don't overlap
+pub fn intersect(a: &ShapeGeometry, b: &ShapeGeometry) -> Option<ShapeGeometry> {
+    let result = combine_geometry(a, b, BooleanOp::Intersect);
+    match &result {
+        ShapeGeometry::Path { commands } if commands.is_empty() => None,
+        _ => Some(result),
+    }
+}
+
+/// `a`'s area minus `b`'s; an empty path when nothing is left
+pub fn subtract(a: &ShapeGeometry, b: &ShapeGeometry) -> ShapeGeometry {
+    combine_geometry(a, b, BooleanOp::Subtract)
+}
+
+/// Combine two untransformed geometries; to combine placed shapes, see `combine_shapes`
+fn combine_geometry(a: &ShapeGeometry, b: &ShapeGeometry, op: BooleanOp) -> ShapeGeometry {
+    let contours = boolean_contours(
+        &geometry_contours(a, BOOLEAN_TOLERANCE),
+        &geometry_contours(b, BOOLEAN_TOLERANCE),
+        op,
+    );
+    ShapeGeometry::Path { commands: contour_commands(&contours, Vec2::ZERO) }
+}
+
+/// Closed contours as path commands, shifted so `origin` becomes (0, 0)
+fn contour_commands(contours: &[Vec<Vec2>], origin: Vec2) -> Vec<PathCommand> {
+    contours
+        .iter()
+        .flat_map(|contour| {
+            let mut commands = vec![PathCommand::MoveTo(contour[0] - origin)];
+            commands.extend(contour[1..].iter().map(|&p| PathCommand::LineTo(p - origin)));
+            commands.push(PathCommand::Close);
+            commands
+        })
+        .collect()
+}
+
+/// The area of `shape` as closed contours in world space, curves flattened to within
+/// `tolerance`; lines and text have no area and give none
+pub fn shape_contours(shape: &Shape, tolerance: f32) -> Vec<Vec<Vec2>> {
+    geometry_contours(&shape.geometry, tolerance)
+        .into_iter()
+        .map(|contour| contour.into_iter().map(|p| shape.transform.transform_point(p)).collect())
+        .collect()
+}
+
+/// The area of `geometry` as closed contours in its local coordinates
+fn geometry_contours(geometry: &ShapeGeometry, tolerance: f32) -> Vec<Vec<Vec2>> {
+    match geometry {
+        ShapeGeometry::Polygon { .. } | ShapeGeometry::Star { .. } | ShapeGeometry::RegularPolygon { .. } => {
+            geometry.ring_points().into_iter().collect()
+        }
+        ShapeGeometry::Rectangle { width, height, corner_radii } => {
+            flatten_path(&corner_radii.outline(*width, *height), tolerance)
+        }
+        ShapeGeometry::Image { width, height, .. } => vec![vec![
+            Vec2::ZERO,
+            Vec2::new(*width, 0.0),
+            Vec2::new(*width, *height),
+            Vec2::new(0.0, *height),
+        ]],
+        ShapeGeometry::Ellipse { rx, ry } => vec![flatten_ellipse(*rx, *ry, tolerance)],
+        ShapeGeometry::Path { commands } => flatten_path(commands, tolerance),
+        ShapeGeometry::Line { .. } | ShapeGeometry::Text { .. } => Vec::new(),
+    }
+}
+
+/// Combine two sets of closed contours, each filled with the even-odd rule
+pub fn boolean_contours(a: &[Vec<Vec2>], b: &[Vec<Vec2>], op: BooleanOp) -> Vec<Vec<Vec2>> {
+    let a = oriented(a);
+    let b = oriented(b);
+    if op == BooleanOp::Exclude {
+        let mut contours = clip(&a, &b, BooleanOp::Subtract);
+        contours.extend(clip(&b, &a, BooleanOp::Subtract));
+        return contours;
+    }
+    clip(&a, &b, op)
+}
+
+/// Signed area of a closed contour; positive when the area is on the left of its edges
+pub fn contour_area(contour: &[Vec2]) -> f32 {
+    let n = contour.len();
+    (0..n).map(|i| contour[i].perp_dot(contour[(i + 1) % n])).sum::<f32>() / 2.0
+}
+
+/// Where a piece of one shape's outline lies relative to the other shape
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Side {
+    Inside,
+    Outside,
+    /// On the other outline, with the other shape's area on the same side
+    Same,
+    /// On the other outline, with the other shape's area on the opposite side
+    Opposite,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Edge {
+    from: Vec2,
+    to: Vec2,
+}
+
+impl Edge {
+    fn reversed(self) -> Self {
+        Edge { from: self.to, to: self.from }
+    }
+}
+
+/// Union, subtract or intersect two sets of oriented contours
+fn clip(a: &[Vec<Vec2>], b: &[Vec<Vec2>], op: BooleanOp) -> Vec<Vec<Vec2>> {
+    let (a_edges, b_edges) = split_edges(&contour_edges(a), &contour_edges(b));
+
+    // Shared boundary is taken from `a` only, so it isn't chained twice
+    let mut kept = Vec::new();
+    for &edge in &a_edges {
+        let keep = matches!(
+            (op, classify(edge, &b_edges, b)),
+            (BooleanOp::Union, Side::Outside | Side::Same)
+                | (BooleanOp::Intersect, Side::Inside | Side::Same)
+                | (BooleanOp::Subtract, Side::Outside | Side::Opposite)
+        );
+        if keep {
+            kept.push(edge);
+        }
+    }
+    for &edge in &b_edges {
+        match (op, classify(edge, &a_edges, a)) {
+            (BooleanOp::Union, Side::Outside) | (BooleanOp::Intersect, Side::Inside) => kept.push(edge),
+            // What's cut out of `a` is bounded by `b` turned inside out
+            (BooleanOp::Subtract, Side::Inside) => kept.push(edge.reversed()),
+            _ => {}
+        }
+    }
+
+    chain(&kept)
+}
+
+/// Drop degenerate contours and orient the rest: outlines with positive area, holes
+/// (inside an odd number of other contours) with negative area
+fn oriented(contours: &[Vec<Vec2>]) -> Vec<Vec<Vec2>> {
+    let contours: Vec<Vec<Vec2>> = contours
+        .iter()
+        .map(|contour| simplified(contour))
+        .filter(|contour| contour.len() >= 3 && contour_area(contour).abs() > POINT_EPSILON)
+        .collect();
+
+    contours
+        .iter()
+        .enumerate()
+        .map(|(i, contour)| {
+            let probe = interior_probe(contour);
+            let depth = contours
+                .iter()
+                .enumerate()
+                .filter(|&(j, other)| j != i && point_in_polygon(probe, other))
+                .count();
+            let is_hole = depth % 2 == 1;
+            let mut contour = contour.clone();
+            if (contour_area(&contour) > 0.0) == is_hole {
+                contour.reverse();
+            }
+            contour
+        })
+        .collect()
+}
+
+/// A point just inside `contour`, next to the middle of its longest edge
+fn interior_probe(contour: &[Vec2]) -> Vec2 {
+    let n = contour.len();
+    let i = (0..n)
+        .max_by(|&i, &j| {
+            let len = |k: usize| contour[k].distance_squared(contour[(k + 1) % n]);
+            len(i).total_cmp(&len(j))
+        })
+        .unwrap_or(0);
+    let (from, to) = (contour[i], contour[(i + 1) % n]);
+    let inward = (to - from).perp().normalize_or_zero() * contour_area(contour).signum();
+    (from + to) / 2.0 + inward * POINT_EPSILON * 10.0
+}
+
+fn contour_edges(contours: &[Vec<Vec2>]) -> Vec<Edge> {
+    contours
+        .iter()
+        .flat_map(|contour| {
+            let n = contour.len();
+            (0..n).map(move |i| Edge { from: contour[i], to: contour[(i + 1) % n] })
+        })
+        .collect()
+}
+
+/// Split every edge of `a` and `b` wherever it meets an edge of the other set
+/// A crossing is computed once and the same point inserted into both edges, so the
+/// pieces on either side share their endpoints exactly
+fn split_edges(a: &[Edge], b: &[Edge]) -> (Vec<Edge>, Vec<Edge>) {
+    let mut a_cuts: Vec<Vec<(f32, Vec2)>> = vec![Vec::new(); a.len()];
+    let mut b_cuts: Vec<Vec<(f32, Vec2)>> = vec![Vec::new(); b.len()];
+
+    for (i, ea) in a.iter().enumerate() {
+        for (j, eb) in b.iter().enumerate() {
+            for (t, u, point) in intersections(*ea, *eb) {
+                a_cuts[i].push((t, point));
+                b_cuts[j].push((u, point));
+            }
+        }
+    }
+
+    (apply_cuts(a, a_cuts), apply_cuts(b, b_cuts))
+}
+
+/// Points where two edges meet, with their parameter along each edge
+/// Collinear overlapping edges meet at each endpoint that lies within the other edge
+fn intersections(a: Edge, b: Edge) -> Vec<(f32, f32, Vec2)> {
+    let r = a.to - a.from;
+    let s = b.to - b.from;
+    let (r_len, s_len) = (r.length(), s.length());
+    if r_len <= POINT_EPSILON || s_len <= POINT_EPSILON {
+        return Vec::new();
+    }
+    let offset = b.from - a.from;
+    let denom = r.perp_dot(s);
+
+    if denom.abs() > 1e-6 * r_len * s_len {
+        let t = offset.perp_dot(s) / denom;
+        let u = offset.perp_dot(r) / denom;
+        let (t_slack, u_slack) = (POINT_EPSILON / r_len, POINT_EPSILON / s_len);
+        if t < -t_slack || t > 1.0 + t_slack || u < -u_slack || u > 1.0 + u_slack {
+            return Vec::new();
+        }
+        // Crossings at an existing vertex reuse that vertex
+        let point = if t <= t_slack {
+            a.from
+        } else if t >= 1.0 - t_slack {
+            a.to
+        } else if u <= u_slack {
+            b.from
+        } else if u >= 1.0 - u_slack {
+            b.to
+        } else {
+            a.from + r * t
+        };
+        return vec![(t.clamp(0.0, 1.0), u.clamp(0.0, 1.0), point)];
+    }
+
+    // Parallel: only collinear edges can overlap
+    if offset.perp_dot(r).abs() / r_len > POINT_EPSILON {
+        return Vec::new();
+    }
+    let along_a = |p: Vec2| (p - a.from).dot(r) / (r_len * r_len);
+    let along_b = |p: Vec2| (p - b.from).dot(s) / (s_len * s_len);
+    let mut points = Vec::new();
+    for p in [b.from, b.to] {
+        let t = along_a(p);
+        if (0.0..=1.0).contains(&t) {
+            points.push((t, along_b(p).clamp(0.0, 1.0), p));
+        }
+    }
+    for p in [a.from, a.to] {
+        let u = along_b(p);
+        if (0.0..=1.0).contains(&u) {
+            points.push((along_a(p).clamp(0.0, 1.0), u, p));
+        }
+    }
+    points
+}
+
+/// Split each edge at its cut points, dropping pieces too short to matter
+fn apply_cuts(edges: &[Edge], cuts: Vec<Vec<(f32, Vec2)>>) -> Vec<Edge> {
+    let mut pieces = Vec::with_capacity(edges.len());
+    for (edge, mut cuts) in edges.iter().zip(cuts) {
+        cuts.sort_by(|x, y| x.0.total_cmp(&y.0));
+        let mut from = edge.from;
+        for (_, point) in cuts {
+            if point.distance(from) > POINT_EPSILON && point.distance(edge.to) > POINT_EPSILON {
+                pieces.push(Edge { from, to: point });
+                from = point;
+            }
+        }
+        pieces.push(Edge { from, to: edge.to });
+    }
+    pieces
+}
+
+/// Where `edge` lies relative to the shape with split edges `other_edges` and
+/// oriented `other_contours`
+fn classify(edge: Edge, other_edges: &[Edge], other_contours: &[Vec<Vec2>]) -> Side {
+    let same = |p: Vec2, q: Vec2| p.distance(q) <= POINT_EPSILON;
+    for other in other_edges {
+        if same(edge.from, other.from) && same(edge.to, other.to) {
+            return Side::Same;
+        }
+        if same(edge.from, other.to) && same(edge.to, other.from) {
+            return Side::Opposite;
+        }
+    }
+
+    let midpoint = (edge.from + edge.to) / 2.0;
+    let crossings = other_contours.iter().filter(|contour| point_in_polygon(midpoint, contour)).count();
+    if crossings % 2 == 1 {
+        Side::Inside
+    } else {
+        Side::Outside
+    }
+}
+
+/// Link edges end to start into closed contours
+fn chain(edges: &[Edge]) -> Vec<Vec<Vec2>> {
+    let mut used = vec![false; edges.len()];
+    let mut contours = Vec::new();
+
+    for start in 0..edges.len() {
+        if used[start] {
+            continue;
+        }
+        used[start] = true;
+        let first = edges[start].from;
+        let mut contour = vec![first];
+        let mut end = edges[start].to;
+
+        while end.distance(first) > POINT_EPSILON {
+            let Some(next) = (0..edges.len()).find(|&i| !used[i] && edges[i].from.distance(end) <= POINT_EPSILON) else {
+                break;
+            };
+            used[next] = true;
+            contour.push(end);
+            end = edges[next].to;
+        }
+
+        let contour = simplified(&contour);
+        if contour.len() >= 3 && contour_area(&contour).abs() > POINT_EPSILON {
+            contours.push(contour);
+        }
+    }
+    contours
+}
+
+/// `contour` without repeated points or points in the middle of a straight run
+fn simplified(contour: &[Vec2]) -> Vec<Vec2> {
+    let mut points: Vec<Vec2> = Vec::with_capacity(contour.len());
+    for &p in contour {
+        if points.last().is_none_or(|last| last.distance(p) > POINT_EPSILON) {
+            points.push(p);
+        }
+    }
+    while points.len() > 1 && points[0].distance(points[points.len() - 1]) <= POINT_EPSILON {
+        points.pop();
+    }
+
+    // Drop vertices that sit on the line between their neighbors
+    let mut changed = true;
+    while changed && points.len() >= 3 {
+        changed = false;
+        let n = points.len();
+        for i in 0..n {
+            let (prev, point, next) = (points[(i + n - 1) % n], points[i], points[(i + 1) % n]);
+            let span = next - prev;
+            let off_line = (point - prev).perp_dot(span).abs() / span.length().max(f32::EPSILON);
+            if off_line <= POINT_EPSILON && (point - prev).dot(next - point) >= 0.0 {
+                points.remove(i);
+                changed = true;
+                break;
+            }
+        }
+    }
+    points
+}
+
+/// Polygon around an ellipse whose edges stay within `tolerance` of the curve
+fn flatten_ellipse(rx: f32, ry: f32, tolerance: f32) -> Vec<Vec2> {
+    let radius = rx.abs().max(ry.abs());
+    let segments = if radius > tolerance {
+        (std::f32::consts::PI / (1.0 - tolerance / radius).acos()).ceil() as usize
+    } else {
+        8
+    };
+    let segments = segments.clamp(8, 1024);
+    (0..segments)
+        .map(|i| {
+            let angle = i as f32 / segments as f32 * std::f32::consts::TAU;
+            Vec2::new(rx * angle.cos(), ry * angle.sin())
+        })
+        .collect()
+}
+
+/// Closed contours of a path's subpaths, curves split into segments within `tolerance`
+/// Open subpaths are closed, as they are when filled
+fn flatten_path(commands: &[PathCommand], tolerance: f32) -> Vec<Vec<Vec2>> {
+    let tolerance = tolerance.max(1e-3);
+    let mut contours = Vec::new();
+    let mut contour: Vec<Vec2> = Vec::new();
+    let mut current = Vec2::ZERO;
+
+    for command in commands {
+        match command {
+            PathCommand::MoveTo(p) => {
+                contours.push(std::mem::take(&mut contour));
+                contour.push(*p);
+                current = *p;
+            }
+            PathCommand::LineTo(p) => {
+                contour.push(*p);
+                current = *p;
+            }
+            PathCommand::QuadraticTo { control, to } => {
+                let bend = (current - *control * 2.0 + *to).length();
+                let segments = ((0.25 * bend / tolerance).sqrt().ceil() as usize).clamp(1, 256);
+                for i in 1..=segments {
+                    let t = i as f32 / segments as f32;
+                    let mt = 1.0 - t;
+                    contour.push(current * (mt * mt) + *control * (2.0 * mt * t) + *to * (t * t));
+                }
+                current = *to;
+            }
+            PathCommand::CubicTo { ctrl1, ctrl2, to } => {
+                push_cubic(&mut contour, current, *ctrl1, *ctrl2, *to, tolerance);
+                current = *to;
+            }
+            PathCommand::ArcTo { rx, ry, x_rotation, large_arc, sweep, to } => {
+                let cubics = arc_to_cubics(current, *rx, *ry, *x_rotation, *large_arc, *sweep, *to);
+                if cubics.is_empty() {
+                    contour.push(*to);
+                }
+                let mut from = current;
+                for (ctrl1, ctrl2, end) in cubics {
+                    push_cubic(&mut contour, from, ctrl1, ctrl2, end, tolerance);
+                    from = end;
+                }
+                current = *to;
+            }
+            PathCommand::Close => {
+                if let Some(&start) = contour.first() {
+                    current = start;
+                }
+                contours.push(std::mem::take(&mut contour));
+            }
+        }
+    }
+    contours.push(contour);
+    contours.retain(|contour| contour.len() >= 3);
+    contours
+}
+
+/// Append points along a cubic Bézier (excluding `from`), enough to stay within `tolerance`
+fn push_cubic(contour: &mut Vec<Vec2>, from: Vec2, ctrl1: Vec2, ctrl2: Vec2, to: Vec2, tolerance: f32) {
+    let bend = (from - ctrl1 * 2.0 + ctrl2).length().max((ctrl1 - ctrl2 * 2.0 + to).length());
+    let segments = ((0.75 * bend / tolerance).sqrt().ceil() as usize).clamp(1, 256);
+    for i in 1..=segments {
+        let t = i as f32 / segments as f32;
+        let mt = 1.0 - t;
+        contour.push(
+            from * (mt * mt * mt) + ctrl1 * (3.0 * mt * mt * t) + ctrl2 * (3.0 * mt * t * t) + to * (t * t * t),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::{Color, ShapeStyle};
+
+    fn rect(x: f32, y: f32, width: f32, height: f32) -> Vec<Vec<Vec2>> {
+        vec![vec![
+            Vec2::new(x, y),
+            Vec2::new(x + width, y),
+            Vec2::new(x + width, y + height),
+            Vec2::new(x, y + height),
+        ]]
+    }
+
+    fn total_area(contours: &[Vec<Vec2>]) -> f32 {
+        contours.iter().map(|contour| contour_area(contour)).sum()
+    }
+
+    /// Even-odd fill of the result, as the path is drawn
+    fn covers(contours: &[Vec<Vec2>], point: Vec2) -> bool {
+        contours.iter().filter(|contour| point_in_polygon(point, contour)).count() % 2 == 1
+    }
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 0.01, "{actual} != {expected}");
+    }
+
+    // Two 100×100 squares overlapping in a 50×50 corner
+    fn overlapping() -> (Vec<Vec<Vec2>>, Vec<Vec<Vec2>>) {
+        (rect(0.0, 0.0, 100.0, 100.0), rect(50.0, 50.0, 100.0, 100.0))
+    }
+
+    #[test]
+    fn test_union_of_overlapping_rectangles() {
+        let (a, b) = overlapping();
+        let result = boolean_contours(&a, &b, BooleanOp::Union);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].len(), 8);
+        assert_close(total_area(&result), 17_500.0);
+        assert!(covers(&result, Vec2::new(25.0, 25.0)));
+        assert!(covers(&result, Vec2::new(125.0, 125.0)));
+        assert!(!covers(&result, Vec2::new(125.0, 25.0)));
+    }
+
+    #[test]
+    fn test_subtract_overlapping_rectangles() {
+        let (a, b) = overlapping();
+        let result = boolean_contours(&a, &b, BooleanOp::Subtract);
+        assert_eq!(result.len(), 1);
+        // An L shape: the square with its overlapped corner cut away
+        assert_eq!(result[0].len(), 6);
+        assert_close(total_area(&result), 7_500.0);
+        assert!(covers(&result, Vec2::new(25.0, 75.0)));
+        assert!(!covers(&result, Vec2::new(75.0, 75.0)));
+        assert!(!covers(&result, Vec2::new(125.0, 125.0)));
+    }
+
+    #[test]
+    fn test_intersect_overlapping_rectangles() {
+        let (a, b) = overlapping();
+        let result = boolean_contours(&a, &b, BooleanOp::Intersect);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].len(), 4);
+        assert_close(total_area(&result), 2_500.0);
+        let bounds = BBox::from_points(&result[0]).unwrap();
+        assert!(bounds.min.distance(Vec2::new(50.0, 50.0)) < 1e-3);
+        assert!(bounds.max.distance(Vec2::new(100.0, 100.0)) < 1e-3);
+    }
+
+    #[test]
+    fn test_exclude_overlapping_rectangles() {
+        let (a, b) = overlapping();
+        let result = boolean_contours(&a, &b, BooleanOp::Exclude);
+        assert_eq!(result.len(), 2);
+        assert_close(total_area(&result), 15_000.0);
+        assert!(covers(&result, Vec2::new(25.0, 25.0)));
+        assert!(covers(&result, Vec2::new(125.0, 125.0)));
+        assert!(!covers(&result, Vec2::new(75.0, 75.0)));
+    }
+
+    #[test]
+    fn test_subtract_inner_rectangle_leaves_a_hole() {
+        let outer = rect(0.0, 0.0, 100.0, 100.0);
+        let inner = rect(25.0, 25.0, 50.0, 50.0);
+        let result = boolean_contours(&outer, &inner, BooleanOp::Subtract);
+        assert_eq!(result.len(), 2);
+        // The hole winds against the outline, so the path fills the same with either rule
+        let mut areas: Vec<f32> = result.iter().map(|contour| contour_area(contour)).collect();
+        areas.sort_by(f32::total_cmp);
+        assert_close(areas[0], -2_500.0);
+        assert_close(areas[1], 10_000.0);
+        assert!(!covers(&result, Vec2::new(50.0, 50.0)));
+        assert!(covers(&result, Vec2::new(10.0, 50.0)));
+
+        // Subtracting the outline from the hole leaves nothing
+        assert!(boolean_contours(&inner, &outer, BooleanOp::Subtract).is_empty());
+    }
+
+    #[test]
+    fn test_rectangles_sharing_an_edge() {
+        let left = rect(0.0, 0.0, 100.0, 100.0);
+        let right = rect(100.0, 0.0, 100.0, 100.0);
+
+        let union = boolean_contours(&left, &right, BooleanOp::Union);
+        assert_eq!(union.len(), 1);
+        assert_eq!(union[0].len(), 4);
+        assert_close(total_area(&union), 20_000.0);
+
+        assert!(boolean_contours(&left, &right, BooleanOp::Intersect).is_empty());
+        assert_close(total_area(&boolean_contours(&left, &right, BooleanOp::Subtract)), 10_000.0);
+    }
+
+    #[test]
+    fn test_disjoint_shapes() {
+        let a = rect(0.0, 0.0, 10.0, 10.0);
+        let b = rect(50.0, 50.0, 10.0, 10.0);
+        assert_eq!(boolean_contours(&a, &b, BooleanOp::Union).len(), 2);
+        assert!(boolean_contours(&a, &b, BooleanOp::Intersect).is_empty());
+        assert_close(total_area(&boolean_contours(&a, &b, BooleanOp::Subtract)), 100.0);
+    }
+
+    #[test]
+    fn test_geometry_union_intersect_subtract() {
+        let area = |geometry: &ShapeGeometry| match geometry {
+            ShapeGeometry::Path { commands } => total_area(&flatten_path(commands, BOOLEAN_TOLERANCE)),
+            other => panic!("expected a path, got {other:?}"),
+        };
+        let square = ShapeGeometry::rectangle(100.0, 100.0);
+        let (_, offset) = overlapping();
+        let shifted = ShapeGeometry::polygon(offset[0].clone());
+
+        assert_close(area(&union(&square, &shifted)), 17500.0);
+        assert_close(area(&intersect(&square, &shifted).unwrap()), 2500.0);
+        assert_close(area(&subtract(&square, &shifted)), 7500.0);
+
+        // Disjoint geometry has no intersection, and subtracting a cover leaves an empty path
+        let far = ShapeGeometry::polygon(rect(500.0, 500.0, 10.0, 10.0).remove(0));
+        assert_eq!(intersect(&square, &far), None);
+        let cover = ShapeGeometry::polygon(rect(-10.0, -10.0, 200.0, 200.0).remove(0));
+        assert_eq!(subtract(&square, &cover), ShapeGeometry::Path { commands: Vec::new() });
+    }
+
+    #[test]
+    fn test_combine_shapes_keeps_the_bottom_shape_and_style() {
+        let bottom = Shape::new(ShapeGeometry::rectangle(100.0, 100.0), ShapeStyle::fill_only(Color::rgb(1.0, 0.0, 0.0)))
+            .with_transform(Transform2D::from_position(Vec2::new(10.0, 20.0)));
+        let top = Shape::new(ShapeGeometry::rectangle(100.0, 100.0), ShapeStyle::fill_only(Color::rgb(0.0, 0.0, 1.0)))
+            .with_transform(Transform2D::from_position(Vec2::new(60.0, 70.0)));
+
+        let result = combine_shapes(&[&bottom, &top], BooleanOp::Union).unwrap();
+        assert_eq!(result.id, bottom.id);
+        assert_eq!(result.style, bottom.style);
+        assert_eq!(result.name, "Union");
+        assert!(matches!(result.geometry, ShapeGeometry::Path { .. }));
+        let bounds = result.world_bounds();
+        assert!(bounds.min.distance(Vec2::new(10.0, 20.0)) < 1e-3);
+        assert!(bounds.max.distance(Vec2::new(160.0, 170.0)) < 1e-3);
+
+        assert!(combine_shapes(&[&bottom], BooleanOp::Union).is_none());
+        let apart = top.clone().with_transform(Transform2D::from_position(Vec2::new(500.0, 0.0)));
+        assert!(combine_shapes(&[&bottom, &apart], BooleanOp::Intersect).is_none());
+    }
+
+    #[test]
+    fn test_curves_are_flattened_within_tolerance() {
+        let circle = Shape::new(ShapeGeometry::circle(50.0), ShapeStyle::default());
+        let contours = shape_contours(&circle, BOOLEAN_TOLERANCE);
+        assert_eq!(contours.len(), 1);
+        assert!(contours[0].iter().all(|p| (p.length() - 50.0).abs() < 1e-3));
+        // Edge midpoints sag inside the circle by no more than the tolerance
+        let ring = &contours[0];
+        for (i, &p) in ring.iter().enumerate() {
+            let mid = (p + ring[(i + 1) % ring.len()]) / 2.0;
+            assert!(50.0 - mid.length() <= BOOLEAN_TOLERANCE + 1e-3);
+        }
+
+        let rounded = Shape::new(ShapeGeometry::rounded_rectangle(100.0, 50.0, 10.0), ShapeStyle::default());
+        let contours = shape_contours(&rounded, BOOLEAN_TOLERANCE);
+        assert_eq!(contours.len(), 1);
+        assert!(contours[0].len() > 8);
+        let line = Shape::new(ShapeGeometry::line(Vec2::ZERO, Vec2::ONE), ShapeStyle::default());
+        assert!(shape_contours(&line, BOOLEAN_TOLERANCE).is_empty());
+    }
+}
@@ -0,0 +1,125 @@
+//! Shapes from SVG basic-shape elements
+//!
+//! Each function takes an element's attributes by name and builds the matching
+//! geometry, positioned by its transform:
+//! - `<rect>` -> Rectangle at (`x`, `y`)
+//! - `<ellipse>` / `<circle>` -> Ellipse centered on (`cx`, `cy`)
+//! - `<line>` -> Line between its endpoints
+//!
+//! Missing position attributes default to 0 as in SVG. A missing, unparsable,
+//! negative or zero size means the element renders nothing, so it yields None.
+//! Shapes get the default style; the importer applies presentation attributes.
+
+use std::collections::HashMap;
+
+use super::shape::{Shape, ShapeGeometry};
+use super::types::{ShapeStyle, Transform2D, Vec2};
+
+/// Parse a length, allowing a `px` suffix
+fn length(attrs: &HashMap<&str, &str>, name: &str) -> Option<f32> {
+    attrs.get(name)?.trim().trim_end_matches("px").parse().ok()
+}
+
+/// A coordinate that defaults to 0 when absent or unparsable
+fn coordinate(attrs: &HashMap<&str, &str>, name: &str) -> f32 {
+    length(attrs, name).unwrap_or(0.0)
+}
+
+/// A size that must be present and positive for the element to render
+fn size(attrs: &HashMap<&str, &str>, name: &str) -> Option<f32> {
+    length(attrs, name).filter(|value| *value > 0.0)
+}
+
+fn positioned(geometry: ShapeGeometry, position: Vec2) -> Shape {
+    Shape::new(geometry, ShapeStyle::default()).with_transform(Transform2D::from_position(position))
+}
+
+/// `<rect>`: `width` and `height` are required; `rx` (or `ry` alone) rounds the corners
+pub fn from_svg_rect(attrs: &HashMap<&str, &str>) -> Option<Shape> {
+    let width = size(attrs, "width")?;
+    let height = size(attrs, "height")?;
+    let corner_radius = length(attrs, "rx")
+        .or_else(|| length(attrs, "ry"))
+        .unwrap_or(0.0)
+        .clamp(0.0, width.min(height) / 2.0);
+
+    Some(positioned(
+        ShapeGeometry::rounded_rectangle(width, height, corner_radius),
+        Vec2::new(coordinate(attrs, "x"), coordinate(attrs, "y")),
+    ))
+}
+
+/// `<ellipse>`: `rx` and `ry` are required
+pub fn from_svg_ellipse(attrs: &HashMap<&str, &str>) -> Option<Shape> {
+    Some(positioned(
+        ShapeGeometry::ellipse(size(attrs, "rx")?, size(attrs, "ry")?),
+        Vec2::new(coordinate(attrs, "cx"), coordinate(attrs, "cy")),
+    ))
+}
+
+/// `<circle>`: `r` is required
+pub fn from_svg_circle(attrs: &HashMap<&str, &str>) -> Option<Shape> {
+    Some(positioned(
+        ShapeGeometry::circle(size(attrs, "r")?),
+        Vec2::new(coordinate(attrs, "cx"), coordinate(attrs, "cy")),
+    ))
+}
+
+/// `<line>`: endpoints default to the origin; a zero-length line is dropped
+pub fn from_svg_line(attrs: &HashMap<&str, &str>) -> Option<Shape> {
+    let start = Vec2::new(coordinate(attrs, "x1"), coordinate(attrs, "y1"));
+    let end = Vec2::new(coordinate(attrs, "x2"), coordinate(attrs, "y2"));
+    (start != end).then(|| positioned(ShapeGeometry::line(start, end), Vec2::ZERO))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attrs<'a>(pairs: &[(&'a str, &'a str)]) -> HashMap<&'a str, &'a str> {
+        pairs.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_rect_reads_position_size_and_corner_radius() {
+        let shape = from_svg_rect(&attrs(&[("x", "5"), ("y", "6px"), ("width", "20"), ("height", "10"), ("rx", "3")])).unwrap();
+        assert_eq!(shape.geometry, ShapeGeometry::rounded_rectangle(20.0, 10.0, 3.0));
+        assert_eq!(shape.transform.position, Vec2::new(5.0, 6.0));
+
+        // ry stands in for a missing rx, and radii are capped at half the short side
+        let shape = from_svg_rect(&attrs(&[("width", "20"), ("height", "10"), ("ry", "8")])).unwrap();
+        assert_eq!(shape.geometry, ShapeGeometry::rounded_rectangle(20.0, 10.0, 5.0));
+        assert_eq!(shape.transform.position, Vec2::ZERO);
+
+        assert!(from_svg_rect(&attrs(&[("width", "20")])).is_none());
+        assert!(from_svg_rect(&attrs(&[("width", "20"), ("height", "0")])).is_none());
+        assert!(from_svg_rect(&attrs(&[("width", "-1"), ("height", "5")])).is_none());
+        assert!(from_svg_rect(&attrs(&[("width", "auto"), ("height", "5")])).is_none());
+    }
+
+    #[test]
+    fn test_ellipse_and_circle_center_on_cx_cy() {
+        let shape = from_svg_ellipse(&attrs(&[("cx", "50"), ("cy", "40"), ("rx", "10"), ("ry", "5")])).unwrap();
+        assert_eq!(shape.geometry, ShapeGeometry::ellipse(10.0, 5.0));
+        assert_eq!(shape.transform.position, Vec2::new(50.0, 40.0));
+        assert!(from_svg_ellipse(&attrs(&[("rx", "10")])).is_none());
+
+        let shape = from_svg_circle(&attrs(&[("cx", "7"), ("r", "3")])).unwrap();
+        assert_eq!(shape.geometry, ShapeGeometry::circle(3.0));
+        assert_eq!(shape.transform.position, Vec2::new(7.0, 0.0));
+        assert!(from_svg_circle(&attrs(&[("cx", "7")])).is_none());
+    }
+
+    #[test]
+    fn test_line_uses_endpoints_in_place() {
+        let shape = from_svg_line(&attrs(&[("x1", "1"), ("y1", "2"), ("x2", "30"), ("y2", "4")])).unwrap();
+        assert_eq!(shape.geometry, ShapeGeometry::line(Vec2::new(1.0, 2.0), Vec2::new(30.0, 4.0)));
+        assert_eq!(shape.transform.position, Vec2::ZERO);
+
+        // Omitted endpoints sit at the origin
+        let shape = from_svg_line(&attrs(&[("x2", "10")])).unwrap();
+        assert_eq!(shape.geometry, ShapeGeometry::line(Vec2::ZERO, Vec2::new(10.0, 0.0)));
+
+        assert!(from_svg_line(&attrs(&[])).is_none());
+    }
+}
@@ -1,6 +1,9 @@
-use super::shape::Shape;
+use super::shape::{generate_shape_id, Shape};
+use super::spatial::SpatialIndex;
 use super::types::{BBox, ShapeStyle, Transform2D, Vec2};
+use super::vertex_edit::{edge_insertion, move_control, move_vertex, remove_vertex, ControlSlot};
 use super::ShapeGeometry;
+use crate::utils::{point_in_polygon, segment_bbox_distance, segment_distance};
 use std::collections::HashSet;
 
 /// Scene graph for managing shapes
@@ -15,6 +18,8 @@ pub struct SceneGraph {
     scene_dirty: bool,
     /// Currently selected shape IDs
     selection: Vec<u64>,
+    /// Grid index over world bounds for hit testing and rect queries
+    index: SpatialIndex,
 }
 
 impl Default for SceneGraph {
@@ -31,14 +36,25 @@ impl SceneGraph {
             dirty_shapes: HashSet::new(),
             scene_dirty: true,
             selection: Vec::new(),
+            index: SpatialIndex::default(),
         }
     }
 
+    /// Create a scene graph from an ordered list of shapes (first is bottom-most)
+    pub fn from_shapes(shapes: Vec<Shape>) -> Self {
+        let mut scene = Self::new();
+        for shape in shapes {
+            scene.add_shape(shape);
+        }
+        scene
+    }
+
     /// Add a shape to the scene and return its ID
     pub fn add_shape(&mut self, shape: Shape) -> u64 {
         let id = shape.id;
         self.dirty_shapes.insert(id);
         self.scene_dirty = true;
+        self.index.insert(id, shape.world_bounds());
         self.shapes.push(shape);
         id
     }
@@ -49,11 +65,33 @@ impl SceneGraph {
         self.add_shape(shape)
     }
 
+    /// Duplicate a shape in place: the copy keeps geometry, style and name, gets a
+    /// fresh ID and sits directly above the original in the z-order
+    pub fn clone_shape(&mut self, id: u64) -> Option<u64> {
+        let pos = self.shapes.iter().position(|s| s.id == id)?;
+        let mut copy = self.shapes[pos].clone();
+        copy.id = generate_shape_id();
+        copy.dirty = true;
+
+        let copy_id = copy.id;
+        self.dirty_shapes.insert(copy_id);
+        self.scene_dirty = true;
+        self.index.insert(copy_id, copy.world_bounds());
+        self.shapes.insert(pos + 1, copy);
+        Some(copy_id)
+    }
+
+    /// Duplicate each shape in `ids`, returning the IDs of the copies in the same order
+    pub fn clone_shapes(&mut self, ids: &[u64]) -> Vec<u64> {
+        ids.iter().filter_map(|&id| self.clone_shape(id)).collect()
+    }
+
     /// Remove a shape by ID
     pub fn remove_shape(&mut self, id: u64) -> Option<Shape> {
         if let Some(pos) = self.shapes.iter().position(|s| s.id == id) {
             self.dirty_shapes.remove(&id);
             self.selection.retain(|&sid| sid != id);
+            self.index.remove(id);
             self.scene_dirty = true;
             Some(self.shapes.remove(pos))
         } else {
@@ -72,6 +110,8 @@ impl SceneGraph {
         if let Some(s) = shape.as_ref() {
             self.dirty_shapes.insert(s.id);
             self.scene_dirty = true;
+            // Bounds are unknown until the caller is done; reindexed in clear_dirty/rebuild
+            self.index.mark_stale(s.id);
         }
         shape
     }
@@ -98,6 +138,7 @@ impl SceneGraph {
             shape.dirty = true;
             self.dirty_shapes.insert(id);
             self.scene_dirty = true;
+            self.index.insert(id, shape.world_bounds());
         }
     }
 
@@ -108,9 +149,46 @@ impl SceneGraph {
             shape.dirty = true;
             self.dirty_shapes.insert(id);
             self.scene_dirty = true;
+            self.index.insert(id, shape.world_bounds());
+        }
+    }
+
+    /// Show or hide a shape
+    pub fn set_visible(&mut self, id: u64, visible: bool) {
+        if let Some(shape) = self.shapes.iter_mut().find(|s| s.id == id) {
+            shape.visible = visible;
+            self.scene_dirty = true;
+        }
+    }
+
+    /// Lock or unlock a shape; locking also drops it from the selection
+    pub fn set_locked(&mut self, id: u64, locked: bool) {
+        if let Some(shape) = self.shapes.iter_mut().find(|s| s.id == id) {
+            shape.locked = locked;
+            self.scene_dirty = true;
+        }
+        if locked {
+            self.deselect(id);
+        }
+    }
+
+    /// Rename a shape; blank names are rejected and surrounding whitespace is trimmed
+    pub fn rename_shape(&mut self, id: u64, name: &str) -> bool {
+        let name = name.trim();
+        match self.shapes.iter_mut().find(|s| s.id == id) {
+            Some(shape) if !name.is_empty() => {
+                shape.name = name.to_string();
+                true
+            }
+            _ => false,
         }
     }
 
+    /// Shapes to draw, in paint order (hidden shapes are skipped)
+    pub fn visible_shapes(&self) -> Vec<Shape> {
+        self.shapes.iter().filter(|shape| shape.visible).cloned().collect()
+    }
+
     /// Update a shape's geometry
     pub fn set_geometry(&mut self, id: u64, geometry: ShapeGeometry) {
         if let Some(shape) = self.shapes.iter_mut().find(|s| s.id == id) {
@@ -118,9 +196,66 @@ impl SceneGraph {
             shape.dirty = true;
             self.dirty_shapes.insert(id);
             self.scene_dirty = true;
+            self.index.insert(id, shape.world_bounds());
+        }
+    }
+
+    /// Move one polygon or path vertex to a world-space position
+    /// Returns false for other shapes and out-of-range indices
+    pub fn move_vertex(&mut self, id: u64, index: usize, world: Vec2) -> bool {
+        let Some(shape) = self.get_shape(id) else {
+            return false;
+        };
+        let local = shape.transform.inverse_transform_point(world);
+        match move_vertex(&shape.geometry, index, local) {
+            Some(geometry) => {
+                self.set_geometry(id, geometry);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Move a path's curve control point to a world-space position
+    pub fn move_control_point(&mut self, id: u64, command: usize, slot: ControlSlot, world: Vec2) -> bool {
+        let Some(shape) = self.get_shape(id) else {
+            return false;
+        };
+        let local = shape.transform.inverse_transform_point(world);
+        match move_control(&shape.geometry, command, slot, local) {
+            Some(geometry) => {
+                self.set_geometry(id, geometry);
+                true
+            }
+            None => false,
         }
     }
 
+    /// Add a polygon vertex on the edge nearest a world-space point, if one is within
+    /// `tolerance` world units; returns the new vertex's index
+    pub fn insert_polygon_vertex(&mut self, id: u64, world: Vec2, tolerance: f32) -> Option<usize> {
+        let shape = self.get_shape(id)?;
+        let ShapeGeometry::Polygon { points } = &shape.geometry else {
+            return None;
+        };
+        let (index, on_edge) = edge_insertion(&shape.world_vertices(), world, tolerance)?;
+
+        let mut points = points.clone();
+        points.insert(index, shape.transform.inverse_transform_point(on_edge));
+        self.set_geometry(id, ShapeGeometry::Polygon { points });
+        Some(index)
+    }
+
+    /// Remove one polygon vertex
+    /// Returns false when out of range or the polygon is down to `MIN_POLYGON_VERTICES`
+    pub fn remove_polygon_vertex(&mut self, id: u64, index: usize) -> bool {
+        let Some(geometry) = self.get_shape(id).and_then(|shape| remove_vertex(&shape.geometry, index)) else {
+            return false;
+        };
+        self.set_geometry(id, geometry);
+        true
+    }
+
     /// Check if the scene needs re-rendering
     pub fn is_dirty(&self) -> bool {
         self.scene_dirty
@@ -133,6 +268,7 @@ impl SceneGraph {
 
     /// Clear dirty flags after rendering
     pub fn clear_dirty(&mut self) {
+        self.reindex_stale();
         self.dirty_shapes.clear();
         self.scene_dirty = false;
         for shape in &mut self.shapes {
@@ -140,6 +276,25 @@ impl SceneGraph {
         }
     }
 
+    /// Re-insert shapes that were mutated through `get_shape_mut`
+    fn reindex_stale(&mut self) {
+        let stale: Vec<u64> = self.index.stale_ids().collect();
+        for id in stale {
+            if let Some(shape) = self.shapes.iter().find(|s| s.id == id) {
+                self.index.insert(id, shape.world_bounds());
+            }
+        }
+    }
+
+    /// Rebuild the spatial index from scratch
+    /// Escape hatch for callers that mutated shapes in ways the index can't see
+    pub fn rebuild(&mut self) {
+        self.index.clear();
+        for shape in &self.shapes {
+            self.index.insert(shape.id, shape.world_bounds());
+        }
+    }
+
     /// Mark entire scene as dirty (force full re-render)
     pub fn mark_dirty(&mut self) {
         self.scene_dirty = true;
@@ -156,9 +311,9 @@ impl SceneGraph {
         &self.selection
     }
 
-    /// Select a shape by ID
+    /// Select a shape by ID (locked shapes are never selected)
     pub fn select(&mut self, id: u64) {
-        if self.get_shape(id).is_some() && !self.selection.contains(&id) {
+        if self.get_shape(id).is_some_and(|shape| !shape.locked) && !self.selection.contains(&id) {
             self.selection.push(id);
         }
     }
@@ -170,6 +325,17 @@ impl SceneGraph {
         }
     }
 
+    /// Replace the current selection with the given shape IDs
+    pub fn set_selection(&mut self, ids: &[u64]) {
+        self.selection.clear();
+        self.select_multiple(ids);
+    }
+
+    /// IDs of every shape that can be selected (visible and unlocked), in draw order
+    pub fn selectable_ids(&self) -> Vec<u64> {
+        self.shapes.iter().filter(|shape| shape.is_interactive()).map(|shape| shape.id).collect()
+    }
+
     /// Deselect a shape
     pub fn deselect(&mut self, id: u64) {
         self.selection.retain(|&sid| sid != id);
@@ -207,28 +373,112 @@ impl SceneGraph {
         Some(bounds)
     }
 
+    /// Union of the world bounds of every visible shape
+    pub fn content_bounds(&self) -> Option<BBox> {
+        self.shapes
+            .iter()
+            .filter(|s| s.visible)
+            .map(Shape::world_bounds)
+            .reduce(|a, b| a.union(&b))
+    }
+
     // === Hit Testing ===
 
     /// Find shape at point (returns topmost shape)
     pub fn hit_test(&self, point: Vec2) -> Option<u64> {
-        // Iterate in reverse to get topmost shape first
-        for shape in self.shapes.iter().rev() {
-            if shape.contains_point(point) {
-                return Some(shape.id);
-            }
+        let candidates = self.index.query_point(point);
+        if candidates.is_empty() {
+            return None;
         }
-        None
+
+        // Iterate in reverse to get topmost shape first
+        self.shapes
+            .iter()
+            .rev()
+            .find(|shape| shape.is_interactive() && candidates.contains(&shape.id) && shape.contains_point(point))
+            .map(|shape| shape.id)
     }
 
-    /// Find all shapes intersecting a rectangle
+    /// Topmost visible shape at point, if it is locked
+    /// Lets the UI explain why a click on a locked shape selected nothing
+    pub fn locked_shape_at(&self, point: Vec2) -> Option<u64> {
+        self.shapes
+            .iter()
+            .rev()
+            .find(|shape| shape.visible && shape.contains_point(point))
+            .filter(|shape| shape.locked)
+            .map(|shape| shape.id)
+    }
+
+    /// Find all hittable shapes intersecting a rectangle, in paint order
     pub fn query_rect(&self, rect: &BBox) -> Vec<u64> {
+        let candidates = self.index.query_rect(rect);
+        self.shapes
+            .iter()
+            .filter(|shape| {
+                shape.is_interactive() && candidates.contains(&shape.id) && shape.world_bounds().intersects(rect)
+            })
+            .map(|shape| shape.id)
+            .collect()
+    }
+
+    /// Find all hittable shapes the polyline `path` passes within `tolerance` of, in paint order
+    /// A shape is hit when any path segment comes near its world bounds; polygons, stars and
+    /// regular polygons are then refined against their outline, also counting path points that fall inside them.
+    /// A single point acts as a zero-length segment. This backs the lasso tool
+    pub fn query_path(&self, path: &[Vec2], tolerance: f32) -> Vec<u64> {
+        let Some(path_bounds) = BBox::from_points(path) else {
+            return Vec::new();
+        };
+        let segments: Vec<(Vec2, Vec2)> = if path.len() == 1 {
+            vec![(path[0], path[0])]
+        } else {
+            path.windows(2).map(|pair| (pair[0], pair[1])).collect()
+        };
+
+        let candidates = self.index.query_rect(&path_bounds.expand(tolerance));
         self.shapes
             .iter()
-            .filter(|shape| shape.world_bounds().intersects(rect))
+            .filter(|shape| shape.is_interactive() && candidates.contains(&shape.id))
+            .filter(|shape| {
+                let bounds = shape.world_bounds();
+                segments.iter().any(|&(a, b)| segment_bbox_distance(a, b, &bounds) <= tolerance)
+            })
+            .filter(|shape| {
+                if shape.geometry.ring_points().is_none() {
+                    return true;
+                }
+                let outline = shape.world_vertices();
+                let edge_count = outline.len();
+                path.iter().any(|p| point_in_polygon(*p, &outline))
+                    || segments.iter().any(|&(a, b)| {
+                        (0..edge_count).any(|i| {
+                            segment_distance(a, b, outline[i], outline[(i + 1) % edge_count]) <= tolerance
+                        })
+                    })
+            })
             .map(|shape| shape.id)
             .collect()
     }
 
+    /// Shapes that could produce a snap for a box at `proposed`, in paint order
+    /// Alignment guides can come from anywhere along each axis, so this keeps the
+    /// shapes overlapping the box's horizontal or vertical band (grown by `threshold`)
+    /// Hidden shapes aren't snap targets; locked ones are, since they're still drawn
+    pub fn snap_candidates(&self, proposed: &BBox, threshold: f32) -> Vec<Shape> {
+        let grown = proposed.expand(threshold);
+        let vertical_band = BBox::new(Vec2::new(grown.min.x, f32::MIN), Vec2::new(grown.max.x, f32::MAX));
+        let horizontal_band = BBox::new(Vec2::new(f32::MIN, grown.min.y), Vec2::new(f32::MAX, grown.max.y));
+
+        let mut candidates = self.index.query_rect(&vertical_band);
+        candidates.extend(self.index.query_rect(&horizontal_band));
+        self.shapes
+            .iter()
+            .filter(|shape| shape.visible && candidates.contains(&shape.id))
+            .cloned()
+            .collect()
+    }
+
     // === Z-Order Management ===
 
     /// Move shape to front (top of z-order)
@@ -269,6 +519,18 @@ impl SceneGraph {
         }
     }
 
+    /// Move shape to `new_index` in the z-order (clamped to the last position)
+    pub fn reorder(&mut self, id: u64, new_index: usize) {
+        if let Some(pos) = self.shapes.iter().position(|s| s.id == id) {
+            let shape = self.shapes.remove(pos);
+            let new_index = new_index.min(self.shapes.len());
+            self.shapes.insert(new_index, shape);
+            if new_index != pos {
+                self.scene_dirty = true;
+            }
+        }
+    }
+
     // === Bulk Operations ===
 
     /// Transform all selected shapes
@@ -276,9 +538,50 @@ impl SceneGraph {
         for &id in &self.selection.clone() {
             if let Some(shape) = self.shapes.iter_mut().find(|s| s.id == id) {
                 shape.transform.position += delta_position;
-                shape.transform.scale *= delta_scale;
+                shape.scale_by(delta_scale);
+                shape.dirty = true;
+                self.dirty_shapes.insert(id);
+                self.index.insert(id, shape.world_bounds());
+            }
+        }
+        if !self.selection.is_empty() {
+            self.scene_dirty = true;
+        }
+    }
+
+    /// Rotate all selected shapes by `rotation_delta` (radians) and scale them by
+    /// `scale_factor` about `anchor` in world space, so the selection turns and grows as one
+    /// Each shape's own scale changes along its local axes, as in `scale_selection_about`
+    pub fn transform_selection_relative(&mut self, anchor: Vec2, rotation_delta: f32, scale_factor: Vec2) {
+        let rotation = Vec2::from_angle(rotation_delta);
+        for &id in &self.selection.clone() {
+            if let Some(shape) = self.shapes.iter_mut().find(|s| s.id == id) {
+                // The shape's own anchor is its fixed point, so move that and turn around it
+                let origin = shape.transform.position + shape.transform.anchor;
+                let moved = anchor + rotation.rotate((origin - anchor) * scale_factor);
+                shape.transform.position = moved - shape.transform.anchor;
+                shape.transform.rotation += rotation_delta;
+                shape.scale_by(scale_factor);
+                shape.dirty = true;
+                self.dirty_shapes.insert(id);
+                self.index.insert(id, shape.world_bounds());
+            }
+        }
+        if !self.selection.is_empty() {
+            self.scene_dirty = true;
+        }
+    }
+
+    /// Scale all selected shapes about `pivot`, then translate them by `translation`
+    /// Positions are scaled relative to the pivot so the selection keeps its layout
+    pub fn scale_selection_about(&mut self, pivot: Vec2, translation: Vec2, scale: Vec2) {
+        for &id in &self.selection.clone() {
+            if let Some(shape) = self.shapes.iter_mut().find(|s| s.id == id) {
+                shape.transform.position = pivot + translation + (shape.transform.position - pivot) * scale;
+                shape.scale_by(scale);
                 shape.dirty = true;
                 self.dirty_shapes.insert(id);
+                self.index.insert(id, shape.world_bounds());
             }
         }
         if !self.selection.is_empty() {
@@ -298,7 +601,7 @@ impl SceneGraph {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::scene::Color;
+    use crate::scene::{Color, PathCommand};
 
     fn create_test_shape() -> Shape {
         Shape::new(
@@ -356,6 +659,21 @@ mod tests {
         assert!(scene.is_selected(id2));
     }
 
+    #[test]
+    fn test_set_selection_replaces_previous() {
+        let shape1 = create_test_shape();
+        let shape2 = create_test_shape();
+        let id1 = shape1.id;
+        let id2 = shape2.id;
+        let mut scene = SceneGraph::from_shapes(vec![shape1, shape2]);
+        assert_eq!(scene.len(), 2);
+
+        scene.set_selection(&[id1]);
+        scene.set_selection(&[id2, 9999]);
+        // Unknown IDs are ignored and the old selection is gone
+        assert_eq!(scene.selection(), &[id2]);
+    }
+
     #[test]
     fn test_dirty_tracking() {
         let mut scene = SceneGraph::new();
@@ -391,4 +709,428 @@ mod tests {
         scene.bring_to_front(id2);
         assert_eq!(scene.shapes()[1].id, id2);
     }
+
+    #[test]
+    fn test_hit_test_skips_hidden_and_locked() {
+        let mut scene = SceneGraph::new();
+        let bottom = create_test_shape();
+        let top = create_test_shape();
+        let (bottom_id, top_id) = (bottom.id, top.id);
+        scene.add_shape(bottom);
+        scene.add_shape(top);
+        let point = Vec2::new(50.0, 25.0);
+        let everything = BBox::new(Vec2::new(-10.0, -10.0), Vec2::new(200.0, 200.0));
+
+        assert_eq!(scene.hit_test(point), Some(top_id));
+
+        scene.set_visible(top_id, false);
+        assert_eq!(scene.hit_test(point), Some(bottom_id));
+
+        scene.set_locked(bottom_id, true);
+        assert_eq!(scene.hit_test(point), None);
+        assert!(scene.query_rect(&everything).is_empty());
+
+        scene.set_visible(top_id, true);
+        assert_eq!(scene.hit_test(point), Some(top_id));
+        assert_eq!(scene.query_rect(&everything), vec![top_id]);
+    }
+
+    #[test]
+    fn test_locked_shapes_cannot_be_selected() {
+        let shapes: Vec<Shape> = (0..2).map(|_| create_test_shape()).collect();
+        let ids: Vec<u64> = shapes.iter().map(|s| s.id).collect();
+        let mut scene = SceneGraph::from_shapes(shapes);
+        let point = Vec2::new(50.0, 25.0);
+
+        scene.set_selection(&ids);
+        scene.set_locked(ids[1], true);
+        assert_eq!(scene.selection(), &[ids[0]]);
+        assert_eq!(scene.locked_shape_at(point), Some(ids[1]));
+
+        scene.set_selection(&ids);
+        assert_eq!(scene.selection(), &[ids[0]]);
+
+        scene.set_locked(ids[1], false);
+        scene.select(ids[1]);
+        assert_eq!(scene.selection(), &ids[..]);
+        assert_eq!(scene.locked_shape_at(point), None);
+    }
+
+    #[test]
+    fn test_select_all_covers_every_selectable_shape() {
+        let shapes: Vec<Shape> = (0..4)
+            .map(|i| create_test_shape().with_transform(Transform2D::from_position(Vec2::new(i as f32 * 40.0, i as f32 * 10.0))))
+            .collect();
+        let ids: Vec<u64> = shapes.iter().map(|s| s.id).collect();
+        let mut scene = SceneGraph::from_shapes(shapes);
+        scene.set_locked(ids[3], true);
+        assert_eq!(scene.selectable_ids(), &ids[..3]);
+
+        scene.set_locked(ids[3], false);
+        scene.set_selection(&scene.selectable_ids());
+        assert_eq!(scene.selection(), &ids[..]);
+        let bounds = scene.selection_bounds().unwrap();
+        assert_eq!(bounds, scene.content_bounds().unwrap());
+
+        // An identity scale with no translation leaves every shape as it was
+        let before = scene.shapes().to_vec();
+        scene.scale_selection_about(bounds.min, Vec2::ZERO, Vec2::ONE);
+        for (shape, original) in scene.shapes().iter().zip(&before) {
+            assert_eq!((shape.transform, &shape.geometry), (original.transform, &original.geometry));
+        }
+        assert_eq!(scene.selection_bounds(), Some(bounds));
+    }
+
+    #[test]
+    fn test_move_vertex() {
+        let shape = create_test_shape().with_transform(Transform2D::from_position(Vec2::new(10.0, 20.0)));
+        let id = shape.id;
+        let mut scene = SceneGraph::from_shapes(vec![shape]);
+
+        assert!(scene.move_vertex(id, 2, Vec2::new(60.0, 220.0)));
+        let shape = scene.get_shape(id).unwrap();
+        assert_eq!(shape.geometry.vertices()[2], Vec2::new(50.0, 200.0));
+        assert_eq!(shape.world_vertices()[2], Vec2::new(60.0, 220.0));
+        // The spatial index follows the new bounds
+        assert_eq!(scene.hit_test(Vec2::new(55.0, 210.0)), Some(id));
+
+        assert!(!scene.move_vertex(id, 3, Vec2::ZERO));
+        let rect = Shape::new(ShapeGeometry::rectangle(10.0, 10.0), ShapeStyle::default());
+        let rect_id = rect.id;
+        scene.add_shape(rect);
+        assert!(!scene.move_vertex(rect_id, 0, Vec2::ZERO));
+    }
+
+    #[test]
+    fn test_insert_and_remove_polygon_vertex() {
+        let mut shape = create_test_shape().with_transform(Transform2D::from_position(Vec2::new(10.0, 20.0)));
+        shape.transform.scale = Vec2::new(2.0, 1.0);
+        let id = shape.id;
+        let mut scene = SceneGraph::from_shapes(vec![shape]);
+
+        // The top edge runs (10, 20) -> (210, 20) in world space
+        assert_eq!(scene.insert_polygon_vertex(id, Vec2::new(110.0, 23.0), 4.0), Some(1));
+        let shape = scene.get_shape(id).unwrap();
+        assert_eq!(shape.geometry.vertices()[1], Vec2::new(50.0, 0.0));
+        assert_eq!(shape.world_vertices()[1], Vec2::new(110.0, 20.0));
+        assert!(shape.dirty);
+        assert_eq!(scene.insert_polygon_vertex(id, Vec2::new(110.0, 30.0), 4.0), None);
+
+        assert!(scene.remove_polygon_vertex(id, 1));
+        assert_eq!(scene.get_shape(id).unwrap().geometry.vertices().len(), 3);
+        // Down to a triangle
+        assert!(!scene.remove_polygon_vertex(id, 0));
+    }
+
+    #[test]
+    fn test_move_path_vertex_and_control_point() {
+        let path = Shape::new(
+            ShapeGeometry::Path {
+                commands: vec![
+                    PathCommand::MoveTo(Vec2::new(0.0, 0.0)),
+                    PathCommand::CubicTo { ctrl1: Vec2::new(0.0, 50.0), ctrl2: Vec2::new(100.0, 50.0), to: Vec2::new(100.0, 0.0) },
+                ],
+            },
+            ShapeStyle::default(),
+        )
+        .with_transform(Transform2D::from_position(Vec2::new(10.0, 10.0)));
+        let id = path.id;
+        let mut scene = SceneGraph::from_shapes(vec![path]);
+
+        assert!(scene.move_vertex(id, 1, Vec2::new(110.0, 30.0)));
+        assert!(scene.move_control_point(id, 1, ControlSlot::Cubic1, Vec2::new(10.0, 110.0)));
+        assert_eq!(
+            scene.get_shape(id).unwrap().geometry,
+            ShapeGeometry::Path {
+                commands: vec![
+                    PathCommand::MoveTo(Vec2::new(0.0, 0.0)),
+                    PathCommand::CubicTo { ctrl1: Vec2::new(0.0, 100.0), ctrl2: Vec2::new(100.0, 70.0), to: Vec2::new(100.0, 20.0) },
+                ],
+            }
+        );
+        assert!(!scene.move_control_point(id, 1, ControlSlot::Quadratic, Vec2::ZERO));
+        assert!(!scene.move_vertex(id, 2, Vec2::ZERO));
+    }
+
+    #[test]
+    fn test_visible_shapes_filters_hidden() {
+        let shapes: Vec<Shape> = (0..3).map(|_| create_test_shape()).collect();
+        let ids: Vec<u64> = shapes.iter().map(|s| s.id).collect();
+        let mut scene = SceneGraph::from_shapes(shapes);
+
+        scene.set_visible(ids[1], false);
+        scene.set_locked(ids[2], true);
+        let drawn: Vec<u64> = scene.visible_shapes().iter().map(|s| s.id).collect();
+        assert_eq!(drawn, vec![ids[0], ids[2]]);
+    }
+
+    #[test]
+    fn test_content_bounds_covers_visible_shapes() {
+        let mut scene = SceneGraph::new();
+        assert_eq!(scene.content_bounds(), None);
+
+        let a = create_test_shape();
+        let b = create_test_shape().with_transform(Transform2D::from_position(Vec2::new(200.0, 50.0)));
+        let hidden = create_test_shape().with_transform(Transform2D::from_position(Vec2::new(-500.0, 0.0)));
+        let hidden_id = hidden.id;
+        for shape in [a, b, hidden] {
+            scene.add_shape(shape);
+        }
+        scene.set_visible(hidden_id, false);
+
+        let bounds = scene.content_bounds().unwrap();
+        assert_eq!(bounds.min, Vec2::new(0.0, 0.0));
+        assert_eq!(bounds.max, Vec2::new(300.0, 150.0));
+    }
+
+    #[test]
+    fn test_query_path_uses_bounds_then_polygon_outline() {
+        let peak = create_test_shape();
+        // Right triangle at (200,0) with its hypotenuse from (300,0) to (200,100);
+        // the bounds' lower-right corner lies outside the triangle
+        let triangle = Shape::new(
+            ShapeGeometry::polygon(vec![Vec2::ZERO, Vec2::new(100.0, 0.0), Vec2::new(0.0, 100.0)]),
+            ShapeStyle::default(),
+        )
+        .with_transform(Transform2D::from_position(Vec2::new(200.0, 0.0)));
+        let (peak_id, triangle_id) = (peak.id, triangle.id);
+        let scene = SceneGraph::from_shapes(vec![peak, triangle]);
+
+        assert!(scene.query_path(&[], 5.0).is_empty());
+
+        // Passing just above the first triangle counts within tolerance only
+        let above = [Vec2::new(-20.0, -3.0), Vec2::new(120.0, -3.0)];
+        assert_eq!(scene.query_path(&above, 5.0), vec![peak_id]);
+        assert!(scene.query_path(&above, 2.0).is_empty());
+
+        // Inside the triangle's bounds but about 7 units off its hypotenuse
+        let corner = [Vec2::new(255.0, 55.0), Vec2::new(290.0, 90.0)];
+        assert!(scene.query_path(&corner, 2.0).is_empty());
+        assert_eq!(scene.query_path(&corner, 10.0), vec![triangle_id]);
+
+        // A single point inside the triangle, and a path touching both shapes
+        assert_eq!(scene.query_path(&[Vec2::new(210.0, 10.0)], 0.0), vec![triangle_id]);
+        let across = [Vec2::new(50.0, 25.0), Vec2::new(220.0, 25.0)];
+        assert_eq!(scene.query_path(&across, 0.0), vec![peak_id, triangle_id]);
+    }
+
+    #[test]
+    fn test_rename_shape() {
+        let shape = create_test_shape();
+        let id = shape.id;
+        let mut scene = SceneGraph::from_shapes(vec![shape]);
+
+        assert!(scene.rename_shape(id, " Logo "));
+        assert_eq!(scene.get_shape(id).unwrap().name, "Logo");
+        assert!(!scene.rename_shape(id, "  "));
+        assert_eq!(scene.get_shape(id).unwrap().name, "Logo");
+    }
+
+    #[test]
+    fn test_reorder() {
+        let shapes: Vec<Shape> = (0..4).map(|_| create_test_shape()).collect();
+        let ids: Vec<u64> = shapes.iter().map(|s| s.id).collect();
+        let mut scene = SceneGraph::from_shapes(shapes);
+
+        scene.reorder(ids[0], 2);
+        let order: Vec<u64> = scene.shapes().iter().map(|s| s.id).collect();
+        assert_eq!(order, vec![ids[1], ids[2], ids[0], ids[3]]);
+
+        // Out-of-range indices clamp to the front of the z-order
+        scene.reorder(ids[1], 99);
+        assert_eq!(scene.shapes()[3].id, ids[1]);
+    }
+
+    /// Small deterministic PRNG so the randomized tests are reproducible
+    struct Lcg(u64);
+
+    impl Lcg {
+        fn next_f32(&mut self, max: f32) -> f32 {
+            self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (self.0 >> 40) as f32 / (1u64 << 24) as f32 * max
+        }
+    }
+
+    fn random_scene(rng: &mut Lcg, count: usize) -> SceneGraph {
+        let shapes = (0..count)
+            .map(|i| {
+                let size = Vec2::new(rng.next_f32(120.0) + 1.0, rng.next_f32(120.0) + 1.0);
+                let geometry = match i % 3 {
+                    0 => ShapeGeometry::rectangle(size.x, size.y),
+                    1 => ShapeGeometry::ellipse(size.x / 2.0, size.y / 2.0),
+                    _ => ShapeGeometry::polygon(vec![Vec2::ZERO, Vec2::new(size.x, 0.0), size]),
+                };
+                let position = Vec2::new(rng.next_f32(1000.0) - 100.0, rng.next_f32(800.0) - 100.0);
+                Shape::new(geometry, ShapeStyle::default()).with_transform(Transform2D::from_position(position))
+            })
+            .collect();
+        SceneGraph::from_shapes(shapes)
+    }
+
+    fn brute_hit_test(scene: &SceneGraph, point: Vec2) -> Option<u64> {
+        scene.shapes().iter().rev().find(|s| s.contains_point(point)).map(|s| s.id)
+    }
+
+    fn brute_query_rect(scene: &SceneGraph, rect: &BBox) -> Vec<u64> {
+        scene
+            .shapes()
+            .iter()
+            .filter(|s| s.world_bounds().intersects(rect))
+            .map(|s| s.id)
+            .collect()
+    }
+
+    fn assert_matches_brute_force(scene: &SceneGraph, rng: &mut Lcg) {
+        for _ in 0..200 {
+            let point = Vec2::new(rng.next_f32(1000.0) - 100.0, rng.next_f32(800.0) - 100.0);
+            assert_eq!(scene.hit_test(point), brute_hit_test(scene, point));
+
+            let size = Vec2::new(rng.next_f32(300.0), rng.next_f32(300.0));
+            let rect = BBox::new(point, point + size);
+            assert_eq!(scene.query_rect(&rect), brute_query_rect(scene, &rect));
+        }
+    }
+
+    #[test]
+    fn test_index_matches_brute_force() {
+        let mut rng = Lcg(42);
+        let mut scene = random_scene(&mut rng, 500);
+        assert_matches_brute_force(&scene, &mut rng);
+
+        // Incremental updates: move, reshape, remove, and mutate in place
+        let ids: Vec<u64> = scene.shapes().iter().map(|s| s.id).collect();
+        for (i, &id) in ids.iter().enumerate().step_by(7) {
+            match i % 4 {
+                0 => scene.set_transform(id, Transform2D::from_position(Vec2::new(rng.next_f32(900.0), rng.next_f32(700.0)))),
+                1 => scene.set_geometry(id, ShapeGeometry::rectangle(rng.next_f32(300.0) + 1.0, 5.0)),
+                2 => {
+                    scene.remove_shape(id);
+                }
+                _ => scene.get_shape_mut(id).unwrap().transform.position += Vec2::new(250.0, -40.0),
+            }
+        }
+        scene.select_multiple(&ids[1..40]);
+        scene.scale_selection_about(Vec2::new(200.0, 200.0), Vec2::new(15.0, 0.0), Vec2::new(1.5, 0.75));
+        assert_matches_brute_force(&scene, &mut rng);
+
+        scene.clear_dirty();
+        assert_matches_brute_force(&scene, &mut rng);
+
+        scene.rebuild();
+        assert_matches_brute_force(&scene, &mut rng);
+    }
+
+    #[test]
+    fn test_scale_selection_about_pivot() {
+        let mut scene = SceneGraph::new();
+        let shape = create_test_shape().with_transform(Transform2D::from_position(Vec2::new(20.0, 30.0)));
+        let id = shape.id;
+        scene.add_shape(shape);
+        scene.select(id);
+
+        scene.scale_selection_about(Vec2::new(10.0, 10.0), Vec2::new(5.0, 0.0), Vec2::new(2.0, 0.5));
+
+        let transform = scene.get_shape(id).unwrap().transform;
+        assert_eq!(transform.position, Vec2::new(35.0, 20.0));
+        assert_eq!(transform.scale, Vec2::new(2.0, 0.5));
+    }
+
+    #[test]
+    fn test_rotate_selection_a_quarter_turn_about_anchor() {
+        let mut scene = SceneGraph::new();
+        let right = create_test_shape().with_transform(Transform2D::from_position(Vec2::new(110.0, 100.0)));
+        let below = create_test_shape().with_transform(
+            Transform2D::from_position(Vec2::new(100.0, 150.0)).with_anchor(Vec2::new(50.0, 50.0)),
+        );
+        let ids = [right.id, below.id];
+        let corners: Vec<Vec2> = [&right, &below].iter().map(|s| s.transform.transform_point(Vec2::new(100.0, 0.0))).collect();
+        for shape in [right, below] {
+            scene.add_shape(shape);
+        }
+        scene.set_selection(&ids);
+
+        let anchor = Vec2::new(100.0, 100.0);
+        scene.transform_selection_relative(anchor, std::f32::consts::FRAC_PI_2, Vec2::ONE);
+
+        let close = |a: Vec2, b: Vec2| a.distance(b) < 1e-3;
+        let right = scene.get_shape(ids[0]).unwrap().transform;
+        let below = scene.get_shape(ids[1]).unwrap().transform;
+        // (110, 100) is 10 right of the anchor, so it ends up 10 below it
+        assert!(close(right.position, Vec2::new(100.0, 110.0)), "{:?}", right.position);
+        // The second shape turns about its own anchor point, (150, 200) in world space
+        assert!(close(below.position + below.anchor, Vec2::new(0.0, 150.0)), "{:?}", below.position);
+        assert!(close(below.position, Vec2::new(-50.0, 100.0)));
+        for transform in [right, below] {
+            assert!((transform.rotation - std::f32::consts::FRAC_PI_2).abs() < 1e-6);
+        }
+
+        // Every point of each shape rotated the same way about the anchor
+        let turn = |p: Vec2| anchor + Vec2::new(anchor.y - p.y, p.x - anchor.x);
+        for (transform, before) in [right, below].iter().zip(corners) {
+            assert!(close(transform.transform_point(Vec2::new(100.0, 0.0)), turn(before)));
+        }
+    }
+
+    #[test]
+    fn test_scale_selection_relative_about_anchor() {
+        let mut scene = SceneGraph::new();
+        let shape = create_test_shape().with_transform(Transform2D::from_position(Vec2::new(20.0, 30.0)));
+        let id = shape.id;
+        scene.add_shape(shape);
+        scene.select(id);
+
+        scene.transform_selection_relative(Vec2::new(10.0, 10.0), 0.0, Vec2::splat(2.0));
+
+        let transform = scene.get_shape(id).unwrap().transform;
+        assert_eq!(transform.position, Vec2::new(30.0, 50.0));
+        assert_eq!(transform.scale, Vec2::splat(2.0));
+        assert!(scene.dirty_shape_ids().contains(&id));
+    }
+
+    #[test]
+    fn test_clone_shape_sits_above_original() {
+        let mut scene = SceneGraph::new();
+        let below = create_test_shape();
+        let original = create_test_shape()
+            .with_transform(Transform2D::from_position(Vec2::new(20.0, 30.0)))
+            .with_name("Original".to_string());
+        let above = create_test_shape();
+        let ids = [below.id, original.id, above.id];
+        for shape in [below, original, above] {
+            scene.add_shape(shape);
+        }
+        scene.clear_dirty();
+
+        let copy_id = scene.clone_shape(ids[1]).unwrap();
+        assert_ne!(copy_id, ids[1]);
+        let (copy, original) = (scene.get_shape(copy_id).unwrap(), scene.get_shape(ids[1]).unwrap());
+        assert_eq!(copy.geometry, original.geometry);
+        assert_eq!(copy.style, original.style);
+        assert_eq!(copy.transform, original.transform);
+        assert_eq!(copy.name, "Original");
+
+        let order: Vec<u64> = scene.shapes().iter().map(|s| s.id).collect();
+        assert_eq!(order, vec![ids[0], ids[1], copy_id, ids[2]]);
+        assert!(scene.is_dirty());
+        assert!(scene.dirty_shape_ids().contains(&copy_id));
+        assert_eq!(scene.hit_test(Vec2::new(70.0, 120.0)), Some(copy_id));
+
+        assert_eq!(scene.clone_shape(9999), None);
+    }
+
+    #[test]
+    fn test_clone_shapes_batch() {
+        let mut scene = SceneGraph::new();
+        let a = create_test_shape();
+        let b = create_test_shape();
+        let (a_id, b_id) = (a.id, b.id);
+        scene.add_shape(a);
+        scene.add_shape(b);
+
+        let copies = scene.clone_shapes(&[a_id, 9999, b_id]);
+        assert_eq!(copies.len(), 2);
+        assert_eq!(scene.len(), 4);
+        assert!(copies.iter().all(|id| *id != a_id && *id != b_id));
+        assert_eq!(scene.get_shape(copies[1]).unwrap().style, scene.get_shape(b_id).unwrap().style);
+    }
 }
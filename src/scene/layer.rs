@@ -1,6 +1,9 @@
-use std::collections::HashSet;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::sync::atomic::{AtomicU64, Ordering};
 
+use super::shape::Shape;
+
 /// Global group ID counter
 static NEXT_GROUP_ID: AtomicU64 = AtomicU64::new(1);
 
@@ -8,6 +11,11 @@ fn generate_group_id() -> u64 {
     NEXT_GROUP_ID.fetch_add(1, Ordering::Relaxed)
 }
 
+/// Make sure groups created from now on get IDs above `id`, e.g. after loading saved groups
+pub fn reserve_group_id(id: u64) {
+    NEXT_GROUP_ID.fetch_max(id + 1, Ordering::Relaxed);
+}
+
 /// Counters for auto-generating group names
 static NEXT_GROUP_NUM: AtomicU64 = AtomicU64::new(1);
 
@@ -16,8 +24,24 @@ fn generate_group_name() -> String {
     format!("Group {}", num)
 }
 
+/// Identifies a layer node; shape and group IDs come from separate counters
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LayerKey {
+    Shape(u64),
+    Group(u64),
+}
+
+/// Where a dragged node lands relative to the drop target
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DropPosition {
+    Before,
+    After,
+    /// As the last child of a group target
+    Inside,
+}
+
 /// A node in the layer hierarchy - either a shape reference or a group
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum LayerNode {
     /// Reference to a shape by its ID
     Shape { shape_id: u64 },
@@ -27,6 +51,8 @@ pub enum LayerNode {
         name: String,
         children: Vec<LayerNode>,
         expanded: bool,
+        /// Multiplied into the opacity of every shape inside the group (0.0 - 1.0)
+        opacity: f32,
     },
 }
 
@@ -43,6 +69,7 @@ impl LayerNode {
             name,
             children: Vec::new(),
             expanded: true,
+            opacity: 1.0,
         }
     }
 
@@ -59,6 +86,14 @@ impl LayerNode {
         }
     }
 
+    /// Key identifying this node in the tree
+    pub fn key(&self) -> LayerKey {
+        match self {
+            LayerNode::Shape { shape_id } => LayerKey::Shape(*shape_id),
+            LayerNode::Group { id, .. } => LayerKey::Group(*id),
+        }
+    }
+
     /// Check if this node is a shape
     pub fn is_shape(&self) -> bool {
         matches!(self, LayerNode::Shape { .. })
@@ -91,7 +126,7 @@ impl LayerNode {
 }
 
 /// Manages the hierarchical layer structure
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct LayerTree {
     /// Top-level nodes in the layer hierarchy
     pub nodes: Vec<LayerNode>,
@@ -141,12 +176,31 @@ impl LayerTree {
         }
     }
 
+    /// Remove several shapes, dropping any groups left empty
+    pub fn remove_shapes(&mut self, shape_ids: &[u64]) {
+        for &shape_id in shape_ids {
+            Self::remove_shape_recursive(&mut self.nodes, shape_id);
+        }
+        Self::prune_empty_groups(&mut self.nodes);
+    }
+
+    fn prune_empty_groups(nodes: &mut Vec<LayerNode>) {
+        for node in nodes.iter_mut() {
+            if let LayerNode::Group { children, .. } = node {
+                Self::prune_empty_groups(children);
+            }
+        }
+        nodes.retain(|node| !matches!(node, LayerNode::Group { children, .. } if children.is_empty()));
+    }
+
     /// Get all shape IDs in the tree in order
     pub fn all_shape_ids(&self) -> Vec<u64> {
         self.nodes.iter().flat_map(|n| n.all_shape_ids()).collect()
     }
 
     /// Create a group from selected shape IDs
+    /// Groups at the shallowest level holding 2+ selected nodes, so a selection
+    /// inside an existing group becomes a nested group there
     /// Returns the group ID if successful
     pub fn group_shapes(&mut self, shape_ids: &[u64]) -> Option<u64> {
         if shape_ids.len() < 2 {
@@ -154,20 +208,22 @@ impl LayerTree {
         }
 
         let shape_set: HashSet<_> = shape_ids.iter().copied().collect();
+        Self::group_in(&mut self.nodes, &shape_set)
+    }
 
+    fn group_in(nodes: &mut Vec<LayerNode>, shape_set: &HashSet<u64>) -> Option<u64> {
         // Find nodes to group and their first position
         let mut first_idx: Option<usize> = None;
         let mut nodes_to_group: Vec<LayerNode> = Vec::new();
 
-        // Collect nodes that match the shape IDs (at top level for now)
         let mut i = 0;
-        while i < self.nodes.len() {
-            let should_include = match &self.nodes[i] {
+        while i < nodes.len() {
+            let should_include = match &nodes[i] {
                 LayerNode::Shape { shape_id } => shape_set.contains(shape_id),
                 LayerNode::Group { .. } => {
                     // Check if all shapes in this group are in the selection
-                    let group_shapes: HashSet<_> = self.nodes[i].all_shape_ids().into_iter().collect();
-                    !group_shapes.is_empty() && group_shapes.is_subset(&shape_set)
+                    let group_shapes: HashSet<_> = nodes[i].all_shape_ids().into_iter().collect();
+                    !group_shapes.is_empty() && group_shapes.is_subset(shape_set)
                 }
             };
 
@@ -175,7 +231,7 @@ impl LayerTree {
                 if first_idx.is_none() {
                     first_idx = Some(i);
                 }
-                nodes_to_group.push(self.nodes.remove(i));
+                nodes_to_group.push(nodes.remove(i));
             } else {
                 i += 1;
             }
@@ -185,10 +241,15 @@ impl LayerTree {
             // Put nodes back if we couldn't form a group
             for node in nodes_to_group.into_iter().rev() {
                 if let Some(idx) = first_idx {
-                    self.nodes.insert(idx, node);
+                    nodes.insert(idx, node);
                 }
             }
-            return None;
+
+            // Try inside child groups instead
+            return nodes.iter_mut().find_map(|node| match node {
+                LayerNode::Group { children, .. } => Self::group_in(children, shape_set),
+                LayerNode::Shape { .. } => None,
+            });
         }
 
         // Create the group
@@ -197,16 +258,40 @@ impl LayerTree {
             name: generate_group_name(),
             children: nodes_to_group,
             expanded: true,
+            opacity: 1.0,
         };
         let group_id = group.id();
 
         // Insert at the first position
-        let insert_idx = first_idx.unwrap_or(self.nodes.len());
-        self.nodes.insert(insert_idx, group);
+        let insert_idx = first_idx.unwrap_or(nodes.len());
+        nodes.insert(insert_idx, group);
 
         Some(group_id)
     }
 
+    /// Find the outermost group whose shapes are exactly the given selection
+    pub fn group_for_selection(&self, shape_ids: &[u64]) -> Option<u64> {
+        let shape_set: HashSet<_> = shape_ids.iter().copied().collect();
+        if shape_set.is_empty() {
+            return None;
+        }
+        Self::find_group_for_selection(&self.nodes, &shape_set)
+    }
+
+    fn find_group_for_selection(nodes: &[LayerNode], shape_set: &HashSet<u64>) -> Option<u64> {
+        nodes.iter().find_map(|node| match node {
+            LayerNode::Group { id, children, .. } => {
+                let group_shapes: HashSet<_> = node.all_shape_ids().into_iter().collect();
+                if group_shapes == *shape_set {
+                    Some(*id)
+                } else {
+                    Self::find_group_for_selection(children, shape_set)
+                }
+            }
+            LayerNode::Shape { .. } => None,
+        })
+    }
+
     /// Ungroup a group by ID, moving its children to the group's position
     pub fn ungroup(&mut self, group_id: u64) -> bool {
         Self::ungroup_recursive(&mut self.nodes, group_id)
@@ -239,6 +324,110 @@ impl LayerTree {
         false
     }
 
+    /// Move a node before, after or into `target`, possibly across groups
+    /// Groups left empty by the move are removed. Returns false (leaving the
+    /// tree unchanged) if either node is missing, `target` is inside `node`,
+    /// or `Inside` is used with a shape target
+    pub fn move_node(&mut self, node: LayerKey, target: LayerKey, position: DropPosition) -> bool {
+        if node == target || (position == DropPosition::Inside && !matches!(target, LayerKey::Group(_))) {
+            return false;
+        }
+
+        let mut nodes = self.nodes.clone();
+        let Some(moved) = Self::detach(&mut nodes, node) else {
+            return false;
+        };
+        // Target inside the moved subtree is no longer in `nodes`
+        if !Self::attach(&mut nodes, moved, target, position) {
+            return false;
+        }
+
+        Self::prune_empty_groups(&mut nodes);
+        self.nodes = nodes;
+        true
+    }
+
+    /// Move a node to `target_index` among the children of group `target_parent_id`,
+    /// or among the top-level nodes when it's None
+    /// The index counts the target's children without the moved node and is clamped
+    /// to their number, so `usize::MAX` appends. Groups left empty are removed.
+    /// Returns false (leaving the tree unchanged) if the node or the target group is
+    /// missing, or the target group is the node itself or inside it.
+    pub fn reorder(&mut self, node: LayerKey, target_parent_id: Option<u64>, target_index: usize) -> bool {
+        let mut nodes = self.nodes.clone();
+        let Some(moved) = Self::detach(&mut nodes, node) else {
+            return false;
+        };
+        // A target group inside the moved subtree is no longer in `nodes`
+        let siblings = match target_parent_id {
+            None => &mut nodes,
+            Some(group_id) => match Self::children_mut(&mut nodes, group_id) {
+                Some(children) => children,
+                None => return false,
+            },
+        };
+        siblings.insert(target_index.min(siblings.len()), moved);
+
+        Self::prune_empty_groups(&mut nodes);
+        self.nodes = nodes;
+        true
+    }
+
+    fn children_mut(nodes: &mut [LayerNode], group_id: u64) -> Option<&mut Vec<LayerNode>> {
+        for node in nodes.iter_mut() {
+            if let LayerNode::Group { id, children, .. } = node {
+                if *id == group_id {
+                    return Some(children);
+                }
+                if let Some(found) = Self::children_mut(children, group_id) {
+                    return Some(found);
+                }
+            }
+        }
+        None
+    }
+
+    fn detach(nodes: &mut Vec<LayerNode>, key: LayerKey) -> Option<LayerNode> {
+        if let Some(i) = nodes.iter().position(|n| n.key() == key) {
+            return Some(nodes.remove(i));
+        }
+        nodes.iter_mut().find_map(|node| match node {
+            LayerNode::Group { children, .. } => Self::detach(children, key),
+            LayerNode::Shape { .. } => None,
+        })
+    }
+
+    /// Insert `node` relative to `target`; returns false if the target isn't found
+    fn attach(nodes: &mut Vec<LayerNode>, node: LayerNode, target: LayerKey, position: DropPosition) -> bool {
+        if let Some(i) = nodes.iter().position(|n| n.key() == target) {
+            match position {
+                DropPosition::Before => nodes.insert(i, node),
+                DropPosition::After => nodes.insert(i + 1, node),
+                DropPosition::Inside => match &mut nodes[i] {
+                    LayerNode::Group { children, .. } => children.push(node),
+                    LayerNode::Shape { .. } => return false,
+                },
+            }
+            return true;
+        }
+
+        for n in nodes.iter_mut() {
+            if let LayerNode::Group { children, .. } = n {
+                if Self::contains_key(children, target) {
+                    return Self::attach(children, node, target, position);
+                }
+            }
+        }
+        false
+    }
+
+    fn contains_key(nodes: &[LayerNode], key: LayerKey) -> bool {
+        nodes.iter().any(|n| {
+            n.key() == key
+                || matches!(n, LayerNode::Group { children, .. } if Self::contains_key(children, key))
+        })
+    }
+
     /// Toggle the expanded state of a group
     pub fn toggle_expanded(&mut self, group_id: u64) {
         Self::toggle_expanded_recursive(&mut self.nodes, group_id);
@@ -257,22 +446,98 @@ impl LayerTree {
     }
 
     /// Rename a group
-    pub fn rename_group(&mut self, group_id: u64, new_name: String) {
-        Self::rename_group_recursive(&mut self.nodes, group_id, new_name);
+    pub fn rename_group(&mut self, group_id: u64, new_name: String) -> bool {
+        let new_name = new_name.trim();
+        !new_name.is_empty() && Self::rename_group_recursive(&mut self.nodes, group_id, new_name)
     }
 
-    fn rename_group_recursive(nodes: &mut [LayerNode], group_id: u64, new_name: String) {
-        for node in nodes.iter_mut() {
-            if let LayerNode::Group { id, name, children, .. } = node {
+    fn rename_group_recursive(nodes: &mut [LayerNode], group_id: u64, new_name: &str) -> bool {
+        nodes.iter_mut().any(|node| match node {
+            LayerNode::Group { id, name, children, .. } => {
                 if *id == group_id {
-                    *name = new_name;
-                    return;
+                    *name = new_name.to_string();
+                    true
+                } else {
+                    Self::rename_group_recursive(children, group_id, new_name)
+                }
+            }
+            LayerNode::Shape { .. } => false,
+        })
+    }
+
+    /// Opacity of a group, if it exists
+    pub fn group_opacity(&self, group_id: u64) -> Option<f32> {
+        Self::find_group_opacity(&self.nodes, group_id)
+    }
+
+    fn find_group_opacity(nodes: &[LayerNode], group_id: u64) -> Option<f32> {
+        nodes.iter().find_map(|node| match node {
+            LayerNode::Group { id, opacity, .. } if *id == group_id => Some(*opacity),
+            LayerNode::Group { children, .. } => Self::find_group_opacity(children, group_id),
+            LayerNode::Shape { .. } => None,
+        })
+    }
+
+    /// Set a group's opacity, clamped to 0.0 - 1.0
+    pub fn set_group_opacity(&mut self, group_id: u64, opacity: f32) -> bool {
+        Self::set_group_opacity_recursive(&mut self.nodes, group_id, opacity.clamp(0.0, 1.0))
+    }
+
+    fn set_group_opacity_recursive(nodes: &mut [LayerNode], group_id: u64, new_opacity: f32) -> bool {
+        nodes.iter_mut().any(|node| match node {
+            LayerNode::Group { id, opacity, children, .. } => {
+                if *id == group_id {
+                    *opacity = new_opacity;
+                    true
+                } else {
+                    Self::set_group_opacity_recursive(children, group_id, new_opacity)
+                }
+            }
+            LayerNode::Shape { .. } => false,
+        })
+    }
+
+    /// Combined opacity of the groups enclosing each grouped shape (nested groups multiply)
+    /// Shapes outside any group are left out
+    pub fn inherited_opacities(&self) -> HashMap<u64, f32> {
+        let mut opacities = HashMap::new();
+        Self::collect_inherited_opacities(&self.nodes, None, &mut opacities);
+        opacities
+    }
+
+    fn collect_inherited_opacities(nodes: &[LayerNode], inherited: Option<f32>, out: &mut HashMap<u64, f32>) {
+        for node in nodes {
+            match node {
+                LayerNode::Shape { shape_id } => {
+                    if let Some(opacity) = inherited {
+                        out.insert(*shape_id, opacity);
+                    }
+                }
+                LayerNode::Group { children, opacity, .. } => {
+                    Self::collect_inherited_opacities(children, Some(inherited.unwrap_or(1.0) * opacity), out);
                 }
-                Self::rename_group_recursive(children, group_id, new_name.clone());
             }
         }
     }
 
+    /// Copies of `shapes` for rendering, with group opacity folded into each shape's own
+    /// The scene itself is untouched, so hit testing ignores opacity
+    pub fn apply_group_opacity(&self, shapes: &[Shape]) -> Vec<Shape> {
+        let inherited = self.inherited_opacities();
+        shapes
+            .iter()
+            .map(|shape| {
+                let mut shape = shape.clone();
+                if let Some(opacity) = inherited.get(&shape.id) {
+                    shape.style.opacity *= opacity;
+                    // Grouped shapes re-tessellate so opacity changes are never served from cache
+                    shape.mark_dirty();
+                }
+                shape
+            })
+            .collect()
+    }
+
     /// Find all shape IDs that are descendants of a group
     pub fn get_group_shape_ids(&self, group_id: u64) -> Vec<u64> {
         Self::find_group_shapes(&self.nodes, group_id)
@@ -360,6 +625,16 @@ mod tests {
         assert_eq!(tree.all_shape_ids(), vec![1, 3]);
     }
 
+    #[test]
+    fn test_remove_shapes_prunes_empty_groups() {
+        let mut tree = LayerTree::from_shapes(&[1, 2, 3, 4]);
+        tree.group_shapes(&[2, 3]).unwrap();
+
+        tree.remove_shapes(&[2, 3]);
+        assert_eq!(tree.all_shape_ids(), vec![1, 4]);
+        assert_eq!(tree.nodes.len(), 2); // group removed along with its shapes
+    }
+
     #[test]
     fn test_nested_groups() {
         let mut tree = LayerTree::from_shapes(&[1, 2, 3, 4, 5]);
@@ -373,4 +648,197 @@ mod tests {
         let all_ids = tree.all_shape_ids();
         assert_eq!(all_ids.len(), 5);
     }
+
+    #[test]
+    fn test_group_nested_selection() {
+        let mut tree = LayerTree::from_shapes(&[1, 2, 3, 4]);
+        let outer_id = tree.group_shapes(&[1, 2, 3]).unwrap();
+
+        // Selecting two members of the group nests a new group inside it
+        let inner_id = tree.group_shapes(&[2, 3]).unwrap();
+        assert_eq!(tree.nodes.len(), 2); // outer group, 4
+        assert_eq!(tree.get_group_shape_ids(inner_id), vec![2, 3]);
+        assert_eq!(tree.get_group_shape_ids(outer_id), vec![1, 2, 3]);
+        assert_eq!(tree.all_shape_ids(), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_click_selection_returns_group_members() {
+        let mut tree = LayerTree::from_shapes(&[1, 2, 3, 4]);
+        tree.group_shapes(&[2, 3]).unwrap();
+        let outer_id = tree.group_shapes(&[1, 2, 3]).unwrap();
+
+        let mut selection = tree.get_selection_for_shape(3);
+        selection.sort();
+        assert_eq!(selection, vec![1, 2, 3]);
+        assert_eq!(tree.get_selection_for_shape(4), vec![4]);
+        assert_eq!(tree.group_for_selection(&selection), Some(outer_id));
+        assert_eq!(tree.group_for_selection(&[1, 2]), None);
+    }
+
+    #[test]
+    fn test_move_node_top_level() {
+        let mut tree = LayerTree::from_shapes(&[1, 2, 3, 4]);
+
+        assert!(tree.move_node(LayerKey::Shape(1), LayerKey::Shape(3), DropPosition::After));
+        assert_eq!(tree.all_shape_ids(), vec![2, 3, 1, 4]);
+
+        assert!(tree.move_node(LayerKey::Shape(4), LayerKey::Shape(2), DropPosition::Before));
+        assert_eq!(tree.all_shape_ids(), vec![4, 2, 3, 1]);
+
+        // Moving onto itself or inside a shape is rejected
+        assert!(!tree.move_node(LayerKey::Shape(4), LayerKey::Shape(4), DropPosition::After));
+        assert!(!tree.move_node(LayerKey::Shape(4), LayerKey::Shape(2), DropPosition::Inside));
+        assert_eq!(tree.all_shape_ids(), vec![4, 2, 3, 1]);
+    }
+
+    #[test]
+    fn test_move_node_into_and_out_of_group() {
+        let mut tree = LayerTree::from_shapes(&[1, 2, 3, 4]);
+        let group_id = tree.group_shapes(&[2, 3]).unwrap();
+        // Tree: 1, group(2,3), 4
+
+        assert!(tree.move_node(LayerKey::Shape(4), LayerKey::Group(group_id), DropPosition::Inside));
+        assert_eq!(tree.get_group_shape_ids(group_id), vec![2, 3, 4]);
+
+        assert!(tree.move_node(LayerKey::Shape(1), LayerKey::Shape(2), DropPosition::After));
+        assert_eq!(tree.get_group_shape_ids(group_id), vec![2, 1, 3, 4]);
+        assert_eq!(tree.nodes.len(), 1);
+
+        // Dragging a shape out past the group
+        assert!(tree.move_node(LayerKey::Shape(3), LayerKey::Group(group_id), DropPosition::After));
+        assert_eq!(tree.all_shape_ids(), vec![2, 1, 4, 3]);
+        assert_eq!(tree.nodes.len(), 2);
+    }
+
+    #[test]
+    fn test_move_node_rejects_cycles_and_prunes_empty_groups() {
+        let mut tree = LayerTree::from_shapes(&[1, 2, 3]);
+        let inner_id = tree.group_shapes(&[1, 2]).unwrap();
+        let outer_id = tree.group_shapes(&[1, 2, 3]).unwrap();
+
+        // A group can't move into its own descendant
+        assert!(!tree.move_node(LayerKey::Group(outer_id), LayerKey::Group(inner_id), DropPosition::Inside));
+        assert!(!tree.move_node(LayerKey::Group(outer_id), LayerKey::Shape(1), DropPosition::Before));
+
+        // Emptying the inner group removes it
+        assert!(tree.move_node(LayerKey::Shape(1), LayerKey::Group(outer_id), DropPosition::After));
+        assert!(tree.move_node(LayerKey::Shape(2), LayerKey::Group(outer_id), DropPosition::After));
+        assert!(tree.get_group_shape_ids(inner_id).is_empty());
+        assert_eq!(tree.all_shape_ids(), vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn test_reorder_within_and_between_groups() {
+        let mut tree = LayerTree::from_shapes(&[1, 2, 3, 4, 5]);
+        let group_a = tree.group_shapes(&[2, 3]).unwrap();
+        let group_b = tree.group_shapes(&[4, 5]).unwrap();
+        // Tree: 1, a(2, 3), b(4, 5)
+
+        // Within a group
+        assert!(tree.reorder(LayerKey::Shape(3), Some(group_a), 0));
+        assert_eq!(tree.get_group_shape_ids(group_a), vec![3, 2]);
+
+        // From one group to the middle of another
+        assert!(tree.reorder(LayerKey::Shape(2), Some(group_b), 1));
+        assert_eq!(tree.get_group_shape_ids(group_a), vec![3]);
+        assert_eq!(tree.get_group_shape_ids(group_b), vec![4, 2, 5]);
+
+        // A top-level shape into a group; out-of-range indices append
+        assert!(tree.reorder(LayerKey::Shape(1), Some(group_a), usize::MAX));
+        assert_eq!(tree.get_group_shape_ids(group_a), vec![3, 1]);
+        assert_eq!(tree.nodes.len(), 2);
+    }
+
+    #[test]
+    fn test_reorder_out_of_groups_and_across_levels() {
+        let mut tree = LayerTree::from_shapes(&[1, 2, 3, 4]);
+        let inner = tree.group_shapes(&[2, 3]).unwrap();
+        let outer = tree.group_shapes(&[2, 3, 4]).unwrap();
+        // Tree: 1, outer(inner(2, 3), 4)
+
+        // Out of a nested group to the top level
+        assert!(tree.reorder(LayerKey::Shape(3), None, 0));
+        assert_eq!(tree.all_shape_ids(), vec![3, 1, 2, 4]);
+
+        // A group moves with its children, up a level past its old parent
+        assert!(tree.reorder(LayerKey::Group(inner), None, 3));
+        assert_eq!(tree.nodes.iter().map(LayerNode::key).collect::<Vec<_>>(), vec![
+            LayerKey::Shape(3),
+            LayerKey::Shape(1),
+            LayerKey::Group(outer),
+            LayerKey::Group(inner),
+        ]);
+
+        // Emptying a group removes it
+        assert!(tree.reorder(LayerKey::Shape(2), Some(outer), 0));
+        assert_eq!(tree.get_group_shape_ids(inner), Vec::<u64>::new());
+        assert!(!tree.nodes.iter().any(|node| node.key() == LayerKey::Group(inner)));
+        assert_eq!(tree.all_shape_ids(), vec![3, 1, 2, 4]);
+    }
+
+    #[test]
+    fn test_reorder_rejects_missing_nodes_and_cycles() {
+        let mut tree = LayerTree::from_shapes(&[1, 2, 3]);
+        let inner = tree.group_shapes(&[1, 2]).unwrap();
+        let outer = tree.group_shapes(&[1, 2, 3]).unwrap();
+        let before = tree.clone();
+
+        assert!(!tree.reorder(LayerKey::Shape(99), None, 0));
+        assert!(!tree.reorder(LayerKey::Shape(1), Some(999), 0));
+        // A group can't move into itself or its descendant
+        assert!(!tree.reorder(LayerKey::Group(outer), Some(outer), 0));
+        assert!(!tree.reorder(LayerKey::Group(outer), Some(inner), 0));
+        assert_eq!(tree, before);
+    }
+
+    #[test]
+    fn test_rename_group_rejects_blank_names() {
+        let mut tree = LayerTree::from_shapes(&[1, 2, 3]);
+        let inner_id = tree.group_shapes(&[1, 2]).unwrap();
+        tree.group_shapes(&[1, 2, 3]).unwrap();
+
+        // Nested groups are found, and the name is trimmed
+        assert!(tree.rename_group(inner_id, "  Header  ".to_string()));
+        assert!(!tree.rename_group(inner_id, "   ".to_string()));
+        assert!(!tree.rename_group(999, "Missing".to_string()));
+
+        let LayerNode::Group { children, .. } = &tree.nodes[0] else {
+            panic!("expected the outer group");
+        };
+        assert!(matches!(&children[0], LayerNode::Group { name, .. } if name == "Header"));
+    }
+
+    #[test]
+    fn test_group_opacity_multiplies_through_nesting() {
+        use crate::scene::{ShapeGeometry, ShapeStyle};
+
+        let shapes: Vec<Shape> = (0..4)
+            .map(|_| Shape::new(ShapeGeometry::rectangle(10.0, 10.0), ShapeStyle::default().with_opacity(0.5)))
+            .collect();
+        let ids: Vec<u64> = shapes.iter().map(|s| s.id).collect();
+        let mut tree = LayerTree::from_shapes(&ids);
+        let inner_id = tree.group_shapes(&ids[..2]).unwrap();
+        let outer_id = tree.group_shapes(&ids[..3]).unwrap();
+
+        assert!(tree.set_group_opacity(inner_id, 0.5));
+        assert!(tree.set_group_opacity(outer_id, 1.5));
+        assert_eq!(tree.group_opacity(outer_id), Some(1.0));
+        assert!(tree.set_group_opacity(outer_id, 0.4));
+        assert!(!tree.set_group_opacity(999, 0.5));
+
+        let inherited = tree.inherited_opacities();
+        assert!((inherited[&ids[0]] - 0.2).abs() < 1e-6);
+        assert!((inherited[&ids[1]] - 0.2).abs() < 1e-6);
+        assert!((inherited[&ids[2]] - 0.4).abs() < 1e-6);
+        assert!(!inherited.contains_key(&ids[3]));
+
+        // Shape opacity is multiplied in; the source shapes are left alone
+        let rendered = tree.apply_group_opacity(&shapes);
+        let opacities: Vec<f32> = rendered.iter().map(|s| s.style.opacity).collect();
+        assert!((opacities[0] - 0.1).abs() < 1e-6);
+        assert!((opacities[2] - 0.2).abs() < 1e-6);
+        assert_eq!(opacities[3], 0.5);
+        assert_eq!(shapes[0].style.opacity, 0.5);
+    }
 }
@@ -1,11 +1,26 @@
+mod arc;
+mod boolean;
+mod css_colors;
+mod from_svg;
 mod graph;
 mod layer;
 mod shape;
+mod spatial;
+mod svg_import;
 mod svg_path;
+mod text;
 mod types;
+mod vertex_edit;
 
+pub use arc::arc_to_cubics;
+pub use boolean::*;
+pub use from_svg::{from_svg_circle, from_svg_ellipse, from_svg_line, from_svg_rect};
 pub use graph::*;
 pub use layer::*;
 pub use shape::*;
-pub use svg_path::parse_svg_path;
+pub use spatial::SpatialIndex;
+pub use svg_import::{import_svg, ImportError, SvgImport};
+pub use svg_path::{parse_svg_path, parse_svg_path_checked, PathParseError};
+pub use text::*;
 pub use types::*;
+pub use vertex_edit::*;
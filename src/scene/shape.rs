@@ -1,5 +1,8 @@
+use super::arc::arc_to_cubics;
+use super::text::{text_bounds, TextAlign, DEFAULT_FONT_FAMILY};
 use super::types::{BBox, Color, ShapeStyle, StrokeStyle, Transform2D, Vec2};
 use crate::types::Polygon;
+use serde::{Deserialize, Deserializer, Serialize};
 use std::sync::atomic::{AtomicU64, Ordering};
 
 /// Global shape ID counter
@@ -10,15 +13,24 @@ static NEXT_POLYGON_NUM: AtomicU64 = AtomicU64::new(1);
 static NEXT_RECTANGLE_NUM: AtomicU64 = AtomicU64::new(1);
 static NEXT_ELLIPSE_NUM: AtomicU64 = AtomicU64::new(1);
 static NEXT_PATH_NUM: AtomicU64 = AtomicU64::new(1);
+static NEXT_LINE_NUM: AtomicU64 = AtomicU64::new(1);
+static NEXT_TEXT_NUM: AtomicU64 = AtomicU64::new(1);
+static NEXT_IMAGE_NUM: AtomicU64 = AtomicU64::new(1);
+static NEXT_STAR_NUM: AtomicU64 = AtomicU64::new(1);
 
-fn generate_shape_id() -> u64 {
+pub(super) fn generate_shape_id() -> u64 {
     NEXT_SHAPE_ID.fetch_add(1, Ordering::Relaxed)
 }
 
+/// Make sure shapes created from now on get IDs above `id`, e.g. after loading saved shapes
+pub fn reserve_shape_id(id: u64) {
+    NEXT_SHAPE_ID.fetch_max(id + 1, Ordering::Relaxed);
+}
+
 /// Generate a default name based on geometry type
 fn generate_shape_name(geometry: &ShapeGeometry) -> String {
     match geometry {
-        ShapeGeometry::Polygon { .. } => {
+        ShapeGeometry::Polygon { .. } | ShapeGeometry::RegularPolygon { .. } => {
             let num = NEXT_POLYGON_NUM.fetch_add(1, Ordering::Relaxed);
             format!("Polygon {}", num)
         }
@@ -38,11 +50,27 @@ fn generate_shape_name(geometry: &ShapeGeometry) -> String {
             let num = NEXT_PATH_NUM.fetch_add(1, Ordering::Relaxed);
             format!("Path {}", num)
         }
+        ShapeGeometry::Line { .. } => {
+            let num = NEXT_LINE_NUM.fetch_add(1, Ordering::Relaxed);
+            format!("Line {}", num)
+        }
+        ShapeGeometry::Text { .. } => {
+            let num = NEXT_TEXT_NUM.fetch_add(1, Ordering::Relaxed);
+            format!("Text {}", num)
+        }
+        ShapeGeometry::Image { .. } => {
+            let num = NEXT_IMAGE_NUM.fetch_add(1, Ordering::Relaxed);
+            format!("Image {}", num)
+        }
+        ShapeGeometry::Star { .. } => {
+            let num = NEXT_STAR_NUM.fetch_add(1, Ordering::Relaxed);
+            format!("Star {}", num)
+        }
     }
 }
 
 /// Path command for arbitrary vector paths
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum PathCommand {
     MoveTo(Vec2),
     LineTo(Vec2),
@@ -65,17 +93,260 @@ pub enum PathCommand {
     Close,
 }
 
+/// Radius of each corner of a rectangle
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct CornerRadii {
+    pub tl: f32,
+    pub tr: f32,
+    pub br: f32,
+    pub bl: f32,
+}
+
+impl CornerRadii {
+    pub fn new(tl: f32, tr: f32, br: f32, bl: f32) -> Self {
+        Self { tl, tr, br, bl }
+    }
+
+    /// The same radius on every corner
+    pub fn uniform(radius: f32) -> Self {
+        Self::new(radius, radius, radius, radius)
+    }
+
+    /// The radius all four corners share, or None when they differ
+    pub fn as_uniform(&self) -> Option<f32> {
+        (self.tl == self.tr && self.tr == self.br && self.br == self.bl).then_some(self.tl)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.as_uniform() == Some(0.0)
+    }
+
+    /// Radii that fit a `width` × `height` rectangle: negative radii become zero, and if the
+    /// two corners along any side would overlap, every radius shrinks by the same factor
+    /// so the corners keep their proportions (as CSS `border-radius` does)
+    pub fn clamped(self, width: f32, height: f32) -> Self {
+        let [tl, tr, br, bl] = [self.tl, self.tr, self.br, self.bl].map(|r| r.max(0.0));
+        let (width, height) = (width.max(0.0), height.max(0.0));
+        let factor = [(width, tl + tr), (height, tr + br), (width, br + bl), (height, bl + tl)]
+            .into_iter()
+            .filter(|&(_, sum)| sum > 0.0)
+            .fold(1.0f32, |factor, (side, sum)| factor.min(side / sum));
+        Self::new(tl * factor, tr * factor, br * factor, bl * factor)
+    }
+
+    /// Outline of a `width` × `height` rectangle from the local origin, clockwise from the
+    /// top-left corner, with a circular arc at each rounded corner
+    pub fn outline(self, width: f32, height: f32) -> Vec<PathCommand> {
+        let r = self.clamped(width, height);
+        let mut commands = vec![PathCommand::MoveTo(Vec2::new(r.tl, 0.0))];
+        let corners = [
+            (Vec2::new(width - r.tr, 0.0), r.tr, Vec2::new(width, r.tr)),
+            (Vec2::new(width, height - r.br), r.br, Vec2::new(width - r.br, height)),
+            (Vec2::new(r.bl, height), r.bl, Vec2::new(0.0, height - r.bl)),
+            (Vec2::new(0.0, r.tl), r.tl, Vec2::new(r.tl, 0.0)),
+        ];
+        for (edge_end, radius, arc_end) in corners {
+            commands.push(PathCommand::LineTo(edge_end));
+            if radius > 0.0 {
+                commands.push(PathCommand::ArcTo {
+                    rx: radius,
+                    ry: radius,
+                    x_rotation: 0.0,
+                    large_arc: false,
+                    sweep: true,
+                    to: arc_end,
+                });
+            }
+        }
+        commands.push(PathCommand::Close);
+        commands
+    }
+}
+
+/// Reads per-corner radii, or the single `corner_radius` number older saves store
+impl<'de> Deserialize<'de> for CornerRadii {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Radii {
+            Uniform(f32),
+            Corners { tl: f32, tr: f32, br: f32, bl: f32 },
+        }
+
+        Ok(match Radii::deserialize(deserializer)? {
+            Radii::Uniform(radius) => CornerRadii::uniform(radius),
+            Radii::Corners { tl, tr, br, bl } => CornerRadii::new(tl, tr, br, bl),
+        })
+    }
+}
+
+/// Parameters of a pie slice centered on the local origin. Angles are in
+/// degrees, measured clockwise from the positive x axis (y points down).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PieSlice {
+    pub radius: f32,
+    pub start_angle: f32,
+    pub end_angle: f32,
+}
+
+impl PieSlice {
+    /// Largest sweep that still yields a distinct start and end point
+    const MAX_SWEEP: f32 = 359.9;
+
+    pub fn new(radius: f32, start_angle: f32, end_angle: f32) -> Self {
+        Self {
+            radius,
+            start_angle,
+            end_angle,
+        }
+    }
+
+    /// Sweep from start to end angle, normalized to (0, 360)
+    pub fn sweep(self) -> f32 {
+        let sweep = (self.end_angle - self.start_angle).rem_euclid(360.0);
+        if sweep == 0.0 {
+            Self::MAX_SWEEP
+        } else {
+            sweep.min(Self::MAX_SWEEP)
+        }
+    }
+
+    /// Build the path: center, out along the start radius, around the arc and back
+    pub fn to_commands(self) -> Vec<PathCommand> {
+        let point_at = |degrees: f32| Vec2::from_angle(degrees.to_radians()) * self.radius;
+        let sweep = self.sweep();
+        vec![
+            PathCommand::MoveTo(Vec2::ZERO),
+            PathCommand::LineTo(point_at(self.start_angle)),
+            PathCommand::ArcTo {
+                rx: self.radius,
+                ry: self.radius,
+                x_rotation: 0.0,
+                large_arc: sweep > 180.0,
+                sweep: true,
+                to: point_at(self.start_angle + sweep),
+            },
+            PathCommand::LineTo(Vec2::ZERO),
+            PathCommand::Close,
+        ]
+    }
+
+    /// Recover the slice parameters from a path built by `to_commands`
+    pub fn from_commands(commands: &[PathCommand]) -> Option<Self> {
+        let [PathCommand::MoveTo(center), PathCommand::LineTo(start), PathCommand::ArcTo { rx, ry, sweep: true, to, .. }, PathCommand::LineTo(back), PathCommand::Close] =
+            commands
+        else {
+            return None;
+        };
+        if *center != Vec2::ZERO || *back != Vec2::ZERO || rx != ry {
+            return None;
+        }
+        let start_angle = start.y.atan2(start.x).to_degrees();
+        let sweep = (to.y.atan2(to.x).to_degrees() - start_angle).rem_euclid(360.0);
+        Some(Self::new(*rx, start_angle, start_angle + sweep))
+    }
+}
+
+/// Round a closed polygon's corners with Catmull-Rom splines, as cubic Béziers
+/// The curve still passes through every vertex; `tension` runs from 0.0 (straight
+/// edges, sharp corners) to 1.0 (a full Catmull-Rom curve)
+pub fn smooth_polygon(points: &[Vec2], tension: f32) -> Vec<PathCommand> {
+    let n = points.len();
+    if n < 3 {
+        return Vec::new();
+    }
+    // Catmull-Rom tangents are (next - prev) / 2; a third of that reaches the Bézier control
+    let k = tension.clamp(0.0, 1.0) / 6.0;
+    let at = |i: usize| points[i % n];
+
+    let mut commands = Vec::with_capacity(n + 2);
+    commands.push(PathCommand::MoveTo(points[0]));
+    for i in 0..n {
+        let (prev, from, to, next) = (at(i + n - 1), at(i), at(i + 1), at(i + 2));
+        commands.push(PathCommand::CubicTo {
+            ctrl1: from + (to - prev) * k,
+            ctrl2: to - (next - from) * k,
+            to,
+        });
+    }
+    commands.push(PathCommand::Close);
+    commands
+}
+
+/// Recover the vertices and tension from a path built by `smooth_polygon`
+fn smooth_polygon_source(commands: &[PathCommand]) -> Option<(Vec<Vec2>, f32)> {
+    let [PathCommand::MoveTo(start), segments @ .., PathCommand::Close] = commands else {
+        return None;
+    };
+    let ends: Vec<Vec2> = segments
+        .iter()
+        .map(|command| match command {
+            PathCommand::CubicTo { to, .. } => Some(*to),
+            _ => None,
+        })
+        .collect::<Option<_>>()?;
+    if ends.len() < 3 || ends.last() != Some(start) {
+        return None;
+    }
+    let mut points = vec![*start];
+    points.extend_from_slice(&ends[..ends.len() - 1]);
+
+    // The first control point sits (next - prev) * tension / 6 past its vertex
+    let n = points.len();
+    let PathCommand::CubicTo { ctrl1, .. } = segments[0] else {
+        return None;
+    };
+    let tangent = points[1] - points[n - 1];
+    let tension = if tangent.length_squared() > f32::EPSILON {
+        ((ctrl1 - points[0]).dot(tangent) / tangent.length_squared() * 6.0).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
+    // Only a path whose every control point matches counts as a smoothed polygon
+    let tolerance = 1e-3 * points.iter().fold(1.0f32, |size, p| size.max(p.abs().max_element()));
+    let matches = smooth_polygon(&points, tension).iter().zip(commands).all(|pair| match pair {
+        (
+            PathCommand::CubicTo { ctrl1: a1, ctrl2: a2, .. },
+            PathCommand::CubicTo { ctrl1: b1, ctrl2: b2, .. },
+        ) => a1.distance(*b1) <= tolerance && a2.distance(*b2) <= tolerance,
+        _ => true,
+    });
+    matches.then_some((points, tension))
+}
+
+/// Vertices of a star centered on the origin, alternating between `outer_radius`
+/// and `inner_radius` clockwise from the top point
+pub fn star_vertices(points: u32, outer_radius: f32, inner_radius: f32) -> Vec<Vec2> {
+    let step = std::f32::consts::PI / points as f32;
+    (0..points * 2)
+        .map(|i| {
+            let radius = if i % 2 == 0 { outer_radius } else { inner_radius };
+            Vec2::from_angle(i as f32 * step - std::f32::consts::FRAC_PI_2) * radius
+        })
+        .collect()
+}
+
+/// Vertices of a regular polygon centered on the origin, clockwise from the top vertex
+pub fn regular_polygon_vertices(sides: u32, radius: f32) -> Vec<Vec2> {
+    let step = std::f32::consts::TAU / sides as f32;
+    (0..sides)
+        .map(|i| Vec2::from_angle(i as f32 * step - std::f32::consts::FRAC_PI_2) * radius)
+        .collect()
+}
+
 /// Geometry definition for different shape types
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum ShapeGeometry {
     /// Polygon defined by a series of points
     Polygon { points: Vec<Vec2> },
 
-    /// Rectangle with optional corner radius
+    /// Rectangle with optionally rounded corners
     Rectangle {
         width: f32,
         height: f32,
-        corner_radius: f32,
+        #[serde(alias = "corner_radius")]
+        corner_radii: CornerRadii,
     },
 
     /// Ellipse defined by x and y radii
@@ -83,6 +354,28 @@ pub enum ShapeGeometry {
 
     /// Arbitrary vector path
     Path { commands: Vec<PathCommand> },
+
+    /// Straight line segment between two points (stroke only)
+    Line { start: Vec2, end: Vec2 },
+
+    /// One line of text with its baseline on the local x axis, filled with the fill color
+    Text {
+        content: String,
+        font_size: f32,
+        font_family: String,
+        align: TextAlign,
+    },
+
+    /// Bitmap image stretched over width × height from the local origin
+    /// `src` is a data URL so the pixels travel with copies and saved versions
+    Image { width: f32, height: f32, src: String },
+
+    /// Star centered on the local origin with `points` tips on `outer_radius`
+    /// and the notches between them on `inner_radius`
+    Star { points: u32, outer_radius: f32, inner_radius: f32 },
+
+    /// Regular polygon centered on the local origin with its vertices on `radius`
+    RegularPolygon { sides: u32, radius: f32 },
 }
 
 impl ShapeGeometry {
@@ -93,19 +386,20 @@ impl ShapeGeometry {
 
     /// Create a rectangle
     pub fn rectangle(width: f32, height: f32) -> Self {
-        Self::Rectangle {
-            width,
-            height,
-            corner_radius: 0.0,
-        }
+        Self::rectangle_with_radii(width, height, CornerRadii::default())
     }
 
-    /// Create a rounded rectangle
+    /// Create a rounded rectangle with the same radius on every corner
     pub fn rounded_rectangle(width: f32, height: f32, corner_radius: f32) -> Self {
+        Self::rectangle_with_radii(width, height, CornerRadii::uniform(corner_radius))
+    }
+
+    /// Create a rectangle with its own radius on each corner
+    pub fn rectangle_with_radii(width: f32, height: f32, corner_radii: CornerRadii) -> Self {
         Self::Rectangle {
             width,
             height,
-            corner_radius,
+            corner_radii,
         }
     }
 
@@ -114,6 +408,11 @@ impl ShapeGeometry {
         Self::Ellipse { rx, ry }
     }
 
+    /// Create a line segment
+    pub fn line(start: Vec2, end: Vec2) -> Self {
+        Self::Line { start, end }
+    }
+
     /// Create a circle (ellipse with equal radii)
     pub fn circle(radius: f32) -> Self {
         Self::Ellipse {
@@ -122,65 +421,127 @@ impl ShapeGeometry {
         }
     }
 
+    /// Create start-aligned text in the default font
+    pub fn text(content: impl Into<String>, font_size: f32) -> Self {
+        Self::Text {
+            content: content.into(),
+            font_size,
+            font_family: DEFAULT_FONT_FAMILY.to_string(),
+            align: TextAlign::Start,
+        }
+    }
+
+    /// Create an image of the given size from the local origin
+    pub fn image(width: f32, height: f32, src: impl Into<String>) -> Self {
+        Self::Image {
+            width,
+            height,
+            src: src.into(),
+        }
+    }
+
+    /// Create a star, see `star_vertices`
+    pub fn star(points: u32, outer_radius: f32, inner_radius: f32) -> Self {
+        Self::Star {
+            points,
+            outer_radius,
+            inner_radius,
+        }
+    }
+
+    /// Create a regular polygon, see `regular_polygon_vertices`
+    pub fn regular_polygon(sides: u32, radius: f32) -> Self {
+        Self::RegularPolygon { sides, radius }
+    }
+
+    /// Create a pie slice path centered on the local origin
+    pub fn pie_slice(slice: PieSlice) -> Self {
+        Self::Path {
+            commands: slice.to_commands(),
+        }
+    }
+
+    /// Pie slice parameters when this is a path built by `pie_slice`
+    pub fn as_pie_slice(&self) -> Option<PieSlice> {
+        match self {
+            ShapeGeometry::Path { commands } => PieSlice::from_commands(commands),
+            _ => None,
+        }
+    }
+
+    /// Closed path through `points` with corners rounded by `tension`, see `smooth_polygon`
+    pub fn smooth_polygon(points: &[Vec2], tension: f32) -> Self {
+        Self::Path {
+            commands: smooth_polygon(points, tension),
+        }
+    }
+
+    /// Original vertices and tension when this is a path built by `smooth_polygon`
+    pub fn as_smooth_polygon(&self) -> Option<(Vec<Vec2>, f32)> {
+        match self {
+            ShapeGeometry::Path { commands } => smooth_polygon_source(commands),
+            _ => None,
+        }
+    }
+
+    /// Closed outline of straight edges for polygons, stars and regular polygons
+    /// Stars and regular polygons generate theirs from their parameters
+    pub fn ring_points(&self) -> Option<Vec<Vec2>> {
+        match self {
+            ShapeGeometry::Polygon { points } => Some(points.clone()),
+            ShapeGeometry::Star { points, outer_radius, inner_radius } => {
+                Some(star_vertices(*points, *outer_radius, *inner_radius))
+            }
+            ShapeGeometry::RegularPolygon { sides, radius } => Some(regular_polygon_vertices(*sides, *radius)),
+            _ => None,
+        }
+    }
+
     /// Get the local bounding box (before transform)
     pub fn local_bounds(&self) -> BBox {
         match self {
             ShapeGeometry::Polygon { points } => {
                 BBox::from_points(points).unwrap_or(BBox::new(Vec2::ZERO, Vec2::ZERO))
             }
-            ShapeGeometry::Rectangle { width, height, .. } => {
+            ShapeGeometry::Star { .. } | ShapeGeometry::RegularPolygon { .. } => self
+                .ring_points()
+                .and_then(|points| BBox::from_points(&points))
+                .unwrap_or(BBox::new(Vec2::ZERO, Vec2::ZERO)),
+            ShapeGeometry::Rectangle { width, height, .. } | ShapeGeometry::Image { width, height, .. } => {
                 BBox::new(Vec2::ZERO, Vec2::new(*width, *height))
             }
             ShapeGeometry::Ellipse { rx, ry } => BBox::new(Vec2::new(-*rx, -*ry), Vec2::new(*rx, *ry)),
+            ShapeGeometry::Line { start, end } => BBox::new(start.min(*end), start.max(*end)),
+            ShapeGeometry::Text { content, font_size, align, .. } => text_bounds(content, *font_size, *align),
             ShapeGeometry::Path { commands } => {
+                // Curves bulge past their end points, so each one adds the points where
+                // it turns along x or y; arcs are measured as their cubics
                 let mut points: Vec<Vec2> = Vec::new();
-                let mut current_pos = Vec2::ZERO;
+                let mut current = Vec2::ZERO;
+                let mut subpath_start = Vec2::ZERO;
 
-                for cmd in commands {
+                for cmd in arcs_to_cubics(commands) {
                     match cmd {
                         PathCommand::MoveTo(p) => {
-                            points.push(*p);
-                            current_pos = *p;
+                            points.push(p);
+                            subpath_start = p;
+                            current = p;
                         }
-                        PathCommand::LineTo(p) => {
-                            points.push(*p);
-                            current_pos = *p;
+                        PathCommand::LineTo(to) | PathCommand::ArcTo { to, .. } => {
+                            points.push(to);
+                            current = to;
                         }
                         PathCommand::QuadraticTo { control, to } => {
-                            // Include control point for proper bounds
-                            points.push(*control);
-                            points.push(*to);
-                            current_pos = *to;
+                            points.extend(quadratic_extrema(current, control, to));
+                            points.push(to);
+                            current = to;
                         }
                         PathCommand::CubicTo { ctrl1, ctrl2, to } => {
-                            // Include both control points for proper bounds
-                            points.push(*ctrl1);
-                            points.push(*ctrl2);
-                            points.push(*to);
-                            current_pos = *to;
+                            points.extend(cubic_extrema(current, ctrl1, ctrl2, to));
+                            points.push(to);
+                            current = to;
                         }
-                        PathCommand::ArcTo { rx, ry, to, .. } => {
-                            // For arcs, the maximum extent from the chord is the radius
-                            // We expand by the radius in all directions from the midpoint
-                            // of the chord to capture the arc's bulge
-                            points.push(*to);
-
-                            let max_r = rx.max(*ry);
-
-                            // Calculate midpoint of the chord
-                            let mid_x = (current_pos.x + to.x) / 2.0;
-                            let mid_y = (current_pos.y + to.y) / 2.0;
-
-                            // Add corners around the midpoint expanded by radius
-                            // This captures the arc's bulge from the chord
-                            points.push(Vec2::new(mid_x - max_r, mid_y - max_r));
-                            points.push(Vec2::new(mid_x + max_r, mid_y - max_r));
-                            points.push(Vec2::new(mid_x - max_r, mid_y + max_r));
-                            points.push(Vec2::new(mid_x + max_r, mid_y + max_r));
-
-                            current_pos = *to;
-                        }
-                        PathCommand::Close => {}
+                        PathCommand::Close => current = subpath_start,
                     }
                 }
                 BBox::from_points(&points).unwrap_or(BBox::new(Vec2::ZERO, Vec2::ZERO))
@@ -188,6 +549,32 @@ impl ShapeGeometry {
         }
     }
 
+    /// Explicit vertices in local coordinates: polygon, star and regular polygon points,
+    /// line endpoints and the on-curve points of paths. Rectangles and ellipses have
+    /// none beyond their bounds, and text and images have none at all.
+    pub fn vertices(&self) -> Vec<Vec2> {
+        match self {
+            ShapeGeometry::Polygon { .. } | ShapeGeometry::Star { .. } | ShapeGeometry::RegularPolygon { .. } => {
+                self.ring_points().unwrap_or_default()
+            }
+            ShapeGeometry::Line { start, end } => vec![*start, *end],
+            ShapeGeometry::Path { commands } => commands
+                .iter()
+                .filter_map(|cmd| match cmd {
+                    PathCommand::MoveTo(p) | PathCommand::LineTo(p) => Some(*p),
+                    PathCommand::QuadraticTo { to, .. }
+                    | PathCommand::CubicTo { to, .. }
+                    | PathCommand::ArcTo { to, .. } => Some(*to),
+                    PathCommand::Close => None,
+                })
+                .collect(),
+            ShapeGeometry::Rectangle { .. }
+            | ShapeGeometry::Ellipse { .. }
+            | ShapeGeometry::Text { .. }
+            | ShapeGeometry::Image { .. } => Vec::new(),
+        }
+    }
+
     /// Get the points for polygon geometry (for compatibility)
     pub fn polygon_points(&self) -> Option<&[Vec2]> {
         match self {
@@ -195,10 +582,152 @@ impl ShapeGeometry {
             _ => None,
         }
     }
+
+    /// The same outline as path commands in local coordinates: straight edges become
+    /// lines, ellipses four cubics and rounded corners a cubic each. Lines stay open.
+    /// None for text and images, which have no outline to edit
+    pub fn to_path_commands(&self) -> Option<Vec<PathCommand>> {
+        let commands = match self {
+            ShapeGeometry::Polygon { .. } | ShapeGeometry::Star { .. } | ShapeGeometry::RegularPolygon { .. } => {
+                let points = self.ring_points()?;
+                let (first, rest) = points.split_first()?;
+                let mut commands = vec![PathCommand::MoveTo(*first)];
+                commands.extend(rest.iter().map(|&p| PathCommand::LineTo(p)));
+                commands.push(PathCommand::Close);
+                commands
+            }
+            ShapeGeometry::Rectangle { width, height, corner_radii } => {
+                arcs_to_cubics(&corner_radii.outline(*width, *height))
+            }
+            ShapeGeometry::Ellipse { rx, ry } => ellipse_commands(*rx, *ry),
+            ShapeGeometry::Path { commands } => commands.clone(),
+            ShapeGeometry::Line { start, end } => vec![PathCommand::MoveTo(*start), PathCommand::LineTo(*end)],
+            ShapeGeometry::Text { .. } | ShapeGeometry::Image { .. } => return None,
+        };
+        Some(commands)
+    }
+}
+
+/// Control point distance, as a fraction of the radius, for a cubic through a quarter circle
+const QUARTER_CIRCLE_KAPPA: f32 = 0.552_284_8;
+
+/// An ellipse centered on the origin as four cubics, clockwise from the positive x axis
+fn ellipse_commands(rx: f32, ry: f32) -> Vec<PathCommand> {
+    let (kx, ky) = (rx * QUARTER_CIRCLE_KAPPA, ry * QUARTER_CIRCLE_KAPPA);
+    let mut commands = vec![PathCommand::MoveTo(Vec2::new(rx, 0.0))];
+    commands.extend([
+        (Vec2::new(rx, ky), Vec2::new(kx, ry), Vec2::new(0.0, ry)),
+        (Vec2::new(-kx, ry), Vec2::new(-rx, ky), Vec2::new(-rx, 0.0)),
+        (Vec2::new(-rx, -ky), Vec2::new(-kx, -ry), Vec2::new(0.0, -ry)),
+        (Vec2::new(kx, -ry), Vec2::new(rx, -ky), Vec2::new(rx, 0.0)),
+    ]
+    .map(|(ctrl1, ctrl2, to)| PathCommand::CubicTo { ctrl1, ctrl2, to }));
+    commands.push(PathCommand::Close);
+    commands
+}
+
+/// Parameters in (0, 1) where a·t² + b·t + c = 0
+fn unit_roots(a: f32, b: f32, c: f32) -> Vec<f32> {
+    let roots = if a.abs() <= f32::EPSILON * (b.abs() + c.abs()) {
+        if b == 0.0 { vec![] } else { vec![-c / b] }
+    } else {
+        let discriminant = b * b - 4.0 * a * c;
+        if discriminant < 0.0 {
+            vec![]
+        } else {
+            // Avoids cancelling b against the root when a is small
+            let q = -0.5 * (b + b.signum() * discriminant.sqrt());
+            if q == 0.0 { vec![0.0] } else { vec![q / a, c / q] }
+        }
+    };
+    roots.into_iter().filter(|t| *t > 0.0 && *t < 1.0).collect()
+}
+
+/// Points inside a quadratic bezier where it turns along x or y
+fn quadratic_extrema(p0: Vec2, p1: Vec2, p2: Vec2) -> Vec<Vec2> {
+    // B'(t) = 2[(p1 - p0) + t(p0 - 2p1 + p2)]
+    let a = p0 - 2.0 * p1 + p2;
+    let c = p1 - p0;
+    [(a.x, c.x), (a.y, c.y)]
+        .into_iter()
+        .flat_map(|(a, c)| unit_roots(0.0, a, c))
+        .map(|t| {
+            let u = 1.0 - t;
+            p0 * (u * u) + p1 * (2.0 * u * t) + p2 * (t * t)
+        })
+        .collect()
+}
+
+/// Points inside a cubic bezier where it turns along x or y
+fn cubic_extrema(p0: Vec2, p1: Vec2, p2: Vec2, p3: Vec2) -> Vec<Vec2> {
+    // B'(t) / 3 = t²(p3 - 3p2 + 3p1 - p0) + 2t(p0 - 2p1 + p2) + (p1 - p0)
+    let a = p3 - 3.0 * p2 + 3.0 * p1 - p0;
+    let b = 2.0 * (p0 - 2.0 * p1 + p2);
+    let c = p1 - p0;
+    [(a.x, b.x, c.x), (a.y, b.y, c.y)]
+        .into_iter()
+        .flat_map(|(a, b, c)| unit_roots(a, b, c))
+        .map(|t| {
+            let u = 1.0 - t;
+            p0 * (u * u * u) + p1 * (3.0 * u * u * t) + p2 * (3.0 * u * t * t) + p3 * (t * t * t)
+        })
+        .collect()
+}
+
+/// `commands` with every arc replaced by cubics (or a line, when the arc is degenerate)
+/// Unlike arcs, cubics stay exact under any transform of their points
+fn arcs_to_cubics(commands: &[PathCommand]) -> Vec<PathCommand> {
+    let mut result = Vec::with_capacity(commands.len());
+    let mut current = Vec2::ZERO;
+    let mut subpath_start = Vec2::ZERO;
+    for command in commands {
+        match command {
+            PathCommand::MoveTo(p) => {
+                subpath_start = *p;
+                current = *p;
+            }
+            PathCommand::LineTo(to) | PathCommand::QuadraticTo { to, .. } | PathCommand::CubicTo { to, .. } => {
+                current = *to;
+            }
+            PathCommand::ArcTo { rx, ry, x_rotation, large_arc, sweep, to } => {
+                let cubics = arc_to_cubics(current, *rx, *ry, *x_rotation, *large_arc, *sweep, *to);
+                if cubics.is_empty() {
+                    result.push(PathCommand::LineTo(*to));
+                }
+                result.extend(cubics.into_iter().map(|(ctrl1, ctrl2, to)| PathCommand::CubicTo { ctrl1, ctrl2, to }));
+                current = *to;
+                continue;
+            }
+            PathCommand::Close => current = subpath_start,
+        }
+        result.push(command.clone());
+    }
+    result
+}
+
+/// `commands` with every point mapped through `map`; arcs must already be cubics
+fn map_commands(commands: &[PathCommand], map: impl Fn(Vec2) -> Vec2) -> Vec<PathCommand> {
+    let map = |p: &Vec2| map(*p);
+    commands
+        .iter()
+        .map(|command| match command {
+            PathCommand::MoveTo(p) => PathCommand::MoveTo(map(p)),
+            PathCommand::LineTo(p) => PathCommand::LineTo(map(p)),
+            PathCommand::QuadraticTo { control, to } => PathCommand::QuadraticTo { control: map(control), to: map(to) },
+            PathCommand::CubicTo { ctrl1, ctrl2, to } => {
+                PathCommand::CubicTo { ctrl1: map(ctrl1), ctrl2: map(ctrl2), to: map(to) }
+            }
+            PathCommand::ArcTo { .. } | PathCommand::Close => command.clone(),
+        })
+        .collect()
+}
+
+fn default_visible() -> bool {
+    true
 }
 
 /// A shape in the scene graph
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Shape {
     /// Unique identifier
     pub id: u64,
@@ -215,7 +744,16 @@ pub struct Shape {
     /// Visual style (fill and stroke)
     pub style: ShapeStyle,
 
+    /// Hidden shapes are not rendered and can't be hit or selected on the canvas
+    #[serde(default = "default_visible")]
+    pub visible: bool,
+
+    /// Locked shapes render normally but can't be hit or selected on the canvas
+    #[serde(default)]
+    pub locked: bool,
+
     /// Whether this shape needs to be re-tessellated
+    #[serde(skip)]
     pub dirty: bool,
 }
 
@@ -229,6 +767,8 @@ impl Shape {
             geometry,
             transform: Transform2D::identity(),
             style,
+            visible: true,
+            locked: false,
             dirty: true,
         }
     }
@@ -242,10 +782,17 @@ impl Shape {
             geometry,
             transform: Transform2D::identity(),
             style,
+            visible: true,
+            locked: false,
             dirty: true,
         }
     }
 
+    /// Whether canvas interactions (hit testing, marquee selection) can reach this shape
+    pub fn is_interactive(&self) -> bool {
+        self.visible && !self.locked
+    }
+
     /// Set a custom name
     pub fn with_name(mut self, name: String) -> Self {
         self.name = name;
@@ -258,9 +805,22 @@ impl Shape {
         self
     }
 
+    /// Get the local bounding box (before transform)
+    /// Lines are expanded by their stroke width so they stay selectable when axis-aligned
+    pub fn local_bounds(&self) -> BBox {
+        let bounds = self.geometry.local_bounds();
+        match (&self.geometry, &self.style.stroke) {
+            (ShapeGeometry::Line { .. }, Some(stroke)) => bounds.expand(stroke.width),
+            _ => bounds,
+        }
+    }
+
     /// Get the world-space bounding box
     pub fn world_bounds(&self) -> BBox {
-        let local = self.geometry.local_bounds();
+        if let Some(points) = self.geometry.ring_points() {
+            return BBox::from_transform_polygon(&points, &self.transform);
+        }
+        let local = self.local_bounds();
 
         // Transform the corners of the local bounding box
         let corners = [
@@ -275,6 +835,97 @@ impl Shape {
         BBox::from_points(&corners).unwrap()
     }
 
+    /// Explicit vertices in world coordinates (see `ShapeGeometry::vertices`)
+    pub fn world_vertices(&self) -> Vec<Vec2> {
+        self.geometry
+            .vertices()
+            .into_iter()
+            .map(|p| self.transform.transform_point(p))
+            .collect()
+    }
+
+    /// Scale the shape in its local space by `scale`
+    /// Text keeps its proportions: the axis that changed most scales the font size
+    pub fn scale_by(&mut self, scale: Vec2) {
+        match &mut self.geometry {
+            ShapeGeometry::Text { font_size, .. } => {
+                let factor = if scale.x.abs().ln().abs() > scale.y.abs().ln().abs() { scale.x } else { scale.y };
+                *font_size = (*font_size * factor.abs()).max(1.0);
+            }
+            _ => self.transform.scale *= scale,
+        }
+    }
+
+    /// This shape with its geometry converted to an editable path, keeping its transform
+    /// None for text and images (see `ShapeGeometry::to_path_commands`)
+    pub fn to_path(&self) -> Option<Shape> {
+        let mut shape = self.clone();
+        shape.geometry = ShapeGeometry::Path { commands: self.geometry.to_path_commands()? };
+        shape.mark_dirty();
+        Some(shape)
+    }
+
+    /// Like `to_path`, with the transform baked into the path's points and reset to
+    /// identity, so the path's coordinates are where it's drawn; the stroke is scaled
+    /// to match (see `apply_transform`)
+    pub fn flattened(&self) -> Option<Shape> {
+        self.flattened_with(|p| p)
+    }
+
+    /// Like `flattened`, with every point also mapped through `map` after the shape's
+    /// transform, e.g. by an enclosing transform that `Transform2D` can't express
+    /// Only the shape's own transform scales the stroke; callers account for `map`
+    pub fn flattened_with(&self, map: impl Fn(Vec2) -> Vec2) -> Option<Shape> {
+        let commands = arcs_to_cubics(&self.geometry.to_path_commands()?);
+        let mut shape = self.clone();
+        shape.geometry = ShapeGeometry::Path {
+            commands: map_commands(&commands, |p| map(self.transform.transform_point(p))),
+        };
+        if let Some(stroke) = &mut shape.style.stroke {
+            stroke.scale_by(self.transform.length_scale());
+        }
+        shape.transform = Transform2D::identity();
+        shape.mark_dirty();
+        Some(shape)
+    }
+
+    /// Bake the transform into the geometry's coordinates and reset it to identity, so
+    /// the shape's points are where it's drawn
+    /// Lines stay lines; polygons, stars, regular polygons and sharp rectangles become
+    /// polygons; ellipses, rounded rectangles and paths become paths (see `flattened`).
+    /// Text and images have no points to move and keep their transform. The stroke is
+    /// scaled with the shape so it's drawn as before; an identity transform changes nothing
+    pub fn apply_transform(&mut self) {
+        let transform = self.transform;
+        if transform.is_identity() {
+            return;
+        }
+        let ring = match &self.geometry {
+            ShapeGeometry::Rectangle { width, height, corner_radii } if corner_radii.is_zero() => Some(vec![
+                Vec2::ZERO,
+                Vec2::new(*width, 0.0),
+                Vec2::new(*width, *height),
+                Vec2::new(0.0, *height),
+            ]),
+            geometry => geometry.ring_points(),
+        };
+        self.geometry = match (ring, &self.geometry) {
+            (Some(points), _) => ShapeGeometry::polygon(points.into_iter().map(|p| transform.transform_point(p)).collect()),
+            (None, ShapeGeometry::Line { start, end }) => {
+                ShapeGeometry::line(transform.transform_point(*start), transform.transform_point(*end))
+            }
+            (None, _) => match self.flattened() {
+                Some(flat) => flat.geometry,
+                None => return,
+            },
+        };
+        if let Some(stroke) = &mut self.style.stroke {
+            stroke.scale_by(transform.length_scale());
+        }
+        self.transform = Transform2D::identity();
+        self.mark_dirty();
+    }
+
     /// Mark this shape as needing re-tessellation
     pub fn mark_dirty(&mut self) {
         self.dirty = true;
@@ -304,35 +955,37 @@ impl From<&Polygon> for Shape {
         let points = parse_svg_points(&polygon.points);
         let geometry = ShapeGeometry::Polygon { points };
 
-        let fill = Color::from_hex(&polygon.fill);
-        let stroke = Color::from_hex(&polygon.stroke);
+        let fill = Color::parse(&polygon.fill);
+        let stroke = Color::parse(&polygon.stroke);
 
-        let style = ShapeStyle {
+        let style = ShapeStyle::new(
             fill,
-            stroke: stroke.map(|color| StrokeStyle::new(color, polygon.stroke_width as f32)),
-        };
+            stroke.map(|color| StrokeStyle::new(color, polygon.stroke_width as f32)),
+        );
 
         Shape::new(geometry, style)
     }
 }
 
 /// Convert back to the old Polygon type (for compatibility during migration)
+/// Stars and regular polygons export as the polygon of their vertices
 impl From<&Shape> for Option<Polygon> {
     fn from(shape: &Shape) -> Self {
-        match &shape.geometry {
-            ShapeGeometry::Polygon { points } => {
-                let points_str = stringify_points(points, &shape.transform);
+        match shape.geometry.ring_points() {
+            Some(points) => {
+                let points_str = stringify_points(&points, &shape.transform);
                 let fill = shape
                     .style
-                    .fill
-                    .map(|c| c.to_hex())
+                    .fill_color()
+                    .map(|c| c.to_css())
                     .unwrap_or_else(|| "#000000".to_string());
                 let stroke = shape
                     .style
                     .stroke
-                    .map(|s| s.color.to_hex())
+                    .as_ref()
+                    .map(|s| s.color.to_css())
                     .unwrap_or_else(|| "#000000".to_string());
-                let stroke_width = shape.style.stroke.map(|s| s.width as f64).unwrap_or(1.0);
+                let stroke_width = shape.style.stroke.as_ref().map(|s| s.width as f64).unwrap_or(1.0);
 
                 Some(Polygon::new(points_str, fill, stroke, stroke_width))
             }
@@ -371,6 +1024,20 @@ pub fn stringify_points(points: &[Vec2], transform: &Transform2D) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_polygon_conversion_keeps_named_and_translucent_colors() {
+        // The same strings the SVG renderer accepts, so GPU mode draws the same stroke
+        let polygon = Polygon::new("0,0 30,0 15,30".to_string(), "#ff000080".to_string(), "black".to_string(), 2.0);
+        let shape = Shape::from(&polygon);
+        assert_eq!(shape.style.fill_color(), Some(Color::from_rgba8(255, 0, 0, 128)));
+        assert_eq!(shape.style.stroke, Some(StrokeStyle::new(Color::black(), 2.0)));
+
+        // Alpha survives the trip back
+        let back: Option<Polygon> = (&shape).into();
+        let back = Shape::from(&back.unwrap());
+        assert_eq!(back.style.fill_color().map(|c| c.to_css()), shape.style.fill_color().map(|c| c.to_css()));
+    }
+
     #[test]
     fn test_parse_svg_points() {
         let points = parse_svg_points("230,220 260,220 245,250");
@@ -404,6 +1071,80 @@ mod tests {
         assert_eq!(bounds.max, Vec2::new(30.0, 30.0));
     }
 
+    #[test]
+    fn test_rotated_polygon_world_bounds_use_vertices() {
+        // A diamond turned 45° about its center becomes an upright square
+        let geometry = ShapeGeometry::polygon(vec![
+            Vec2::new(10.0, 0.0),
+            Vec2::new(20.0, 10.0),
+            Vec2::new(10.0, 20.0),
+            Vec2::new(0.0, 10.0),
+        ]);
+        let mut shape = Shape::new(geometry, ShapeStyle::default());
+        shape.transform = Transform2D::identity()
+            .with_anchor(Vec2::new(10.0, 10.0))
+            .with_rotation(std::f32::consts::FRAC_PI_4);
+
+        let half = 5.0 * std::f32::consts::SQRT_2;
+        let bounds = shape.world_bounds();
+        assert!((bounds.min - Vec2::splat(10.0 - half)).length() < 1e-4);
+        assert!((bounds.max - Vec2::splat(10.0 + half)).length() < 1e-4);
+    }
+
+    #[test]
+    fn test_corner_radii_that_fit_are_kept() {
+        let radii = CornerRadii::new(10.0, 0.0, 20.0, 5.0);
+        assert_eq!(radii.clamped(100.0, 50.0), radii);
+        assert_eq!(CornerRadii::uniform(-4.0).clamped(100.0, 50.0), CornerRadii::default());
+    }
+
+    #[test]
+    fn test_oversized_corner_radii_shrink_together() {
+        // The left side needs 40 + 60 = 100 but is only 50 tall, so everything halves
+        let radii = CornerRadii::new(40.0, 10.0, 20.0, 60.0).clamped(200.0, 50.0);
+        assert_eq!(radii, CornerRadii::new(20.0, 5.0, 10.0, 30.0));
+
+        // A uniform radius larger than the rectangle becomes half its shorter side
+        assert_eq!(CornerRadii::uniform(100.0).clamped(80.0, 25.0), CornerRadii::uniform(12.5));
+    }
+
+    #[test]
+    fn test_corner_radii_on_zero_size_rectangle() {
+        assert_eq!(CornerRadii::uniform(10.0).clamped(0.0, 0.0), CornerRadii::default());
+        assert_eq!(CornerRadii::new(0.0, 5.0, 0.0, 0.0).clamped(0.0, 20.0), CornerRadii::default());
+
+        // The outline collapses to a point without arcs
+        let outline = CornerRadii::uniform(10.0).outline(0.0, 0.0);
+        assert!(outline.iter().all(|command| !matches!(command, PathCommand::ArcTo { .. })));
+        assert_eq!(outline.first(), Some(&PathCommand::MoveTo(Vec2::ZERO)));
+    }
+
+    #[test]
+    fn test_outline_arcs_only_rounded_corners() {
+        let outline = CornerRadii::new(10.0, 0.0, 0.0, 0.0).outline(100.0, 50.0);
+        let arcs: Vec<_> = outline
+            .iter()
+            .filter_map(|command| match command {
+                PathCommand::ArcTo { rx, to, .. } => Some((*rx, *to)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(arcs, vec![(10.0, Vec2::new(10.0, 0.0))]);
+        assert_eq!(outline[0], PathCommand::MoveTo(Vec2::new(10.0, 0.0)));
+        assert_eq!(outline.last(), Some(&PathCommand::Close));
+    }
+
+    #[test]
+    fn test_rectangle_reads_single_corner_radius() {
+        let old: ShapeGeometry =
+            serde_json::from_str(r#"{"Rectangle":{"width":10.0,"height":20.0,"corner_radius":4.0}}"#).unwrap();
+        assert_eq!(old, ShapeGeometry::rounded_rectangle(10.0, 20.0, 4.0));
+
+        let geometry = ShapeGeometry::rectangle_with_radii(10.0, 20.0, CornerRadii::new(1.0, 2.0, 3.0, 4.0));
+        let json = serde_json::to_string(&geometry).unwrap();
+        assert_eq!(serde_json::from_str::<ShapeGeometry>(&json).unwrap(), geometry);
+    }
+
     #[test]
     fn test_rectangle_local_bounds() {
         let geometry = ShapeGeometry::rectangle(100.0, 50.0);
@@ -419,4 +1160,399 @@ mod tests {
         assert_eq!(bounds.min, Vec2::new(-20.0, -10.0));
         assert_eq!(bounds.max, Vec2::new(20.0, 10.0));
     }
+
+    fn assert_near(actual: Vec2, expected: Vec2) {
+        assert!(actual.distance(expected) < 1e-2, "{actual:?} != {expected:?}");
+    }
+
+    #[test]
+    fn test_cubic_bounds_hug_the_curve() {
+        // Both controls sit far outside the end points, above and to either side
+        let geometry = ShapeGeometry::Path {
+            commands: vec![
+                PathCommand::MoveTo(Vec2::ZERO),
+                PathCommand::CubicTo { ctrl1: Vec2::new(-50.0, -100.0), ctrl2: Vec2::new(150.0, -100.0), to: Vec2::new(100.0, 0.0) },
+            ],
+        };
+        let bounds = geometry.local_bounds();
+
+        // x turns at t = (1 ± √0.6) / 2, y at t = 0.5
+        let t = (1.0 - 0.6f32.sqrt()) / 2.0;
+        let u = 1.0 - t;
+        let overhang = 3.0 * u * u * t * 50.0 - 3.0 * u * t * t * 150.0 - t * t * t * 100.0;
+        assert_near(bounds.min, Vec2::new(-overhang, -75.0));
+        assert_near(bounds.max, Vec2::new(100.0 + overhang, 0.0));
+
+        // Matches a dense sampling of the curve
+        let sampled: Vec<Vec2> = (0..=1000)
+            .map(|i| {
+                let t = i as f32 / 1000.0;
+                let u = 1.0 - t;
+                Vec2::new(-50.0, -100.0) * (3.0 * u * u * t) + Vec2::new(150.0, -100.0) * (3.0 * u * t * t) + Vec2::new(100.0, 0.0) * (t * t * t)
+            })
+            .collect();
+        let sampled = BBox::from_points(&sampled).unwrap();
+        assert_near(bounds.min, sampled.min);
+        assert_near(bounds.max, sampled.max);
+    }
+
+    #[test]
+    fn test_quadratic_and_arc_bounds_hug_the_curve() {
+        let quadratic = ShapeGeometry::Path {
+            commands: vec![
+                PathCommand::MoveTo(Vec2::ZERO),
+                PathCommand::QuadraticTo { control: Vec2::new(50.0, 100.0), to: Vec2::new(100.0, 0.0) },
+            ],
+        };
+        let bounds = quadratic.local_bounds();
+        assert_near(bounds.min, Vec2::ZERO);
+        assert_near(bounds.max, Vec2::new(100.0, 50.0));
+
+        // A half circle bulges to one side of its chord only
+        let arc = ShapeGeometry::Path {
+            commands: vec![
+                PathCommand::MoveTo(Vec2::ZERO),
+                PathCommand::ArcTo { rx: 50.0, ry: 50.0, x_rotation: 0.0, large_arc: false, sweep: true, to: Vec2::new(100.0, 0.0) },
+            ],
+        };
+        let bounds = arc.local_bounds();
+        assert!((bounds.width() - 100.0).abs() < 1e-2);
+        assert!((bounds.height() - 50.0).abs() < 0.1);
+
+        // Straight segments keep their end point bounds
+        let lines = ShapeGeometry::Path {
+            commands: vec![PathCommand::MoveTo(Vec2::new(10.0, 5.0)), PathCommand::LineTo(Vec2::new(-10.0, 20.0)), PathCommand::Close],
+        };
+        assert_eq!(lines.local_bounds(), BBox::new(Vec2::new(-10.0, 5.0), Vec2::new(10.0, 20.0)));
+    }
+
+    #[test]
+    fn test_line_local_bounds_expanded_by_stroke() {
+        let geometry = ShapeGeometry::line(Vec2::new(40.0, 10.0), Vec2::new(0.0, 10.0));
+        let bounds = geometry.local_bounds();
+        assert_eq!(bounds.min, Vec2::new(0.0, 10.0));
+        assert_eq!(bounds.max, Vec2::new(40.0, 10.0));
+
+        let shape = Shape::new(geometry, ShapeStyle::stroke_only(StrokeStyle::new(Color::black(), 2.0)));
+        let bounds = shape.local_bounds();
+        assert_eq!(bounds.min, Vec2::new(-2.0, 8.0));
+        assert_eq!(bounds.max, Vec2::new(42.0, 12.0));
+    }
+
+    #[test]
+    fn test_pie_slice_commands() {
+        let geometry = ShapeGeometry::pie_slice(PieSlice::new(10.0, 0.0, 90.0));
+        let ShapeGeometry::Path { commands } = &geometry else {
+            panic!("pie slice should be a path");
+        };
+        assert_eq!(commands.len(), 5);
+        assert_eq!(commands[0], PathCommand::MoveTo(Vec2::ZERO));
+        assert_eq!(commands[1], PathCommand::LineTo(Vec2::new(10.0, 0.0)));
+        let PathCommand::ArcTo { rx, ry, large_arc, sweep, to, .. } = commands[2] else {
+            panic!("expected an arc, got {:?}", commands[2]);
+        };
+        assert_eq!((rx, ry), (10.0, 10.0));
+        assert!(!large_arc);
+        assert!(sweep);
+        assert!((to - Vec2::new(0.0, 10.0)).length() < 1e-4);
+        assert_eq!(commands[3], PathCommand::LineTo(Vec2::ZERO));
+        assert_eq!(commands[4], PathCommand::Close);
+    }
+
+    #[test]
+    fn test_pie_slice_large_arc_and_round_trip() {
+        let slice = PieSlice::new(25.0, -45.0, 225.0);
+        let geometry = ShapeGeometry::pie_slice(slice);
+        let ShapeGeometry::Path { commands } = &geometry else {
+            panic!("pie slice should be a path");
+        };
+        assert!(matches!(commands[2], PathCommand::ArcTo { large_arc: true, .. }));
+
+        let parsed = geometry.as_pie_slice().expect("should parse back");
+        assert!((parsed.radius - 25.0).abs() < 1e-4);
+        assert!((parsed.start_angle + 45.0).abs() < 1e-3);
+        assert!((parsed.end_angle - 225.0).abs() < 1e-3);
+
+        // A full turn keeps the end point distinct from the start point
+        assert!(PieSlice::new(10.0, 30.0, 390.0).sweep() < 360.0);
+        assert_eq!(ShapeGeometry::rectangle(1.0, 1.0).as_pie_slice(), None);
+    }
+
+    #[test]
+    fn test_scaling_text_scales_its_font_size() {
+        let mut text = Shape::new(ShapeGeometry::text("Label", 20.0), ShapeStyle::default());
+        text.scale_by(Vec2::new(1.0, 2.0));
+        assert_eq!(text.transform.scale, Vec2::ONE);
+        assert!(matches!(text.geometry, ShapeGeometry::Text { font_size, .. } if font_size == 40.0));
+
+        // The axis that changed most wins, and flips don't mirror the text
+        text.scale_by(Vec2::new(-0.5, 1.1));
+        assert!(matches!(text.geometry, ShapeGeometry::Text { font_size, .. } if font_size == 20.0));
+
+        let mut rect = Shape::new(ShapeGeometry::rectangle(10.0, 10.0), ShapeStyle::default());
+        rect.scale_by(Vec2::new(1.0, 2.0));
+        assert_eq!(rect.transform.scale, Vec2::new(1.0, 2.0));
+    }
+
+    #[test]
+    fn test_smooth_polygon_passes_through_vertices() {
+        let square = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(60.0, 0.0),
+            Vec2::new(60.0, 60.0),
+            Vec2::new(0.0, 60.0),
+        ];
+        let commands = smooth_polygon(&square, 1.0);
+        assert_eq!(commands.len(), 6);
+        assert_eq!(commands[0], PathCommand::MoveTo(square[0]));
+        assert_eq!(commands[5], PathCommand::Close);
+
+        // Each edge ends on the next vertex, leaving with the Catmull-Rom tangent
+        for (i, command) in commands[1..5].iter().enumerate() {
+            let PathCommand::CubicTo { ctrl1, to, .. } = *command else {
+                panic!("expected a cubic, got {:?}", command);
+            };
+            assert_eq!(to, square[(i + 1) % 4]);
+            if i == 0 {
+                // From (0,0): (next - prev) / 6 = ((60,0) - (0,60)) / 6
+                assert_eq!(ctrl1, Vec2::new(10.0, -10.0));
+            }
+        }
+
+        // Zero tension keeps the edges straight
+        for command in &smooth_polygon(&square, 0.0)[1..5] {
+            let PathCommand::CubicTo { ctrl1, ctrl2, to } = *command else {
+                panic!("expected a cubic, got {:?}", command);
+            };
+            assert_eq!(ctrl2, to);
+            assert!(square.contains(&ctrl1));
+        }
+
+        assert!(smooth_polygon(&square[..2], 1.0).is_empty());
+    }
+
+    #[test]
+    fn test_smooth_polygon_round_trip() {
+        let points = vec![Vec2::new(0.0, 0.0), Vec2::new(100.0, 20.0), Vec2::new(40.0, 90.0)];
+        for tension in [0.0, 0.35, 1.0] {
+            let geometry = ShapeGeometry::smooth_polygon(&points, tension);
+            let (parsed, parsed_tension) = geometry.as_smooth_polygon().expect("should parse back");
+            assert_eq!(parsed, points);
+            assert!((parsed_tension - tension).abs() < 1e-4);
+        }
+
+        // Other closed cubic paths are not mistaken for smoothed polygons
+        let mut commands = smooth_polygon(&points, 0.5);
+        if let PathCommand::CubicTo { ctrl2, .. } = &mut commands[2] {
+            *ctrl2 += Vec2::new(5.0, 5.0);
+        }
+        assert_eq!(ShapeGeometry::Path { commands }.as_smooth_polygon(), None);
+        assert_eq!(ShapeGeometry::pie_slice(PieSlice::new(10.0, 0.0, 90.0)).as_smooth_polygon(), None);
+    }
+
+    #[test]
+    fn test_star_bounds_and_vertices_come_from_its_parameters() {
+        let star = ShapeGeometry::star(5, 50.0, 20.0);
+        let vertices = star.vertices();
+        assert_eq!(vertices.len(), 10);
+        // Tips on the outer radius, notches on the inner one, starting at the top
+        assert!(vertices[0].distance(Vec2::new(0.0, -50.0)) < 1e-4);
+        assert!(vertices.iter().step_by(2).all(|v| (v.length() - 50.0).abs() < 1e-4));
+        assert!(vertices.iter().skip(1).step_by(2).all(|v| (v.length() - 20.0).abs() < 1e-4));
+
+        let bounds = star.local_bounds();
+        assert!((bounds.min.y + 50.0).abs() < 1e-4);
+        assert!((bounds.max.x - 50.0 * (0.4 * std::f32::consts::PI).sin()).abs() < 1e-3);
+
+        let shape = Shape::new(star, ShapeStyle::default())
+            .with_transform(Transform2D::from_position(Vec2::new(100.0, 100.0)));
+        assert!((shape.world_bounds().min.y - 50.0).abs() < 1e-4);
+        assert!(shape.name.starts_with("Star "));
+    }
+
+    #[test]
+    fn test_regular_polygon_vertices() {
+        let square = regular_polygon_vertices(4, 10.0);
+        let expected = [Vec2::new(0.0, -10.0), Vec2::new(10.0, 0.0), Vec2::new(0.0, 10.0), Vec2::new(-10.0, 0.0)];
+        assert!(square.iter().zip(expected).all(|(a, b)| a.distance(b) < 1e-4));
+        assert!(regular_polygon_vertices(0, 10.0).is_empty());
+
+        let hexagon = ShapeGeometry::regular_polygon(6, 30.0);
+        assert_eq!(hexagon.ring_points().map(|points| points.len()), Some(6));
+        let polygon: Option<Polygon> = (&Shape::new(hexagon, ShapeStyle::default())).into();
+        assert_eq!(polygon.map(|polygon| polygon.points.split_whitespace().count()), Some(6));
+    }
+
+    #[test]
+    fn test_star_and_regular_polygon_serde_round_trip() {
+        for geometry in [ShapeGeometry::star(7, 40.0, 15.0), ShapeGeometry::regular_polygon(8, 25.0)] {
+            let json = serde_json::to_string(&geometry).unwrap();
+            assert_eq!(serde_json::from_str::<ShapeGeometry>(&json).unwrap(), geometry);
+        }
+    }
+
+    fn assert_bounds_match(before: BBox, after: BBox) {
+        assert!(
+            before.min.distance(after.min) < 1e-3 && before.max.distance(after.max) < 1e-3,
+            "{before:?} != {after:?}"
+        );
+    }
+
+    #[test]
+    fn test_to_path_keeps_the_outline_and_transform() {
+        let transform = Transform2D::from_position(Vec2::new(30.0, 40.0)).with_scale(Vec2::new(2.0, 0.5));
+        for geometry in [
+            ShapeGeometry::rectangle(100.0, 50.0),
+            ShapeGeometry::rounded_rectangle(100.0, 50.0, 10.0),
+            ShapeGeometry::ellipse(40.0, 20.0),
+            ShapeGeometry::star(5, 50.0, 20.0),
+            ShapeGeometry::polygon(vec![Vec2::ZERO, Vec2::new(10.0, 0.0), Vec2::new(5.0, 8.0)]),
+        ] {
+            let shape = Shape::new(geometry, ShapeStyle::default()).with_transform(transform);
+            let path = shape.to_path().unwrap();
+            assert!(matches!(path.geometry, ShapeGeometry::Path { .. }));
+            assert_eq!(path.id, shape.id);
+            assert_eq!(path.transform, shape.transform);
+            assert_bounds_match(shape.world_bounds(), path.world_bounds());
+        }
+
+        // Rounded corners become cubics, so nothing is left as an arc
+        let rounded = ShapeGeometry::rounded_rectangle(100.0, 50.0, 10.0).to_path_commands().unwrap();
+        assert_eq!(rounded.iter().filter(|c| matches!(c, PathCommand::CubicTo { .. })).count(), 4);
+        assert!(!rounded.iter().any(|c| matches!(c, PathCommand::ArcTo { .. })));
+
+        let ellipse = ShapeGeometry::circle(10.0).to_path_commands().unwrap();
+        assert_eq!(ellipse.iter().filter(|c| matches!(c, PathCommand::CubicTo { .. })).count(), 4);
+        // The first cubic's midpoint lies on the circle, within the approximation's error
+        if let PathCommand::CubicTo { ctrl1, ctrl2, to } = ellipse[1] {
+            let mid = (Vec2::new(10.0, 0.0) + ctrl1 * 3.0 + ctrl2 * 3.0 + to) / 8.0;
+            assert!((mid.length() - 10.0).abs() < 0.01);
+        }
+
+        assert!(ShapeGeometry::text("Label", 12.0).to_path_commands().is_none());
+        assert!(ShapeGeometry::image(1.0, 1.0, "data:").to_path_commands().is_none());
+    }
+
+    #[test]
+    fn test_flatten_bakes_the_transform_and_keeps_world_bounds() {
+        let transforms = [
+            Transform2D::from_position(Vec2::new(100.0, 50.0)).with_scale(Vec2::new(1.5, 3.0)),
+            Transform2D::new(Vec2::new(20.0, 10.0), Vec2::new(2.0, 1.0), 0.7, Vec2::new(50.0, 25.0)),
+        ];
+        for transform in transforms {
+            for geometry in [
+                ShapeGeometry::rectangle(100.0, 50.0),
+                ShapeGeometry::star(5, 50.0, 20.0),
+                ShapeGeometry::regular_polygon(6, 30.0),
+            ] {
+                let shape = Shape::new(geometry, ShapeStyle::default()).with_transform(transform);
+                let flat = shape.flattened().unwrap();
+                assert_eq!(flat.transform, Transform2D::identity());
+                assert_bounds_match(shape.world_bounds(), flat.world_bounds());
+            }
+        }
+
+        // Curves and lines are bounded by a rotated box, so only compare them unrotated
+        let transform = transforms[0];
+        for geometry in [
+            ShapeGeometry::ellipse(40.0, 20.0),
+            ShapeGeometry::rounded_rectangle(100.0, 50.0, 10.0),
+            ShapeGeometry::line(Vec2::ZERO, Vec2::new(40.0, 30.0)),
+        ] {
+            let shape = Shape::new(geometry, ShapeStyle::default()).with_transform(transform);
+            assert_bounds_match(shape.world_bounds(), shape.flattened().unwrap().world_bounds());
+        }
+
+        // Arcs in paths are baked as cubics, which stay exact under rotation
+        let pie = Shape::new(ShapeGeometry::pie_slice(PieSlice::new(20.0, 0.0, 90.0)), ShapeStyle::default())
+            .with_transform(transforms[1]);
+        let ShapeGeometry::Path { commands } = pie.flattened().unwrap().geometry else {
+            panic!("flattened shapes are paths");
+        };
+        assert!(!commands.iter().any(|c| matches!(c, PathCommand::ArcTo { .. })));
+    }
+
+    #[test]
+    fn test_flatten_scales_the_stroke_with_the_shape() {
+        let stroke = StrokeStyle::new(Color::black(), 3.0).with_dash_pattern(Some(vec![4.0, 2.0]));
+        let shape = Shape::new(ShapeGeometry::ellipse(40.0, 20.0), ShapeStyle::stroke_only(stroke))
+            .with_transform(Transform2D::new(Vec2::new(5.0, 5.0), Vec2::new(3.0, 12.0), 0.5, Vec2::ZERO));
+        let before = shape.style.stroke.as_ref().unwrap();
+        let flat = shape.flattened().unwrap();
+        let after = flat.style.stroke.as_ref().unwrap();
+        assert_eq!(after.width, before.width * 6.0);
+        assert_eq!(after.dash_pattern, Some(vec![24.0, 12.0]));
+
+        // An unscaled shape keeps its stroke as is
+        let moved = shape.clone().with_transform(Transform2D::from_position(Vec2::new(5.0, 5.0)).with_rotation(0.5));
+        assert_eq!(moved.flattened().unwrap().style.stroke, moved.style.stroke);
+    }
+
+    #[test]
+    fn test_apply_transform_round_trips_world_geometry() {
+        let transform = Transform2D::new(Vec2::new(20.0, 10.0), Vec2::new(2.0, 1.5), 0.7, Vec2::new(5.0, 5.0));
+        let baked = |geometry: ShapeGeometry| {
+            let shape = Shape::new(geometry, ShapeStyle::default()).with_transform(transform);
+            let mut applied = shape.clone();
+            applied.apply_transform();
+            (shape, applied)
+        };
+
+        // Polygon points land where they were drawn
+        let (shape, applied) = baked(ShapeGeometry::polygon(vec![Vec2::ZERO, Vec2::new(10.0, 0.0), Vec2::new(5.0, 8.0)]));
+        assert_eq!(applied.transform, Transform2D::identity());
+        assert_eq!(applied.geometry.polygon_points().unwrap(), shape.world_vertices());
+        assert_bounds_match(shape.world_bounds(), applied.world_bounds());
+
+        // A sharp rectangle becomes a polygon of its four world corners
+        let (shape, applied) = baked(ShapeGeometry::rectangle(100.0, 50.0));
+        let corners: Vec<Vec2> = [Vec2::ZERO, Vec2::new(100.0, 0.0), Vec2::new(100.0, 50.0), Vec2::new(0.0, 50.0)]
+            .into_iter()
+            .map(|p| transform.transform_point(p))
+            .collect();
+        assert_eq!(applied.geometry.polygon_points().unwrap(), &corners[..]);
+        assert_eq!(applied.transform, Transform2D::identity());
+        assert_bounds_match(shape.world_bounds(), applied.world_bounds());
+
+        // An ellipse becomes a path through its transformed extremes
+        let (_, applied) = baked(ShapeGeometry::ellipse(40.0, 20.0));
+        let on_curve: Vec<Vec2> = [Vec2::new(40.0, 0.0), Vec2::new(0.0, 20.0), Vec2::new(-40.0, 0.0), Vec2::new(0.0, -20.0)]
+            .into_iter()
+            .map(|p| transform.transform_point(p))
+            .collect();
+        let vertices = applied.geometry.vertices();
+        assert!(matches!(applied.geometry, ShapeGeometry::Path { .. }));
+        assert_eq!(applied.transform, Transform2D::identity());
+        for (vertex, expected) in vertices.iter().zip(&on_curve) {
+            assert!(vertex.distance(*expected) < 1e-3, "{vertex:?} != {expected:?}");
+        }
+
+        // Lines keep their kind; text has nothing to bake
+        let (shape, applied) = baked(ShapeGeometry::line(Vec2::ZERO, Vec2::new(40.0, 30.0)));
+        assert_eq!(applied.geometry.vertices(), shape.world_vertices());
+        let (shape, applied) = baked(ShapeGeometry::text("Label", 12.0));
+        assert_eq!((applied.geometry, applied.transform), (shape.geometry, shape.transform));
+    }
+
+    #[test]
+    fn test_apply_transform_scales_the_stroke_and_skips_identity() {
+        let stroke = StrokeStyle::new(Color::black(), 2.0).with_dash_pattern(Some(vec![6.0, 3.0])).with_dash_offset(1.0);
+        let mut shape = Shape::new(ShapeGeometry::rectangle(100.0, 50.0), ShapeStyle::stroke_only(stroke))
+            .with_transform(Transform2D::from_position(Vec2::new(10.0, 0.0)).with_scale(Vec2::new(2.0, 8.0)));
+        shape.apply_transform();
+        // Drawn 4× larger on average, so the outline keeps its on-screen weight
+        let stroke = shape.style.stroke.as_ref().unwrap();
+        assert_eq!(stroke.width, 8.0);
+        assert_eq!(stroke.dash_pattern, Some(vec![24.0, 12.0]));
+        assert_eq!(stroke.dash_offset, 4.0);
+
+        // Nothing to bake: the rectangle stays a rectangle
+        let mut plain = Shape::new(ShapeGeometry::rectangle(100.0, 50.0), ShapeStyle::stroke_only(StrokeStyle::new(Color::black(), 2.0)));
+        plain.clear_dirty();
+        let before = plain.clone();
+        plain.apply_transform();
+        assert_eq!(plain.geometry, before.geometry);
+        assert_eq!(plain.style, before.style);
+        assert!(!plain.dirty);
+    }
 }
@@ -0,0 +1,274 @@
+//! Uniform grid index over shape world bounds
+//!
+//! Each shape is registered in every cell its bounds overlap, so point and
+//! rectangle queries only look at shapes in nearby cells. Queries return
+//! candidates whose bounds intersect the query; callers do the exact test.
+//! Shapes marked stale (mutated in place, bounds unknown) are always returned
+//! as candidates until they are re-inserted. Shapes whose bounds would cover more
+//! than `MAX_SHAPE_CELLS` cells, or aren't finite, skip the grid and are checked
+//! against every query directly.
+
+use super::types::{BBox, Vec2};
+use std::collections::{HashMap, HashSet};
+
+/// Default cell size in canvas pixels
+pub const DEFAULT_CELL_SIZE: f32 = 128.0;
+/// Most cells one shape is registered in; larger shapes go in the oversized bucket
+pub const MAX_SHAPE_CELLS: i64 = 4096;
+
+type Cell = (i32, i32);
+
+#[derive(Clone, Debug)]
+pub struct SpatialIndex {
+    cell_size: f32,
+    cells: HashMap<Cell, Vec<u64>>,
+    bounds: HashMap<u64, BBox>,
+    stale: HashSet<u64>,
+    /// Shapes too large (or unbounded) to register cell by cell
+    oversized: HashSet<u64>,
+}
+
+impl Default for SpatialIndex {
+    fn default() -> Self {
+        Self::new(DEFAULT_CELL_SIZE)
+    }
+}
+
+/// The index is derived from the shapes, so it never makes two scenes unequal
+impl PartialEq for SpatialIndex {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl SpatialIndex {
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::new(),
+            bounds: HashMap::new(),
+            stale: HashSet::new(),
+            oversized: HashSet::new(),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.bounds.clear();
+        self.stale.clear();
+        self.oversized.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.bounds.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bounds.is_empty()
+    }
+
+    fn cell_of(&self, point: Vec2) -> Cell {
+        (
+            (point.x / self.cell_size).floor() as i32,
+            (point.y / self.cell_size).floor() as i32,
+        )
+    }
+
+    /// Cells overlapped by `bounds`, as an inclusive (min, max) range
+    fn cell_range(&self, bounds: &BBox) -> (Cell, Cell) {
+        (self.cell_of(bounds.min), self.cell_of(bounds.max))
+    }
+
+    /// Whether `bounds` is too large, or not finite, to register in every cell it covers
+    fn is_oversized(&self, bounds: &BBox) -> bool {
+        if !(bounds.min.is_finite() && bounds.max.is_finite()) {
+            return true;
+        }
+        let ((x0, y0), (x1, y1)) = self.cell_range(bounds);
+        (x1 as i64 - x0 as i64 + 1) * (y1 as i64 - y0 as i64 + 1) > MAX_SHAPE_CELLS
+    }
+
+    /// Insert or move a shape
+    pub fn insert(&mut self, id: u64, bounds: BBox) {
+        self.remove(id);
+        if self.is_oversized(&bounds) {
+            self.oversized.insert(id);
+            self.bounds.insert(id, bounds);
+            return;
+        }
+
+        let ((x0, y0), (x1, y1)) = self.cell_range(&bounds);
+        for x in x0..=x1 {
+            for y in y0..=y1 {
+                self.cells.entry((x, y)).or_default().push(id);
+            }
+        }
+        self.bounds.insert(id, bounds);
+    }
+
+    pub fn remove(&mut self, id: u64) {
+        self.stale.remove(&id);
+        let Some(bounds) = self.bounds.remove(&id) else {
+            return;
+        };
+        if self.oversized.remove(&id) {
+            return;
+        }
+
+        let ((x0, y0), (x1, y1)) = self.cell_range(&bounds);
+        for x in x0..=x1 {
+            for y in y0..=y1 {
+                if let Some(ids) = self.cells.get_mut(&(x, y)) {
+                    ids.retain(|&other| other != id);
+                    if ids.is_empty() {
+                        self.cells.remove(&(x, y));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Mark a shape whose bounds may have changed without being re-inserted
+    pub fn mark_stale(&mut self, id: u64) {
+        self.stale.insert(id);
+    }
+
+    /// IDs marked stale since they were last inserted
+    pub fn stale_ids(&self) -> impl Iterator<Item = u64> + '_ {
+        self.stale.iter().copied()
+    }
+
+    /// IDs whose bounds contain `point`, plus stale IDs
+    pub fn query_point(&self, point: Vec2) -> HashSet<u64> {
+        self.cells
+            .get(&self.cell_of(point))
+            .into_iter()
+            .flatten()
+            .chain(self.oversized.iter())
+            .copied()
+            .filter(|id| self.bounds[id].contains(point))
+            .chain(self.stale.iter().copied())
+            .collect()
+    }
+
+    /// IDs whose bounds intersect `rect`, plus stale IDs
+    pub fn query_rect(&self, rect: &BBox) -> HashSet<u64> {
+        let ((x0, y0), (x1, y1)) = self.cell_range(rect);
+        let span = (x1 as i64 - x0 as i64 + 1) * (y1 as i64 - y0 as i64 + 1);
+
+        let mut candidates = HashSet::new();
+        if span > self.cells.len() as i64 {
+            // Huge query (e.g. a whole-canvas band): walk occupied cells instead
+            for (&(x, y), ids) in &self.cells {
+                if (x0..=x1).contains(&x) && (y0..=y1).contains(&y) {
+                    candidates.extend(ids);
+                }
+            }
+        } else {
+            for x in x0..=x1 {
+                for y in y0..=y1 {
+                    if let Some(ids) = self.cells.get(&(x, y)) {
+                        candidates.extend(ids);
+                    }
+                }
+            }
+        }
+
+        candidates.extend(&self.oversized);
+        candidates.retain(|id| self.bounds[id].intersects(rect));
+        candidates.extend(&self.stale);
+        candidates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bbox(x: f32, y: f32, w: f32, h: f32) -> BBox {
+        BBox::new(Vec2::new(x, y), Vec2::new(x + w, y + h))
+    }
+
+    #[test]
+    fn test_insert_query_remove() {
+        let mut index = SpatialIndex::new(50.0);
+        index.insert(1, bbox(10.0, 10.0, 20.0, 20.0));
+        index.insert(2, bbox(40.0, 40.0, 100.0, 100.0)); // spans several cells
+
+        assert_eq!(index.query_point(Vec2::new(15.0, 15.0)), HashSet::from([1]));
+        assert_eq!(index.query_point(Vec2::new(120.0, 120.0)), HashSet::from([2]));
+        assert_eq!(index.query_rect(&bbox(0.0, 0.0, 45.0, 45.0)), HashSet::from([1, 2]));
+
+        index.remove(2);
+        assert!(index.query_point(Vec2::new(120.0, 120.0)).is_empty());
+        assert_eq!(index.len(), 1);
+
+        // Stale shapes are candidates everywhere until re-inserted
+        index.mark_stale(1);
+        assert_eq!(index.query_point(Vec2::new(900.0, 900.0)), HashSet::from([1]));
+        index.insert(1, bbox(10.0, 10.0, 20.0, 20.0));
+        assert!(index.query_point(Vec2::new(900.0, 900.0)).is_empty());
+    }
+
+    #[test]
+    fn test_reinsert_moves_shape() {
+        let mut index = SpatialIndex::new(50.0);
+        index.insert(1, bbox(0.0, 0.0, 10.0, 10.0));
+        index.insert(1, bbox(500.0, 500.0, 10.0, 10.0));
+
+        assert!(index.query_point(Vec2::new(5.0, 5.0)).is_empty());
+        assert_eq!(index.query_point(Vec2::new(505.0, 505.0)), HashSet::from([1]));
+    }
+
+    #[test]
+    fn test_unbounded_band_query() {
+        let mut index = SpatialIndex::new(50.0);
+        index.insert(1, bbox(100.0, 5000.0, 10.0, 10.0));
+        index.insert(2, bbox(300.0, 0.0, 10.0, 10.0));
+
+        let band = BBox::new(Vec2::new(90.0, -1.0e9), Vec2::new(120.0, 1.0e9));
+        assert_eq!(index.query_rect(&band), HashSet::from([1]));
+    }
+
+    #[test]
+    fn test_huge_and_unbounded_shapes_skip_the_grid() {
+        let mut index = SpatialIndex::new(DEFAULT_CELL_SIZE);
+        let shapes = [
+            (1, bbox(10.0, 10.0, 20.0, 20.0)),
+            (2, bbox(-100_000.0, -100_000.0, 200_000.0, 200_000.0)),
+            (3, BBox::new(Vec2::splat(f32::NEG_INFINITY), Vec2::splat(f32::INFINITY))),
+            (4, BBox::new(Vec2::new(0.0, 500.0), Vec2::new(f32::INFINITY, 600.0))),
+        ];
+
+        let started = std::time::Instant::now();
+        for _ in 0..100 {
+            for (id, bounds) in shapes {
+                index.insert(id, bounds);
+            }
+        }
+        assert!(started.elapsed().as_secs_f32() < 1.0, "{:?}", started.elapsed());
+        assert_eq!(index.len(), 4);
+
+        // Same answers as checking every shape
+        let brute_point = |point: Vec2| -> HashSet<u64> {
+            shapes.iter().filter(|(_, b)| b.contains(point)).map(|(id, _)| *id).collect()
+        };
+        let brute_rect = |rect: &BBox| -> HashSet<u64> {
+            shapes.iter().filter(|(_, b)| b.intersects(rect)).map(|(id, _)| *id).collect()
+        };
+        for point in [Vec2::new(15.0, 15.0), Vec2::new(5000.0, 550.0), Vec2::new(-5000.0, 550.0), Vec2::new(2.0e5, 2.0e5)] {
+            assert_eq!(index.query_point(point), brute_point(point), "{point:?}");
+        }
+        for rect in [bbox(0.0, 0.0, 50.0, 50.0), bbox(1000.0, 0.0, 10.0, 10.0), bbox(-3.0e5, 520.0, 10.0, 10.0)] {
+            assert_eq!(index.query_rect(&rect), brute_rect(&rect), "{rect:?}");
+        }
+
+        // Removing them leaves no trace in the grid
+        for (id, _) in shapes {
+            index.remove(id);
+        }
+        assert!(index.is_empty());
+        assert!(index.cells.is_empty());
+        assert!(index.query_rect(&bbox(-10.0, -10.0, 100.0, 100.0)).is_empty());
+    }
+}
@@ -0,0 +1,668 @@
+//! SVG document importer
+//!
+//! Extracts drawable elements from SVG text and converts them to shapes:
+//! - `<polygon>` -> Polygon
+//! - `<rect>` -> Rectangle
+//! - `<ellipse>` / `<circle>` -> Ellipse
+//! - `<line>` -> Line
+//! - `<path>` / `<polyline>` -> Path
+//!
+//! `transform` attributes on elements and enclosing `<g>` groups compose, with
+//! `translate`, `scale`, `rotate`, `skewX`, `skewY` and `matrix`. A shape whose
+//! transform has a skew is baked into a path. Other elements are skipped, along
+//! with everything inside them, and reported as warnings.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use glam::Affine2;
+
+use super::from_svg::{from_svg_circle, from_svg_ellipse, from_svg_line, from_svg_rect};
+use super::shape::{PathCommand, Shape, ShapeGeometry};
+use super::svg_path::{parse_svg_path, parse_svg_path_checked};
+use super::types::{Color, FillRule, LineCap, LineJoin, ShapeStyle, StrokeStyle, Transform2D, Vec2};
+
+/// A start (or self-closing) tag with its attributes
+struct Tag<'a> {
+    name: &'a str,
+    attributes: Vec<(&'a str, &'a str)>,
+    self_closing: bool,
+}
+
+impl<'a> Tag<'a> {
+    fn attr(&self, name: &str) -> Option<&'a str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| *value)
+    }
+
+    /// Look up a presentation property, preferring inline `style` over attributes
+    fn property(&self, name: &str) -> Option<&'a str> {
+        let from_style = self.attr("style").and_then(|style| {
+            style.split(';').find_map(|decl| {
+                let (key, value) = decl.split_once(':')?;
+                (key.trim() == name).then(|| value.trim())
+            })
+        });
+        from_style.or_else(|| self.attr(name))
+    }
+}
+
+/// Pull events produced by the tag scanner
+enum Event<'a> {
+    Start(Tag<'a>),
+    End(&'a str),
+}
+
+/// Scan the next start or end tag, skipping text, comments, declarations and
+/// processing instructions
+fn next_event<'a>(input: &mut &'a str) -> Option<Event<'a>> {
+    loop {
+        let start = input.find('<')?;
+        *input = &input[start..];
+
+        if let Some(rest) = input.strip_prefix("<!--") {
+            *input = rest.find("-->").map_or("", |end| &rest[end + 3..]);
+            continue;
+        }
+        if input.starts_with("<!") || input.starts_with("<?") {
+            *input = input.find('>').map_or("", |end| &input[end + 1..]);
+            continue;
+        }
+
+        let end = find_tag_end(input)?;
+        let body = &input[1..end];
+        *input = &input[end + 1..];
+
+        if let Some(name) = body.strip_prefix('/') {
+            return Some(Event::End(name.trim()));
+        }
+
+        let self_closing = body.ends_with('/');
+        let body = body.trim_end_matches('/');
+        let name_end = body.find(|c: char| c.is_whitespace()).unwrap_or(body.len());
+        return Some(Event::Start(Tag {
+            name: &body[..name_end],
+            attributes: parse_attributes(&body[name_end..]),
+            self_closing,
+        }));
+    }
+}
+
+/// Find the closing `>` of a tag, ignoring any inside quoted attribute values
+fn find_tag_end(input: &str) -> Option<usize> {
+    let mut quote: Option<char> = None;
+    for (i, c) in input.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '>') => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn parse_attributes(mut input: &str) -> Vec<(&str, &str)> {
+    let mut attributes = Vec::new();
+    loop {
+        input = input.trim_start();
+        let Some(eq) = input.find('=') else {
+            break;
+        };
+        let name = input[..eq].trim();
+        let rest = input[eq + 1..].trim_start();
+        let Some(quote) = rest.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+            break;
+        };
+        let Some(len) = rest[1..].find(quote) else {
+            break;
+        };
+        attributes.push((name, &rest[1..1 + len]));
+        input = &rest[len + 2..];
+    }
+    attributes
+}
+
+/// Split a list of numbers separated by whitespace and/or commas
+fn parse_numbers(input: &str) -> Vec<f32> {
+    input
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter_map(|n| n.parse().ok())
+        .collect()
+}
+
+/// Parse an SVG `transform` attribute; unknown functions are ignored
+pub fn parse_svg_transform(input: &str) -> Affine2 {
+    let mut transform = Affine2::IDENTITY;
+    for function in input.split(')') {
+        let Some((name, args)) = function.split_once('(') else {
+            continue;
+        };
+        let args = parse_numbers(args);
+        let step = match (name.trim(), args.as_slice()) {
+            ("translate", [x]) => Affine2::from_translation(Vec2::new(*x, 0.0)),
+            ("translate", [x, y, ..]) => Affine2::from_translation(Vec2::new(*x, *y)),
+            ("scale", [s]) => Affine2::from_scale(Vec2::splat(*s)),
+            ("scale", [sx, sy, ..]) => Affine2::from_scale(Vec2::new(*sx, *sy)),
+            ("rotate", [angle]) => Affine2::from_angle(angle.to_radians()),
+            ("rotate", [angle, cx, cy, ..]) => {
+                let center = Vec2::new(*cx, *cy);
+                Affine2::from_translation(center)
+                    * Affine2::from_angle(angle.to_radians())
+                    * Affine2::from_translation(-center)
+            }
+            ("skewX", [angle]) => Affine2::from_cols_array(&[1.0, 0.0, angle.to_radians().tan(), 1.0, 0.0, 0.0]),
+            ("skewY", [angle]) => Affine2::from_cols_array(&[1.0, angle.to_radians().tan(), 0.0, 1.0, 0.0, 0.0]),
+            ("matrix", [a, b, c, d, e, f, ..]) => Affine2::from_cols_array(&[*a, *b, *c, *d, *e, *f]),
+            _ => continue,
+        };
+        transform *= step;
+    }
+    transform
+}
+
+/// The affine map `transform` applies
+fn transform_affine(transform: &Transform2D) -> Affine2 {
+    Affine2::from_translation(transform.position + transform.anchor)
+        * Affine2::from_angle(transform.rotation)
+        * Affine2::from_scale(transform.scale)
+        * Affine2::from_translation(-transform.anchor)
+}
+
+/// `affine` as a translation, rotation and scale, or None when it skews
+fn decompose(affine: &Affine2) -> Option<Transform2D> {
+    let (x_axis, y_axis) = (affine.matrix2.x_axis, affine.matrix2.y_axis);
+    let (sx, sy) = (x_axis.length(), y_axis.length());
+    if x_axis.dot(y_axis).abs() > 1e-4 * sx * sy || sx == 0.0 || sy == 0.0 {
+        return None;
+    }
+    // A reflection shows up as a negative y scale
+    let sy = if affine.matrix2.determinant() < 0.0 { -sy } else { sy };
+    Some(Transform2D::new(affine.translation, Vec2::new(sx, sy), x_axis.y.atan2(x_axis.x), Vec2::ZERO))
+}
+
+/// `shape` moved by `parent`: kept as a transform when that can express the result,
+/// otherwise baked into a path, with the stroke scaled by the area change
+fn place(shape: Shape, parent: &Affine2) -> Option<Shape> {
+    let affine = *parent * transform_affine(&shape.transform);
+    if let Some(transform) = decompose(&affine) {
+        return Some(shape.with_transform(transform));
+    }
+
+    let mut baked = shape.flattened_with(|p| parent.transform_point2(p))?;
+    if let Some(stroke) = &mut baked.style.stroke {
+        stroke.width *= parent.matrix2.determinant().abs().sqrt();
+    }
+    Some(baked)
+}
+
+/// Parse a paint value; `None` means "none" or an unsupported color
+fn parse_paint(value: &str) -> Option<Color> {
+    let value = value.trim();
+    match value {
+        "none" | "transparent" => None,
+        _ => Color::parse(value),
+    }
+}
+
+/// Parse a `stroke-dasharray` value (comma or space separated); `None` for "none" or invalid input
+fn parse_dasharray(value: &str) -> Option<Vec<f32>> {
+    if value.trim() == "none" {
+        return None;
+    }
+    value
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|v| !v.is_empty())
+        .map(|v| v.trim_end_matches("px").parse().ok())
+        .collect()
+}
+
+fn parse_style(tag: &Tag) -> ShapeStyle {
+    // SVG fills default to black and strokes default to none
+    let fill = tag.property("fill").map_or(Some(Color::black()), parse_paint);
+    let stroke = tag.property("stroke").and_then(parse_paint).map(|color| {
+        let width = tag
+            .property("stroke-width")
+            .and_then(|w| w.trim_end_matches("px").parse().ok())
+            .unwrap_or(1.0);
+        let dash_pattern = tag.property("stroke-dasharray").and_then(parse_dasharray);
+        let dash_offset = tag.property("stroke-dashoffset").and_then(|v| v.trim().parse().ok()).unwrap_or(0.0);
+        StrokeStyle::new(color, width)
+            .with_dash_pattern(dash_pattern)
+            .with_dash_offset(dash_offset)
+            .with_line_cap(tag.property("stroke-linecap").and_then(LineCap::from_svg).unwrap_or_default())
+            .with_line_join(tag.property("stroke-linejoin").and_then(LineJoin::from_svg).unwrap_or_default())
+    });
+    let opacity = tag
+        .property("opacity")
+        .and_then(|o| o.trim().parse().ok())
+        .unwrap_or(1.0);
+    let fill_rule = tag.property("fill-rule").and_then(FillRule::from_svg).unwrap_or_default();
+    ShapeStyle::new(fill, stroke).with_opacity(opacity).with_fill_rule(fill_rule)
+}
+
+/// Elements whose contents are never drawn directly
+const SILENT_ELEMENTS: [&str; 3] = ["title", "desc", "metadata"];
+/// Elements whose children are drawn as part of the document
+const GROUP_ELEMENTS: [&str; 3] = ["svg", "g", "a"];
+/// Elements that become shapes
+const DRAWABLE_ELEMENTS: [&str; 7] = ["polygon", "polyline", "rect", "ellipse", "circle", "line", "path"];
+
+/// Why an SVG document couldn't be imported
+#[derive(Clone, Debug, PartialEq)]
+pub enum ImportError {
+    /// The text has no `<svg>` element
+    NotSvg,
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::NotSvg => write!(f, "not an SVG document"),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+/// Shapes imported from an SVG document, and what was left out
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SvgImport {
+    /// Shapes in document (paint) order
+    pub shapes: Vec<Shape>,
+    /// One line per kind of element that was skipped, with how often
+    pub warnings: Vec<String>,
+}
+
+impl SvgImport {
+    /// One-line summary for the user, e.g. "Imported 3 shapes (skipped 2 <text>)"
+    pub fn summary(&self) -> String {
+        let count = self.shapes.len();
+        let imported = format!("Imported {} shape{}", count, if count == 1 { "" } else { "s" });
+        if self.warnings.is_empty() {
+            imported
+        } else {
+            format!("{} ({})", imported, self.warnings.join(", "))
+        }
+    }
+}
+
+/// Skipped element names in first-seen order, with how often each was skipped
+#[derive(Default)]
+struct Skipped(Vec<(String, usize)>);
+
+impl Skipped {
+    fn add(&mut self, what: String) {
+        match self.0.iter_mut().find(|(seen, _)| *seen == what) {
+            Some((_, count)) => *count += 1,
+            None => self.0.push((what, 1)),
+        }
+    }
+
+    fn into_warnings(self) -> Vec<String> {
+        self.0.into_iter().map(|(what, count)| format!("skipped {} {}", count, what)).collect()
+    }
+}
+
+/// Convert a drawable element to a shape; None when its attributes don't describe one
+fn tag_to_shape(tag: &Tag, parent: &Affine2) -> Option<Shape> {
+    let parent = *parent * tag.attr("transform").map(parse_svg_transform).unwrap_or_default();
+
+    let attrs: HashMap<&str, &str> = tag.attributes.iter().copied().collect();
+    let points = || -> Option<Vec<Vec2>> {
+        let points: Vec<Vec2> = parse_numbers(tag.attr("points")?)
+            .chunks_exact(2)
+            .map(|pair| Vec2::new(pair[0], pair[1]))
+            .collect();
+        (points.len() >= 2).then_some(points)
+    };
+    let mut shape = match tag.name {
+        "polygon" => Shape::new(ShapeGeometry::polygon(points()?), ShapeStyle::default()),
+        "polyline" => {
+            let points = points()?;
+            let commands = std::iter::once(PathCommand::MoveTo(points[0]))
+                .chain(points[1..].iter().map(|p| PathCommand::LineTo(*p)))
+                .collect();
+            Shape::new(ShapeGeometry::Path { commands }, ShapeStyle::default())
+        }
+        "rect" => from_svg_rect(&attrs)?,
+        "ellipse" => from_svg_ellipse(&attrs)?,
+        "circle" => from_svg_circle(&attrs)?,
+        "line" => from_svg_line(&attrs)?,
+        "path" => Shape::new(
+            ShapeGeometry::Path { commands: parse_svg_path(tag.attr("d")?) },
+            ShapeStyle::default(),
+        ),
+        _ => return None,
+    };
+
+    shape.style = parse_style(tag);
+    // The element's own position goes inside its transform
+    place(shape, &parent)
+}
+
+/// Parse an SVG document into shapes, in document (paint) order
+/// Elements that can't be imported are skipped, along with their children, and counted
+/// in the warnings
+pub fn import_svg(svg: &str) -> Result<SvgImport, ImportError> {
+    let mut shapes = Vec::new();
+    let mut skipped = Skipped::default();
+    let mut found_svg = false;
+    let mut group_transforms = vec![Affine2::IDENTITY];
+    // Open elements inside a skipped one; its children are skipped with it
+    let mut skip_depth = 0usize;
+    let mut input = svg;
+
+    while let Some(event) = next_event(&mut input) {
+        let parent = *group_transforms.last().unwrap();
+        match event {
+            Event::Start(tag) if skip_depth > 0 => {
+                if !tag.self_closing {
+                    skip_depth += 1;
+                }
+            }
+            Event::End(_) if skip_depth > 0 => skip_depth -= 1,
+            Event::Start(tag) if GROUP_ELEMENTS.contains(&tag.name) => {
+                found_svg |= tag.name == "svg";
+                if !tag.self_closing {
+                    let local = tag.attr("transform").map(parse_svg_transform).unwrap_or_default();
+                    group_transforms.push(parent * local);
+                }
+            }
+            Event::End(name) if GROUP_ELEMENTS.contains(&name) => {
+                if group_transforms.len() > 1 {
+                    group_transforms.pop();
+                }
+            }
+            Event::Start(tag) if DRAWABLE_ELEMENTS.contains(&tag.name) => {
+                // A path is drawn up to its first error; the rest of its data is dropped
+                if tag.name == "path" && tag.attr("d").is_some_and(|d| parse_svg_path_checked(d).is_err()) {
+                    skipped.add("<path> data after an error".to_string());
+                }
+                match tag_to_shape(&tag, &parent) {
+                    Some(shape) => shapes.push(shape),
+                    None => skipped.add(format!("invalid <{}>", tag.name)),
+                }
+            }
+            Event::Start(tag) => {
+                if !SILENT_ELEMENTS.contains(&tag.name) {
+                    skipped.add(format!("<{}>", tag.name));
+                }
+                if !tag.self_closing {
+                    skip_depth = 1;
+                }
+            }
+            Event::End(_) => {}
+        }
+    }
+
+    if !found_svg {
+        return Err(ImportError::NotSvg);
+    }
+    Ok(SvgImport { shapes, warnings: skipped.into_warnings() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::FRAC_PI_2;
+
+    fn shapes(svg: &str) -> Vec<Shape> {
+        import_svg(svg).unwrap().shapes
+    }
+
+    fn assert_near(actual: Vec2, expected: Vec2) {
+        assert!(actual.distance(expected) < 1e-3, "{actual:?} != {expected:?}");
+    }
+
+    /// An icon-style document: nested groups, each with its own transform
+    const NESTED_GROUPS: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 200 200">
+        <title>Badge</title>
+        <g id="layer" transform="translate(100 100)">
+            <g transform="rotate(90)">
+                <rect x="10" y="0" width="20" height="10" fill="#336699"/>
+                <g transform="scale(2) translate(5 0)">
+                    <circle cx="0" cy="0" r="4"/>
+                </g>
+            </g>
+            <polyline points="0,0 10,10 20,0" fill="none" stroke="black"/>
+        </g>
+        <rect width="5" height="5"/>
+    </svg>"##;
+
+    /// Elements the importer can't draw, alongside ones it can
+    const MIXED_CONTENT: &str = r##"<svg xmlns="http://www.w3.org/2000/svg">
+        <defs>
+            <linearGradient id="fade"><stop offset="0"/></linearGradient>
+            <rect id="template" width="10" height="10"/>
+        </defs>
+        <text x="0" y="10">Hello <tspan>there</tspan></text>
+        <polygon points="1"/>
+        <ellipse cx="5" cy="5" rx="5" ry="3"/>
+        <text>Again</text>
+        <use href="#template"/>
+    </svg>"##;
+
+    #[test]
+    fn test_parse_svg_transform() {
+        let transform = parse_svg_transform("translate(10, 20) scale(2)");
+        assert_near(transform.transform_point2(Vec2::new(1.0, 1.0)), Vec2::new(12.0, 22.0));
+
+        let transform = parse_svg_transform("rotate(90 10 10)");
+        assert_near(transform.transform_point2(Vec2::new(20.0, 10.0)), Vec2::new(10.0, 20.0));
+
+        let transform = parse_svg_transform("matrix(1 0 0 1 5 -5) skewX(45)");
+        assert_near(transform.transform_point2(Vec2::new(0.0, 10.0)), Vec2::new(15.0, 5.0));
+
+        let transform = parse_svg_transform("skewY(45) wobble(3)");
+        assert_near(transform.transform_point2(Vec2::new(10.0, 0.0)), Vec2::new(10.0, 10.0));
+    }
+
+    #[test]
+    fn test_nested_group_fixture() {
+        let import = import_svg(NESTED_GROUPS).unwrap();
+        assert!(import.warnings.is_empty());
+        let [rect, circle, polyline, corner] = import.shapes.as_slice() else {
+            panic!("expected 4 shapes, got {}", import.shapes.len());
+        };
+
+        // Rotated a quarter turn about the layer's origin
+        assert_near(rect.transform.position, Vec2::new(100.0, 110.0));
+        assert!((rect.transform.rotation - FRAC_PI_2).abs() < 1e-5);
+        assert_eq!(rect.style.fill_color(), Color::parse("#336699"));
+        let bounds = rect.world_bounds();
+        assert_near(bounds.min, Vec2::new(90.0, 110.0));
+        assert_near(bounds.max, Vec2::new(100.0, 130.0));
+
+        // Scaled, translated and rotated through three groups
+        assert_near(circle.world_bounds().center(), Vec2::new(100.0, 110.0));
+        assert!((circle.world_bounds().width() - 16.0).abs() < 1e-3);
+
+        assert_eq!(
+            polyline.geometry,
+            ShapeGeometry::Path {
+                commands: vec![
+                    PathCommand::MoveTo(Vec2::new(0.0, 0.0)),
+                    PathCommand::LineTo(Vec2::new(10.0, 10.0)),
+                    PathCommand::LineTo(Vec2::new(20.0, 0.0)),
+                ],
+            }
+        );
+        assert_near(polyline.transform.position, Vec2::new(100.0, 100.0));
+        assert_eq!(polyline.style.fill, None);
+
+        // Outside every group again
+        assert_eq!(corner.transform, Transform2D::identity());
+    }
+
+    #[test]
+    fn test_skewed_shapes_are_baked_into_paths() {
+        let svg = r#"<svg>
+                <g transform="skewX(45)">
+                    <rect x="0" y="0" width="10" height="10" stroke="black" stroke-width="2"/>
+                </g>
+                <rect width="10" height="10" transform="matrix(-1 0 0 1 0 0)"/>
+            </svg>"#;
+        let shapes = shapes(svg);
+
+        let ShapeGeometry::Path { commands } = &shapes[0].geometry else {
+            panic!("skewed rect should be a path");
+        };
+        assert_eq!(shapes[0].transform, Transform2D::identity());
+        assert_eq!(shapes[0].geometry.vertices()[..4], [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, 0.0),
+            Vec2::new(20.0, 10.0),
+            Vec2::new(10.0, 10.0),
+        ]);
+        assert_eq!(commands.last(), Some(&PathCommand::Close));
+        // A shear keeps area, so the stroke keeps its width
+        assert!((shapes[0].style.stroke.as_ref().unwrap().width - 2.0).abs() < 1e-5);
+
+        // A mirror is still a plain transform
+        assert_eq!(shapes[1].geometry, ShapeGeometry::rectangle(10.0, 10.0));
+        let bounds = shapes[1].world_bounds();
+        assert_near(bounds.min, Vec2::new(-10.0, 0.0));
+        assert_near(bounds.max, Vec2::new(0.0, 10.0));
+    }
+
+    #[test]
+    fn test_unsupported_elements_are_skipped_with_warnings() {
+        let import = import_svg(MIXED_CONTENT).unwrap();
+        // Nothing inside <defs> or <text> is drawn or reported on its own
+        assert_eq!(import.shapes.len(), 1);
+        assert_eq!(import.shapes[0].geometry, ShapeGeometry::ellipse(5.0, 3.0));
+        assert_eq!(import.warnings, vec![
+            "skipped 1 <defs>".to_string(),
+            "skipped 2 <text>".to_string(),
+            "skipped 1 invalid <polygon>".to_string(),
+            "skipped 1 <use>".to_string(),
+        ]);
+        assert_eq!(
+            import.summary(),
+            "Imported 1 shape (skipped 1 <defs>, skipped 2 <text>, skipped 1 invalid <polygon>, skipped 1 <use>)"
+        );
+        assert_eq!(shapes(NESTED_GROUPS).len(), 4);
+        assert_eq!(import_svg(NESTED_GROUPS).unwrap().summary(), "Imported 4 shapes");
+    }
+
+    #[test]
+    fn test_paths_with_errors_are_drawn_up_to_the_error() {
+        let import = import_svg(r#"<svg><path d="M0 0 L10 0 L10 10 X5 L0 10 Z"/><path d="M0 0 L5 5"/></svg>"#).unwrap();
+        assert_eq!(import.shapes.len(), 2);
+        assert_eq!(
+            import.shapes[0].geometry,
+            ShapeGeometry::Path { commands: parse_svg_path("M0 0 L10 0 L10 10") }
+        );
+        assert_eq!(import.warnings, vec!["skipped 1 <path> data after an error".to_string()]);
+    }
+
+    #[test]
+    fn test_text_without_an_svg_element_is_rejected() {
+        assert_eq!(import_svg("<html><body><rect width=\"1\" height=\"1\"/></body></html>"), Err(ImportError::NotSvg));
+        assert_eq!(import_svg("just some text"), Err(ImportError::NotSvg));
+        assert_eq!(import_svg("<svg></svg>"), Ok(SvgImport::default()));
+    }
+
+    #[test]
+    fn test_parse_document_elements() {
+        let svg = r##"<?xml version="1.0"?>
+            <svg xmlns="http://www.w3.org/2000/svg" width="100" height="100">
+                <!-- a comment with <rect> inside -->
+                <polygon points="0,0 10,0 5,10" fill="#ff0000"/>
+                <rect x="5" y="6" width="20" height="10" style="fill: none; stroke: #00f"/>
+                <ellipse cx="50" cy="50" rx="10" ry="5"/>
+                <path d="M0 0 L10 10"></path>
+            </svg>"##;
+        let shapes = shapes(svg);
+        assert_eq!(shapes.len(), 4);
+
+        assert_eq!(shapes[0].geometry.polygon_points().unwrap().len(), 3);
+        assert_eq!(shapes[0].style.fill_color(), Color::parse("#ff0000"));
+
+        assert_eq!(shapes[1].geometry, ShapeGeometry::rectangle(20.0, 10.0));
+        assert_eq!(shapes[1].transform.position, Vec2::new(5.0, 6.0));
+        assert_eq!(shapes[1].style.fill, None);
+        assert_eq!(shapes[1].style.stroke.as_ref().unwrap().color, Color::rgb(0.0, 0.0, 1.0));
+
+        assert_eq!(shapes[2].geometry, ShapeGeometry::ellipse(10.0, 5.0));
+        assert_eq!(shapes[2].transform.position, Vec2::new(50.0, 50.0));
+
+        assert!(matches!(shapes[3].geometry, ShapeGeometry::Path { .. }));
+    }
+
+    #[test]
+    fn test_parse_stroke_dasharray() {
+        let svg = r##"<svg>
+                <line x1="0" y1="0" x2="10" y2="0" stroke="#000" stroke-dasharray="8, 4"/>
+                <line x1="0" y1="0" x2="10" y2="0" stroke="#000" style="stroke-dasharray: none"/>
+            </svg>"##;
+        let shapes = shapes(svg);
+
+        assert_eq!(shapes[0].style.stroke.as_ref().unwrap().dash_pattern, Some(vec![8.0, 4.0]));
+        assert_eq!(shapes[1].style.stroke.as_ref().unwrap().dash_pattern, None);
+    }
+
+    #[test]
+    fn test_parse_stroke_caps_and_joins() {
+        let svg = r##"<svg>
+                <polygon points="0,0 10,0 5,10" stroke="#000" stroke-linecap="round" stroke-linejoin="bevel" stroke-dashoffset="3"/>
+                <polygon points="0,0 10,0 5,10" stroke="#000" stroke-linecap="wobbly"/>
+            </svg>"##;
+        let shapes = shapes(svg);
+
+        let stroke = shapes[0].style.stroke.as_ref().unwrap();
+        assert_eq!((stroke.line_cap, stroke.line_join, stroke.dash_offset), (LineCap::Round, LineJoin::Bevel, 3.0));
+        let stroke = shapes[1].style.stroke.as_ref().unwrap();
+        assert_eq!((stroke.line_cap, stroke.line_join), (LineCap::Butt, LineJoin::Miter));
+    }
+
+    #[test]
+    fn test_parse_fill_rule() {
+        let svg = r##"<svg>
+                <path d="M0 0 L10 0 L10 10 Z" fill-rule="evenodd"/>
+                <path d="M0 0 L10 0 L10 10 Z" style="fill-rule: nonzero"/>
+                <path d="M0 0 L10 0 L10 10 Z" fill-rule="sideways"/>
+            </svg>"##;
+        let rules: Vec<FillRule> = shapes(svg).iter().map(|s| s.style.fill_rule).collect();
+        assert_eq!(rules, vec![FillRule::EvenOdd, FillRule::NonZero, FillRule::NonZero]);
+    }
+
+    #[test]
+    fn test_parse_css_paint_values() {
+        let svg = r##"<svg>
+                <rect width="1" height="1" fill="red"/>
+                <rect width="1" height="1" fill="Navy"/>
+                <rect width="1" height="1" fill="#0f0"/>
+                <rect width="1" height="1" style="fill: rgb(0, 128, 255)"/>
+                <rect width="1" height="1" fill="rgba(255, 0, 0, 0.5)"/>
+                <rect width="1" height="1" fill="chartreuse-ish"/>
+            </svg>"##;
+        let fills: Vec<Option<Color>> = shapes(svg).iter().map(|s| s.style.fill_color()).collect();
+        assert_eq!(fills, vec![
+            Some(Color::rgb(1.0, 0.0, 0.0)),
+            Some(Color::from_rgba8(0, 0, 128, 255)),
+            Some(Color::rgb(0.0, 1.0, 0.0)),
+            Some(Color::from_rgba8(0, 128, 255, 255)),
+            Some(Color::new(1.0, 0.0, 0.0, 0.5)),
+            None,
+        ]);
+    }
+
+    #[test]
+    fn test_group_transforms_compose() {
+        let svg = r#"<svg>
+                <g transform="translate(100 0) scale(2)">
+                    <rect x="5" y="5" width="10" height="10" transform="translate(1 1)"/>
+                </g>
+                <rect width="1" height="1"/>
+            </svg>"#;
+        let shapes = shapes(svg);
+        assert_eq!(shapes.len(), 2);
+        assert_eq!(shapes[0].transform.position, Vec2::new(112.0, 12.0));
+        assert_eq!(shapes[0].transform.scale, Vec2::new(2.0, 2.0));
+        assert_eq!(shapes[1].transform.position, Vec2::ZERO);
+    }
+}
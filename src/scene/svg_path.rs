@@ -12,212 +12,52 @@
 //! - T/t: smooth quadratic bezier
 //! - A/a: elliptical arc
 //! - Z/z: close path
+//!
+//! Malformed data is reported by `parse_svg_path_checked` with its byte offset.
 
 use super::types::Vec2;
 use super::PathCommand;
+use std::fmt;
+
+/// Letters that start a path command
+const COMMAND_LETTERS: &str = "MmLlHhVvCcSsQqTtAaZz";
+
+/// A problem in a path string, found by `parse_svg_path_checked`
+#[derive(Clone, Debug, PartialEq)]
+pub struct PathParseError {
+    /// Byte offset into the path string where the problem starts
+    pub offset: usize,
+    /// The command being parsed, or the unrecognized letter; None outside any command
+    pub command: Option<char>,
+    pub message: String,
+}
 
-/// Parse an SVG path string into a vector of PathCommands
-pub fn parse_svg_path(d: &str) -> Vec<PathCommand> {
-    let mut commands = Vec::new();
-    let mut tokenizer = PathTokenizer::new(d);
+impl fmt::Display for PathParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.command {
+            Some(command) => write!(f, "{} (in '{}' at byte {})", self.message, command, self.offset),
+            None => write!(f, "{} (at byte {})", self.message, self.offset),
+        }
+    }
+}
 
-    let mut current_pos = Vec2::ZERO;
-    let mut start_pos = Vec2::ZERO;
-    let mut last_control: Option<Vec2> = None;
-    let mut last_command: Option<char> = None;
+impl std::error::Error for PathParseError {}
 
-    while let Some(cmd) = tokenizer.next_command() {
-        let is_relative = cmd.is_ascii_lowercase();
-        let cmd_upper = cmd.to_ascii_uppercase();
+/// Parse an SVG path string into a vector of PathCommands
+/// Malformed data ends the path: like a browser, this keeps the commands before the
+/// first error, so a typo draws part of the shape rather than nothing
+pub fn parse_svg_path(d: &str) -> Vec<PathCommand> {
+    PathParser::new(d).parse().0
+}
 
-        match cmd_upper {
-            'M' => {
-                // MoveTo - first pair is moveto, subsequent pairs are lineto
-                let mut first = true;
-                while let Some((x, y)) = tokenizer.next_point() {
-                    let point = if is_relative && !first {
-                        Vec2::new(current_pos.x + x, current_pos.y + y)
-                    } else if is_relative {
-                        Vec2::new(current_pos.x + x, current_pos.y + y)
-                    } else {
-                        Vec2::new(x, y)
-                    };
-
-                    if first {
-                        commands.push(PathCommand::MoveTo(point));
-                        start_pos = point;
-                        first = false;
-                    } else {
-                        commands.push(PathCommand::LineTo(point));
-                    }
-                    current_pos = point;
-                }
-                last_control = None;
-                last_command = Some('M');
-            }
-            'L' => {
-                while let Some((x, y)) = tokenizer.next_point() {
-                    let point = if is_relative {
-                        Vec2::new(current_pos.x + x, current_pos.y + y)
-                    } else {
-                        Vec2::new(x, y)
-                    };
-                    commands.push(PathCommand::LineTo(point));
-                    current_pos = point;
-                }
-                last_control = None;
-                last_command = Some('L');
-            }
-            'H' => {
-                while let Some(x) = tokenizer.next_number() {
-                    let new_x = if is_relative { current_pos.x + x } else { x };
-                    let point = Vec2::new(new_x, current_pos.y);
-                    commands.push(PathCommand::LineTo(point));
-                    current_pos = point;
-                }
-                last_control = None;
-                last_command = Some('H');
-            }
-            'V' => {
-                while let Some(y) = tokenizer.next_number() {
-                    let new_y = if is_relative { current_pos.y + y } else { y };
-                    let point = Vec2::new(current_pos.x, new_y);
-                    commands.push(PathCommand::LineTo(point));
-                    current_pos = point;
-                }
-                last_control = None;
-                last_command = Some('V');
-            }
-            'C' => {
-                while let Some((x1, y1)) = tokenizer.next_point() {
-                    let (x2, y2) = tokenizer.next_point().unwrap_or((x1, y1));
-                    let (x, y) = tokenizer.next_point().unwrap_or((x2, y2));
-
-                    let (ctrl1, ctrl2, end) = if is_relative {
-                        (
-                            Vec2::new(current_pos.x + x1, current_pos.y + y1),
-                            Vec2::new(current_pos.x + x2, current_pos.y + y2),
-                            Vec2::new(current_pos.x + x, current_pos.y + y),
-                        )
-                    } else {
-                        (Vec2::new(x1, y1), Vec2::new(x2, y2), Vec2::new(x, y))
-                    };
-
-                    commands.push(PathCommand::CubicTo {
-                        ctrl1,
-                        ctrl2,
-                        to: end,
-                    });
-                    last_control = Some(ctrl2);
-                    current_pos = end;
-                }
-                last_command = Some('C');
-            }
-            'S' => {
-                // Smooth cubic - first control point is reflection of last
-                while let Some((x2, y2)) = tokenizer.next_point() {
-                    let (x, y) = tokenizer.next_point().unwrap_or((x2, y2));
-
-                    let ctrl1 = match (last_command, last_control) {
-                        (Some('C'), Some(lc)) | (Some('S'), Some(lc)) => {
-                            // Reflect last control point
-                            Vec2::new(2.0 * current_pos.x - lc.x, 2.0 * current_pos.y - lc.y)
-                        }
-                        _ => current_pos,
-                    };
-
-                    let (ctrl2, end) = if is_relative {
-                        (
-                            Vec2::new(current_pos.x + x2, current_pos.y + y2),
-                            Vec2::new(current_pos.x + x, current_pos.y + y),
-                        )
-                    } else {
-                        (Vec2::new(x2, y2), Vec2::new(x, y))
-                    };
-
-                    commands.push(PathCommand::CubicTo {
-                        ctrl1,
-                        ctrl2,
-                        to: end,
-                    });
-                    last_control = Some(ctrl2);
-                    current_pos = end;
-                }
-                last_command = Some('S');
-            }
-            'Q' => {
-                while let Some((x1, y1)) = tokenizer.next_point() {
-                    let (x, y) = tokenizer.next_point().unwrap_or((x1, y1));
-
-                    let (control, end) = if is_relative {
-                        (
-                            Vec2::new(current_pos.x + x1, current_pos.y + y1),
-                            Vec2::new(current_pos.x + x, current_pos.y + y),
-                        )
-                    } else {
-                        (Vec2::new(x1, y1), Vec2::new(x, y))
-                    };
-
-                    commands.push(PathCommand::QuadraticTo { control, to: end });
-                    last_control = Some(control);
-                    current_pos = end;
-                }
-                last_command = Some('Q');
-            }
-            'T' => {
-                // Smooth quadratic - control point is reflection of last
-                while let Some((x, y)) = tokenizer.next_point() {
-                    let control = match (last_command, last_control) {
-                        (Some('Q'), Some(lc)) | (Some('T'), Some(lc)) => {
-                            Vec2::new(2.0 * current_pos.x - lc.x, 2.0 * current_pos.y - lc.y)
-                        }
-                        _ => current_pos,
-                    };
-
-                    let end = if is_relative {
-                        Vec2::new(current_pos.x + x, current_pos.y + y)
-                    } else {
-                        Vec2::new(x, y)
-                    };
-
-                    commands.push(PathCommand::QuadraticTo { control, to: end });
-                    last_control = Some(control);
-                    current_pos = end;
-                }
-                last_command = Some('T');
-            }
-            'A' => {
-                while let Some(arc) = tokenizer.next_arc() {
-                    let end = if is_relative {
-                        Vec2::new(current_pos.x + arc.x, current_pos.y + arc.y)
-                    } else {
-                        Vec2::new(arc.x, arc.y)
-                    };
-
-                    commands.push(PathCommand::ArcTo {
-                        rx: arc.rx,
-                        ry: arc.ry,
-                        x_rotation: arc.x_rotation,
-                        large_arc: arc.large_arc,
-                        sweep: arc.sweep,
-                        to: end,
-                    });
-                    current_pos = end;
-                }
-                last_control = None;
-                last_command = Some('A');
-            }
-            'Z' => {
-                commands.push(PathCommand::Close);
-                current_pos = start_pos;
-                last_control = None;
-                last_command = Some('Z');
-            }
-            _ => {}
-        }
+/// Parse an SVG path string, or report every problem in it
+/// After an error, parsing resumes at the next command letter so later problems are
+/// found too; `parse_svg_path` returns the commands before the first one
+pub fn parse_svg_path_checked(d: &str) -> Result<Vec<PathCommand>, Vec<PathParseError>> {
+    match PathParser::new(d).parse() {
+        (commands, errors) if errors.is_empty() => Ok(commands),
+        (_, errors) => Err(errors),
     }
-
-    commands
 }
 
 /// Arc parameters from SVG
@@ -231,160 +71,305 @@ struct ArcParams {
     y: f32,
 }
 
-/// Simple tokenizer for SVG path strings
-struct PathTokenizer<'a> {
-    chars: std::iter::Peekable<std::str::Chars<'a>>,
+/// Walks a path string, turning commands into PathCommands and tracking the pen
+struct PathParser<'a> {
+    d: &'a str,
+    /// Byte offset of the next unread character
+    pos: usize,
+    commands: Vec<PathCommand>,
+    current_pos: Vec2,
+    start_pos: Vec2,
+    last_control: Option<Vec2>,
+    last_command: Option<char>,
 }
 
-impl<'a> PathTokenizer<'a> {
-    fn new(s: &'a str) -> Self {
+impl<'a> PathParser<'a> {
+    fn new(d: &'a str) -> Self {
         Self {
-            chars: s.chars().peekable(),
+            d,
+            pos: 0,
+            commands: Vec::new(),
+            current_pos: Vec2::ZERO,
+            start_pos: Vec2::ZERO,
+            last_control: None,
+            last_command: None,
         }
     }
 
-    fn skip_whitespace_and_comma(&mut self) {
-        while let Some(&c) = self.chars.peek() {
-            if c.is_whitespace() || c == ',' {
-                self.chars.next();
-            } else {
-                break;
+    /// The commands before the first error, and every error
+    fn parse(mut self) -> (Vec<PathCommand>, Vec<PathParseError>) {
+        let mut errors = Vec::new();
+        let mut valid_len = None;
+        let mut first = true;
+
+        while let Some(result) = self.next_command() {
+            let outcome = result.and_then(|(offset, cmd)| {
+                if std::mem::take(&mut first) && !matches!(cmd, 'M' | 'm') {
+                    return Err(self.error_at(offset, Some(cmd), "path must start with a moveto (M or m)"));
+                }
+                self.command(cmd)
+            });
+            if let Err(error) = outcome {
+                valid_len.get_or_insert(self.commands.len());
+                errors.push(error);
+                self.skip_to_next_command();
             }
         }
+
+        let mut commands = self.commands;
+        commands.truncate(valid_len.unwrap_or(commands.len()));
+        (commands, errors)
     }
 
-    fn next_command(&mut self) -> Option<char> {
-        self.skip_whitespace_and_comma();
-        while let Some(&c) = self.chars.peek() {
-            if c.is_alphabetic() {
-                self.chars.next();
-                return Some(c);
-            } else if c.is_whitespace() || c == ',' {
-                self.chars.next();
-            } else {
-                break;
-            }
+    fn peek(&self) -> Option<char> {
+        self.d[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) {
+        if let Some(c) = self.peek() {
+            self.pos += c.len_utf8();
         }
-        None
     }
 
-    fn peek_is_command(&mut self) -> bool {
-        self.skip_whitespace_and_comma();
-        if let Some(&c) = self.chars.peek() {
-            c.is_alphabetic()
-        } else {
-            false
+    fn error_at(&self, offset: usize, command: Option<char>, message: impl Into<String>) -> PathParseError {
+        PathParseError { offset, command, message: message.into() }
+    }
+
+    fn skip_whitespace_and_comma(&mut self) {
+        while self.peek().is_some_and(|c| c.is_whitespace() || c == ',') {
+            self.bump();
         }
     }
 
-    fn next_number(&mut self) -> Option<f32> {
+    /// Skip past a bad command's data to the next command letter, starting with the
+    /// offending token so a stray letter in it isn't taken for a command
+    fn skip_to_next_command(&mut self) {
+        while self.peek().is_some_and(|c| !c.is_whitespace() && c != ',') {
+            self.bump();
+        }
+        while self.peek().is_some_and(|c| !COMMAND_LETTERS.contains(c)) {
+            self.bump();
+        }
+    }
+
+    /// The next command letter and its offset; None at the end of the path
+    fn next_command(&mut self) -> Option<Result<(usize, char), PathParseError>> {
         self.skip_whitespace_and_comma();
+        let offset = self.pos;
+        let c = self.peek()?;
+        self.bump();
+        Some(if COMMAND_LETTERS.contains(c) {
+            Ok((offset, c))
+        } else if c.is_alphabetic() {
+            Err(self.error_at(offset, Some(c), format!("unknown command '{}'", c)))
+        } else {
+            Err(self.error_at(offset, self.last_command, format!("expected a command, found '{}'", c)))
+        })
+    }
 
-        let mut s = String::new();
+    /// Whether more numbers follow, i.e. the current command repeats
+    fn at_number(&mut self) -> bool {
+        self.skip_whitespace_and_comma();
+        self.peek().is_some_and(|c| c.is_ascii_digit() || matches!(c, '.' | '-' | '+'))
+    }
 
-        // Handle sign
-        if let Some(&c) = self.chars.peek() {
-            if c == '-' || c == '+' {
-                s.push(c);
-                self.chars.next();
-            }
+    /// Parse one command and all its repeated argument sets
+    fn command(&mut self, cmd: char) -> Result<(), PathParseError> {
+        let cmd_upper = cmd.to_ascii_uppercase();
+        if cmd_upper == 'Z' {
+            self.commands.push(PathCommand::Close);
+            self.current_pos = self.start_pos;
+            self.last_control = None;
+            self.last_command = Some('Z');
+            return Ok(());
         }
 
-        // Handle digits before decimal
-        while let Some(&c) = self.chars.peek() {
-            if c.is_ascii_digit() {
-                s.push(c);
-                self.chars.next();
-            } else {
-                break;
-            }
+        // At least one set of arguments, then as many more as follow
+        let mut first = true;
+        while first || self.at_number() {
+            self.segment(cmd, first)?;
+            first = false;
         }
+        self.last_command = Some(cmd_upper);
+        Ok(())
+    }
 
-        // Handle decimal point
-        if let Some(&c) = self.chars.peek() {
-            if c == '.' {
-                s.push(c);
-                self.chars.next();
-
-                // Handle digits after decimal
-                while let Some(&c) = self.chars.peek() {
-                    if c.is_ascii_digit() {
-                        s.push(c);
-                        self.chars.next();
-                    } else {
-                        break;
-                    }
+    /// Parse one set of arguments for `cmd` and push its PathCommand
+    /// Nothing is pushed unless the whole set parses
+    fn segment(&mut self, cmd: char, first: bool) -> Result<(), PathParseError> {
+        let is_relative = cmd.is_ascii_lowercase();
+        let origin = if is_relative { self.current_pos } else { Vec2::ZERO };
+        let current_pos = self.current_pos;
+
+        match cmd.to_ascii_uppercase() {
+            'M' => {
+                // MoveTo - first pair is moveto, subsequent pairs are lineto
+                let point = origin + self.point(cmd)?;
+                if first {
+                    self.commands.push(PathCommand::MoveTo(point));
+                    self.start_pos = point;
+                } else {
+                    self.commands.push(PathCommand::LineTo(point));
                 }
+                self.current_pos = point;
+                self.last_control = None;
+            }
+            'L' => {
+                let point = origin + self.point(cmd)?;
+                self.commands.push(PathCommand::LineTo(point));
+                self.current_pos = point;
+                self.last_control = None;
+            }
+            'H' => {
+                let x = self.number(cmd)?;
+                let point = Vec2::new(origin.x + x, current_pos.y);
+                self.commands.push(PathCommand::LineTo(point));
+                self.current_pos = point;
+                self.last_control = None;
+            }
+            'V' => {
+                let y = self.number(cmd)?;
+                let point = Vec2::new(current_pos.x, origin.y + y);
+                self.commands.push(PathCommand::LineTo(point));
+                self.current_pos = point;
+                self.last_control = None;
             }
+            'C' => {
+                let ctrl1 = origin + self.point(cmd)?;
+                let ctrl2 = origin + self.point(cmd)?;
+                let to = origin + self.point(cmd)?;
+                self.commands.push(PathCommand::CubicTo { ctrl1, ctrl2, to });
+                self.last_control = Some(ctrl2);
+                self.current_pos = to;
+                self.last_command = Some('C');
+            }
+            'S' => {
+                let ctrl2 = origin + self.point(cmd)?;
+                let to = origin + self.point(cmd)?;
+                // Smooth cubic - first control point is reflection of last
+                let ctrl1 = match (self.last_command, self.last_control) {
+                    (Some('C' | 'S'), Some(lc)) => 2.0 * current_pos - lc,
+                    _ => current_pos,
+                };
+                self.commands.push(PathCommand::CubicTo { ctrl1, ctrl2, to });
+                self.last_control = Some(ctrl2);
+                self.current_pos = to;
+                self.last_command = Some('S');
+            }
+            'Q' => {
+                let control = origin + self.point(cmd)?;
+                let to = origin + self.point(cmd)?;
+                self.commands.push(PathCommand::QuadraticTo { control, to });
+                self.last_control = Some(control);
+                self.current_pos = to;
+                self.last_command = Some('Q');
+            }
+            'T' => {
+                let to = origin + self.point(cmd)?;
+                // Smooth quadratic - control point is reflection of last
+                let control = match (self.last_command, self.last_control) {
+                    (Some('Q' | 'T'), Some(lc)) => 2.0 * current_pos - lc,
+                    _ => current_pos,
+                };
+                self.commands.push(PathCommand::QuadraticTo { control, to });
+                self.last_control = Some(control);
+                self.current_pos = to;
+                self.last_command = Some('T');
+            }
+            'A' => {
+                let arc = self.arc(cmd)?;
+                let to = origin + Vec2::new(arc.x, arc.y);
+                self.commands.push(PathCommand::ArcTo {
+                    rx: arc.rx,
+                    ry: arc.ry,
+                    x_rotation: arc.x_rotation,
+                    large_arc: arc.large_arc,
+                    sweep: arc.sweep,
+                    to,
+                });
+                self.current_pos = to;
+                self.last_control = None;
+            }
+            _ => unreachable!("not a command letter: {}", cmd),
         }
+        Ok(())
+    }
 
-        // Handle exponent
-        if let Some(&c) = self.chars.peek() {
-            if c == 'e' || c == 'E' {
-                s.push(c);
-                self.chars.next();
-
-                if let Some(&c) = self.chars.peek() {
-                    if c == '-' || c == '+' {
-                        s.push(c);
-                        self.chars.next();
-                    }
-                }
+    fn number(&mut self, cmd: char) -> Result<f32, PathParseError> {
+        self.skip_whitespace_and_comma();
+        let start = self.pos;
+        let digits = |parser: &mut Self| {
+            while parser.peek().is_some_and(|c| c.is_ascii_digit()) {
+                parser.bump();
+            }
+        };
 
-                while let Some(&c) = self.chars.peek() {
-                    if c.is_ascii_digit() {
-                        s.push(c);
-                        self.chars.next();
-                    } else {
-                        break;
-                    }
-                }
+        // Sign, digits, then a fraction; a second decimal point starts a new number
+        if matches!(self.peek(), Some('-' | '+')) {
+            self.bump();
+        }
+        digits(self);
+        if self.peek() == Some('.') {
+            self.bump();
+            digits(self);
+        }
+        // Exponent, only when digits follow so "1e" isn't swallowed
+        if matches!(self.peek(), Some('e' | 'E')) {
+            let mantissa_end = self.pos;
+            self.bump();
+            if matches!(self.peek(), Some('-' | '+')) {
+                self.bump();
+            }
+            if self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                digits(self);
+            } else {
+                self.pos = mantissa_end;
             }
         }
 
-        if s.is_empty() || s == "-" || s == "+" {
-            None
-        } else {
-            s.parse().ok()
+        let text = &self.d[start..self.pos];
+        match text.parse::<f32>() {
+            Ok(value) if value.is_finite() => Ok(value),
+            _ => {
+                let found = match self.d[start..].chars().next() {
+                    _ if !text.is_empty() => format!("'{}'", text),
+                    Some(c) => format!("'{}'", c),
+                    None => "the end of the path".to_string(),
+                };
+                self.pos = start;
+                Err(self.error_at(start, Some(cmd), format!("expected a number, found {}", found)))
+            }
         }
     }
 
-    fn next_point(&mut self) -> Option<(f32, f32)> {
-        if self.peek_is_command() {
-            return None;
-        }
-        let x = self.next_number()?;
-        let y = self.next_number()?;
-        Some((x, y))
+    fn point(&mut self, cmd: char) -> Result<Vec2, PathParseError> {
+        let x = self.number(cmd)?;
+        let y = self.number(cmd)?;
+        Ok(Vec2::new(x, y))
     }
 
-    fn next_flag(&mut self) -> Option<bool> {
+    /// A single-character arc flag; flags need no separator, so "0130" is 0, 1, 30
+    fn flag(&mut self, cmd: char) -> Result<bool, PathParseError> {
         self.skip_whitespace_and_comma();
-        if let Some(&c) = self.chars.peek() {
-            if c == '0' {
-                self.chars.next();
-                return Some(false);
-            } else if c == '1' {
-                self.chars.next();
-                return Some(true);
-            }
-        }
-        None
+        let flag = match self.peek() {
+            Some('0') => false,
+            Some('1') => true,
+            _ => return Err(self.error_at(self.pos, Some(cmd), "arc flag must be 0 or 1")),
+        };
+        self.bump();
+        Ok(flag)
     }
 
-    fn next_arc(&mut self) -> Option<ArcParams> {
-        if self.peek_is_command() {
-            return None;
-        }
-        let rx = self.next_number()?;
-        let ry = self.next_number()?;
-        let x_rotation = self.next_number()?;
-        let large_arc = self.next_flag()?;
-        let sweep = self.next_flag()?;
-        let x = self.next_number()?;
-        let y = self.next_number()?;
-
-        Some(ArcParams {
+    fn arc(&mut self, cmd: char) -> Result<ArcParams, PathParseError> {
+        let rx = self.number(cmd)?;
+        let ry = self.number(cmd)?;
+        let x_rotation = self.number(cmd)?;
+        let large_arc = self.flag(cmd)?;
+        let sweep = self.flag(cmd)?;
+        let x = self.number(cmd)?;
+        let y = self.number(cmd)?;
+
+        Ok(ArcParams {
             rx,
             ry,
             x_rotation,
@@ -433,6 +418,24 @@ mod tests {
             if *rx == 5.0 && *ry == 10.0 && *x_rotation == 45.0 && *large_arc && !*sweep && to.x == 20.0));
     }
 
+    /// The single point a lone moveto parses to
+    fn moveto_point(d: &str) -> Option<Vec2> {
+        match parse_svg_path(d).as_slice() {
+            [PathCommand::MoveTo(p)] => Some(*p),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_parse_numbers_without_separators() {
+        // A sign starts a new number
+        assert_eq!(moveto_point("M10-20"), Some(Vec2::new(10.0, -20.0)));
+        // A second decimal point starts a new number
+        assert_eq!(moveto_point("M1.5.3"), Some(Vec2::new(1.5, 0.3)));
+        // A sign right after an exponent's digits starts a new number
+        assert_eq!(moveto_point("M1e2-3"), Some(Vec2::new(100.0, -3.0)));
+    }
+
     #[test]
     fn test_parse_h_v() {
         let cmds = parse_svg_path("M10 10 H50 V30");
@@ -440,4 +443,61 @@ mod tests {
         assert!(matches!(cmds[1], PathCommand::LineTo(p) if p.x == 50.0 && p.y == 10.0));
         assert!(matches!(cmds[2], PathCommand::LineTo(p) if p.x == 50.0 && p.y == 30.0));
     }
+
+    #[test]
+    fn test_parse_compact_arc_flags() {
+        let expected = parse_svg_path_checked("M0 0 A5 5 0 0 1 30 30").unwrap();
+        assert_eq!(parse_svg_path_checked("M0 0 A5 5 0 0130 30"), Ok(expected.clone()));
+        assert_eq!(parse_svg_path_checked("M0 0 A5,5,0,0,1,30,30"), Ok(expected));
+        assert!(matches!(parse_svg_path("M0 0 a5 5 0 1110-10").as_slice(),
+            [_, PathCommand::ArcTo { large_arc: true, sweep: true, to, .. }] if *to == Vec2::new(10.0, -10.0)));
+    }
+
+    /// The single error in a path, as (offset, command, message)
+    fn only_error(d: &str) -> (usize, Option<char>, String) {
+        match parse_svg_path_checked(d) {
+            Err(errors) if errors.len() == 1 => (errors[0].offset, errors[0].command, errors[0].message.clone()),
+            other => panic!("expected one error in {:?}, got {:?}", d, other),
+        }
+    }
+
+    #[test]
+    fn test_unknown_command_keeps_prefix() {
+        let d = "M0 0 L10 10 X5 5 L20 20";
+        assert_eq!(only_error(d), (12, Some('X'), "unknown command 'X'".to_string()));
+        assert_eq!(parse_svg_path(d), parse_svg_path("M0 0 L10 10"));
+    }
+
+    #[test]
+    fn test_malformed_numbers() {
+        assert_eq!(only_error("M0 0 L10 abc"), (9, Some('L'), "expected a number, found 'a'".to_string()));
+        assert_eq!(only_error("M0 0 C1 2 3 4 5 -"), (16, Some('C'), "expected a number, found '-'".to_string()));
+        assert_eq!(only_error("M0 0 L10"), (8, Some('L'), "expected a number, found the end of the path".to_string()));
+        // A command needs at least one set of arguments
+        assert_eq!(only_error("M0 0 L Z"), (7, Some('L'), "expected a number, found 'Z'".to_string()));
+        // An incomplete set draws nothing
+        assert_eq!(parse_svg_path("M0 0 L5 5 C1 2 3 4"), parse_svg_path("M0 0 L5 5"));
+    }
+
+    #[test]
+    fn test_bad_arc_flag() {
+        assert_eq!(only_error("M0 0 A5 5 0 2 1 30 30"), (12, Some('A'), "arc flag must be 0 or 1".to_string()));
+        assert_eq!(only_error("M0 0 A5 5 0 0"), (13, Some('A'), "arc flag must be 0 or 1".to_string()));
+    }
+
+    #[test]
+    fn test_path_structure_errors() {
+        assert_eq!(only_error("L10 10"), (0, Some('L'), "path must start with a moveto (M or m)".to_string()));
+        assert_eq!(only_error("M0 0 Z 5"), (7, Some('Z'), "expected a command, found '5'".to_string()));
+        assert!(parse_svg_path("L10 10 M0 0").is_empty());
+    }
+
+    #[test]
+    fn test_every_error_is_reported() {
+        let errors = parse_svg_path_checked("M0 0 L10 x L20 20 Q1 2 ? 5").unwrap_err();
+        let found: Vec<(usize, Option<char>)> = errors.iter().map(|e| (e.offset, e.command)).collect();
+        assert_eq!(found, vec![(9, Some('L')), (23, Some('Q'))]);
+        assert_eq!(errors[1].to_string(), "expected a number, found '?' (in 'Q' at byte 23)");
+        assert_eq!(parse_svg_path("M0 0 L10 x L20 20"), parse_svg_path("M0 0"));
+    }
 }
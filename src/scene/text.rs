@@ -0,0 +1,131 @@
+//! Text layout metrics
+//!
+//! Text is laid out without asking the browser: advances come from a built-in
+//! table of Helvetica/Arial widths, so bounds are known in plain Rust (selection,
+//! snapping, hit testing) and the GPU renderer places glyphs the same way.
+//! Text starts at the local origin with its baseline on y = 0, like SVG `<text>`.
+
+use super::types::{BBox, Vec2};
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_FONT_FAMILY: &str = "Arial, Helvetica, sans-serif";
+pub const DEFAULT_FONT_SIZE: f32 = 24.0;
+
+/// Height above the baseline, as a fraction of the font size
+pub const TEXT_ASCENT: f32 = 0.905;
+/// Depth below the baseline, as a fraction of the font size
+pub const TEXT_DESCENT: f32 = 0.212;
+
+/// Advance widths of printable ASCII (space to tilde), in 1/1000 em
+const ASCII_ADVANCES: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, // ' ' to '/'
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, // '0' to '9'
+    278, 278, 584, 584, 584, 556, 1015, // ':' to '@'
+    667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, // 'A' to 'M'
+    722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, // 'N' to 'Z'
+    278, 278, 278, 469, 556, 333, // '[' to '`'
+    556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, // 'a' to 'm'
+    556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, // 'n' to 'z'
+    334, 260, 334, 584, // '{' to '~'
+];
+
+/// Advance for characters outside the table
+const FALLBACK_ADVANCE: u16 = 556;
+
+/// Which point of the text sits on the local origin (SVG `text-anchor`)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TextAlign {
+    #[default]
+    Start,
+    Middle,
+    End,
+}
+
+impl TextAlign {
+    pub const ALL: [TextAlign; 3] = [TextAlign::Start, TextAlign::Middle, TextAlign::End];
+
+    pub fn to_svg(self) -> &'static str {
+        match self {
+            TextAlign::Start => "start",
+            TextAlign::Middle => "middle",
+            TextAlign::End => "end",
+        }
+    }
+
+    pub fn from_svg(value: &str) -> Option<Self> {
+        match value.trim() {
+            "start" => Some(TextAlign::Start),
+            "middle" => Some(TextAlign::Middle),
+            "end" => Some(TextAlign::End),
+            _ => None,
+        }
+    }
+
+    /// Where a line of `width` starts, relative to the origin
+    pub fn start_x(self, width: f32) -> f32 {
+        match self {
+            TextAlign::Start => 0.0,
+            TextAlign::Middle => -width / 2.0,
+            TextAlign::End => -width,
+        }
+    }
+}
+
+/// Horizontal advance of `ch` at `font_size`
+pub fn glyph_advance(ch: char, font_size: f32) -> f32 {
+    let units = (ch as u32)
+        .checked_sub(' ' as u32)
+        .and_then(|i| ASCII_ADVANCES.get(i as usize))
+        .copied()
+        .unwrap_or(FALLBACK_ADVANCE);
+    units as f32 / 1000.0 * font_size
+}
+
+/// Width of `content` set on one line at `font_size`
+pub fn text_width(content: &str, font_size: f32) -> f32 {
+    content.chars().map(|ch| glyph_advance(ch, font_size)).sum()
+}
+
+/// Local bounds of `content`: the advance width by ascent plus descent around the baseline
+pub fn text_bounds(content: &str, font_size: f32, align: TextAlign) -> BBox {
+    let width = text_width(content, font_size);
+    let left = align.start_x(width);
+    BBox::new(
+        Vec2::new(left, -TEXT_ASCENT * font_size),
+        Vec2::new(left + width, TEXT_DESCENT * font_size),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_bounds_follow_advances_and_alignment() {
+        // "Hi": H = 722, i = 222 thousandths of an em
+        let bounds = text_bounds("Hi", 100.0, TextAlign::Start);
+        assert!((bounds.min.x - 0.0).abs() < 1e-4);
+        assert!((bounds.max.x - 94.4).abs() < 1e-3);
+        assert!((bounds.min.y + 90.5).abs() < 1e-3);
+        assert!((bounds.max.y - 21.2).abs() < 1e-3);
+
+        let middle = text_bounds("Hi", 100.0, TextAlign::Middle);
+        assert!((middle.min.x + 47.2).abs() < 1e-3);
+        assert!((middle.max.x - 47.2).abs() < 1e-3);
+        let end = text_bounds("Hi", 100.0, TextAlign::End);
+        assert!((end.max.x - 0.0).abs() < 1e-4);
+
+        // Bounds scale with the font size; unknown characters get an average width
+        assert!((text_width("Hi", 50.0) - 47.2).abs() < 1e-3);
+        assert!((glyph_advance('é', 10.0) - 5.56).abs() < 1e-4);
+        assert_eq!(text_width("", 20.0), 0.0);
+    }
+
+    #[test]
+    fn test_text_align_svg_round_trip() {
+        for align in TextAlign::ALL {
+            assert_eq!(TextAlign::from_svg(align.to_svg()), Some(align));
+        }
+        assert_eq!(TextAlign::from_svg("left"), None);
+    }
+}
@@ -1,8 +1,11 @@
 use bytemuck::{Pod, Zeroable};
 pub use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+use super::css_colors::named_color;
 
 /// RGBA color with f32 components (0.0 - 1.0)
-#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable, Serialize, Deserialize)]
 #[repr(C)]
 pub struct Color {
     pub r: f32,
@@ -32,18 +35,79 @@ impl Color {
         Self::new(0.0, 0.0, 0.0, 0.0)
     }
 
-    /// Parse a hex color string (e.g., "#ef4444" or "ef4444")
-    pub fn from_hex(hex: &str) -> Option<Self> {
-        let hex = hex.trim_start_matches('#');
-        if hex.len() != 6 {
-            return None;
+    /// Color from 8-bit channels
+    pub fn from_rgba8(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self::new(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, a as f32 / 255.0)
+    }
+
+    /// Parse a CSS color, keeping its alpha:
+    /// - hex: `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa` (the `#` may be left off the
+    ///   6 and 8 digit forms, e.g. "ef4444")
+    /// - `rgb()`/`rgba()` with comma or space separated channels, as 0-255 or percentages,
+    ///   and an optional alpha as 0-1 or a percentage
+    /// - `transparent` and the CSS named colors, in any case
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim().to_ascii_lowercase();
+        if let Some(args) = value.strip_prefix("rgba(").or_else(|| value.strip_prefix("rgb(")) {
+            return Self::parse_rgb_function(args.strip_suffix(')')?);
         }
+        if value == "transparent" {
+            return Some(Self::transparent());
+        }
+        if let Some([r, g, b]) = named_color(&value) {
+            return Some(Self::from_rgba8(r, g, b, 255));
+        }
+        match value.strip_prefix('#') {
+            Some(hex) => Self::parse_hex(hex),
+            None if matches!(value.len(), 6 | 8) => Self::parse_hex(&value),
+            None => None,
+        }
+    }
 
-        let r = u8::from_str_radix(&hex[0..2], 16).ok()? as f32 / 255.0;
-        let g = u8::from_str_radix(&hex[2..4], 16).ok()? as f32 / 255.0;
-        let b = u8::from_str_radix(&hex[4..6], 16).ok()? as f32 / 255.0;
+    /// Hex digits without the `#`: 3 or 4 shorthand digits, or 6 or 8 full ones
+    fn parse_hex(hex: &str) -> Option<Self> {
+        let digits: Vec<u8> = hex
+            .chars()
+            .map(|c| c.to_digit(16).map(|d| d as u8))
+            .collect::<Option<_>>()?;
+        let channels: Vec<u8> = match digits.len() {
+            3 | 4 => digits.iter().map(|d| d * 17).collect(),
+            6 | 8 => digits.chunks(2).map(|pair| pair[0] * 16 + pair[1]).collect(),
+            _ => return None,
+        };
+        let alpha = channels.get(3).copied().unwrap_or(255);
+        Some(Self::from_rgba8(channels[0], channels[1], channels[2], alpha))
+    }
+
+    /// Arguments of `rgb()`/`rgba()`: three channels and an optional alpha,
+    /// separated by commas, spaces or a slash before the alpha
+    fn parse_rgb_function(args: &str) -> Option<Self> {
+        let parts: Vec<&str> = args
+            .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+            .filter(|part| !part.is_empty())
+            .collect();
+        if !matches!(parts.len(), 3 | 4) {
+            return None;
+        }
 
-        Some(Self::rgb(r, g, b))
+        // A percentage, or a number out of `scale`
+        let component = |part: &str, scale: f32| -> Option<f32> {
+            let value = match part.strip_suffix('%') {
+                Some(percent) => percent.parse::<f32>().ok()? / 100.0,
+                None => part.parse::<f32>().ok()? / scale,
+            };
+            value.is_finite().then(|| value.clamp(0.0, 1.0))
+        };
+        let alpha = match parts.get(3) {
+            Some(part) => component(part, 1.0)?,
+            None => 1.0,
+        };
+        Some(Self::new(
+            component(parts[0], 255.0)?,
+            component(parts[1], 255.0)?,
+            component(parts[2], 255.0)?,
+            alpha,
+        ))
     }
 
     /// Convert to hex string (e.g., "#ef4444")
@@ -54,10 +118,106 @@ impl Color {
         format!("#{:02x}{:02x}{:02x}", r, g, b)
     }
 
+    /// Convert to a CSS color that keeps alpha: hex when opaque, otherwise `rgba()`
+    /// `parse` reads it back to the same color
+    pub fn to_css(&self) -> String {
+        if self.a >= 1.0 {
+            return self.to_hex();
+        }
+        let channel = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+        format!(
+            "rgba({}, {}, {}, {})",
+            channel(self.r),
+            channel(self.g),
+            channel(self.b),
+            self.a.max(0.0)
+        )
+    }
+
+    /// Hue (degrees, 0 - 360), saturation and lightness (0.0 - 1.0); greys have hue 0
+    pub fn to_hsl(&self) -> (f32, f32, f32) {
+        let (max, min) = (self.r.max(self.g).max(self.b), self.r.min(self.g).min(self.b));
+        let lightness = (max + min) / 2.0;
+        let chroma = max - min;
+        let saturation = if chroma == 0.0 {
+            0.0
+        } else {
+            chroma / (1.0 - (2.0 * lightness - 1.0).abs())
+        };
+        (self.hue(max, chroma), saturation, lightness)
+    }
+
+    /// Color from hue (degrees, wraps), saturation and lightness (0.0 - 1.0)
+    pub fn from_hsl(hue: f32, saturation: f32, lightness: f32, a: f32) -> Self {
+        let (saturation, lightness) = (saturation.clamp(0.0, 1.0), lightness.clamp(0.0, 1.0));
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        Self::from_hue_chroma(hue, chroma, lightness - chroma / 2.0, a)
+    }
+
+    /// Hue (degrees, 0 - 360), saturation and value (0.0 - 1.0); greys have hue 0
+    pub fn to_hsv(&self) -> (f32, f32, f32) {
+        let (max, min) = (self.r.max(self.g).max(self.b), self.r.min(self.g).min(self.b));
+        let chroma = max - min;
+        let saturation = if max == 0.0 { 0.0 } else { chroma / max };
+        (self.hue(max, chroma), saturation, max)
+    }
+
+    /// Color from hue (degrees, wraps), saturation and value (0.0 - 1.0)
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32, a: f32) -> Self {
+        let (saturation, value) = (saturation.clamp(0.0, 1.0), value.clamp(0.0, 1.0));
+        let chroma = value * saturation;
+        Self::from_hue_chroma(hue, chroma, value - chroma, a)
+    }
+
+    /// Hue in degrees shared by HSL and HSV, from the largest channel and the chroma
+    fn hue(&self, max: f32, chroma: f32) -> f32 {
+        if chroma == 0.0 {
+            return 0.0;
+        }
+        let sector = if max == self.r {
+            ((self.g - self.b) / chroma).rem_euclid(6.0)
+        } else if max == self.g {
+            (self.b - self.r) / chroma + 2.0
+        } else {
+            (self.r - self.g) / chroma + 4.0
+        };
+        sector * 60.0
+    }
+
+    /// RGB with `chroma` spread by `hue`, lifted by `offset` on every channel
+    fn from_hue_chroma(hue: f32, chroma: f32, offset: f32, a: f32) -> Self {
+        let sector = hue.rem_euclid(360.0) / 60.0;
+        let x = chroma * (1.0 - (sector.rem_euclid(2.0) - 1.0).abs());
+        let (r, g, b) = match sector as u32 {
+            0 => (chroma, x, 0.0),
+            1 => (x, chroma, 0.0),
+            2 => (0.0, chroma, x),
+            3 => (0.0, x, chroma),
+            4 => (x, 0.0, chroma),
+            _ => (chroma, 0.0, x),
+        };
+        Self::new(r + offset, g + offset, b + offset, a)
+    }
+
+    /// Scale the alpha channel, e.g. to apply a shape's opacity
+    pub fn with_alpha_multiplied(self, factor: f32) -> Self {
+        Self { a: self.a * factor, ..self }
+    }
+
     /// Convert to array for GPU upload
     pub fn to_array(&self) -> [f32; 4] {
         [self.r, self.g, self.b, self.a]
     }
+
+    /// Component-wise interpolation from `self` (t = 0) to `other` (t = 1)
+    pub fn lerp(self, other: Color, t: f32) -> Self {
+        Self::new(
+            self.r + (other.r - self.r) * t,
+            self.g + (other.g - self.g) * t,
+            self.b + (other.b - self.b) * t,
+            self.a + (other.a - self.a) * t,
+        )
+    }
 }
 
 impl Default for Color {
@@ -67,7 +227,7 @@ impl Default for Color {
 }
 
 /// 2D transform with position, scale, rotation, and anchor point
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Transform2D {
     pub position: Vec2,
     pub scale: Vec2,
@@ -125,6 +285,17 @@ impl Transform2D {
         self
     }
 
+    /// Whether this transform leaves every point where it is
+    pub fn is_identity(&self) -> bool {
+        self.position == Vec2::ZERO && self.scale == Vec2::ONE && self.rotation == 0.0
+    }
+
+    /// How much the transform stretches lengths, averaged over directions: the square
+    /// root of its area change, as used to scale stroke widths
+    pub fn length_scale(&self) -> f32 {
+        (self.scale.x * self.scale.y).abs().sqrt()
+    }
+
     /// Apply this transform to a point
     pub fn transform_point(&self, point: Vec2) -> Vec2 {
         // Translate to anchor, scale, rotate, translate back, then apply position
@@ -139,6 +310,19 @@ impl Transform2D {
         rotated + self.anchor + self.position
     }
 
+    /// Map a world-space point back into local space (inverse of `transform_point`)
+    /// Axes with zero scale collapse onto the anchor
+    pub fn inverse_transform_point(&self, point: Vec2) -> Vec2 {
+        let rotated = point - self.position - self.anchor;
+        let (sin_r, cos_r) = self.rotation.sin_cos();
+        let scaled = Vec2::new(
+            rotated.x * cos_r + rotated.y * sin_r,
+            -rotated.x * sin_r + rotated.y * cos_r,
+        );
+        let unscale = |v: f32, s: f32| if s != 0.0 { v / s } else { 0.0 };
+        Vec2::new(unscale(scaled.x, self.scale.x), unscale(scaled.y, self.scale.y)) + self.anchor
+    }
+
     /// Get the 3x3 transformation matrix (as 4x4 for GPU compatibility)
     pub fn to_matrix(&self) -> glam::Mat4 {
         let translation = glam::Mat4::from_translation(glam::Vec3::new(
@@ -165,59 +349,393 @@ impl Default for Transform2D {
     }
 }
 
+/// How the open ends of a stroke are drawn, as in SVG `stroke-linecap`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LineCap {
+    #[default]
+    Butt,
+    Round,
+    Square,
+}
+
+impl LineCap {
+    pub const ALL: [LineCap; 3] = [LineCap::Butt, LineCap::Round, LineCap::Square];
+
+    /// SVG attribute value
+    pub fn to_svg(self) -> &'static str {
+        match self {
+            LineCap::Butt => "butt",
+            LineCap::Round => "round",
+            LineCap::Square => "square",
+        }
+    }
+
+    pub fn from_svg(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|cap| cap.to_svg() == value.trim())
+    }
+}
+
+/// How stroke segments meet at corners, as in SVG `stroke-linejoin`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LineJoin {
+    #[default]
+    Miter,
+    Round,
+    Bevel,
+}
+
+impl LineJoin {
+    pub const ALL: [LineJoin; 3] = [LineJoin::Miter, LineJoin::Round, LineJoin::Bevel];
+
+    /// SVG attribute value
+    pub fn to_svg(self) -> &'static str {
+        match self {
+            LineJoin::Miter => "miter",
+            LineJoin::Round => "round",
+            LineJoin::Bevel => "bevel",
+        }
+    }
+
+    pub fn from_svg(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|join| join.to_svg() == value.trim())
+    }
+}
+
+/// Which regions of a self-overlapping outline are filled, as in SVG `fill-rule`
+/// With `EvenOdd`, a sub-path inside another cuts a hole whichever way it winds
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FillRule {
+    #[default]
+    NonZero,
+    EvenOdd,
+}
+
+impl FillRule {
+    pub const ALL: [FillRule; 2] = [FillRule::NonZero, FillRule::EvenOdd];
+
+    /// SVG attribute value
+    pub fn to_svg(self) -> &'static str {
+        match self {
+            FillRule::NonZero => "nonzero",
+            FillRule::EvenOdd => "evenodd",
+        }
+    }
+
+    pub fn from_svg(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|rule| rule.to_svg() == value.trim())
+    }
+}
+
 /// Stroke styling for shape outlines
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct StrokeStyle {
     pub color: Color,
     pub width: f32,
+    /// Alternating dash and gap lengths, as in SVG `stroke-dasharray`; None is solid
+    #[serde(default)]
+    pub dash_pattern: Option<Vec<f32>>,
+    /// Distance into the dash pattern at which each sub-path starts (`stroke-dashoffset`)
+    #[serde(default)]
+    pub dash_offset: f32,
+    #[serde(default)]
+    pub line_cap: LineCap,
+    #[serde(default)]
+    pub line_join: LineJoin,
 }
 
 impl StrokeStyle {
     pub fn new(color: Color, width: f32) -> Self {
-        Self { color, width }
+        Self {
+            color,
+            width,
+            dash_pattern: None,
+            dash_offset: 0.0,
+            line_cap: LineCap::default(),
+            line_join: LineJoin::default(),
+        }
+    }
+
+    /// Set the dash pattern; empty or all-zero patterns mean a solid stroke
+    pub fn with_dash_pattern(mut self, pattern: Option<Vec<f32>>) -> Self {
+        self.dash_pattern = pattern.filter(|p| p.iter().all(|v| *v >= 0.0) && p.iter().any(|v| *v > 0.0));
+        self
+    }
+
+    pub fn with_dash_offset(mut self, offset: f32) -> Self {
+        self.dash_offset = offset;
+        self
+    }
+
+    pub fn with_line_cap(mut self, line_cap: LineCap) -> Self {
+        self.line_cap = line_cap;
+        self
+    }
+
+    pub fn with_line_join(mut self, line_join: LineJoin) -> Self {
+        self.line_join = line_join;
+        self
+    }
+
+    /// Scale the stroke's lengths by `factor`: its width, dashes, gaps and dash offset
+    pub fn scale_by(&mut self, factor: f32) {
+        self.width *= factor;
+        if let Some(pattern) = &mut self.dash_pattern {
+            pattern.iter_mut().for_each(|length| *length *= factor);
+        }
+        self.dash_offset *= factor;
+    }
+
+    /// Dash pattern as an SVG `stroke-dasharray` value ("none" when solid)
+    pub fn dasharray(&self) -> String {
+        match &self.dash_pattern {
+            Some(pattern) => pattern.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(" "),
+            None => "none".to_string(),
+        }
     }
 }
 
 impl Default for StrokeStyle {
     fn default() -> Self {
+        Self::new(Color::black(), 1.0)
+    }
+}
+
+/// A color at a position along a gradient (offset 0.0 - 1.0)
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GradientStop {
+    pub offset: f32,
+    pub color: Color,
+}
+
+impl GradientStop {
+    pub fn new(offset: f32, color: Color) -> Self {
+        Self { offset, color }
+    }
+}
+
+/// Color of the gradient at `t`: the end stops' colors outside them, linear in between
+/// Stops must be sorted by offset
+pub fn sample_gradient(stops: &[GradientStop], t: f32) -> Color {
+    let (Some(first), Some(last)) = (stops.first(), stops.last()) else {
+        return Color::transparent();
+    };
+    if t <= first.offset {
+        return first.color;
+    }
+    if t >= last.offset {
+        return last.color;
+    }
+    stops
+        .windows(2)
+        .find(|pair| t <= pair[1].offset)
+        .map(|pair| {
+            let span = pair[1].offset - pair[0].offset;
+            let local = if span > 0.0 { (t - pair[0].offset) / span } else { 1.0 };
+            pair[0].color.lerp(pair[1].color, local)
+        })
+        .unwrap_or(last.color)
+}
+
+/// How a shape's interior is painted
+/// Gradient geometry is in the shape's local coordinates, so it follows the shape's transform
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Fill {
+    Solid(Color),
+    LinearGradient {
+        start: Vec2,
+        end: Vec2,
+        stops: Vec<GradientStop>,
+    },
+    RadialGradient {
+        center: Vec2,
+        radius: f32,
+        stops: Vec<GradientStop>,
+    },
+}
+
+impl Fill {
+    /// Two-stop gradient running left to right across `bounds`
+    pub fn linear_across(bounds: &BBox, from: Color, to: Color) -> Self {
+        let mid_y = bounds.center().y;
+        Fill::LinearGradient {
+            start: Vec2::new(bounds.min.x, mid_y),
+            end: Vec2::new(bounds.max.x, mid_y),
+            stops: vec![GradientStop::new(0.0, from), GradientStop::new(1.0, to)],
+        }
+    }
+
+    /// Two-stop gradient from the center of `bounds` out to its longer half-extent
+    pub fn radial_within(bounds: &BBox, from: Color, to: Color) -> Self {
+        Fill::RadialGradient {
+            center: bounds.center(),
+            radius: bounds.width().max(bounds.height()) / 2.0,
+            stops: vec![GradientStop::new(0.0, from), GradientStop::new(1.0, to)],
+        }
+    }
+
+    pub fn is_gradient(&self) -> bool {
+        !matches!(self, Fill::Solid(_))
+    }
+
+    /// Gradient stops (empty for a solid fill)
+    pub fn stops(&self) -> &[GradientStop] {
+        match self {
+            Fill::Solid(_) => &[],
+            Fill::LinearGradient { stops, .. } | Fill::RadialGradient { stops, .. } => stops,
+        }
+    }
+
+    /// The solid color, or a gradient's first stop; used where only one color can be shown
+    pub fn primary_color(&self) -> Color {
+        match self {
+            Fill::Solid(color) => *color,
+            _ => self.stops().first().map_or(Color::transparent(), |stop| stop.color),
+        }
+    }
+
+    /// Color at a point in the shape's local coordinates
+    pub fn color_at(&self, point: Vec2) -> Color {
+        match self {
+            Fill::Solid(color) => *color,
+            Fill::LinearGradient { start, end, stops } => {
+                let axis = *end - *start;
+                let length_sq = axis.length_squared();
+                let t = if length_sq > 0.0 { (point - *start).dot(axis) / length_sq } else { 0.0 };
+                sample_gradient(stops, t)
+            }
+            Fill::RadialGradient { center, radius, stops } => {
+                let t = if *radius > 0.0 { point.distance(*center) / radius } else { 0.0 };
+                sample_gradient(stops, t)
+            }
+        }
+    }
+
+    /// Replace the color of stop `index`; solid fills and missing stops are unchanged
+    pub fn with_stop_color(mut self, index: usize, color: Color) -> Self {
+        if let Fill::LinearGradient { stops, .. } | Fill::RadialGradient { stops, .. } = &mut self {
+            if let Some(stop) = stops.get_mut(index) {
+                stop.color = color;
+            }
+        }
+        self
+    }
+
+    /// Scale every color's alpha, e.g. to apply a shape's opacity
+    pub fn with_alpha_multiplied(mut self, factor: f32) -> Self {
+        match &mut self {
+            Fill::Solid(color) => *color = color.with_alpha_multiplied(factor),
+            Fill::LinearGradient { stops, .. } | Fill::RadialGradient { stops, .. } => {
+                for stop in stops {
+                    stop.color = stop.color.with_alpha_multiplied(factor);
+                }
+            }
+        }
+        self
+    }
+}
+
+/// Blurred copy of a shape's silhouette drawn behind it, like CSS `drop-shadow()`
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DropShadow {
+    /// Offset from the shape in world units, unaffected by the shape's scale or rotation
+    pub offset: Vec2,
+    /// Blur radius in world units, as in CSS `drop-shadow()`; 0 is a hard shadow
+    pub blur: f32,
+    pub color: Color,
+}
+
+impl Default for DropShadow {
+    fn default() -> Self {
+        Self::new(Vec2::new(4.0, 4.0), 8.0, Color::new(0.0, 0.0, 0.0, 0.35))
+    }
+}
+
+impl DropShadow {
+    pub fn new(offset: Vec2, blur: f32, color: Color) -> Self {
         Self {
-            color: Color::black(),
-            width: 1.0,
+            offset,
+            blur: blur.max(0.0),
+            color,
         }
     }
+
+    /// CSS `drop-shadow()` filter with the shape's `opacity` folded into the color
+    pub fn to_css(&self, opacity: f32) -> String {
+        format!(
+            "drop-shadow({}px {}px {}px {})",
+            self.offset.x,
+            self.offset.y,
+            self.blur,
+            self.color.with_alpha_multiplied(opacity).to_css(),
+        )
+    }
 }
 
 /// Complete styling for a shape (fill and/or stroke)
-#[derive(Clone, Copy, Debug, PartialEq, Default)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ShapeStyle {
-    pub fill: Option<Color>,
+    pub fill: Option<Fill>,
     pub stroke: Option<StrokeStyle>,
+    /// Whole-shape opacity (0.0 - 1.0), applied on top of fill and stroke alpha
+    pub opacity: f32,
+    #[serde(default)]
+    pub fill_rule: FillRule,
+    #[serde(default)]
+    pub drop_shadow: Option<DropShadow>,
+}
+
+impl Default for ShapeStyle {
+    fn default() -> Self {
+        Self::new(None, None)
+    }
 }
 
 impl ShapeStyle {
     pub fn new(fill: Option<Color>, stroke: Option<StrokeStyle>) -> Self {
-        Self { fill, stroke }
+        Self {
+            fill: fill.map(Fill::Solid),
+            stroke,
+            opacity: 1.0,
+            fill_rule: FillRule::default(),
+            drop_shadow: None,
+        }
     }
 
     pub fn fill_only(color: Color) -> Self {
-        Self {
-            fill: Some(color),
-            stroke: None,
-        }
+        Self::new(Some(color), None)
     }
 
     pub fn stroke_only(stroke: StrokeStyle) -> Self {
-        Self {
-            fill: None,
-            stroke: Some(stroke),
-        }
+        Self::new(None, Some(stroke))
     }
 
     pub fn fill_and_stroke(fill: Color, stroke: StrokeStyle) -> Self {
-        Self {
-            fill: Some(fill),
-            stroke: Some(stroke),
-        }
+        Self::new(Some(fill), Some(stroke))
+    }
+
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_fill_rule(mut self, fill_rule: FillRule) -> Self {
+        self.fill_rule = fill_rule;
+        self
+    }
+
+    pub fn with_fill(mut self, fill: Option<Fill>) -> Self {
+        self.fill = fill;
+        self
+    }
+
+    pub fn with_drop_shadow(mut self, drop_shadow: Option<DropShadow>) -> Self {
+        self.drop_shadow = drop_shadow;
+        self
+    }
+
+    /// Single color standing in for the fill (see `Fill::primary_color`)
+    pub fn fill_color(&self) -> Option<Color> {
+        self.fill.as_ref().map(Fill::primary_color)
     }
 }
 
@@ -249,6 +767,16 @@ impl BBox {
         Some(Self { min, max })
     }
 
+    /// World bounds of a polygon from its transformed vertices, which stay tight under
+    /// rotation where transforming the local box's corners would not
+    pub fn from_transform_polygon(points: &[Vec2], transform: &Transform2D) -> BBox {
+        let world: Vec<Vec2> = points.iter().map(|&p| transform.transform_point(p)).collect();
+        Self::from_points(&world).unwrap_or_else(|| {
+            let origin = transform.transform_point(Vec2::ZERO);
+            Self::new(origin, origin)
+        })
+    }
+
     pub fn width(&self) -> f32 {
         self.max.x - self.min.x
     }
@@ -257,6 +785,16 @@ impl BBox {
         self.max.y - self.min.y
     }
 
+    /// Width over height, or 1.0 for a box with no height
+    pub fn aspect_ratio(&self) -> f32 {
+        let height = self.height();
+        if height == 0.0 {
+            1.0
+        } else {
+            self.width() / height
+        }
+    }
+
     pub fn center(&self) -> Vec2 {
         (self.min + self.max) * 0.5
     }
@@ -275,6 +813,14 @@ impl BBox {
             && self.max.y >= other.min.y
     }
 
+    /// Overlapping region of two bounding boxes, or None if they don't overlap
+    pub fn intersect(&self, other: &BBox) -> Option<BBox> {
+        self.intersects(other).then(|| Self {
+            min: self.min.max(other.min),
+            max: self.max.min(other.max),
+        })
+    }
+
     /// Expand to include another bounding box
     pub fn union(&self, other: &BBox) -> Self {
         Self {
@@ -298,7 +844,7 @@ mod tests {
 
     #[test]
     fn test_color_hex_parsing() {
-        let color = Color::from_hex("#ef4444").unwrap();
+        let color = Color::parse("#ef4444").unwrap();
         assert!((color.r - 0.937).abs() < 0.01);
         assert!((color.g - 0.267).abs() < 0.01);
         assert!((color.b - 0.267).abs() < 0.01);
@@ -307,11 +853,112 @@ mod tests {
     #[test]
     fn test_color_hex_roundtrip() {
         let original = "#3b82f6";
-        let color = Color::from_hex(original).unwrap();
+        let color = Color::parse(original).unwrap();
         let hex = color.to_hex();
         assert_eq!(hex, original);
     }
 
+    #[test]
+    fn test_color_parse_hex_forms() {
+        let red = Color::rgb(1.0, 0.0, 0.0);
+        assert_eq!(Color::parse("#ff0000"), Some(red));
+        assert_eq!(Color::parse("ff0000"), Some(red));
+        assert_eq!(Color::parse("#F00"), Some(red));
+        assert_eq!(Color::parse("  #f00  "), Some(red));
+        assert_eq!(Color::parse("#ff000080"), Some(Color::from_rgba8(255, 0, 0, 128)));
+        assert_eq!(Color::parse("ff000080"), Some(Color::from_rgba8(255, 0, 0, 128)));
+        assert_eq!(Color::parse("#f008"), Some(Color::from_rgba8(255, 0, 0, 136)));
+        assert_eq!(Color::parse("#abc"), Color::parse("#aabbcc"));
+
+        // Wrong lengths and non-hex digits
+        for bad in ["", "#", "#ff", "#fffff", "#fffffff", "#fffffffff", "#ggg", "#12345z", "f00", "fff0"] {
+            assert_eq!(Color::parse(bad), None, "{bad:?}");
+        }
+    }
+
+    #[test]
+    fn test_color_parse_rgb_functions() {
+        assert_eq!(Color::parse("rgb(255,0,0)"), Some(Color::rgb(1.0, 0.0, 0.0)));
+        assert_eq!(Color::parse("rgb(255, 128, 0)"), Some(Color::from_rgba8(255, 128, 0, 255)));
+        assert_eq!(Color::parse("RGB(0 0 255)"), Some(Color::rgb(0.0, 0.0, 1.0)));
+        assert_eq!(Color::parse("rgba(0, 0, 0, 0.5)"), Some(Color::new(0.0, 0.0, 0.0, 0.5)));
+        assert_eq!(Color::parse("rgb(0 0 0 / 25%)"), Some(Color::new(0.0, 0.0, 0.0, 0.25)));
+        assert_eq!(Color::parse("rgba(100%, 50%, 0%, 1)"), Some(Color::rgb(1.0, 0.5, 0.0)));
+        // Out-of-range channels are clamped, as in CSS
+        assert_eq!(Color::parse("rgb(300, -20, 0)"), Some(Color::rgb(1.0, 0.0, 0.0)));
+        assert_eq!(Color::parse("rgba(0, 0, 0, 2)"), Some(Color::black()));
+
+        for bad in ["rgb(255, 0)", "rgb(1, 2, 3, 4, 5)", "rgb(a, b, c)", "rgb(255, 0, 0", "rgb(nan, 0, 0)", "rgb()"] {
+            assert_eq!(Color::parse(bad), None, "{bad:?}");
+        }
+    }
+
+    #[test]
+    fn test_color_parse_named_colors() {
+        assert_eq!(Color::parse("black"), Some(Color::black()));
+        assert_eq!(Color::parse("White"), Some(Color::white()));
+        assert_eq!(Color::parse("red"), Some(Color::rgb(1.0, 0.0, 0.0)));
+        assert_eq!(Color::parse("rebeccapurple"), Some(Color::from_rgba8(102, 51, 153, 255)));
+        assert_eq!(Color::parse("grey"), Color::parse("gray"));
+        assert_eq!(Color::parse("transparent"), Some(Color::transparent()));
+        assert_eq!(Color::parse("blackish"), None);
+        assert_eq!(Color::parse("none"), None);
+    }
+
+    #[test]
+    fn test_color_css_round_trips_alpha() {
+        assert_eq!(Color::rgb(1.0, 0.0, 0.0).to_css(), "#ff0000");
+        assert_eq!(Color::from_rgba8(255, 0, 0, 128).to_css(), format!("rgba(255, 0, 0, {})", 128.0_f32 / 255.0));
+        for color in [
+            Color::black(),
+            Color::transparent(),
+            Color::from_rgba8(12, 34, 56, 78),
+            Color::from_rgba8(255, 255, 255, 1),
+            Color::new(0.2, 0.4, 0.6, 0.35),
+        ] {
+            let parsed = Color::parse(&color.to_css()).unwrap();
+            assert!((parsed.a - color.a).abs() < 1e-6, "{color:?}");
+            assert_eq!(parsed.to_hex(), color.to_hex());
+        }
+    }
+
+    #[test]
+    fn test_color_hsl_and_hsv_at_primaries_and_greys() {
+        let cases = [
+            // color, hsl, hsv
+            (Color::rgb(1.0, 0.0, 0.0), (0.0, 1.0, 0.5), (0.0, 1.0, 1.0)),
+            (Color::rgb(0.0, 1.0, 0.0), (120.0, 1.0, 0.5), (120.0, 1.0, 1.0)),
+            (Color::rgb(0.0, 0.0, 1.0), (240.0, 1.0, 0.5), (240.0, 1.0, 1.0)),
+            (Color::rgb(1.0, 1.0, 0.0), (60.0, 1.0, 0.5), (60.0, 1.0, 1.0)),
+            (Color::rgb(1.0, 0.0, 1.0), (300.0, 1.0, 0.5), (300.0, 1.0, 1.0)),
+            (Color::black(), (0.0, 0.0, 0.0), (0.0, 0.0, 0.0)),
+            (Color::white(), (0.0, 0.0, 1.0), (0.0, 0.0, 1.0)),
+            (Color::rgb(0.5, 0.5, 0.5), (0.0, 0.0, 0.5), (0.0, 0.0, 0.5)),
+        ];
+        for (color, hsl, hsv) in cases {
+            assert_eq!(color.to_hsl(), hsl, "{color:?}");
+            assert_eq!(color.to_hsv(), hsv, "{color:?}");
+            assert_eq!(Color::from_hsl(hsl.0, hsl.1, hsl.2, 1.0), color, "{hsl:?}");
+            assert_eq!(Color::from_hsv(hsv.0, hsv.1, hsv.2, 1.0), color, "{hsv:?}");
+        }
+
+        // Hue wraps, alpha passes through, and greys ignore hue
+        assert_eq!(Color::from_hsv(360.0, 1.0, 1.0, 0.25), Color::new(1.0, 0.0, 0.0, 0.25));
+        assert_eq!(Color::from_hsl(-120.0, 1.0, 0.5, 1.0), Color::rgb(0.0, 0.0, 1.0));
+        assert_eq!(Color::from_hsv(200.0, 0.0, 0.5, 1.0), Color::rgb(0.5, 0.5, 0.5));
+    }
+
+    #[test]
+    fn test_color_hsl_and_hsv_round_trip() {
+        for color in [Color::from_rgba8(239, 68, 68, 255), Color::from_rgba8(59, 130, 246, 128), Color::new(0.2, 0.7, 0.4, 1.0)] {
+            let (h, s, l) = color.to_hsl();
+            let (hv, sv, v) = color.to_hsv();
+            for back in [Color::from_hsl(h, s, l, color.a), Color::from_hsv(hv, sv, v, color.a)] {
+                assert_eq!(back.to_css(), color.to_css());
+            }
+        }
+    }
+
     #[test]
     fn test_transform_identity() {
         let t = Transform2D::identity();
@@ -328,6 +975,18 @@ mod tests {
         assert_eq!(transformed, Vec2::new(15.0, 30.0));
     }
 
+    #[test]
+    fn test_transform_inverse_roundtrip() {
+        let transform = Transform2D::from_position(Vec2::new(30.0, -10.0))
+            .with_scale(Vec2::new(2.0, -0.5))
+            .with_rotation(0.7)
+            .with_anchor(Vec2::new(5.0, 5.0));
+        let local = Vec2::new(12.0, -3.0);
+
+        let back = transform.inverse_transform_point(transform.transform_point(local));
+        assert!((back - local).length() < 1e-4);
+    }
+
     #[test]
     fn test_bbox_from_points() {
         let points = vec![
@@ -339,4 +998,96 @@ mod tests {
         assert_eq!(bbox.min, Vec2::new(0.0, 0.0));
         assert_eq!(bbox.max, Vec2::new(10.0, 15.0));
     }
+
+    #[test]
+    fn test_bbox_aspect_ratio() {
+        assert_eq!(BBox::new(Vec2::new(10.0, 10.0), Vec2::new(50.0, 30.0)).aspect_ratio(), 2.0);
+        assert_eq!(BBox::new(Vec2::ZERO, Vec2::new(40.0, 0.0)).aspect_ratio(), 1.0);
+    }
+
+    #[test]
+    fn test_bbox_intersect() {
+        let a = BBox::new(Vec2::new(0.0, 0.0), Vec2::new(100.0, 100.0));
+        let b = BBox::new(Vec2::new(50.0, 60.0), Vec2::new(150.0, 160.0));
+        assert_eq!(
+            a.intersect(&b),
+            Some(BBox::new(Vec2::new(50.0, 60.0), Vec2::new(100.0, 100.0)))
+        );
+
+        let c = BBox::new(Vec2::new(200.0, 200.0), Vec2::new(300.0, 300.0));
+        assert_eq!(a.intersect(&c), None);
+    }
+
+    #[test]
+    fn test_color_lerp() {
+        assert_eq!(Color::black().lerp(Color::white(), 0.5), Color::rgb(0.5, 0.5, 0.5));
+        assert_eq!(Color::black().lerp(Color::white(), 0.0), Color::black());
+        assert_eq!(Color::black().lerp(Color::white(), 1.0), Color::white());
+        // Alpha is interpolated like the other channels
+        assert_eq!(Color::transparent().lerp(Color::new(1.0, 1.0, 1.0, 1.0), 0.25).a, 0.25);
+    }
+
+    #[test]
+    fn test_drop_shadow_css_filter() {
+        let shadow = DropShadow::new(Vec2::new(3.0, -2.0), 6.0, Color::new(1.0, 0.0, 0.0, 0.5));
+        assert_eq!(shadow.to_css(1.0), "drop-shadow(3px -2px 6px rgba(255, 0, 0, 0.5))");
+        // Shape opacity fades the shadow too
+        assert_eq!(shadow.to_css(0.5), "drop-shadow(3px -2px 6px rgba(255, 0, 0, 0.25))");
+        let opaque = DropShadow::new(Vec2::ZERO, 2.0, Color::black());
+        assert_eq!(opaque.to_css(1.0), "drop-shadow(0px 0px 2px #000000)");
+        // Negative blur is meaningless
+        assert_eq!(DropShadow::new(Vec2::ZERO, -4.0, Color::black()).blur, 0.0);
+    }
+
+    #[test]
+    fn test_styles_saved_without_a_shadow_still_load() {
+        let json = r#"{"fill":null,"stroke":null,"opacity":1.0}"#;
+        let style: ShapeStyle = serde_json::from_str(json).unwrap();
+        assert_eq!(style.drop_shadow, None);
+    }
+
+    fn two_stop() -> Vec<GradientStop> {
+        vec![
+            GradientStop::new(0.25, Color::rgb(1.0, 0.0, 0.0)),
+            GradientStop::new(0.75, Color::rgb(0.0, 0.0, 1.0)),
+        ]
+    }
+
+    #[test]
+    fn test_gradient_sampling_at_stop_boundaries() {
+        let stops = two_stop();
+        // Before the first and after the last stop, the end colors are held
+        assert_eq!(sample_gradient(&stops, 0.0), stops[0].color);
+        assert_eq!(sample_gradient(&stops, 0.25), stops[0].color);
+        assert_eq!(sample_gradient(&stops, 0.75), stops[1].color);
+        assert_eq!(sample_gradient(&stops, 1.0), stops[1].color);
+        assert_eq!(sample_gradient(&stops, 0.5), Color::rgb(0.5, 0.0, 0.5));
+    }
+
+    #[test]
+    fn test_linear_and_radial_color_at() {
+        let bounds = BBox::new(Vec2::ZERO, Vec2::new(100.0, 50.0));
+        let (red, blue) = (Color::rgb(1.0, 0.0, 0.0), Color::rgb(0.0, 0.0, 1.0));
+
+        let linear = Fill::linear_across(&bounds, red, blue);
+        assert_eq!(linear.color_at(Vec2::new(0.0, 0.0)), red);
+        // Only the position along the axis matters
+        assert_eq!(linear.color_at(Vec2::new(50.0, 0.0)), linear.color_at(Vec2::new(50.0, 50.0)));
+        assert_eq!(linear.color_at(Vec2::new(100.0, 25.0)), blue);
+
+        let radial = Fill::radial_within(&bounds, red, blue);
+        assert_eq!(radial.color_at(Vec2::new(50.0, 25.0)), red);
+        assert_eq!(radial.color_at(Vec2::new(100.0, 25.0)), blue);
+    }
+
+    #[test]
+    fn test_gradient_stop_hex_roundtrip() {
+        let fill = Fill::linear_across(&BBox::new(Vec2::ZERO, Vec2::ONE), Color::black(), Color::white())
+            .with_stop_color(1, Color::parse("#3b82f6").unwrap());
+        let hexes: Vec<String> = fill.stops().iter().map(|stop| stop.color.to_hex()).collect();
+        assert_eq!(hexes, vec!["#000000", "#3b82f6"]);
+
+        let parsed: Vec<Color> = hexes.iter().filter_map(|hex| Color::parse(hex)).collect();
+        assert_eq!(parsed, fill.stops().iter().map(|stop| stop.color).collect::<Vec<_>>());
+    }
 }
@@ -0,0 +1,278 @@
+//! Vertex editing of polygons and paths
+//!
+//! Vertices are the points `ShapeGeometry::vertices` lists: a polygon's points, or the
+//! on-curve end point of each path command other than Close. Paths also expose the
+//! control points of their curves. Edits here work in local coordinates and return a
+//! new geometry, or None when the edit doesn't apply.
+
+use super::shape::{PathCommand, ShapeGeometry};
+use super::types::Vec2;
+use crate::utils::point_segment_distance;
+
+/// Fewest vertices a polygon keeps when vertices are removed
+pub const MIN_POLYGON_VERTICES: usize = 3;
+
+/// Which control point of a curve command
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ControlSlot {
+    /// The single control point of a quadratic
+    Quadratic,
+    /// A cubic's first control point, leaving the segment's start
+    Cubic1,
+    /// A cubic's second control point, arriving at the segment's end
+    Cubic2,
+}
+
+/// A curve's control point, and the on-curve point its handle line is drawn from
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ControlHandle {
+    /// Index of the curve in the path's commands
+    pub command: usize,
+    pub slot: ControlSlot,
+    pub position: Vec2,
+    pub anchor: Vec2,
+}
+
+/// Control points of every curve in a path, in command order; none for other shapes
+pub fn control_handles(geometry: &ShapeGeometry) -> Vec<ControlHandle> {
+    let ShapeGeometry::Path { commands } = geometry else {
+        return Vec::new();
+    };
+    let mut handles = Vec::new();
+    let mut current = Vec2::ZERO;
+    let mut subpath_start = Vec2::ZERO;
+    for (command, cmd) in commands.iter().enumerate() {
+        match cmd {
+            PathCommand::MoveTo(p) => {
+                subpath_start = *p;
+                current = *p;
+            }
+            PathCommand::LineTo(to) | PathCommand::ArcTo { to, .. } => current = *to,
+            PathCommand::QuadraticTo { control, to } => {
+                handles.push(ControlHandle { command, slot: ControlSlot::Quadratic, position: *control, anchor: *to });
+                current = *to;
+            }
+            PathCommand::CubicTo { ctrl1, ctrl2, to } => {
+                handles.push(ControlHandle { command, slot: ControlSlot::Cubic1, position: *ctrl1, anchor: current });
+                handles.push(ControlHandle { command, slot: ControlSlot::Cubic2, position: *ctrl2, anchor: *to });
+                current = *to;
+            }
+            PathCommand::Close => current = subpath_start,
+        }
+    }
+    handles
+}
+
+/// Index of the command whose end point is vertex `index`
+fn vertex_command(commands: &[PathCommand], index: usize) -> Option<usize> {
+    commands
+        .iter()
+        .enumerate()
+        .filter(|(_, cmd)| !matches!(cmd, PathCommand::Close))
+        .nth(index)
+        .map(|(command, _)| command)
+}
+
+/// `geometry` with vertex `index` moved to `point`
+/// A path vertex carries the cubic control points on either side of it along, so the
+/// curves through it keep their shape
+pub fn move_vertex(geometry: &ShapeGeometry, index: usize, point: Vec2) -> Option<ShapeGeometry> {
+    match geometry {
+        ShapeGeometry::Polygon { points } => {
+            let mut points = points.clone();
+            *points.get_mut(index)? = point;
+            Some(ShapeGeometry::Polygon { points })
+        }
+        ShapeGeometry::Path { commands } => {
+            let command = vertex_command(commands, index)?;
+            let mut commands = commands.clone();
+            let (ctrl2, to) = match &mut commands[command] {
+                PathCommand::MoveTo(to)
+                | PathCommand::LineTo(to)
+                | PathCommand::QuadraticTo { to, .. }
+                | PathCommand::ArcTo { to, .. } => (None, to),
+                PathCommand::CubicTo { ctrl2, to, .. } => (Some(ctrl2), to),
+                PathCommand::Close => return None,
+            };
+            let delta = point - *to;
+            *to = point;
+            if let Some(ctrl2) = ctrl2 {
+                *ctrl2 += delta;
+            }
+            // The next segment's first control leaves from this vertex
+            if let Some(PathCommand::CubicTo { ctrl1, .. }) = commands.get_mut(command + 1) {
+                *ctrl1 += delta;
+            }
+            Some(ShapeGeometry::Path { commands })
+        }
+        _ => None,
+    }
+}
+
+/// `geometry` with the control point in `slot` of curve `command` moved to `point`
+pub fn move_control(geometry: &ShapeGeometry, command: usize, slot: ControlSlot, point: Vec2) -> Option<ShapeGeometry> {
+    let ShapeGeometry::Path { commands } = geometry else {
+        return None;
+    };
+    let mut commands = commands.clone();
+    let control = match (commands.get_mut(command)?, slot) {
+        (PathCommand::QuadraticTo { control, .. }, ControlSlot::Quadratic) => control,
+        (PathCommand::CubicTo { ctrl1, .. }, ControlSlot::Cubic1) => ctrl1,
+        (PathCommand::CubicTo { ctrl2, .. }, ControlSlot::Cubic2) => ctrl2,
+        _ => return None,
+    };
+    *control = point;
+    Some(ShapeGeometry::Path { commands })
+}
+
+/// Where a vertex goes for a click at `point` near the closed ring `points`: the index
+/// the new vertex takes and its position on the nearest edge, or None when no edge is
+/// within `tolerance`
+pub fn edge_insertion(points: &[Vec2], point: Vec2, tolerance: f32) -> Option<(usize, Vec2)> {
+    let n = points.len();
+    if n < 2 {
+        return None;
+    }
+    let (edge, distance) = (0..n)
+        .map(|i| (i, point_segment_distance(point, points[i], points[(i + 1) % n])))
+        .min_by(|a, b| a.1.total_cmp(&b.1))?;
+    if distance > tolerance {
+        return None;
+    }
+
+    let (a, b) = (points[edge], points[(edge + 1) % n]);
+    let t = if a == b { 0.0 } else { ((point - a).dot(b - a) / a.distance_squared(b)).clamp(0.0, 1.0) };
+    Some((edge + 1, a.lerp(b, t)))
+}
+
+/// `geometry` without polygon vertex `index`; None when out of range or the polygon
+/// would drop below `MIN_POLYGON_VERTICES`
+pub fn remove_vertex(geometry: &ShapeGeometry, index: usize) -> Option<ShapeGeometry> {
+    let ShapeGeometry::Polygon { points } = geometry else {
+        return None;
+    };
+    if index >= points.len() || points.len() <= MIN_POLYGON_VERTICES {
+        return None;
+    }
+    let mut points = points.clone();
+    points.remove(index);
+    Some(ShapeGeometry::Polygon { points })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square() -> ShapeGeometry {
+        ShapeGeometry::polygon(vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(100.0, 0.0),
+            Vec2::new(100.0, 100.0),
+            Vec2::new(0.0, 100.0),
+        ])
+    }
+
+    fn curved_path() -> ShapeGeometry {
+        ShapeGeometry::Path {
+            commands: vec![
+                PathCommand::MoveTo(Vec2::new(0.0, 0.0)),
+                PathCommand::CubicTo { ctrl1: Vec2::new(10.0, -20.0), ctrl2: Vec2::new(40.0, -20.0), to: Vec2::new(50.0, 0.0) },
+                PathCommand::CubicTo { ctrl1: Vec2::new(60.0, 20.0), ctrl2: Vec2::new(90.0, 20.0), to: Vec2::new(100.0, 0.0) },
+                PathCommand::QuadraticTo { control: Vec2::new(50.0, 80.0), to: Vec2::new(0.0, 50.0) },
+                PathCommand::Close,
+            ],
+        }
+    }
+
+    #[test]
+    fn test_edge_insertion_index_follows_the_clicked_edge() {
+        let ShapeGeometry::Polygon { points } = square() else { unreachable!() };
+        let insertion = |point: Vec2| {
+            let (index, on_edge) = edge_insertion(&points, point, 5.0).unwrap();
+            (index, (on_edge * 1000.0).round() / 1000.0)
+        };
+        // Each edge i -> i+1 takes the new vertex at i+1; the closing edge appends it
+        assert_eq!(insertion(Vec2::new(50.0, 2.0)), (1, Vec2::new(50.0, 0.0)));
+        assert_eq!(insertion(Vec2::new(98.0, 30.0)), (2, Vec2::new(100.0, 30.0)));
+        assert_eq!(insertion(Vec2::new(40.0, 103.0)), (3, Vec2::new(40.0, 100.0)));
+        assert_eq!(insertion(Vec2::new(-1.0, 70.0)), (4, Vec2::new(0.0, 70.0)));
+
+        // Too far from every edge
+        assert_eq!(edge_insertion(&points, Vec2::new(50.0, 50.0), 5.0), None);
+        assert_eq!(edge_insertion(&points[..1], Vec2::ZERO, 5.0), None);
+    }
+
+    #[test]
+    fn test_remove_vertex_keeps_a_triangle() {
+        let triangle = remove_vertex(&square(), 1).unwrap();
+        assert_eq!(
+            triangle.vertices(),
+            vec![Vec2::new(0.0, 0.0), Vec2::new(100.0, 100.0), Vec2::new(0.0, 100.0)]
+        );
+        assert_eq!(remove_vertex(&triangle, 0), None);
+        assert_eq!(remove_vertex(&square(), 4), None);
+        assert_eq!(remove_vertex(&curved_path(), 0), None);
+    }
+
+    #[test]
+    fn test_control_handles_tie_to_their_segment_ends() {
+        let handles = control_handles(&curved_path());
+        let summary: Vec<(usize, ControlSlot, Vec2)> = handles.iter().map(|h| (h.command, h.slot, h.anchor)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                (1, ControlSlot::Cubic1, Vec2::new(0.0, 0.0)),
+                (1, ControlSlot::Cubic2, Vec2::new(50.0, 0.0)),
+                (2, ControlSlot::Cubic1, Vec2::new(50.0, 0.0)),
+                (2, ControlSlot::Cubic2, Vec2::new(100.0, 0.0)),
+                (3, ControlSlot::Quadratic, Vec2::new(0.0, 50.0)),
+            ]
+        );
+        assert_eq!(handles[4].position, Vec2::new(50.0, 80.0));
+        assert!(control_handles(&square()).is_empty());
+    }
+
+    #[test]
+    fn test_move_path_vertex_carries_its_controls() {
+        let moved = move_vertex(&curved_path(), 1, Vec2::new(50.0, 10.0)).unwrap();
+        let ShapeGeometry::Path { commands } = &moved else { unreachable!() };
+        assert_eq!(
+            commands[1],
+            PathCommand::CubicTo { ctrl1: Vec2::new(10.0, -20.0), ctrl2: Vec2::new(40.0, -10.0), to: Vec2::new(50.0, 10.0) }
+        );
+        assert_eq!(
+            commands[2],
+            PathCommand::CubicTo { ctrl1: Vec2::new(60.0, 30.0), ctrl2: Vec2::new(90.0, 20.0), to: Vec2::new(100.0, 0.0) }
+        );
+
+        // Vertex 0 is the MoveTo; its outgoing control follows it
+        let moved = move_vertex(&curved_path(), 0, Vec2::new(-10.0, 0.0)).unwrap();
+        let ShapeGeometry::Path { commands } = &moved else { unreachable!() };
+        assert_eq!(commands[0], PathCommand::MoveTo(Vec2::new(-10.0, 0.0)));
+        assert!(matches!(commands[1], PathCommand::CubicTo { ctrl1, .. } if ctrl1 == Vec2::new(0.0, -20.0)));
+
+        // Vertex 3 ends the quadratic; Close isn't a vertex
+        let moved = move_vertex(&curved_path(), 3, Vec2::new(0.0, 60.0)).unwrap();
+        assert_eq!(moved.vertices()[3], Vec2::new(0.0, 60.0));
+        assert!(move_vertex(&curved_path(), 4, Vec2::ZERO).is_none());
+
+        let moved = move_vertex(&square(), 2, Vec2::new(120.0, 120.0)).unwrap();
+        assert_eq!(moved.vertices()[2], Vec2::new(120.0, 120.0));
+        assert!(move_vertex(&square(), 4, Vec2::ZERO).is_none());
+    }
+
+    #[test]
+    fn test_move_control() {
+        let moved = move_control(&curved_path(), 2, ControlSlot::Cubic2, Vec2::new(95.0, 40.0)).unwrap();
+        let ShapeGeometry::Path { commands } = &moved else { unreachable!() };
+        assert!(matches!(commands[2], PathCommand::CubicTo { ctrl2, to, .. } if ctrl2 == Vec2::new(95.0, 40.0) && to == Vec2::new(100.0, 0.0)));
+
+        let moved = move_control(&curved_path(), 3, ControlSlot::Quadratic, Vec2::new(20.0, 90.0)).unwrap();
+        assert_eq!(control_handles(&moved)[4].position, Vec2::new(20.0, 90.0));
+
+        // The slot has to match the command
+        assert!(move_control(&curved_path(), 3, ControlSlot::Cubic1, Vec2::ZERO).is_none());
+        assert!(move_control(&curved_path(), 0, ControlSlot::Quadratic, Vec2::ZERO).is_none());
+        assert!(move_control(&square(), 0, ControlSlot::Quadratic, Vec2::ZERO).is_none());
+    }
+}
@@ -1,9 +1,124 @@
 use crate::scene::Shape;
-use crate::types::{BoundingBox, Guideline, GuidelineType, Point};
+use crate::types::{BoundingBox, Dimensions, Guideline, GuidelineType, HandleName, Point, SpacingIndicator};
+
+/// Options controlling whether and to what the selection snaps while dragging
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SnapConfig {
+    pub enabled: bool,
+    /// Maximum distance (px) at which a snap engages
+    pub threshold: f64,
+    /// Snap to the bounds of other shapes (and equal spacing between them)
+    pub snap_to_shapes: bool,
+    /// Snap to the canvas edges and center
+    pub snap_to_canvas: bool,
+    /// Also snap to the actual vertices of other shapes (e.g. a triangle's apex)
+    pub snap_to_vertices: bool,
+    /// Upper bound on vertices taken from any single shape, to keep complex paths cheap
+    pub max_vertices_per_shape: usize,
+    /// Round the box origin to the grid on axes with no other snap
+    pub snap_to_grid: bool,
+    pub grid_size: f64,
+}
+
+impl Default for SnapConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            threshold: 10.0,
+            snap_to_shapes: true,
+            snap_to_canvas: true,
+            snap_to_vertices: true,
+            max_vertices_per_shape: 64,
+            snap_to_grid: false,
+            grid_size: 10.0,
+        }
+    }
+}
+
+impl SnapConfig {
+    /// The config to use for one drag event; holding Ctrl/Cmd turns snapping off
+    pub fn bypassed(self, modifier_held: bool) -> Self {
+        Self {
+            enabled: self.enabled && !modifier_held,
+            ..self
+        }
+    }
+
+    /// The config for a canvas zoomed by `zoom`; the threshold stays constant on screen
+    pub fn at_zoom(self, zoom: f64) -> Self {
+        Self {
+            threshold: self.threshold / zoom,
+            ..self
+        }
+    }
+
+    /// Offset that moves `value` onto the nearest grid line, if grid snapping is on
+    fn grid_delta(&self, value: f64) -> Option<f64> {
+        (self.snap_to_grid && self.grid_size > 0.0)
+            .then(|| (value / self.grid_size).round() * self.grid_size - value)
+    }
+}
 
 pub struct SnapResult {
     pub translation: Point,
     pub guidelines: Vec<Guideline>,
+    pub spacing_indicators: Vec<SpacingIndicator>,
+}
+
+impl SnapResult {
+    /// Drop the snap along the axis a Shift-drag has locked, keeping the free axis
+    pub fn restricted_to(mut self, axis: AxisLock) -> Self {
+        // Vertical guidelines and horizontal spacing markers come from x snaps
+        let free = match axis {
+            AxisLock::Horizontal => {
+                self.translation.y = 0.0;
+                GuidelineType::Vertical
+            }
+            AxisLock::Vertical => {
+                self.translation.x = 0.0;
+                GuidelineType::Horizontal
+            }
+        };
+        let free_spacing = match free {
+            GuidelineType::Vertical => GuidelineType::Horizontal,
+            GuidelineType::Horizontal => GuidelineType::Vertical,
+        };
+        self.guidelines.retain(|g| g.guideline_type == free);
+        self.spacing_indicators.retain(|s| s.axis == free_spacing);
+        self
+    }
+}
+
+/// Axis a Shift-drag moves along
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AxisLock {
+    Horizontal,
+    Vertical,
+}
+
+impl AxisLock {
+    /// The axis with the larger movement; ties go to horizontal
+    pub fn from_delta(delta_x: f64, delta_y: f64) -> Self {
+        if delta_x.abs() >= delta_y.abs() {
+            AxisLock::Horizontal
+        } else {
+            AxisLock::Vertical
+        }
+    }
+
+    /// Zero the component of `(delta_x, delta_y)` off this axis
+    pub fn apply(self, delta_x: f64, delta_y: f64) -> (f64, f64) {
+        match self {
+            AxisLock::Horizontal => (delta_x, 0.0),
+            AxisLock::Vertical => (0.0, delta_y),
+        }
+    }
+}
+
+pub struct ResizeSnapResult {
+    /// Signed dimensions after snapping the dragged edges
+    pub dimensions: Dimensions,
+    pub guidelines: Vec<Guideline>,
 }
 
 struct SnapCheck {
@@ -35,16 +150,74 @@ fn check_snap(
     })
 }
 
-pub fn calculate_snap(
-    proposed_box: &BoundingBox,
-    shapes: &[Shape],
-    excluded_ids: &[u64],
-    canvas_width: f64,
-    canvas_height: f64,
-    threshold: f64,
-) -> SnapResult {
-    // Calculate bounding boxes for non-excluded shapes
-    let mut other_boxes: Vec<BoundingBox> = shapes
+/// A box projected onto one axis: `min..max` along the axis, `cross_min..cross_max` across it
+#[derive(Clone, Copy)]
+struct AxisSpan {
+    min: f64,
+    max: f64,
+    cross_min: f64,
+    cross_max: f64,
+}
+
+impl AxisSpan {
+    fn along_x(b: &BoundingBox) -> Self {
+        Self { min: b.x, max: b.x + b.width, cross_min: b.y, cross_max: b.y + b.height }
+    }
+
+    fn along_y(b: &BoundingBox) -> Self {
+        Self { min: b.y, max: b.y + b.height, cross_min: b.x, cross_max: b.x + b.width }
+    }
+
+    fn overlaps_cross(&self, other: &AxisSpan) -> bool {
+        self.cross_min < other.cross_max && other.cross_min < self.cross_max
+    }
+}
+
+/// Equal-spacing snap along one axis
+struct SpacingSnap {
+    dist: f64,
+    snap_delta: f64,
+    /// Where each gap starts along the axis (before and after the proposed box)
+    gap_starts: [f64; 2],
+    gap: f64,
+}
+
+/// Find the closest position (within `threshold`) that centers `proposed` between
+/// two neighbors on either side of it, making both gaps equal
+fn find_equal_spacing(proposed: &AxisSpan, neighbors: &[AxisSpan], threshold: f64) -> Option<SpacingSnap> {
+    let size = proposed.max - proposed.min;
+    let mut best: Option<SpacingSnap> = None;
+
+    for before in neighbors.iter().filter(|n| n.overlaps_cross(proposed)) {
+        for after in neighbors.iter().filter(|n| n.overlaps_cross(proposed)) {
+            let gap = (after.min - before.max - size) / 2.0;
+            if gap <= 0.0 {
+                continue;
+            }
+
+            let target = before.max + gap;
+            let dist = (target - proposed.min).abs();
+            if dist < threshold && best.as_ref().is_none_or(|b| dist < b.dist) {
+                best = Some(SpacingSnap {
+                    dist,
+                    snap_delta: target - proposed.min,
+                    gap_starts: [before.max, target + size],
+                    gap,
+                });
+            }
+        }
+    }
+
+    best
+}
+
+/// Bounding boxes of the non-excluded shapes (empty when shape snapping is off)
+fn collect_shape_boxes(shapes: &[Shape], excluded_ids: &[u64], config: &SnapConfig) -> Vec<BoundingBox> {
+    if !config.snap_to_shapes {
+        return Vec::new();
+    }
+
+    shapes
         .iter()
         .filter(|shape| !excluded_ids.contains(&shape.id))
         .map(|shape| {
@@ -56,10 +229,63 @@ pub fn calculate_snap(
                 bbox.height() as f64,
             )
         })
-        .collect();
+        .collect()
+}
+
+/// Bounding boxes of all snap targets: non-excluded shapes plus the canvas itself
+fn collect_snap_targets(
+    shapes: &[Shape],
+    excluded_ids: &[u64],
+    canvas_width: f64,
+    canvas_height: f64,
+    config: &SnapConfig,
+) -> Vec<BoundingBox> {
+    let mut other_boxes = collect_shape_boxes(shapes, excluded_ids, config);
 
     // Add canvas edges as a bounding box
-    other_boxes.push(BoundingBox::new(0.0, 0.0, canvas_width, canvas_height));
+    if config.snap_to_canvas {
+        other_boxes.push(BoundingBox::new(0.0, 0.0, canvas_width, canvas_height));
+    }
+    other_boxes
+}
+
+/// World-space vertices of all non-excluded shapes, capped per shape
+fn collect_vertex_targets(shapes: &[Shape], excluded_ids: &[u64], max_per_shape: usize) -> Vec<Point> {
+    shapes
+        .iter()
+        .filter(|shape| !excluded_ids.contains(&shape.id))
+        .flat_map(|shape| {
+            shape
+                .geometry
+                .vertices()
+                .into_iter()
+                .take(max_per_shape)
+                .map(|v| {
+                    let world = shape.transform.transform_point(v);
+                    Point::new(world.x as f64, world.y as f64)
+                })
+        })
+        .collect()
+}
+
+pub fn calculate_snap(
+    proposed_box: &BoundingBox,
+    shapes: &[Shape],
+    excluded_ids: &[u64],
+    canvas_width: f64,
+    canvas_height: f64,
+    config: &SnapConfig,
+) -> SnapResult {
+    if !config.enabled {
+        return SnapResult {
+            translation: Point::new(0.0, 0.0),
+            guidelines: Vec::new(),
+            spacing_indicators: Vec::new(),
+        };
+    }
+
+    let threshold = config.threshold;
+    let other_boxes = collect_snap_targets(shapes, excluded_ids, canvas_width, canvas_height, config);
 
     let mut guidelines = Vec::new();
     let mut snap_delta_x = 0.0;
@@ -155,6 +381,71 @@ pub fn calculate_snap(
         }
     }
 
+    // Vertex snaps; guides span from the vertex to the dragged box
+    let vertices = if config.snap_to_shapes && config.snap_to_vertices {
+        collect_vertex_targets(shapes, excluded_ids, config.max_vertices_per_shape)
+    } else {
+        Vec::new()
+    };
+    for vertex in &vertices {
+        for &edge in &edges_x {
+            let start = proposed_box.y.min(vertex.y);
+            let end = (proposed_box.y + proposed_box.height).max(vertex.y);
+            if let Some(result) = check_snap(edge, vertex.x, GuidelineType::Vertical, start, end, threshold, min_dist_x) {
+                min_dist_x = result.dist;
+                snap_delta_x = result.snap_delta;
+                best_x_match = Some(SnapMatch { target: result.pos, start: result.start, end: result.end });
+            }
+        }
+
+        for &edge in &edges_y {
+            let start = proposed_box.x.min(vertex.x);
+            let end = (proposed_box.x + proposed_box.width).max(vertex.x);
+            if let Some(result) = check_snap(edge, vertex.y, GuidelineType::Horizontal, start, end, threshold, min_dist_y) {
+                min_dist_y = result.dist;
+                snap_delta_y = result.snap_delta;
+                best_y_match = Some(SnapMatch { target: result.pos, start: result.start, end: result.end });
+            }
+        }
+    }
+
+    // Equal-spacing snaps between pairs of neighbors (the canvas itself is not a neighbor)
+    let neighbors = collect_shape_boxes(shapes, excluded_ids, config);
+    let spacing_x = find_equal_spacing(
+        &AxisSpan::along_x(proposed_box),
+        &neighbors.iter().map(AxisSpan::along_x).collect::<Vec<_>>(),
+        threshold,
+    )
+    .filter(|snap| snap.dist < min_dist_x);
+    let spacing_y = find_equal_spacing(
+        &AxisSpan::along_y(proposed_box),
+        &neighbors.iter().map(AxisSpan::along_y).collect::<Vec<_>>(),
+        threshold,
+    )
+    .filter(|snap| snap.dist < min_dist_y);
+
+    // A closer spacing snap replaces the edge snap on that axis
+    if let Some(snap) = &spacing_x {
+        snap_delta_x = snap.snap_delta;
+        best_x_match = None;
+    }
+    if let Some(snap) = &spacing_y {
+        snap_delta_y = snap.snap_delta;
+        best_y_match = None;
+    }
+
+    // Axes with no shape, canvas or spacing snap fall back to the grid
+    if best_x_match.is_none() && spacing_x.is_none() {
+        if let Some(delta) = config.grid_delta(proposed_box.x) {
+            snap_delta_x = delta;
+        }
+    }
+    if best_y_match.is_none() && spacing_y.is_none() {
+        if let Some(delta) = config.grid_delta(proposed_box.y) {
+            snap_delta_y = delta;
+        }
+    }
+
     if let Some(match_x) = best_x_match {
         guidelines.push(Guideline::new(
             GuidelineType::Vertical,
@@ -173,12 +464,147 @@ pub fn calculate_snap(
         ));
     }
 
+    // Spacing markers run through the middle of the snapped box
+    let mut spacing_indicators = Vec::new();
+    if let Some(snap) = spacing_x {
+        let y = proposed_box.y + snap_delta_y + proposed_box.height / 2.0;
+        for start in snap.gap_starts {
+            spacing_indicators.push(SpacingIndicator::new(GuidelineType::Horizontal, Point::new(start, y), snap.gap));
+        }
+    }
+    if let Some(snap) = spacing_y {
+        let x = proposed_box.x + snap_delta_x + proposed_box.width / 2.0;
+        for start in snap.gap_starts {
+            spacing_indicators.push(SpacingIndicator::new(GuidelineType::Vertical, Point::new(x, start), snap.gap));
+        }
+    }
+
     SnapResult {
         translation: Point::new(snap_delta_x, snap_delta_y),
         guidelines,
+        spacing_indicators,
     }
 }
 
+/// Snap the edges being dragged during a resize.
+///
+/// `proposed_box` is anchored at the fixed corner: `x`/`y` is the anchor and
+/// `width`/`height` are the signed dimensions, so the dragged edges sit at
+/// `x + width` and `y + height`. Only the axes the handle controls are snapped,
+/// which keeps the anchored edges fixed.
+pub fn calculate_resize_snap(
+    proposed_box: &BoundingBox,
+    active_handle: HandleName,
+    shapes: &[Shape],
+    excluded_ids: &[u64],
+    canvas_width: f64,
+    canvas_height: f64,
+    config: &SnapConfig,
+) -> ResizeSnapResult {
+    if !config.enabled {
+        return ResizeSnapResult {
+            dimensions: Dimensions::new(proposed_box.width, proposed_box.height),
+            guidelines: Vec::new(),
+        };
+    }
+
+    let threshold = config.threshold;
+    let other_boxes = collect_snap_targets(shapes, excluded_ids, canvas_width, canvas_height, config);
+
+    let snaps_x = !matches!(active_handle, HandleName::Top | HandleName::Bottom);
+    let snaps_y = !matches!(active_handle, HandleName::Left | HandleName::Right);
+
+    let edge_x = proposed_box.x + proposed_box.width;
+    let edge_y = proposed_box.y + proposed_box.height;
+    let (min_y, max_y) = (proposed_box.y.min(edge_y), proposed_box.y.max(edge_y));
+    let (min_x, max_x) = (proposed_box.x.min(edge_x), proposed_box.x.max(edge_x));
+
+    let mut best_x: Option<SnapCheck> = None;
+    let mut best_y: Option<SnapCheck> = None;
+
+    for target_box in &other_boxes {
+        if snaps_x {
+            let start = min_y.min(target_box.y);
+            let end = max_y.max(target_box.y + target_box.height);
+            for target in [
+                target_box.x,
+                target_box.x + target_box.width / 2.0,
+                target_box.x + target_box.width,
+            ] {
+                let current = best_x.as_ref().map_or(threshold, |b| b.dist);
+                if let Some(result) = check_snap(edge_x, target, GuidelineType::Vertical, start, end, threshold, current) {
+                    best_x = Some(result);
+                }
+            }
+        }
+
+        if snaps_y {
+            let start = min_x.min(target_box.x);
+            let end = max_x.max(target_box.x + target_box.width);
+            for target in [
+                target_box.y,
+                target_box.y + target_box.height / 2.0,
+                target_box.y + target_box.height,
+            ] {
+                let current = best_y.as_ref().map_or(threshold, |b| b.dist);
+                if let Some(result) = check_snap(edge_y, target, GuidelineType::Horizontal, start, end, threshold, current) {
+                    best_y = Some(result);
+                }
+            }
+        }
+    }
+
+    let mut dimensions = Dimensions::new(proposed_box.width, proposed_box.height);
+    let mut guidelines = Vec::new();
+
+    if let Some(snap) = best_x {
+        dimensions.width += snap.snap_delta;
+        guidelines.push(Guideline::new(GuidelineType::Vertical, snap.pos, snap.start, snap.end));
+    } else if let Some(delta) = config.grid_delta(edge_x).filter(|_| snaps_x) {
+        dimensions.width += delta;
+    }
+
+    if let Some(snap) = best_y {
+        dimensions.height += snap.snap_delta;
+        guidelines.push(Guideline::new(GuidelineType::Horizontal, snap.pos, snap.start, snap.end));
+    } else if let Some(delta) = config.grid_delta(edge_y).filter(|_| snaps_y) {
+        dimensions.height += delta;
+    }
+
+    ResizeSnapResult { dimensions, guidelines }
+}
+
+/// Constrain signed resize dimensions to the aspect ratio of `base` (Shift-resize).
+///
+/// Corner handles follow whichever axis moved further relative to its base size;
+/// edge handles scale the perpendicular axis by the dragged axis. Each axis keeps
+/// the sign of `dims` (or of `base` for the undragged axis of an edge handle), so
+/// dragging past the anchor still flips the box. Neither axis shrinks below `min_size`.
+pub fn constrain_resize_aspect(dims: Dimensions, base: Dimensions, handle: HandleName, min_size: f64) -> Dimensions {
+    if base.width == 0.0 || base.height == 0.0 {
+        return dims;
+    }
+
+    let scale_x = dims.width / base.width;
+    let scale_y = dims.height / base.height;
+    let scale = match handle {
+        HandleName::Left | HandleName::Right => scale_x.abs(),
+        HandleName::Top | HandleName::Bottom => scale_y.abs(),
+        _ if (scale_x - 1.0).abs() >= (scale_y - 1.0).abs() => scale_x.abs(),
+        _ => scale_y.abs(),
+    };
+    let scale = scale
+        .max(min_size / base.width.abs())
+        .max(min_size / base.height.abs());
+
+    let (sign_x, sign_y) = match handle {
+        HandleName::Left | HandleName::Right => (dims.width.signum(), base.height.signum()),
+        HandleName::Top | HandleName::Bottom => (base.width.signum(), dims.height.signum()),
+        _ => (dims.width.signum(), dims.height.signum()),
+    };
+    Dimensions::new(sign_x * base.width.abs() * scale, sign_y * base.height.abs() * scale)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -195,12 +621,12 @@ mod tests {
 
         // Proposed box at (160, 100) - just outside snap threshold
         let proposed = BoundingBox::new(160.0, 100.0, 30.0, 30.0);
-        let result = calculate_snap(&proposed, &[target.clone()], &[], 800.0, 600.0, 10.0);
+        let result = calculate_snap(&proposed, &[target.clone()], &[], 800.0, 600.0, &SnapConfig::default());
         assert_eq!(result.translation.x, 0.0); // No snap - too far
 
         // Proposed box at (155, 100) - within threshold of target right edge (150)
         let proposed = BoundingBox::new(155.0, 100.0, 30.0, 30.0);
-        let result = calculate_snap(&proposed, &[target], &[], 800.0, 600.0, 10.0);
+        let result = calculate_snap(&proposed, &[target], &[], 800.0, 600.0, &SnapConfig::default());
         assert_eq!(result.translation.x, -5.0); // Snap to align left edge with right edge
         assert_eq!(result.guidelines.len(), 1);
     }
@@ -217,10 +643,34 @@ mod tests {
         // Proposed box at (108, 200) with 30x30 size (center at 123)
         // Should snap center to 125
         let proposed = BoundingBox::new(108.0, 200.0, 30.0, 30.0);
-        let result = calculate_snap(&proposed, &[target], &[], 800.0, 600.0, 10.0);
+        let result = calculate_snap(&proposed, &[target], &[], 800.0, 600.0, &SnapConfig::default());
         assert_eq!(result.translation.x, 2.0); // Snap center 123 -> 125
     }
 
+    #[test]
+    fn test_snap_center_to_center() {
+        // The request's case: a 30x30 target at (100, 100), centered on (115, 115)
+        let target = Shape::new(
+            ShapeGeometry::rectangle(30.0, 30.0),
+            ShapeStyle::default(),
+        )
+        .with_transform(Transform2D::from_position(Vec2::new(100.0, 100.0)));
+        assert_eq!(target.world_bounds().center(), Vec2::new(115.0, 115.0));
+
+        // A moving 20x20 box at (103, 107): its center is nearer the target's than any edge pair
+        let proposed = BoundingBox::new(103.0, 107.0, 20.0, 20.0);
+        let result = calculate_snap(&proposed, &[target], &[], 800.0, 600.0, &SnapConfig::default());
+        let snapped = BoundingBox::new(
+            proposed.x + result.translation.x,
+            proposed.y + result.translation.y,
+            proposed.width,
+            proposed.height,
+        );
+        assert_eq!((snapped.x, snapped.y), (105.0, 105.0));
+        assert_eq!((snapped.x + snapped.width / 2.0, snapped.y + snapped.height / 2.0), (115.0, 115.0));
+        assert_eq!(result.guidelines.len(), 2);
+    }
+
     #[test]
     fn test_snap_excludes_self() {
         // Create two shapes
@@ -241,7 +691,7 @@ mod tests {
         // Propose moving shape at index 0 to near shape at index 1
         // excluded_ids=[0] should prevent snapping to self
         let proposed = BoundingBox::new(145.0, 100.0, 50.0, 50.0);
-        let result = calculate_snap(&proposed, &shapes, &[0], 800.0, 600.0, 10.0);
+        let result = calculate_snap(&proposed, &shapes, &[0], 800.0, 600.0, &SnapConfig::default());
 
         // Should snap to shape at index 1 (right edge at 250), not to self
         // proposed right edge at 195, target left edge at 200 -> delta +5
@@ -252,7 +702,7 @@ mod tests {
     fn test_snap_to_canvas_edge() {
         // No other shapes, should snap to canvas edges
         let proposed = BoundingBox::new(5.0, 5.0, 30.0, 30.0);
-        let result = calculate_snap(&proposed, &[], &[], 800.0, 600.0, 10.0);
+        let result = calculate_snap(&proposed, &[], &[], 800.0, 600.0, &SnapConfig::default());
 
         // Should snap to canvas origin (0, 0)
         assert_eq!(result.translation.x, -5.0);
@@ -265,7 +715,7 @@ mod tests {
         // Proposed box center at x=397 (box x=382, width=30, center=397)
         // Should snap center to x=400, delta = +3
         let proposed = BoundingBox::new(382.0, 100.0, 30.0, 30.0);
-        let result = calculate_snap(&proposed, &[], &[], 800.0, 600.0, 10.0);
+        let result = calculate_snap(&proposed, &[], &[], 800.0, 600.0, &SnapConfig::default());
 
         // Proposed center is at 382 + 15 = 397, canvas center is 400
         // Snap delta should be 3.0 to align centers
@@ -284,7 +734,7 @@ mod tests {
         // Proposed box center at y=297 (box y=282, height=30, center=297)
         // Should snap center to y=300, delta = +3
         let proposed = BoundingBox::new(100.0, 282.0, 30.0, 30.0);
-        let result = calculate_snap(&proposed, &[], &[], 800.0, 600.0, 10.0);
+        let result = calculate_snap(&proposed, &[], &[], 800.0, 600.0, &SnapConfig::default());
 
         // Proposed center is at 282 + 15 = 297, canvas center is 300
         // Snap delta should be 3.0 to align centers
@@ -303,7 +753,7 @@ mod tests {
         // Proposed box at (382, 282) with size 30x30
         // Center would be at (397, 297), should snap to (400, 300)
         let proposed = BoundingBox::new(382.0, 282.0, 30.0, 30.0);
-        let result = calculate_snap(&proposed, &[], &[], 800.0, 600.0, 10.0);
+        let result = calculate_snap(&proposed, &[], &[], 800.0, 600.0, &SnapConfig::default());
 
         assert_eq!(result.translation.x, 3.0);
         assert_eq!(result.translation.y, 3.0);
@@ -316,7 +766,7 @@ mod tests {
         // Proposed box with right edge at x=795 (box x=765, width=30, right=795)
         // Should snap right edge to x=800, delta = +5
         let proposed = BoundingBox::new(765.0, 100.0, 30.0, 30.0);
-        let result = calculate_snap(&proposed, &[], &[], 800.0, 600.0, 10.0);
+        let result = calculate_snap(&proposed, &[], &[], 800.0, 600.0, &SnapConfig::default());
 
         // Right edge at 765 + 30 = 795, canvas right edge is 800
         // Snap delta should be 5.0
@@ -329,10 +779,300 @@ mod tests {
         // Proposed box with bottom edge at y=595 (box y=565, height=30, bottom=595)
         // Should snap bottom edge to y=600, delta = +5
         let proposed = BoundingBox::new(100.0, 565.0, 30.0, 30.0);
-        let result = calculate_snap(&proposed, &[], &[], 800.0, 600.0, 10.0);
+        let result = calculate_snap(&proposed, &[], &[], 800.0, 600.0, &SnapConfig::default());
 
         // Bottom edge at 565 + 30 = 595, canvas bottom edge is 600
         // Snap delta should be 5.0
         assert_eq!(result.translation.y, 5.0);
     }
+
+    #[test]
+    fn test_resize_snap_right_edge_to_neighbor() {
+        // Neighbor's left edge at x=200
+        let neighbor = Shape::new(
+            ShapeGeometry::rectangle(50.0, 50.0),
+            ShapeStyle::default(),
+        )
+        .with_transform(Transform2D::from_position(Vec2::new(200.0, 100.0)));
+
+        // Anchored at x=100, right edge dragged to 196
+        let proposed = BoundingBox::new(100.0, 100.0, 96.0, 40.0);
+        let result = calculate_resize_snap(&proposed, HandleName::Right, &[neighbor], &[], 800.0, 600.0, &SnapConfig::default());
+
+        assert_eq!(result.dimensions.width, 100.0); // Right edge snaps to 200
+        assert_eq!(result.dimensions.height, 40.0); // Right handle never changes height
+        assert_eq!(result.guidelines.len(), 1);
+        assert_eq!(result.guidelines[0].guideline_type, GuidelineType::Vertical);
+        assert_eq!(result.guidelines[0].pos, 200.0);
+    }
+
+    #[test]
+    fn test_resize_snap_keeps_anchor_fixed() {
+        // Dragging the left handle: anchor on the right at x=300, signed width negative
+        let neighbor = Shape::new(
+            ShapeGeometry::rectangle(50.0, 50.0),
+            ShapeStyle::default(),
+        )
+        .with_transform(Transform2D::from_position(Vec2::new(100.0, 100.0)));
+
+        // Left edge dragged to 153, near the neighbor's right edge at 150
+        let proposed = BoundingBox::new(300.0, 100.0, -147.0, 40.0);
+        let result = calculate_resize_snap(&proposed, HandleName::Left, &[neighbor], &[], 800.0, 600.0, &SnapConfig::default());
+
+        // Anchor + snapped width lands on 150; the anchor itself is untouched
+        assert_eq!(proposed.x + result.dimensions.width, 150.0);
+        assert_eq!(result.dimensions.width, -150.0);
+        assert_eq!(result.dimensions.height, 40.0);
+    }
+
+    fn rect_at(x: f32, y: f32, w: f32, h: f32) -> Shape {
+        Shape::new(ShapeGeometry::rectangle(w, h), ShapeStyle::default())
+            .with_transform(Transform2D::from_position(Vec2::new(x, y)))
+    }
+
+    #[test]
+    fn test_equal_spacing_horizontal() {
+        // Neighbors at x 100..150 and 250..300; a 40px box fits with 30px gaps at x=180
+        let shapes = vec![rect_at(100.0, 100.0, 50.0, 50.0), rect_at(250.0, 100.0, 50.0, 50.0)];
+        let proposed = BoundingBox::new(184.0, 105.0, 40.0, 40.0);
+        let result = calculate_snap(&proposed, &shapes, &[], 800.0, 600.0, &SnapConfig::default());
+
+        assert_eq!(result.translation.x, -4.0);
+        assert_eq!(result.spacing_indicators.len(), 2);
+        assert!(result.spacing_indicators.iter().all(|s| s.axis == GuidelineType::Horizontal));
+        assert!(result.spacing_indicators.iter().all(|s| s.length == 30.0));
+        assert_eq!(result.spacing_indicators[0].position.x, 150.0);
+        assert_eq!(result.spacing_indicators[1].position.x, 220.0);
+    }
+
+    #[test]
+    fn test_equal_spacing_vertical() {
+        // Neighbors at y 100..150 and 250..300; a 40px box fits with 30px gaps at y=180
+        let shapes = vec![rect_at(100.0, 100.0, 50.0, 50.0), rect_at(100.0, 250.0, 50.0, 50.0)];
+        let proposed = BoundingBox::new(105.0, 177.0, 40.0, 40.0);
+        let result = calculate_snap(&proposed, &shapes, &[], 800.0, 600.0, &SnapConfig::default());
+
+        assert_eq!(result.translation.y, 3.0);
+        assert_eq!(result.spacing_indicators.len(), 2);
+        assert!(result.spacing_indicators.iter().all(|s| s.axis == GuidelineType::Vertical));
+        assert!(result.spacing_indicators.iter().all(|s| s.length == 30.0));
+        assert_eq!(result.spacing_indicators[0].position.y, 150.0);
+        assert_eq!(result.spacing_indicators[1].position.y, 220.0);
+    }
+
+    fn triangle_apex_at(x: f32, y: f32) -> Shape {
+        // Skewed base keeps the apex off the bbox center and edges
+        Shape::new(
+            ShapeGeometry::polygon(vec![Vec2::new(x, y), Vec2::new(x + 20.0, y + 80.0), Vec2::new(x - 80.0, y + 80.0)]),
+            ShapeStyle::default(),
+        )
+    }
+
+    #[test]
+    fn test_snap_edge_to_triangle_apex() {
+        // Apex at (200, 100); triangle bbox is x 120..220, so 200 is not a bbox line
+        let shapes = vec![triangle_apex_at(200.0, 100.0)];
+        let proposed = BoundingBox::new(196.0, 300.0, 30.0, 30.0);
+        let result = calculate_snap(&proposed, &shapes, &[], 800.0, 600.0, &SnapConfig::default());
+
+        assert_eq!(result.translation.x, 4.0); // Left edge 196 -> apex 200
+        let guide = result
+            .guidelines
+            .iter()
+            .find(|g| g.guideline_type == GuidelineType::Vertical)
+            .unwrap();
+        assert_eq!(guide.pos, 200.0);
+        // Guide spans from the apex down to the bottom of the dragged box
+        assert_eq!(guide.start, 100.0);
+        assert_eq!(guide.end, 330.0);
+    }
+
+    #[test]
+    fn test_vertex_snapping_can_be_disabled() {
+        let shapes = vec![triangle_apex_at(200.0, 100.0)];
+        let proposed = BoundingBox::new(196.0, 300.0, 30.0, 30.0);
+        let config = SnapConfig { snap_to_vertices: false, ..SnapConfig::default() };
+        let result = calculate_snap(&proposed, &shapes, &[], 800.0, 600.0, &config);
+
+        // Without vertices the nearest target is the triangle's right bbox edge (220)
+        // from the box's right edge (226)
+        assert_eq!(result.translation.x, -6.0);
+    }
+
+    #[test]
+    fn test_threshold_override() {
+        // Box left edge 15px from the canvas origin: out of reach at the default threshold
+        let proposed = BoundingBox::new(15.0, 100.0, 30.0, 30.0);
+        let result = calculate_snap(&proposed, &[], &[], 800.0, 600.0, &SnapConfig::default());
+        assert_eq!(result.translation.x, 0.0);
+
+        let config = SnapConfig { threshold: 20.0, ..SnapConfig::default() };
+        let result = calculate_snap(&proposed, &[], &[], 800.0, 600.0, &config);
+        assert_eq!(result.translation.x, -15.0);
+    }
+
+    #[test]
+    fn test_grid_rounds_box_origin() {
+        let config = SnapConfig { snap_to_grid: true, grid_size: 25.0, ..SnapConfig::default() };
+        let proposed = BoundingBox::new(137.0, 161.0, 30.0, 30.0);
+        let result = calculate_snap(&proposed, &[], &[], 800.0, 600.0, &config);
+
+        assert_eq!(result.translation.x, -12.0); // 137 -> 125
+        assert_eq!(result.translation.y, -11.0); // 161 -> 150
+        assert!(result.guidelines.is_empty());
+    }
+
+    #[test]
+    fn test_modifier_bypass_returns_zero_translation() {
+        // Would snap (-5, -5) to the canvas origin without the modifier
+        let proposed = BoundingBox::new(5.0, 5.0, 30.0, 30.0);
+        let config = SnapConfig::default().bypassed(true);
+        let result = calculate_snap(&proposed, &[], &[], 800.0, 600.0, &config);
+
+        assert_eq!(result.translation.x, 0.0);
+        assert_eq!(result.translation.y, 0.0);
+        assert!(result.guidelines.is_empty());
+    }
+
+    #[test]
+    fn test_threshold_shrinks_in_world_space_when_zoomed_in() {
+        // 6 world px from the canvas origin: snaps at 1x, but is 24 screen px away at 4x
+        let proposed = BoundingBox::new(6.0, 6.0, 30.0, 30.0);
+        let snapped = calculate_snap(&proposed, &[], &[], 800.0, 600.0, &SnapConfig::default());
+        assert_eq!(snapped.translation.x, -6.0);
+
+        let config = SnapConfig::default().at_zoom(4.0);
+        let result = calculate_snap(&proposed, &[], &[], 800.0, 600.0, &config);
+        assert_eq!(result.translation.x, 0.0);
+    }
+
+    #[test]
+    fn test_snap_candidates_match_full_scan() {
+        use crate::scene::{BBox, SceneGraph};
+
+        // Deterministic LCG so failures are reproducible
+        let mut seed = 7u64;
+        let mut next = |max: f32| {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (seed >> 40) as f32 / (1u64 << 24) as f32 * max
+        };
+
+        let shapes: Vec<Shape> = (0..300)
+            .map(|_| rect_at(next(800.0), next(600.0), next(80.0) + 1.0, next(80.0) + 1.0))
+            .collect();
+        let scene = SceneGraph::from_shapes(shapes);
+        let config = SnapConfig::default();
+
+        for _ in 0..200 {
+            let proposed = BoundingBox::new(
+                next(800.0) as f64,
+                next(600.0) as f64,
+                next(100.0) as f64 + 1.0,
+                next(100.0) as f64 + 1.0,
+            );
+            let rect = BBox::new(
+                Vec2::new(proposed.x as f32, proposed.y as f32),
+                Vec2::new((proposed.x + proposed.width) as f32, (proposed.y + proposed.height) as f32),
+            );
+            let candidates = scene.snap_candidates(&rect, config.threshold as f32);
+
+            let full = calculate_snap(&proposed, scene.shapes(), &[], 800.0, 600.0, &config);
+            let indexed = calculate_snap(&proposed, &candidates, &[], 800.0, 600.0, &config);
+            assert_eq!(indexed.translation, full.translation);
+            assert_eq!(indexed.guidelines, full.guidelines);
+            assert_eq!(indexed.spacing_indicators, full.spacing_indicators);
+        }
+    }
+
+    #[test]
+    fn test_no_spacing_without_cross_overlap() {
+        // Right neighbor is far below the proposed box, so it doesn't count
+        let shapes = vec![rect_at(100.0, 100.0, 50.0, 50.0), rect_at(250.0, 400.0, 50.0, 50.0)];
+        let proposed = BoundingBox::new(184.0, 105.0, 40.0, 40.0);
+        let result = calculate_snap(&proposed, &shapes, &[], 800.0, 600.0, &SnapConfig::default());
+
+        assert!(result.spacing_indicators.is_empty());
+    }
+
+    #[test]
+    fn test_constrain_resize_aspect_corner_follows_dominant_axis() {
+        let base = Dimensions::new(100.0, 50.0);
+
+        // Width grew more, so height follows it
+        let dims = constrain_resize_aspect(Dimensions::new(200.0, 60.0), base, HandleName::BottomRight, 1.0);
+        assert_eq!(dims, Dimensions::new(200.0, 100.0));
+
+        // Height grew more, so width follows it
+        let dims = constrain_resize_aspect(Dimensions::new(110.0, 150.0), base, HandleName::BottomRight, 1.0);
+        assert_eq!(dims, Dimensions::new(300.0, 150.0));
+    }
+
+    #[test]
+    fn test_constrain_resize_aspect_keeps_flipped_signs() {
+        // Top-left handle: base dimensions are negative from the anchor
+        let base = Dimensions::new(-100.0, -50.0);
+        let dims = constrain_resize_aspect(Dimensions::new(-50.0, -40.0), base, HandleName::TopLeft, 1.0);
+        assert_eq!(dims, Dimensions::new(-50.0, -25.0));
+
+        // Dragging past the anchor flips only the axis that crossed it
+        let base = Dimensions::new(100.0, 50.0);
+        let dims = constrain_resize_aspect(Dimensions::new(-200.0, 20.0), base, HandleName::BottomRight, 1.0);
+        assert_eq!(dims, Dimensions::new(-200.0, 100.0));
+        assert_eq!((dims.width / dims.height).abs(), 2.0);
+    }
+
+    #[test]
+    fn test_constrain_resize_aspect_edge_scales_perpendicular_axis() {
+        let base = Dimensions::new(100.0, 50.0);
+        let dims = constrain_resize_aspect(Dimensions::new(150.0, 50.0), base, HandleName::Right, 1.0);
+        assert_eq!(dims, Dimensions::new(150.0, 75.0));
+
+        let base = Dimensions::new(100.0, -50.0);
+        let dims = constrain_resize_aspect(Dimensions::new(100.0, -25.0), base, HandleName::Top, 1.0);
+        assert_eq!(dims, Dimensions::new(50.0, -25.0));
+
+        // The minimum size applies to the shorter axis too
+        let dims = constrain_resize_aspect(Dimensions::new(4.0, 50.0), Dimensions::new(100.0, 50.0), HandleName::Right, 5.0);
+        assert_eq!(dims, Dimensions::new(10.0, 5.0));
+    }
+
+    #[test]
+    fn test_axis_lock_picks_dominant_axis_near_tie() {
+        assert_eq!(AxisLock::from_delta(10.0, 10.0), AxisLock::Horizontal);
+        assert_eq!(AxisLock::from_delta(-10.0, 10.0), AxisLock::Horizontal);
+        assert_eq!(AxisLock::from_delta(10.0, 10.001), AxisLock::Vertical);
+        assert_eq!(AxisLock::from_delta(-9.999, -10.0), AxisLock::Vertical);
+        assert_eq!(AxisLock::from_delta(10.001, -10.0), AxisLock::Horizontal);
+        assert_eq!(AxisLock::from_delta(0.0, 0.0), AxisLock::Horizontal);
+
+        assert_eq!(AxisLock::Horizontal.apply(12.0, -3.0), (12.0, 0.0));
+        assert_eq!(AxisLock::Vertical.apply(12.0, -3.0), (0.0, -3.0));
+    }
+
+    #[test]
+    fn test_snap_result_restricted_to_free_axis() {
+        let result = || SnapResult {
+            translation: Point::new(2.0, -3.0),
+            guidelines: vec![
+                Guideline::new(GuidelineType::Vertical, 100.0, 0.0, 50.0),
+                Guideline::new(GuidelineType::Horizontal, 40.0, 0.0, 50.0),
+            ],
+            spacing_indicators: vec![
+                SpacingIndicator::new(GuidelineType::Horizontal, Point::new(0.0, 0.0), 10.0),
+                SpacingIndicator::new(GuidelineType::Vertical, Point::new(0.0, 0.0), 10.0),
+            ],
+        };
+
+        let horizontal = result().restricted_to(AxisLock::Horizontal);
+        assert_eq!(horizontal.translation, Point::new(2.0, 0.0));
+        assert_eq!(horizontal.guidelines.len(), 1);
+        assert_eq!(horizontal.guidelines[0].guideline_type, GuidelineType::Vertical);
+        assert_eq!(horizontal.spacing_indicators[0].axis, GuidelineType::Horizontal);
+
+        let vertical = result().restricted_to(AxisLock::Vertical);
+        assert_eq!(vertical.translation, Point::new(0.0, -3.0));
+        assert_eq!(vertical.guidelines[0].guideline_type, GuidelineType::Horizontal);
+        assert_eq!(vertical.spacing_indicators.len(), 1);
+        assert_eq!(vertical.spacing_indicators[0].axis, GuidelineType::Vertical);
+    }
 }
@@ -0,0 +1,117 @@
+use yew::prelude::*;
+use web_sys::HtmlInputElement;
+use crate::snap_logic::SnapConfig;
+
+#[derive(Properties, PartialEq)]
+pub struct SnapSettingsProps {
+    pub config: SnapConfig,
+    pub on_change: Callback<SnapConfig>,
+}
+
+/// Render a labelled checkbox that updates one flag of the config
+fn render_toggle(
+    label: &str,
+    testid: &str,
+    checked: bool,
+    config: SnapConfig,
+    on_change: &Callback<SnapConfig>,
+    apply: fn(SnapConfig, bool) -> SnapConfig,
+) -> Html {
+    let on_change = on_change.clone();
+    html! {
+        <label class="flex items-center gap-2 text-sm text-gray-700">
+            <input
+                type="checkbox"
+                data-testid={testid.to_string()}
+                {checked}
+                onchange={Callback::from(move |e: Event| {
+                    if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
+                        on_change.emit(apply(config, input.checked()));
+                    }
+                })}
+            />
+            {label}
+        </label>
+    }
+}
+
+/// Render a labelled number input that updates one value of the config
+fn render_number(
+    label: &str,
+    testid: &str,
+    value: f64,
+    config: SnapConfig,
+    on_change: &Callback<SnapConfig>,
+    apply: fn(SnapConfig, f64) -> SnapConfig,
+) -> Html {
+    let on_change = on_change.clone();
+    html! {
+        <label class="flex items-center justify-between gap-2 text-sm text-gray-700">
+            {label}
+            <input
+                type="number"
+                min="1"
+                data-testid={testid.to_string()}
+                value={value.to_string()}
+                oninput={Callback::from(move |e: InputEvent| {
+                    if let Some(input) = e.target_dyn_into::<HtmlInputElement>() {
+                        if let Ok(v) = input.value().parse::<f64>() {
+                            if v > 0.0 {
+                                on_change.emit(apply(config, v));
+                            }
+                        }
+                    }
+                })}
+                class="w-16 px-2 py-1 border border-gray-300 rounded text-sm bg-white text-gray-900"
+            />
+        </label>
+    }
+}
+
+/// Button that opens a small popover for editing the snap settings
+#[function_component(SnapSettings)]
+pub fn snap_settings(props: &SnapSettingsProps) -> Html {
+    let is_open = use_state(|| false);
+    let config = props.config;
+
+    let on_toggle_open = {
+        let is_open = is_open.clone();
+        Callback::from(move |_: MouseEvent| is_open.set(!*is_open))
+    };
+
+    html! {
+        <div class="relative">
+            <button
+                data-testid="snap-settings"
+                data-active={config.enabled.to_string()}
+                onclick={on_toggle_open}
+                class="h-10 px-3 rounded border border-gray-200 bg-white shadow-sm text-sm text-gray-700 hover:bg-gray-50"
+            >
+                {"Snapping"}
+            </button>
+
+            if *is_open {
+                <div
+                    data-testid="snap-settings-popover"
+                    class="absolute top-12 left-0 z-10 w-56 p-3 space-y-2 rounded border border-gray-200 bg-white shadow-md"
+                >
+                    {render_toggle("Enable snapping", "snap-enabled", config.enabled, config, &props.on_change,
+                        |c, v| SnapConfig { enabled: v, ..c })}
+                    {render_number("Threshold (px)", "snap-threshold", config.threshold, config, &props.on_change,
+                        |c, v| SnapConfig { threshold: v, ..c })}
+                    {render_toggle("Shapes", "snap-to-shapes", config.snap_to_shapes, config, &props.on_change,
+                        |c, v| SnapConfig { snap_to_shapes: v, ..c })}
+                    {render_toggle("Vertices", "snap-to-vertices", config.snap_to_vertices, config, &props.on_change,
+                        |c, v| SnapConfig { snap_to_vertices: v, ..c })}
+                    {render_toggle("Canvas", "snap-to-canvas", config.snap_to_canvas, config, &props.on_change,
+                        |c, v| SnapConfig { snap_to_canvas: v, ..c })}
+                    {render_toggle("Grid", "snap-to-grid", config.snap_to_grid, config, &props.on_change,
+                        |c, v| SnapConfig { snap_to_grid: v, ..c })}
+                    {render_number("Grid size (px)", "snap-grid-size", config.grid_size, config, &props.on_change,
+                        |c, v| SnapConfig { grid_size: v, ..c })}
+                    <p class="text-xs text-gray-500">{"Hold Ctrl/Cmd while dragging to bypass"}</p>
+                </div>
+            }
+        </div>
+    }
+}
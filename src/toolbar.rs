@@ -0,0 +1,159 @@
+use yew::prelude::*;
+use crate::types::{AddShape, ToolMode};
+
+#[derive(Properties, PartialEq)]
+pub struct ToolbarProps {
+    pub active_tool: ToolMode,
+    pub on_tool_change: Callback<ToolMode>,
+    /// Add a shape at the center of the view
+    pub on_add_shape: Callback<AddShape>,
+    /// Select every visible, unlocked shape
+    pub on_select_all: Callback<()>,
+    /// Whether shapes are drawn with vertices snapped to pixel centers
+    pub pixel_snap: bool,
+    pub on_toggle_pixel_snap: Callback<()>,
+    /// Whether panel edits animate shapes to their new position and size
+    pub animations: bool,
+    pub on_toggle_animations: Callback<()>,
+    /// Whether shapes are drawn with multisample anti-aliasing
+    pub msaa: bool,
+    pub on_toggle_msaa: Callback<()>,
+}
+
+/// Render a minimalist icon for a tool
+fn render_tool_icon(tool: ToolMode) -> Html {
+    let icon = match tool {
+        ToolMode::Select => html! {
+            <path d="M4 2L12 9H8L10 14L8.5 14.5L6.5 9.5L4 12V2Z" stroke="currentColor" stroke-width="1.2" stroke-linejoin="round"/>
+        },
+        ToolMode::Rectangle => html! {
+            <rect x="2" y="3" width="12" height="10" rx="1" stroke="currentColor" stroke-width="1.5"/>
+        },
+        ToolMode::Ellipse => html! {
+            <ellipse cx="8" cy="8" rx="6" ry="5" stroke="currentColor" stroke-width="1.5"/>
+        },
+        ToolMode::Line => html! {
+            <path d="M3 13L13 3" stroke="currentColor" stroke-width="1.5" stroke-linecap="round"/>
+        },
+        ToolMode::Arc => html! {
+            <path d="M4 4L4 13A9 9 0 0 0 13 4Z" stroke="currentColor" stroke-width="1.5" stroke-linejoin="round"/>
+        },
+        ToolMode::Star => html! {
+            <path d="M8 2L9.5 6.2L14 6.3L10.4 9L11.7 13.4L8 10.8L4.3 13.4L5.6 9L2 6.3L6.5 6.2Z" stroke="currentColor" stroke-width="1.2" stroke-linejoin="round"/>
+        },
+        ToolMode::Pen => html! {
+            <path d="M2 12L6 4L11 8L14 3M2 12L11 8" stroke="currentColor" stroke-width="1.2" stroke-linejoin="round"/>
+        },
+        ToolMode::Text => html! {
+            <path d="M3 3H13M8 3V13" stroke="currentColor" stroke-width="1.5" stroke-linecap="round"/>
+        },
+    };
+
+    html! {
+        <svg width="16" height="16" viewBox="0 0 16 16" fill="none" class="text-gray-600">
+            {icon}
+        </svg>
+    }
+}
+
+#[function_component(Toolbar)]
+pub fn toolbar(props: &ToolbarProps) -> Html {
+    let tools = [
+        ToolMode::Select,
+        ToolMode::Rectangle,
+        ToolMode::Ellipse,
+        ToolMode::Line,
+        ToolMode::Arc,
+        ToolMode::Star,
+        ToolMode::Pen,
+        ToolMode::Text,
+    ];
+
+    html! {
+        <div class="flex gap-1 p-1 rounded border border-gray-200 bg-white shadow-sm">
+            {
+                tools.iter().map(|&tool| {
+                    let on_tool_change = props.on_tool_change.clone();
+                    let is_active = props.active_tool == tool;
+                    html! {
+                        <button
+                            key={tool.to_kebab_case()}
+                            data-testid={format!("tool-{}", tool.to_kebab_case())}
+                            data-active={is_active.to_string()}
+                            title={tool.label()}
+                            onclick={Callback::from(move |_: MouseEvent| on_tool_change.emit(tool))}
+                            class={classes!(
+                                "h-8", "w-8", "flex", "items-center", "justify-center", "rounded", "border",
+                                if is_active { "border-blue-500 bg-blue-50" } else { "border-transparent hover:bg-gray-50" }
+                            )}
+                        >
+                            {render_tool_icon(tool)}
+                        </button>
+                    }
+                }).collect::<Html>()
+            }
+            <div class="w-px my-1 bg-gray-200"></div>
+            {
+                AddShape::ALL.iter().map(|&kind| {
+                    let on_add_shape = props.on_add_shape.clone();
+                    html! {
+                        <button
+                            key={kind.to_kebab_case()}
+                            data-testid={format!("add-{}", kind.to_kebab_case())}
+                            onclick={Callback::from(move |_: MouseEvent| on_add_shape.emit(kind))}
+                            class="h-8 px-2 rounded border border-transparent text-xs text-gray-700 hover:bg-gray-50"
+                        >
+                            {kind.label()}
+                        </button>
+                    }
+                }).collect::<Html>()
+            }
+            <div class="w-px my-1 bg-gray-200"></div>
+            <button
+                data-testid="select-all"
+                title="Select all shapes (Ctrl/Cmd+A)"
+                onclick={props.on_select_all.reform(|_: MouseEvent| ())}
+                class="h-8 px-2 rounded border border-transparent text-xs text-gray-700 hover:bg-gray-50"
+            >
+                {"Select All"}
+            </button>
+            <div class="w-px my-1 bg-gray-200"></div>
+            <button
+                data-testid="pixel-snap"
+                data-active={props.pixel_snap.to_string()}
+                title="Snap to pixels for crisp thin lines"
+                onclick={props.on_toggle_pixel_snap.reform(|_: MouseEvent| ())}
+                class={classes!(
+                    "h-8", "px-2", "rounded", "border", "text-xs", "text-gray-700",
+                    if props.pixel_snap { "border-blue-500 bg-blue-50" } else { "border-transparent hover:bg-gray-50" }
+                )}
+            >
+                {"Pixel Snap"}
+            </button>
+            <button
+                data-testid="animations"
+                data-active={props.animations.to_string()}
+                title="Animate position and size changes; turn off for performance"
+                onclick={props.on_toggle_animations.reform(|_: MouseEvent| ())}
+                class={classes!(
+                    "h-8", "px-2", "rounded", "border", "text-xs", "text-gray-700",
+                    if props.animations { "border-blue-500 bg-blue-50" } else { "border-transparent hover:bg-gray-50" }
+                )}
+            >
+                {"Animations"}
+            </button>
+            <button
+                data-testid="msaa"
+                data-active={props.msaa.to_string()}
+                title="Smooth shape edges; turn off for performance on mobile"
+                onclick={props.on_toggle_msaa.reform(|_: MouseEvent| ())}
+                class={classes!(
+                    "h-8", "px-2", "rounded", "border", "text-xs", "text-gray-700",
+                    if props.msaa { "border-blue-500 bg-blue-50" } else { "border-transparent hover:bg-gray-50" }
+                )}
+            >
+                {"Anti-alias"}
+            </button>
+        </div>
+    }
+}
@@ -40,6 +40,15 @@ impl BoundingBox {
     pub fn new(x: f64, y: f64, width: f64, height: f64) -> Self {
         Self { x, y, width, height }
     }
+
+    /// Width over height, or 1.0 for a box with no height
+    pub fn aspect_ratio(&self) -> f64 {
+        if self.height == 0.0 {
+            1.0
+        } else {
+            self.width / self.height
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -86,6 +95,27 @@ impl Guideline {
     }
 }
 
+/// Equal-spacing marker showing the gap between the dragged selection and a neighbor
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpacingIndicator {
+    /// Orientation of the marker line (Horizontal measures a gap along x)
+    pub axis: GuidelineType,
+    /// Start of the marker line
+    pub position: Point,
+    /// Gap size in pixels
+    pub length: f64,
+}
+
+impl SpacingIndicator {
+    pub fn new(axis: GuidelineType, position: Point, length: f64) -> Self {
+        Self {
+            axis,
+            position,
+            length,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct SelectionRect {
     pub start: Point,
@@ -106,6 +136,86 @@ impl SelectionRect {
     }
 }
 
+/// Smallest and largest canvas zoom factors
+pub const MIN_ZOOM: f64 = 0.1;
+pub const MAX_ZOOM: f64 = 8.0;
+
+/// Canvas view: world point `p` is drawn at screen point `p * zoom + pan`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ViewTransform {
+    pub zoom: f64,
+    pub pan: Point,
+}
+
+impl ViewTransform {
+    pub fn new(zoom: f64, pan: Point) -> Self {
+        Self { zoom, pan }
+    }
+
+    pub fn identity() -> Self {
+        Self::new(1.0, Point::zero())
+    }
+
+    /// Map a point in canvas element pixels to world (shape) coordinates
+    pub fn screen_to_world(&self, screen: Point) -> Point {
+        Point::new(
+            (screen.x - self.pan.x) / self.zoom,
+            (screen.y - self.pan.y) / self.zoom,
+        )
+    }
+
+    /// Map a world (shape) point to canvas element pixels
+    pub fn world_to_screen(&self, world: Point) -> Point {
+        Point::new(world.x * self.zoom + self.pan.x, world.y * self.zoom + self.pan.y)
+    }
+
+    /// Zoom by `factor`, keeping the world point under `anchor` (screen pixels) fixed
+    pub fn zoomed_about(&self, anchor: Point, factor: f64) -> Self {
+        let zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+        let world = self.screen_to_world(anchor);
+        Self::new(
+            zoom,
+            Point::new(anchor.x - world.x * zoom, anchor.y - world.y * zoom),
+        )
+    }
+
+    pub fn panned(&self, dx: f64, dy: f64) -> Self {
+        Self::new(self.zoom, Point::new(self.pan.x + dx, self.pan.y + dy))
+    }
+
+    /// Follow a two-finger pinch from touch points `from` to `to` (screen pixels): zoom by
+    /// the change in finger spacing about their midpoint, then pan as the midpoint moves
+    pub fn pinched(&self, from: (Point, Point), to: (Point, Point)) -> Self {
+        let spacing = |(a, b): (Point, Point)| (b.x - a.x).hypot(b.y - a.y);
+        let midpoint = |(a, b): (Point, Point)| Point::new((a.x + b.x) / 2.0, (a.y + b.y) / 2.0);
+        let factor = if spacing(from) > 0.0 { spacing(to) / spacing(from) } else { 1.0 };
+        let (start, end) = (midpoint(from), midpoint(to));
+        self.zoomed_about(start, factor).panned(end.x - start.x, end.y - start.y)
+    }
+
+    /// SVG `transform` attribute value for content drawn in world coordinates
+    pub fn to_svg_transform(self) -> String {
+        format!("translate({} {}) scale({})", self.pan.x, self.pan.y, self.zoom)
+    }
+
+    /// Column-major 4x4 matrix for the GPU, matching `Transform2D::to_matrix4`
+    pub fn to_matrix4(self) -> [[f32; 4]; 4] {
+        let z = self.zoom as f32;
+        [
+            [z, 0.0, 0.0, 0.0],
+            [0.0, z, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [self.pan.x as f32, self.pan.y as f32, 0.0, 1.0],
+        ]
+    }
+}
+
+impl Default for ViewTransform {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum HandleName {
     Right,
@@ -119,6 +229,18 @@ pub enum HandleName {
 }
 
 impl HandleName {
+    /// All handles, clockwise from the top-left corner
+    pub const ALL: [HandleName; 8] = [
+        HandleName::TopLeft,
+        HandleName::Top,
+        HandleName::TopRight,
+        HandleName::Right,
+        HandleName::BottomRight,
+        HandleName::Bottom,
+        HandleName::BottomLeft,
+        HandleName::Left,
+    ];
+
     pub fn to_kebab_case(&self) -> &'static str {
         match self {
             HandleName::Right => "right",
@@ -188,3 +310,149 @@ pub enum ActiveTab {
     Chat,
     Versions,
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlignMode {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    CenterHorizontal,
+    CenterVertical,
+}
+
+impl AlignMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            AlignMode::Left => "Align left",
+            AlignMode::Right => "Align right",
+            AlignMode::Top => "Align top",
+            AlignMode::Bottom => "Align bottom",
+            AlignMode::CenterHorizontal => "Align horizontal centers",
+            AlignMode::CenterVertical => "Align vertical centers",
+        }
+    }
+
+    pub fn to_kebab_case(self) -> &'static str {
+        match self {
+            AlignMode::Left => "left",
+            AlignMode::Right => "right",
+            AlignMode::Top => "top",
+            AlignMode::Bottom => "bottom",
+            AlignMode::CenterHorizontal => "center-h",
+            AlignMode::CenterVertical => "center-v",
+        }
+    }
+}
+
+/// Axis along which selected shapes are spaced evenly
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DistributeMode {
+    Horizontal,
+    Vertical,
+}
+
+impl DistributeMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DistributeMode::Horizontal => "Distribute Horizontally",
+            DistributeMode::Vertical => "Distribute Vertically",
+        }
+    }
+
+    pub fn to_kebab_case(self) -> &'static str {
+        match self {
+            DistributeMode::Horizontal => "horizontal",
+            DistributeMode::Vertical => "vertical",
+        }
+    }
+}
+
+/// Active canvas tool
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ToolMode {
+    #[default]
+    Select,
+    Rectangle,
+    Ellipse,
+    Line,
+    Arc,
+    Star,
+    Pen,
+    Text,
+}
+
+impl ToolMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ToolMode::Select => "Select",
+            ToolMode::Rectangle => "Rectangle",
+            ToolMode::Ellipse => "Ellipse",
+            ToolMode::Line => "Line",
+            ToolMode::Arc => "Arc",
+            ToolMode::Star => "Star",
+            ToolMode::Pen => "Pen",
+            ToolMode::Text => "Text",
+        }
+    }
+
+    pub fn to_kebab_case(self) -> &'static str {
+        match self {
+            ToolMode::Select => "select",
+            ToolMode::Rectangle => "rectangle",
+            ToolMode::Ellipse => "ellipse",
+            ToolMode::Line => "line",
+            ToolMode::Arc => "arc",
+            ToolMode::Star => "star",
+            ToolMode::Pen => "pen",
+            ToolMode::Text => "text",
+        }
+    }
+}
+
+/// Shape placed at the center of the view by the toolbar's add buttons
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddShape {
+    Rectangle,
+    Ellipse,
+    Polygon,
+}
+
+impl AddShape {
+    pub const ALL: [AddShape; 3] = [AddShape::Rectangle, AddShape::Ellipse, AddShape::Polygon];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            AddShape::Rectangle => "Add Rect",
+            AddShape::Ellipse => "Add Ellipse",
+            AddShape::Polygon => "Add Polygon",
+        }
+    }
+
+    pub fn to_kebab_case(self) -> &'static str {
+        match self {
+            AddShape::Rectangle => "rect",
+            AddShape::Ellipse => "ellipse",
+            AddShape::Polygon => "polygon",
+        }
+    }
+}
+
+/// What direct manipulation of the selection edits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EditMode {
+    /// Bounding-box handles move and resize the selection
+    #[default]
+    Transform,
+    /// Per-vertex handles reshape a single selected polygon or path
+    Vertex { shape_id: u64 },
+}
+
+/// A handle grabbed while editing vertices
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditHandle {
+    /// An on-curve vertex, by its index in the shape's vertices
+    Vertex(usize),
+    /// A path's curve control point, by its index in the path's control handles
+    Control(usize),
+}
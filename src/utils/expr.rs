@@ -0,0 +1,238 @@
+//! Arithmetic for numeric property fields
+//!
+//! Evaluates expressions like `400/2` or `(100 + 15) * 2` typed into a field.
+//! Supports `+ - * /`, unary minus, parentheses, and a postfix `%` that takes a
+//! percentage of the field's current value, so `50%` halves it.
+
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// Why an expression couldn't be evaluated
+#[derive(Clone, Debug, PartialEq)]
+pub enum ExprError {
+    /// Nothing but whitespace
+    Empty,
+    /// A character that can't start or continue the expression here
+    UnexpectedChar(char),
+    /// The input stopped mid-expression, e.g. `3 +` or `(2`
+    UnexpectedEnd,
+    DivisionByZero,
+    /// The result overflowed to infinity
+    NotFinite,
+}
+
+impl std::fmt::Display for ExprError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExprError::Empty => write!(f, "Empty expression"),
+            ExprError::UnexpectedChar(c) => write!(f, "Unexpected '{}'", c),
+            ExprError::UnexpectedEnd => write!(f, "Incomplete expression"),
+            ExprError::DivisionByZero => write!(f, "Division by zero"),
+            ExprError::NotFinite => write!(f, "Result is too large"),
+        }
+    }
+}
+
+/// Evaluate `input`, with `n%` meaning n percent of `current`
+pub fn eval_expression(input: &str, current: f64) -> Result<f64, ExprError> {
+    let mut parser = ExprParser {
+        chars: input.chars().peekable(),
+        current,
+    };
+    if parser.peek().is_none() {
+        return Err(ExprError::Empty);
+    }
+
+    let value = parser.expression()?;
+    if let Some(c) = parser.peek() {
+        return Err(ExprError::UnexpectedChar(c));
+    }
+    if value.is_finite() {
+        Ok(value)
+    } else {
+        Err(ExprError::NotFinite)
+    }
+}
+
+/// Recursive descent over the grammar:
+/// expression = term (("+" | "-") term)*
+/// term       = unary (("*" | "/") unary)*
+/// unary      = ("+" | "-") unary | primary "%"?
+/// primary    = number | "(" expression ")"
+struct ExprParser<'a> {
+    chars: Peekable<Chars<'a>>,
+    current: f64,
+}
+
+impl ExprParser<'_> {
+    /// Next non-whitespace character, without consuming it
+    fn peek(&mut self) -> Option<char> {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+        self.chars.peek().copied()
+    }
+
+    fn expression(&mut self) -> Result<f64, ExprError> {
+        let mut value = self.term()?;
+        while let Some(op @ ('+' | '-')) = self.peek() {
+            self.chars.next();
+            let rhs = self.term()?;
+            value = if op == '+' { value + rhs } else { value - rhs };
+        }
+        Ok(value)
+    }
+
+    fn term(&mut self) -> Result<f64, ExprError> {
+        let mut value = self.unary()?;
+        while let Some(op @ ('*' | '/')) = self.peek() {
+            self.chars.next();
+            let rhs = self.unary()?;
+            value = if op == '*' {
+                value * rhs
+            } else if rhs == 0.0 {
+                return Err(ExprError::DivisionByZero);
+            } else {
+                value / rhs
+            };
+        }
+        Ok(value)
+    }
+
+    fn unary(&mut self) -> Result<f64, ExprError> {
+        match self.peek() {
+            Some('-') => {
+                self.chars.next();
+                Ok(-self.unary()?)
+            }
+            Some('+') => {
+                self.chars.next();
+                self.unary()
+            }
+            _ => {
+                let value = self.primary()?;
+                if self.peek() == Some('%') {
+                    self.chars.next();
+                    Ok(self.current * value / 100.0)
+                } else {
+                    Ok(value)
+                }
+            }
+        }
+    }
+
+    fn primary(&mut self) -> Result<f64, ExprError> {
+        match self.peek() {
+            Some('(') => {
+                self.chars.next();
+                let value = self.expression()?;
+                match self.peek() {
+                    Some(')') => {
+                        self.chars.next();
+                        Ok(value)
+                    }
+                    Some(c) => Err(ExprError::UnexpectedChar(c)),
+                    None => Err(ExprError::UnexpectedEnd),
+                }
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => self.number(),
+            Some(c) => Err(ExprError::UnexpectedChar(c)),
+            None => Err(ExprError::UnexpectedEnd),
+        }
+    }
+
+    /// Digits with at most one decimal point, e.g. `12`, `1.5` or `.5`
+    fn number(&mut self) -> Result<f64, ExprError> {
+        let mut text = String::new();
+        let mut seen_point = false;
+        while let Some(c) = self.chars.next_if(|&c| c.is_ascii_digit() || (c == '.' && !seen_point)) {
+            seen_point |= c == '.';
+            text.push(c);
+        }
+        text.parse().map_err(|_| ExprError::UnexpectedChar('.'))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(input: &str) -> Result<f64, ExprError> {
+        eval_expression(input, 200.0)
+    }
+
+    #[test]
+    fn test_plain_numbers() {
+        assert_eq!(eval("42"), Ok(42.0));
+        assert_eq!(eval("  1.5 "), Ok(1.5));
+        assert_eq!(eval(".5"), Ok(0.5));
+        assert_eq!(eval("-3"), Ok(-3.0));
+    }
+
+    #[test]
+    fn test_basic_operators() {
+        assert_eq!(eval("400/2"), Ok(200.0));
+        assert_eq!(eval("100+15"), Ok(115.0));
+        assert_eq!(eval("10 - 4"), Ok(6.0));
+        assert_eq!(eval("6*7"), Ok(42.0));
+    }
+
+    #[test]
+    fn test_precedence_and_associativity() {
+        assert_eq!(eval("2+3*4"), Ok(14.0));
+        assert_eq!(eval("2*3+4"), Ok(10.0));
+        assert_eq!(eval("10-4-3"), Ok(3.0));
+        assert_eq!(eval("100/10/2"), Ok(5.0));
+        assert_eq!(eval("8-2*3+1"), Ok(3.0));
+    }
+
+    #[test]
+    fn test_parentheses() {
+        assert_eq!(eval("(2+3)*4"), Ok(20.0));
+        assert_eq!(eval("((1+1))*(3)"), Ok(6.0));
+        assert_eq!(eval("100/(2*(3+2))"), Ok(10.0));
+    }
+
+    #[test]
+    fn test_unary_signs() {
+        assert_eq!(eval("-(2+3)"), Ok(-5.0));
+        assert_eq!(eval("--4"), Ok(4.0));
+        assert_eq!(eval("3*-2"), Ok(-6.0));
+        assert_eq!(eval("+7"), Ok(7.0));
+    }
+
+    #[test]
+    fn test_percent_of_current_value() {
+        assert_eq!(eval("50%"), Ok(100.0));
+        assert_eq!(eval("100% + 10"), Ok(210.0));
+        assert_eq!(eval("(20+30)%"), Ok(100.0));
+        assert_eq!(eval("-25%"), Ok(-50.0));
+        assert_eq!(eval_expression("50%", 0.0), Ok(0.0));
+    }
+
+    #[test]
+    fn test_division_by_zero() {
+        assert_eq!(eval("1/0"), Err(ExprError::DivisionByZero));
+        assert_eq!(eval("5/(2-2)"), Err(ExprError::DivisionByZero));
+        assert_eq!(eval("0/0"), Err(ExprError::DivisionByZero));
+        assert_eq!(eval("0/5"), Ok(0.0));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert_eq!(eval(""), Err(ExprError::Empty));
+        assert_eq!(eval("   "), Err(ExprError::Empty));
+        assert_eq!(eval("3+"), Err(ExprError::UnexpectedEnd));
+        assert_eq!(eval("(2+3"), Err(ExprError::UnexpectedEnd));
+        assert_eq!(eval("2+3)"), Err(ExprError::UnexpectedChar(')')));
+        assert_eq!(eval("abc"), Err(ExprError::UnexpectedChar('a')));
+        assert_eq!(eval("2 3"), Err(ExprError::UnexpectedChar('3')));
+        assert_eq!(eval("1.2.3"), Err(ExprError::UnexpectedChar('.')));
+        assert_eq!(eval("*4"), Err(ExprError::UnexpectedChar('*')));
+        assert_eq!(eval("."), Err(ExprError::UnexpectedChar('.')));
+    }
+
+    #[test]
+    fn test_overflow_is_rejected() {
+        let huge = format!("1{}", "0".repeat(308));
+        assert_eq!(eval(&format!("{huge}*10")), Err(ExprError::NotFinite));
+    }
+}
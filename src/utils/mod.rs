@@ -0,0 +1,348 @@
+use crate::scene::{BBox, Shape, ShapeGeometry, Vec2};
+use crate::types::{Point, ViewTransform, MAX_ZOOM, MIN_ZOOM};
+use web_sys::{MouseEvent, SvgsvgElement};
+
+mod expr;
+
+pub use expr::*;
+
+/// Convert a mouse event's client position to canvas (world) coordinates
+/// All pointer math and the coordinate readout go through here, so the canvas
+/// zoom and pan are undone in this one place
+pub fn client_to_svg_coords(event: &MouseEvent, svg_element: &SvgsvgElement, view: &ViewTransform) -> Point {
+    // Get the bounding rectangle of the SVG element
+    let rect = svg_element.get_bounding_client_rect();
+
+    // Position within the element, then back through the view transform
+    let x = event.client_x() as f64 - rect.left();
+    let y = event.client_y() as f64 - rect.top();
+
+    view.screen_to_world(Point::new(x, y))
+}
+
+/// Check whether segments `a1-a2` and `b1-b2` intersect (touching counts)
+pub fn segments_intersect(a1: Vec2, a2: Vec2, b1: Vec2, b2: Vec2) -> bool {
+    fn orientation(p: Vec2, q: Vec2, r: Vec2) -> f32 {
+        (q - p).perp_dot(r - p)
+    }
+
+    fn on_segment(p: Vec2, q: Vec2, r: Vec2) -> bool {
+        r.x >= p.x.min(q.x) && r.x <= p.x.max(q.x) && r.y >= p.y.min(q.y) && r.y <= p.y.max(q.y)
+    }
+
+    let d1 = orientation(b1, b2, a1);
+    let d2 = orientation(b1, b2, a2);
+    let d3 = orientation(a1, a2, b1);
+    let d4 = orientation(a1, a2, b2);
+
+    if ((d1 > 0.0 && d2 < 0.0) || (d1 < 0.0 && d2 > 0.0))
+        && ((d3 > 0.0 && d4 < 0.0) || (d3 < 0.0 && d4 > 0.0))
+    {
+        return true;
+    }
+
+    // Collinear cases: an endpoint lies on the other segment
+    (d1 == 0.0 && on_segment(b1, b2, a1))
+        || (d2 == 0.0 && on_segment(b1, b2, a2))
+        || (d3 == 0.0 && on_segment(a1, a2, b1))
+        || (d4 == 0.0 && on_segment(a1, a2, b2))
+}
+
+/// Distance from `point` to the segment `a-b`
+pub fn point_segment_distance(point: Vec2, a: Vec2, b: Vec2) -> f32 {
+    let ab = b - a;
+    let len_sq = ab.length_squared();
+    if len_sq == 0.0 {
+        return point.distance(a);
+    }
+    let t = ((point - a).dot(ab) / len_sq).clamp(0.0, 1.0);
+    point.distance(a + ab * t)
+}
+
+/// Shortest distance between segments `a1-a2` and `b1-b2` (0 when they cross)
+pub fn segment_distance(a1: Vec2, a2: Vec2, b1: Vec2, b2: Vec2) -> f32 {
+    if segments_intersect(a1, a2, b1, b2) {
+        return 0.0;
+    }
+    point_segment_distance(a1, b1, b2)
+        .min(point_segment_distance(a2, b1, b2))
+        .min(point_segment_distance(b1, a1, a2))
+        .min(point_segment_distance(b2, a1, a2))
+}
+
+/// Shortest distance from segment `a-b` to a box (0 when it touches or enters it)
+pub fn segment_bbox_distance(a: Vec2, b: Vec2, bbox: &BBox) -> f32 {
+    if bbox.contains(a) || bbox.contains(b) {
+        return 0.0;
+    }
+    let corners = [
+        bbox.min,
+        Vec2::new(bbox.max.x, bbox.min.y),
+        bbox.max,
+        Vec2::new(bbox.min.x, bbox.max.y),
+    ];
+    (0..4)
+        .map(|k| segment_distance(a, b, corners[k], corners[(k + 1) % 4]))
+        .fold(f32::INFINITY, f32::min)
+}
+
+/// Even-odd point-in-polygon test
+pub fn point_in_polygon(point: Vec2, polygon: &[Vec2]) -> bool {
+    let mut inside = false;
+    let mut j = polygon.len().wrapping_sub(1);
+    for i in 0..polygon.len() {
+        let (pi, pj) = (polygon[i], polygon[j]);
+        if (pi.y > point.y) != (pj.y > point.y)
+            && point.x < (pj.x - pi.x) * (point.y - pi.y) / (pj.y - pi.y) + pi.x
+        {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+/// Check whether a polygon (world-space points) intersects an axis-aligned rectangle
+/// Covers a vertex inside the rect, a rect corner inside the polygon, and crossing edges
+pub fn polygon_intersects_rect(polygon: &[Vec2], rect: &BBox) -> bool {
+    if polygon.iter().any(|p| rect.contains(*p)) {
+        return true;
+    }
+
+    let corners = [
+        rect.min,
+        Vec2::new(rect.max.x, rect.min.y),
+        rect.max,
+        Vec2::new(rect.min.x, rect.max.y),
+    ];
+    if polygon.len() >= 3 && corners.iter().any(|c| point_in_polygon(*c, polygon)) {
+        return true;
+    }
+
+    let edge_count = if polygon.len() >= 3 { polygon.len() } else { polygon.len().saturating_sub(1) };
+    (0..edge_count).any(|i| {
+        let a = polygon[i];
+        let b = polygon[(i + 1) % polygon.len()];
+        (0..4).any(|k| segments_intersect(a, b, corners[k], corners[(k + 1) % 4]))
+    })
+}
+
+/// Check whether a shape intersects a marquee rectangle
+/// Polygons and lines use their exact outline; other geometry uses the world bounds outline
+pub fn shape_intersects_rect(shape: &Shape, rect: &BBox) -> bool {
+    let transform = &shape.transform;
+    match &shape.geometry {
+        ShapeGeometry::Polygon { points } => {
+            let world: Vec<Vec2> = points.iter().map(|p| transform.transform_point(*p)).collect();
+            polygon_intersects_rect(&world, rect)
+        }
+        ShapeGeometry::Line { start, end } => {
+            let world = [transform.transform_point(*start), transform.transform_point(*end)];
+            polygon_intersects_rect(&world, rect)
+        }
+        _ => shape.world_bounds().intersects(rect),
+    }
+}
+
+/// Check whether a shape's world bounds lie entirely inside a marquee rectangle
+pub fn shape_enclosed_by_rect(shape: &Shape, rect: &BBox) -> bool {
+    let bounds = shape.world_bounds();
+    [
+        bounds.min,
+        Vec2::new(bounds.max.x, bounds.min.y),
+        bounds.max,
+        Vec2::new(bounds.min.x, bounds.max.y),
+    ]
+    .into_iter()
+    .all(|corner| rect.contains(corner))
+}
+
+/// Find the IDs of shapes selected by a marquee rectangle, in paint order
+/// With `enclosed_only` (Alt held) a shape must lie fully inside the rectangle;
+/// otherwise touching it is enough. Hidden and locked shapes are never selected
+pub fn find_shapes_in_rect(shapes: &[Shape], rect: &BBox, enclosed_only: bool) -> Vec<u64> {
+    shapes
+        .iter()
+        .filter(|shape| shape.is_interactive())
+        .filter(|shape| {
+            if enclosed_only {
+                shape_enclosed_by_rect(shape, rect)
+            } else {
+                shape_intersects_rect(shape, rect)
+            }
+        })
+        .map(|shape| shape.id)
+        .collect()
+}
+
+/// Fraction of the viewport left empty on each side when fitting content
+pub const FIT_PADDING: f64 = 0.1;
+
+/// View that centers `bounds` in a `width` x `height` viewport, as large as fits
+/// inside the padding. An empty scene resets to the identity view.
+pub fn fit_view_to_bounds(bounds: Option<BBox>, width: f64, height: f64) -> ViewTransform {
+    let Some(bounds) = bounds else {
+        return ViewTransform::identity();
+    };
+    let (content_w, content_h) = (bounds.width() as f64, bounds.height() as f64);
+    let (avail_w, avail_h) = (width * (1.0 - 2.0 * FIT_PADDING), height * (1.0 - 2.0 * FIT_PADDING));
+
+    // A degenerate axis (a horizontal line, a single point) doesn't limit the zoom
+    let zoom = match (content_w > 0.0, content_h > 0.0) {
+        (true, true) => (avail_w / content_w).min(avail_h / content_h),
+        (true, false) => avail_w / content_w,
+        (false, true) => avail_h / content_h,
+        (false, false) => 1.0,
+    }
+    .clamp(MIN_ZOOM, MAX_ZOOM);
+
+    let center = bounds.center();
+    ViewTransform::new(
+        zoom,
+        Point::new(width / 2.0 - center.x as f64 * zoom, height / 2.0 - center.y as f64 * zoom),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scene::ShapeStyle;
+
+    fn triangle() -> Vec<Vec2> {
+        vec![Vec2::new(0.0, 0.0), Vec2::new(100.0, 0.0), Vec2::new(50.0, 100.0)]
+    }
+
+    fn rect(x: f32, y: f32, w: f32, h: f32) -> BBox {
+        BBox::new(Vec2::new(x, y), Vec2::new(x + w, y + h))
+    }
+
+    #[test]
+    fn test_vertex_in_rect() {
+        assert!(polygon_intersects_rect(&triangle(), &rect(90.0, -5.0, 20.0, 10.0)));
+    }
+
+    #[test]
+    fn test_rect_inside_polygon() {
+        // Small rect in the middle of the triangle contains no vertices
+        assert!(polygon_intersects_rect(&triangle(), &rect(45.0, 20.0, 10.0, 10.0)));
+    }
+
+    #[test]
+    fn test_edges_cross() {
+        // Thin horizontal band through the triangle: no vertex inside either way
+        assert!(polygon_intersects_rect(&triangle(), &rect(-10.0, 40.0, 120.0, 5.0)));
+    }
+
+    #[test]
+    fn test_rect_contains_polygon() {
+        assert!(polygon_intersects_rect(&triangle(), &rect(-10.0, -10.0, 200.0, 200.0)));
+    }
+
+    #[test]
+    fn test_disjoint_inside_bounds() {
+        // Inside the triangle's bounding box but outside the triangle itself
+        assert!(!polygon_intersects_rect(&triangle(), &rect(0.0, 80.0, 10.0, 10.0)));
+    }
+
+    #[test]
+    fn test_enclosed_only_requires_full_containment() {
+        let shape = Shape::new(ShapeGeometry::polygon(triangle()), ShapeStyle::default());
+        let id = shape.id;
+        let shapes = [shape];
+
+        // Touches the triangle but cuts off its apex
+        let partial = rect(-10.0, -10.0, 200.0, 80.0);
+        assert_eq!(find_shapes_in_rect(&shapes, &partial, false), vec![id]);
+        assert!(find_shapes_in_rect(&shapes, &partial, true).is_empty());
+
+        let full = rect(-10.0, -10.0, 200.0, 200.0);
+        assert_eq!(find_shapes_in_rect(&shapes, &full, true), vec![id]);
+    }
+
+    #[test]
+    fn test_view_zoom_keeps_anchor_fixed() {
+        let view = ViewTransform::identity().panned(20.0, -10.0);
+        let anchor = Point::new(300.0, 200.0);
+        let before = view.screen_to_world(anchor);
+
+        let zoomed = view.zoomed_about(anchor, 2.5);
+        assert_eq!(zoomed.zoom, 2.5);
+        let after = zoomed.screen_to_world(anchor);
+        assert!((after.x - before.x).abs() < 1e-9 && (after.y - before.y).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_view_screen_world_roundtrip_at_zoom_levels() {
+        let screen = Point::new(317.0, 92.5);
+        for zoom in [MIN_ZOOM, 0.5, 1.0, 2.0, 4.75, MAX_ZOOM] {
+            let view = ViewTransform::new(zoom, Point::new(-140.0, 65.0));
+            let world = view.screen_to_world(screen);
+            let back = view.world_to_screen(world);
+            assert!((back.x - screen.x).abs() < 1e-9 && (back.y - screen.y).abs() < 1e-9, "zoom {}", zoom);
+
+            // One screen pixel spans 1/zoom world units
+            let next = view.screen_to_world(Point::new(screen.x + 1.0, screen.y));
+            assert!((next.x - world.x - 1.0 / zoom).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_fit_view_to_wide_and_tall_content() {
+        // Wide content in a 4:3 viewport is limited by width: 640 usable px / 320
+        let view = fit_view_to_bounds(Some(rect(100.0, 100.0, 320.0, 40.0)), 800.0, 600.0);
+        assert!((view.zoom - 2.0).abs() < 1e-9);
+        let center = view.world_to_screen(Point::new(260.0, 120.0));
+        assert!((center.x - 400.0).abs() < 1e-9 && (center.y - 300.0).abs() < 1e-9);
+
+        // Tall content is limited by height: 480 usable px / 960
+        let view = fit_view_to_bounds(Some(rect(-50.0, 0.0, 100.0, 960.0)), 800.0, 600.0);
+        assert!((view.zoom - 0.5).abs() < 1e-9);
+        let top = view.world_to_screen(Point::new(0.0, 0.0));
+        assert!((top.y - 60.0).abs() < 1e-9);
+
+        // Same content, portrait viewport: now width is the tighter axis
+        let view = fit_view_to_bounds(Some(rect(0.0, 0.0, 300.0, 300.0)), 300.0, 900.0);
+        assert!((view.zoom - 0.8).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_fit_view_handles_empty_and_degenerate_bounds() {
+        assert_eq!(fit_view_to_bounds(None, 800.0, 600.0), ViewTransform::identity());
+
+        // A horizontal line fits by its length alone
+        let view = fit_view_to_bounds(Some(rect(0.0, 50.0, 160.0, 0.0)), 800.0, 600.0);
+        assert!((view.zoom - 4.0).abs() < 1e-9);
+
+        // A single point just centers, and tiny content stops at the zoom limit
+        let view = fit_view_to_bounds(Some(rect(10.0, 10.0, 0.0, 0.0)), 800.0, 600.0);
+        assert_eq!(view.zoom, 1.0);
+        assert_eq!(view.world_to_screen(Point::new(10.0, 10.0)), Point::new(400.0, 300.0));
+        assert_eq!(fit_view_to_bounds(Some(rect(0.0, 0.0, 1.0, 1.0)), 800.0, 600.0).zoom, MAX_ZOOM);
+    }
+
+    #[test]
+    fn test_view_pinch_zooms_and_pans_with_fingers() {
+        let view = ViewTransform::new(1.5, Point::new(40.0, -20.0));
+        let from = (Point::new(100.0, 100.0), Point::new(200.0, 100.0));
+        let under_midpoint = view.screen_to_world(Point::new(150.0, 100.0));
+
+        // Fingers spread to twice the spacing while their midpoint moves to (180, 130)
+        let to = (Point::new(80.0, 130.0), Point::new(280.0, 130.0));
+        let pinched = view.pinched(from, to);
+        assert!((pinched.zoom - 3.0).abs() < 1e-9);
+        let after = pinched.world_to_screen(under_midpoint);
+        assert!((after.x - 180.0).abs() < 1e-9 && (after.y - 130.0).abs() < 1e-9);
+
+        // Two fingers on the same spot only pan
+        let same = (Point::new(50.0, 50.0), Point::new(50.0, 50.0));
+        let moved = (Point::new(60.0, 50.0), Point::new(60.0, 50.0));
+        assert_eq!(view.pinched(same, moved), view.panned(10.0, 0.0));
+    }
+
+    #[test]
+    fn test_view_zoom_is_clamped() {
+        let anchor = Point::zero();
+        assert_eq!(ViewTransform::identity().zoomed_about(anchor, 100.0).zoom, MAX_ZOOM);
+        assert_eq!(ViewTransform::identity().zoomed_about(anchor, 0.001).zoom, MIN_ZOOM);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::layer_thumbnail::snapshot_markup;
 use crate::scene::{Shape, LayerTree};
 
 /// Represents a single saved version/snapshot of the canvas state
@@ -13,6 +14,8 @@ pub struct Version {
     pub shapes: Vec<Shape>,
     /// Snapshot of the layer tree (grouping hierarchy)
     pub layer_tree: LayerTree,
+    /// Inline SVG preview of the shapes, rendered when the version is saved
+    pub thumbnail: Option<String>,
 }
 
 impl Version {
@@ -23,8 +26,32 @@ impl Version {
             created_at,
             shapes,
             layer_tree,
+            thumbnail: None,
         }
     }
+
+    /// Preview markup for the version list; versions saved without a thumbnail get one
+    /// rendered from their shapes on demand
+    pub fn thumbnail_markup(&self) -> String {
+        self.thumbnail.clone().unwrap_or_else(|| snapshot_markup(&self.shapes))
+    }
+}
+
+/// Shape-level changes between two versions, keyed by the shape IDs saved in each
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VersionDiff {
+    /// Shapes only in the later version
+    pub added: Vec<u64>,
+    /// Shapes only in the earlier version
+    pub removed: Vec<u64>,
+    /// Shapes in both whose geometry, style or position changed
+    pub modified: Vec<u64>,
+}
+
+impl VersionDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
 }
 
 /// Version history manager
@@ -55,13 +82,14 @@ impl VersionHistory {
 
     /// Save current state as a new version
     pub fn save_version(&mut self, shapes: Vec<Shape>, layer_tree: LayerTree, label: Option<String>, timestamp: f64) -> &Version {
-        let version = Version::new(
+        let mut version = Version::new(
             self.next_id,
             label.unwrap_or_else(|| format!("Version {}", self.next_id)),
             timestamp,
             shapes,
             layer_tree,
         );
+        version.thumbnail = Some(snapshot_markup(&version.shapes));
         self.next_id += 1;
         self.versions.push(version);
         self.current_version_idx = Some(self.versions.len() - 1);
@@ -83,6 +111,36 @@ impl VersionHistory {
         self.versions.is_empty()
     }
 
+    /// Compare two versions: what `b` adds, drops and changes relative to `a`
+    /// IDs are listed in the order the shapes appear in their version
+    pub fn diff(a: &Version, b: &Version) -> VersionDiff {
+        fn find(shapes: &[Shape], id: u64) -> Option<&Shape> {
+            shapes.iter().find(|shape| shape.id == id)
+        }
+        let mut diff = VersionDiff::default();
+
+        for shape in &b.shapes {
+            match find(&a.shapes, shape.id) {
+                None => diff.added.push(shape.id),
+                Some(old) => {
+                    let changed = old.geometry != shape.geometry
+                        || old.style != shape.style
+                        || old.transform != shape.transform;
+                    if changed {
+                        diff.modified.push(shape.id);
+                    }
+                }
+            }
+        }
+        diff.removed = a.shapes
+            .iter()
+            .filter(|shape| find(&b.shapes, shape.id).is_none())
+            .map(|shape| shape.id)
+            .collect();
+
+        diff
+    }
+
     /// Set the current version index (for restoring a version)
     pub fn set_current_version(&mut self, idx: usize) {
         if idx < self.versions.len() {
@@ -94,7 +152,7 @@ impl VersionHistory {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::scene::{Shape, ShapeGeometry, ShapeStyle};
+    use crate::scene::{Color, SceneGraph, Shape, ShapeGeometry, ShapeStyle, Vec2};
 
     fn create_test_shape() -> Shape {
         Shape::new(
@@ -130,6 +188,99 @@ mod tests {
         assert_eq!(version.layer_tree.nodes.len(), 1);
     }
 
+    #[test]
+    fn test_saved_versions_carry_a_thumbnail() {
+        let mut history = VersionHistory::new();
+        history.save_version(vec![create_test_shape()], LayerTree::from_shapes(&[]), None, 1000.0);
+        history.save_version(Vec::new(), LayerTree::from_shapes(&[]), None, 2000.0);
+
+        let version = history.get_version(0).unwrap();
+        let thumbnail = version.thumbnail.as_deref().unwrap();
+        assert!(thumbnail.contains("<rect"));
+        // An empty canvas still gets a (blank) preview
+        assert_eq!(history.get_version(1).unwrap().thumbnail.as_deref(), Some(snapshot_markup(&[]).as_str()));
+
+        // Versions from before thumbnails render one when asked
+        let older = Version::new(7, "Old".to_string(), 0.0, version.shapes.clone(), LayerTree::from_shapes(&[]));
+        assert_eq!(older.thumbnail, None);
+        assert_eq!(older.thumbnail_markup(), thumbnail);
+    }
+
+    #[test]
+    fn test_diff_reports_added_removed_and_modified_shapes() {
+        let kept = create_test_shape();
+        let moved = create_test_shape();
+        let restyled = create_test_shape();
+        let dropped = create_test_shape();
+        let before = vec![kept.clone(), moved.clone(), restyled.clone(), dropped.clone()];
+
+        let mut moved_later = moved.clone();
+        moved_later.transform.position += Vec2::new(10.0, 0.0);
+        let mut restyled_later = restyled.clone();
+        restyled_later.style = ShapeStyle::fill_only(Color::rgb(1.0, 0.0, 0.0));
+        // Renaming or hiding a shape isn't a content change
+        let mut renamed = kept.clone();
+        renamed.name = "Renamed".to_string();
+        let new_shape = create_test_shape();
+        let after = vec![renamed, moved_later, restyled_later, new_shape.clone()];
+
+        let mut history = VersionHistory::new();
+        let ids = |shapes: &[Shape]| shapes.iter().map(|s| s.id).collect::<Vec<_>>();
+        history.save_version(before.clone(), LayerTree::from_shapes(&ids(&before)), None, 1000.0);
+        history.save_version(after.clone(), LayerTree::from_shapes(&ids(&after)), None, 2000.0);
+
+        let diff = VersionHistory::diff(history.get_version(0).unwrap(), history.get_version(1).unwrap());
+        assert_eq!(diff.added, vec![new_shape.id]);
+        assert_eq!(diff.removed, vec![dropped.id]);
+        assert_eq!(diff.modified, vec![moved.id, restyled.id]);
+
+        // Reversed, additions and removals swap
+        let reverse = VersionHistory::diff(history.get_version(1).unwrap(), history.get_version(0).unwrap());
+        assert_eq!(reverse.added, vec![dropped.id]);
+        assert_eq!(reverse.removed, vec![new_shape.id]);
+
+        let same = VersionHistory::diff(history.get_version(0).unwrap(), history.get_version(0).unwrap());
+        assert!(same.is_empty());
+    }
+
+    #[test]
+    fn test_restored_version_keeps_shape_ids() {
+        let shapes = vec![create_test_shape(), create_test_shape()];
+        let mut history = VersionHistory::new();
+        history.save_version(shapes.clone(), LayerTree::from_shapes(&[]), None, 1000.0);
+
+        // Restoring rebuilds the scene from the snapshot; saving it again must diff clean
+        let restored = SceneGraph::from_shapes(history.get_version(0).unwrap().shapes.clone());
+        history.save_version(restored.shapes().to_vec(), LayerTree::from_shapes(&[]), None, 2000.0);
+
+        let diff = VersionHistory::diff(history.get_version(0).unwrap(), history.get_version(1).unwrap());
+        assert!(diff.is_empty(), "{diff:?}");
+    }
+
+    #[test]
+    fn test_restore_replaces_scene_and_clears_selection() {
+        let first = create_test_shape();
+        let mut scene = SceneGraph::from_shapes(vec![first.clone()]);
+        let mut history = VersionHistory::new();
+        history.save_version(scene.shapes().to_vec(), LayerTree::from_shapes(&[first.id]), None, 1000.0);
+
+        // Edits after saving don't reach the snapshot
+        let second = create_test_shape();
+        scene.add_shape(second.clone());
+        scene.set_selection(&[first.id, second.id]);
+        assert_eq!(history.get_version(0).unwrap().shapes.len(), 1);
+
+        history.save_version(scene.shapes().to_vec(), LayerTree::from_shapes(&[first.id, second.id]), None, 2000.0);
+        scene = SceneGraph::from_shapes(history.get_version(0).unwrap().shapes.clone());
+        history.set_current_version(0);
+
+        assert_eq!(scene.shapes().iter().map(|s| s.id).collect::<Vec<_>>(), vec![first.id]);
+        assert!(scene.selection().is_empty());
+        assert_eq!(history.current_version_idx, Some(0));
+        // Restoring keeps later versions around
+        assert_eq!(history.len(), 2);
+    }
+
     #[test]
     fn test_set_current_version() {
         let mut history = VersionHistory::new();
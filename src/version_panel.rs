@@ -1,7 +1,7 @@
 use yew::prelude::*;
 use web_sys::window;
 use crate::types::ActiveTab;
-use crate::version::VersionHistory;
+use crate::version::{VersionDiff, VersionHistory};
 
 #[derive(Properties, PartialEq)]
 pub struct VersionHistoryPanelProps {
@@ -18,6 +18,9 @@ pub fn version_history_panel(props: &VersionHistoryPanelProps) -> Html {
         return html! {};
     }
 
+    // Version row under the mouse; non-current rows show their diff against the current version
+    let hovered_idx = use_state(|| None::<usize>);
+
     let on_save = {
         let on_save_version = props.on_save_version.clone();
         Callback::from(move |_: MouseEvent| {
@@ -55,6 +58,22 @@ pub fn version_history_panel(props: &VersionHistoryPanelProps) -> Html {
                 {
                     props.history.versions.iter().enumerate().rev().map(|(idx, version)| {
                         let is_current = props.history.current_version_idx == Some(idx);
+                        let diff = match props.history.current_version_idx {
+                            Some(current_idx) if *hovered_idx == Some(idx) && !is_current => {
+                                // Older first, so "added" always means added later in history
+                                let (from, to) = (idx.min(current_idx), idx.max(current_idx));
+                                Some(VersionHistory::diff(&props.history.versions[from], &props.history.versions[to]))
+                            }
+                            _ => None,
+                        };
+                        let onmouseenter = {
+                            let hovered_idx = hovered_idx.clone();
+                            Callback::from(move |_: MouseEvent| hovered_idx.set(Some(idx)))
+                        };
+                        let onmouseleave = {
+                            let hovered_idx = hovered_idx.clone();
+                            Callback::from(move |_: MouseEvent| hovered_idx.set(None))
+                        };
                         let on_restore = props.on_restore_version.clone();
                         let version_label = version.label.clone();
                         let onclick = Callback::from(move |_: MouseEvent| {
@@ -69,7 +88,10 @@ pub fn version_history_panel(props: &VersionHistoryPanelProps) -> Html {
                         html! {
                             <div
                                 key={version.id}
+                                data-testid={format!("version-row-{}", idx)}
                                 {onclick}
+                                {onmouseenter}
+                                {onmouseleave}
                                 class={classes!(
                                     "p-3",
                                     "rounded-lg",
@@ -85,12 +107,21 @@ pub fn version_history_panel(props: &VersionHistoryPanelProps) -> Html {
                             >
                                 <div class="flex items-center justify-between gap-2">
                                     <span class="font-medium text-sm">{&version.label}</span>
+                                    if let Some(diff) = &diff {
+                                        {render_diff_dots(idx, diff)}
+                                    }
                                     if is_current {
                                         <span class="text-xs bg-blue-500 text-white px-2 py-0.5 rounded">
                                             {"Current"}
                                         </span>
                                     }
                                 </div>
+                                <div
+                                    data-testid={format!("version-thumbnail-{}", idx)}
+                                    class="mt-2 rounded border border-gray-200 bg-white overflow-hidden flex justify-center"
+                                >
+                                    {Html::from_html_unchecked(AttrValue::from(version.thumbnail_markup()))}
+                                </div>
                                 <div class="text-xs text-gray-500 mt-1">
                                     {format_timestamp(version.created_at)}
                                 </div>
@@ -112,6 +143,33 @@ pub fn version_history_panel(props: &VersionHistoryPanelProps) -> Html {
     }
 }
 
+/// Green/red/yellow dots with counts of added, removed and modified shapes
+fn render_diff_dots(idx: usize, diff: &VersionDiff) -> Html {
+    let dots = [
+        ("added", "bg-green-500", diff.added.len()),
+        ("removed", "bg-red-500", diff.removed.len()),
+        ("modified", "bg-yellow-400", diff.modified.len()),
+    ];
+
+    html! {
+        <span data-testid={format!("version-diff-{}", idx)} class="flex items-center gap-2 text-xs text-gray-600">
+            if diff.is_empty() {
+                <span class="text-gray-400">{"No changes"}</span>
+            }
+            {for dots.into_iter().filter(|(_, _, count)| *count > 0).map(|(kind, color, count)| html! {
+                <span
+                    data-testid={format!("version-diff-{}", kind)}
+                    title={format!("{} shape(s) {}", count, kind)}
+                    class="flex items-center gap-1"
+                >
+                    <span class={classes!("inline-block", "w-2", "h-2", "rounded-full", color)}></span>
+                    {count}
+                </span>
+            })}
+        </span>
+    }
+}
+
 fn format_timestamp(ts: f64) -> String {
     // Convert milliseconds to seconds for display
     // In a real app, use a date formatting library
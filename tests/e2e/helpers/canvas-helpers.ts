@@ -7,6 +7,14 @@ export async function getSVGOffset(page: Page): Promise<{ x: number; y: number }
   return { x: box.x, y: box.y };
 }
 
+// The canvas fills the center area, so its size depends on the viewport
+export async function getCanvasSize(page: Page): Promise<{ width: number; height: number }> {
+  const svg = page.locator('[data-testid="main-canvas"]');
+  const width = Number(await svg.getAttribute('width'));
+  const height = Number(await svg.getAttribute('height'));
+  return { width, height };
+}
+
 export async function getElementCenter(element: Locator): Promise<{ x: number; y: number }> {
   const box = await element.boundingBox();
   if (!box) throw new Error('Element not found');
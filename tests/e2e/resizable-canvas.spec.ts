@@ -59,6 +59,17 @@ test.describe('ResizableCanvas E2E Tests', () => {
       await assertNoSelection(page);
     });
 
+    test('TC-35: Alt-drag marquee selects only fully enclosed shapes', async ({ page }) => {
+      // Covers the red triangle entirely and clips the blue one
+      await drawSelectionRectangle(page, 220, 210, 290, 265);
+      await assertSelectionState(page, [0, 1]);
+
+      await page.keyboard.down('Alt');
+      await drawSelectionRectangle(page, 220, 210, 290, 265);
+      await page.keyboard.up('Alt');
+      await assertSelectionState(page, [0]);
+    });
+
     test('TC-17: Verify preview box appears during marquee selection', async ({ page }) => {
       const svg = page.locator('[data-testid="main-canvas"]');
       const offset = await getSVGOffset(page);
@@ -135,6 +146,35 @@ test.describe('ResizableCanvas E2E Tests', () => {
         height: 80,
       });
     });
+
+    test('TC-47: Shift locks dragging to the dominant axis and can switch mid-drag', async ({ page }) => {
+      const svg = page.locator('[data-testid="main-canvas"]');
+      await drawSelectionRectangle(page, 220, 210, 310, 310);
+
+      const selectionBox = svg.locator('[data-testid="selection-bounding-box"]');
+      let box = await selectionBox.boundingBox();
+      if (!box) throw new Error('Selection box not found');
+      let centerX = box.x + box.width / 2;
+      let centerY = box.y + box.height / 2;
+
+      // Mostly horizontal: the vertical wobble is dropped
+      await page.keyboard.down('Shift');
+      await dragFromTo(page, centerX, centerY, centerX + 50, centerY + 12);
+      await assertBoundingBox(selectionBox, { x: 280, y: 220, width: 70, height: 80 });
+
+      // Start horizontal, then move further vertically: the lock follows
+      box = await selectionBox.boundingBox();
+      if (!box) throw new Error('Selection box not found');
+      centerX = box.x + box.width / 2;
+      centerY = box.y + box.height / 2;
+      await page.mouse.move(centerX, centerY);
+      await page.mouse.down();
+      await page.mouse.move(centerX + 20, centerY + 5, { steps: 5 });
+      await page.mouse.move(centerX + 20, centerY + 60, { steps: 5 });
+      await releaseMouse(page);
+      await page.keyboard.up('Shift');
+      await assertBoundingBox(selectionBox, { x: 280, y: 280, width: 70, height: 80 });
+    });
   });
 
   test.describe('Complete Workflow', () => {
@@ -307,6 +347,49 @@ test.describe('ResizableCanvas E2E Tests', () => {
     });
   });
 
+  test.describe('Aspect Ratio Tests', () => {
+    test('TC-46: Shift keeps the aspect ratio on corner and edge handles', async ({ page }) => {
+      const svg = page.locator('[data-testid="main-canvas"]');
+      const offset = await getSVGOffset(page);
+      const selectionBox = svg.locator('[data-testid="selection-bounding-box"]');
+
+      await page.click('[data-testid="tool-rectangle"]');
+      await dragFromTo(page, offset.x + 100, offset.y + 50, offset.x + 160, offset.y + 90);
+
+      // Width moved further, so the 3:2 box follows it to 120 wide
+      await page.keyboard.down('Shift');
+      await startDragHandle(page, 'bottom-right', 60, 10);
+      await releaseMouse(page);
+      await assertBoundingBox(selectionBox, { x: 100, y: 50, width: 120, height: 80 });
+
+      // An edge handle scales the other axis with it
+      await startDragHandle(page, 'right', -60, 0);
+      await releaseMouse(page);
+      await page.keyboard.up('Shift');
+      await assertBoundingBox(selectionBox, { x: 100, y: 50, width: 60, height: 40 });
+    });
+  });
+
+  test.describe('Dimension Field Tests', () => {
+    test('TC-48: Aspect ratio lock keeps width and height proportional', async ({ page }) => {
+      const svg = page.locator('[data-testid="main-canvas"]');
+      const offset = await getSVGOffset(page);
+      const selectionBox = svg.locator('[data-testid="selection-bounding-box"]');
+
+      await page.click('[data-testid="tool-rectangle"]');
+      await dragFromTo(page, offset.x + 100, offset.y + 50, offset.x + 160, offset.y + 90);
+
+      // Unlocked: only the edited axis changes
+      await page.fill('[data-testid="dim-width"]', '90');
+      await assertBoundingBox(selectionBox, { x: 100, y: 50, width: 90, height: 40 });
+
+      await page.click('[data-testid="aspect-lock-toggle"]');
+      await expect(page.locator('[data-testid="aspect-lock-toggle"]')).toHaveAttribute('aria-pressed', 'true');
+      await page.fill('[data-testid="dim-height"]', '80');
+      await assertBoundingBox(selectionBox, { x: 100, y: 50, width: 180, height: 80 });
+    });
+  });
+
   // Note: Hover Tests (TC-17 through TC-21) removed
   // With GPU rendering, shapes are rendered on canvas, not as SVG polygons.
   // Hover effects are visual only (cursor changes to pointer via is_shape_hovered prop).
@@ -502,4 +585,529 @@ test.describe('ResizableCanvas E2E Tests', () => {
         'Bottom-right Y should stay anchored').toBeLessThan(10);
     });
   });
+
+  test.describe('Alignment Tests', () => {
+    test('TC-28: Align left lines up selected shapes on the leftmost edge', async ({ page }) => {
+      const svg = page.locator('[data-testid="main-canvas"]');
+
+      // Select all three triangles (each is 30px wide)
+      await drawSelectionRectangle(page, 220, 210, 310, 310);
+      const selectionBox = svg.locator('[data-testid="selection-bounding-box"]');
+
+      await page.click('[data-testid="align-left"]');
+      await page.waitForTimeout(100);
+
+      // All triangles now share the leftmost edge, so the selection collapses to one width
+      await assertBoundingBox(selectionBox, { x: 230, y: 220, width: 30, height: 80 });
+    });
+
+    test('TC-29: Align buttons are hidden for a single selection', async ({ page }) => {
+      await clickOnShape(page, 0);
+
+      await expect(page.locator('[data-testid="align-left"]')).toHaveCount(0);
+    });
+  });
+
+  test.describe('Keyboard Nudge Tests', () => {
+    test('TC-33: Arrow keys nudge the selection by 1px, Shift+arrow by 10px', async ({ page }) => {
+      const svg = page.locator('[data-testid="main-canvas"]');
+
+      await drawSelectionRectangle(page, 220, 210, 310, 310);
+      const selectionBox = svg.locator('[data-testid="selection-bounding-box"]');
+
+      await page.keyboard.press('ArrowRight');
+      await page.waitForTimeout(100);
+      await assertBoundingBox(selectionBox, { ...INITIAL_BOUNDING_BOX, x: INITIAL_BOUNDING_BOX.x + 1 });
+
+      await page.keyboard.press('Shift+ArrowDown');
+      await page.waitForTimeout(100);
+      await assertBoundingBox(selectionBox, {
+        ...INITIAL_BOUNDING_BOX,
+        x: INITIAL_BOUNDING_BOX.x + 1,
+        y: INITIAL_BOUNDING_BOX.y + 10,
+      });
+    });
+  });
+
+  test.describe('Delete Tests', () => {
+    test('TC-34: Delete key removes the selected shapes and shows a toast', async ({ page }) => {
+      await drawSelectionRectangle(page, 220, 210, 310, 310);
+
+      await page.keyboard.press('Delete');
+      await page.waitForTimeout(100);
+
+      await assertNoSelection(page);
+      await expect(page.locator('[data-testid="toast"]')).toHaveText('Deleted 3 shapes');
+
+      // Marquee over the same area now finds nothing
+      await drawSelectionRectangle(page, 220, 210, 310, 310);
+      await assertNoSelection(page);
+    });
+  });
+
+  test.describe('Coordinate Display Tests', () => {
+    test('TC-36: Coordinate readout follows the cursor and shows selection size', async ({ page }) => {
+      const offset = await getSVGOffset(page);
+      const coords = page.locator('[data-testid="cursor-coords"]');
+
+      await page.mouse.move(offset.x + 123, offset.y + 45);
+      await expect(coords).toContainText('X: 123.0  Y: 45.0');
+      await expect(page.locator('[data-testid="selection-size"]')).toHaveCount(0);
+
+      await drawSelectionRectangle(page, 220, 210, 310, 310);
+      await expect(page.locator('[data-testid="selection-size"]')).toHaveText(
+        `W: ${INITIAL_BOUNDING_BOX.width.toFixed(1)}  H: ${INITIAL_BOUNDING_BOX.height.toFixed(1)}`
+      );
+    });
+
+    test('TC-37: Wheel pans and Ctrl+wheel zooms about the cursor', async ({ page }) => {
+      const offset = await getSVGOffset(page);
+      const coords = page.locator('[data-testid="cursor-coords"]');
+
+      await page.mouse.move(offset.x + 100, offset.y + 100);
+      await page.mouse.wheel(0, 50);
+      await page.mouse.move(offset.x + 100, offset.y + 101);
+      await expect(coords).toContainText('X: 100.0  Y: 151.0');
+
+      // Zooming keeps the point under the cursor fixed
+      await page.keyboard.down('Control');
+      await page.mouse.wheel(0, -200);
+      await page.keyboard.up('Control');
+      await page.mouse.move(offset.x + 100, offset.y + 101);
+      await expect(coords).toContainText('X: 100.0  Y: 151.0');
+    });
+
+    test('TC-49: Middle-button and Space drags pan the view', async ({ page }) => {
+      const offset = await getSVGOffset(page);
+      const coords = page.locator('[data-testid="cursor-coords"]');
+
+      await page.mouse.move(offset.x + 100, offset.y + 100);
+      await page.mouse.down({ button: 'middle' });
+      await page.mouse.move(offset.x + 150, offset.y + 120, { steps: 5 });
+      await page.mouse.up({ button: 'middle' });
+      await expect(coords).toContainText('X: 100.0  Y: 100.0');
+
+      // Space + left drag pans without selecting anything
+      await page.keyboard.down('Space');
+      await page.mouse.down();
+      await page.mouse.move(offset.x + 120, offset.y + 120, { steps: 5 });
+      await page.mouse.up();
+      await page.keyboard.up('Space');
+      await page.mouse.move(offset.x + 100, offset.y + 100);
+      await expect(coords).toContainText('X: 80.0  Y: 80.0');
+      await assertNoSelection(page);
+    });
+
+    test('TC-51: Zoom controls step, reset and fit the view', async ({ page }) => {
+      const readout = page.locator('[data-testid="zoom-readout"]');
+      await expect(readout).toHaveText('100%');
+
+      await page.click('[data-testid="zoom-in"]');
+      await expect(readout).toHaveText('125%');
+      await page.click('[data-testid="zoom-out"]');
+      await page.click('[data-testid="zoom-out"]');
+      await expect(readout).toHaveText('80%');
+
+      await page.keyboard.press('Control+0');
+      await expect(readout).toHaveText('100%');
+      await page.keyboard.press('Control+=');
+      await expect(readout).toHaveText('125%');
+      await page.keyboard.press('Control+-');
+      await expect(readout).toHaveText('100%');
+
+      // Fitting changes the zoom to frame the content; clicking the readout resets it
+      await page.click('[data-testid="zoom-fit"]');
+      await expect(readout).not.toHaveText('100%');
+      await readout.click();
+      await expect(readout).toHaveText('100%');
+    });
+  });
+
+  test.describe('Drawing Tests', () => {
+    test('TC-30: Line tool creates a selected line from two clicks', async ({ page }) => {
+      const svg = page.locator('[data-testid="main-canvas"]');
+      const offset = await getSVGOffset(page);
+
+      await page.click('[data-testid="tool-line"]');
+      await page.mouse.click(offset.x + 100, offset.y + 50);
+      await page.mouse.click(offset.x + 200, offset.y + 50);
+      await page.waitForTimeout(100);
+
+      // Line bounds are expanded by the 2px stroke width
+      const selectionBox = svg.locator('[data-testid="selection-bounding-box"]');
+      await assertBoundingBox(selectionBox, { x: 98, y: 48, width: 104, height: 4 });
+      const selectedIds = await svg.getAttribute('data-selection-ids');
+      expect(selectedIds?.split(',').filter(Boolean)).toHaveLength(1);
+    });
+
+    test('TC-31: Rectangle tool creates a shape sized to the drag rect', async ({ page }) => {
+      const svg = page.locator('[data-testid="main-canvas"]');
+      const offset = await getSVGOffset(page);
+
+      await page.click('[data-testid="tool-rectangle"]');
+      await dragFromTo(page, offset.x + 100, offset.y + 50, offset.x + 160, offset.y + 90);
+
+      const selectionBox = svg.locator('[data-testid="selection-bounding-box"]');
+      await assertBoundingBox(selectionBox, { x: 100, y: 50, width: 60, height: 40 });
+      await expect(page.locator('[data-testid="tool-select"]')).toHaveAttribute('data-active', 'true');
+    });
+
+    test('TC-32: Shift constrains the ellipse tool to a circle', async ({ page }) => {
+      const svg = page.locator('[data-testid="main-canvas"]');
+      const offset = await getSVGOffset(page);
+
+      await page.click('[data-testid="tool-ellipse"]');
+      await page.keyboard.down('Shift');
+      await dragFromTo(page, offset.x + 100, offset.y + 50, offset.x + 160, offset.y + 70);
+      await page.keyboard.up('Shift');
+
+      const selectionBox = svg.locator('[data-testid="selection-bounding-box"]');
+      await assertBoundingBox(selectionBox, { x: 100, y: 50, width: 60, height: 60 });
+    });
+
+    test('TC-39: Pen tool places vertices and closes the polygon on double-click', async ({ page }) => {
+      const svg = page.locator('[data-testid="main-canvas"]');
+      const offset = await getSVGOffset(page);
+
+      await page.click('[data-testid="tool-pen"]');
+      await page.mouse.click(offset.x + 100, offset.y + 50);
+      await page.mouse.click(offset.x + 180, offset.y + 50);
+      await page.mouse.move(offset.x + 140, offset.y + 120);
+      await expect(svg.locator('[data-testid="pen-preview"] circle')).toHaveCount(2);
+
+      await page.mouse.dblclick(offset.x + 140, offset.y + 120);
+      await expect(svg.locator('[data-testid="pen-preview"]')).toHaveCount(0);
+
+      const selectionBox = svg.locator('[data-testid="selection-bounding-box"]');
+      await assertBoundingBox(selectionBox, { x: 100, y: 50, width: 80, height: 70 });
+      await expect(page.locator('[data-testid="tool-select"]')).toHaveAttribute('data-active', 'true');
+    });
+
+    test('TC-43: Vertex editing drags a polygon vertex and refits the selection on Escape', async ({ page }) => {
+      const svg = page.locator('[data-testid="main-canvas"]');
+      const offset = await getSVGOffset(page);
+
+      await page.click('[data-testid="tool-pen"]');
+      await page.mouse.click(offset.x + 100, offset.y + 50);
+      await page.mouse.click(offset.x + 180, offset.y + 50);
+      await page.mouse.dblclick(offset.x + 140, offset.y + 120);
+
+      await page.keyboard.press('e');
+      await expect(svg.locator('[data-testid="selection-bounding-box"]')).toHaveCount(0);
+      await expect(svg.locator('[data-testid^="vertex-handle-"]')).toHaveCount(3);
+
+      await dragFromTo(page, offset.x + 140, offset.y + 120, offset.x + 140, offset.y + 150);
+      await expect(svg.locator('[data-testid="vertex-handle-2"]')).toHaveAttribute('cy', '150');
+
+      await page.keyboard.press('Escape');
+      await expect(svg.locator('[data-testid="vertex-edit"]')).toHaveCount(0);
+      const selectionBox = svg.locator('[data-testid="selection-bounding-box"]');
+      await assertBoundingBox(selectionBox, { x: 100, y: 50, width: 80, height: 100 });
+    });
+
+    test('TC-45: Arc tool draws a pie slice with editable angles and radius', async ({ page }) => {
+      const svg = page.locator('[data-testid="main-canvas"]');
+      const offset = await getSVGOffset(page);
+
+      await page.click('[data-testid="tool-arc"]');
+      await dragFromTo(page, offset.x + 100, offset.y + 50, offset.x + 160, offset.y + 90);
+
+      const selectedIds = await svg.getAttribute('data-selection-ids');
+      expect(selectedIds?.split(',').filter(Boolean)).toHaveLength(1);
+      await expect(page.locator('[data-testid="arc-start-angle"]')).toHaveValue('0');
+      await expect(page.locator('[data-testid="arc-end-angle"]')).toHaveValue('90');
+      await expect(page.locator('[data-testid="arc-radius"]')).toHaveValue('60');
+
+      await page.fill('[data-testid="arc-end-angle"]', '180');
+      await page.fill('[data-testid="arc-radius"]', '40');
+      await expect(page.locator('[data-testid="arc-end-angle"]')).toHaveValue('180');
+      await expect(page.locator('[data-testid="arc-radius"]')).toHaveValue('40');
+    });
+
+    test('TC-55: Smooth turns a polygon into a curve with adjustable tension', async ({ page }) => {
+      const offset = await getSVGOffset(page);
+
+      await page.click('[data-testid="tool-pen"]');
+      await page.mouse.click(offset.x + 100, offset.y + 50);
+      await page.mouse.click(offset.x + 180, offset.y + 50);
+      await page.mouse.dblclick(offset.x + 140, offset.y + 120);
+
+      await expect(page.locator('[data-testid="smooth-tension-slider"]')).toHaveCount(0);
+      await page.click('[data-testid="smooth-polygon"]');
+      await expect(page.locator('[data-testid="smooth-polygon"]')).toHaveCount(0);
+
+      const slider = page.locator('[data-testid="smooth-tension-slider"]');
+      await expect(slider).toHaveValue('0.5');
+      await slider.fill('1');
+      await expect(slider).toHaveValue('1');
+      await expect(page.getByText('1.00')).toBeVisible();
+    });
+
+    test('TC-56: Text tool places editable text whose box follows its size', async ({ page }) => {
+      const svg = page.locator('[data-testid="main-canvas"]');
+      const offset = await getSVGOffset(page);
+
+      await page.click('[data-testid="tool-text"]');
+      await page.mouse.click(offset.x + 100, offset.y + 50);
+
+      await expect(page.locator('[data-testid="text-content"]')).toHaveValue('Text');
+      await expect(page.locator('[data-testid="text-font-size"]')).toHaveValue('24');
+      const selectionBox = svg.locator('[data-testid="selection-bounding-box"]');
+      const width = Number(await selectionBox.getAttribute('width'));
+      expect(width).toBeGreaterThan(0);
+
+      // Doubling the font size doubles the box; the content updates in place
+      await page.fill('[data-testid="text-font-size"]', '48');
+      await expect.poll(async () => Number(await selectionBox.getAttribute('width'))).toBeCloseTo(width * 2, 0);
+      await page.fill('[data-testid="text-content"]', 'Label');
+      await expect(page.locator('[data-testid="text-content"]')).toHaveValue('Label');
+    });
+
+    test('TC-57: Dropping a PNG adds an image at its natural size on the drop point', async ({ page }) => {
+      const svg = page.locator('[data-testid="main-canvas"]');
+      const offset = await getSVGOffset(page);
+
+      // Drop a 120x60 PNG, plus a text file that should be ignored
+      await page.evaluate(async ({ x, y }) => {
+        const source = document.createElement('canvas');
+        source.width = 120;
+        source.height = 60;
+        source.getContext('2d')!.fillRect(0, 0, 120, 60);
+        const blob: Blob = await new Promise((resolve) => source.toBlob((b) => resolve(b!), 'image/png'));
+
+        const data = new DataTransfer();
+        data.items.add(new File([blob], 'photo.png', { type: 'image/png' }));
+        data.items.add(new File(['hello'], 'notes.txt', { type: 'text/plain' }));
+        const target = document.querySelector('[data-testid="canvas-drop-target"]')!;
+        target.dispatchEvent(new DragEvent('drop', { dataTransfer: data, clientX: x, clientY: y, bubbles: true, cancelable: true }));
+      }, { x: offset.x + 200, y: offset.y + 150 });
+
+      const selectionBox = svg.locator('[data-testid="selection-bounding-box"]');
+      await expect(selectionBox).toBeVisible();
+      expect(Number(await selectionBox.getAttribute('width'))).toBeCloseTo(120, 0);
+      expect(Number(await selectionBox.getAttribute('height'))).toBeCloseTo(60, 0);
+      expect(Number(await selectionBox.getAttribute('x'))).toBeCloseTo(140, 0);
+      expect(Number(await selectionBox.getAttribute('y'))).toBeCloseTo(120, 0);
+      await expect(page.locator('[data-testid^="layer-name-"]', { hasText: /^Image \d+$/ })).toHaveCount(1);
+    });
+  });
+
+  test.describe('Fill Tests', () => {
+    test('TC-40: Switching a shape to a linear gradient shows editable stops', async ({ page }) => {
+      const offset = await getSVGOffset(page);
+
+      await page.click('[data-testid="tool-rectangle"]');
+      await dragFromTo(page, offset.x + 100, offset.y + 50, offset.x + 200, offset.y + 100);
+
+      await page.selectOption('[data-testid="fill-type-select"]', 'linear');
+      await expect(page.locator('[data-testid="gradient-stop-0"]')).toBeVisible();
+      await expect(page.locator('[data-testid="gradient-stop-1"]')).toBeVisible();
+      await expect(page.locator('linearGradient stop')).toHaveCount(2);
+
+      await page.selectOption('[data-testid="fill-type-select"]', 'solid');
+      await expect(page.locator('[data-testid="gradient-stop-0"]')).toHaveCount(0);
+    });
+
+    test('TC-59: The fill color picker edits alpha live and remembers recent colors', async ({ page }) => {
+      const offset = await getSVGOffset(page);
+
+      await page.click('[data-testid="tool-rectangle"]');
+      await dragFromTo(page, offset.x + 100, offset.y + 50, offset.x + 200, offset.y + 100);
+
+      await page.fill('[data-testid="fill-hex"]', '#ff0000');
+      await expect(page.locator('[data-testid="fill-hex"]')).toHaveValue('#ff0000');
+
+      await expect(page.locator('[data-testid="fill-alpha"]')).toHaveCount(0);
+      await page.click('[data-testid="fill-swatch"]');
+      await page.locator('[data-testid="fill-alpha"]').fill('50');
+      await expect(page.locator('[data-testid="fill-hex"]')).toHaveValue('#ff000080');
+      await expect(page.locator('[data-testid="fill-recent-0"]')).toBeVisible();
+
+      // Dragging across the square recolors the shape with the hue kept
+      const square = await page.locator('[data-testid="fill-picker-square"]').boundingBox();
+      await dragFromTo(page, square!.x + 2, square!.y + 2, square!.x + square!.width - 2, square!.y + 2);
+      await expect(page.locator('[data-testid="fill-hue"]')).toHaveValue('0');
+    });
+  });
+
+  test.describe('Stroke Tests', () => {
+    test('TC-53: Stroke width controls restroke the selection and refit its box', async ({ page }) => {
+      const svg = page.locator('[data-testid="main-canvas"]');
+      const offset = await getSVGOffset(page);
+
+      await page.click('[data-testid="tool-rectangle"]');
+      await dragFromTo(page, offset.x + 100, offset.y + 50, offset.x + 200, offset.y + 100);
+
+      await page.fill('[data-testid="stroke-width-input"]', '4');
+      await page.locator('[data-testid="stroke-width-input"]').press('Enter');
+      await expect(page.locator('[data-testid="stroke-width-slider"]')).toHaveValue('4');
+
+      // Bounds grow by half the stroke width on every side
+      const selectionBox = svg.locator('[data-testid="selection-bounding-box"]');
+      await assertBoundingBox(selectionBox, { x: 98, y: 48, width: 104, height: 54 });
+
+      await page.locator('[data-testid="stroke-width-slider"]').fill('0');
+      await expect(page.locator('[data-testid="stroke-width-input"]')).toHaveValue('0');
+      await assertBoundingBox(selectionBox, { x: 100, y: 50, width: 100, height: 50 });
+    });
+  });
+
+  test.describe('Shadow Tests', () => {
+    test('TC-58: Drop shadow toggle adds editable shadow controls without moving the box', async ({ page }) => {
+      const svg = page.locator('[data-testid="main-canvas"]');
+      const offset = await getSVGOffset(page);
+
+      await page.click('[data-testid="tool-rectangle"]');
+      await dragFromTo(page, offset.x + 100, offset.y + 50, offset.x + 200, offset.y + 100);
+      const selectionBox = svg.locator('[data-testid="selection-bounding-box"]');
+      await assertBoundingBox(selectionBox, { x: 100, y: 50, width: 100, height: 50 });
+
+      await expect(page.locator('[data-testid="shadow-blur"]')).toHaveCount(0);
+      await page.check('[data-testid="drop-shadow-toggle"]');
+      await expect(page.locator('[data-testid="shadow-offset-x"]')).toHaveValue('4');
+      await expect(page.locator('[data-testid="shadow-blur"]')).toHaveValue('8');
+
+      await page.fill('[data-testid="shadow-offset-y"]', '12');
+      await expect(page.locator('[data-testid="shadow-offset-y"]')).toHaveValue('12');
+      // The shadow sits outside the shape's bounds
+      await assertBoundingBox(selectionBox, { x: 100, y: 50, width: 100, height: 50 });
+
+      await page.uncheck('[data-testid="drop-shadow-toggle"]');
+      await expect(page.locator('[data-testid="shadow-blur"]')).toHaveCount(0);
+    });
+  });
+
+  test.describe('Opacity Tests', () => {
+    test('TC-44: Selecting a group shows a group opacity slider', async ({ page }) => {
+      await page.locator('[data-testid^="group-name-"]').first().click();
+
+      const slider = page.locator('[data-testid="group-opacity-slider"]');
+      await expect(slider).toHaveValue('100');
+      await slider.fill('40');
+      await expect(slider).toHaveValue('40');
+      await expect(page.getByText('40%')).toBeVisible();
+    });
+  });
+
+  test.describe('Canvas Size Tests', () => {
+    test('TC-54: The canvas follows the window size', async ({ page }) => {
+      const svg = page.locator('[data-testid="main-canvas"]');
+      const before = Number(await svg.getAttribute('width'));
+
+      await page.setViewportSize({ width: 1600, height: 900 });
+      await expect.poll(async () => Number(await svg.getAttribute('width'))).toBeGreaterThan(before);
+
+      // The GPU canvas and the overlay stay the same size
+      const width = await svg.getAttribute('width');
+      await expect(page.locator('canvas').first()).toHaveCSS('width', `${width}px`);
+    });
+  });
+
+  test.describe('Canvas Tab Tests', () => {
+    test('TC-52: Tabs keep separate canvases and can be opened and closed', async ({ page }) => {
+      const layerNames = page.locator('[data-testid^="layer-name-"]:not([data-testid^="layer-name-input-"])');
+      const demoCount = await layerNames.count();
+      expect(demoCount).toBeGreaterThan(0);
+
+      // A new tab starts empty and becomes active
+      await page.click('[data-testid="canvas-tab-new"]');
+      await expect(page.locator('[data-testid="canvas-tab-1"]')).toHaveAttribute('data-active', 'true');
+      await expect(layerNames).toHaveCount(0);
+
+      // Switching back restores the first canvas
+      await page.click('[data-testid="canvas-tab-0"]');
+      await expect(layerNames).toHaveCount(demoCount);
+
+      // Keyboard: Ctrl+T opens a third tab, Ctrl+W closes it again
+      await page.keyboard.press('Control+t');
+      await expect(page.locator('[data-testid="canvas-tab-2"]')).toHaveAttribute('data-active', 'true');
+      await page.keyboard.press('Control+w');
+      await expect(page.locator('[data-testid="canvas-tab-2"]')).toHaveCount(0);
+
+      // Closing the empty tab leaves the demo canvas as the only tab, with no close button
+      await page.click('[data-testid="canvas-tab-close-1"]');
+      await expect(page.locator('[data-testid^="canvas-tab-close-"]')).toHaveCount(0);
+      await expect(layerNames).toHaveCount(demoCount);
+    });
+  });
+
+  test.describe('Layer Naming Tests', () => {
+    test('TC-50: Ctrl+G groups the selection and Ctrl+Shift+G ungroups it', async ({ page }) => {
+      const svg = page.locator('[data-testid="main-canvas"]');
+      const groupRows = page.locator('[data-testid^="group-name-"]');
+      const initialGroups = await groupRows.count();
+
+      await drawSelectionRectangle(page, 220, 210, 310, 310);
+      const selected = await svg.getAttribute('data-selection-ids');
+
+      await page.keyboard.press('Control+g');
+      await expect(groupRows).toHaveCount(initialGroups + 1);
+      // The new group holds exactly the shapes that were selected
+      expect(await svg.getAttribute('data-selection-ids')).toBe(selected);
+
+      await page.keyboard.press('Control+Shift+G');
+      await expect(groupRows).toHaveCount(initialGroups);
+      expect(await svg.getAttribute('data-selection-ids')).toBe(selected);
+    });
+
+    test('TC-41: Hidden layers cannot be clicked until shown again', async ({ page }) => {
+      const svg = page.locator('[data-testid="main-canvas"]');
+      const offset = await getSVGOffset(page);
+
+      await page.click('[data-testid="tool-rectangle"]');
+      await dragFromTo(page, offset.x + 100, offset.y + 50, offset.x + 160, offset.y + 90);
+      const id = await svg.getAttribute('data-selection-ids');
+
+      await page.click(`[data-testid="toggle-visible-${id}"]`);
+      await page.click('[data-testid="tool-select"]');
+      await page.mouse.click(offset.x + 300, offset.y + 300);
+      await page.mouse.click(offset.x + 130, offset.y + 70);
+      expect(await svg.getAttribute('data-selection-ids')).toBe('');
+
+      await page.click(`[data-testid="toggle-visible-${id}"]`);
+      await page.mouse.click(offset.x + 130, offset.y + 70);
+      expect(await svg.getAttribute('data-selection-ids')).toBe(id);
+    });
+
+    test('TC-42: Locked layers cannot be selected and show a notice', async ({ page }) => {
+      const svg = page.locator('[data-testid="main-canvas"]');
+      const offset = await getSVGOffset(page);
+
+      await page.click('[data-testid="tool-rectangle"]');
+      await dragFromTo(page, offset.x + 100, offset.y + 50, offset.x + 160, offset.y + 90);
+      const id = await svg.getAttribute('data-selection-ids');
+
+      // Locking drops the shape from the selection
+      await page.click(`[data-testid="toggle-locked-${id}"]`);
+      expect(await svg.getAttribute('data-selection-ids')).toBe('');
+
+      await page.click('[data-testid="tool-select"]');
+      await page.mouse.click(offset.x + 130, offset.y + 70);
+      expect(await svg.getAttribute('data-selection-ids')).toBe('');
+      await expect(page.locator('[data-testid="locked-notice"]')).toHaveText('Layer is locked');
+
+      await page.mouse.click(offset.x + 300, offset.y + 300);
+      await expect(page.locator('[data-testid="locked-notice"]')).toHaveCount(0);
+    });
+
+    test('TC-38: Double-click renames a layer and rejects empty names', async ({ page }) => {
+      const svg = page.locator('[data-testid="main-canvas"]');
+      const offset = await getSVGOffset(page);
+
+      await page.click('[data-testid="tool-rectangle"]');
+      await dragFromTo(page, offset.x + 100, offset.y + 50, offset.x + 160, offset.y + 90);
+      const id = await svg.getAttribute('data-selection-ids');
+
+      await page.dblclick(`[data-testid="layer-name-${id}"]`);
+      await page.fill(`[data-testid="layer-name-input-${id}"]`, 'Header bar');
+      await page.keyboard.press('Enter');
+      await expect(page.locator(`[data-testid="layer-name-${id}"]`)).toHaveText('Header bar');
+      await expect(page.locator('[data-testid="properties-title"]')).toHaveText('Header bar');
+
+      await page.dblclick(`[data-testid="layer-name-${id}"]`);
+      await page.fill(`[data-testid="layer-name-input-${id}"]`, '   ');
+      await page.keyboard.press('Enter');
+      await expect(page.locator(`[data-testid="layer-name-${id}"]`)).toHaveText('Header bar');
+    });
+  });
 });
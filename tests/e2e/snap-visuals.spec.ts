@@ -1,9 +1,5 @@
 import { test, expect, Page } from '@playwright/test';
-import { getSVGOffset } from './helpers/canvas-helpers';
-
-// Canvas dimensions (must match CANVAS_WIDTH/HEIGHT in resizable_canvas.rs)
-const CANVAS_WIDTH = 800;
-const CANVAS_HEIGHT = 600;
+import { getCanvasSize, getSVGOffset } from './helpers/canvas-helpers';
 
 async function selectShapeAt(page: Page, x: number, y: number) {
   const offset = await getSVGOffset(page);
@@ -155,12 +151,13 @@ test.describe('Snap Guidelines E2E Tests', () => {
     await startDrag(page, box);
 
     // Drag to horizontal center of canvas
-    const centerX = CANVAS_WIDTH / 2;
+    const canvas = await getCanvasSize(page);
+    const centerX = canvas.width / 2;
     await dragTo(page, centerX, 400);
 
     const snapLines = await getSnapLines(page);
 
-    // Should have a vertical snap line near center (x=400)
+    // Should have a vertical snap line near center
     const verticalLines = snapLines.filter(l => l.isVertical);
     if (verticalLines.length > 0) {
       const centerLine = verticalLines.find(l => Math.abs(l.x1 - centerX) < 20);
@@ -184,12 +181,13 @@ test.describe('Snap Guidelines E2E Tests', () => {
     await startDrag(page, box);
 
     // Drag to vertical center of canvas
-    const centerY = CANVAS_HEIGHT / 2;
+    const canvas = await getCanvasSize(page);
+    const centerY = canvas.height / 2;
     await dragTo(page, 100, centerY);
 
     const snapLines = await getSnapLines(page);
 
-    // Should have a horizontal snap line near center (y=300)
+    // Should have a horizontal snap line near center
     const horizontalLines = snapLines.filter(l => !l.isVertical);
     if (horizontalLines.length > 0) {
       const centerLine = horizontalLines.find(l => Math.abs(l.y1 - centerY) < 20);
@@ -213,8 +211,9 @@ test.describe('Snap Guidelines E2E Tests', () => {
     await startDrag(page, box);
 
     // Drag to exact center of canvas
-    const centerX = CANVAS_WIDTH / 2;
-    const centerY = CANVAS_HEIGHT / 2;
+    const canvas = await getCanvasSize(page);
+    const centerX = canvas.width / 2;
+    const centerY = canvas.height / 2;
     await dragTo(page, centerX, centerY);
 
     const snapLines = await getSnapLines(page);
@@ -242,16 +241,17 @@ test.describe('Snap Guidelines E2E Tests', () => {
     await startDrag(page, box);
 
     // Drag to right edge of canvas
-    await dragTo(page, CANVAS_WIDTH - 5, 400);
+    const canvas = await getCanvasSize(page);
+    await dragTo(page, canvas.width - 5, 400);
 
     const snapLines = await getSnapLines(page);
 
-    // Should have a vertical snap line near x=800
+    // Should have a vertical snap line near the right edge
     const verticalLines = snapLines.filter(l => l.isVertical);
     if (verticalLines.length > 0) {
-      const rightEdgeLine = verticalLines.find(l => l.x1 > CANVAS_WIDTH - 20);
+      const rightEdgeLine = verticalLines.find(l => l.x1 > canvas.width - 20);
       if (rightEdgeLine) {
-        expect(rightEdgeLine.x1).toBeCloseTo(CANVAS_WIDTH, 0);
+        expect(rightEdgeLine.x1).toBeCloseTo(canvas.width, 0);
       }
     }
 
@@ -289,4 +289,23 @@ test.describe('Snap Guidelines E2E Tests', () => {
     const newBox = await getSelectionBox(page);
     expect(newBox).toBeDefined();
   });
+
+  test('TC-Snap-9: Enabling grid snapping draws the grid behind the overlay', async ({ page }) => {
+    const svg = page.locator('[data-testid="main-canvas"]');
+    await expect(svg.locator('[data-testid="snap-grid"]')).toHaveCount(0);
+
+    await page.click('[data-testid="snap-settings"]');
+    await page.click('[data-testid="snap-to-grid"]');
+    await page.fill('[data-testid="snap-grid-size"]', '20');
+
+    const grid = svg.locator('[data-testid="snap-grid"]');
+    await expect(grid).toHaveAttribute('data-grid-size', '20');
+    const canvas = await getCanvasSize(page);
+    await expect(grid).toHaveAttribute('width', String(canvas.width));
+    await expect(grid).toHaveAttribute('height', String(canvas.height));
+    await expect(svg.locator('pattern#snap-grid-pattern')).toHaveAttribute('width', '20');
+
+    await page.click('[data-testid="snap-to-grid"]');
+    await expect(svg.locator('[data-testid="snap-grid"]')).toHaveCount(0);
+  });
 });